@@ -0,0 +1,223 @@
+//! One decode, every analysis.
+//!
+//! ReplayGain, tempo, key and fingerprint each need the decoded samples;
+//! run separately they decode a file four times. Here each file is decoded
+//! once through [`pcm_decode`] and every selected analyzer is fed from the
+//! same blocks. Decoding stops early once the remaining analyzers have seen
+//! all the audio they use, which is the whole file only when ReplayGain is
+//! selected.
+//!
+//! Each analyzer's result is stored as soon as the file is done; one that
+//! fails (too short for a tempo, say) is reported without discarding the
+//! others.
+
+use crate::bpm::{self, BpmDetector};
+use crate::database::Database;
+use crate::fingerprint::{self, Fingerprinter};
+use crate::key_detection::{self, KeyDetector, KeyInfo};
+use crate::pcm_decode::{self, PcmBlock};
+use crate::replaygain::{GainAnalyzer, ReplayGainData};
+use crate::replaygain_store::store_replaygain;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Which analyses to run. Fields left out when deserializing are off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnalyzerSelection {
+    pub replaygain: bool,
+    pub bpm: bool,
+    pub key: bool,
+    pub fingerprint: bool,
+}
+
+impl AnalyzerSelection {
+    pub fn all() -> Self {
+        Self { replaygain: true, bpm: true, key: true, fingerprint: true }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.replaygain || self.bpm || self.key || self.fingerprint)
+    }
+}
+
+/// What was found for one file. Analyses that weren't selected, or that
+/// failed, are `None`; failures are described in `errors`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackAnalysis {
+    pub path: String,
+    pub replaygain: Option<ReplayGainData>,
+    pub bpm: Option<f64>,
+    pub key: Option<KeyInfo>,
+    pub fingerprint: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// Emitted before each file of a batch is analysed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisProgress {
+    pub current: usize,
+    pub total: usize,
+    pub current_file: String,
+}
+
+const CANCELLED: &str = "Analysis cancelled";
+
+/// The selected analyzers for one file, created once the sample rate and
+/// channel count are known from the first block.
+struct Analyzers {
+    gain: Option<GainAnalyzer>,
+    bpm: Option<BpmDetector>,
+    key: Option<KeyDetector>,
+    fingerprint: Option<Fingerprinter>,
+}
+
+impl Analyzers {
+    fn new(selection: AnalyzerSelection, block: &PcmBlock, errors: &mut Vec<String>) -> Self {
+        let gain = if selection.replaygain {
+            GainAnalyzer::new(block.channels, block.sample_rate)
+                .map_err(|e| errors.push(format!("ReplayGain: {}", e)))
+                .ok()
+        } else {
+            None
+        };
+        Self {
+            gain,
+            bpm: selection.bpm.then(|| BpmDetector::new(block.sample_rate)),
+            key: selection.key.then(|| KeyDetector::new(block.sample_rate)),
+            fingerprint: selection.fingerprint.then(|| Fingerprinter::new(block.sample_rate)),
+        }
+    }
+
+    fn push(&mut self, block: &PcmBlock, errors: &mut Vec<String>) {
+        if let Some(gain) = &mut self.gain {
+            if let Err(e) = gain.push(block.interleaved) {
+                errors.push(format!("ReplayGain: {}", e));
+                self.gain = None;
+            }
+        }
+        if let Some(bpm) = &mut self.bpm {
+            bpm.push(block.mono);
+        }
+        if let Some(key) = &mut self.key {
+            key.push(block.mono);
+        }
+        if let Some(fingerprint) = &mut self.fingerprint {
+            fingerprint.push(block.mono);
+        }
+    }
+
+    /// Whether any analyzer still uses more audio.
+    fn wants_more(&self) -> bool {
+        self.gain.is_some()
+            || self.bpm.as_ref().is_some_and(|d| !d.is_full())
+            || self.key.as_ref().is_some_and(|d| !d.is_full())
+            || self.fingerprint.as_ref().is_some_and(|f| !f.is_full())
+    }
+}
+
+/// Decode `path` once, run the selected analyzers and store what they find.
+/// Fails if the file can't be decoded or `cancel` is set mid-file, in which
+/// case nothing is stored.
+pub fn analyze_track_all(
+    db: &Database,
+    path: &str,
+    selection: AnalyzerSelection,
+    target_lufs: f64,
+    cancel: &AtomicBool,
+) -> Result<TrackAnalysis, String> {
+    info!("Analyzing {:?}: {}", selection, path);
+    let mtime = fingerprint::file_mtime(path);
+    let mut result = TrackAnalysis { path: path.to_string(), ..Default::default() };
+
+    let mut analyzers: Option<Analyzers> = None;
+    let mut cancelled = false;
+    pcm_decode::decode_file(path, |block| {
+        if cancel.load(Ordering::Relaxed) {
+            cancelled = true;
+            return false;
+        }
+        let analyzers = analyzers.get_or_insert_with(|| Analyzers::new(selection, block, &mut result.errors));
+        analyzers.push(block, &mut result.errors);
+        analyzers.wants_more()
+    })?;
+    if cancelled {
+        return Err(CANCELLED.to_string());
+    }
+    let analyzers = analyzers.ok_or_else(|| "No audio decoded".to_string())?;
+
+    if let Some(gain) = analyzers.gain {
+        match gain.finish(target_lufs) {
+            Ok(data) => match store_replaygain(&db.conn, path, &data) {
+                Ok(()) => result.replaygain = Some(data),
+                Err(e) => result.errors.push(format!("ReplayGain: {}", e)),
+            },
+            Err(e) => result.errors.push(format!("ReplayGain: {}", e)),
+        }
+    }
+    if let Some(detector) = analyzers.bpm {
+        match detector.finish().and_then(|bpm| bpm::store_bpm(db, path, bpm)) {
+            Ok(bpm) => result.bpm = Some(bpm),
+            Err(e) => result.errors.push(format!("BPM: {}", e)),
+        }
+    }
+    if let Some(detector) = analyzers.key {
+        match detector.finish().and_then(|key| key_detection::store_key(db, path, key).map(|_| key)) {
+            Ok(key) => result.key = Some(key.into()),
+            Err(e) => result.errors.push(format!("Key: {}", e)),
+        }
+    }
+    if let Some(fingerprinter) = analyzers.fingerprint {
+        let stored = fingerprinter.finish().and_then(|fp| match mtime {
+            Some(mtime) => fingerprint::store_fingerprint(db, path, &fp, mtime).map(|_| fp),
+            None => Ok(fp),
+        });
+        match stored {
+            Ok(fp) => result.fingerprint = Some(fp),
+            Err(e) => result.errors.push(format!("Fingerprint: {}", e)),
+        }
+    }
+
+    for e in &result.errors {
+        warn!("{}: {}", path, e);
+    }
+    Ok(result)
+}
+
+/// Analyse `paths` in order, calling `on_progress` before each. Stops at
+/// the first file after `cancel` is set; the file in progress then is
+/// abandoned and left out of the results. A file that can't be decoded is
+/// reported with the error and the batch moves on.
+pub fn analyze_tracks(
+    db: &Database,
+    paths: &[String],
+    selection: AnalyzerSelection,
+    target_lufs: f64,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&AnalysisProgress),
+) -> Vec<TrackAnalysis> {
+    let mut results = Vec::with_capacity(paths.len());
+    for (i, path) in paths.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            info!("Analysis cancelled after {} of {} files", i, paths.len());
+            break;
+        }
+        on_progress(&AnalysisProgress { current: i + 1, total: paths.len(), current_file: path.clone() });
+
+        match analyze_track_all(db, path, selection, target_lufs, cancel) {
+            Ok(result) => results.push(result),
+            Err(e) if cancel.load(Ordering::Relaxed) => {
+                info!("Analysis cancelled during {}: {}", path, e);
+                break;
+            }
+            Err(e) => {
+                warn!("Analysis failed for {}: {}", path, e);
+                results.push(TrackAnalysis { path: path.clone(), errors: vec![e], ..Default::default() });
+            }
+        }
+    }
+    results
+}
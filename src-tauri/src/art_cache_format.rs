@@ -0,0 +1,74 @@
+//! The format album art is stored in the database cache.
+//!
+//! By default art is cached exactly as embedded. With WebP enabled it is
+//! re-encoded (lossy, at the configured quality) before caching, which
+//! typically shrinks large JPEG/PNG covers several times over. Art that
+//! fails to decode or encode, or that would not get smaller, is cached as is.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+
+/// Settings key under which `ArtCacheSettings` is persisted.
+pub const ART_CACHE_SETTINGS_KEY: &str = "album_art_cache";
+
+/// MIME type assumed for cached art whose format cannot be recognised.
+pub const FALLBACK_ART_MIME: &str = "image/jpeg";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtCacheFormat {
+    #[default]
+    Original,
+    Webp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArtCacheSettings {
+    pub format: ArtCacheFormat,
+    /// WebP quality, 0 (smallest) to 100 (best).
+    pub quality: f32,
+}
+
+impl Default for ArtCacheSettings {
+    fn default() -> Self {
+        Self { format: ArtCacheFormat::Original, quality: 80.0 }
+    }
+}
+
+/// The stored settings; defaults when nothing (or something unreadable) is stored.
+pub fn stored_settings(db: &Database) -> ArtCacheSettings {
+    db.get_json_setting(ART_CACHE_SETTINGS_KEY).ok().flatten().unwrap_or_default()
+}
+
+/// MIME type of encoded image bytes, from their signature.
+pub fn sniff_mime(art: &[u8]) -> &'static str {
+    image::guess_format(art).map(|f| f.to_mime_type()).unwrap_or(FALLBACK_ART_MIME)
+}
+
+/// The bytes to cache for `art` under `settings`, with their MIME type.
+pub fn encode_for_cache(art: &[u8], settings: &ArtCacheSettings) -> (Vec<u8>, &'static str) {
+    if settings.format == ArtCacheFormat::Webp {
+        match encode_webp(art, settings.quality) {
+            Ok(webp) if webp.len() < art.len() => return (webp, "image/webp"),
+            Ok(_) => {}
+            Err(e) => warn!("Caching album art unconverted: {}", e),
+        }
+    }
+    (art.to_vec(), sniff_mime(art))
+}
+
+fn encode_webp(art: &[u8], quality: f32) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(art).map_err(|e| format!("Failed to decode album art: {}", e))?;
+    let quality = quality.clamp(0.0, 100.0);
+    let encoded = if image.color().has_alpha() {
+        let rgba = image.to_rgba8();
+        webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height()).encode_simple(false, quality)
+    } else {
+        let rgb = image.to_rgb8();
+        webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height()).encode_simple(false, quality)
+    };
+    encoded.map(|webp| webp.to_vec()).map_err(|e| format!("Failed to encode WebP: {:?}", e))
+}
@@ -0,0 +1,105 @@
+//! Album art extraction that decodes each file at most once at a time.
+//!
+//! On library load the frontend asks for many tracks' art at once, often
+//! several tracks of one album sharing a file or the same track twice.
+//! Concurrent requests for a path that is already being extracted wait for
+//! that extraction's result instead of decoding the file again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use crate::art_cache_format::sniff_mime;
+use crate::database::Database;
+use crate::database_album_art::CachedAlbumArt;
+use crate::error::{AppError, AppResult};
+
+type ArtResult = Result<Option<Vec<u8>>, String>;
+
+/// One extraction in progress; waiters block on `ready` until `result` is set.
+#[derive(Default)]
+struct InFlight {
+    result: Mutex<Option<ArtResult>>,
+    ready: Condvar,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Default)]
+pub struct ArtExtractor {
+    in_flight: Mutex<HashMap<String, Arc<InFlight>>>,
+}
+
+impl ArtExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Art for `track_id` with its MIME type, from the database cache or
+    /// else extracted from `track_path` with `extract` and cached. Concurrent
+    /// calls for the same path share one extraction.
+    pub fn extract_and_cache<F>(&self, db: &Database, track_id: &str, track_path: &str, extract: F) -> AppResult<Option<CachedAlbumArt>>
+    where
+        F: FnOnce(&str) -> ArtResult,
+    {
+        if let Some(art) = db.get_album_art_with_mime(track_id).map_err(|e| AppError::Database(e.to_string()))? {
+            return Ok(Some(art));
+        }
+
+        let (slot, leader) = {
+            let mut in_flight = lock(&self.in_flight);
+            match in_flight.get(track_path) {
+                Some(slot) => (slot.clone(), false),
+                None => {
+                    let slot = Arc::new(InFlight::default());
+                    in_flight.insert(track_path.to_string(), slot.clone());
+                    (slot, true)
+                }
+            }
+        };
+
+        let result = if leader {
+            let mut publish = Publish { extractor: self, path: track_path, slot: &slot, result: None };
+            let result = extract(track_path);
+            // Cache before retiring the entry, so a request arriving after
+            // it finds the art in the database rather than extracting again.
+            let stored = match &result {
+                Ok(Some(art)) => db.set_album_art(track_id, art),
+                _ => Ok(()),
+            };
+            publish.result = Some(result.clone());
+            drop(publish);
+            stored.map_err(|e| AppError::Database(format!("Failed to cache album art: {}", e)))?;
+            result
+        } else {
+            let mut guard = lock(&slot.result);
+            while guard.is_none() {
+                guard = slot.ready.wait(guard).unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+            guard.clone().unwrap_or(Ok(None))
+        };
+
+        let art = result.map_err(|e| AppError::Scanner(format!("Failed to extract album art: {}", e)))?;
+        Ok(art.map(|data| CachedAlbumArt { mime: sniff_mime(&data).to_string(), data }))
+    }
+}
+
+/// Hands the leader's result to waiters and retires the in-flight entry,
+/// even if extraction panics (waiters then see an error). The leader has
+/// already cached the art, so waiters only take the published bytes.
+struct Publish<'a> {
+    extractor: &'a ArtExtractor,
+    path: &'a str,
+    slot: &'a InFlight,
+    result: Option<ArtResult>,
+}
+
+impl Drop for Publish<'_> {
+    fn drop(&mut self) {
+        lock(&self.extractor.in_flight).remove(self.path);
+        let result = self.result.take().unwrap_or_else(|| Err("extraction panicked".to_string()));
+        *lock(&self.slot.result) = Some(result);
+        self.slot.ready.notify_all();
+    }
+}
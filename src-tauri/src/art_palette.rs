@@ -0,0 +1,217 @@
+//! Colour palettes from album art, for theming the UI around the cover.
+//!
+//! The art is shrunk to at most `SAMPLE_SIZE` pixels a side and its pixels
+//! are split by median cut: the box with the widest channel range is
+//! repeatedly halved at its median until there are `PALETTE_SIZE` boxes.
+//! Each box's average colour is a palette entry, weighted by how many
+//! pixels fell into it. Palettes are cached by a hash of the art bytes, so
+//! tracks sharing a cover share one computation.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use image::imageops::FilterType;
+use serde::Serialize;
+
+/// Longest edge the art is reduced to before sampling pixels.
+const SAMPLE_SIZE: u32 = 64;
+
+/// Colours in a palette (fewer for art with fewer distinct colours).
+const PALETTE_SIZE: usize = 6;
+
+/// Pixels at or below this alpha are ignored.
+const MIN_ALPHA: u8 = 128;
+
+/// Smallest RGB distance from the dominant colour for an accent to stand out.
+const MIN_ACCENT_DISTANCE: f32 = 60.0;
+
+/// Palettes kept before the oldest is evicted.
+const PALETTE_CACHE_CAPACITY: usize = 256;
+
+/// Colours as `#rrggbb`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtPalette {
+    /// The colour covering most of the art.
+    pub dominant: String,
+    /// A vivid colour that contrasts with `dominant`; `dominant` itself for
+    /// art with nothing that stands out.
+    pub accent: String,
+    /// All palette colours, most common first.
+    pub colors: Vec<String>,
+}
+
+type Rgb = [u8; 3];
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+fn distance(a: Rgb, b: Rgb) -> f32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x as f32 - *y as f32).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// HSV-style saturation, 0 (grey) to 1 (pure hue).
+fn saturation(color: Rgb) -> f32 {
+    let max = *color.iter().max().unwrap_or(&0) as f32;
+    let min = *color.iter().min().unwrap_or(&0) as f32;
+    if max == 0.0 {
+        0.0
+    } else {
+        (max - min) / max
+    }
+}
+
+/// The channel with the widest spread in `pixels`, and that spread.
+fn widest_channel(pixels: &[Rgb]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (lo, hi) = pixels
+                .iter()
+                .fold((u8::MAX, u8::MIN), |(lo, hi), p| (lo.min(p[c]), hi.max(p[c])));
+            (c, hi.saturating_sub(lo))
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap_or((0, 0))
+}
+
+fn average(pixels: &[Rgb]) -> Rgb {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for c in 0..3 {
+            sum[c] += p[c] as u64;
+        }
+    }
+    let n = pixels.len().max(1) as u64;
+    [(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8]
+}
+
+/// Median cut of `pixels` into at most `boxes` colours with pixel counts,
+/// most common first.
+fn median_cut(pixels: Vec<Rgb>, boxes: usize) -> Vec<(usize, Rgb)> {
+    let mut buckets = vec![pixels];
+    while buckets.len() < boxes {
+        let widest = buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| bucket.len() > 1)
+            .map(|(i, bucket)| {
+                let (channel, range) = widest_channel(bucket);
+                (i, channel, range)
+            })
+            .max_by_key(|(_, _, range)| *range);
+        let Some((index, channel, range)) = widest else {
+            break;
+        };
+        if range == 0 {
+            break;
+        }
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_unstable_by_key(|p| p[channel]);
+        let upper = bucket.split_off(bucket.len() / 2);
+        buckets.push(bucket);
+        buckets.push(upper);
+    }
+
+    let mut colors: Vec<(usize, Rgb)> = buckets
+        .iter()
+        .filter(|bucket| !bucket.is_empty())
+        .map(|bucket| (bucket.len(), average(bucket)))
+        .collect();
+    colors.sort_by(|a, b| b.0.cmp(&a.0));
+    colors
+}
+
+/// Palette of the encoded image `art`.
+pub fn extract_palette(art: &[u8]) -> Result<ArtPalette, String> {
+    let image = image::load_from_memory(art).map_err(|e| format!("Failed to decode album art: {}", e))?;
+    let image = if image.width() > SAMPLE_SIZE || image.height() > SAMPLE_SIZE {
+        image.resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let pixels: Vec<Rgb> = image
+        .to_rgba8()
+        .pixels()
+        .filter(|p| p[3] > MIN_ALPHA)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    if pixels.is_empty() {
+        return Err("Album art has no opaque pixels".to_string());
+    }
+
+    let colors = median_cut(pixels, PALETTE_SIZE);
+    let dominant = colors[0].1;
+    let accent = colors[1..]
+        .iter()
+        .map(|(_, color)| *color)
+        .filter(|color| distance(*color, dominant) >= MIN_ACCENT_DISTANCE)
+        .max_by(|a, b| {
+            let score = |c: Rgb| saturation(c) * distance(c, dominant);
+            score(*a).total_cmp(&score(*b))
+        })
+        .unwrap_or(dominant);
+
+    Ok(ArtPalette {
+        dominant: hex(dominant),
+        accent: hex(accent),
+        colors: colors.into_iter().map(|(_, color)| hex(color)).collect(),
+    })
+}
+
+/// Palettes by art hash, oldest evicted first once full.
+#[derive(Default)]
+pub struct PaletteCache {
+    entries: Mutex<(HashMap<u64, ArtPalette>, VecDeque<u64>)>,
+}
+
+impl PaletteCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn art_hash(art: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        art.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|p| p.into_inner()).0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The palette of `art`, computed on first request.
+    pub fn get_or_extract(&self, art: &[u8]) -> Result<ArtPalette, String> {
+        let key = Self::art_hash(art);
+        if let Some(palette) = self.entries.lock().unwrap_or_else(|p| p.into_inner()).0.get(&key) {
+            return Ok(palette.clone());
+        }
+
+        // Computed without the lock held; a concurrent request for the same
+        // art just computes the same palette.
+        let palette = extract_palette(art)?;
+
+        let mut guard = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        let (palettes, order) = &mut *guard;
+        if palettes.insert(key, palette.clone()).is_none() {
+            order.push_back(key);
+            while order.len() > PALETTE_CACHE_CAPACITY {
+                if let Some(oldest) = order.pop_front() {
+                    palettes.remove(&oldest);
+                }
+            }
+        }
+        Ok(palette)
+    }
+}
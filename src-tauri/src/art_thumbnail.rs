@@ -0,0 +1,39 @@
+//! Small copies of album art for list and grid views.
+//!
+//! Embedded covers are often full-resolution scans several megabytes in
+//! size; base64-encoding those for every row of a library view is wasteful.
+//! Thumbnails fit within `THUMBNAIL_SIZE` on both sides, keeping the art's
+//! aspect ratio.
+
+use std::io::Cursor;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+
+/// Longest edge of a thumbnail, in pixels.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// JPEG quality for opaque thumbnails.
+const THUMBNAIL_JPEG_QUALITY: u8 = 85;
+
+/// Scale `art` down to fit within `max_size`×`max_size`. Art already that
+/// small is re-encoded at its own size rather than enlarged. Opaque art is
+/// encoded as JPEG, art with transparency as PNG.
+pub fn make_thumbnail(art: &[u8], max_size: u32) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(art).map_err(|e| format!("Failed to decode album art: {}", e))?;
+    let image = if image.width() > max_size || image.height() > max_size {
+        image.resize(max_size, max_size, FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let mut out = Cursor::new(Vec::new());
+    if image.color().has_alpha() {
+        image.write_to(&mut out, ImageFormat::Png)
+    } else {
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        rgb.write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, THUMBNAIL_JPEG_QUALITY))
+    }
+    .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+    Ok(out.into_inner())
+}
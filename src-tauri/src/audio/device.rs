@@ -8,8 +8,8 @@ use rodio::cpal::traits::{HostTrait, DeviceTrait as CpalDeviceTrait};
 use rodio::mixer::Mixer;
 use log::{info, warn, error};
 use std::sync::Arc;
-use std::time::Instant;
-use serde::Serialize;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use crate::error::{AppError, AppResult};
 
 // ---------------------------------------------------------------------------
@@ -32,13 +32,137 @@ unsafe impl Sync for SendOutputStream {}
 // DeviceState — groups all audio-output resources
 // ---------------------------------------------------------------------------
 
+/// Opens an output stream on the named device (the system default for
+/// `None`) for a track at the given sample rate (if one is loaded);
+/// swappable so tests can simulate missing hardware.
+pub type OutputOpener = fn(&AudioConfig, Option<u32>, Option<&str>) -> AppResult<(OutputStream, Mixer, Option<String>)>;
+
+/// Settings key under which `AudioConfig` is persisted.
+pub const AUDIO_CONFIG_KEY: &str = "audio_config";
+
+/// Channel layout requested for the output stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputChannels {
+    /// Whatever layout the device opens with by default.
+    #[default]
+    DeviceNative,
+    /// Two channels where the device supports it. On devices that default
+    /// to a multichannel layout, mono and stereo content otherwise plays
+    /// from only the front pair.
+    Stereo,
+}
+
+/// Sample rate requested for the output stream. Whatever the stream runs
+/// at, tracks at another rate are resampled by the mixer; effects run
+/// before that, at the track's own rate, so they are unaffected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputSampleRate {
+    /// The device's default rate.
+    #[default]
+    DeviceDefault,
+    /// The rate of the loaded track, so it reaches the device unresampled.
+    /// The stream is reopened when a track at another rate is loaded; a
+    /// gapless transition keeps the rate already open.
+    MatchSource,
+    /// Always this rate.
+    Fixed(u32),
+}
+
+/// Lowest and highest rates accepted for [`OutputSampleRate::Fixed`].
+pub const MIN_OUTPUT_SAMPLE_RATE: u32 = 8_000;
+pub const MAX_OUTPUT_SAMPLE_RATE: u32 = 768_000;
+
+/// Output stream options, applied whenever the stream is (re)opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioConfig {
+    pub output_channels: OutputChannels,
+    pub sample_rate: OutputSampleRate,
+}
+
+impl AudioConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if let OutputSampleRate::Fixed(rate) = self.sample_rate {
+            if !(MIN_OUTPUT_SAMPLE_RATE..=MAX_OUTPUT_SAMPLE_RATE).contains(&rate) {
+                return Err(format!(
+                    "Output sample rate must be between {} and {} Hz",
+                    MIN_OUTPUT_SAMPLE_RATE, MAX_OUTPUT_SAMPLE_RATE
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The rate range of one of a device's supported output configurations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupportedRates {
+    pub channels: u16,
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Format the open stream actually runs at, which can differ from the
+/// one requested when the device doesn't support it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Channel count to request from a device whose supported configurations
+/// have the `supported` channel counts. `None` keeps the device default.
+pub fn output_channel_count(config: &AudioConfig, supported: &[u16]) -> Option<u16> {
+    match config.output_channels {
+        OutputChannels::DeviceNative => None,
+        OutputChannels::Stereo => supported.contains(&2).then_some(2),
+    }
+}
+
+/// Sample rate to request for a stream of `channels` channels (any, if
+/// unknown) playing a track at `source_rate`. `None` keeps the device
+/// default, including when no supported configuration offers the rate.
+pub fn output_sample_rate(
+    config: &AudioConfig,
+    source_rate: Option<u32>,
+    channels: Option<u16>,
+    supported: &[SupportedRates],
+) -> Option<u32> {
+    let wanted = match config.sample_rate {
+        OutputSampleRate::DeviceDefault => return None,
+        OutputSampleRate::MatchSource => source_rate?,
+        OutputSampleRate::Fixed(rate) => rate,
+    };
+    let offered = supported
+        .iter()
+        .filter(|s| channels.is_none_or(|c| s.channels == c))
+        .any(|s| (s.min..=s.max).contains(&wanted));
+    if !offered {
+        warn!("Device does not support {} Hz output; using its default rate", wanted);
+    }
+    offered.then_some(wanted)
+}
+
 /// Holds the audio output resources (stream, mixer, device info).
+///
+/// Stream and mixer are `None` in "no device" mode: the app started without
+/// an output device, or the last reinit failed.
 pub struct DeviceState {
     pub stream: Option<SendOutputStream>,
     // We hold the mixer to connect new Sinks to the output.
     // Mixer is a handle (Arc<Inner>) so it is cheap to clone and Send.
     pub mixer: Option<Mixer>,
     pub connected_device_name: Option<String>,
+    /// Device the user picked; `None` follows the system default. Every
+    /// reopen goes to this device.
+    pub requested_device: Option<String>,
+    /// Format of the open stream.
+    pub format: Option<OutputFormat>,
+    /// Rate of the track the stream was opened for, if one was loaded.
+    pub source_rate: Option<u32>,
     pub last_active: Instant,
     /// Monotonically increasing counter, bumped on every device reinit.
     /// Used by PreloadManager to detect stale preloaded sinks that were
@@ -49,19 +173,42 @@ pub struct DeviceState {
 impl DeviceState {
     pub fn new(stream: OutputStream, mixer: Mixer, device_name: Option<String>) -> Self {
         Self {
+            format: Some(stream_format(&stream)),
             stream: Some(SendOutputStream(stream)),
             mixer: Some(mixer),
             connected_device_name: device_name,
+            requested_device: None,
+            source_rate: None,
+            last_active: Instant::now(),
+            generation: 0,
+        }
+    }
+
+    /// State with no output stream, used when no device could be opened.
+    pub fn disconnected() -> Self {
+        Self {
+            stream: None,
+            mixer: None,
+            connected_device_name: None,
+            requested_device: None,
+            format: None,
+            source_rate: None,
             last_active: Instant::now(),
             generation: 0,
         }
     }
 
+    /// Whether an output stream is open.
+    pub fn is_connected(&self) -> bool {
+        self.mixer.is_some()
+    }
+
     pub fn update_active(&mut self) {
         self.last_active = Instant::now();
     }
 
     pub fn replace(&mut self, stream: OutputStream, mixer: Mixer, device_name: Option<String>) {
+        self.format = Some(stream_format(&stream));
         self.stream = Some(SendOutputStream(stream));
         self.mixer = Some(mixer);
         self.connected_device_name = device_name;
@@ -70,7 +217,7 @@ impl DeviceState {
     }
 
     pub fn has_device_changed(&self) -> bool {
-        has_device_changed(&self.connected_device_name)
+        has_device_changed(&self.connected_device_name, self.requested_device.is_none())
     }
 
     /// Returns a reference to the mixer handle.
@@ -85,30 +232,176 @@ impl DeviceState {
 }
 
 /// Audio device information
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct AudioDevice {
     pub name: String,
     pub is_default: bool,
 }
 
-/// Creates a high-quality (F32) output stream and returns it along with the mixer handle.
-pub fn create_high_quality_output_with_device_name() -> AppResult<(OutputStream, Mixer, Option<String>)> {
+/// How often the background poll re-enumerates output devices.
+pub const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Minimum gap between `devices-changed` events; changes inside the gap are
+/// coalesced into the next event.
+pub const DEVICE_EVENT_MIN_GAP: Duration = Duration::from_secs(3);
+
+/// Payload of the `devices-changed` event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicesChanged {
+    pub devices: Vec<AudioDevice>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub default_changed: bool,
+}
+
+/// Payload of the `audio-reinitialized` event, sent after the output stream
+/// is reopened (device change, long pause, recovery or new audio config).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioReinitialized {
+    pub device_name: Option<String>,
+    /// Where the reloaded track resumes, in seconds (0 with no track loaded).
+    pub position: f64,
+}
+
+/// Called after each successful reinit; main routes it to the frontend.
+pub type ReinitListener = Box<dyn Fn(&AudioReinitialized) + Send + Sync>;
+
+/// Compare two device listings by name. `None` if nothing changed.
+pub fn diff_device_lists(old: &[AudioDevice], new: &[AudioDevice]) -> Option<DevicesChanged> {
+    let added: Vec<String> = new
+        .iter()
+        .filter(|d| !old.iter().any(|o| o.name == d.name))
+        .map(|d| d.name.clone())
+        .collect();
+    let removed: Vec<String> = old
+        .iter()
+        .filter(|o| !new.iter().any(|d| d.name == o.name))
+        .map(|o| o.name.clone())
+        .collect();
+    let default_of = |list: &[AudioDevice]| list.iter().find(|d| d.is_default).map(|d| d.name.clone());
+    let default_changed = default_of(old) != default_of(new);
+
+    if added.is_empty() && removed.is_empty() && !default_changed {
+        return None;
+    }
+    Some(DevicesChanged {
+        devices: new.to_vec(),
+        added,
+        removed,
+        default_changed,
+    })
+}
+
+/// Tracks the last reported device list and throttles change events.
+pub struct DeviceListWatcher {
+    known: Option<Vec<AudioDevice>>,
+    last_emit: Option<Instant>,
+}
+
+impl DeviceListWatcher {
+    pub fn new() -> Self {
+        Self { known: None, last_emit: None }
+    }
+
+    /// Feed a fresh listing taken at `now`. The first call only records a
+    /// baseline. Returns the change to emit, if any and if the throttle
+    /// allows; a throttled change is reported by a later call instead.
+    pub fn observe(&mut self, devices: Vec<AudioDevice>, now: Instant) -> Option<DevicesChanged> {
+        let Some(known) = &self.known else {
+            self.known = Some(devices);
+            return None;
+        };
+        let change = diff_device_lists(known, &devices)?;
+        if let Some(at) = self.last_emit {
+            if now.saturating_duration_since(at) < DEVICE_EVENT_MIN_GAP {
+                return None;
+            }
+        }
+        self.known = Some(devices);
+        self.last_emit = Some(now);
+        Some(change)
+    }
+}
+
+impl Default for DeviceListWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn stream_format(stream: &OutputStream) -> OutputFormat {
+    OutputFormat {
+        sample_rate: stream.config().sample_rate(),
+        channels: stream.config().channel_count(),
+    }
+}
+
+/// The output device called `name` on `host`.
+fn find_output_device(host: &rodio::cpal::Host, name: &str) -> AppResult<rodio::cpal::Device> {
+    host.output_devices()
+        .map_err(|e| AppError::Audio(format!("Failed to enumerate devices: {}", e)))?
+        .find(|d| d.name().ok().as_deref() == Some(name))
+        .ok_or_else(|| AppError::NotFound(format!("Device '{}' not found", name)))
+}
+
+/// Whether an output device called `name` is currently present.
+pub fn output_device_present(name: &str) -> bool {
+    find_output_device(&rodio::cpal::default_host(), name).is_ok()
+}
+
+/// Creates a high-quality (F32) output stream on `device_name`, or on the
+/// system default device for `None`, and returns it along with the mixer
+/// handle and the name of the device actually opened.
+pub fn create_high_quality_output_with_device_name(
+    config: &AudioConfig,
+    source_rate: Option<u32>,
+    device_name: Option<&str>,
+) -> AppResult<(OutputStream, Mixer, Option<String>)> {
     let host = rodio::cpal::default_host();
-    let device = host.default_output_device()
-        .ok_or_else(|| AppError::Audio("No output device available".to_string()))?;
+    let device = match device_name {
+        Some(name) => find_output_device(&host, name)?,
+        None => host.default_output_device()
+            .ok_or_else(|| AppError::Audio("No output device available".to_string()))?,
+    };
     
     let device_name = device.name().ok();
     info!("Using audio device: {:?}", device_name);
 
-    if let Ok(config) = device.default_output_config() {
+    let default_config = device.default_output_config().ok();
+    if let Some(config) = &default_config {
          info!("Device default sample rate: {}", config.sample_rate().0);
     }
     
+    let supported: Vec<SupportedRates> = device
+        .supported_output_configs()
+        .map(|configs| {
+            configs
+                .map(|c| SupportedRates {
+                    channels: c.channels(),
+                    min: c.min_sample_rate().0,
+                    max: c.max_sample_rate().0,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let supported_channels: Vec<u16> = supported.iter().map(|s| s.channels).collect();
+
     // We use OutputStreamBuilder to customize the stream
-    let result = OutputStreamBuilder::from_device(device.clone())
+    let mut builder = OutputStreamBuilder::from_device(device.clone())
         .map_err(|e| AppError::Audio(format!("Failed to create stream builder: {}", e)))?
-        .with_sample_format(rodio::cpal::SampleFormat::F32)
-        .open_stream();
+        .with_sample_format(rodio::cpal::SampleFormat::F32);
+    let channels = output_channel_count(config, &supported_channels);
+    if let Some(channels) = channels {
+        info!("Opening output with {} channels", channels);
+        builder = builder.with_channels(channels);
+    }
+    let stream_channels = channels.or(default_config.as_ref().map(|c| c.channels()));
+    if let Some(rate) = output_sample_rate(config, source_rate, stream_channels, &supported) {
+        info!("Opening output at {} Hz", rate);
+        builder = builder.with_sample_rate(rate);
+    }
+    let result = builder.open_stream();
         
     match result {
         Ok(stream) => {
@@ -117,9 +410,11 @@ pub fn create_high_quality_output_with_device_name() -> AppResult<(OutputStream,
              Ok((stream, mixer, device_name))
         },
         Err(e) => {
-            // Fallback to default if F32 fails (unlikely given rodio converts, but possible)
-            warn!("Failed to open F32 stream, trying default config: {}", e);
-            let stream = OutputStreamBuilder::open_default_stream()
+            // Fall back to the device's own config if F32 fails (unlikely
+            // given rodio converts, but possible). Same device either way.
+            warn!("Failed to open F32 stream, trying the device's default config: {}", e);
+            let stream = OutputStreamBuilder::from_device(device)
+                .and_then(|builder| builder.open_stream())
                 .map_err(|e| AppError::Audio(format!("Failed to open default stream: {}", e)))?;
             let mixer = stream.mixer().clone();
             Ok((stream, mixer, device_name))
@@ -128,6 +423,8 @@ pub fn create_high_quality_output_with_device_name() -> AppResult<(OutputStream,
 }
 
 /// Check if the audio situation has changed in a way that requires reinit.
+/// `follows_default` is false when the user picked the device, in which case
+/// only its disappearance counts.
 ///
 /// Returns `true` in two cases:
 ///
@@ -143,7 +440,7 @@ pub fn create_high_quality_output_with_device_name() -> AppResult<(OutputStream,
 ///    `reinit_and_reload()` → `create_high_quality_output_with_device_name()`
 ///    to open a fresh stream on the current Windows default, restoring audio
 ///    without requiring an app restart.
-pub fn has_device_changed(connected_device_name: &Option<String>) -> bool {
+pub fn has_device_changed(connected_device_name: &Option<String>, follows_default: bool) -> bool {
     let name = match connected_device_name {
         Some(n) => n,
         // If we don't know what we connected to, assume it hasn't changed.
@@ -167,6 +464,10 @@ pub fn has_device_changed(connected_device_name: &Option<String>) -> bool {
     // This covers the "started app with device off, device powers on, Windows
     // promotes it to default" scenario. The old device is still present so
     // Check 1 passes, but we are sending audio to the wrong endpoint.
+    // A device the user picked stays in use whatever the default is.
+    if !follows_default {
+        return false;
+    }
     let default_name = host
         .default_output_device()
         .and_then(|d| d.name().ok());
@@ -194,9 +495,160 @@ pub fn is_device_available() -> bool {
     true
 }
 
+/// Test tone played by [`test_output_device`]: short and quiet enough to
+/// pass unnoticed under music, long enough to exercise the stream.
+const TEST_TONE_HZ: f32 = 440.0;
+const TEST_TONE_DURATION: Duration = Duration::from_millis(50);
+const TEST_TONE_AMPLITUDE: f32 = 0.01;
+/// How long the tone may take to drain before the device counts as stalled.
+const TEST_TONE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of probing an output device with [`test_output_device`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTestResult {
+    pub device_name: String,
+    pub ok: bool,
+    /// Format the test stream opened with, when it opened.
+    pub format: Option<OutputFormat>,
+    pub error: Option<String>,
+}
+
+/// An output device that can be probed; implemented for cpal devices and
+/// by stubs in tests.
+pub trait ProbeableDevice {
+    fn device_name(&self) -> Option<String>;
+    /// Open a separate stream, play `duration` of a quiet tone through it
+    /// and close it again, returning the format it ran at.
+    fn play_test_tone(&self, duration: Duration) -> Result<OutputFormat, String>;
+}
+
+impl ProbeableDevice for rodio::cpal::Device {
+    fn device_name(&self) -> Option<String> {
+        self.name().ok()
+    }
+
+    fn play_test_tone(&self, duration: Duration) -> Result<OutputFormat, String> {
+        use rodio::Source;
+
+        let mut stream = OutputStreamBuilder::from_device(self.clone())
+            .map_err(|e| format!("Failed to create stream builder: {}", e))?
+            .open_stream()
+            .map_err(|e| format!("Failed to open stream: {}", e))?;
+        stream.log_on_drop(false);
+        let format = stream_format(&stream);
+
+        let sink = rodio::Sink::connect_new(stream.mixer());
+        sink.append(
+            rodio::source::SineWave::new(TEST_TONE_HZ)
+                .take_duration(duration)
+                .amplify(TEST_TONE_AMPLITUDE),
+        );
+        // A stalled device (e.g. a Bluetooth headset mid-handshake) opens
+        // fine but never pulls samples, so don't wait on it forever.
+        let deadline = Instant::now() + duration + TEST_TONE_TIMEOUT;
+        while !sink.empty() {
+            if Instant::now() >= deadline {
+                return Err("Device opened but did not play the test tone".to_string());
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(format)
+    }
+}
+
+/// Find `device_name` among `devices` and play a short test tone on it.
+/// Uses a stream of its own, so current playback is left alone.
+pub fn test_output_device<D: ProbeableDevice>(devices: impl IntoIterator<Item = D>, device_name: &str) -> DeviceTestResult {
+    let failed = |error: String| {
+        warn!("Output device test failed for {}: {}", device_name, error);
+        DeviceTestResult {
+            device_name: device_name.to_string(),
+            ok: false,
+            format: None,
+            error: Some(error),
+        }
+    };
+
+    let Some(device) = devices
+        .into_iter()
+        .find(|d| d.device_name().as_deref() == Some(device_name))
+    else {
+        return failed(format!("Device '{}' not found", device_name));
+    };
+
+    match device.play_test_tone(TEST_TONE_DURATION) {
+        Ok(format) => {
+            info!("Output device {} passed test at {:?}", device_name, format);
+            DeviceTestResult {
+                device_name: device_name.to_string(),
+                ok: true,
+                format: Some(format),
+                error: None,
+            }
+        }
+        Err(e) => failed(e),
+    }
+}
+
+/// Probe `device_name` on the default host.
+pub fn test_audio_device(device_name: &str) -> AppResult<DeviceTestResult> {
+    let devices = rodio::cpal::default_host()
+        .output_devices()
+        .map_err(|e| AppError::Audio(format!("Failed to enumerate devices: {}", e)))?;
+    Ok(test_output_device(devices, device_name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    struct StubDevice {
+        name: &'static str,
+        result: Result<OutputFormat, &'static str>,
+        played: Cell<Option<Duration>>,
+    }
+
+    impl StubDevice {
+        fn new(name: &'static str, result: Result<OutputFormat, &'static str>) -> Self {
+            Self { name, result, played: Cell::new(None) }
+        }
+    }
+
+    impl ProbeableDevice for &StubDevice {
+        fn device_name(&self) -> Option<String> {
+            Some(self.name.to_string())
+        }
+
+        fn play_test_tone(&self, duration: Duration) -> Result<OutputFormat, String> {
+            self.played.set(Some(duration));
+            self.result.map_err(str::to_string)
+        }
+    }
+
+    #[test]
+    fn device_test_reports_open_result_of_the_named_device_only() {
+        let stereo = OutputFormat { sample_rate: 48_000, channels: 2 };
+        let speakers = StubDevice::new("Speakers", Ok(stereo));
+        let headset = StubDevice::new("BT Headset", Err("Failed to open stream: device busy"));
+
+        let result = test_output_device([&speakers, &headset], "Speakers");
+        assert!(result.ok);
+        assert_eq!(result.format, Some(stereo));
+        assert_eq!(result.error, None);
+        assert_eq!(speakers.played.get(), Some(TEST_TONE_DURATION));
+        assert_eq!(headset.played.get(), None, "other devices are not touched");
+
+        let result = test_output_device([&speakers, &headset], "BT Headset");
+        assert!(!result.ok);
+        assert_eq!(result.format, None);
+        assert_eq!(result.error.as_deref(), Some("Failed to open stream: device busy"));
+
+        let result = test_output_device([&speakers, &headset], "HDMI");
+        assert!(!result.ok);
+        assert!(result.error.unwrap().contains("not found"));
+    }
 
     // ── F-017e / F-009: device-change detection (hardware-free) ──────────────
 
@@ -206,7 +658,7 @@ mod tests {
     fn has_device_changed_returns_false_when_no_device_recorded() {
         let connected: Option<String> = None;
         assert!(
-            !has_device_changed(&connected),
+            !has_device_changed(&connected, true),
             "has_device_changed should return false when connected_device_name is None"
         );
     }
@@ -217,7 +669,7 @@ mod tests {
     fn has_device_changed_returns_true_for_nonexistent_device() {
         let connected = Some("VPlayer_NonExistent_Audio_Device_xyz_1a2b3c".to_string());
         assert!(
-            has_device_changed(&connected),
+            has_device_changed(&connected, true),
             "has_device_changed should return true when the device is absent from OS list"
         );
     }
@@ -236,7 +688,7 @@ mod tests {
         };
         let connected = Some(default_name);
         assert!(
-            !has_device_changed(&connected),
+            !has_device_changed(&connected, true),
             "has_device_changed should return false when connected to the current default device"
         );
     }
@@ -281,7 +733,7 @@ mod tests {
             // Check 2 should fire: default != connected_name.
             let connected = Some(connected_name.clone());
             assert!(
-                has_device_changed(&connected),
+                has_device_changed(&connected, true),
                 "has_device_changed should return true when connected device is not the current default"
             );
         }
@@ -289,6 +741,102 @@ mod tests {
         // we cannot simulate a default-switch without real hardware.
     }
 
+    fn dev(name: &str, is_default: bool) -> AudioDevice {
+        AudioDevice { name: name.to_string(), is_default }
+    }
+
+    #[test]
+    fn diff_detects_added_and_removed_devices() {
+        let before = vec![dev("Speakers", true), dev("HDMI", false)];
+        let after = vec![dev("Speakers", true), dev("Headphones", false)];
+
+        let change = diff_device_lists(&before, &after).expect("lists differ");
+        assert_eq!(change.added, vec!["Headphones".to_string()]);
+        assert_eq!(change.removed, vec!["HDMI".to_string()]);
+        assert!(!change.default_changed);
+        assert_eq!(change.devices, after);
+
+        assert!(diff_device_lists(&after, &after.clone()).is_none());
+    }
+
+    #[test]
+    fn diff_detects_default_switch_without_list_change() {
+        let before = vec![dev("Speakers", true), dev("Headphones", false)];
+        let after = vec![dev("Speakers", false), dev("Headphones", true)];
+
+        let change = diff_device_lists(&before, &after).expect("default moved");
+        assert!(change.added.is_empty() && change.removed.is_empty());
+        assert!(change.default_changed);
+    }
+
+    #[test]
+    fn watcher_sets_baseline_then_throttles_and_coalesces() {
+        let mut watcher = DeviceListWatcher::new();
+        let t0 = Instant::now();
+        let one = vec![dev("Speakers", true)];
+        let two = vec![dev("Speakers", true), dev("Headphones", false)];
+        let three = vec![dev("Speakers", true), dev("Headphones", false), dev("DAC", false)];
+
+        assert!(watcher.observe(one.clone(), t0).is_none(), "first listing is the baseline");
+        assert!(watcher.observe(one, t0 + Duration::from_secs(1)).is_none());
+
+        let change = watcher.observe(two, t0 + Duration::from_secs(2)).unwrap();
+        assert_eq!(change.added, vec!["Headphones".to_string()]);
+
+        // Inside the gap: held back, then reported once the gap has passed.
+        assert!(watcher.observe(three.clone(), t0 + Duration::from_secs(3)).is_none());
+        let change = watcher.observe(three, t0 + Duration::from_secs(2) + DEVICE_EVENT_MIN_GAP).unwrap();
+        assert_eq!(change.added, vec!["DAC".to_string()]);
+    }
+
+    #[test]
+    fn stereo_output_selects_two_channels_when_supported() {
+        let stereo = AudioConfig { output_channels: OutputChannels::Stereo, ..Default::default() };
+        assert_eq!(output_channel_count(&stereo, &[8, 6, 2]), Some(2));
+        assert_eq!(output_channel_count(&stereo, &[8, 6]), None, "no stereo layout: keep the device default");
+
+        let native = AudioConfig::default();
+        assert_eq!(native.output_channels, OutputChannels::DeviceNative);
+        assert_eq!(output_channel_count(&native, &[8, 6, 2]), None);
+    }
+
+    fn rates(channels: u16, min: u32, max: u32) -> SupportedRates {
+        SupportedRates { channels, min, max }
+    }
+
+    #[test]
+    fn sample_rate_follows_the_mode_and_what_the_device_supports() {
+        // A DAC running stereo at 44.1–96 kHz, plus a 5.1 layout up to 192 kHz.
+        let supported = [rates(2, 44_100, 96_000), rates(6, 44_100, 192_000)];
+        let with = |sample_rate| AudioConfig { sample_rate, ..Default::default() };
+
+        let default = with(OutputSampleRate::DeviceDefault);
+        assert_eq!(output_sample_rate(&default, Some(48_000), Some(2), &supported), None);
+
+        let source = with(OutputSampleRate::MatchSource);
+        assert_eq!(output_sample_rate(&source, Some(44_100), Some(2), &supported), Some(44_100));
+        assert_eq!(output_sample_rate(&source, Some(88_200), Some(2), &supported), Some(88_200));
+        // Nothing loaded yet, or a rate the device can't run at.
+        assert_eq!(output_sample_rate(&source, None, Some(2), &supported), None);
+        assert_eq!(output_sample_rate(&source, Some(22_050), Some(2), &supported), None);
+
+        let fixed = with(OutputSampleRate::Fixed(192_000));
+        // Only offered by the 5.1 layout.
+        assert_eq!(output_sample_rate(&fixed, Some(44_100), Some(2), &supported), None);
+        assert_eq!(output_sample_rate(&fixed, Some(44_100), Some(6), &supported), Some(192_000));
+        assert_eq!(output_sample_rate(&fixed, None, None, &supported), Some(192_000));
+        assert_eq!(output_sample_rate(&fixed, None, None, &[]), None);
+    }
+
+    #[test]
+    fn fixed_sample_rate_is_validated() {
+        let with = |rate| AudioConfig { sample_rate: OutputSampleRate::Fixed(rate), ..Default::default() };
+        assert!(with(48_000).validate().is_ok());
+        assert!(with(0).validate().is_err());
+        assert!(with(1_000_000).validate().is_err());
+        assert!(AudioConfig::default().validate().is_ok());
+    }
+
     /// `is_device_available` must not panic regardless of whether hardware is
     /// present. The return value is environment-dependent.
     #[test]
@@ -0,0 +1,64 @@
+//! Output volume remembered per device.
+//!
+//! Headphones usually want a much lower level than speakers, so switching
+//! output devices restores the volume last used on the new device instead
+//! of carrying the current one over.
+
+use crate::database::Database;
+use log::warn;
+use std::collections::HashMap;
+
+/// Settings key under which device name → volume is persisted.
+pub const DEVICE_VOLUMES_KEY: &str = "device_volumes";
+
+/// The stored volumes; empty when nothing (or something unreadable) is stored.
+pub fn stored_device_volumes(db: &Database) -> HashMap<String, f32> {
+    db.get_json_setting::<HashMap<String, f32>>(DEVICE_VOLUMES_KEY)
+        .unwrap_or_else(|e| {
+            warn!("Failed to read device volumes: {}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Record `volume` as the level of the device being left (`from`, if known)
+/// and return the level last used on `to`, if there is one.
+pub fn switch_device_volume(db: &Database, from: Option<&str>, volume: f32, to: &str) -> Option<f32> {
+    let mut volumes = stored_device_volumes(db);
+    if let Some(from) = from {
+        volumes.insert(from.to_string(), volume.clamp(0.0, 1.0));
+        if let Err(e) = db.set_json_setting(DEVICE_VOLUMES_KEY, &volumes) {
+            warn!("Failed to save volume for {}: {}", from, e);
+        }
+    }
+    volumes.get(to).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switching_restores_the_level_stored_for_the_new_device() {
+        let dir = std::env::temp_dir().join(format!("vplayer_device_volume_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(&dir.join("library.db")).unwrap();
+
+        // Nothing stored for the headphones yet: the current level carries over.
+        assert_eq!(switch_device_volume(&db, Some("Speakers"), 0.9, "Headphones"), None);
+        // Turned down on the headphones, then back to the speakers.
+        assert_eq!(switch_device_volume(&db, Some("Headphones"), 0.2, "Speakers"), Some(0.9));
+        assert_eq!(switch_device_volume(&db, Some("Speakers"), 0.8, "Headphones"), Some(0.2));
+
+        let volumes = stored_device_volumes(&db);
+        assert_eq!(volumes.get("Speakers"), Some(&0.8));
+        assert_eq!(volumes.get("Headphones"), Some(&0.2));
+
+        // An unknown previous device records nothing.
+        assert_eq!(switch_device_volume(&db, None, 0.5, "Speakers"), Some(0.8));
+        assert_eq!(stored_device_volumes(&db).len(), 2);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -7,9 +7,12 @@ use rodio::{Source};
 use rodio::source::SeekError;
 use rodio::cpal::FromSample;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
 use std::time::Duration;
 use crate::effects::EffectsProcessor;
+use super::normalize::{NormalizeMode, PeakNormalizer};
+use super::pcm_tap::PcmTap;
+use super::playback_state::SampleClock;
 use super::visualizer::VisualizerBuffer;
 
 /// EffectsSource wraps a Source and applies audio effects (EQ, etc.) to each sample
@@ -31,7 +34,28 @@ where
     /// Shared atomic balance value (f32 stored as u32 bits).
     /// -1.0 = full left, 0.0 = center, 1.0 = full right.
     balance: Arc<AtomicU32>,
+    /// Shared atomic ReplayGain multiplier (f32 stored as u32 bits), applied
+    /// ahead of the effects so boosts above unity are not lost to the sink.
+    replaygain: Arc<AtomicU32>,
+    /// Shared atomic NormalizeMode (see `NormalizeMode::as_u8`).
+    normalize_mode: Arc<AtomicU8>,
+    /// Real-time peak normalizer, active in `NormalizeMode::Peak`.
+    normalizer: PeakNormalizer,
+    /// Counts samples handed downstream for sample-accurate position.
+    clock: Arc<SampleClock>,
+    /// Position (seconds) at which the source ends early, e.g. to skip
+    /// trailing silence.
+    end_at: Option<f64>,
+    /// Receives a copy of every output sample while enabled.
+    pcm_tap: Option<Arc<PcmTap>>,
+    /// Whether the tap was enabled when the current batch was read.
+    tap_active: bool,
     sample_rate_initialized: bool,
+    /// Set by the input's [`WatchedReader`](super::read_errors::WatchedReader)
+    /// when reading the file failed. rodio's decoder already skips packets
+    /// that fail to decode, so its `None` only tells that the stream ended;
+    /// this says whether it ended on an error.
+    read_failed: Option<Arc<AtomicBool>>,
     /// Tracks interleaved channel position (0 = left, 1 = right, etc.)
     channel_index: u16,
     /// Internal buffer for batched processing
@@ -50,18 +74,48 @@ where
         processor: Arc<Mutex<EffectsProcessor>>,
         visualizer_buffer: Arc<VisualizerBuffer>,
         balance: Arc<AtomicU32>,
+        replaygain: Arc<AtomicU32>,
+        normalize_mode: Arc<AtomicU8>,
     ) -> Self {
         Self {
             input,
             processor,
             visualizer_buffer,
             balance,
+            replaygain,
+            normalize_mode,
+            normalizer: PeakNormalizer::new(),
+            clock: Arc::new(SampleClock::new()),
+            end_at: None,
+            pcm_tap: None,
+            tap_active: false,
             sample_rate_initialized: false,
+            read_failed: None,
             channel_index: 0,
             batch_buf: Vec::with_capacity(BATCH_SIZE),
             batch_pos: 0,
         }
     }
+
+    /// The clock tracking this source's playback position.
+    pub fn clock(&self) -> Arc<SampleClock> {
+        self.clock.clone()
+    }
+
+    /// Stop yielding samples once playback reaches `secs`.
+    pub fn set_end(&mut self, secs: Option<f64>) {
+        self.end_at = secs;
+    }
+
+    /// Copy output samples into `tap` whenever it is enabled.
+    pub fn set_pcm_tap(&mut self, tap: Arc<PcmTap>) {
+        self.pcm_tap = Some(tap);
+    }
+
+    /// Report the end of the input as a failure once `failed` is set.
+    pub fn set_read_failed(&mut self, failed: Arc<AtomicBool>) {
+        self.read_failed = Some(failed);
+    }
 }
 
 impl<I> Iterator for EffectsSource<I>
@@ -77,6 +131,11 @@ where
             let source_sample_rate = self.input.sample_rate();
             if let Ok(mut processor) = self.processor.lock() {
                 processor.set_sample_rate(source_sample_rate);
+                processor.set_channels(self.input.channels());
+            }
+            self.clock.set_format(source_sample_rate, self.input.channels());
+            if let Some(tap) = &self.pcm_tap {
+                tap.set_format(source_sample_rate, self.input.channels());
             }
             self.sample_rate_initialized = true;
         }
@@ -86,8 +145,17 @@ where
             self.batch_buf.clear();
             self.batch_pos = 0;
 
-            // Read up to BATCH_SIZE raw samples from input
-            for _ in 0..BATCH_SIZE {
+            if let (Some(end), Some(position)) = (self.end_at, self.clock.position()) {
+                if position >= end {
+                    return None;
+                }
+            }
+
+            // Read up to BATCH_SIZE raw samples from input, rounded down to
+            // whole frames so every batch starts on channel 0.
+            let frame_len = self.input.channels().max(1) as usize;
+            let batch_len = (BATCH_SIZE - BATCH_SIZE % frame_len).max(frame_len);
+            for _ in 0..batch_len {
                 match self.input.next() {
                     Some(s) => self.batch_buf.push(f32::from_sample_(s)),
                     None => break,
@@ -95,9 +163,30 @@ where
             }
 
             if self.batch_buf.is_empty() {
-                log::debug!("EffectsSource input returned None - track finished or decode error");
+                if self.read_failed.as_ref().is_some_and(|failed| failed.load(Ordering::Relaxed)) {
+                    log::debug!("EffectsSource input stopped on a read error - ending track early");
+                    self.clock.mark_read_failed();
+                } else {
+                    log::debug!("EffectsSource input returned None - track finished");
+                }
                 return None;
             }
+            self.tap_active = self.pcm_tap.as_ref().is_some_and(|tap| tap.is_enabled());
+
+            // Peak normalization stage (no-op unless the mode is Peak)
+            if NormalizeMode::from_u8(self.normalize_mode.load(Ordering::Relaxed)) == NormalizeMode::Peak {
+                self.normalizer.process(&mut self.batch_buf, self.input.sample_rate(), self.input.channels());
+            } else {
+                self.normalizer.reset();
+            }
+
+            // ReplayGain stage (lock-free atomic read, once per batch)
+            let gain = f32::from_bits(self.replaygain.load(Ordering::Relaxed));
+            if gain != 1.0 {
+                for sample in self.batch_buf.iter_mut() {
+                    *sample *= gain;
+                }
+            }
 
             // Acquire effects lock once for the whole batch
             match self.processor.try_lock() {
@@ -114,9 +203,11 @@ where
         // Yield the next sample from the batch
         let processed = self.batch_buf[self.batch_pos];
         self.batch_pos += 1;
+        self.clock.advance(1);
 
         // Apply stereo balance (lock-free atomic read)
         let channels = self.input.channels();
+        let channel = self.channel_index;
         let balanced = if channels >= 2 {
             let balance = f32::from_bits(self.balance.load(Ordering::Relaxed));
             let gain = if self.channel_index == 0 {
@@ -133,7 +224,12 @@ where
         };
 
         // Send sample to visualizer buffer (lock-free)
-        self.visualizer_buffer.push(balanced);
+        self.visualizer_buffer.push_channel(balanced, channel, channels);
+        if self.tap_active {
+            if let Some(tap) = &self.pcm_tap {
+                tap.push(balanced);
+            }
+        }
 
         Some(balanced)
     }
@@ -167,6 +263,7 @@ where
             // fresh audio from the seeked position rather than leftover batch data.
             self.batch_buf.clear();
             self.batch_pos = 0;
+            self.clock.reset_to(pos.as_secs_f64());
         }
         result
     }
@@ -182,3 +279,323 @@ where
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::normalize::PEAK_TARGET;
+    use crate::audio::volume_manager::VolumeManager;
+    use crate::audio::read_errors::WatchedReader;
+    use crate::effects::EffectsConfig;
+    use rodio::buffer::SamplesBuffer;
+    use rodio::Decoder;
+    use std::io::{self, BufReader, Read, Seek, SeekFrom};
+    use std::path::{Path, PathBuf};
+    use symphonia::core::checksum::{Crc16Ansi, Crc8Ccitt};
+    use symphonia::core::io::Monitor;
+
+    fn sine(amplitude: f32, secs: usize) -> Vec<f32> {
+        (0..44100 * secs)
+            .map(|n| (2.0 * std::f32::consts::PI * 1000.0 * n as f32 / 44100.0).sin() * amplitude)
+            .collect()
+    }
+
+    fn effects_source(samples: Vec<f32>, multiplier: f32, mode: NormalizeMode) -> EffectsSource<SamplesBuffer> {
+        EffectsSource::new(
+            SamplesBuffer::new(1, 44100, samples),
+            Arc::new(Mutex::new(EffectsProcessor::new(44100, EffectsConfig::default()))),
+            Arc::new(VisualizerBuffer::new(4096)),
+            Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            Arc::new(AtomicU32::new(multiplier.to_bits())),
+            Arc::new(AtomicU8::new(mode.as_u8())),
+        )
+    }
+
+    /// RMS of a 1 kHz sine at `amplitude` after the ReplayGain stage, effects
+    /// and a sink at `volume`.
+    fn output_rms(amplitude: f32, gain_db: Option<f32>, volume: f32) -> f32 {
+        let samples = sine(amplitude, 1);
+
+        let mut vm = VolumeManager::new();
+        let sink_volume = vm.set_volume(volume);
+        let multiplier = match gain_db {
+            Some(db) => vm.set_replaygain(db, 0.0, Some(amplitude)),
+            None => vm.clear_replaygain(),
+        };
+
+        let source = effects_source(samples, multiplier, NormalizeMode::ReplayGain);
+        let out: Vec<f32> = source.map(|s| s * sink_volume).collect();
+        (out.iter().map(|s| s * s).sum::<f32>() / out.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn positive_replaygain_is_realized_at_half_volume() {
+        let plain = output_rms(0.25, None, 0.5);
+        let boosted = output_rms(0.25, Some(6.0), 0.5);
+        let ratio_db = 20.0 * (boosted / plain).log10();
+        assert!((ratio_db - 6.0).abs() < 0.5, "expected ~+6 dB, got {:.2} dB", ratio_db);
+    }
+
+    #[test]
+    fn positive_replaygain_is_realized_at_full_volume() {
+        // Previously the sink volume ceiling swallowed any boost at 1.0.
+        let plain = output_rms(0.25, None, 1.0);
+        let boosted = output_rms(0.25, Some(6.0), 1.0);
+        assert!(boosted > plain * 1.8, "plain {:.4}, boosted {:.4}", plain, boosted);
+    }
+
+    #[test]
+    fn peak_mode_raises_quiet_signal_toward_target() {
+        let out: Vec<f32> = effects_source(sine(0.25, 4), 1.0, NormalizeMode::Peak).collect();
+
+        let peak = |s: &[f32]| s.iter().fold(0.0_f32, |m, x| m.max(x.abs()));
+        let settled = peak(&out[out.len() - 44100 / 2..]);
+        assert!(settled > 0.8, "quiet signal only reached {:.3}", settled);
+        assert!(peak(&out) <= PEAK_TARGET + 0.01, "output overshot to {:.3}", peak(&out));
+    }
+
+    #[test]
+    fn off_mode_leaves_signal_alone() {
+        let out: Vec<f32> = effects_source(sine(0.25, 1), 1.0, NormalizeMode::Off).collect();
+        let peak = out.iter().fold(0.0_f32, |m, x| m.max(x.abs()));
+        assert!((peak - 0.25).abs() < 0.01, "peak was {:.3}", peak);
+    }
+
+    #[test]
+    fn set_end_stops_the_source_early() {
+        let mut source = effects_source(sine(0.25, 2), 1.0, NormalizeMode::Off);
+        source.set_end(Some(0.5));
+
+        let pulled = source.by_ref().count();
+        // Stops within one batch of the requested end.
+        assert!((pulled as i64 - 22050).abs() <= BATCH_SIZE as i64, "pulled {}", pulled);
+    }
+
+    #[test]
+    fn clock_tracks_pulled_samples() {
+        let mut source = effects_source(sine(0.25, 2), 1.0, NormalizeMode::Off);
+        let clock = source.clock();
+
+        // Pull 0.75 s of mono audio at 44.1 kHz.
+        for _ in 0..33075 {
+            source.next().unwrap();
+        }
+        assert!((clock.position().unwrap() - 0.75).abs() < 1e-9);
+
+        source.try_seek(Duration::from_secs(1)).unwrap();
+        for _ in 0..4410 {
+            source.next().unwrap();
+        }
+        assert!((clock.position().unwrap() - 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn position_advances_at_playback_speed() {
+        // The sink's tempo stage is rodio's `speed`: it relabels the source
+        // at 1.5x its sample rate, so one second of output (that many
+        // samples, resampled by the mixer) covers 1.5s of track.
+        let source = effects_source(sine(0.25, 3), 1.0, NormalizeMode::Off);
+        let clock = source.clock();
+        let mut sped_up = source.speed(1.5);
+
+        for _ in 0..sped_up.sample_rate() {
+            sped_up.next().unwrap();
+        }
+        let position = clock.position().unwrap();
+        assert!((position - 1.5).abs() < 1e-3, "1s of output at 1.5x should be 1.5s, got {}", position);
+    }
+
+    #[test]
+    fn pcm_tap_captures_processed_samples_only_when_enabled() {
+        let tap = Arc::new(PcmTap::new(crate::audio::pcm_tap::PCM_TAP_CAPACITY));
+
+        let mut source = effects_source(sine(0.25, 1), 2.0, NormalizeMode::Off);
+        source.set_pcm_tap(tap.clone());
+        source.for_each(drop);
+        assert!(tap.read(usize::MAX).samples.is_empty(), "the tap is off by default");
+
+        tap.set_enabled(true);
+        let mut source = effects_source(sine(0.25, 1), 2.0, NormalizeMode::Off);
+        source.set_pcm_tap(tap.clone());
+        let out: Vec<f32> = source.collect();
+
+        let chunk = tap.read(usize::MAX);
+        assert_eq!(chunk.samples.len(), 44100);
+        assert_eq!((chunk.channels, chunk.sample_rate), (1, 44100));
+        assert_eq!(chunk.samples, out, "the tap sees exactly what the sink gets");
+        assert!(chunk.samples.iter().any(|s| s.abs() > 0.4), "ReplayGain is applied before the tap");
+    }
+
+    #[test]
+    fn visualizer_receives_channels_separately() {
+        // Hard-left stereo: signal on the left channel, silence on the right.
+        let samples: Vec<f32> = sine(0.25, 1).into_iter().flat_map(|s| [s, 0.0]).collect();
+        let visualizer = Arc::new(VisualizerBuffer::new(4096));
+        let source = EffectsSource::new(
+            SamplesBuffer::new(2, 44100, samples),
+            Arc::new(Mutex::new(EffectsProcessor::new(44100, EffectsConfig::default()))),
+            visualizer.clone(),
+            Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            Arc::new(AtomicU32::new(1.0_f32.to_bits())),
+            Arc::new(AtomicU8::new(NormalizeMode::Off.as_u8())),
+        );
+        source.for_each(drop);
+
+        let (left, right) = visualizer.get_channel_samples();
+        assert!(left.iter().any(|s| s.abs() > 0.1));
+        assert!(right.iter().all(|s| s.abs() < 1e-3));
+    }
+
+    /// A file that fails to read past `limit` bytes, like a disk or
+    /// network share giving out mid-track.
+    struct FailingFile {
+        file: std::fs::File,
+        limit: u64,
+    }
+
+    impl Read for FailingFile {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let pos = self.file.stream_position()?;
+            if pos >= self.limit {
+                return Err(io::Error::other("device not ready"));
+            }
+            let len = buf.len().min((self.limit - pos) as usize);
+            self.file.read(&mut buf[..len])
+        }
+    }
+
+    impl Seek for FailingFile {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.file.seek(pos)
+        }
+    }
+
+    /// Write `samples` as a 16-bit mono WAV.
+    fn write_wav(path: &Path, sample_rate: u32, samples: &[f32]) {
+        let data_len = samples.len() as u32 * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for &v in samples {
+            bytes.extend_from_slice(&((v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+        }
+        std::fs::write(path, bytes).expect("write wav failed");
+    }
+
+    /// Write `frames` blocks of 4096 samples as a 16-bit mono FLAC. Block
+    /// `corrupt` has its subframe's must-be-zero bit set: its framing and
+    /// checksums are intact, but the packet fails to decode.
+    fn write_flac(path: &Path, frames: u8, corrupt: u8) {
+        let mut bytes = b"fLaC".to_vec();
+        // STREAMINFO, the last metadata block: 4096-sample blocks, 44.1 kHz,
+        // mono, 16 bits, length and MD5 unknown.
+        bytes.extend_from_slice(&[0x80, 0, 0, 34]);
+        bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&[0x0a, 0xc4, 0x40, 0xf0, 0, 0, 0, 0]);
+        bytes.extend_from_slice(&[0; 16]);
+        for n in 0..frames {
+            // Fixed blocking; 4096 samples at 44.1 kHz; mono, 16 bits; frame n.
+            let mut frame = vec![0xff, 0xf8, 0xc9, 0x08, n];
+            let mut crc8 = Crc8Ccitt::new(0);
+            crc8.process_buf_bytes(&frame);
+            frame.push(crc8.crc());
+            // A CONSTANT subframe.
+            frame.extend_from_slice(&[if n == corrupt { 0x80 } else { 0x00 }, 0x20, 0x00]);
+            let mut crc16 = Crc16Ansi::new(0);
+            crc16.process_buf_bytes(&frame);
+            frame.extend_from_slice(&crc16.crc().to_be_bytes());
+            bytes.extend(frame);
+        }
+        std::fs::write(path, bytes).expect("write flac failed");
+    }
+
+    fn wav_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("vplayer_{}_{}.wav", name, uuid::Uuid::new_v4()))
+    }
+
+    /// Decode `path` the way the player does, through a `WatchedReader`.
+    fn file_source(path: &Path, limit: u64) -> EffectsSource<Decoder<BufReader<WatchedReader<FailingFile>>>> {
+        let file = std::fs::File::open(path).expect("open file");
+        let reader = WatchedReader::new(FailingFile { file, limit });
+        let read_failed = reader.failed();
+        let decoder = Decoder::new(BufReader::new(reader)).expect("decode file");
+        let mut source = EffectsSource::new(
+            decoder,
+            Arc::new(Mutex::new(EffectsProcessor::new(44100, EffectsConfig::default()))),
+            Arc::new(VisualizerBuffer::new(4096)),
+            Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            Arc::new(AtomicU32::new(1.0_f32.to_bits())),
+            Arc::new(AtomicU8::new(NormalizeMode::Off.as_u8())),
+        );
+        source.set_read_failed(read_failed);
+        source
+    }
+
+    #[test]
+    fn truncated_file_ends_without_a_read_error() {
+        // The header still promises 2s; only the first 0.5s made it to disk.
+        let path = wav_path("truncated");
+        write_wav(&path, 44100, &sine(0.25, 2));
+        std::fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(44 + 22050 * 2).unwrap();
+
+        let source = file_source(&path, u64::MAX);
+        let clock = source.clock();
+        let pulled = source.count();
+        std::fs::remove_file(&path).ok();
+
+        assert!(pulled > 0 && pulled <= 22050, "plays what is there, got {}", pulled);
+        assert!(!clock.read_failed(), "a short file is not a read error");
+    }
+
+    #[test]
+    fn read_error_mid_file_is_reported() {
+        let path = wav_path("read_error");
+        write_wav(&path, 44100, &sine(0.25, 2));
+
+        let source = file_source(&path, 100_000);
+        let clock = source.clock();
+        let pulled = source.count();
+        std::fs::remove_file(&path).ok();
+
+        assert!(pulled < 88200, "playback stops at the failed read");
+        assert!(clock.read_failed());
+    }
+
+    #[test]
+    fn complete_file_is_not_a_read_error() {
+        let path = wav_path("complete");
+        write_wav(&path, 44100, &sine(0.25, 1));
+
+        let source = file_source(&path, u64::MAX);
+        let clock = source.clock();
+        let pulled = source.count();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(pulled, 44100);
+        assert!(!clock.read_failed());
+    }
+
+    #[test]
+    fn corrupt_packet_is_skipped_without_a_read_error() {
+        let path = wav_path("corrupt_packet").with_extension("flac");
+        write_flac(&path, 10, 4);
+
+        let source = file_source(&path, u64::MAX);
+        let clock = source.clock();
+        let pulled = source.count();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(pulled, 9 * 4096, "plays every block but the corrupt one");
+        assert!(!clock.read_failed(), "a bad packet is not a read error");
+    }
+}
@@ -4,9 +4,15 @@
 //! - playback_state: Position tracking, timing
 //! - preload: Gapless playback preloading
 //! - volume_manager: Volume, ReplayGain, balance
+//! - normalize: Normalization modes and the real-time peak normalizer
 //! - device: Device detection, DeviceState, SendOutputStream
 //! - effects: EQ and effects processing
 //! - visualizer: Audio visualization buffer
+//! - progress: Interval gating for `playback-progress` events
+//! - silence: Leading/trailing silence detection for trimming
+//! - resume: Per-track saved positions for resuming long-form audio
+//! - speed: Playback speed presets per content type
+//! - pcm_tap: Opt-in copy of the processed output for recording
 //! 
 //! # Thread Safety
 //! All public methods are thread-safe (Send + Sync).
@@ -18,6 +24,14 @@ pub mod device;
 pub mod playback_state;
 pub mod preload;
 pub mod volume_manager;
+pub mod normalize;
+pub mod progress;
+pub mod silence;
+pub mod resume;
+pub mod speed;
+pub mod device_volume;
+pub mod pcm_tap;
+pub mod read_errors;
 
 use rodio::{Decoder, Sink, Source};
 use std::fs::File;
@@ -25,9 +39,11 @@ use std::io::BufReader;
 use log::{info, error, warn};
 use crate::context_log::LogContext;
 use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Condvar};
-use std::sync::atomic::{AtomicU32, AtomicBool, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU8, AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
+use crate::chapters::{self, Chapter};
+use crate::media_container;
 use crate::error::{AppError, AppResult};
 
 /// Acquire a Mutex lock, recovering from poison if a previous holder panicked.
@@ -40,15 +56,22 @@ use crate::error::{AppError, AppResult};
 fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
     mutex.lock().unwrap_or_else(PoisonError::into_inner)
 }
-use crate::effects::{EffectsConfig, EffectsProcessor};
+use crate::effects::{ClipStats, EffectsConfig, EffectsCost, EffectsProcessor};
 use visualizer::VisualizerBuffer;
 use effects::EffectsSource;
+use pcm_tap::{PcmChunk, PcmTap, PCM_TAP_CAPACITY};
+use read_errors::WatchedReader;
 
 use playback_state::PlaybackState;
-use preload::PreloadManager;
-use volume_manager::VolumeManager;
-use device::DeviceState;
+use preload::{PreloadManager, PreloadedTrack};
+use volume_manager::{TrackGains, VolumeManager};
+use crate::replaygain::ReplayGainMode;
+use playback_state::SampleClock;
+use silence::{SilenceBounds, SilenceTrimConfig};
+use resume::ResumeLookup;
+use device::{AudioConfig, AudioReinitialized, DeviceState, OutputFormat, OutputOpener, OutputSampleRate, ReinitListener};
 pub use device::AudioDevice;
+pub use normalize::NormalizeMode;
 
 /// Threshold for considering a pause "long" — after this duration, we proactively
 /// reinitialize the audio stream to prevent stale device issues.
@@ -100,6 +123,40 @@ pub struct BroadcastSnapshot {
     pub is_paused: bool,
     pub position: f64,
     pub duration: f64,
+    /// The source stopped because reading its file failed, not at its end.
+    pub read_failed: bool,
+}
+
+/// Whether `source` supports random access. Seeking a freshly opened
+/// decoder to zero is a no-op for those that do; the rest report
+/// `NotSupported` (or a decoder-specific error such as forward-only streams).
+fn probe_seekable<S: Source>(source: &mut S) -> bool {
+    match source.try_seek(Duration::ZERO) {
+        Ok(()) => true,
+        Err(e) => {
+            info!("Source is not seekable: {}", e);
+            false
+        }
+    }
+}
+
+/// Reset playback timing for a freshly opened `source` and cache whether it
+/// can seek, so `can_seek` doesn't have to touch the decoder again.
+fn record_load<S: Source>(playback: &mut PlaybackState, path: String, source: &mut S) {
+    let duration = source.total_duration().unwrap_or(Duration::ZERO);
+    playback.reset_for_load(path, duration);
+    playback.seekable = probe_seekable(source);
+}
+
+/// Where a skip of `delta` seconds from `position` lands, clamped to the
+/// track. An unknown (zero) duration only clamps at the start.
+fn relative_seek_target(position: f64, delta: f64, duration: f64) -> f64 {
+    let target = (position + delta).max(0.0);
+    if duration > 0.0 {
+        target.min(duration)
+    } else {
+        target
+    }
 }
 
 /// Thin coordinator that owns focused sub-structs.
@@ -123,43 +180,94 @@ pub struct AudioPlayer {
     effects_processor: Arc<Mutex<EffectsProcessor>>,
     effects_enabled: Mutex<bool>,
     visualizer_buffer: Arc<VisualizerBuffer>,
+    /// Processed output for `get_pcm_tap`; off unless enabled.
+    pcm_tap: Arc<PcmTap>,
     /// Shared atomic balance for lock-free per-sample L/R attenuation.
     /// Stored as f32 bits in AtomicU32 (0.0 = center, -1.0 = left, 1.0 = right).
     balance: Arc<AtomicU32>,
+    /// Shared atomic ReplayGain multiplier for the per-sample gain stage.
+    /// Stored as f32 bits in AtomicU32 (1.0 = no change).
+    replaygain: Arc<AtomicU32>,
+    /// Shared atomic NormalizeMode, read once per batch by EffectsSource.
+    normalize_mode: Arc<AtomicU8>,
+    /// Sample clock of the source currently in the sink.
+    clock: Mutex<Arc<SampleClock>>,
+    /// Leading/trailing silence skipping, applied at load.
+    silence_trim: Mutex<SilenceTrimConfig>,
+    /// Whether tracks continue from their saved position when loaded.
+    resume_per_track: AtomicBool,
+    /// Where saved positions come from; unset means nothing is resumed.
+    resume_lookup: Mutex<Option<ResumeLookup>>,
     /// Condvar wake signal for the broadcast thread — play/load signal it to
     /// break out of idle sleep immediately.
     broadcast_wake: Arc<BroadcastWake>,
     /// Prevents multiple threads from blocking on CPAL initialization concurrently.
     is_reinitializing: AtomicBool,
+    /// Opens the output stream (at startup, on reinit, and lazily on play).
+    open_output: OutputOpener,
+    /// Options the output stream is opened with.
+    audio_config: Mutex<AudioConfig>,
+    /// Sample rate of the loaded track (0 before the first load).
+    source_rate: AtomicU32,
+    /// Told about every successful reinit so the UI can resync.
+    reinit_listener: Mutex<Option<ReinitListener>>,
 }
 
 impl AudioPlayer {
+    /// Create the player on the default output device. With no usable device
+    /// the player starts in "no device" mode instead of failing, so library
+    /// management keeps working; the stream is opened on the first `play()`
+    /// once a device is present.
     pub fn new() -> AppResult<Self> {
+        Ok(Self::with_output_opener(device::create_high_quality_output_with_device_name))
+    }
+
+    fn with_output_opener(open_output: OutputOpener) -> Self {
         info!("Initializing audio player with high-quality settings");
 
-        let (stream, mixer, device_name) = device::create_high_quality_output_with_device_name()?;
-        
-        // Use Sink::connect_new to attach to our manual mixer
-        let sink = Sink::connect_new(&mixer);
-        
+        let audio_config = AudioConfig::default();
+        let (sink, device_state) = match open_output(&audio_config, None, None) {
+            Ok((stream, mixer, device_name)) => {
+                info!("Audio player initialized successfully on device: {:?}", device_name);
+                // Use Sink::connect_new to attach to our manual mixer
+                (Sink::connect_new(&mixer), DeviceState::new(stream, mixer, device_name))
+            }
+            Err(e) => {
+                warn!("No audio output available, starting in no-device mode: {}", e);
+                // Detached sink: accepts sources but plays nowhere until
+                // reinit_device swaps in a connected one.
+                (Sink::new().0, DeviceState::disconnected())
+            }
+        };
+
         let visualizer_buffer = Arc::new(VisualizerBuffer::new(4096));
 
-        info!("Audio player initialized successfully on device: {:?}", device_name);
-        Ok(Self {
+        Self {
             sink: Mutex::new(sink),
             playback: Mutex::new(PlaybackState::new()),
             preload: Mutex::new(PreloadManager::new()),
             volume_mgr: Mutex::new(VolumeManager::new()),
-            device: Mutex::new(DeviceState::new(stream, mixer, device_name)),
+            device: Mutex::new(device_state),
             effects_processor: Arc::new(Mutex::new(
                 EffectsProcessor::new(44100, EffectsConfig::default()),
             )),
             effects_enabled: Mutex::new(true),
             visualizer_buffer,
+            pcm_tap: Arc::new(PcmTap::new(PCM_TAP_CAPACITY)),
             balance: Arc::new(AtomicU32::new(0.0_f32.to_bits())),
+            replaygain: Arc::new(AtomicU32::new(1.0_f32.to_bits())),
+            normalize_mode: Arc::new(AtomicU8::new(NormalizeMode::default().as_u8())),
+            clock: Mutex::new(Arc::new(SampleClock::new())),
+            silence_trim: Mutex::new(SilenceTrimConfig::default()),
+            resume_per_track: AtomicBool::new(false),
+            resume_lookup: Mutex::new(None),
             broadcast_wake: Arc::new(BroadcastWake::new()),
             is_reinitializing: AtomicBool::new(false),
-        })
+            open_output,
+            audio_config: Mutex::new(audio_config),
+            source_rate: AtomicU32::new(0),
+            reinit_listener: Mutex::new(None),
+        }
     }
 
     // ── Device queries ──────────────────────────────────────────────
@@ -172,6 +280,28 @@ impl AudioPlayer {
         device::is_device_available()
     }
 
+    /// Whether an output stream is open (false in "no device" mode).
+    pub fn has_output(&self) -> bool {
+        lock_or_recover(&self.device).is_connected()
+    }
+
+    /// Open the output stream if the player is in "no device" mode.
+    /// A loaded track is reloaded by the next `play()`.
+    pub fn ensure_output(&self) -> AppResult<()> {
+        if self.has_output() {
+            return Ok(());
+        }
+        self.reinit_device().map_err(|e| {
+            warn!("Still no audio output: {}", e);
+            AppError::Audio(
+                "No audio output device available. Please connect an audio device.".to_string(),
+            )
+        })?;
+        info!("Audio output opened after starting without a device");
+        self.notify_reinitialized();
+        Ok(())
+    }
+
     pub fn get_audio_devices() -> AppResult<Vec<AudioDevice>> {
         device::get_audio_devices()
     }
@@ -183,6 +313,99 @@ impl AudioPlayer {
 
     // ── Track loading ───────────────────────────────────────────────
 
+    /// Load a track and apply its manual gain override on top of ReplayGain.
+    pub fn load_with_gains(&self, path: String, gains: &TrackGains) -> AppResult<()> {
+        self.load(path)?;
+        self.apply_track_gains(gains);
+        Ok(())
+    }
+
+    /// Path of the loaded track, if any.
+    pub fn current_path(&self) -> Option<String> {
+        lock_or_recover(&self.playback).current_path.clone()
+    }
+
+    /// Configure skipping of leading/trailing silence. Applies from the next load.
+    pub fn set_silence_trim(&self, config: SilenceTrimConfig) {
+        *lock_or_recover(&self.silence_trim) = config;
+        info!("Silence trim: {:?}", config);
+    }
+
+    pub fn get_silence_trim(&self) -> SilenceTrimConfig {
+        *lock_or_recover(&self.silence_trim)
+    }
+
+    /// Turn per-track resume on or off. Applies from the next load.
+    pub fn set_resume_per_track(&self, enabled: bool) {
+        self.resume_per_track.store(enabled, Ordering::Relaxed);
+        info!("Resume per track: {}", enabled);
+    }
+
+    pub fn resume_per_track(&self) -> bool {
+        self.resume_per_track.load(Ordering::Relaxed)
+    }
+
+    /// Register how saved positions are looked up for per-track resume.
+    pub fn set_resume_lookup(&self, lookup: ResumeLookup) {
+        *lock_or_recover(&self.resume_lookup) = Some(lookup);
+    }
+
+    /// Start `source` at the saved position of `path` when per-track resume
+    /// is on. Returns the position it now starts from, if it was moved.
+    fn apply_resume<I>(&self, path: &str, duration: Duration, source: &mut EffectsSource<I>) -> Option<f64>
+    where
+        I: Source,
+        f32: rodio::cpal::FromSample<I::Item>,
+    {
+        if !self.resume_per_track() {
+            return None;
+        }
+        let position = {
+            let lookup = lock_or_recover(&self.resume_lookup);
+            let lookup = lookup.as_ref()?;
+            lookup(path, duration.as_secs_f64())?
+        };
+        match source.try_seek(Duration::from_secs_f64(position)) {
+            Ok(()) => {
+                info!("Resuming {} at {:.1}s", path, position);
+                Some(position)
+            }
+            Err(e) => {
+                warn!("Failed to resume {} at {:.1}s: {:?}", path, position, e);
+                None
+            }
+        }
+    }
+
+    /// Playback range of `path` with silence trimming applied. Detection
+    /// failures are logged and leave the track untrimmed.
+    fn silence_bounds(&self, path: &str) -> SilenceBounds {
+        let config = self.get_silence_trim();
+        if !config.enabled {
+            return SilenceBounds::default();
+        }
+        silence::detect(path, &config).unwrap_or_else(|e| {
+            warn!("Silence detection failed for {}: {}", path, e);
+            SilenceBounds::default()
+        })
+    }
+
+    /// Start `source` after its leading silence and end it before its trailing silence.
+    fn apply_silence_trim<I>(&self, path: &str, source: &mut EffectsSource<I>)
+    where
+        I: Source,
+        f32: rodio::cpal::FromSample<I::Item>,
+    {
+        let bounds = self.silence_bounds(path);
+        if bounds.start_secs > 0.0 {
+            match source.try_seek(Duration::from_secs_f64(bounds.start_secs)) {
+                Ok(()) => info!("Skipping {:.2}s of leading silence", bounds.start_secs),
+                Err(e) => warn!("Could not skip leading silence: {:?}", e),
+            }
+        }
+        source.set_end(bounds.end_secs);
+    }
+
     pub fn load(&self, path: String) -> AppResult<()> {
         if self.is_reinitializing.load(Ordering::SeqCst) {
             return Err(AppError::Audio("Audio system is busy recovering. Please try again in a moment.".into()));
@@ -195,31 +418,75 @@ impl AudioPlayer {
             AppError::NotFound(format!("Failed to open file {}: {}", path, e))
         })?;
 
-        let source = Decoder::new(BufReader::new(file)).map_err(|e| {
+        // A video file without sound would otherwise fail with a vague
+        // decode error, and the decoder takes its duration from the first
+        // (usually video) track rather than the audio it plays.
+        let container_audio = if media_container::is_video_container(std::path::Path::new(&path)) {
+            let audio = media_container::probe_audio_track(std::path::Path::new(&path)).map_err(|e| {
+                ctx.error(&e);
+                AppError::Decode(e)
+            })?;
+            Some(audio)
+        } else {
+            None
+        };
+
+        let reader = WatchedReader::new(file);
+        let read_failed = reader.failed();
+        let mut source = Decoder::new(BufReader::new(reader)).map_err(|e| {
             ctx.error(&format!("Decode failed: {}", e));
             AppError::Decode(format!("Failed to decode audio: {}", e))
         })?;
 
-        let duration = source.total_duration().unwrap_or(Duration::ZERO);
-        ctx.info(&format!("Loaded, duration={:?}", duration));
+        // Reopen before touching playback state: if that fails, the current
+        // stream is kept and the state still describes what it plays.
+        self.match_source_rate(source.sample_rate());
+        self.source_rate.store(source.sample_rate(), Ordering::Relaxed);
+
+        let (duration, seekable) = {
+            let mut pb = lock_or_recover(&self.playback);
+            record_load(&mut pb, path.clone(), &mut source);
+            if let Some(secs) = container_audio.and_then(|a| a.duration) {
+                pb.total_duration = Duration::from_secs_f64(secs);
+            }
+            (pb.total_duration, pb.seekable)
+        };
+        ctx.info(&format!("Loaded, duration={:?}, seekable={}", duration, seekable));
 
-        // Clear visualizer buffer for new track
+        // Clear visualizer buffer and clip stats for new track
         self.visualizer_buffer.clear();
+        lock_or_recover(&self.effects_processor).reset_clip_stats();
 
         // Wrap source with effects processor for EQ and visualizer
-        let effects_source = EffectsSource::new(
+        let mut effects_source = EffectsSource::new(
             source,
             self.effects_processor.clone(),
             self.visualizer_buffer.clone(),
             self.balance.clone(),
+            self.replaygain.clone(),
+            self.normalize_mode.clone(),
         );
-
-        let sink = lock_or_recover(&self.sink);
-        sink.clear();
+        effects_source.set_pcm_tap(self.pcm_tap.clone());
+        effects_source.set_read_failed(read_failed);
+        self.apply_silence_trim(&path, &mut effects_source);
+        let resumed_at = self.apply_resume(&path, duration, &mut effects_source);
+        *lock_or_recover(&self.clock) = effects_source.clock();
+
+        let mut sink = lock_or_recover(&self.sink);
+        if self.has_output() {
+            sink.clear();
+        } else {
+            // A detached sink has no consumer, so clear() would wait forever
+            // for it to drain; start over with a fresh one instead.
+            *sink = Sink::new().0;
+            sink.set_speed(self.speed());
+        }
         sink.append(effects_source);
         sink.pause();
+        if let Some(position) = resumed_at {
+            lock_or_recover(&self.playback).mark_seeked(position, true);
+        }
 
-        lock_or_recover(&self.playback).reset_for_load(path, duration);
         lock_or_recover(&self.device).update_active();
 
         // Wake the broadcast thread so it picks up the new track quickly
@@ -236,24 +503,66 @@ impl AudioPlayer {
     /// the sink is empty (no source appended) — callers must reload/seek as
     /// needed for their specific use-case.
     fn reinit_device(&self) -> AppResult<()> {
+        let source_rate = match self.source_rate.load(Ordering::Relaxed) {
+            0 => None,
+            rate => Some(rate),
+        };
+        self.open_output_for(source_rate)?;
+
+        // Discard stale preload — its sink was connected to the old mixer.
+        self.clear_preload();
+
+        Ok(())
+    }
+
+    /// Open a new output stream and sink for `source_rate`, replacing the
+    /// current ones only once the new stream is open.
+    fn open_output_for(&self, source_rate: Option<u32>) -> AppResult<()> {
+        let requested_device = lock_or_recover(&self.device).requested_device.clone();
         let (new_stream, new_mixer, new_device_name) =
-            device::create_high_quality_output_with_device_name()?;
+            (self.open_output)(&self.audio_config(), source_rate, requested_device.as_deref())?;
 
         info!("Audio output reinitialized on device: {:?}", new_device_name);
 
         let new_sink = Sink::connect_new(&new_mixer);
-        new_sink.set_volume(lock_or_recover(&self.volume_mgr).effective_volume());
+        new_sink.set_volume(lock_or_recover(&self.volume_mgr).sink_volume());
+        new_sink.set_speed(self.speed());
 
-        lock_or_recover(&self.device)
-            .replace(new_stream, new_mixer, new_device_name);
+        {
+            let mut device = lock_or_recover(&self.device);
+            device.replace(new_stream, new_mixer, new_device_name);
+            device.source_rate = source_rate;
+        }
         *lock_or_recover(&self.sink) = new_sink;
 
-        // Discard stale preload — its sink was connected to the old mixer.
-        self.clear_preload();
-
         Ok(())
     }
 
+    /// With [`OutputSampleRate::MatchSource`], reopen the stream when a
+    /// track's rate differs from the one it was opened for. If that fails,
+    /// the current stream is kept and resamples as before. A preloaded track
+    /// is rebuilt on the new stream rather than dropped.
+    fn match_source_rate(&self, source_rate: u32) {
+        if self.audio_config().sample_rate != OutputSampleRate::MatchSource || !self.has_output() {
+            return;
+        }
+        if lock_or_recover(&self.device).source_rate == Some(source_rate) {
+            return;
+        }
+        info!("Reopening output for a {} Hz track", source_rate);
+        let preloaded = self.get_preloaded_path();
+        if let Err(e) = self.open_output_for(Some(source_rate)) {
+            warn!("Keeping the current output; reopening at {} Hz failed: {}", source_rate, e);
+            return;
+        }
+        self.clear_preload();
+        if let Some(path) = preloaded {
+            if let Err(e) = self.preload(path) {
+                warn!("Failed to preload again on the new output: {}", e);
+            }
+        }
+    }
+
     /// Reinit device, then reload the current track at the given position.
     /// Returns Ok(()) even if there was no track to reload.
     fn reinit_and_reload(&self) -> AppResult<()> {
@@ -282,6 +591,48 @@ impl AudioPlayer {
             }
         }
 
+        self.notify_reinitialized();
+        Ok(())
+    }
+
+    /// Register the callback run after each successful reinit.
+    pub fn set_reinit_listener(&self, listener: ReinitListener) {
+        *lock_or_recover(&self.reinit_listener) = Some(listener);
+    }
+
+    fn notify_reinitialized(&self) {
+        let event = AudioReinitialized {
+            device_name: lock_or_recover(&self.device).connected_device_name.clone(),
+            position: self.get_position(),
+        };
+        if let Some(listener) = lock_or_recover(&self.reinit_listener).as_ref() {
+            listener(&event);
+        }
+    }
+
+    pub fn audio_config(&self) -> AudioConfig {
+        *lock_or_recover(&self.audio_config)
+    }
+
+    /// Format the output stream was actually opened with; `None` in
+    /// "no device" mode.
+    pub fn output_format(&self) -> Option<OutputFormat> {
+        lock_or_recover(&self.device).format
+    }
+
+    /// Change the output stream options. An open stream is reopened with
+    /// them, reloading the current track at its position.
+    pub fn set_audio_config(&self, config: AudioConfig) -> AppResult<()> {
+        let previous = std::mem::replace(&mut *lock_or_recover(&self.audio_config), config);
+        if previous == config || !self.has_output() {
+            return Ok(());
+        }
+
+        let was_playing = self.is_playing();
+        self.reinit_and_reload()?;
+        if was_playing {
+            self.play()?;
+        }
         Ok(())
     }
 
@@ -294,6 +645,9 @@ impl AudioPlayer {
         
         info!("Starting playback");
 
+        // Lazily open the stream if we started (or ended up) without a device
+        self.ensure_output()?;
+
         let pause_duration = {
             let pb = lock_or_recover(&self.playback);
             pb.pause_start.map(|s| s.elapsed()).unwrap_or(Duration::ZERO)
@@ -391,18 +745,93 @@ impl AudioPlayer {
     pub fn set_volume(&self, volume: f32) -> AppResult<()> {
         let effective = lock_or_recover(&self.volume_mgr).set_volume(volume);
         lock_or_recover(&self.sink).set_volume(effective);
+        lock_or_recover(&self.effects_processor).set_loudness_volume(volume);
         Ok(())
     }
 
-    pub fn set_replaygain(&self, gain_db: f32, preamp_db: f32) -> AppResult<()> {
-        let effective = lock_or_recover(&self.volume_mgr).set_replaygain(gain_db, preamp_db);
+    /// The user-set volume (0.0–1.0), whether or not output is muted.
+    pub fn volume(&self) -> f32 {
+        lock_or_recover(&self.volume_mgr).last_volume
+    }
+
+    /// Silence output without losing the volume level. ReplayGain and the
+    /// other gain stages are untouched, so unmuting restores the same
+    /// effective volume.
+    pub fn set_muted(&self, muted: bool) {
+        let effective = lock_or_recover(&self.volume_mgr).set_muted(muted);
+        lock_or_recover(&self.sink).set_volume(effective);
+    }
+
+    /// Flip the mute state and return the new one.
+    pub fn toggle_mute(&self) -> bool {
+        let (muted, effective) = {
+            let mut volume = lock_or_recover(&self.volume_mgr);
+            let muted = !volume.muted;
+            (muted, volume.set_muted(muted))
+        };
         lock_or_recover(&self.sink).set_volume(effective);
+        muted
+    }
+
+    pub fn is_muted(&self) -> bool {
+        lock_or_recover(&self.volume_mgr).muted
+    }
+
+    /// Set the ReplayGain stage. `peak` (linear) caps boosts so the track's
+    /// loudest sample stays below full scale.
+    pub fn set_replaygain(&self, gain_db: f32, preamp_db: f32, peak: Option<f32>) -> AppResult<()> {
+        let multiplier = lock_or_recover(&self.volume_mgr).set_replaygain(gain_db, preamp_db, peak);
+        self.replaygain.store(multiplier.to_bits(), Ordering::Relaxed);
         Ok(())
     }
 
     pub fn clear_replaygain(&self) {
-        let effective = lock_or_recover(&self.volume_mgr).clear_replaygain();
-        lock_or_recover(&self.sink).set_volume(effective);
+        let multiplier = lock_or_recover(&self.volume_mgr).clear_replaygain();
+        self.replaygain.store(multiplier.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Set the loaded track's manual gain override in dB (None = no adjustment).
+    pub fn set_gain_override(&self, gain_db: Option<f32>) {
+        let multiplier = lock_or_recover(&self.volume_mgr).set_track_gain_override(gain_db);
+        self.replaygain.store(multiplier.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Set the gain stage for the track just loaded: the stored ReplayGain
+    /// the current ReplayGain mode picks, plus its manual override.
+    pub fn apply_track_gains(&self, gains: &TrackGains) {
+        let multiplier = lock_or_recover(&self.volume_mgr).apply_track_gains(gains);
+        self.replaygain.store(multiplier.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Choose which stored gain (track, album, either, or none) later loads apply.
+    pub fn set_replaygain_mode(&self, mode: ReplayGainMode) {
+        lock_or_recover(&self.volume_mgr).replaygain_mode = mode;
+        info!("ReplayGain mode: {:?}", mode);
+    }
+
+    pub fn get_replaygain_mode(&self) -> ReplayGainMode {
+        lock_or_recover(&self.volume_mgr).replaygain_mode
+    }
+
+    /// Set the preamp (dB) added to the ReplayGain of later loads.
+    pub fn set_replaygain_preamp(&self, preamp_db: f32) {
+        lock_or_recover(&self.volume_mgr).set_replaygain_preamp(preamp_db);
+    }
+
+    pub fn get_replaygain_preamp(&self) -> f32 {
+        lock_or_recover(&self.volume_mgr).replaygain_preamp_db
+    }
+
+    /// Choose between no normalization, real-time peak normalization and
+    /// ReplayGain. The track gain override applies in every mode.
+    pub fn set_normalize_mode(&self, mode: NormalizeMode) {
+        let multiplier = lock_or_recover(&self.volume_mgr).set_normalize_mode(mode);
+        self.replaygain.store(multiplier.to_bits(), Ordering::Relaxed);
+        self.normalize_mode.store(mode.as_u8(), Ordering::Relaxed);
+    }
+
+    pub fn get_normalize_mode(&self) -> NormalizeMode {
+        lock_or_recover(&self.volume_mgr).normalize_mode
     }
 
     pub fn get_replaygain_multiplier(&self) -> f32 {
@@ -423,6 +852,21 @@ impl AudioPlayer {
 
     // ── Seeking ─────────────────────────────────────────────────────
 
+    /// Whether the loaded track supports direct seeking. When false, `seek`
+    /// still works but falls back to reloading the file, which is slower.
+    pub fn can_seek(&self) -> bool {
+        let pb = lock_or_recover(&self.playback);
+        pb.current_path.is_some() && pb.seekable
+    }
+
+    /// Skip forward (positive `delta`) or back from the current position,
+    /// staying within the track. Returns the position sought to.
+    pub fn seek_relative(&self, delta: f64) -> AppResult<f64> {
+        let target = relative_seek_target(self.get_position(), delta, self.get_duration());
+        self.seek(target)?;
+        Ok(target)
+    }
+
     pub fn seek(&self, mut position: f64) -> AppResult<()> {
         info!("Seeking to position: {}s", position);
 
@@ -465,15 +909,24 @@ impl AudioPlayer {
                     let file = File::open(&path)
                         .map_err(|e| AppError::NotFound(format!("Failed to open file: {}", e)))?;
 
-                    let source = Decoder::new(BufReader::new(file))
+                    let reader = WatchedReader::new(file);
+                    let read_failed = reader.failed();
+                    let source = Decoder::new(BufReader::new(reader))
                         .map_err(|e| AppError::Decode(format!("Failed to decode audio: {}", e)))?;
 
-                    let effects_source = EffectsSource::new(
+                    let mut effects_source = EffectsSource::new(
                         source,
                         self.effects_processor.clone(),
                         self.visualizer_buffer.clone(),
                         self.balance.clone(),
+                        self.replaygain.clone(),
+                        self.normalize_mode.clone(),
                     );
+                    effects_source.set_pcm_tap(self.pcm_tap.clone());
+                    effects_source.set_read_failed(read_failed);
+                    // Only the trimmed end matters here; the seek below sets the start.
+                    effects_source.set_end(self.silence_bounds(&path).end_secs);
+                    *lock_or_recover(&self.clock) = effects_source.clock();
 
                     let sink = lock_or_recover(&self.sink);
                     sink.clear();
@@ -522,12 +975,41 @@ impl AudioPlayer {
         }
     }
 
+    /// Seek to the next embedded chapter of the loaded track. Returns the
+    /// chapter sought to, or None when there is no later chapter.
+    pub fn next_chapter(&self) -> AppResult<Option<Chapter>> {
+        let chapters = self.current_chapters()?;
+        let target = chapters::next_chapter(&chapters, self.get_position()).cloned();
+        if let Some(chapter) = &target {
+            self.seek(chapter.start)?;
+        }
+        Ok(target)
+    }
+
+    /// Seek to the start of the current chapter, or to the previous one when
+    /// playback is within a few seconds of the current chapter's start.
+    pub fn previous_chapter(&self) -> AppResult<Option<Chapter>> {
+        let chapters = self.current_chapters()?;
+        let target = chapters::previous_chapter(&chapters, self.get_position()).cloned();
+        if let Some(chapter) = &target {
+            self.seek(chapter.start)?;
+        }
+        Ok(target)
+    }
+
+    fn current_chapters(&self) -> AppResult<Vec<Chapter>> {
+        let path = self
+            .current_path()
+            .ok_or_else(|| AppError::InvalidState("No track loaded".to_string()))?;
+        chapters::read_chapters(std::path::Path::new(&path)).map_err(AppError::Decode)
+    }
+
     // ── Position & state queries ────────────────────────────────────
 
     pub fn get_position(&self) -> f64 {
         let sink = lock_or_recover(&self.sink);
         let pb = lock_or_recover(&self.playback);
-        pb.get_position(sink.empty(), sink.is_paused())
+        pb.get_position(sink.empty(), sink.is_paused(), self.sample_position())
     }
 
     pub fn is_playing(&self) -> bool {
@@ -539,6 +1021,11 @@ impl AudioPlayer {
         lock_or_recover(&self.sink).empty()
     }
 
+    /// Sample-accurate position of the current source, if it has played.
+    fn sample_position(&self) -> Option<f64> {
+        lock_or_recover(&self.clock).position()
+    }
+
     pub fn get_duration(&self) -> f64 {
         lock_or_recover(&self.playback).total_duration.as_secs_f64()
     }
@@ -559,37 +1046,56 @@ impl AudioPlayer {
             is_playing: !is_paused && !is_empty,
             is_finished: is_empty,
             is_paused,
-            position: pb.get_position(is_empty, is_paused),
+            position: pb.get_position(is_empty, is_paused, self.sample_position()),
             duration: pb.total_duration.as_secs_f64(),
+            read_failed: lock_or_recover(&self.clock).read_failed(),
         }
     }
 
     // ── Output device switching ─────────────────────────────────────
 
-    pub fn set_output_device(&self, device_name: &str) -> AppResult<()> {
-        let host = rodio::cpal::default_host();
-        use rodio::cpal::traits::HostTrait;
+    /// Name of the device the output stream is open on, if any.
+    pub fn connected_device_name(&self) -> Option<String> {
+        lock_or_recover(&self.device).connected_device_name.clone()
+    }
 
-        let mut output_devices = host
-            .output_devices()
-            .map_err(|e| AppError::Audio(format!("Failed to enumerate devices: {}", e)))?;
+    /// Device playback goes to: the one the stream is open on, or while no
+    /// stream is open, the one the user picked.
+    pub fn output_device_name(&self) -> Option<String> {
+        let device = lock_or_recover(&self.device);
+        device.connected_device_name.clone().or_else(|| device.requested_device.clone())
+    }
 
-        let _device = output_devices
-            .find(|d| {
-                use rodio::DeviceTrait;
-                d.name().ok().as_deref() == Some(device_name)
-            })
-            .ok_or_else(|| AppError::NotFound(format!("Device '{}' not found", device_name)))?;
+    /// Reopen output on `device_name`, reloading the current track at its
+    /// position. Returns `false` without touching the stream when that
+    /// device is already the one in use; it is still pinned, so later
+    /// reopens stay on it.
+    pub fn set_output_device(&self, device_name: &str) -> AppResult<bool> {
+        if self.connected_device_name().as_deref() == Some(device_name) {
+            info!("Output already on {}; not reopening", device_name);
+            lock_or_recover(&self.device).requested_device = Some(device_name.to_string());
+            return Ok(false);
+        }
 
         let was_playing = self.is_playing();
-
-        self.reinit_and_reload()?;
+        let previous = lock_or_recover(&self.device)
+            .requested_device
+            .replace(device_name.to_string());
+
+        if let Err(e) = self.reinit_and_reload() {
+            // Unless the stream did move, later reopens stay on the device
+            // that is still playing.
+            if self.connected_device_name().as_deref() != Some(device_name) {
+                lock_or_recover(&self.device).requested_device = previous;
+            }
+            return Err(e);
+        }
 
         if was_playing {
             self.play()?;
         }
 
-        Ok(())
+        Ok(true)
     }
 
     // ── Gapless playback (preload) ──────────────────────────────────
@@ -600,10 +1106,13 @@ impl AudioPlayer {
         let file = File::open(&path)
             .map_err(|e| AppError::NotFound(format!("Failed to open file {}: {}", path, e)))?;
 
-        let source = Decoder::new(BufReader::new(file))
+        let reader = WatchedReader::new(file);
+        let read_failed = reader.failed();
+        let mut source = Decoder::new(BufReader::new(reader))
             .map_err(|e| AppError::Decode(format!("Failed to decode audio: {}", e)))?;
 
         let duration = source.total_duration().unwrap_or(Duration::ZERO);
+        let seekable = probe_seekable(&mut source);
 
         // Reuse the existing device mixer
         let device = lock_or_recover(&self.device);
@@ -612,19 +1121,35 @@ impl AudioPlayer {
         drop(device); // release device lock before acquiring sink lock
 
         // Wrap source with effects processor for EQ and visualizer (same as load())
-        let effects_source = EffectsSource::new(
+        let mut effects_source = EffectsSource::new(
             source,
             self.effects_processor.clone(),
             self.visualizer_buffer.clone(),
             self.balance.clone(),
+            self.replaygain.clone(),
+            self.normalize_mode.clone(),
         );
+        effects_source.set_pcm_tap(self.pcm_tap.clone());
+        effects_source.set_read_failed(read_failed);
+        self.apply_silence_trim(&path, &mut effects_source);
+        let start_secs = self.apply_resume(&path, duration, &mut effects_source).unwrap_or(0.0);
+        let clock = effects_source.clock();
 
         let current_volume = lock_or_recover(&self.sink).volume();
         new_sink.set_volume(current_volume);
+        new_sink.set_speed(self.speed());
         new_sink.append(effects_source);
         new_sink.pause();
 
-        lock_or_recover(&self.preload).set(new_sink, path, duration, generation);
+        let track = PreloadedTrack {
+            sink: new_sink,
+            clock,
+            path,
+            total_duration: duration,
+            seekable,
+            start_secs,
+        };
+        lock_or_recover(&self.preload).set(track, generation);
         info!("Audio file preloaded successfully (reusing existing output, gen={})", generation);
         Ok(())
     }
@@ -634,25 +1159,28 @@ impl AudioPlayer {
 
         let current_gen = lock_or_recover(&self.device).generation;
         let taken = lock_or_recover(&self.preload).take_if_current(current_gen);
-        if let Some((new_sink, new_path, duration)) = taken {
+        if let Some(track) = taken {
             // Hold a single sink lock across stop → replace → play to prevent
             // another thread from observing a half-swapped state.
             {
                 let mut sink = lock_or_recover(&self.sink);
                 sink.stop();
-                *sink = new_sink;
+                *sink = track.sink;
+                *lock_or_recover(&self.clock) = track.clock;
                 sink.play();
             }
 
             {
                 let mut pb = lock_or_recover(&self.playback);
-                pb.current_path = Some(new_path);
+                pb.current_path = Some(track.path);
                 pb.start_time = Some(Instant::now());
-                pb.seek_offset = Duration::ZERO;
+                pb.seek_offset = Duration::from_secs_f64(track.start_secs);
                 pb.paused_duration = Duration::ZERO;
                 pb.pause_start = None;
-                pb.total_duration = duration;
+                pb.total_duration = track.total_duration;
+                pb.seekable = track.seekable;
             }
+            lock_or_recover(&self.effects_processor).reset_clip_stats();
 
             info!("Successfully swapped to preloaded track");
             Ok(())
@@ -677,19 +1205,78 @@ impl AudioPlayer {
 
     // ── Effects ─────────────────────────────────────────────────────
 
+    /// Apply an effects configuration. `tempo` is ignored: speed belongs to
+    /// `set_speed`, so saving EQ settings can't reset it.
     pub fn set_effects(&self, config: EffectsConfig) {
-        // Apply tempo/speed at the Sink level (changes playback rate)
-        let tempo = config.tempo.clamp(0.5, 2.0);
-        lock_or_recover(&self.sink).set_speed(tempo);
         lock_or_recover(&self.effects_processor).update_config(config);
     }
 
+    /// Back to the default (flat) effects with every filter and delay buffer
+    /// cleared, so no reverb or echo tail survives. Speed is left alone.
+    pub fn reset_effects(&self) {
+        lock_or_recover(&self.effects_processor).reset();
+    }
+
+    /// The effects configuration, with `tempo` reporting the current speed.
     pub fn get_effects(&self) -> EffectsConfig {
-        lock_or_recover(&self.effects_processor).get_config()
+        let mut config = lock_or_recover(&self.effects_processor).get_config();
+        config.tempo = self.speed();
+        config
+    }
+
+    // ── Speed ───────────────────────────────────────────────────────
+
+    /// Set the playback speed multiplier. The sink resamples, so pitch
+    /// changes with speed. Positions keep reporting track time, so a minute
+    /// of audio is still 60s at 2x.
+    pub fn set_speed(&self, speed: f32) {
+        let speed = speed.clamp(speed::MIN_SPEED, speed::MAX_SPEED);
+        let position = self.get_position();
+        let sink = lock_or_recover(&self.sink);
+        sink.set_speed(speed);
+        lock_or_recover(&self.playback).set_speed(speed as f64, position, sink.is_paused());
+    }
+
+    pub fn speed(&self) -> f32 {
+        lock_or_recover(&self.playback).speed as f32
+    }
+
+    /// How far the audible output trails `get_position()` because of
+    /// lookahead stages in the effects chain.
+    pub fn get_effects_latency_secs(&self) -> f64 {
+        let processor = lock_or_recover(&self.effects_processor);
+        processor.latency_samples() as f64 / processor.sample_rate().max(1) as f64
+    }
+
+    /// Toggle equal-loudness compensation; the contour tracks `set_volume`.
+    pub fn set_loudness_enabled(&self, enabled: bool) {
+        lock_or_recover(&self.effects_processor).set_loudness_enabled(enabled);
+    }
+
+    pub fn is_loudness_enabled(&self) -> bool {
+        lock_or_recover(&self.effects_processor).is_loudness_enabled()
+    }
+
+    /// Full-scale overflows ahead of the limiter since the track started.
+    pub fn get_clip_stats(&self) -> ClipStats {
+        lock_or_recover(&self.effects_processor).clip_stats()
+    }
+
+    /// Benchmark the current effects chain off the audio path. Measures the
+    /// configured chain even while effects are disabled, so the cost can be
+    /// checked before turning them on.
+    pub fn measure_effects_cost(&self) -> EffectsCost {
+        let settings = lock_or_recover(&self.effects_processor).benchmark_settings();
+        EffectsProcessor::benchmark_copy(settings).measure_cost()
     }
 
+    /// Turning effects back on starts from clean buffers rather than the
+    /// tail left over from when they were switched off.
     pub fn set_effects_enabled(&self, enabled: bool) {
-        *lock_or_recover(&self.effects_enabled) = enabled;
+        let was_enabled = std::mem::replace(&mut *lock_or_recover(&self.effects_enabled), enabled);
+        if enabled && !was_enabled {
+            lock_or_recover(&self.effects_processor).flush();
+        }
     }
 
     pub fn is_effects_enabled(&self) -> bool {
@@ -712,6 +1299,16 @@ impl AudioPlayer {
             return Ok(false);
         }
 
+        // A picked device that was unplugged can't be reopened; go back to
+        // following the system default rather than staying silent.
+        let requested = lock_or_recover(&self.device).requested_device.clone();
+        if let Some(name) = requested {
+            if !device::output_device_present(&name) {
+                warn!("Output device {} is gone; falling back to the default device", name);
+                lock_or_recover(&self.device).requested_device = None;
+            }
+        }
+
         let was_playing = self.is_playing();
 
         match self.reinit_and_reload() {
@@ -777,11 +1374,35 @@ impl AudioPlayer {
     pub fn get_visualizer_samples(&self) -> Vec<f32> {
         self.visualizer_buffer.get_samples()
     }
+
+    /// Recent left and right channel samples for the stereo spectrum.
+    pub fn get_visualizer_channel_samples(&self) -> (Vec<f32>, Vec<f32>) {
+        self.visualizer_buffer.get_channel_samples()
+    }
+
+    /// Start or stop copying the processed output into the PCM tap.
+    pub fn set_pcm_tap_enabled(&self, enabled: bool) {
+        self.pcm_tap.set_enabled(enabled);
+    }
+
+    pub fn is_pcm_tap_enabled(&self) -> bool {
+        self.pcm_tap.is_enabled()
+    }
+
+    /// Up to `max_frames` processed frames since the last read.
+    pub fn read_pcm_tap(&self, max_frames: usize) -> PcmChunk {
+        self.pcm_tap.read(max_frames)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{BroadcastWake, AudioPlayer};
+    use super::{AudioConfig, BroadcastWake, AudioPlayer, OutputSampleRate, PlaybackState, ReplayGainMode, TrackGains, probe_seekable, record_load, relative_seek_target};
+    use crate::error::{AppError, AppResult};
+    use rodio::mixer::Mixer;
+    use rodio::source::SeekError;
+    use rodio::{OutputStream, Source};
+    use std::sync::atomic::{AtomicU32, Ordering};
     use std::sync::{mpsc, Arc};
     use std::thread;
     use std::time::{Duration, Instant};
@@ -837,6 +1458,56 @@ mod tests {
         assert!(timeout_elapsed >= Duration::from_millis(30));
     }
 
+    /// Silent stub source; `seekable` decides how it answers `try_seek`.
+    struct StubSource {
+        seekable: bool,
+    }
+
+    impl Iterator for StubSource {
+        type Item = f32;
+        fn next(&mut self) -> Option<f32> {
+            Some(0.0)
+        }
+    }
+
+    impl Source for StubSource {
+        fn current_span_len(&self) -> Option<usize> { None }
+        fn channels(&self) -> u16 { 2 }
+        fn sample_rate(&self) -> u32 { 44100 }
+        fn total_duration(&self) -> Option<Duration> { None }
+
+        fn try_seek(&mut self, _pos: Duration) -> Result<(), SeekError> {
+            if self.seekable {
+                Ok(())
+            } else {
+                Err(SeekError::NotSupported { underlying_source: "StubSource" })
+            }
+        }
+    }
+
+    #[test]
+    fn probe_reports_seek_capability() {
+        assert!(probe_seekable(&mut StubSource { seekable: true }));
+        assert!(!probe_seekable(&mut StubSource { seekable: false }));
+    }
+
+    #[test]
+    fn load_caches_seek_capability_per_track() {
+        let mut playback = PlaybackState::new();
+        assert!(!playback.seekable, "nothing loaded yet");
+
+        record_load(&mut playback, "a.flac".into(), &mut StubSource { seekable: true });
+        assert!(playback.seekable);
+        assert_eq!(playback.current_path.as_deref(), Some("a.flac"));
+
+        record_load(&mut playback, "b.stream".into(), &mut StubSource { seekable: false });
+        assert!(!playback.seekable, "flag must follow the newly loaded track");
+
+        record_load(&mut playback, "c.flac".into(), &mut StubSource { seekable: true });
+        playback.clear();
+        assert!(!playback.seekable, "stop clears the cached capability");
+    }
+
     // ── F-017e: AudioPlayer recover() contract (requires audio hardware) ─────
 
     /// Full recover() cycle on a freshly constructed AudioPlayer.
@@ -857,6 +1528,147 @@ mod tests {
         assert!(player.is_healthy(), "AudioPlayer should still be healthy after recover()");
     }
 
+    /// Each successful reinit is reported once, with the restored position.
+    #[test]
+    #[ignore = "requires real audio hardware — run with --include-ignored on a dev machine"]
+    fn recover_reports_reinit_once() {
+        let player = AudioPlayer::new().expect("AudioPlayer::new requires audio hardware");
+        let (tx, rx) = mpsc::channel();
+        player.set_reinit_listener(Box::new(move |event| tx.send(event.clone()).unwrap()));
+
+        let dir = std::env::temp_dir().join(format!("vplayer_reinit_event_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        player.load(path.to_string_lossy().to_string()).unwrap();
+        player.seek(1.0).unwrap();
+
+        assert!(player.recover().unwrap());
+        let event = rx.try_recv().expect("recover must report the reinit");
+        assert!(event.device_name.is_some());
+        assert!((event.position - 1.0).abs() < 0.1, "position was {}", event.position);
+        assert!(rx.try_recv().is_err(), "one reinit, one event");
+
+        assert!(player.recover().unwrap());
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn switching_to_the_current_device_is_a_no_op() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        player.device.lock().unwrap().connected_device_name = Some("Speakers".to_string());
+        let (tx, rx) = mpsc::channel();
+        player.set_reinit_listener(Box::new(move |event| tx.send(event.clone()).unwrap()));
+
+        let dir = std::env::temp_dir().join(format!("vplayer_same_device_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        let path = path.to_string_lossy().to_string();
+        player.load(path.clone()).unwrap();
+        // A reload would reset this to the file's real duration.
+        player.playback.lock().unwrap().total_duration = Duration::from_secs(999);
+
+        // Short-circuits before opening anything, so this holds even
+        // where "Speakers" doesn't exist.
+        assert!(!player.set_output_device("Speakers").unwrap());
+        assert!(rx.try_recv().is_err(), "no reinit");
+        let playback = player.playback.lock().unwrap();
+        assert_eq!(playback.current_path.as_deref(), Some(path.as_str()));
+        assert_eq!(playback.total_duration, Duration::from_secs(999), "track was not reloaded");
+        drop(playback);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_reinit_is_not_reported() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        let (tx, rx) = mpsc::channel();
+        player.set_reinit_listener(Box::new(move |event| tx.send(event.clone()).unwrap()));
+
+        assert!(player.ensure_output().is_err());
+        assert!(player.play().is_err());
+        assert!(player.set_audio_config(AudioConfig { output_channels: super::device::OutputChannels::Stereo, ..Default::default() }).is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    static OPENED_FOR_RATE: AtomicU32 = AtomicU32::new(0);
+
+    fn no_device_recording_rate(config: &AudioConfig, source_rate: Option<u32>, device_name: Option<&str>) -> AppResult<(OutputStream, Mixer, Option<String>)> {
+        OPENED_FOR_RATE.store(source_rate.unwrap_or(0), Ordering::SeqCst);
+        no_device(config, source_rate, device_name)
+    }
+
+    #[test]
+    fn output_is_opened_for_the_loaded_track_rate() {
+        let player = AudioPlayer::with_output_opener(no_device_recording_rate);
+        let config = AudioConfig { sample_rate: OutputSampleRate::MatchSource, ..Default::default() };
+        player.set_audio_config(config).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("vplayer_source_rate_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        player.load(path.to_string_lossy().to_string()).unwrap();
+
+        assert!(player.ensure_output().is_err());
+        assert_eq!(OPENED_FOR_RATE.load(Ordering::SeqCst), 44_100);
+        assert_eq!(player.output_format(), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    static REQUESTED_DEVICES: std::sync::Mutex<Vec<Option<String>>> = std::sync::Mutex::new(Vec::new());
+
+    fn no_device_recording_name(config: &AudioConfig, source_rate: Option<u32>, device_name: Option<&str>) -> AppResult<(OutputStream, Mixer, Option<String>)> {
+        REQUESTED_DEVICES.lock().unwrap().push(device_name.map(str::to_string));
+        no_device(config, source_rate, device_name)
+    }
+
+    #[test]
+    fn output_is_opened_on_the_requested_device() {
+        let player = AudioPlayer::with_output_opener(no_device_recording_name);
+        assert_eq!(*REQUESTED_DEVICES.lock().unwrap(), vec![None], "starts on the default device");
+        REQUESTED_DEVICES.lock().unwrap().clear();
+
+        // The named device is what gets opened; when that fails, the
+        // output keeps following the device that was in use.
+        assert!(player.set_output_device("Headphones").is_err());
+        assert_eq!(*REQUESTED_DEVICES.lock().unwrap(), vec![Some("Headphones".to_string())]);
+        assert_eq!(player.device.lock().unwrap().requested_device, None);
+        REQUESTED_DEVICES.lock().unwrap().clear();
+
+        // Later reopens go to the picked device.
+        player.device.lock().unwrap().requested_device = Some("Headphones".to_string());
+        assert!(player.ensure_output().is_err());
+        assert_eq!(*REQUESTED_DEVICES.lock().unwrap(), vec![Some("Headphones".to_string())]);
+        REQUESTED_DEVICES.lock().unwrap().clear();
+
+        // Picking the device already in use opens nothing but pins it.
+        {
+            let mut device = player.device.lock().unwrap();
+            device.connected_device_name = Some("Speakers".to_string());
+            device.requested_device = None;
+        }
+        assert!(!player.set_output_device("Speakers").unwrap());
+        assert!(REQUESTED_DEVICES.lock().unwrap().is_empty());
+        assert_eq!(player.device.lock().unwrap().requested_device.as_deref(), Some("Speakers"));
+    }
+
+    #[test]
+    fn output_device_name_falls_back_to_the_picked_device() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        assert_eq!(player.output_device_name(), None);
+        player.device.lock().unwrap().requested_device = Some("Headphones".to_string());
+        assert_eq!(player.output_device_name().as_deref(), Some("Headphones"));
+        player.device.lock().unwrap().connected_device_name = Some("Speakers".to_string());
+        assert_eq!(player.output_device_name().as_deref(), Some("Speakers"));
+    }
+
     /// needs_reinit() must be false immediately after construction (no long
     /// pause has elapsed, and the device name is still present in the OS list).
     #[test]
@@ -868,4 +1680,181 @@ mod tests {
             "needs_reinit should be false immediately after construction"
         );
     }
+
+    fn no_device(_config: &AudioConfig, _source_rate: Option<u32>, _device_name: Option<&str>) -> AppResult<(OutputStream, Mixer, Option<String>)> {
+        Err(AppError::Audio("No output device available".to_string()))
+    }
+
+    /// Write a short silent 16-bit mono WAV.
+    fn write_silent_wav(path: &std::path::Path) {
+        write_silent_wav_at(path, 44100, 4410);
+    }
+
+    /// Write `frames` of silence as a 16-bit mono WAV at `sample_rate`.
+    fn write_silent_wav_at(path: &std::path::Path, sample_rate: u32, frames: u32) {
+        let data_len = frames * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        bytes.resize(44 + data_len as usize, 0);
+        std::fs::write(path, bytes).expect("write wav failed");
+    }
+
+    #[test]
+    fn starts_without_device_and_play_reports_it() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        assert!(!player.has_output());
+
+        // Non-output operations keep working in no-device mode.
+        player.set_volume(0.5).unwrap();
+        player.pause().unwrap();
+        player.stop().unwrap();
+
+        let dir = std::env::temp_dir().join(format!("vplayer_no_device_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        player.load(path.to_string_lossy().to_string()).unwrap();
+        // Loading again must not wait on the detached sink to drain.
+        player.load(path.to_string_lossy().to_string()).unwrap();
+
+        let err = player.play().expect_err("play must fail without a device");
+        assert!(
+            err.to_string().contains("No audio output device available"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(!player.has_output());
+        assert!(!player.is_playing());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_starts_at_the_saved_position_when_resume_is_on() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        // Resume halfway, which also checks the lookup is given the duration.
+        player.set_resume_lookup(Box::new(|_, duration| Some(duration / 2.0)));
+
+        let dir = std::env::temp_dir().join(format!("vplayer_resume_load_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("episode.wav");
+        write_silent_wav_at(&path, 8000, 8000 * 60);
+        let path = path.to_string_lossy().to_string();
+
+        player.load(path.clone()).unwrap();
+        assert_eq!(player.get_position(), 0.0, "resume is off");
+
+        player.set_resume_per_track(true);
+        player.load(path.clone()).unwrap();
+        let position = player.get_position();
+        assert!((position - 30.0).abs() < 0.01, "expected to start at 30s, got {}", position);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn relative_seek_is_clamped_to_the_track() {
+        assert_eq!(relative_seek_target(10.0, -5.0, 180.0), 5.0);
+        assert_eq!(relative_seek_target(2.0, -5.0, 180.0), 0.0);
+        assert_eq!(relative_seek_target(175.0, 10.0, 180.0), 180.0);
+        assert_eq!(relative_seek_target(175.0, 10.0, 0.0), 185.0, "unknown duration");
+    }
+
+    #[test]
+    fn unmute_restores_exact_volume_and_gain() {
+        let player = AudioPlayer::with_output_opener(no_device);
+        player.set_volume(0.37).unwrap();
+        player.set_normalize_mode(crate::audio::NormalizeMode::ReplayGain);
+        player.set_replaygain(-4.5, 0.0, None).unwrap();
+        let gain = player.replaygain.load(std::sync::atomic::Ordering::Relaxed);
+
+        assert!(player.toggle_mute());
+        assert!(player.is_muted());
+        assert_eq!(player.sink.lock().unwrap().volume(), 0.0);
+
+        // Volume changes while muted are remembered, not played.
+        player.set_volume(0.42).unwrap();
+        assert_eq!(player.sink.lock().unwrap().volume(), 0.0);
+        player.set_volume(0.37).unwrap();
+
+        assert!(!player.toggle_mute());
+        assert_eq!(player.sink.lock().unwrap().volume(), 0.37);
+        assert_eq!(player.replaygain.load(std::sync::atomic::Ordering::Relaxed), gain);
+
+        player.set_muted(true);
+        player.set_muted(false);
+        assert_eq!(player.sink.lock().unwrap().volume(), 0.37);
+    }
+
+    #[test]
+    fn load_applies_track_gain_override() {
+        let player = AudioPlayer::with_output_opener(no_device);
+
+        let dir = std::env::temp_dir().join(format!("vplayer_gain_override_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        let path = path.to_string_lossy().to_string();
+
+        let with_override = TrackGains { override_db: Some(3.0), ..Default::default() };
+        player.load_with_gains(path.clone(), &with_override).unwrap();
+        let gain = f32::from_bits(player.replaygain.load(std::sync::atomic::Ordering::Relaxed));
+        assert!((gain - 10_f32.powf(3.0 / 20.0)).abs() < 0.001, "gain was {}", gain);
+
+        // A track without an override plays unadjusted.
+        player.load_with_gains(path, &TrackGains::default()).unwrap();
+        let gain = f32::from_bits(player.replaygain.load(std::sync::atomic::Ordering::Relaxed));
+        assert!((gain - 1.0).abs() < f32::EPSILON);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_applies_gain_picked_by_replaygain_mode() {
+        use crate::replaygain::{AlbumReplayGainData, ReplayGainData, StoredGains};
+
+        let player = AudioPlayer::with_output_opener(no_device);
+        let dir = std::env::temp_dir().join(format!("vplayer_rg_mode_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        write_silent_wav(&path);
+        let path = path.to_string_lossy().to_string();
+
+        let mut gains = TrackGains {
+            stored: StoredGains {
+                track: Some(ReplayGainData { track_gain: -6.0, track_peak: 0.5, loudness: -12.0, target_lufs: -18.0 }),
+                album: Some(AlbumReplayGainData {
+                    album_gain: -10.0, album_peak: 0.5, loudness: -8.0, track_count: 3, target_lufs: -18.0,
+                }),
+            },
+            target_lufs: -18.0,
+            override_db: None,
+        };
+        let gain = |player: &AudioPlayer| f32::from_bits(player.replaygain.load(std::sync::atomic::Ordering::Relaxed));
+
+        player.set_replaygain_mode(ReplayGainMode::AlbumWithTrackFallback);
+        player.load_with_gains(path.clone(), &gains).unwrap();
+        assert!((gain(&player) - 10_f32.powf(-10.0 / 20.0)).abs() < 0.001);
+
+        gains.stored.album = None;
+        player.load_with_gains(path.clone(), &gains).unwrap();
+        assert!((gain(&player) - 10_f32.powf(-6.0 / 20.0)).abs() < 0.001);
+
+        player.set_replaygain_mode(ReplayGainMode::Off);
+        player.load_with_gains(path, &gains).unwrap();
+        assert!((gain(&player) - 1.0).abs() < f32::EPSILON);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
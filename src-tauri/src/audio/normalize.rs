@@ -0,0 +1,117 @@
+//! Volume normalization modes.
+//!
+//! `ReplayGain` uses pre-analysed per-track gain (the VolumeManager's
+//! multiplier). `Peak` needs no analysis: `PeakNormalizer` follows the
+//! signal's peak envelope in the DSP chain and scales it toward
+//! [`PEAK_TARGET`]. Each batch is measured before it is scaled, so the
+//! batch itself is the lookahead window and gain drops land before the
+//! peak that caused them — the output never clips.
+
+use serde::{Deserialize, Serialize};
+
+/// Settings key under which the chosen mode is persisted.
+pub const NORMALIZE_MODE_KEY: &str = "normalize_mode";
+
+/// Output peak the `Peak` mode aims for (-1 dBFS).
+pub const PEAK_TARGET: f32 = 0.891;
+/// Largest boost `Peak` mode applies to quiet material.
+pub const MAX_PEAK_GAIN_DB: f32 = 12.0;
+/// How fast the gain may rise once the signal gets quieter.
+const GAIN_RISE_DB_PER_SEC: f32 = 6.0;
+/// How fast the remembered peak decays.
+const ENVELOPE_RELEASE_DB_PER_SEC: f32 = 3.0;
+/// Peaks below this (-60 dBFS) are treated as silence and not boosted.
+const SILENCE_FLOOR: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NormalizeMode {
+    Off,
+    Peak,
+    #[default]
+    ReplayGain,
+}
+
+impl NormalizeMode {
+    pub fn as_u8(self) -> u8 {
+        match self {
+            NormalizeMode::Off => 0,
+            NormalizeMode::Peak => 1,
+            NormalizeMode::ReplayGain => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            0 => NormalizeMode::Off,
+            1 => NormalizeMode::Peak,
+            _ => NormalizeMode::ReplayGain,
+        }
+    }
+}
+
+/// Real-time peak normalizer operating on interleaved sample batches.
+pub struct PeakNormalizer {
+    envelope: f32,
+    gain: f32,
+}
+
+impl PeakNormalizer {
+    pub fn new() -> Self {
+        Self { envelope: 0.0, gain: 1.0 }
+    }
+
+    /// Forget the envelope and gain so re-enabling Peak mode starts fresh.
+    pub fn reset(&mut self) {
+        self.envelope = 0.0;
+        self.gain = 1.0;
+    }
+
+    /// Scale `buf` toward the target peak.
+    pub fn process(&mut self, buf: &mut [f32], sample_rate: u32, channels: u16) {
+        if buf.is_empty() {
+            return;
+        }
+        let frames = buf.len() / channels.max(1) as usize;
+        let secs = frames as f32 / sample_rate.max(1) as f32;
+
+        let batch_peak = buf.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+        let release = 10_f32.powf(-ENVELOPE_RELEASE_DB_PER_SEC * secs / 20.0);
+        self.envelope = batch_peak.max(self.envelope * release);
+
+        let max_gain = 10_f32.powf(MAX_PEAK_GAIN_DB / 20.0);
+        let wanted = if self.envelope > SILENCE_FLOOR {
+            (PEAK_TARGET / self.envelope).min(max_gain)
+        } else {
+            self.gain
+        };
+
+        let start = self.gain;
+        let end = if wanted < start {
+            // Cut immediately so this batch's peak stays under the target.
+            wanted
+        } else {
+            wanted.min(start * 10_f32.powf(GAIN_RISE_DB_PER_SEC * secs / 20.0))
+        };
+
+        if end < start {
+            for sample in buf.iter_mut() {
+                *sample *= end;
+            }
+        } else {
+            // Ramp upward across the batch; every step is <= `end`, which is
+            // safe for this batch's peak.
+            let step = (end - start) / buf.len() as f32;
+            for (i, sample) in buf.iter_mut().enumerate() {
+                *sample *= start + step * i as f32;
+            }
+        }
+        self.gain = end;
+    }
+}
+
+impl Default for PeakNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,133 @@
+//! Opt-in tap on the processed PCM stream.
+//!
+//! When enabled, `EffectsSource` copies every sample it hands to the sink
+//! (after ReplayGain, effects and balance) into a lock-free ring. A
+//! recorder or external visualizer polls `get_pcm_tap` and receives the
+//! samples written since its last read. Disabled, the tap costs one atomic
+//! load per batch.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+use super::visualizer::Ring;
+
+/// Ring size in samples: about four seconds of 48 kHz stereo, rounded to a
+/// multiple of 840 (divisible by every channel count up to 8) so overruns
+/// stay frame-aligned.
+pub const PCM_TAP_CAPACITY: usize = 840 * 460;
+
+/// Processed samples read from the tap.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcmChunk {
+    /// Interleaved samples, whole frames only.
+    pub samples: Vec<f32>,
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Frames lost because the reader fell more than a ring behind.
+    pub dropped_frames: u64,
+}
+
+pub struct PcmTap {
+    enabled: AtomicBool,
+    ring: Ring,
+    /// Push count up to which samples have been read.
+    read_pos: AtomicU64,
+    channels: AtomicU32,
+    sample_rate: AtomicU32,
+}
+
+impl PcmTap {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            ring: Ring::new(capacity),
+            read_pos: AtomicU64::new(0),
+            channels: AtomicU32::new(2),
+            sample_rate: AtomicU32::new(44_100),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turn the tap on or off. Enabling starts from the current position so
+    /// stale samples from an earlier session aren't returned.
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled && !self.is_enabled() {
+            self.read_pos.store(self.ring.written(), Ordering::Relaxed);
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Format of the samples being pushed (set when a source starts).
+    pub fn set_format(&self, sample_rate: u32, channels: u16) {
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+        self.channels.store(channels.max(1) as u32, Ordering::Relaxed);
+    }
+
+    /// Add a sample (audio thread, lock-free). Callers check `is_enabled`.
+    pub fn push(&self, sample: f32) {
+        self.ring.push(sample);
+    }
+
+    /// Up to `max_frames` frames written since the last read, oldest first.
+    pub fn read(&self, max_frames: usize) -> PcmChunk {
+        let channels = self.channels.load(Ordering::Relaxed).max(1) as u16;
+        let frame = channels as u64;
+        let written = self.ring.written();
+        let mut start = self.read_pos.load(Ordering::Relaxed).min(written);
+
+        let oldest = written.saturating_sub(self.ring.capacity() as u64);
+        let dropped_frames = oldest.saturating_sub(start) / frame;
+        start = start.max(oldest);
+
+        let available = (written - start) / frame;
+        let frames = available.min(max_frames as u64);
+        let end = start + frames * frame;
+        self.read_pos.store(end, Ordering::Relaxed);
+
+        PcmChunk {
+            samples: self.ring.copy_range(start, end),
+            channels,
+            sample_rate: self.sample_rate.load(Ordering::Relaxed),
+            dropped_frames,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_only_new_whole_frames() {
+        let tap = PcmTap::new(16);
+        tap.set_format(48_000, 2);
+        tap.set_enabled(true);
+        for i in 0..5 {
+            tap.push(i as f32);
+        }
+        let chunk = tap.read(10);
+        assert_eq!(chunk.samples, vec![0.0, 1.0, 2.0, 3.0]);
+        assert_eq!((chunk.channels, chunk.sample_rate, chunk.dropped_frames), (2, 48_000, 0));
+
+        tap.push(5.0);
+        assert_eq!(tap.read(10).samples, vec![4.0, 5.0]);
+        assert!(tap.read(10).samples.is_empty());
+    }
+
+    #[test]
+    fn overrun_skips_to_the_oldest_kept_frame() {
+        let tap = PcmTap::new(8);
+        tap.set_format(44_100, 2);
+        tap.set_enabled(true);
+        for i in 0..12 {
+            tap.push(i as f32);
+        }
+        let chunk = tap.read(100);
+        assert_eq!(chunk.dropped_frames, 2);
+        assert_eq!(chunk.samples, (4..12).map(|i| i as f32).collect::<Vec<_>>());
+    }
+}
@@ -2,9 +2,84 @@
 //!
 //! Tracks current position, pause state, and timing for the audio engine.
 //! All fields are plain data — no audio resources — inherently Send + Sync.
+//!
+//! Position comes from a `SampleClock` (samples actually pulled through the
+//! DSP chain) when one has seen audio, and from wall-clock timing otherwise.
 
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+/// Sample-accurate position of one source: the last seek target plus the
+/// samples pulled since. Written by the audio thread, read lock-free.
+pub struct SampleClock {
+    /// Seek base in seconds (f64 bits).
+    base_secs: AtomicU64,
+    /// Interleaved samples pulled since the base.
+    samples: AtomicU64,
+    sample_rate: AtomicU32,
+    channels: AtomicU32,
+    /// Set when the source stopped because reading its file failed.
+    read_failed: AtomicBool,
+}
+
+impl SampleClock {
+    pub fn new() -> Self {
+        Self {
+            base_secs: AtomicU64::new(0.0_f64.to_bits()),
+            samples: AtomicU64::new(0),
+            sample_rate: AtomicU32::new(0),
+            channels: AtomicU32::new(0),
+            read_failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Record the source format; position is unknown until this is set.
+    pub fn set_format(&self, sample_rate: u32, channels: u16) {
+        self.channels.store(channels.max(1) as u32, Ordering::Relaxed);
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+    }
+
+    /// Count interleaved samples handed to the output.
+    pub fn advance(&self, samples: u64) {
+        self.samples.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    /// Restart counting from `secs` (after a seek).
+    pub fn reset_to(&self, secs: f64) {
+        self.samples.store(0, Ordering::Relaxed);
+        self.base_secs.store(secs.to_bits(), Ordering::Relaxed);
+        self.read_failed.store(false, Ordering::Relaxed);
+    }
+
+    /// Record that the source ended because reading its file failed.
+    pub fn mark_read_failed(&self) {
+        self.read_failed.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the source ended on a read failure since the last seek.
+    pub fn read_failed(&self) -> bool {
+        self.read_failed.load(Ordering::Relaxed)
+    }
+
+    /// Position in seconds, or None before any format is known.
+    pub fn position(&self) -> Option<f64> {
+        let rate = self.sample_rate.load(Ordering::Relaxed);
+        if rate == 0 {
+            return None;
+        }
+        let channels = self.channels.load(Ordering::Relaxed).max(1) as u64;
+        let frames = self.samples.load(Ordering::Relaxed) / channels;
+        let base = f64::from_bits(self.base_secs.load(Ordering::Relaxed));
+        Some(base + frames as f64 / rate as f64)
+    }
+}
+
+impl Default for SampleClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Tracks playback position, pause state, and timing.
 pub struct PlaybackState {
     pub current_path: Option<String>,
@@ -13,6 +88,11 @@ pub struct PlaybackState {
     pub pause_start: Option<Instant>,
     pub paused_duration: Duration,
     pub total_duration: Duration,
+    /// Whether the loaded source answered `try_seek`; probed once per load.
+    pub seekable: bool,
+    /// Playback speed multiplier; wall-clock time is scaled by it. Kept
+    /// across loads.
+    pub speed: f64,
 }
 
 impl PlaybackState {
@@ -24,6 +104,8 @@ impl PlaybackState {
             pause_start: None,
             paused_duration: Duration::ZERO,
             total_duration: Duration::ZERO,
+            seekable: false,
+            speed: 1.0,
         }
     }
 
@@ -58,6 +140,7 @@ impl PlaybackState {
     /// Clear all state (stopped).
     pub fn clear(&mut self) {
         self.current_path = None;
+        self.seekable = false;
         self.start_time = None;
         self.seek_offset = Duration::ZERO;
         self.paused_duration = Duration::ZERO;
@@ -72,16 +155,32 @@ impl PlaybackState {
         self.pause_start = if is_paused { Some(Instant::now()) } else { None };
     }
 
+    /// Change the speed multiplier, rebasing the wall-clock timing at
+    /// `position` so time already played keeps the old speed.
+    pub fn set_speed(&mut self, speed: f64, position: f64, is_paused: bool) {
+        if self.start_time.is_some() {
+            self.mark_seeked(position, is_paused);
+        }
+        self.speed = speed;
+    }
+
     /// Calculate current playback position in seconds.
     ///
-    /// Requires the caller to pass sink state to avoid nested locking.
-    pub fn get_position(&self, sink_empty: bool, sink_paused: bool) -> f64 {
+    /// `sample_position` is the current source's `SampleClock` reading; the
+    /// wall-clock estimate is used when it is None. Requires the caller to
+    /// pass sink state to avoid nested locking.
+    pub fn get_position(&self, sink_empty: bool, sink_paused: bool, sample_position: Option<f64>) -> f64 {
         // If sink is empty (track finished), return the total duration rather than
         // letting the wall-clock counter drift past the end of the track.
         if sink_empty && self.start_time.is_some() {
             return self.total_duration.as_secs_f64();
         }
 
+        if let (Some(_), Some(position)) = (self.start_time, sample_position) {
+            let total = self.total_duration.as_secs_f64();
+            return if total > 0.0 { position.min(total) } else { position };
+        }
+
         if let Some(start) = self.start_time {
             let elapsed = start.elapsed();
 
@@ -94,7 +193,7 @@ impl PlaybackState {
             };
 
             let playing_time = elapsed.saturating_sub(self.paused_duration + additional_pause);
-            let position = self.seek_offset + playing_time;
+            let position = self.seek_offset + playing_time.mul_f64(self.speed);
 
             // Clamp to total duration to prevent wall-clock drift past track end
             if self.total_duration > Duration::ZERO {
@@ -117,7 +216,7 @@ mod tests {
         let state = PlaybackState::new();
         assert!(state.current_path.is_none());
         assert!(state.start_time.is_none());
-        assert_eq!(state.get_position(false, false), 0.0);
+        assert_eq!(state.get_position(false, false, None), 0.0);
     }
 
     #[test]
@@ -197,7 +296,7 @@ mod tests {
         state.reset_for_load("done.mp3".into(), Duration::from_secs(200));
         state.mark_playing();
 
-        let pos = state.get_position(true, false);
+        let pos = state.get_position(true, false, None);
         assert_eq!(pos, 200.0);
     }
 
@@ -209,7 +308,52 @@ mod tests {
         // Small sleep to let wall-clock exceed the 50ms total_duration
         std::thread::sleep(Duration::from_millis(80));
 
-        let pos = state.get_position(false, false);
+        let pos = state.get_position(false, false, None);
         assert!(pos <= 0.05 + 0.01, "position should be clamped near total_duration");
     }
+
+    #[test]
+    fn wall_clock_position_advances_at_playback_speed() {
+        let mut state = PlaybackState::new();
+        state.reset_for_load("book.m4a".into(), Duration::from_secs(3600));
+        state.set_speed(1.5, 0.0, false);
+        state.mark_playing();
+
+        std::thread::sleep(Duration::from_millis(200));
+        let pos = state.get_position(false, false, None);
+        assert!((0.29..0.4).contains(&pos), "200ms at 1.5x should be ~0.3s, got {}", pos);
+
+        // Slowing down keeps the time already played.
+        state.set_speed(1.0, pos, false);
+        assert!(state.get_position(false, false, None) >= pos);
+        assert_eq!(state.speed, 1.0);
+    }
+
+    #[test]
+    fn sample_clock_counts_pulled_frames() {
+        let clock = SampleClock::new();
+        assert_eq!(clock.position(), None);
+
+        clock.set_format(44100, 2);
+        clock.advance(44100 * 2 * 3); // three seconds of stereo
+        assert!((clock.position().unwrap() - 3.0).abs() < 1e-9);
+
+        clock.reset_to(60.0);
+        clock.advance(22050 * 2);
+        assert!((clock.position().unwrap() - 60.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sample_position_wins_over_wall_clock() {
+        let mut state = PlaybackState::new();
+        state.reset_for_load("s.mp3".into(), Duration::from_secs(300));
+        state.mark_playing();
+
+        assert_eq!(state.get_position(false, false, Some(42.25)), 42.25);
+        // Still clamped to the track length.
+        assert_eq!(state.get_position(false, false, Some(400.0)), 300.0);
+        // Nothing is reported before playback starts.
+        state.clear();
+        assert_eq!(state.get_position(false, false, Some(5.0)), 0.0);
+    }
 }
@@ -7,13 +7,27 @@
 
 use rodio::Sink;
 use log::warn;
+use std::sync::Arc;
 use std::time::Duration;
 
+use super::playback_state::SampleClock;
+
+/// A decoded track waiting in its own paused sink.
+pub struct PreloadedTrack {
+    pub sink: Sink,
+    /// Sample clock of the preloaded source, handed over on swap.
+    pub clock: Arc<SampleClock>,
+    pub path: String,
+    pub total_duration: Duration,
+    /// Whether the source answered `try_seek`, probed at preload time.
+    pub seekable: bool,
+    /// Where the source starts: its resume position, or 0.
+    pub start_secs: f64,
+}
+
 /// Manages preloaded tracks for gapless playback.
 pub struct PreloadManager {
-    sink: Option<Sink>,
-    path: Option<String>,
-    total_duration: Duration,
+    track: Option<PreloadedTrack>,
     /// Device generation at the time the preload was created.
     device_generation: u64,
 }
@@ -21,30 +35,24 @@ pub struct PreloadManager {
 impl PreloadManager {
     pub fn new() -> Self {
         Self {
-            sink: None,
-            path: None,
-            total_duration: Duration::ZERO,
+            track: None,
             device_generation: 0,
         }
     }
 
-    /// Store a preloaded sink, path, duration, and the current device generation.
-    pub fn set(&mut self, sink: Sink, path: String, duration: Duration, device_generation: u64) {
-        self.sink = Some(sink);
-        self.path = Some(path);
-        self.total_duration = duration;
+    /// Store a preloaded track and the current device generation.
+    pub fn set(&mut self, track: PreloadedTrack, device_generation: u64) {
+        self.track = Some(track);
         self.device_generation = device_generation;
     }
 
-    /// Take the preloaded sink and path if the device generation still matches.
+    /// Take the preloaded track if the device generation still matches.
     ///
     /// If the device has been reinitialized since the preload was created,
     /// the sink is connected to the old (dead) mixer — discard it and
     /// return None so the caller falls back to a full load.
-    pub fn take_if_current(&mut self, current_generation: u64) -> Option<(Sink, String, Duration)> {
-        if self.sink.is_none() {
-            return None;
-        }
+    pub fn take_if_current(&mut self, current_generation: u64) -> Option<PreloadedTrack> {
+        self.track.as_ref()?;
 
         if self.device_generation != current_generation {
             warn!(
@@ -55,26 +63,58 @@ impl PreloadManager {
             return None;
         }
 
-        match (self.sink.take(), self.path.take()) {
-            (Some(sink), Some(path)) => {
-                let dur = self.total_duration;
-                Some((sink, path, dur))
-            }
-            _ => None,
-        }
+        self.track.take()
     }
 
     pub fn has_preloaded(&self) -> bool {
-        self.sink.is_some()
+        self.track.is_some()
     }
 
     /// Return the file path of the currently preloaded track, if any.
     pub fn get_path(&self) -> Option<&str> {
-        self.path.as_deref()
+        self.track.as_ref().map(|track| track.path.as_str())
     }
 
     pub fn clear(&mut self) {
-        self.sink = None;
-        self.path = None;
+        self.track = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preloaded(path: &str, seekable: bool) -> PreloadedTrack {
+        PreloadedTrack {
+            sink: Sink::new().0,
+            clock: Arc::new(SampleClock::new()),
+            path: path.to_string(),
+            total_duration: Duration::from_secs(180),
+            seekable,
+            start_secs: 0.0,
+        }
+    }
+
+    #[test]
+    fn take_hands_over_the_probed_seek_capability() {
+        let mut manager = PreloadManager::new();
+        manager.set(preloaded("stream.ogg", false), 3);
+        assert_eq!(manager.get_path(), Some("stream.ogg"));
+
+        let track = manager.take_if_current(3).expect("same generation");
+        assert!(!track.seekable);
+        assert_eq!(track.path, "stream.ogg");
+        assert!(!manager.has_preloaded());
+
+        manager.set(preloaded("song.flac", true), 3);
+        assert!(manager.take_if_current(3).unwrap().seekable);
+    }
+
+    #[test]
+    fn stale_preload_is_discarded() {
+        let mut manager = PreloadManager::new();
+        manager.set(preloaded("song.flac", true), 1);
+        assert!(manager.take_if_current(2).is_none());
+        assert!(!manager.has_preloaded());
     }
 }
@@ -0,0 +1,128 @@
+//! Gating for the `playback-progress` event.
+//!
+//! The broadcast thread asks `should_emit` on every pass; the ticker answers
+//! `true` at most once per configured interval while playing, and never while
+//! paused or stopped. The interval is an atomic so `set_progress_interval`
+//! can change it without touching the broadcast thread.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, AppResult};
+
+pub const DEFAULT_PROGRESS_INTERVAL_MS: u64 = 250;
+pub const MIN_PROGRESS_INTERVAL_MS: u64 = 16;
+pub const MAX_PROGRESS_INTERVAL_MS: u64 = 5000;
+/// Settings key under which the chosen interval is persisted.
+pub const PROGRESS_INTERVAL_KEY: &str = "progress_interval_ms";
+
+pub struct ProgressTicker {
+    interval_ms: AtomicU64,
+    last_emit: Mutex<Option<Instant>>,
+}
+
+impl ProgressTicker {
+    pub fn new(interval_ms: u64) -> Self {
+        Self {
+            interval_ms: AtomicU64::new(
+                interval_ms.clamp(MIN_PROGRESS_INTERVAL_MS, MAX_PROGRESS_INTERVAL_MS),
+            ),
+            last_emit: Mutex::new(None),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    pub fn set_interval(&self, interval_ms: u64) -> AppResult<()> {
+        if !(MIN_PROGRESS_INTERVAL_MS..=MAX_PROGRESS_INTERVAL_MS).contains(&interval_ms) {
+            return Err(AppError::Validation(format!(
+                "Progress interval must be between {} and {} ms",
+                MIN_PROGRESS_INTERVAL_MS, MAX_PROGRESS_INTERVAL_MS
+            )));
+        }
+        self.interval_ms.store(interval_ms, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// When the next progress event is due, or `None` before the first one
+    /// (which goes out on the next pass while playing).
+    pub fn next_due(&self) -> Option<Instant> {
+        let last = *self.last_emit.lock().unwrap_or_else(|p| p.into_inner());
+        last.map(|at| at + self.interval())
+    }
+
+    /// Whether a progress event is due at `now`. Pausing resets the clock so
+    /// the first event after resuming goes out immediately.
+    pub fn should_emit(&self, is_playing: bool, now: Instant) -> bool {
+        let mut last = self.last_emit.lock().unwrap_or_else(|p| p.into_inner());
+        if !is_playing {
+            *last = None;
+            return false;
+        }
+        match *last {
+            Some(at) if now.saturating_duration_since(at) < self.interval() => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+impl Default for ProgressTicker {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROGRESS_INTERVAL_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_at_interval_while_playing() {
+        let ticker = ProgressTicker::new(250);
+        let t0 = Instant::now();
+
+        assert!(ticker.should_emit(true, t0), "first tick goes out immediately");
+        assert!(!ticker.should_emit(true, t0 + Duration::from_millis(100)));
+        assert!(!ticker.should_emit(true, t0 + Duration::from_millis(249)));
+        assert!(ticker.should_emit(true, t0 + Duration::from_millis(250)));
+        assert!(!ticker.should_emit(true, t0 + Duration::from_millis(400)));
+        assert!(ticker.should_emit(true, t0 + Duration::from_millis(500)));
+        assert_eq!(ticker.next_due(), Some(t0 + Duration::from_millis(750)));
+    }
+
+    #[test]
+    fn silent_while_paused_and_resumes_immediately() {
+        let ticker = ProgressTicker::new(250);
+        let t0 = Instant::now();
+
+        assert!(ticker.should_emit(true, t0));
+        assert!(!ticker.should_emit(false, t0 + Duration::from_millis(10)));
+        assert_eq!(ticker.next_due(), None, "nothing is scheduled while paused");
+        for ms in [300, 600, 900, 5000] {
+            assert!(!ticker.should_emit(false, t0 + Duration::from_millis(ms)));
+        }
+        assert!(ticker.should_emit(true, t0 + Duration::from_millis(5010)));
+    }
+
+    #[test]
+    fn interval_changes_take_effect_and_are_bounded() {
+        let ticker = ProgressTicker::default();
+        assert_eq!(ticker.interval(), Duration::from_millis(DEFAULT_PROGRESS_INTERVAL_MS));
+
+        ticker.set_interval(1000).unwrap();
+        let t0 = Instant::now();
+        assert!(ticker.should_emit(true, t0));
+        assert!(!ticker.should_emit(true, t0 + Duration::from_millis(500)));
+        assert!(ticker.should_emit(true, t0 + Duration::from_millis(1000)));
+
+        assert!(ticker.set_interval(0).is_err());
+        assert!(ticker.set_interval(MAX_PROGRESS_INTERVAL_MS + 1).is_err());
+        assert_eq!(ticker.interval(), Duration::from_millis(1000));
+    }
+}
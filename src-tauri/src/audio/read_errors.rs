@@ -0,0 +1,53 @@
+//! Notices when reading an audio file fails partway through.
+//!
+//! rodio's decoder skips packets that fail to decode and simply ends the
+//! stream when the file can't be read any further, without saying why. The
+//! player wraps each file in a [`WatchedReader`] so an I/O error is recorded
+//! and the end of the track can be reported as a failure instead of passing
+//! for a normal finish.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use log::warn;
+
+/// A reader that records the first I/O error its inner reader returns.
+pub struct WatchedReader<R> {
+    inner: R,
+    failed: Arc<AtomicBool>,
+}
+
+impl<R> WatchedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, failed: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Flag set once reading has failed; shared so it outlives the decoder.
+    pub fn failed(&self) -> Arc<AtomicBool> {
+        self.failed.clone()
+    }
+
+    fn record<T>(&self, result: io::Result<T>) -> io::Result<T> {
+        if let Err(e) = &result {
+            if e.kind() != io::ErrorKind::Interrupted && !self.failed.swap(true, Ordering::Relaxed) {
+                warn!("Reading audio file failed: {}", e);
+            }
+        }
+        result
+    }
+}
+
+impl<R: Read> Read for WatchedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf);
+        self.record(result)
+    }
+}
+
+impl<R: Seek> Seek for WatchedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let result = self.inner.seek(pos);
+        self.record(result)
+    }
+}
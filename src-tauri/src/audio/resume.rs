@@ -0,0 +1,122 @@
+//! Per-track resume for long-form audio (podcasts, audiobooks).
+//!
+//! While enabled, the broadcast thread saves the playing track's position
+//! every [`POSITION_SAVE_INTERVAL`] and on pause, and forgets it when the
+//! track ends. The player starts a track with a saved position from there,
+//! whether it is loaded or swapped in from a preload.
+
+use crate::database::Database;
+use log::warn;
+use std::time::Duration;
+
+/// Settings key under which the "resume per track" switch is persisted.
+pub const RESUME_PER_TRACK_KEY: &str = "resume_per_track";
+
+/// How often the playing track's position is written while playing.
+pub const POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Looks up where to start `path` (of the given duration in seconds);
+/// main wires it to [`saved_position`] on the library database.
+pub type ResumeLookup = Box<dyn Fn(&str, f64) -> Option<f64> + Send + Sync>;
+
+/// Positions this close to either end of a track are not resumed. Wider
+/// than the save interval so a track that rolled straight into the next one
+/// (and never reported an end) is not resumed a few seconds from its end.
+const RESUME_MARGIN_SECS: f64 = 10.0;
+
+/// Where to start a track of `duration` seconds given its saved position.
+/// None when there is nothing useful to resume (no position, or one so near
+/// the start or end that starting over is what the listener expects).
+pub fn resume_position(saved: Option<f64>, duration: f64) -> Option<f64> {
+    let saved = saved.filter(|s| s.is_finite())?;
+    if saved < RESUME_MARGIN_SECS {
+        return None;
+    }
+    if duration > 0.0 && saved > duration - RESUME_MARGIN_SECS {
+        return None;
+    }
+    Some(saved)
+}
+
+/// Save `position` for the library track at `path`; None forgets it.
+/// Files that are not in the library are ignored.
+pub fn save_position(db: &Database, path: &str, position: Option<f64>) {
+    let result = db
+        .get_track_by_path(path)
+        .and_then(|track| match track {
+            Some(track) => db.set_track_position(&track.id, position).map(|_| ()),
+            None => Ok(()),
+        });
+    if let Err(e) = result {
+        warn!("Failed to save playback position for {}: {}", path, e);
+    }
+}
+
+/// Position to resume the library track at `path` from, if any.
+pub fn saved_position(db: &Database, path: &str, duration: f64) -> Option<f64> {
+    let saved = db
+        .get_track_by_path(path)
+        .and_then(|track| match track {
+            Some(track) => db.get_track_position(&track.id),
+            None => Ok(None),
+        })
+        .unwrap_or_else(|e| {
+            warn!("Failed to read playback position for {}: {}", path, e);
+            None
+        });
+    resume_position(saved, duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumes_from_the_middle_only() {
+        assert_eq!(resume_position(Some(754.5), 3600.0), Some(754.5));
+        assert_eq!(resume_position(None, 3600.0), None);
+        assert_eq!(resume_position(Some(4.0), 3600.0), None, "near the start");
+        assert_eq!(resume_position(Some(3594.0), 3600.0), None, "near the end");
+        assert_eq!(resume_position(Some(f64::NAN), 3600.0), None);
+        // Unknown duration: trust the saved position.
+        assert_eq!(resume_position(Some(42.0), 0.0), Some(42.0));
+    }
+
+    #[test]
+    fn saved_position_round_trips_through_the_library() {
+        let dir = std::env::temp_dir().join(format!("vplayer_resume_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(&dir.join("library.db")).unwrap();
+        db.add_track(&crate::scanner::Track {
+            id: "episode".to_string(),
+            path: "C:/Podcasts/episode.mp3".to_string(),
+            name: "episode.mp3".to_string(),
+            title: None,
+            artist: None,
+            album: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            duration: 3600.0,
+            date_added: 0,
+            rating: 0,
+            play_count: 0,
+            last_played: 0,
+        })
+        .unwrap();
+
+        save_position(&db, "C:/Podcasts/episode.mp3", Some(1234.0));
+        assert_eq!(saved_position(&db, "C:/Podcasts/episode.mp3", 3600.0), Some(1234.0));
+
+        save_position(&db, "C:/Podcasts/episode.mp3", None);
+        assert_eq!(saved_position(&db, "C:/Podcasts/episode.mp3", 3600.0), None);
+
+        // Files outside the library have nothing saved and are not an error.
+        save_position(&db, "C:/elsewhere.mp3", Some(10.0));
+        assert_eq!(saved_position(&db, "C:/elsewhere.mp3", 3600.0), None);
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
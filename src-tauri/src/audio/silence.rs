@@ -0,0 +1,183 @@
+//! Leading/trailing silence detection for the optional "trim silence" stage.
+//!
+//! At load the first and last [`SCAN_WINDOW_SECS`] of a track are decoded
+//! and scanned for frames whose loudest channel stays under the threshold.
+//! A silent run only counts when it lasts at least `min_duration_secs`, so a
+//! quiet fade or a short breath before the downbeat is left alone while a
+//! long run of digital silence is skipped.
+
+use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+/// Settings key under which the trim configuration is persisted.
+pub const SILENCE_TRIM_KEY: &str = "silence_trim";
+
+/// How much audio at each end of a track is scanned.
+pub const SCAN_WINDOW_SECS: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceTrimConfig {
+    pub enabled: bool,
+    /// Frames quieter than this (dBFS) count as silence.
+    pub threshold_db: f32,
+    /// Shortest silent run that is trimmed.
+    pub min_duration_secs: f32,
+}
+
+impl Default for SilenceTrimConfig {
+    fn default() -> Self {
+        Self { enabled: false, threshold_db: -60.0, min_duration_secs: 1.0 }
+    }
+}
+
+impl SilenceTrimConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-96.0..=-20.0).contains(&self.threshold_db) {
+            return Err("Silence threshold must be between -96 and -20 dBFS".to_string());
+        }
+        if !(0.1..=30.0).contains(&self.min_duration_secs) {
+            return Err("Minimum silence duration must be between 0.1 and 30 seconds".to_string());
+        }
+        Ok(())
+    }
+
+    fn threshold(&self) -> f32 {
+        10_f32.powf(self.threshold_db / 20.0)
+    }
+}
+
+/// Effective playback range of a track after trimming.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SilenceBounds {
+    /// Where playback starts, in seconds.
+    pub start_secs: f64,
+    /// Where playback stops, in seconds (None = play to the end).
+    pub end_secs: Option<f64>,
+}
+
+/// Number of frames, from the start of `frames`, whose samples are all below `threshold`.
+fn silent_frames<'a>(frames: impl Iterator<Item = &'a [f32]>, threshold: f32) -> usize {
+    frames
+        .take_while(|frame| frame.iter().all(|s| s.abs() < threshold))
+        .count()
+}
+
+/// Length in seconds of the silence at the start of `samples`, or 0 when it
+/// is shorter than the configured minimum.
+pub fn leading_silence(samples: &[f32], sample_rate: u32, channels: u16, config: &SilenceTrimConfig) -> f64 {
+    let channels = channels.max(1) as usize;
+    let frames = silent_frames(samples.chunks_exact(channels), config.threshold());
+    qualifying_secs(frames, sample_rate, config)
+}
+
+/// Length in seconds of the silence at the end of `samples`, or 0 when it
+/// is shorter than the configured minimum.
+pub fn trailing_silence(samples: &[f32], sample_rate: u32, channels: u16, config: &SilenceTrimConfig) -> f64 {
+    let channels = channels.max(1) as usize;
+    let whole = samples.len() - samples.len() % channels;
+    let frames = silent_frames(samples[..whole].chunks_exact(channels).rev(), config.threshold());
+    qualifying_secs(frames, sample_rate, config)
+}
+
+fn qualifying_secs(frames: usize, sample_rate: u32, config: &SilenceTrimConfig) -> f64 {
+    let secs = frames as f64 / sample_rate.max(1) as f64;
+    if secs >= config.min_duration_secs as f64 { secs } else { 0.0 }
+}
+
+fn open(path: &str) -> Result<Decoder<BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    Decoder::new(BufReader::new(file)).map_err(|e| format!("Failed to decode audio: {}", e))
+}
+
+/// Scan both ends of the file at `path` for silence to skip.
+pub fn detect(path: &str, config: &SilenceTrimConfig) -> Result<SilenceBounds, String> {
+    let head = open(path)?;
+    let (sample_rate, channels) = (head.sample_rate(), head.channels());
+    let total = head.total_duration().map(|d| d.as_secs_f64());
+    let window_samples = (SCAN_WINDOW_SECS * sample_rate as f64) as usize * channels as usize;
+
+    let head: Vec<f32> = head.take(window_samples).collect();
+    let start_secs = leading_silence(&head, sample_rate, channels, config);
+
+    // The tail scan needs a known length and a seekable source; without
+    // them only the leading silence is trimmed.
+    let mut end_secs = None;
+    if let Some(total) = total.filter(|t| *t > SCAN_WINDOW_SECS) {
+        let mut tail = open(path)?;
+        let tail_start = total - SCAN_WINDOW_SECS;
+        if tail.try_seek(Duration::from_secs_f64(tail_start)).is_ok() {
+            let tail: Vec<f32> = tail.collect();
+            let silence = trailing_silence(&tail, sample_rate, channels, config);
+            if silence > 0.0 {
+                end_secs = Some(tail_start + tail.len() as f64 / (sample_rate as f64 * channels as f64) - silence);
+            }
+        }
+    } else if let Some(total) = total {
+        let silence = trailing_silence(&head, sample_rate, channels, config);
+        if silence > 0.0 {
+            end_secs = Some(total - silence);
+        }
+    }
+
+    // An entirely silent file is played as-is rather than trimmed to nothing.
+    if end_secs.is_some_and(|end| end <= start_secs) {
+        return Ok(SilenceBounds::default());
+    }
+    Ok(SilenceBounds { start_secs, end_secs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(secs: f64, sample_rate: u32, amplitude: f32) -> Vec<f32> {
+        (0..(secs * sample_rate as f64) as usize)
+            .flat_map(|i| {
+                let s = (i as f32 * 0.05).sin() * amplitude;
+                [s, s]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn leading_silence_of_two_seconds_is_detected() {
+        let config = SilenceTrimConfig { enabled: true, ..Default::default() };
+        let mut samples = vec![0.0; 2 * 44100 * 2];
+        samples.extend(tone(1.0, 44100, 0.5));
+
+        let start = leading_silence(&samples, 44100, 2, &config);
+        assert!((start - 2.0).abs() < 0.01, "start offset was {}", start);
+    }
+
+    #[test]
+    fn short_quiet_passages_are_kept() {
+        let config = SilenceTrimConfig { enabled: true, min_duration_secs: 1.0, ..Default::default() };
+        let mut samples = vec![0.0; 44100]; // 0.5 s of stereo silence
+        samples.extend(tone(1.0, 44100, 0.5));
+        samples.extend(vec![0.0; 44100]);
+
+        assert_eq!(leading_silence(&samples, 44100, 2, &config), 0.0);
+        assert_eq!(trailing_silence(&samples, 44100, 2, &config), 0.0);
+    }
+
+    #[test]
+    fn trailing_silence_is_measured_from_the_end() {
+        let config = SilenceTrimConfig { enabled: true, ..Default::default() };
+        let mut samples = tone(1.0, 44100, 0.5);
+        samples.extend(vec![0.0001; 3 * 44100 * 2]); // -80 dBFS hiss
+
+        let tail = trailing_silence(&samples, 44100, 2, &config);
+        assert!((tail - 3.0).abs() < 0.01, "trailing silence was {}", tail);
+    }
+
+    #[test]
+    fn config_is_validated() {
+        assert!(SilenceTrimConfig::default().validate().is_ok());
+        assert!(SilenceTrimConfig { threshold_db: 0.0, ..Default::default() }.validate().is_err());
+        assert!(SilenceTrimConfig { min_duration_secs: 0.0, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Playback speed presets, remembered separately for music and spoken word.
+//!
+//! The speed drives the sink's resampling speed stage, so pitch moves with
+//! it. Loading a track applies the
+//! speed stored for its content type, so an audiobook at 1.5x doesn't leave
+//! the next album playing fast.
+
+use crate::database::Database;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// Settings key under which the per-content-type speeds are persisted.
+pub const PLAYBACK_SPEED_KEY: &str = "playback_speed";
+
+pub const MIN_SPEED: f32 = 0.5;
+pub const MAX_SPEED: f32 = 2.0;
+
+/// Genres (matched case-insensitively as substrings) treated as spoken word.
+const SPOKEN_GENRES: [&str; 5] = ["podcast", "audiobook", "audio book", "spoken", "speech"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentType {
+    Music,
+    Spoken,
+}
+
+impl ContentType {
+    pub fn from_genre(genre: Option<&str>) -> Self {
+        let genre = genre.unwrap_or_default().to_lowercase();
+        if SPOKEN_GENRES.iter().any(|g| genre.contains(g)) {
+            ContentType::Spoken
+        } else {
+            ContentType::Music
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlaybackSpeeds {
+    pub music: f32,
+    pub spoken: f32,
+}
+
+impl Default for PlaybackSpeeds {
+    fn default() -> Self {
+        Self { music: 1.0, spoken: 1.0 }
+    }
+}
+
+impl PlaybackSpeeds {
+    pub fn get(&self, content: ContentType) -> f32 {
+        match content {
+            ContentType::Music => self.music,
+            ContentType::Spoken => self.spoken,
+        }
+    }
+
+    pub fn set(&mut self, content: ContentType, speed: f32) {
+        match content {
+            ContentType::Music => self.music = speed,
+            ContentType::Spoken => self.spoken = speed,
+        }
+    }
+}
+
+/// Content type of the file at `path`, from its library genre. Files that
+/// are not in the library count as music.
+pub fn content_type_of(db: &Database, path: &str) -> ContentType {
+    match db.get_track_by_path(path) {
+        Ok(track) => ContentType::from_genre(track.as_ref().and_then(|t| t.genre.as_deref())),
+        Err(e) => {
+            warn!("Failed to look up content type of {}: {}", path, e);
+            ContentType::Music
+        }
+    }
+}
+
+/// The stored speeds; defaults when nothing (or something unreadable) is stored.
+pub fn stored_speeds(db: &Database) -> PlaybackSpeeds {
+    db.get_json_setting::<PlaybackSpeeds>(PLAYBACK_SPEED_KEY)
+        .unwrap_or_else(|e| {
+            warn!("Failed to read playback speeds: {}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Speed to play the file at `path` at.
+pub fn speed_for(db: &Database, path: &str) -> f32 {
+    stored_speeds(db).get(content_type_of(db, path))
+}
+
+/// Wall-clock seconds left to play `queue`, given as (duration, speed)
+/// pairs with the current track first, when the current track is
+/// `position` seconds in.
+pub fn remaining_playtime(queue: &[(f64, f32)], position: f64) -> f64 {
+    queue
+        .iter()
+        .enumerate()
+        .map(|(i, &(duration, speed))| {
+            let left = if i == 0 { (duration - position).max(0.0) } else { duration };
+            left / speed.clamp(MIN_SPEED, MAX_SPEED) as f64
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spoken_word_is_recognised_by_genre() {
+        assert_eq!(ContentType::from_genre(Some("Podcast")), ContentType::Spoken);
+        assert_eq!(ContentType::from_genre(Some("Audiobooks")), ContentType::Spoken);
+        assert_eq!(ContentType::from_genre(Some("Spoken Word")), ContentType::Spoken);
+        assert_eq!(ContentType::from_genre(Some("Rock")), ContentType::Music);
+        assert_eq!(ContentType::from_genre(None), ContentType::Music);
+    }
+
+    #[test]
+    fn remaining_playtime_counts_from_the_current_position() {
+        let queue = [(200.0, 1.0), (180.0, 1.0), (3600.0, 1.5)];
+        assert_eq!(remaining_playtime(&queue, 50.0), 150.0 + 180.0 + 2400.0);
+        assert_eq!(remaining_playtime(&queue[..1], 250.0), 0.0, "never negative");
+        assert_eq!(remaining_playtime(&[], 10.0), 0.0);
+    }
+
+    #[test]
+    fn speeds_are_kept_per_content_type() {
+        let dir = std::env::temp_dir().join(format!("vplayer_speed_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Database::new(&dir.join("library.db")).unwrap();
+        db.add_track(&crate::scanner::Track {
+            id: "episode".to_string(),
+            path: "C:/Podcasts/episode.mp3".to_string(),
+            name: "episode.mp3".to_string(),
+            title: None,
+            artist: None,
+            album: None,
+            genre: Some("Podcast".to_string()),
+            year: None,
+            track_number: None,
+            disc_number: None,
+            duration: 3600.0,
+            date_added: 0,
+            rating: 0,
+            play_count: 0,
+            last_played: 0,
+        })
+        .unwrap();
+
+        assert_eq!(speed_for(&db, "C:/Podcasts/episode.mp3"), 1.0);
+
+        let mut speeds = stored_speeds(&db);
+        speeds.set(ContentType::Spoken, 1.5);
+        db.set_json_setting(PLAYBACK_SPEED_KEY, &speeds).unwrap();
+
+        assert_eq!(speed_for(&db, "C:/Podcasts/episode.mp3"), 1.5);
+        assert_eq!(speed_for(&db, "C:/Music/song.flac"), 1.0, "music keeps its own speed");
+
+        drop(db);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -6,11 +6,8 @@
 
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
-/// Lock-free ring buffer for visualizer samples.
-///
-/// The audio thread writes via `push()` (no locks, no allocation).
-/// The UI thread reads via `get_samples()` (snapshot of recent data).
-pub struct VisualizerBuffer {
+/// Single-producer ring of f32 samples stored as atomic bits.
+pub(super) struct Ring {
     /// Sample data stored as f32 bits in AtomicU32 for lock-free access.
     samples: Box<[AtomicU32]>,
     /// Total number of samples ever pushed (monotonically increasing).
@@ -18,12 +15,8 @@ pub struct VisualizerBuffer {
     capacity: usize,
 }
 
-// Safety: All fields use atomic operations. No mutable aliasing occurs.
-unsafe impl Send for VisualizerBuffer {}
-unsafe impl Sync for VisualizerBuffer {}
-
-impl VisualizerBuffer {
-    pub fn new(capacity: usize) -> Self {
+impl Ring {
+    pub(super) fn new(capacity: usize) -> Self {
         let samples: Vec<AtomicU32> = (0..capacity)
             .map(|_| AtomicU32::new(0.0_f32.to_bits()))
             .collect();
@@ -33,31 +26,101 @@ impl VisualizerBuffer {
             capacity,
         }
     }
-    
-    /// Add a sample to the buffer (called from audio thread, lock-free).
-    pub fn push(&self, sample: f32) {
+
+    pub(super) fn push(&self, sample: f32) {
         let total = self.write_pos.fetch_add(1, Ordering::Relaxed);
         let pos = (total % self.capacity as u64) as usize;
         self.samples[pos].store(sample.to_bits(), Ordering::Relaxed);
     }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Total number of samples ever pushed.
+    pub(super) fn written(&self) -> u64 {
+        self.write_pos.load(Ordering::Relaxed)
+    }
+
+    /// Samples `start..end` by push count; the range must lie within the
+    /// last `capacity` samples.
+    pub(super) fn copy_range(&self, start: u64, end: u64) -> Vec<f32> {
+        (start..end)
+            .map(|i| f32::from_bits(self.samples[(i % self.capacity as u64) as usize].load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    fn snapshot(&self) -> Vec<f32> {
+        let total = self.written();
+        let start = total.saturating_sub(self.capacity as u64);
+        self.copy_range(start, total)
+    }
+
+    fn clear(&self) {
+        self.write_pos.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Lock-free ring buffer for visualizer samples.
+///
+/// The audio thread writes via `push()` (no locks, no allocation).
+/// The UI thread reads via `get_samples()` (snapshot of recent data).
+/// Alongside the combined stream, left and right samples are kept in
+/// their own rings for the stereo-split spectrum.
+pub struct VisualizerBuffer {
+    mixed: Ring,
+    left: Ring,
+    right: Ring,
+}
+
+// Safety: All fields use atomic operations. No mutable aliasing occurs.
+unsafe impl Send for VisualizerBuffer {}
+unsafe impl Sync for VisualizerBuffer {}
+
+impl VisualizerBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            mixed: Ring::new(capacity),
+            left: Ring::new(capacity / 2),
+            right: Ring::new(capacity / 2),
+        }
+    }
+    
+    /// Add a sample to the buffer (called from audio thread, lock-free).
+    pub fn push(&self, sample: f32) {
+        self.mixed.push(sample);
+    }
+
+    /// Add a sample and file it under its channel. Channel 0 is left, 1 is
+    /// right; a mono source (`channels == 1`) feeds both sides.
+    pub fn push_channel(&self, sample: f32, channel: u16, channels: u16) {
+        self.push(sample);
+        match (channels, channel) {
+            (1, _) => {
+                self.left.push(sample);
+                self.right.push(sample);
+            }
+            (_, 0) => self.left.push(sample),
+            (_, 1) => self.right.push(sample),
+            _ => {}
+        }
+    }
     
     /// Get a copy of current samples for visualization.
     pub fn get_samples(&self) -> Vec<f32> {
-        let total = self.write_pos.load(Ordering::Relaxed);
-        let len = (total.min(self.capacity as u64)) as usize;
-        let mut result = Vec::with_capacity(len);
-        
-        let start = if total > self.capacity as u64 { total - self.capacity as u64 } else { 0 };
-        for i in start..total {
-            let idx = (i % self.capacity as u64) as usize;
-            result.push(f32::from_bits(self.samples[idx].load(Ordering::Relaxed)));
-        }
-        result
+        self.mixed.snapshot()
+    }
+
+    /// Get copies of the recent left and right channel samples.
+    pub fn get_channel_samples(&self) -> (Vec<f32>, Vec<f32>) {
+        (self.left.snapshot(), self.right.snapshot())
     }
     
     /// Clear the buffer.
     pub fn clear(&self) {
-        self.write_pos.store(0, Ordering::Relaxed);
+        self.mixed.clear();
+        self.left.clear();
+        self.right.clear();
     }
 }
 
@@ -106,5 +169,21 @@ mod tests {
         let samples = buf.get_samples();
         assert_eq!(samples.len(), 0, "get_samples after clear should return empty");
     }
-}
 
+    #[test]
+    fn push_channel_splits_left_and_right() {
+        let buf = VisualizerBuffer::new(16);
+        for (i, s) in [0.1, -0.1, 0.2, -0.2].into_iter().enumerate() {
+            buf.push_channel(s, (i % 2) as u16, 2);
+        }
+        let (left, right) = buf.get_channel_samples();
+        assert_eq!(left, vec![0.1, 0.2]);
+        assert_eq!(right, vec![-0.1, -0.2]);
+        assert_eq!(buf.get_samples().len(), 4);
+
+        // Mono feeds both sides.
+        buf.clear();
+        buf.push_channel(0.5, 0, 1);
+        assert_eq!(buf.get_channel_samples(), (vec![0.5], vec![0.5]));
+    }
+}
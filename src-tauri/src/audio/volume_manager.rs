@@ -3,53 +3,175 @@
 //! Pure data struct — no audio resources — inherently Send + Sync.
 
 use log::info;
+use super::normalize::NormalizeMode;
+use crate::replaygain::{ReplayGainMode, StoredGains};
+
+/// Lowest ReplayGain multiplier (-20 dB).
+pub const MIN_REPLAYGAIN_MULTIPLIER: f32 = 0.1;
+/// Largest ReplayGain boost, before peak limiting.
+pub const MAX_REPLAYGAIN_DB: f32 = 12.0;
+/// Largest manual per-track gain adjustment, in either direction.
+pub const MAX_TRACK_GAIN_OVERRIDE_DB: f32 = 12.0;
+/// Largest ReplayGain preamp, in either direction.
+pub const MAX_REPLAYGAIN_PREAMP_DB: f32 = 15.0;
+
+/// Everything needed to set a track's gain stage when it is loaded.
+#[derive(Debug, Clone, Default)]
+pub struct TrackGains {
+    /// Stored ReplayGain data; the ReplayGain mode picks which to apply.
+    pub stored: StoredGains,
+    /// Loudness target the picked gain is rebased to.
+    pub target_lufs: f64,
+    /// Manual per-track adjustment in dB.
+    pub override_db: Option<f32>,
+}
 
 /// Volume state including ReplayGain and balance adjustments.
+///
+/// User volume drives the sink; ReplayGain is a separate gain stage applied
+/// to samples in the DSP chain, so boosts above unity are not lost to the
+/// sink's 0–1 volume range.
 pub struct VolumeManager {
     /// User-set volume (0.0–1.0)
     pub last_volume: f32,
-    /// ReplayGain multiplier (1.0 = no change)
+    /// Muted output; `last_volume` is kept so unmuting restores it
+    pub muted: bool,
+    /// ReplayGain multiplier applied in the DSP chain (1.0 = no change)
     pub replaygain_multiplier: f32,
+    /// Known linear peak of the current track, limiting the combined gain
+    pub replaygain_peak: Option<f32>,
+    /// Manual per-track adjustment in dB, applied on top of ReplayGain
+    pub track_gain_override_db: f32,
     /// Stereo balance (-1.0 = left, 0.0 = center, 1.0 = right)
     pub balance: f32,
+    /// Which normalization is active; ReplayGain only applies in its own mode
+    pub normalize_mode: NormalizeMode,
+    /// Which stored gain a newly loaded track gets
+    pub replaygain_mode: ReplayGainMode,
+    /// Added to whichever stored gain the mode picks
+    pub replaygain_preamp_db: f32,
 }
 
 impl VolumeManager {
     pub fn new() -> Self {
         Self {
             last_volume: 1.0,
+            muted: false,
             replaygain_multiplier: 1.0,
+            replaygain_peak: None,
+            track_gain_override_db: 0.0,
             balance: 0.0,
+            normalize_mode: NormalizeMode::default(),
+            replaygain_mode: ReplayGainMode::default(),
+            replaygain_preamp_db: 0.0,
         }
     }
 
-    /// Calculate the effective volume (user volume × ReplayGain), clamped to [0, 1].
-    pub fn effective_volume(&self) -> f32 {
-        (self.last_volume * self.replaygain_multiplier).clamp(0.0, 1.0)
+    /// Volume to apply to the sink: the user volume alone, or silence while muted.
+    pub fn sink_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.last_volume
+        }
     }
 
-    /// Set user volume and return the effective volume to apply to the sink.
+    /// Set user volume and return the volume to apply to the sink. While
+    /// muted the new level is only remembered for unmuting.
     pub fn set_volume(&mut self, volume: f32) -> f32 {
         self.last_volume = volume.clamp(0.0, 1.0);
-        self.effective_volume()
+        self.sink_volume()
     }
 
-    /// Set ReplayGain in dB and return the effective volume to apply.
-    pub fn set_replaygain(&mut self, gain_db: f32, preamp_db: f32) -> f32 {
+    /// Mute or unmute and return the volume to apply to the sink.
+    pub fn set_muted(&mut self, muted: bool) -> f32 {
+        self.muted = muted;
+        info!("Muted: {}", muted);
+        self.sink_volume()
+    }
+
+    /// Multiplier for the DSP gain stage: ReplayGain times the track override.
+    /// With a known peak the product is capped like ReplayGain alone, so the
+    /// override can't push a peak-limited track back over full scale.
+    pub fn dsp_gain(&self) -> f32 {
+        let override_gain = 10_f32.powf(self.track_gain_override_db / 20.0);
+        if self.normalize_mode != NormalizeMode::ReplayGain {
+            return override_gain;
+        }
+        let gain = self.replaygain_multiplier * override_gain;
+        match self.replaygain_peak {
+            Some(peak) => gain.min((1.0 / peak).max(1.0)),
+            None => gain,
+        }
+    }
+
+    /// Switch normalization mode and return the multiplier for the DSP stage.
+    pub fn set_normalize_mode(&mut self, mode: NormalizeMode) -> f32 {
+        self.normalize_mode = mode;
+        info!("Normalization mode: {:?}", mode);
+        self.dsp_gain()
+    }
+
+    /// Set ReplayGain in dB and return the multiplier for the DSP gain stage.
+    ///
+    /// With a known `peak` (linear, 0–1) the multiplier is capped so the
+    /// loudest sample reaches at most full scale instead of clipping.
+    pub fn set_replaygain(&mut self, gain_db: f32, preamp_db: f32, peak: Option<f32>) -> f32 {
         let total_gain_db = gain_db + preamp_db;
-        let multiplier = 10_f32.powf(total_gain_db / 20.0);
-        self.replaygain_multiplier = multiplier.clamp(0.1, 2.0);
+        let mut multiplier = 10_f32
+            .powf(total_gain_db.min(MAX_REPLAYGAIN_DB) / 20.0)
+            .max(MIN_REPLAYGAIN_MULTIPLIER);
+        self.replaygain_peak = peak.filter(|p| p.is_finite() && *p > 0.0);
+        if let Some(peak) = self.replaygain_peak {
+            multiplier = multiplier.min((1.0 / peak).max(1.0));
+        }
+        self.replaygain_multiplier = multiplier;
         info!(
-            "ReplayGain: {}dB + {}dB preamp = {}dB (multiplier: {:.3})",
-            gain_db, preamp_db, total_gain_db, self.replaygain_multiplier
+            "ReplayGain: {}dB + {}dB preamp = {}dB (multiplier: {:.3}, peak: {:?})",
+            gain_db, preamp_db, total_gain_db, self.replaygain_multiplier, peak
         );
-        self.effective_volume()
+        self.dsp_gain()
     }
 
-    /// Clear ReplayGain and return the effective volume to apply.
+    /// Clear ReplayGain and return the (unity) multiplier for the DSP stage.
     pub fn clear_replaygain(&mut self) -> f32 {
         self.replaygain_multiplier = 1.0;
-        self.effective_volume()
+        self.replaygain_peak = None;
+        self.dsp_gain()
+    }
+
+    /// Apply a newly loaded track's gains and return the multiplier for the
+    /// DSP stage. ReplayGain is cleared when the mode finds nothing to apply.
+    pub fn apply_track_gains(&mut self, gains: &TrackGains) -> f32 {
+        match self.replaygain_mode.select(&gains.stored, gains.target_lufs) {
+            Some(picked) => self.set_replaygain(
+                picked.gain_db as f32,
+                self.replaygain_preamp_db,
+                Some(picked.peak as f32),
+            ),
+            None => self.clear_replaygain(),
+        };
+        self.set_track_gain_override(gains.override_db)
+    }
+
+    /// Set the preamp later loads add to their ReplayGain.
+    pub fn set_replaygain_preamp(&mut self, preamp_db: f32) {
+        self.replaygain_preamp_db = if preamp_db.is_finite() {
+            preamp_db.clamp(-MAX_REPLAYGAIN_PREAMP_DB, MAX_REPLAYGAIN_PREAMP_DB)
+        } else {
+            0.0
+        };
+        info!("ReplayGain preamp: {}dB", self.replaygain_preamp_db);
+    }
+
+    /// Set the current track's manual gain override (None = no adjustment)
+    /// and return the multiplier for the DSP stage.
+    pub fn set_track_gain_override(&mut self, gain_db: Option<f32>) -> f32 {
+        self.track_gain_override_db = gain_db
+            .filter(|g| g.is_finite())
+            .unwrap_or(0.0)
+            .clamp(-MAX_TRACK_GAIN_OVERRIDE_DB, MAX_TRACK_GAIN_OVERRIDE_DB);
+        self.dsp_gain()
     }
 
     /// Set stereo balance.
@@ -72,9 +194,9 @@ mod tests {
     }
 
     #[test]
-    fn effective_volume_defaults_to_full() {
+    fn sink_volume_defaults_to_full() {
         let vm = VolumeManager::new();
-        assert_eq!(vm.effective_volume(), 1.0);
+        assert_eq!(vm.sink_volume(), 1.0);
     }
 
     #[test]
@@ -94,39 +216,103 @@ mod tests {
     }
 
     #[test]
-    fn replaygain_multiplier_affects_effective_volume() {
+    fn replaygain_leaves_sink_volume_alone() {
         let mut vm = VolumeManager::new();
         vm.set_volume(0.8);
 
-        // +6 dB ≈ 2× multiplier → effective = 0.8 * 2.0 = 1.0 (clamped)
-        let eff = vm.set_replaygain(6.0, 0.0);
-        assert!(eff <= 1.0);
-        assert!(vm.replaygain_multiplier > 1.0);
+        // +6 dB ≈ 2× multiplier, realized in the DSP stage rather than by
+        // pushing the sink past its ceiling.
+        let mult = vm.set_replaygain(6.0, 0.0, None);
+        assert!((mult - 1.995).abs() < 0.01);
+        assert_eq!(vm.sink_volume(), 0.8);
     }
 
     #[test]
     fn replaygain_multiplier_is_clamped() {
         let mut vm = VolumeManager::new();
 
-        // Extreme positive gain — code clamps multiplier at 2.0 (≤ +6 dB)
-        vm.set_replaygain(100.0, 0.0);
-        assert!(vm.replaygain_multiplier <= 2.0);
+        vm.set_replaygain(100.0, 0.0, None);
+        let max = 10_f32.powf(MAX_REPLAYGAIN_DB / 20.0);
+        assert!((vm.replaygain_multiplier - max).abs() < 0.001);
 
         // Extreme negative gain
-        vm.set_replaygain(-100.0, 0.0);
-        assert!(vm.replaygain_multiplier >= 0.1);
+        vm.set_replaygain(-100.0, 0.0, None);
+        assert!(vm.replaygain_multiplier >= MIN_REPLAYGAIN_MULTIPLIER);
+    }
+
+    #[test]
+    fn track_override_stacks_on_replaygain() {
+        let mut vm = VolumeManager::new();
+        vm.set_replaygain(-6.0, 0.0, None);
+
+        let mult = vm.set_track_gain_override(Some(6.0));
+        assert!((mult - 1.0).abs() < 0.01);
+
+        // Clearing the override leaves ReplayGain in place.
+        let mult = vm.set_track_gain_override(None);
+        assert!((mult - vm.replaygain_multiplier).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn track_override_is_peak_limited_with_replaygain() {
+        let mut vm = VolumeManager::new();
+        vm.normalize_mode = NormalizeMode::ReplayGain;
+
+        // ReplayGain is already held to 1.25× by the 0.8 peak; a +6 dB
+        // override on top may not take it past full scale.
+        vm.set_replaygain(6.0, 0.0, Some(0.8));
+        let mult = vm.set_track_gain_override(Some(6.0));
+        assert!((mult - 1.25).abs() < 0.001);
+
+        // A ReplayGain cut leaves room for the override, up to the peak.
+        vm.set_replaygain(-3.0, 0.0, Some(0.5));
+        let mult = vm.set_track_gain_override(Some(12.0));
+        assert!((mult - 2.0).abs() < 0.001);
+
+        // Cuts are never raised, and without a peak nothing is capped.
+        assert!(vm.set_track_gain_override(Some(-12.0)) < 0.2);
+        vm.clear_replaygain();
+        assert!((vm.set_track_gain_override(Some(6.0)) - 1.995).abs() < 0.01);
+    }
+
+    #[test]
+    fn replaygain_is_bypassed_outside_its_mode() {
+        let mut vm = VolumeManager::new();
+        vm.set_replaygain(6.0, 0.0, None);
+
+        assert!((vm.set_normalize_mode(NormalizeMode::Peak) - 1.0).abs() < f32::EPSILON);
+        assert!((vm.set_normalize_mode(NormalizeMode::Off) - 1.0).abs() < f32::EPSILON);
+        assert!((vm.set_normalize_mode(NormalizeMode::ReplayGain) - 1.995).abs() < 0.01);
+    }
+
+    #[test]
+    fn replaygain_boost_is_peak_limited() {
+        let mut vm = VolumeManager::new();
+
+        // Peak 0.25 allows up to 4× (+12 dB); +6 dB fits.
+        let mult = vm.set_replaygain(6.0, 0.0, Some(0.25));
+        assert!((mult - 1.995).abs() < 0.01);
+
+        // Peak 0.8 only allows 1.25× before clipping.
+        let mult = vm.set_replaygain(6.0, 0.0, Some(0.8));
+        assert!((mult - 1.25).abs() < 0.001);
+
+        // Cuts are never raised by the peak limit.
+        let mult = vm.set_replaygain(-6.0, 0.0, Some(0.99));
+        assert!(mult < 0.51);
     }
 
     #[test]
     fn clear_replaygain_resets_multiplier() {
         let mut vm = VolumeManager::new();
         vm.set_volume(0.7);
-        vm.set_replaygain(3.0, 0.0);
+        vm.set_replaygain(3.0, 0.0, None);
         assert_ne!(vm.replaygain_multiplier, 1.0);
 
-        let eff = vm.clear_replaygain();
+        let mult = vm.clear_replaygain();
         assert_eq!(vm.replaygain_multiplier, 1.0);
-        assert_eq!(eff, 0.7);
+        assert_eq!(mult, 1.0);
+        assert_eq!(vm.sink_volume(), 0.7);
     }
 
     #[test]
@@ -148,15 +334,46 @@ mod tests {
         let mut vm = VolumeManager::new();
         vm.set_volume(1.0);
 
-        vm.set_replaygain(3.0, 2.0);
+        vm.set_replaygain(3.0, 2.0, None);
         let mult_combined = vm.replaygain_multiplier;
 
         let mut vm2 = VolumeManager::new();
         vm2.set_volume(1.0);
-        vm2.set_replaygain(5.0, 0.0);
+        vm2.set_replaygain(5.0, 0.0, None);
         let mult_single = vm2.replaygain_multiplier;
 
         assert!((mult_combined - mult_single).abs() < 0.001,
             "gain+preamp should equal the same total dB");
     }
+
+    #[test]
+    fn loaded_track_gain_includes_the_configured_preamp() {
+        use crate::replaygain::ReplayGainData;
+
+        let gains = TrackGains {
+            stored: StoredGains {
+                track: Some(ReplayGainData {
+                    track_gain: -6.0,
+                    track_peak: 0.5,
+                    loudness: -12.0,
+                    target_lufs: -18.0,
+                }),
+                album: None,
+            },
+            target_lufs: -18.0,
+            override_db: None,
+        };
+        let mut vm = VolumeManager::new();
+        vm.normalize_mode = NormalizeMode::ReplayGain;
+        vm.replaygain_mode = ReplayGainMode::Track;
+
+        vm.set_replaygain_preamp(3.0);
+        let mult = vm.apply_track_gains(&gains);
+        assert!((mult - 10_f32.powf(-3.0 / 20.0)).abs() < 0.001, "-6 dB + 3 dB preamp");
+
+        vm.set_replaygain_preamp(40.0);
+        assert_eq!(vm.replaygain_preamp_db, MAX_REPLAYGAIN_PREAMP_DB);
+        vm.set_replaygain_preamp(f32::NAN);
+        assert_eq!(vm.replaygain_preamp_db, 0.0);
+    }
 }
@@ -0,0 +1,172 @@
+//! Tempo (BPM) estimation for the library.
+//!
+//! The mono signal is reduced to an onset envelope by spectral flux: every
+//! ~5 ms the log magnitude spectrum of a ~23 ms frame is compared with the
+//! previous one and the rises are summed. A drum hit lifts many bins at
+//! once, while sustained notes (and the beating between them) leave their
+//! bins steady. The envelope's autocorrelation is then searched for the
+//! strongest period between [`MIN_BPM`] and [`MAX_BPM`], with a mild
+//! preference for tempos near 120 BPM to settle the usual half/double-time
+//! ambiguity.
+//!
+//! The result is stored on the `tracks` row so smart playlists can filter
+//! and sort by it.
+
+use crate::database::Database;
+use crate::pcm_decode;
+use log::info;
+use rusqlite::params;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+pub const MIN_BPM: f64 = 60.0;
+pub const MAX_BPM: f64 = 200.0;
+
+/// How much audio from the start of the file is analysed.
+pub const BPM_ANALYSIS_SECONDS: f64 = 300.0;
+
+/// Onset envelope samples per second.
+const ENVELOPE_RATE_HZ: u32 = 200;
+
+/// Tempo the octave preference is centred on.
+const PREFERRED_BPM: f64 = 120.0;
+
+/// Incremental tempo estimator fed with mono samples.
+pub struct BpmDetector {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    hop: usize,
+    envelope_rate: f64,
+    max_samples: usize,
+    pending: Vec<f32>,
+    consumed: usize,
+    prev_spectrum: Option<Vec<f32>>,
+    envelope: Vec<f64>,
+}
+
+impl BpmDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        let frame_len = ((sample_rate as f64 * 0.023) as usize).next_power_of_two().max(128);
+        let hop = (sample_rate / ENVELOPE_RATE_HZ).max(1) as usize;
+
+        let window = (0..frame_len)
+            .map(|i| {
+                let x = std::f32::consts::PI * 2.0 * i as f32 / (frame_len - 1) as f32;
+                0.5 - 0.5 * x.cos()
+            })
+            .collect();
+
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(frame_len),
+            window,
+            hop,
+            envelope_rate: sample_rate as f64 / hop as f64,
+            max_samples: (BPM_ANALYSIS_SECONDS * sample_rate as f64) as usize,
+            pending: Vec::with_capacity(frame_len * 2),
+            consumed: 0,
+            prev_spectrum: None,
+            envelope: Vec::new(),
+        }
+    }
+
+    /// True once enough audio has been seen; further samples are ignored.
+    pub fn is_full(&self) -> bool {
+        self.consumed >= self.max_samples
+    }
+
+    /// Feed mono samples. Anything past the analysis window is dropped.
+    pub fn push(&mut self, samples: &[f32]) {
+        let take = samples.len().min(self.max_samples.saturating_sub(self.consumed));
+        self.pending.extend_from_slice(&samples[..take]);
+        self.consumed += take;
+
+        let frame_len = self.window.len();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            let spectrum = self.log_spectrum(start);
+            let flux = self.prev_spectrum.as_ref().map_or(0.0, |prev| {
+                spectrum.iter().zip(prev).map(|(cur, prev)| (cur - prev).max(0.0) as f64).sum()
+            });
+            self.envelope.push(flux);
+            self.prev_spectrum = Some(spectrum);
+            start += self.hop;
+        }
+        self.pending.drain(..start);
+    }
+
+    /// The estimated tempo in BPM.
+    pub fn finish(self) -> Result<f64, String> {
+        let min_lag = (self.envelope_rate * 60.0 / MAX_BPM).floor().max(1.0) as usize;
+        let max_lag = (self.envelope_rate * 60.0 / MIN_BPM).ceil() as usize;
+        // A few beats at the slowest tempo are needed to see a period at all.
+        if self.envelope.len() < max_lag * 4 {
+            return Err("Audio is too short to estimate tempo".to_string());
+        }
+
+        let mean = self.envelope.iter().sum::<f64>() / self.envelope.len() as f64;
+        let env: Vec<f64> = self.envelope.iter().map(|v| v - mean).collect();
+        let autocorrelation = |lag: usize| -> f64 {
+            env.iter().zip(&env[lag..]).map(|(a, b)| a * b).sum::<f64>() / env.len() as f64
+        };
+
+        let scores: Vec<f64> = (min_lag - 1..=max_lag + 1).map(autocorrelation).collect();
+        let score_at = |lag: usize| scores[lag + 1 - min_lag];
+
+        let (best_lag, best_weighted) = (min_lag..=max_lag)
+            .map(|lag| {
+                let bpm = 60.0 * self.envelope_rate / lag as f64;
+                let octaves = (bpm / PREFERRED_BPM).log2();
+                (lag, score_at(lag) * (-0.5 * octaves * octaves).exp())
+            })
+            .fold((min_lag, f64::MIN), |best, cur| if cur.1 > best.1 { cur } else { best });
+        if best_weighted <= 0.0 {
+            return Err("No steady beat found".to_string());
+        }
+
+        // Parabolic interpolation around the peak for sub-hop precision.
+        let (prev, peak, next) = (score_at(best_lag - 1), score_at(best_lag), score_at(best_lag + 1));
+        let curvature = prev - 2.0 * peak + next;
+        let offset = if curvature < 0.0 { (0.5 * (prev - next) / curvature).clamp(-0.5, 0.5) } else { 0.0 };
+
+        Ok(60.0 * self.envelope_rate / (best_lag as f64 + offset))
+    }
+
+    /// Log-compressed magnitudes of the frame starting at `start`.
+    fn log_spectrum(&self, start: usize) -> Vec<f32> {
+        let mut buf: Vec<Complex<f32>> = self.pending[start..start + self.window.len()]
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buf);
+        buf[..buf.len() / 2].iter().map(|c| c.norm().ln_1p()).collect()
+    }
+}
+
+/// Decode the start of `path` and estimate its tempo.
+pub fn detect_file_bpm(path: &str) -> Result<f64, String> {
+    let mut detector: Option<BpmDetector> = None;
+    pcm_decode::decode_file(path, |block| {
+        let detector = detector.get_or_insert_with(|| BpmDetector::new(block.sample_rate));
+        detector.push(block.mono);
+        !detector.is_full()
+    })?;
+    detector.ok_or_else(|| "No audio decoded".to_string())?.finish()
+}
+
+/// Store `bpm` (rounded to 0.1) on the track at `path`.
+pub fn store_bpm(db: &Database, path: &str, bpm: f64) -> Result<f64, String> {
+    let bpm = (bpm * 10.0).round() / 10.0;
+    db.conn()
+        .execute("UPDATE tracks SET bpm = ?1 WHERE path = ?2", params![bpm, path])
+        .map_err(|e| format!("Failed to store BPM: {}", e))?;
+    Ok(bpm)
+}
+
+/// Estimate the tempo of the track at `path` and store it.
+pub fn analyze_track_bpm(db: &Database, path: &str) -> Result<f64, String> {
+    info!("Estimating BPM: {}", path);
+    let bpm = store_bpm(db, path, detect_file_bpm(path)?)?;
+    info!("BPM of {}: {:.1}", path, bpm);
+    Ok(bpm)
+}
@@ -0,0 +1,275 @@
+//! Embedded chapter markers for podcasts and audiobooks.
+//!
+//! Neither lofty nor symphonia expose chapters for the formats VPlayer
+//! plays, so the three common encodings are read directly:
+//!
+//! - ID3v2 `CHAP` frames (MP3, AAC with an ID3 header)
+//! - the Nero `moov/udta/chpl` atom (M4A/M4B)
+//! - `CHAPTERnnn` / `CHAPTERnnnNAME` Vorbis comments (FLAC, Ogg, Opus)
+//!
+//! Files without chapters yield an empty list.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use lofty::{ItemKey, TagType, TaggedFileExt};
+use serde::Serialize;
+
+/// Going to the previous chapter this far into the current one restarts it
+/// instead, like the previous-track button.
+pub const PREVIOUS_CHAPTER_RESTART_SECS: f64 = 3.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    /// Offset from the start of the file, in seconds.
+    pub start: f64,
+    pub title: String,
+}
+
+/// Chapters embedded in the file at `path`, sorted by start time.
+pub fn read_chapters(path: &Path) -> Result<Vec<Chapter>, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut magic = [0u8; 8];
+    let read = file.read(&mut magic).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+
+    let mut chapters = if read >= 3 && &magic[..3] == b"ID3" {
+        id3v2_chapters(&mut file).map_err(|e| e.to_string())?
+    } else if read == 8 && &magic[4..8] == b"ftyp" {
+        mp4_chapters(&mut file).map_err(|e| e.to_string())?
+    } else {
+        vorbis_chapters(path)?
+    };
+
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(chapters)
+}
+
+/// Start of the first chapter after `position`, if any.
+pub fn next_chapter(chapters: &[Chapter], position: f64) -> Option<&Chapter> {
+    // A small tolerance so landing exactly on a marker doesn't stick there.
+    chapters.iter().find(|c| c.start > position + 0.5)
+}
+
+/// The chapter to go back to from `position`: the start of the current one,
+/// or the one before it when `position` is near the current start.
+pub fn previous_chapter(chapters: &[Chapter], position: f64) -> Option<&Chapter> {
+    let current = chapters.iter().rposition(|c| c.start <= position)?;
+    if position - chapters[current].start > PREVIOUS_CHAPTER_RESTART_SECS || current == 0 {
+        Some(&chapters[current])
+    } else {
+        Some(&chapters[current - 1])
+    }
+}
+
+// ── ID3v2 ──────────────────────────────────────────────────────────
+
+fn synchsafe(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |acc, b| (acc << 7) | (*b as usize & 0x7f))
+}
+
+fn be_u32(bytes: &[u8]) -> usize {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+}
+
+fn id3v2_chapters(file: &mut File) -> std::io::Result<Vec<Chapter>> {
+    let mut header = [0u8; 10];
+    file.read_exact(&mut header)?;
+    let major = header[3];
+    // ID3v2.2 has no CHAP frame.
+    if major != 3 && major != 4 {
+        return Ok(Vec::new());
+    }
+    // A corrupt size can't make us allocate more than the file holds.
+    let available = file.metadata()?.len().saturating_sub(10);
+    let mut body = vec![0u8; (synchsafe(&header[6..10]) as u64).min(available) as usize];
+    file.read_exact(&mut body)?;
+
+    let mut offset = 0;
+    if header[5] & 0x40 != 0 && body.len() >= 4 {
+        // Extended header: v2.4 counts its own size, v2.3 doesn't.
+        offset = if major == 4 { synchsafe(&body[..4]) } else { be_u32(&body[..4]) + 4 };
+    }
+
+    let mut chapters = Vec::new();
+    for (id, frame) in id3v2_frames(&body[offset.min(body.len())..], major) {
+        if id == b"CHAP" {
+            if let Some(chapter) = parse_chap(frame, major) {
+                chapters.push(chapter);
+            }
+        }
+    }
+    Ok(chapters)
+}
+
+/// `(frame id, frame body)` pairs up to the padding or the end of `data`.
+fn id3v2_frames(mut data: &[u8], major: u8) -> Vec<(&[u8], &[u8])> {
+    let mut frames = Vec::new();
+    while data.len() >= 10 && data[0] != 0 {
+        let size = if major == 4 { synchsafe(&data[4..8]) } else { be_u32(&data[4..8]) };
+        let end = (10 + size).min(data.len());
+        frames.push((&data[..4], &data[10..end]));
+        data = &data[end..];
+    }
+    frames
+}
+
+fn parse_chap(frame: &[u8], major: u8) -> Option<Chapter> {
+    let id_end = frame.iter().position(|b| *b == 0)?;
+    let times = frame.get(id_end + 1..id_end + 17)?;
+    let start_ms = be_u32(&times[..4]);
+    let element_id = String::from_utf8_lossy(&frame[..id_end]).to_string();
+
+    let title = id3v2_frames(&frame[id_end + 17..], major)
+        .into_iter()
+        .find(|(id, _)| *id == b"TIT2")
+        .and_then(|(_, text)| decode_id3_text(text))
+        .filter(|t| !t.is_empty())
+        .unwrap_or(element_id);
+
+    Some(Chapter { start: start_ms as f64 / 1000.0, title })
+}
+
+fn decode_id3_text(frame: &[u8]) -> Option<String> {
+    let (encoding, text) = frame.split_first()?;
+    let decoded = match encoding {
+        0 => text.iter().map(|b| *b as char).collect(),
+        1 | 2 => {
+            let mut units: Vec<u16> = text.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            match units.first() {
+                Some(0xfffe) => {
+                    units.remove(0);
+                    units.iter_mut().for_each(|u| *u = u.swap_bytes());
+                }
+                Some(0xfeff) => {
+                    units.remove(0);
+                }
+                _ => {}
+            }
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).to_string(),
+    };
+    Some(decoded.trim_end_matches('\0').to_string())
+}
+
+// ── MP4 ────────────────────────────────────────────────────────────
+
+/// Payload range of the first `name` box among the boxes between `start`
+/// and `end`.
+fn mp4_find_box(file: &mut File, start: u64, end: u64, name: &[u8; 4]) -> std::io::Result<Option<(u64, u64)>> {
+    let mut pos = start;
+    while pos.checked_add(8).is_some_and(|header_end| header_end <= end) {
+        file.seek(SeekFrom::Start(pos))?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let mut header_len = 8;
+        let mut size = be_u32(&header[..4]) as u64;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            file.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        let box_end = match pos.checked_add(size) {
+            Some(box_end) if size >= header_len && box_end <= end => box_end,
+            _ => break,
+        };
+        if &header[4..8] == name {
+            return Ok(Some((pos + header_len, box_end)));
+        }
+        pos = box_end;
+    }
+    Ok(None)
+}
+
+fn mp4_chapters(file: &mut File) -> std::io::Result<Vec<Chapter>> {
+    let len = file.metadata()?.len();
+    let Some((moov, moov_end)) = mp4_find_box(file, 0, len, b"moov")? else {
+        return Ok(Vec::new());
+    };
+    let Some((udta, udta_end)) = mp4_find_box(file, moov, moov_end, b"udta")? else {
+        return Ok(Vec::new());
+    };
+    let Some((chpl, chpl_end)) = mp4_find_box(file, udta, udta_end, b"chpl")? else {
+        return Ok(Vec::new());
+    };
+
+    file.seek(SeekFrom::Start(chpl))?;
+    let mut data = vec![0u8; (chpl_end - chpl) as usize];
+    file.read_exact(&mut data)?;
+    Ok(parse_chpl(&data))
+}
+
+/// Nero chapter list: version/flags, a reserved word in version 1, a count,
+/// then (start in 100 ns units, length-prefixed UTF-8 title) per chapter.
+fn parse_chpl(data: &[u8]) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let Some(version) = data.first() else {
+        return chapters;
+    };
+    let mut pos = if *version == 0 { 4 } else { 8 };
+    let Some(count) = data.get(pos) else {
+        return chapters;
+    };
+    pos += 1;
+
+    for _ in 0..*count {
+        let Some(start) = data.get(pos..pos + 8) else { break };
+        let start = u64::from_be_bytes(start.try_into().unwrap_or_default());
+        let Some(title_len) = data.get(pos + 8) else { break };
+        let Some(title) = data.get(pos + 9..pos + 9 + *title_len as usize) else { break };
+        chapters.push(Chapter {
+            start: start as f64 / 10_000_000.0,
+            title: String::from_utf8_lossy(title).to_string(),
+        });
+        pos += 9 + *title_len as usize;
+    }
+    chapters
+}
+
+// ── Vorbis comments ────────────────────────────────────────────────
+
+fn vorbis_chapters(path: &Path) -> Result<Vec<Chapter>, String> {
+    let tagged = lofty::read_from_path(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let Some(tag) = tagged.tag(TagType::VorbisComments) else {
+        return Ok(Vec::new());
+    };
+
+    let items: Vec<(String, &str)> = tag
+        .items()
+        .filter_map(|item| match (item.key(), item.value().text()) {
+            (ItemKey::Unknown(key), Some(value)) => Some((key.to_uppercase(), value)),
+            _ => None,
+        })
+        .collect();
+    let title_for = |key: &str| {
+        let name_key = format!("{}NAME", key);
+        items.iter().find(|(k, _)| *k == name_key).map(|(_, v)| v.to_string())
+    };
+
+    Ok(items
+        .iter()
+        .filter(|(key, _)| key.strip_prefix("CHAPTER").is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())))
+        .filter_map(|(key, value)| {
+            let start = parse_timestamp(value)?;
+            Some(Chapter { start, title: title_for(key).unwrap_or_else(|| key.clone()) })
+        })
+        .collect())
+}
+
+/// `HH:MM:SS(.fff)` as used by the Vorbis chapter extension.
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.trim().split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
@@ -1,10 +1,45 @@
 // Audio playback commands
 use crate::AppState;
-use crate::audio::{AudioPlayer, AudioDevice};
+use crate::chapters::{self, Chapter};
+use crate::crossfade::{self, CrossfadePolicy, CROSSFADE_POLICY_KEY};
+use crate::effects::EffectsConfig;
+use crate::fade_durations::FadeDurations;
+use crate::audio::{AudioPlayer, AudioDevice, NormalizeMode};
+use crate::audio::device::{self, AudioConfig, DeviceTestResult, OutputFormat, AUDIO_CONFIG_KEY};
+use crate::audio::device_volume;
+use crate::audio::normalize::NORMALIZE_MODE_KEY;
+use crate::audio::progress::PROGRESS_INTERVAL_KEY;
+use crate::audio::resume::RESUME_PER_TRACK_KEY;
+use crate::audio::silence::{SilenceTrimConfig, SILENCE_TRIM_KEY};
+use crate::audio::speed::{self, ContentType, PlaybackSpeeds, PLAYBACK_SPEED_KEY};
+use crate::audio::volume_manager::TrackGains;
+use crate::track_end::{self, SKIP_UNAVAILABLE_KEY};
+use crate::replaygain::{active_target_lufs, get_stored_gains, playback_gain_db};
 use crate::error::{AppError, AppResult};
+use crate::genre_eq::{EqBandsChanged, EQ_BANDS_CHANGED_EVENT};
 use crate::validation;
-use log::info;
+use log::{info, warn};
 use serde::Serialize;
+use tauri::Emitter;
+
+/// The stored ReplayGain data and manual override for the track at `path`.
+/// Lookup errors are logged and treated as "nothing stored" so they never
+/// block playback.
+pub(crate) fn track_gains(state: &AppState, path: &str) -> TrackGains {
+    let override_db = match state.db.get_track_gain_override(path) {
+        Ok(gain_db) => gain_db.map(|g| g as f32),
+        Err(e) => {
+            warn!("Failed to read gain override for {}: {}", path, e);
+            None
+        }
+    };
+    let stored = get_stored_gains(&state.db.conn, path).unwrap_or_else(|e| {
+        warn!("Failed to read ReplayGain for {}: {}", path, e);
+        Default::default()
+    });
+    let device = state.player.output_device_name();
+    TrackGains { stored, target_lufs: active_target_lufs(&state.db, device.as_deref()), override_db }
+}
 
 /// Combined audio health status — avoids multiple IPC round-trips.
 #[derive(Debug, Clone, Serialize)]
@@ -28,16 +63,42 @@ pub fn get_audio_health(state: tauri::State<AppState>) -> AudioHealthStatus {
     }
 }
 
+/// Effects with the EQ the genre mapping picks for `path`, or `None` to
+/// keep the current ones.
+fn genre_effects(state: &AppState, path: &str) -> Option<EffectsConfig> {
+    let mut config = state.player.get_effects();
+    config.eq_bands = state.genre_eq.bands_for_track(&state.db, path, &config.eq_bands)?;
+    Some(config)
+}
+
+/// Apply the genre mapping's effects and tell the equalizer UI.
+fn apply_genre_effects(app: &tauri::AppHandle, player: &AudioPlayer, config: EffectsConfig) {
+    let eq_bands = config.eq_bands.clone();
+    player.set_effects(config);
+    if let Err(e) = app.emit(EQ_BANDS_CHANGED_EVENT, EqBandsChanged { eq_bands }) {
+        warn!("Failed to emit {}: {}", EQ_BANDS_CHANGED_EVENT, e);
+    }
+}
+
 #[tauri::command]
-pub async fn load_track(path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
+pub async fn load_track(path: String, app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> AppResult<()> {
     info!("Loading track: {}", path);
     // Validate path exists before loading
     validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
     
+    let gains = track_gains(&state, &path);
+    let playback_speed = speed::speed_for(&state.db, &path);
+    let genre_effects = genre_effects(&state, &path);
+
     // Run blocking audio operations off the main IPC thread
     let player = state.player.clone();
     tauri::async_runtime::spawn_blocking(move || {
-        player.load(path).map_err(|e| AppError::Audio(e.to_string()))
+        player.load_with_gains(path, &gains).map_err(|e| AppError::Audio(e.to_string()))?;
+        player.set_speed(playback_speed);
+        if let Some(config) = genre_effects {
+            apply_genre_effects(&app, &player, config);
+        }
+        Ok(())
     }).await.map_err(|e| AppError::Audio(format!("Thread panic: {}", e)))?
 }
 
@@ -71,6 +132,22 @@ pub async fn set_volume(volume: f32, state: tauri::State<'_, AppState>) -> AppRe
     state.player.set_volume(valid_volume).map_err(|e| AppError::Audio(e.to_string()))
 }
 
+/// Mute without losing the volume level; returns whether output is now muted.
+#[tauri::command]
+pub fn toggle_mute(state: tauri::State<AppState>) -> bool {
+    state.player.toggle_mute()
+}
+
+#[tauri::command]
+pub fn set_muted(muted: bool, state: tauri::State<AppState>) {
+    state.player.set_muted(muted);
+}
+
+#[tauri::command]
+pub fn is_muted(state: tauri::State<AppState>) -> bool {
+    state.player.is_muted()
+}
+
 #[tauri::command]
 pub fn set_balance(balance: f32, state: tauri::State<AppState>) -> AppResult<()> {
     // Balance is -1.0 (full left) to 1.0 (full right), 0.0 is center
@@ -93,11 +170,57 @@ pub fn seek_to(position: f64, state: tauri::State<AppState>) -> AppResult<()> {
     state.player.seek(position).map_err(|e| AppError::Audio(e.to_string()))
 }
 
+/// Skip by `delta_secs` (negative skips back), clamped to the track.
+/// Returns the position sought to.
+#[tauri::command]
+pub fn seek_relative(delta_secs: f64, state: tauri::State<AppState>) -> AppResult<f64> {
+    if !delta_secs.is_finite() {
+        return Err(AppError::Validation("Seek delta must be a finite number".to_string()));
+    }
+    state.player.seek_relative(delta_secs)
+}
+
 #[tauri::command]
 pub fn get_position(state: tauri::State<AppState>) -> f64 {
     state.player.get_position()
 }
 
+/// Chapter markers embedded in the file at `path`; empty when it has none.
+#[tauri::command]
+pub fn get_chapters(path: String) -> AppResult<Vec<Chapter>> {
+    validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    chapters::read_chapters(std::path::Path::new(&path)).map_err(AppError::Decode)
+}
+
+/// Seek to the next chapter of the loaded track; None when it is in the last one.
+#[tauri::command]
+pub fn next_chapter(state: tauri::State<AppState>) -> AppResult<Option<Chapter>> {
+    state.player.next_chapter()
+}
+
+/// Restart the current chapter, or go to the previous one near its start.
+#[tauri::command]
+pub fn previous_chapter(state: tauri::State<AppState>) -> AppResult<Option<Chapter>> {
+    state.player.previous_chapter()
+}
+
+/// Change how often `playback-progress` is emitted while playing, and persist it.
+#[tauri::command]
+pub fn set_progress_interval(interval_ms: u64, state: tauri::State<AppState>) -> AppResult<()> {
+    state.progress_ticker.set_interval(interval_ms)?;
+    state
+        .db
+        .set_json_setting(PROGRESS_INTERVAL_KEY, &interval_ms)
+        .map_err(|e| AppError::Database(format!("Failed to save progress interval: {}", e)))
+}
+
+/// Whether the loaded track supports direct seeking (false means seeks go
+/// through the slower reload path).
+#[tauri::command]
+pub fn can_seek(state: tauri::State<AppState>) -> bool {
+    state.player.can_seek()
+}
+
 #[tauri::command]
 pub fn get_duration(state: tauri::State<AppState>) -> f64 {
     state.player.get_duration()
@@ -125,12 +248,52 @@ pub fn get_audio_devices() -> AppResult<Vec<AudioDevice>> {
     AudioPlayer::get_audio_devices().map_err(|e| AppError::Audio(e.to_string()))
 }
 
+/// Play a short, quiet tone on `device_name` through a separate stream and
+/// report whether it worked, without touching current playback. Meant to be
+/// called before `set_audio_device`. The device already playing passes
+/// without a tone: an exclusive backend could refuse a second stream on it.
 #[tauri::command]
-pub fn set_audio_device(device_name: String, state: tauri::State<AppState>) -> AppResult<()> {
+pub async fn test_audio_device(device_name: String, state: tauri::State<'_, AppState>) -> AppResult<DeviceTestResult> {
     if device_name.trim().is_empty() {
         return Err(AppError::Validation("Device name cannot be empty".to_string()));
     }
-    state.player.set_output_device(&device_name).map_err(|e| AppError::Audio(e.to_string()))
+    if state.player.connected_device_name().as_deref() == Some(device_name.as_str()) {
+        return Ok(DeviceTestResult {
+            device_name,
+            ok: true,
+            format: state.player.output_format(),
+            error: None,
+        });
+    }
+    tauri::async_runtime::spawn_blocking(move || device::test_audio_device(&device_name))
+        .await
+        .map_err(|e| AppError::InvalidState(e.to_string()))?
+}
+
+/// Switch output to `device_name`, restoring the volume last used on it.
+/// Returns the restored volume, or `None` when the current one was kept.
+#[tauri::command]
+pub fn set_audio_device(device_name: String, state: tauri::State<AppState>) -> AppResult<Option<f32>> {
+    if device_name.trim().is_empty() {
+        return Err(AppError::Validation("Device name cannot be empty".to_string()));
+    }
+    let previous_device = state.player.connected_device_name();
+    let previous_volume = state.player.volume();
+    let switched = state.player.set_output_device(&device_name).map_err(|e| AppError::Audio(e.to_string()))?;
+    if !switched {
+        return Ok(None);
+    }
+
+    let restored = device_volume::switch_device_volume(&state.db, previous_device.as_deref(), previous_volume, &device_name);
+    if let Some(volume) = restored {
+        info!("Restoring volume {:.2} for {}", volume, device_name);
+        state.player.set_volume(volume).map_err(|e| AppError::Audio(e.to_string()))?;
+    }
+    // The new device may normalize to a different loudness target.
+    if let Some(path) = state.player.current_path() {
+        state.player.apply_track_gains(&track_gains(&state, &path));
+    }
+    Ok(restored)
 }
 
 // Gapless playback commands
@@ -142,8 +305,22 @@ pub fn preload_track(path: String, state: tauri::State<AppState>) -> AppResult<(
 }
 
 #[tauri::command]
-pub fn swap_to_preloaded(state: tauri::State<AppState>) -> AppResult<()> {
-    state.player.swap_to_preloaded().map_err(|e| AppError::Audio(e.to_string()))
+pub fn swap_to_preloaded(app: tauri::AppHandle, state: tauri::State<AppState>) -> AppResult<()> {
+    let preloaded = state.player.get_preloaded_path();
+    let gains = preloaded.as_ref()
+        .map(|path| track_gains(&state, path))
+        .unwrap_or_default();
+    let genre_effects = preloaded.as_ref().and_then(|path| genre_effects(&state, path));
+    let playback_speed = preloaded.map(|path| speed::speed_for(&state.db, &path));
+    state.player.swap_to_preloaded().map_err(|e| AppError::Audio(e.to_string()))?;
+    state.player.apply_track_gains(&gains);
+    if let Some(playback_speed) = playback_speed {
+        state.player.set_speed(playback_speed);
+    }
+    if let Some(config) = genre_effects {
+        apply_genre_effects(&app, &state.player, config);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -162,12 +339,286 @@ pub fn get_preloaded_path(state: tauri::State<AppState>) -> Option<String> {
 }
 
 // ReplayGain commands
+
+/// Apply a ReplayGain value. `target_lufs` is the target `gain_db` was
+/// computed against (the -18 LUFS default if omitted); the gain is moved to
+/// the user's current target before it is applied. `peak` (linear) limits
+/// boosts so they do not clip.
 #[tauri::command]
-pub fn set_replaygain(gain_db: f32, preamp_db: f32, state: tauri::State<AppState>) -> AppResult<()> {
-    state.player.set_replaygain(gain_db, preamp_db).map_err(|e| AppError::Audio(e.to_string()))
+pub fn set_replaygain(
+    gain_db: f32,
+    preamp_db: f32,
+    target_lufs: Option<f64>,
+    peak: Option<f32>,
+    state: tauri::State<AppState>,
+) -> AppResult<()> {
+    let device = state.player.output_device_name();
+    let gain_db = playback_gain_db(&state.db, device.as_deref(), gain_db as f64, target_lufs) as f32;
+    state.player.set_replaygain(gain_db, preamp_db, peak).map_err(|e| AppError::Audio(e.to_string()))
 }
 
 #[tauri::command]
 pub fn clear_replaygain(state: tauri::State<AppState>) {
     state.player.clear_replaygain()
 }
+
+/// Select Off, Peak (real-time, no analysis needed) or ReplayGain
+/// normalization and persist the choice.
+#[tauri::command]
+pub fn set_normalize_mode(mode: NormalizeMode, state: tauri::State<AppState>) -> AppResult<()> {
+    state.player.set_normalize_mode(mode);
+    state
+        .db
+        .set_json_setting(NORMALIZE_MODE_KEY, &mode)
+        .map_err(|e| AppError::Database(format!("Failed to save normalization mode: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_normalize_mode(state: tauri::State<AppState>) -> NormalizeMode {
+    state.player.get_normalize_mode()
+}
+
+/// Configure skipping of leading/trailing silence (threshold in dBFS and
+/// the shortest run that counts as silence) and persist it. Takes effect
+/// from the next track loaded.
+#[tauri::command]
+pub fn set_silence_trim(config: SilenceTrimConfig, state: tauri::State<AppState>) -> AppResult<()> {
+    config.validate().map_err(AppError::Validation)?;
+    state.player.set_silence_trim(config);
+    state
+        .db
+        .set_json_setting(SILENCE_TRIM_KEY, &config)
+        .map_err(|e| AppError::Database(format!("Failed to save silence trim settings: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_silence_trim(state: tauri::State<AppState>) -> SilenceTrimConfig {
+    state.player.get_silence_trim()
+}
+
+/// Choose when the frontend may crossfade: always, never, or only when the
+/// album changes. Persisted.
+#[tauri::command]
+pub fn set_crossfade_policy(policy: CrossfadePolicy, state: tauri::State<AppState>) -> AppResult<()> {
+    info!("Crossfade policy set to {:?}", policy);
+    state
+        .db
+        .set_json_setting(CROSSFADE_POLICY_KEY, &policy)
+        .map_err(|e| AppError::Database(format!("Failed to save crossfade policy: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_crossfade_policy(state: tauri::State<AppState>) -> CrossfadePolicy {
+    CrossfadePolicy::load(&state.db)
+}
+
+/// Whether the transition from `from_path` to `to_path` should crossfade
+/// under the current policy; `false` means a gapless swap.
+#[tauri::command]
+pub fn should_crossfade(from_path: String, to_path: String, state: tauri::State<AppState>) -> AppResult<bool> {
+    crossfade::should_crossfade_between(&state.db, &from_path, &to_path)
+}
+
+/// The sleep timer fade and crossfade lengths, in seconds
+#[tauri::command]
+pub fn get_fade_durations(state: tauri::State<AppState>) -> FadeDurations {
+    FadeDurations::load(&state.db)
+}
+
+/// Set how long the sleep timer fades out before pausing, clamped to
+/// 0–30 s. Returns the stored lengths.
+#[tauri::command]
+pub fn set_sleep_fade_secs(secs: f32, state: tauri::State<AppState>) -> AppResult<FadeDurations> {
+    FadeDurations::set_sleep_fade_secs(&state.db, secs)
+}
+
+/// Set the crossfade overlap, clamped to 0–30 s. Returns the stored lengths.
+#[tauri::command]
+pub fn set_crossfade_secs(secs: f32, state: tauri::State<AppState>) -> AppResult<FadeDurations> {
+    FadeDurations::set_crossfade_secs(&state.db, secs)
+}
+
+/// Apply output stream options (e.g. forcing stereo) and persist them. An
+/// open stream is reopened right away.
+#[tauri::command]
+pub fn set_audio_config(config: AudioConfig, state: tauri::State<AppState>) -> AppResult<()> {
+    config.validate().map_err(AppError::Validation)?;
+    state.player.set_audio_config(config)?;
+    state
+        .db
+        .set_json_setting(AUDIO_CONFIG_KEY, &config)
+        .map_err(|e| AppError::Database(format!("Failed to save audio config: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_audio_config(state: tauri::State<AppState>) -> AudioConfig {
+    state.player.audio_config()
+}
+
+/// Sample rate and channels the output stream actually runs at; `None`
+/// while no output device is open.
+#[tauri::command]
+pub fn get_output_format(state: tauri::State<AppState>) -> Option<OutputFormat> {
+    state.player.output_format()
+}
+
+/// Turn per-track resume on or off and persist the choice. When on, loading
+/// a track continues from where it was last left.
+#[tauri::command]
+pub fn set_resume_per_track(enabled: bool, state: tauri::State<AppState>) -> AppResult<()> {
+    state.player.set_resume_per_track(enabled);
+    state
+        .db
+        .set_json_setting(RESUME_PER_TRACK_KEY, &enabled)
+        .map_err(|e| AppError::Database(format!("Failed to save resume setting: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_resume_per_track(state: tauri::State<AppState>) -> bool {
+    state.player.resume_per_track()
+}
+
+/// Choose whether playback moves on to the next track when the current
+/// file becomes unreadable mid-play, and persist the choice.
+#[tauri::command]
+pub fn set_skip_unavailable(enabled: bool, state: tauri::State<AppState>) -> AppResult<()> {
+    state
+        .db
+        .set_json_setting(SKIP_UNAVAILABLE_KEY, &enabled)
+        .map_err(|e| AppError::Database(format!("Failed to save skip unavailable setting: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_skip_unavailable(state: tauri::State<AppState>) -> bool {
+    track_end::skip_unavailable(&state.db)
+}
+
+/// Set the playback speed for `content_type` (default: that of the loaded
+/// track) and persist it. Applies immediately when the loaded track is of
+/// that type.
+#[tauri::command]
+pub fn set_playback_speed(
+    multiplier: f32,
+    content_type: Option<ContentType>,
+    state: tauri::State<AppState>,
+) -> AppResult<()> {
+    if !(speed::MIN_SPEED..=speed::MAX_SPEED).contains(&multiplier) {
+        return Err(AppError::Validation(format!(
+            "Playback speed must be between {}x and {}x",
+            speed::MIN_SPEED,
+            speed::MAX_SPEED
+        )));
+    }
+    let current = state
+        .player
+        .current_path()
+        .map(|path| speed::content_type_of(&state.db, &path));
+    let content_type = content_type.or(current).unwrap_or(ContentType::Music);
+
+    let mut speeds = speed::stored_speeds(&state.db);
+    speeds.set(content_type, multiplier);
+    state
+        .db
+        .set_json_setting(PLAYBACK_SPEED_KEY, &speeds)
+        .map_err(|e| AppError::Database(format!("Failed to save playback speed: {}", e)))?;
+
+    if current.unwrap_or(ContentType::Music) == content_type {
+        state.player.set_speed(multiplier);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_playback_speeds(state: tauri::State<AppState>) -> PlaybackSpeeds {
+    speed::stored_speeds(&state.db)
+}
+
+/// Seconds of playback left in the queue `track_ids` when the track at
+/// `current_index` is playing, at the current position. The current track
+/// plays at the current speed; later tracks at the speed stored for their
+/// content type. Tracks no longer in the library are skipped.
+#[tauri::command]
+pub fn get_queue_remaining_duration(
+    track_ids: Vec<String>,
+    current_index: usize,
+    state: tauri::State<AppState>,
+) -> AppResult<f64> {
+    let speeds = speed::stored_speeds(&state.db);
+    let mut queue = Vec::new();
+    let mut position = 0.0;
+    for (i, id) in track_ids.iter().enumerate().skip(current_index) {
+        let Some(track) = state.db.get_track_by_id(id).map_err(|e| AppError::Database(e.to_string()))? else {
+            continue;
+        };
+        let track_speed = if i == current_index {
+            position = state.player.get_position();
+            state.player.speed()
+        } else {
+            speeds.get(ContentType::from_genre(track.genre.as_deref()))
+        };
+        queue.push((track.duration, track_speed));
+    }
+    Ok(speed::remaining_playtime(&queue, position))
+}
+
+/// Save where playback of a track stopped; `None` clears it.
+#[tauri::command]
+pub fn set_track_position(track_id: String, secs: Option<f64>, state: tauri::State<AppState>) -> AppResult<()> {
+    if secs.is_some_and(|s| !s.is_finite() || s < 0.0) {
+        return Err(AppError::Validation("Position must be a non-negative number of seconds".to_string()));
+    }
+    let updated = state
+        .db
+        .set_track_position(&track_id, secs)
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if updated == 0 {
+        return Err(AppError::NotFound(format!("Track not found: {}", track_id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_track_position(track_id: String, state: tauri::State<AppState>) -> AppResult<Option<f64>> {
+    state.db.get_track_position(&track_id).map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Permanently adjust one track's volume by `db` decibels on top of
+/// ReplayGain; `None` removes the adjustment. Applies immediately if the
+/// track is the one currently loaded.
+#[tauri::command]
+pub fn set_track_volume_override(
+    track_id: String,
+    db: Option<f64>,
+    state: tauri::State<AppState>,
+) -> AppResult<()> {
+    use crate::audio::volume_manager::MAX_TRACK_GAIN_OVERRIDE_DB;
+
+    let limit = MAX_TRACK_GAIN_OVERRIDE_DB as f64;
+    if let Some(gain_db) = db {
+        if !gain_db.is_finite() || gain_db.abs() > limit {
+            return Err(AppError::Validation(format!(
+                "Volume override must be between -{0} and +{0} dB", limit
+            )));
+        }
+    }
+
+    let track = state.db.get_track_by_id(&track_id)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))?;
+    state.db.set_track_gain_override(&track_id, db)
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    info!("Volume override for {}: {:?} dB", track.path, db);
+
+    if state.player.current_path().as_deref() == Some(track.path.as_str()) {
+        state.player.set_gain_override(db.map(|g| g as f32));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_track_volume_override(track_id: String, state: tauri::State<AppState>) -> AppResult<Option<f64>> {
+    let track = state.db.get_track_by_id(&track_id)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))?;
+    state.db.get_track_gain_override(&track.path).map_err(|e| AppError::Database(e.to_string()))
+}
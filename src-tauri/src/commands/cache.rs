@@ -1,5 +1,7 @@
 // Cache and system commands
 use crate::AppState;
+use crate::database_maintenance::{AutoVacuumSettings, OrphanCleanup, AUTO_VACUUM_KEY, VACUUM_RECOMMENDED_BYTES};
+use crate::diagnostics::{AudioDiagnostics, Diagnostics};
 use crate::error::{AppError, AppResult};
 use tauri::{AppHandle, Manager};
 use log::info;
@@ -116,7 +118,7 @@ pub fn get_performance_stats(state: tauri::State<'_, AppState>) -> AppResult<ser
             "query_time_ms": query_time_ms,
         },
         "recommendations": {
-            "vacuum_recommended": db_size > 10_000_000, // > 10MB
+            "vacuum_recommended": db_size as u64 > VACUUM_RECOMMENDED_BYTES,
             "optimize_queries": query_time_ms > 100,
         }
     }))
@@ -149,6 +151,20 @@ pub fn get_runtime_diagnostics(state: tauri::State<'_, AppState>) -> AppResult<s
     }))
 }
 
+/// App, OS, audio and database details to attach to support requests
+#[tauri::command]
+pub fn get_diagnostics(state: tauri::State<'_, AppState>) -> AppResult<Diagnostics> {
+    let format = state.player.output_format();
+    let audio = AudioDiagnostics {
+        output_device: state.player.connected_device_name(),
+        sample_rate: format.map(|f| f.sample_rate),
+        channels: format.map(|f| f.channels),
+        ..AudioDiagnostics::from_host()
+    };
+    Diagnostics::collect(&state.db, audio)
+        .map_err(|e| AppError::Database(format!("Failed to collect diagnostics: {}", e)))
+}
+
 /// Run database vacuum to reclaim space and optimize
 #[tauri::command]
 pub fn vacuum_database(state: tauri::State<'_, AppState>) -> AppResult<()> {
@@ -161,13 +177,34 @@ pub fn vacuum_database(state: tauri::State<'_, AppState>) -> AppResult<()> {
         ));
     }
     info!("Running database vacuum to reclaim space and optimize");
-    let conn = state.db.conn();
-    conn.execute("VACUUM", [])
+    state.db.vacuum()
         .map_err(|e| AppError::Database(format!("Failed to vacuum database: {}", e)))?;
     info!("Database vacuum completed successfully");
     Ok(())
 }
 
+#[tauri::command]
+pub fn get_auto_vacuum_settings(state: tauri::State<'_, AppState>) -> AppResult<AutoVacuumSettings> {
+    Ok(AutoVacuumSettings::load(&state.db))
+}
+
+/// Vacuum automatically on startup once the database exceeds a size.
+#[tauri::command]
+pub fn set_auto_vacuum_settings(settings: AutoVacuumSettings, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    if settings.threshold_bytes == 0 {
+        return Err(AppError::Validation("Auto-vacuum threshold must be greater than 0 bytes".to_string()));
+    }
+    state.db.set_json_setting(AUTO_VACUUM_KEY, &settings)
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Remove playlist entries and cached art left behind by deleted tracks.
+#[tauri::command]
+pub fn cleanup_database(state: tauri::State<'_, AppState>) -> AppResult<OrphanCleanup> {
+    state.db.cleanup_orphans()
+        .map_err(|e| AppError::Database(format!("Failed to clean up database: {}", e)))
+}
+
 /// Evict oldest album-art cache files until total size is ≤ `limit_mb` MB.
 #[tauri::command]
 pub fn enforce_cache_limit(app: tauri::AppHandle, limit_mb: u64) -> AppResult<u64> {
@@ -1,11 +1,20 @@
 // Audio effects commands
 use crate::AppState;
-use crate::error::AppResult;
-use crate::effects::EffectsConfig;
+use crate::error::{AppError, AppResult};
+use crate::effects::{ClipStats, EffectsConfig, EffectsCost};
+use crate::genre_eq::{bands_differ, GenreEqSettings, GENRE_EQ_KEY};
+
+/// Settings key for the loudness compensation toggle.
+pub const LOUDNESS_SETTING_KEY: &str = "loudness_enabled";
 
 /// Set audio effects configuration
 #[tauri::command]
 pub fn set_audio_effects(config: EffectsConfig, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    // The UI echoes EQ it was sent back with slider rounding; only a real
+    // change overrides a genre preset.
+    if bands_differ(&state.player.get_effects().eq_bands, &config.eq_bands) {
+        state.genre_eq.manual_change();
+    }
     state.player.set_effects(config);
     Ok(())
 }
@@ -16,6 +25,30 @@ pub fn get_audio_effects(state: tauri::State<'_, AppState>) -> AppResult<Effects
     Ok(state.player.get_effects())
 }
 
+/// Reset effects to flat defaults, clearing any reverb or echo tail.
+/// Returns the new configuration.
+#[tauri::command]
+pub fn reset_effects(state: tauri::State<'_, AppState>) -> AppResult<EffectsConfig> {
+    state.genre_eq.manual_change();
+    state.player.reset_effects();
+    Ok(state.player.get_effects())
+}
+
+/// Map genres to EQ presets applied when a track of that genre loads
+#[tauri::command]
+pub fn set_genre_eq_settings(settings: GenreEqSettings, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    settings.validate()?;
+    state
+        .db
+        .set_json_setting(GENRE_EQ_KEY, &settings)
+        .map_err(|e| AppError::Database(format!("Failed to save genre EQ settings: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_genre_eq_settings(state: tauri::State<'_, AppState>) -> GenreEqSettings {
+    GenreEqSettings::load(&state.db)
+}
+
 /// Enable or disable audio effects
 #[tauri::command]
 pub fn set_effects_enabled(enabled: bool, state: tauri::State<'_, AppState>) -> AppResult<()> {
@@ -28,3 +61,35 @@ pub fn set_effects_enabled(enabled: bool, state: tauri::State<'_, AppState>) ->
 pub fn is_effects_enabled(state: tauri::State<'_, AppState>) -> AppResult<bool> {
     Ok(state.player.is_effects_enabled())
 }
+
+/// Enable or disable equal-loudness compensation and persist the choice
+#[tauri::command]
+pub fn set_loudness_enabled(enabled: bool, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    state.player.set_loudness_enabled(enabled);
+    state
+        .db
+        .set_json_setting(LOUDNESS_SETTING_KEY, &enabled)
+        .map_err(|e| AppError::Database(format!("Failed to save loudness setting: {}", e)))
+}
+
+/// Check if equal-loudness compensation is enabled
+#[tauri::command]
+pub fn is_loudness_enabled(state: tauri::State<'_, AppState>) -> AppResult<bool> {
+    Ok(state.player.is_loudness_enabled())
+}
+
+/// How often the current track has overflowed full scale ahead of the limiter
+#[tauri::command]
+pub fn get_clip_stats(state: tauri::State<'_, AppState>) -> ClipStats {
+    state.player.get_clip_stats()
+}
+
+/// Benchmark the current effects chain so the UI can warn before a heavy
+/// configuration causes dropouts
+#[tauri::command]
+pub async fn measure_effects_cost(state: tauri::State<'_, AppState>) -> AppResult<EffectsCost> {
+    let player = state.player.clone();
+    tauri::async_runtime::spawn_blocking(move || player.measure_effects_cost())
+        .await
+        .map_err(|e| AppError::InvalidState(e.to_string()))
+}
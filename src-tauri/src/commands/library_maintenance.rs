@@ -1,6 +1,7 @@
 // Library maintenance commands — split from library.rs
 use crate::AppState;
 use crate::error::{AppError, AppResult};
+use crate::library_export::{self, ExportFormat, LibraryImportSummary};
 use crate::scanner::Track;
 use log::info;
 use tauri::{Manager, Emitter};
@@ -139,3 +140,34 @@ pub fn write_text_file(file_path: String, content: String, app_handle: tauri::Ap
     fs::write(&canonical_target, content)
         .map_err(|e| AppError::Io(std::io::Error::other(format!("Failed to write file: {}", e))))
 }
+
+/// Write the whole library (metadata, ratings, play counts) to `path` as CSV
+/// or JSON. Returns the number of tracks exported.
+#[tauri::command]
+pub fn export_library(path: String, format: ExportFormat, state: tauri::State<'_, AppState>) -> AppResult<usize> {
+    use std::path::Path;
+
+    if path.contains("..") {
+        return Err(AppError::Security("Security: directory traversal is not allowed".to_string()));
+    }
+    let parent = Path::new(&path)
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .ok_or_else(|| AppError::Validation("Export path must include a directory".to_string()))?;
+    crate::validation::validate_path(&parent.to_string_lossy())?;
+
+    library_export::export_library(&state.db, Path::new(&path), format)
+}
+
+/// Merge a library export back in, matching tracks by path. Existing ratings
+/// and play counts are kept unless `overwrite` is set.
+#[tauri::command]
+pub fn import_library(
+    path: String,
+    format: ExportFormat,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<LibraryImportSummary> {
+    let path = crate::validation::validate_path(&path)?;
+    library_export::import_library(&state.db, &path, format, overwrite.unwrap_or(false))
+}
@@ -1,7 +1,8 @@
 // Library scanning commands — split from library.rs
 use crate::AppState;
 use crate::error::{AppError, AppResult};
-use crate::scanner::{Scanner, Track};
+use crate::scan_events::{ScanEventSink, ScanProgressThrottle, SCAN_PROGRESS_THROTTLE_KEY};
+use crate::scanner::{FolderRescanDiff, ScanOptions, Scanner, Track};
 use crate::time_utils::now_millis;
 use log::info;
 use tauri::Window;
@@ -18,14 +19,8 @@ pub async fn scan_folder(
 
     // Check if this folder already exists in the database — if so, do an
     // incremental scan instead of a full rescan to avoid redundant I/O.
-    let existing_folder: Option<String> = {
-        let conn = state.db.conn();
-        conn.query_row(
-            "SELECT id FROM folders WHERE path = ?1",
-            rusqlite::params![&folder_path],
-            |row| row.get(0),
-        ).ok()
-    };
+    let existing_folder = state.db.get_folder_id_by_path(&folder_path)
+        .map_err(|e| AppError::Database(e.to_string()))?;
 
     if existing_folder.is_some() {
         info!("Folder already registered — delegating to incremental scan");
@@ -44,7 +39,9 @@ pub async fn scan_folder(
     let window_clone = window.clone();
 
     tauri::async_runtime::spawn_blocking(move || {
-        let tracks = Scanner::scan_directory(&folder_path_clone, Some(&window_clone), Some(cancel_flag), Some(&db))
+        let options = ScanOptions::load_for_folder(&db, &folder_path_clone);
+        let cancel_flag = Some(cancel_flag);
+        let mut tracks = Scanner::scan_directory(&folder_path_clone, Some(&window_clone), cancel_flag.clone(), Some(&db), &options)
             .map_err(AppError::Scanner)?;
 
         // Save folder info
@@ -57,12 +54,19 @@ pub async fn scan_folder(
             .unwrap_or(&folder_path_clone)
             .to_string();
 
-        db.add_folder_with_tracks(&folder_id, &folder_path_clone, &folder_name, now, &tracks)
-            .map_err(|e| AppError::Database(format!("Failed to persist scanned folder/tracks transactionally: {}", e)))?;
-
-        info!("Scan complete, persisted {} tracks in one transaction", tracks.len());
-
-        info!("Scan complete, persisted {} tracks in one transaction", tracks.len());
+        Scanner::persist_and_analyze(
+            &db,
+            &mut tracks,
+            Some(&window_clone as &dyn ScanEventSink),
+            &cancel_flag,
+            options.analyze_replaygain,
+            |tracks| {
+                let ids = db.add_folder_with_tracks(&folder_id, &folder_path_clone, &folder_name, now, tracks)
+                    .map_err(|e| AppError::Database(format!("Failed to persist scanned folder/tracks transactionally: {}", e)))?;
+                info!("Scan complete, persisted {} tracks in one transaction", tracks.len());
+                Ok(ids)
+            },
+        )?;
 
         // Clear scan ID
         *scan_id_ref.lock().unwrap() = None;
@@ -95,25 +99,35 @@ pub async fn scan_folder_incremental(
 
     tauri::async_runtime::spawn_blocking(move || {
         // Perform incremental scan (only new/modified files)
-        let tracks = Scanner::scan_directory_incremental(&folder_path_clone, Some(&window_clone), Some(cancel_flag), &db)
+        let options = ScanOptions::load_for_folder(&db, &folder_path_clone);
+        let cancel_flag = Some(cancel_flag);
+        let mut tracks = Scanner::scan_directory_incremental(&folder_path_clone, Some(&window_clone), cancel_flag.clone(), &db, &options)
             .map_err(AppError::Scanner)?;
 
         info!("Incremental scan complete, updating {} tracks in database", tracks.len());
 
-        // Collect (track, mtime) pairs so we can persist everything in one transaction.
-        let mut batch: Vec<(crate::scanner::Track, i64)> = Vec::with_capacity(tracks.len());
-        for track in tracks.iter() {
-            let path = std::path::Path::new(&track.path);
-            let mtime = std::fs::metadata(path)
-                .and_then(|m| m.modified())
-                .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64)
-                .unwrap_or(0);
-            batch.push((track.clone(), mtime));
-        }
-
         // Single transaction — same pattern as full scan's add_folder_with_tracks.
-        db.add_tracks_incremental_batch(&batch)
-            .map_err(|e| AppError::Database(format!("Failed to persist incremental tracks: {}", e)))?;
+        Scanner::persist_and_analyze(
+            &db,
+            &mut tracks,
+            Some(&window_clone as &dyn ScanEventSink),
+            &cancel_flag,
+            options.analyze_replaygain,
+            |tracks| {
+                // Collect (track, mtime) pairs so we can persist everything in one transaction.
+                let mut batch: Vec<(crate::scanner::Track, i64)> = Vec::with_capacity(tracks.len());
+                for track in tracks.iter() {
+                    let path = std::path::Path::new(&track.path);
+                    let mtime = std::fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64)
+                        .unwrap_or(0);
+                    batch.push((track.clone(), mtime));
+                }
+                db.add_tracks_incremental_batch(&batch)
+                    .map_err(|e| AppError::Database(format!("Failed to persist incremental tracks: {}", e)))
+            },
+        )?;
 
         // Clear scan ID
         *scan_id_ref.lock().unwrap() = None;
@@ -124,6 +138,83 @@ pub async fn scan_folder_incremental(
     .map_err(|e| AppError::InvalidState(format!("Incremental scan task panicked: {}", e)))?
 }
 
+/// Rescan one library folder: pick up new and modified files and drop
+/// tracks whose file was deleted. The folder must already be in the library;
+/// its row is kept as is. If part of the folder can't be read, or it is
+/// missing or empty, nothing is removed and the diff only proposes it.
+#[tauri::command]
+pub async fn rescan_folder(
+    folder_path: String,
+    window: Window,
+    state: tauri::State<'_, AppState>
+) -> AppResult<FolderRescanDiff> {
+    info!("Rescanning folder: {}", folder_path);
+    crate::validation::validate_path(&folder_path).map_err(|e| AppError::Validation(e.to_string()))?;
+    if state.db.get_folder_id_by_path(&folder_path).map_err(|e| AppError::Database(e.to_string()))?.is_none() {
+        return Err(AppError::NotFound(format!("Folder is not in the library: {}", folder_path)));
+    }
+
+    state.scan_cancel_flag.store(false, std::sync::atomic::Ordering::SeqCst);
+    let cancel_flag = state.scan_cancel_flag.clone();
+    let db = state.db.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        Scanner::rescan_folder(&folder_path, Some(&window), Some(cancel_flag), &db).map_err(AppError::Scanner)
+    })
+    .await
+    .map_err(|e| AppError::InvalidState(format!("Rescan task panicked: {}", e)))?
+}
+
+/// Save the scan profile (extensions, symlinks, depth, ignore patterns) used
+/// for `folder_path` on subsequent scans.
+#[tauri::command]
+pub fn set_folder_scan_options(
+    folder_path: String,
+    options: ScanOptions,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<()> {
+    crate::validation::validate_path(&folder_path).map_err(|e| AppError::Validation(e.to_string()))?;
+    options.compile_ignore_globs().map_err(AppError::Validation)?;
+    if options.extensions.is_empty() {
+        return Err(AppError::Validation("At least one file extension is required".to_string()));
+    }
+    options.save_for_folder(&state.db, &folder_path).map_err(AppError::Database)
+}
+
+/// Scan profile for `folder_path` (the default profile if none was saved).
+#[tauri::command]
+pub fn get_folder_scan_options(folder_path: String, state: tauri::State<'_, AppState>) -> ScanOptions {
+    ScanOptions::load_for_folder(&state.db, &folder_path)
+}
+
+/// Exclude a file or folder (and everything below it) from all future scans.
+#[tauri::command]
+pub fn add_ignored_path(path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    let path = crate::scanner::canonical_ignored_path(&path);
+    info!("Ignoring path in scans: {}", path);
+    state.db.add_ignored_path(&path).map_err(|e| AppError::Database(e.to_string()))
+}
+
+#[tauri::command]
+pub fn remove_ignored_path(path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    // Entries are stored canonical, but older ones may be stored as given.
+    let removed = state.db.remove_ignored_path(&path).map_err(|e| AppError::Database(e.to_string()))?
+        || state
+            .db
+            .remove_ignored_path(&crate::scanner::canonical_ignored_path(&path))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    if !removed {
+        return Err(AppError::NotFound(format!("Path is not ignored: {}", path)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_ignored_paths(state: tauri::State<'_, AppState>) -> AppResult<Vec<String>> {
+    state.db.list_ignored_paths().map_err(|e| AppError::Database(e.to_string()))
+}
+
 /// Return the IDs of all tracks whose path starts with `folder_path`.
 /// The frontend calls this after an incremental scan so it can add both newly
 /// scanned tracks AND pre-existing tracks in the same folder to the playlist.
@@ -154,3 +245,18 @@ pub fn cancel_scan(scan_id: String, state: tauri::State<'_, AppState>) -> AppRes
     }
     Ok(())
 }
+
+/// Change how often `scan-progress` is emitted during scans, and persist it.
+#[tauri::command]
+pub fn set_scan_progress_throttle(throttle: ScanProgressThrottle, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    throttle.validate()?;
+    state
+        .db
+        .set_json_setting(SCAN_PROGRESS_THROTTLE_KEY, &throttle)
+        .map_err(|e| AppError::Database(format!("Failed to save scan progress throttle: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_scan_progress_throttle(state: tauri::State<'_, AppState>) -> ScanProgressThrottle {
+    ScanProgressThrottle::load(&state.db)
+}
@@ -1,9 +1,17 @@
 // Library track CRUD commands — split from library.rs
 use crate::AppState;
+use crate::art_cache_format::sniff_mime;
+use crate::art_palette::ArtPalette;
+use crate::database_album_art::CachedAlbumArt;
+use crate::database_history::ListeningStats;
 use crate::error::{AppError, AppResult};
 use crate::scanner::{Scanner, Track};
-use log::info;
+use crate::similar_tracks;
+use crate::smart_shuffle::{self, ShuffleWeights};
+use crate::time_utils::now_millis;
+use log::{info, warn};
 use base64::{Engine as _, engine::general_purpose};
+use tauri::{Emitter, Window};
 
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -56,6 +64,12 @@ pub fn get_all_folders(state: tauri::State<AppState>) -> AppResult<Vec<(String,
     state.db.get_all_folders().map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// Folders as (id, path, name, date_added, track_count), newest first.
+#[tauri::command]
+pub fn get_folders_with_counts(state: tauri::State<AppState>) -> AppResult<Vec<crate::database_folders::FolderWithCount>> {
+    state.db.get_folders_with_counts().map_err(|e| AppError::Database(e.to_string()))
+}
+
 #[tauri::command]
 pub fn remove_folder(folder_id: String, folder_path: String, state: tauri::State<AppState>) -> AppResult<()> {
     state.db
@@ -78,6 +92,31 @@ pub fn update_track_path(track_id: String, new_path: String, state: tauri::State
     state.db.update_track_path(&track_id, &new_path).map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// Move/rename a track's file on disk and point its library entry at the new
+/// location. Fails without touching anything if the destination exists.
+#[tauri::command]
+pub async fn move_track_file(track_id: String, new_path: String, state: tauri::State<'_, AppState>) -> AppResult<Track> {
+    if new_path.contains("..") {
+        return Err(AppError::Security("Invalid path: directory traversal not allowed".to_string()));
+    }
+    info!("Moving track file: {} -> {}", track_id, new_path);
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::track_files::move_track_file(&db, &track_id, &new_path))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("Move task panicked: {}", e)))?
+}
+
+/// Re-read a track's tags from its file, e.g. after editing them in another
+/// program. Rating and play history are preserved.
+#[tauri::command]
+pub async fn refresh_track_metadata(track_id: String, state: tauri::State<'_, AppState>) -> AppResult<Track> {
+    info!("Refreshing metadata for track: {}", track_id);
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::track_files::refresh_track_metadata(&db, &track_id))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("Refresh task panicked: {}", e)))?
+}
+
 #[tauri::command]
 pub fn find_duplicates(state: tauri::State<'_, AppState>, sensitivity: Option<String>) -> AppResult<Vec<Vec<Track>>> {
     let level = sensitivity.as_deref().unwrap_or("medium");
@@ -124,6 +163,12 @@ pub fn increment_play_count(track_id: String, state: tauri::State<AppState>) ->
     state.db.increment_play_count(&track_id).map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// Called when the user skips a track before it is half over.
+#[tauri::command]
+pub fn increment_skip_count(track_id: String, state: tauri::State<AppState>) -> AppResult<()> {
+    state.db.increment_skip_count(&track_id).map_err(|e| AppError::Database(e.to_string()))
+}
+
 #[tauri::command]
 pub fn get_recently_played(limit: usize, state: tauri::State<AppState>) -> AppResult<Vec<Track>> {
     state.db.get_recently_played(limit).map_err(|e| AppError::Database(e.to_string()))
@@ -134,24 +179,102 @@ pub fn get_most_played(limit: usize, state: tauri::State<AppState>) -> AppResult
     state.db.get_most_played(limit).map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// Never-played tracks, oldest additions first.
 #[tauri::command]
-pub fn get_album_art(track_id: String, state: tauri::State<'_, AppState>) -> AppResult<Option<String>> {
+pub fn get_unplayed_tracks(limit: usize, state: tauri::State<AppState>) -> AppResult<Vec<Track>> {
+    state.db.get_unplayed_tracks(limit).map_err(|e| AppError::Database(e.to_string()))
+}
+
+#[tauri::command]
+pub fn get_unplayed_track_count(state: tauri::State<AppState>) -> AppResult<usize> {
+    state.db.count_unplayed_tracks().map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Tracks with a zero or implausible duration, for review or a rescan.
+#[tauri::command]
+pub fn get_suspect_tracks(state: tauri::State<AppState>) -> AppResult<Vec<Track>> {
+    state.db.get_suspect_tracks().map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Genre facet: each genre with its track count, untagged tracks as "Unknown".
+#[tauri::command]
+pub fn get_genres(state: tauri::State<AppState>) -> AppResult<Vec<(String, usize)>> {
+    state.db.get_genres().map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// Year facet: each year with its track count, untagged tracks as year 0.
+#[tauri::command]
+pub fn get_years(state: tauri::State<AppState>) -> AppResult<Vec<(i32, usize)>> {
+    state.db.get_years().map_err(|e| AppError::Database(e.to_string()))
+}
+
+/// A queue of up to `size` library tracks, shuffled with a bias toward
+/// well-rated, often-played tracks that haven't been played lately. Passing
+/// the same `seed` reproduces the same queue.
+#[tauri::command]
+pub fn build_smart_queue(
+    size: usize,
+    weights: Option<ShuffleWeights>,
+    seed: Option<u64>,
+    state: tauri::State<AppState>,
+) -> AppResult<Vec<Track>> {
+    let tracks = state.db.get_all_tracks().map_err(|e| AppError::Database(e.to_string()))?;
+    let now = now_millis();
+    let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0);
+    Ok(smart_shuffle::build_smart_queue(&tracks, size, &weights.unwrap_or_default(), now, seed))
+}
+
+/// Up to `limit` tracks most like `track_id`, by shared artist and genre,
+/// tempo and key.
+#[tauri::command]
+pub fn get_similar_tracks(track_id: String, limit: usize, state: tauri::State<AppState>) -> AppResult<Vec<Track>> {
+    similar_tracks::get_similar_tracks(&state.db, &track_id, limit)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))
+}
+
+#[tauri::command]
+pub fn get_listening_stats(state: tauri::State<AppState>) -> AppResult<ListeningStats> {
+    state.db.get_listening_stats().map_err(|e| AppError::Database(e.to_string()))
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumArtResponse {
+    /// Base64 image bytes.
+    pub data: String,
+    pub mime: String,
+}
+
+impl From<CachedAlbumArt> for AlbumArtResponse {
+    fn from(art: CachedAlbumArt) -> Self {
+        Self { data: general_purpose::STANDARD.encode(&art.data), mime: art.mime }
+    }
+}
+
+#[tauri::command]
+pub fn get_album_art(track_id: String, state: tauri::State<'_, AppState>) -> AppResult<Option<AlbumArtResponse>> {
     info!("Getting album art for track: {}", track_id);
-    match state.db.get_album_art(&track_id) {
-        Ok(Some(art_data)) => {
-            let base64_data = general_purpose::STANDARD.encode(&art_data);
-            Ok(Some(base64_data))
-        },
+    match state.db.get_album_art_with_mime(&track_id) {
+        Ok(Some(art)) => Ok(Some(art.into())),
         Ok(None) => Ok(None),
         Err(e) => Err(AppError::Database(format!("Failed to get album art: {}", e))),
     }
 }
 
+/// A small copy of the track's cached art (see `art_thumbnail`).
+#[tauri::command]
+pub fn get_album_art_thumbnail(track_id: String, state: tauri::State<'_, AppState>) -> AppResult<Option<AlbumArtResponse>> {
+    state.db.get_album_art_thumbnail(&track_id)
+        .map(|art| art.map(|data| AlbumArtResponse::from(CachedAlbumArt { mime: sniff_mime(&data).to_string(), data })))
+        .map_err(|e| AppError::Database(format!("Failed to get album art thumbnail: {}", e)))
+}
+
 #[tauri::command]
 pub fn get_album_art_batch(
     track_ids: Vec<String>,
     state: tauri::State<'_, AppState>,
-) -> AppResult<Vec<(String, Option<String>)>> {
+) -> AppResult<Vec<(String, Option<AlbumArtResponse>)>> {
     info!("Getting album art batch for {} tracks", track_ids.len());
     let items = state
         .db
@@ -160,38 +283,274 @@ pub fn get_album_art_batch(
 
     Ok(items
         .into_iter()
-        .map(|(track_id, art)| {
-            let encoded = art.map(|bytes| general_purpose::STANDARD.encode(&bytes));
-            (track_id, encoded)
-        })
+        .map(|(track_id, art)| (track_id, art.map(AlbumArtResponse::from)))
         .collect())
 }
 
+/// Art for a track, extracted from its file and cached on first request.
+/// Runs off the IPC thread; concurrent requests for the same file share a
+/// single extraction.
 #[tauri::command]
-pub fn extract_and_cache_album_art(track_id: String, track_path: String, state: tauri::State<'_, AppState>) -> AppResult<Option<String>> {
+pub async fn extract_and_cache_album_art(track_id: String, track_path: String, state: tauri::State<'_, AppState>) -> AppResult<Option<AlbumArtResponse>> {
     info!("Extracting album art for: {}", track_path);
-    
-    // Check if already cached
-    if state.db.has_album_art(&track_id) {
-        return get_album_art(track_id, state);
+
+    let db = state.db.clone();
+    let extractor = state.art_extractor.clone();
+    let art = tauri::async_runtime::spawn_blocking(move || {
+        extractor.extract_and_cache(&db, &track_id, &track_path, Scanner::extract_album_art)
+    })
+    .await
+    .map_err(|e| AppError::Scanner(format!("Thread panic: {}", e)))??;
+
+    Ok(art.map(AlbumArtResponse::from))
+}
+
+/// Dominant and accent colours of the track's cached album art, for
+/// theming; `None` when no art is cached.
+#[tauri::command]
+pub async fn extract_art_colors(track_id: String, state: tauri::State<'_, AppState>) -> AppResult<Option<ArtPalette>> {
+    let db = state.db.clone();
+    let palette_cache = state.palette_cache.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(art) = db.get_album_art(&track_id).map_err(|e| AppError::Database(e.to_string()))? else {
+            return Ok(None);
+        };
+        palette_cache.get_or_extract(&art).map(Some).map_err(AppError::Decode)
+    })
+    .await
+    .map_err(|e| AppError::Decode(format!("Thread panic: {}", e)))?
+}
+
+/// Cache the waveform and album art of the track that plays next, so
+/// skipping to it shows both at once. Returns immediately; the work runs in
+/// the background and doesn't touch playback.
+#[tauri::command]
+pub fn prepare_next(path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let db = state.db.clone();
+    let extractor = state.art_extractor.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = crate::next_track::prepare_next(&db, &extractor, &path) {
+            warn!("Failed to prepare next track {}: {}", path, e);
+        }
+    });
+    Ok(())
+}
+
+/// Embed new cover art in the track's file and replace the cached copy, so
+/// later `get_album_art` calls return the new image. Returns the new art.
+#[tauri::command]
+pub fn set_album_art(
+    track_id: String,
+    track_path: String,
+    image_bytes: Vec<u8>,
+    mime: String,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<AlbumArtResponse> {
+    use crate::tag_service::{validate_cover_image, write_album_art};
+
+    crate::validation::validate_path(&track_path).map_err(|e| AppError::Validation(e.to_string()))?;
+    validate_cover_image(&image_bytes, &mime).map_err(AppError::Validation)?;
+
+    info!("Setting album art for: {} ({} bytes)", track_path, image_bytes.len());
+    write_album_art(&track_path, &image_bytes, &mime).map_err(AppError::Decode)?;
+
+    state.db.set_album_art(&track_id, &image_bytes)
+        .map_err(|e| AppError::Database(format!("Failed to cache album art: {}", e)))?;
+
+    Ok(CachedAlbumArt { data: image_bytes, mime }.into())
+}
+
+#[tauri::command]
+pub async fn fingerprint_track(path: String, state: tauri::State<'_, AppState>) -> AppResult<String> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::fingerprint::fingerprint_track(&db, &path))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("Fingerprint task panicked: {}", e)))?
+        .map_err(AppError::Decode)
+}
+
+/// Estimate the tempo of the track at `path`, store it and return it.
+#[tauri::command]
+pub async fn analyze_bpm(path: String, state: tauri::State<'_, AppState>) -> AppResult<f64> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::bpm::analyze_track_bpm(&db, &path))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("BPM task panicked: {}", e)))?
+        .map_err(AppError::Decode)
+}
+
+/// Detect the musical key of the track at `path`, store it and return it
+/// with its Camelot code.
+#[tauri::command]
+pub async fn analyze_key(
+    path: String,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<crate::key_detection::KeyInfo> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::key_detection::analyze_track_key(&db, &path))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("Key detection task panicked: {}", e)))?
+        .map_err(AppError::Decode)
+}
+
+/// Decode each of `paths` once and run the chosen analyzers (all of them
+/// when `analyzers` is omitted) on it, storing the results. Emits
+/// `analysis-progress` before each file and `analysis-complete` with the
+/// number analysed; `cancel_analysis` stops the batch.
+#[tauri::command]
+pub async fn analyze_all(
+    paths: Vec<String>,
+    analyzers: Option<crate::analysis::AnalyzerSelection>,
+    window: Window,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Vec<crate::analysis::TrackAnalysis>> {
+    let selection = analyzers.unwrap_or_else(crate::analysis::AnalyzerSelection::all);
+    if selection.is_empty() {
+        return Err(AppError::Validation("No analyzers selected".to_string()));
     }
-    
-    // Extract from file
-    match Scanner::extract_album_art(&track_path) {
-        Ok(Some(art_data)) => {
-            // Cache in database
-            state.db.set_album_art(&track_id, &art_data)
-                .map_err(|e| AppError::Database(format!("Failed to cache album art: {}", e)))?;
-            
-            let base64_data = general_purpose::STANDARD.encode(&art_data);
-            Ok(Some(base64_data))
-        },
-        Ok(None) => Ok(None),
-        Err(e) => Err(AppError::Scanner(format!("Failed to extract album art: {}", e))),
+    for path in &paths {
+        crate::validation::validate_path(path).map_err(|e| AppError::Validation(e.to_string()))?;
+    }
+
+    info!("Analyzing {} files ({:?})", paths.len(), selection);
+    let target_lufs = crate::replaygain::get_target_lufs(&state.db);
+    state.analysis_cancel_flag.store(false, std::sync::atomic::Ordering::SeqCst);
+    let cancel_flag = state.analysis_cancel_flag.clone();
+    let db = state.db.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let results = crate::analysis::analyze_tracks(&db, &paths, selection, target_lufs, &cancel_flag, |progress| {
+            let _ = window.emit("analysis-progress", progress);
+        });
+        let _ = window.emit("analysis-complete", results.len());
+        results
+    })
+    .await
+    .map_err(|e| AppError::InvalidState(format!("Analysis task panicked: {}", e)))
+}
+
+/// Stop a running `analyze_all` batch; results stored so far are kept.
+#[tauri::command]
+pub fn cancel_analysis(state: tauri::State<'_, AppState>) {
+    info!("Cancelling analysis");
+    state.analysis_cancel_flag.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Candidate title/artist/album/year for a track from AcoustID (when a
+/// Chromaprint fingerprint is given and an API key is configured) or a
+/// MusicBrainz search on its partial tags. Network failures yield no
+/// candidates rather than an error.
+#[tauri::command]
+pub async fn lookup_track_metadata(
+    track_id: String,
+    chromaprint: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Vec<crate::metadata_lookup::MetadataCandidate>> {
+    use crate::metadata_lookup::{lookup_candidates, ReqwestClient, ACOUSTID_API_KEY_KEY};
+
+    let track = state.db.get_track_by_id(&track_id)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))?;
+    let api_key = state.db.get_json_setting::<Option<String>>(ACOUSTID_API_KEY_KEY)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .flatten();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let client = match ReqwestClient::new() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Metadata lookup unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+        lookup_candidates(&client, &track, chromaprint.as_deref(), api_key.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::InvalidState(format!("Metadata lookup task panicked: {}", e)))
+}
+
+/// Write an accepted lookup candidate to the track's file and library row.
+#[tauri::command]
+pub async fn apply_metadata_candidate(
+    track_id: String,
+    candidate: crate::metadata_lookup::MetadataCandidate,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<()> {
+    info!("Applying looked-up metadata to track {}", track_id);
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::metadata_lookup::apply_candidate(&db, &track_id, &candidate)
+    })
+    .await
+    .map_err(|e| AppError::InvalidState(format!("Metadata apply task panicked: {}", e)))?
+    .map_err(AppError::Decode)
+}
+
+#[tauri::command]
+pub fn get_album_art_cache_settings(state: tauri::State<'_, AppState>) -> AppResult<crate::art_cache_format::ArtCacheSettings> {
+    Ok(crate::art_cache_format::stored_settings(&state.db))
+}
+
+/// Choose the format art is cached in from now on. Art already cached keeps
+/// its format until it is cached again.
+#[tauri::command]
+pub fn set_album_art_cache_settings(
+    settings: crate::art_cache_format::ArtCacheSettings,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<()> {
+    use crate::art_cache_format::ART_CACHE_SETTINGS_KEY;
+
+    if !(0.0..=100.0).contains(&settings.quality) {
+        return Err(AppError::Validation(format!("Art quality must be between 0 and 100, got {}", settings.quality)));
     }
+    state.db.set_json_setting(ART_CACHE_SETTINGS_KEY, &settings)
+        .map_err(|e| AppError::Database(format!("Failed to save art cache settings: {}", e)))
+}
+
+/// Store (or with `None`, clear) the AcoustID API key used for lookups.
+#[tauri::command]
+pub fn set_acoustid_api_key(api_key: Option<String>, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    use crate::metadata_lookup::ACOUSTID_API_KEY_KEY;
+
+    let api_key = api_key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+    state.db.set_json_setting(ACOUSTID_API_KEY_KEY, &api_key)
+        .map_err(|e| AppError::Database(format!("Failed to save AcoustID API key: {}", e)))
+}
+
+/// Every text tag in the file, keyed by the format's own key names
+/// (including custom fields such as ID3v2 TXXX frames), for a tag editor.
+#[tauri::command]
+pub fn read_all_tags(path: String) -> AppResult<std::collections::BTreeMap<String, Vec<String>>> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    crate::tag_service::read_all_tags(&path).map_err(AppError::Decode)
 }
 
-#[derive(serde::Deserialize)]
+/// Set an arbitrary tag key, replacing its existing values.
+#[tauri::command]
+pub fn write_tag(path: String, key: String, value: String) -> AppResult<()> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    crate::tag_service::validate_tag_key(&key).map_err(AppError::Validation)?;
+
+    info!("Writing tag {} for: {}", key, path);
+    crate::tag_service::write_tag(&path, &key, &value).map_err(AppError::Decode)
+}
+
+/// Remove every value of an arbitrary tag key.
+#[tauri::command]
+pub fn remove_tag(path: String, key: String) -> AppResult<()> {
+    crate::validation::validate_path(&path).map_err(|e| AppError::Validation(e.to_string()))?;
+    crate::tag_service::validate_tag_key(&key).map_err(AppError::Validation)?;
+
+    info!("Removing tag {} from: {}", key, path);
+    crate::tag_service::remove_tag(&path, &key).map_err(AppError::Decode)
+}
+
+#[derive(serde::Deserialize, Clone)]
 pub struct TagUpdate {
     pub title: Option<String>,
     pub artist: Option<String>,
@@ -203,6 +562,21 @@ pub struct TagUpdate {
     pub disc_number: Option<String>,
 }
 
+impl From<TagUpdate> for crate::tag_service::TagUpdateInput {
+    fn from(tags: TagUpdate) -> Self {
+        Self {
+            title: tags.title,
+            artist: tags.artist,
+            album: tags.album,
+            year: tags.year,
+            genre: tags.genre,
+            comment: tags.comment,
+            track_number: tags.track_number,
+            disc_number: tags.disc_number,
+        }
+    }
+}
+
 #[tauri::command]
 pub fn update_track_tags(track_id: String, track_path: String, tags: TagUpdate, state: tauri::State<'_, AppState>) -> AppResult<()> {
     use crate::tag_service::{apply_tags_to_file, TagUpdateInput};
@@ -212,16 +586,7 @@ pub fn update_track_tags(track_id: String, track_path: String, tags: TagUpdate,
     
     info!("Updating tags for: {}", track_path);
     
-    let tag_update = TagUpdateInput {
-        title: tags.title.clone(),
-        artist: tags.artist.clone(),
-        album: tags.album.clone(),
-        year: tags.year.clone(),
-        genre: tags.genre.clone(),
-        comment: tags.comment.clone(),
-        track_number: tags.track_number.clone(),
-        disc_number: tags.disc_number.clone(),
-    };
+    let tag_update = TagUpdateInput::from(tags.clone());
     apply_tags_to_file(&track_path, &tag_update).map_err(AppError::Decode)?;
     
     // Update database with all edited metadata fields
@@ -243,6 +608,23 @@ pub fn update_track_tags(track_id: String, track_path: String, tags: TagUpdate,
     Ok(())
 }
 
+/// Apply the provided (non-empty) tag fields to many tracks at once, e.g. to
+/// set one album or genre across a whole folder. Per-file failures are
+/// reported in the result instead of aborting the batch.
+#[tauri::command]
+pub async fn update_tags_bulk(
+    track_ids: Vec<String>,
+    tags: TagUpdate,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<crate::tag_service::BulkTagResult> {
+    info!("Bulk tag update for {} tracks", track_ids.len());
+    let db = state.db.clone();
+    let update = crate::tag_service::TagUpdateInput::from(tags);
+    tauri::async_runtime::spawn_blocking(move || crate::tag_service::apply_tags_bulk(&db, &track_ids, &update))
+        .await
+        .map_err(|e| AppError::InvalidState(format!("Bulk tag task panicked: {}", e)))
+}
+
 #[tauri::command]
 pub fn reset_play_count(track_id: String, state: tauri::State<AppState>) -> AppResult<()> {
     info!("Resetting play count for track: {}", track_id);
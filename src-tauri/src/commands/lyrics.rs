@@ -1,17 +1,19 @@
 // Lyrics commands
+use crate::AppState;
 use crate::lyrics::Lrc;
 use crate::error::{AppError, AppResult};
 
 /// Load lyrics from an LRC file for a given track.
 /// Returns the parsed LRC data including lines and metadata.
 #[tauri::command]
-pub fn load_lyrics(track_path: String) -> AppResult<Lrc> {
+pub fn load_lyrics(track_path: String, state: tauri::State<'_, AppState>) -> AppResult<Lrc> {
     // Try .lrc file with same name as track
     let lrc_path = std::path::Path::new(&track_path)
         .with_extension("lrc");
     
     if lrc_path.exists() {
-        Lrc::from_file(&lrc_path)
+        state.lyrics_cache.get(&lrc_path)
+            .map(|lrc| (*lrc).clone())
             .map_err(|e| AppError::Decode(format!("Failed to load lyrics: {}", e)))
     } else {
         Err(AppError::NotFound("No lyrics file found".to_string()))
@@ -20,8 +22,16 @@ pub fn load_lyrics(track_path: String) -> AppResult<Lrc> {
 
 /// Get the current lyric line for a given timestamp.
 /// Returns the lyric line that should be displayed at the specified time.
+/// `time` is a playback position; it is shifted back by the effects-chain
+/// latency so the line matches what is actually audible. `lyric_offset`
+/// (seconds, positive = sooner) lets the UI nudge sync without editing the file.
 #[tauri::command]
-pub fn get_lyric_at_time(track_path: String, time: f64) -> AppResult<Option<(f64, String)>> {
+pub fn get_lyric_at_time(
+    track_path: String,
+    time: f64,
+    lyric_offset: Option<f64>,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Option<(f64, String)>> {
     let lrc_path = std::path::Path::new(&track_path)
         .with_extension("lrc");
     
@@ -29,8 +39,38 @@ pub fn get_lyric_at_time(track_path: String, time: f64) -> AppResult<Option<(f64
         return Ok(None);
     }
     
-    let lrc = Lrc::from_file(&lrc_path)
+    let lrc = state.lyrics_cache.get(&lrc_path)
         .map_err(|e| AppError::Decode(format!("Failed to load lyrics: {}", e)))?;
     
-    Ok(lrc.get_lyric_at(time).map(|line| (line.timestamp, line.text.clone())))
+    let audible_time = (time - state.player.get_effects_latency_secs()).max(0.0);
+    Ok(lrc
+        .get_lyric_at_offset(audible_time, lyric_offset.unwrap_or(0.0))
+        .map(|line| (line.timestamp, line.text.clone())))
+}
+
+/// Like `get_lyric_at_time`, but returns every line sharing the active
+/// timestamp (e.g. original and translation in bilingual LRC files).
+#[tauri::command]
+pub fn get_lyrics_at_time(
+    track_path: String,
+    time: f64,
+    lyric_offset: Option<f64>,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<Vec<(f64, String)>> {
+    let lrc_path = std::path::Path::new(&track_path)
+        .with_extension("lrc");
+
+    if !lrc_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let lrc = state.lyrics_cache.get(&lrc_path)
+        .map_err(|e| AppError::Decode(format!("Failed to load lyrics: {}", e)))?;
+
+    let audible_time = (time - state.player.get_effects_latency_secs()).max(0.0);
+    Ok(lrc
+        .get_lyrics_at_offset(audible_time, lyric_offset.unwrap_or(0.0))
+        .into_iter()
+        .map(|line| (line.timestamp, line.text.clone()))
+        .collect())
 }
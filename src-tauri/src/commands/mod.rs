@@ -15,6 +15,8 @@ pub mod lyrics;
 pub mod replaygain;
 pub mod cache;
 pub mod tray;
+pub mod shortcuts;
+pub mod open_files;
 
 // Re-export all commands for easy importing in main.rs
 pub use audio::*;
@@ -28,3 +30,5 @@ pub use lyrics::*;
 pub use replaygain::*;
 pub use cache::*;
 pub use tray::*;
+pub use shortcuts::*;
+pub use open_files::*;
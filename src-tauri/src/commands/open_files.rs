@@ -0,0 +1,40 @@
+// "Open with" file handling
+use crate::open_files::OpenRequest;
+use crate::AppState;
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Start playing the first opened file and tell the frontend via
+/// `open-file`. The request itself is kept until the frontend collects it
+/// with `take_opened_files`, which clears it: at startup the event can fire
+/// before any listener is attached, and a later request replaces one that
+/// was never collected.
+pub fn dispatch_open_request(app: &AppHandle, request: OpenRequest) {
+    info!(
+        "Opening {} (+{} queued) from the OS",
+        request.play,
+        request.enqueue.len()
+    );
+    let Some(state) = app.try_state::<AppState>() else {
+        warn!("Open request before app state was ready: {}", request.play);
+        return;
+    };
+
+    let gains = super::audio::track_gains(&state, &request.play);
+    if let Err(e) = state
+        .player
+        .load_with_gains(request.play.clone(), &gains)
+        .and_then(|_| state.player.play())
+    {
+        warn!("Failed to play opened file {}: {}", request.play, e);
+    }
+
+    *state.opened_files.lock().unwrap_or_else(|p| p.into_inner()) = Some(request.clone());
+    let _ = app.emit("open-file", request);
+}
+
+/// Collect (and clear) the most recent open request, if any.
+#[tauri::command]
+pub fn take_opened_files(state: tauri::State<'_, AppState>) -> Option<OpenRequest> {
+    state.opened_files.lock().unwrap_or_else(|p| p.into_inner()).take()
+}
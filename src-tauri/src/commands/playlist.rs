@@ -1,9 +1,9 @@
 // Playlist commands
 use crate::AppState;
 use crate::error::{AppError, AppResult};
-use crate::scanner::{Scanner, Track};
-use crate::playlist_io::PlaylistIO;
-use log::{info, warn};
+use crate::scanner::Track;
+use crate::playlist_io::{PlaylistIO, PlaylistImportResult};
+use log::info;
 
 #[tauri::command]
 pub fn create_playlist(name: String, state: tauri::State<AppState>) -> AppResult<String> {
@@ -102,6 +102,20 @@ pub fn get_playlist_tracks(
         .map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// (track id, path) of the playlist's tracks whose file is missing, so a
+/// playlist can be checked before it is played or exported.
+#[tauri::command]
+pub fn validate_playlist(playlist_id: String, state: tauri::State<AppState>) -> AppResult<Vec<(String, String)>> {
+    let missing = state
+        .db
+        .get_missing_playlist_files(&playlist_id)
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    info!("Playlist {} has {} missing files", playlist_id, missing.len());
+    Ok(missing)
+}
+
+/// Write the playlist as M3U, UTF-8 without a BOM, so the same output is
+/// valid as `.m3u` or `.m3u8`.
 #[tauri::command]
 pub fn export_playlist(playlist_id: String, output_path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
     use std::io::Write;
@@ -166,51 +180,21 @@ pub fn import_playlist(playlist_name: String, input_path: String, state: tauri::
     crate::validation::validate_path(&input_path)
         .map_err(|e| AppError::Validation(format!("Invalid input path: {}", e)))?;
     
-    // Import M3U file
-    let tracks = PlaylistIO::import_m3u(&input_path)
+    let tracks = PlaylistIO::import_file(&input_path)
         .map_err(|e| AppError::Io(std::io::Error::other(format!("Failed to import playlist: {}", e))))?;
-    
-    // Create playlist in database
-    let playlist_id = state.db.create_playlist(&validated_name)
-        .map_err(|e| AppError::Database(format!("Failed to create playlist: {}", e)))?;
-    
-    let mut imported_track_ids = Vec::new();
-    
-    // Add tracks to database and playlist
-    for (_title, path) in tracks {
-        // Check if track exists in library
-        let track_id = match state.db.get_track_by_path(&path) {
-            Ok(Some(track)) => track.id,
-            Ok(None) => {
-                // Track not in library, scan it
-                match Scanner::extract_track_info(std::path::Path::new(&path)) {
-                    Ok(track) => {
-                        state.db.add_track(&track)
-                            .map_err(|e| AppError::Database(format!("Failed to add track: {}", e)))?;
-                        track.id
-                    },
-                    Err(e) => {
-                        warn!("Failed to scan {}: {}", path, e);
-                        continue;
-                    }
-                }
-            },
-            Err(e) => {
-                warn!("Database error for {}: {}", path, e);
-                continue;
-            }
-        };
-        
-        imported_track_ids.push(track_id);
-    }
-    
-    // Batch-insert all resolved tracks in a single transaction.
-    // This is atomic (all-or-nothing) and avoids N separate SQLite commits.
-    if !imported_track_ids.is_empty() {
-        state.db.add_tracks_to_playlist_batch(&playlist_id, &imported_track_ids, 0)
-            .map_err(|e| AppError::Database(format!("Failed to add tracks to playlist: {}", e)))?;
-    }
-    
+
+    let (_playlist_id, imported_track_ids) = crate::playlist_io::import_as_playlist(&state.db, &validated_name, &tracks)
+        .map_err(|e| AppError::Database(format!("{:#}", e)))?;
+
     info!("Successfully imported {} tracks", imported_track_ids.len());
     Ok(imported_track_ids)
 }
+
+#[tauri::command]
+pub fn import_playlists_from_folder(dir: String, state: tauri::State<'_, AppState>) -> AppResult<Vec<PlaylistImportResult>> {
+    crate::validation::validate_path(&dir)
+        .map_err(|e| AppError::Validation(format!("Invalid folder path: {}", e)))?;
+
+    crate::playlist_io::import_playlists_from_folder(&state.db, std::path::Path::new(&dir))
+        .map_err(|e| AppError::Io(std::io::Error::other(format!("{:#}", e))))
+}
@@ -7,10 +7,19 @@ use crate::replaygain::{
     get_album_replaygain as get_album_replaygain_data,
     get_replaygain,
     store_replaygain,
+    get_target_lufs,
+    set_target_lufs,
+    get_device_targets,
+    set_device_target_lufs,
     AlbumReplayGainData,
     ReplayGainData,
+    ReplayGainMode,
+    REPLAYGAIN_MODE_KEY,
+    REPLAYGAIN_PREAMP_KEY,
 };
+use crate::r128_gain;
 use log::info;
+use std::collections::HashMap;
 
 /// Analyze track for ReplayGain data and store in database.
 /// Runs the CPU-heavy decode + EBU R128 analysis on a blocking thread
@@ -20,8 +29,9 @@ pub async fn analyze_replaygain(track_path: String, state: tauri::State<'_, AppS
     info!("Analyzing ReplayGain for: {}", track_path);
 
     let path = track_path.clone();
+    let target_lufs = get_target_lufs(&state.db);
     let data = tauri::async_runtime::spawn_blocking(move || {
-        analyze_track(&path)
+        analyze_track(&path, target_lufs)
     })
     .await
     .map_err(|e| AppError::Audio(format!("ReplayGain task panicked: {}", e)))?
@@ -39,6 +49,17 @@ pub fn get_track_replaygain(track_path: String, state: tauri::State<'_, AppState
     get_replaygain(&state.db.conn, &track_path).map_err(|e| AppError::Database(e.to_string()))
 }
 
+/// Write a track's stored ReplayGain into its file: `R128_TRACK_GAIN` for
+/// Opus, `REPLAYGAIN_TRACK_*` otherwise.
+#[tauri::command]
+pub fn write_replaygain_tags(track_path: String, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    crate::validation::validate_path(&track_path).map_err(|e| AppError::Validation(e.to_string()))?;
+    let data = get_replaygain(&state.db.conn, &track_path)
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("No ReplayGain data for {}", track_path)))?;
+    r128_gain::write_track_gain(&track_path, &data).map_err(AppError::Decode)
+}
+
 /// Get cached album-level ReplayGain data for artist+album.
 #[tauri::command]
 pub fn get_album_replaygain(
@@ -70,6 +91,90 @@ pub fn analyze_album_replaygain(
     }
 
     info!("Analyzing album ReplayGain for {} - {}", artist, album);
-    analyze_album_replaygain_data(&state.db.conn, artist, album)
+    analyze_album_replaygain_data(&state.db.conn, artist, album, get_target_lufs(&state.db))
         .map_err(|e| AppError::Database(e.to_string()))
 }
+
+/// Loudness target (LUFS) used for new analyses and for playback adjustment.
+#[tauri::command]
+pub fn get_replaygain_target(state: tauri::State<'_, AppState>) -> f64 {
+    get_target_lufs(&state.db)
+}
+
+/// Set the loudness target, e.g. -14 (streaming), -18 (default) or -23
+/// (broadcast). Existing analyses are rebased at playback, not re-run.
+#[tauri::command]
+pub fn set_replaygain_target(target_lufs: f64, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    info!("Setting ReplayGain target to {} LUFS", target_lufs);
+    set_target_lufs(&state.db, target_lufs).map_err(AppError::Validation)
+}
+
+/// Loudness targets set for specific output devices, by device name.
+#[tauri::command]
+pub fn get_device_replaygain_targets(state: tauri::State<'_, AppState>) -> HashMap<String, f64> {
+    get_device_targets(&state.db)
+}
+
+/// Set the loudness target used while `device_name` is the output, e.g. a
+/// quieter one for headphones; `None` falls back to the global target.
+/// Applies to the current track right away when that device is in use.
+#[tauri::command]
+pub fn set_device_replaygain_target(
+    device_name: String,
+    target_lufs: Option<f64>,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<()> {
+    if device_name.trim().is_empty() {
+        return Err(AppError::Validation("Device name cannot be empty".to_string()));
+    }
+    info!("Setting ReplayGain target for {} to {:?} LUFS", device_name, target_lufs);
+    set_device_target_lufs(&state.db, &device_name, target_lufs).map_err(AppError::Validation)?;
+    if state.player.output_device_name().as_deref() == Some(device_name.as_str()) {
+        reapply_current_track_gains(&state);
+    }
+    Ok(())
+}
+
+/// Re-pick the current track's gain after a ReplayGain setting changed.
+fn reapply_current_track_gains(state: &AppState) {
+    if let Some(path) = state.player.current_path() {
+        state.player.apply_track_gains(&super::audio::track_gains(state, &path));
+    }
+}
+
+/// Choose which stored gain each loaded track gets (off, track, album, or
+/// album with track fallback) and persist the choice. The playing track
+/// switches right away.
+#[tauri::command]
+pub fn set_replaygain_mode(mode: ReplayGainMode, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    state.player.set_replaygain_mode(mode);
+    reapply_current_track_gains(&state);
+    state
+        .db
+        .set_json_setting(REPLAYGAIN_MODE_KEY, &mode)
+        .map_err(|e| AppError::Database(format!("Failed to save ReplayGain mode: {}", e)))
+}
+
+#[tauri::command]
+pub fn get_replaygain_mode(state: tauri::State<'_, AppState>) -> ReplayGainMode {
+    state.player.get_replaygain_mode()
+}
+
+/// Set the preamp (dB, clamped to ±15) added to every track's ReplayGain
+/// and persist it. Returns the value actually applied.
+#[tauri::command]
+pub fn set_replaygain_preamp(preamp_db: f32, state: tauri::State<'_, AppState>) -> AppResult<f32> {
+    state.player.set_replaygain_preamp(preamp_db);
+    reapply_current_track_gains(&state);
+    let applied = state.player.get_replaygain_preamp();
+    state
+        .db
+        .set_json_setting(REPLAYGAIN_PREAMP_KEY, &applied)
+        .map_err(|e| AppError::Database(format!("Failed to save ReplayGain preamp: {}", e)))?;
+    Ok(applied)
+}
+
+#[tauri::command]
+pub fn get_replaygain_preamp(state: tauri::State<'_, AppState>) -> f32 {
+    state.player.get_replaygain_preamp()
+}
@@ -0,0 +1,60 @@
+// Global shortcut commands
+use crate::AppState;
+use crate::error::{AppError, AppResult};
+use crate::shortcuts;
+use log::info;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// Bind `accelerator` (e.g. "Ctrl+Alt+Right") to `action` and persist it.
+/// Replaces any previous accelerator for the same action.
+#[tauri::command]
+pub fn register_shortcut(
+    action: String,
+    accelerator: String,
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> AppResult<()> {
+    shortcuts::validate_action(&action).map_err(AppError::Validation)?;
+    let accelerator = shortcuts::validate_accelerator(&accelerator).map_err(AppError::Validation)?;
+    let shortcut = shortcuts::parse_accelerator(&accelerator)?;
+
+    let mut bindings = state.shortcut_bindings.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some((other, _)) = bindings
+        .iter()
+        .find(|(a, acc)| **a != action && acc.eq_ignore_ascii_case(&accelerator))
+    {
+        return Err(AppError::Validation(format!(
+            "{} is already bound to {}",
+            accelerator, other
+        )));
+    }
+
+    if let Some(previous) = bindings.get(&action) {
+        shortcuts::unbind(&app, previous)?;
+    }
+    shortcuts::bind(&app, &action, shortcut)?;
+
+    info!("Global shortcut {} -> {}", accelerator, action);
+    bindings.insert(action, accelerator);
+    shortcuts::save_bindings(&state.db, &bindings)
+}
+
+/// Remove the binding for `action` (if any) and persist the change.
+#[tauri::command]
+pub fn unregister_shortcut(action: String, app: AppHandle, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    shortcuts::validate_action(&action).map_err(AppError::Validation)?;
+
+    let mut bindings = state.shortcut_bindings.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(previous) = bindings.remove(&action) {
+        shortcuts::unbind(&app, &previous)?;
+        info!("Global shortcut {} unbound from {}", previous, action);
+    }
+    shortcuts::save_bindings(&state.db, &bindings)
+}
+
+/// Current action → accelerator mapping.
+#[tauri::command]
+pub fn get_shortcuts(state: tauri::State<'_, AppState>) -> HashMap<String, String> {
+    state.shortcut_bindings.lock().unwrap_or_else(|p| p.into_inner()).clone()
+}
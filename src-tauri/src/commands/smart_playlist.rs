@@ -13,14 +13,14 @@ pub fn create_smart_playlist(playlist: SmartPlaylist, state: tauri::State<'_, Ap
 
 #[tauri::command]
 pub fn get_all_smart_playlists(state: tauri::State<'_, AppState>) -> AppResult<Vec<SmartPlaylist>> {
-    let conn = state.db.conn();
+    let conn = state.db.read_conn();
     smart_playlists::load_all_smart_playlists(&conn)
     .map_err(|e| AppError::Database(format!("Failed to load smart playlists: {}", e)))
 }
 
 #[tauri::command]
 pub fn get_smart_playlist(id: String, state: tauri::State<'_, AppState>) -> AppResult<SmartPlaylist> {
-    let conn = state.db.conn();
+    let conn = state.db.read_conn();
     smart_playlists::load_smart_playlist(&conn, &id)
     .map_err(|e| AppError::Database(format!("Failed to load smart playlist: {}", e)))
 }
@@ -41,7 +41,7 @@ pub fn delete_smart_playlist(id: String, state: tauri::State<'_, AppState>) -> A
 
 #[tauri::command]
 pub fn execute_smart_playlist(id: String, state: tauri::State<'_, AppState>) -> AppResult<Vec<Track>> {
-    let conn = state.db.conn();
+    let conn = state.db.read_conn();
     
     // Load the smart playlist
     let playlist = smart_playlists::load_smart_playlist(&conn, &id)
@@ -1,6 +1,8 @@
 // Tray settings commands
 use crate::AppState;
 use crate::TraySettings;
+use crate::error::{AppError, AppResult};
+use crate::startup::START_MINIMIZED_KEY;
 use log::info;
 
 #[tauri::command]
@@ -8,15 +10,13 @@ pub fn set_tray_settings(
     state: tauri::State<'_, AppState>,
     close_to_tray: bool,
     minimize_to_tray: bool,
-    start_minimized: bool,
 ) {
     let mut s = state.tray_settings.lock().unwrap_or_else(|p| p.into_inner());
     s.close_to_tray = close_to_tray;
     s.minimize_to_tray = minimize_to_tray;
-    s.start_minimized = start_minimized;
     info!(
-        "Tray settings updated: close_to_tray={}, minimize_to_tray={}, start_minimized={}",
-        close_to_tray, minimize_to_tray, start_minimized
+        "Tray settings updated: close_to_tray={}, minimize_to_tray={}",
+        close_to_tray, minimize_to_tray
     );
 }
 
@@ -24,3 +24,21 @@ pub fn set_tray_settings(
 pub fn get_tray_settings(state: tauri::State<'_, AppState>) -> TraySettings {
     state.tray_settings.lock().unwrap_or_else(|p| p.into_inner()).clone()
 }
+
+/// Turn "start minimized to tray" on or off and persist it. Takes effect on
+/// the next launch.
+#[tauri::command]
+pub fn set_start_minimized(enabled: bool, state: tauri::State<'_, AppState>) -> AppResult<()> {
+    state
+        .db
+        .set_json_setting(START_MINIMIZED_KEY, &enabled)
+        .map_err(|e| AppError::Database(format!("Failed to save start minimized setting: {}", e)))?;
+    state.tray_settings.lock().unwrap_or_else(|p| p.into_inner()).start_minimized = enabled;
+    info!("Start minimized set to {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_start_minimized(state: tauri::State<'_, AppState>) -> bool {
+    state.tray_settings.lock().unwrap_or_else(|p| p.into_inner()).start_minimized
+}
@@ -0,0 +1,76 @@
+//! When to crossfade between tracks.
+//!
+//! The fade itself runs in the frontend; before starting one it asks
+//! [`should_crossfade_between`] whether the configured [`CrossfadePolicy`]
+//! allows it for this pair. Declining leaves the transition to the gapless
+//! preload swap, which keeps continuous albums (live sets, DJ mixes,
+//! classical movements) flowing as recorded.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+
+/// Settings key under which the policy is persisted.
+pub const CROSSFADE_POLICY_KEY: &str = "crossfade_policy";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrossfadePolicy {
+    /// Crossfade every transition.
+    #[default]
+    Always,
+    /// Never crossfade; every transition is a gapless swap.
+    Never,
+    /// Gapless within an album, crossfade when the album changes.
+    OnlyBetweenAlbums,
+}
+
+fn same_album(outgoing: Option<&str>, incoming: Option<&str>) -> bool {
+    match (outgoing.map(str::trim), incoming.map(str::trim)) {
+        (Some(a), Some(b)) => !a.is_empty() && a.to_lowercase() == b.to_lowercase(),
+        _ => false,
+    }
+}
+
+impl CrossfadePolicy {
+    /// The persisted policy, or the default when unset or unreadable.
+    pub fn load(db: &Database) -> Self {
+        match db.get_json_setting::<Self>(CROSSFADE_POLICY_KEY) {
+            Ok(policy) => policy.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to read crossfade policy: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether to crossfade from a track on `outgoing_album` to one on
+    /// `incoming_album`. Untagged tracks never count as the same album.
+    pub fn should_crossfade(&self, outgoing_album: Option<&str>, incoming_album: Option<&str>) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::OnlyBetweenAlbums => !same_album(outgoing_album, incoming_album),
+        }
+    }
+}
+
+/// Whether the stored policy allows crossfading from the track at
+/// `from_path` to the one at `to_path`. Files outside the library have no
+/// album to compare and are treated as different albums.
+pub fn should_crossfade_between(db: &Database, from_path: &str, to_path: &str) -> AppResult<bool> {
+    let policy = CrossfadePolicy::load(db);
+    if policy != CrossfadePolicy::OnlyBetweenAlbums {
+        return Ok(policy.should_crossfade(None, None));
+    }
+    let album_of = |path: &str| -> AppResult<Option<String>> {
+        Ok(db
+            .get_track_by_path(path)
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .and_then(|track| track.album))
+    };
+    let (outgoing, incoming) = (album_of(from_path)?, album_of(to_path)?);
+    Ok(policy.should_crossfade(outgoing.as_deref(), incoming.as_deref()))
+}
@@ -1,6 +1,7 @@
 use log::warn;
 use rusqlite::Connection;
 use serde::Deserialize;
+use crate::database_pool::{PooledConnection, ReadPool};
 use std::sync::Mutex;
 
 #[derive(Debug, Deserialize, Default)]
@@ -22,9 +23,22 @@ pub struct TrackFilter {
 }
 
 
+/// Metadata columns to overwrite on a track; `None` leaves the column as is.
+#[derive(Debug, Clone, Default)]
+pub struct TrackMetadataPatch {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    pub year: Option<i32>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+}
 
 pub struct Database {
+    /// The single writer connection. Writes are serialized through it.
     pub conn: Mutex<Connection>,
+    pub(crate) readers: ReadPool,
 }
 
 impl Database {
@@ -38,6 +52,13 @@ impl Database {
             poisoned.into_inner()
         })
     }
+
+    /// A read-only connection for queries that only `SELECT`. It doesn't
+    /// wait on the writer, so reads stay responsive during a scan; it sees
+    /// writes once they commit.
+    pub fn read_conn(&self) -> PooledConnection<'_> {
+        self.readers.get()
+    }
 }
 
 #[cfg(test)]
@@ -169,6 +190,122 @@ mod tests {
         cleanup_db_files(&db_path);
     }
 
+    #[test]
+    fn rescanning_a_folder_keeps_a_single_folder_row() {
+        let db_path = temp_db_path("folder_rescan_row");
+        let db = Database::new(&db_path).expect("db init failed");
+        let folder_path = "C:/Music/Jazz";
+        let tracks = [sample_track("jazz_1", "C:/Music/Jazz/one.mp3")];
+
+        // Every scan comes with a freshly generated folder id.
+        db.add_folder_with_tracks("folder_first", folder_path, "Jazz", 1_000, &tracks)
+            .expect("first scan failed");
+        db.add_folder_with_tracks("folder_second", folder_path, "Jazz", 2_000, &tracks)
+            .expect("second scan failed");
+        db.add_folder("folder_third", folder_path, "Jazz", 3_000)
+            .expect("add_folder failed");
+
+        let folders = db.get_all_folders().expect("get_all_folders failed");
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0].0, "folder_first");
+        assert_eq!(folders[0].3, 3_000);
+
+        let duplicate = db.conn().execute(
+            "INSERT INTO folders (id, path, name, date_added) VALUES ('folder_raw', ?1, 'Jazz', 0)",
+            [folder_path],
+        );
+        assert!(duplicate.is_err(), "folder paths are unique");
+
+        cleanup_db_files(&db_path);
+    }
+
+    #[test]
+    fn folders_with_counts_respect_directory_boundaries() {
+        let db_path = temp_db_path("folder_counts");
+        let db = Database::new(&db_path).expect("db init failed");
+
+        db.add_folder_with_tracks(
+            "folder_a",
+            "C:/Music/Rock",
+            "Rock",
+            1_000,
+            &[
+                sample_track("rock_1", "C:/Music/Rock/one.mp3"),
+                sample_track("rock_2", "C:/Music/Rock/Live/two.mp3"),
+                sample_track("rock_3", "C:/Music/Rock/three.mp3"),
+            ],
+        )
+        .expect("add folder a failed");
+        // Shares a name prefix with the first folder but none of its tracks.
+        db.add_folder_with_tracks(
+            "folder_b",
+            "C:/Music/Rock Classics/",
+            "Rock Classics",
+            2_000,
+            &[sample_track("classic_1", "C:/Music/Rock Classics/one.mp3")],
+        )
+        .expect("add folder b failed");
+        db.add_folder_with_tracks(
+            "folder_c",
+            "D:\\Audio_Books",
+            "Audio_Books",
+            3_000,
+            &[
+                sample_track("book_1", "D:\\Audio_Books\\one.mp3"),
+                sample_track("book_2", "D:\\Audio_Books\\two.mp3"),
+                sample_track("book_other", "D:\\AudioXBooks\\one.mp3"),
+            ],
+        )
+        .expect("add folder c failed");
+
+        let counts: Vec<(String, i64)> = db
+            .get_folders_with_counts()
+            .expect("get_folders_with_counts failed")
+            .into_iter()
+            .map(|(id, _, _, _, count)| (id, count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("folder_c".to_string(), 2),
+                ("folder_b".to_string(), 1),
+                ("folder_a".to_string(), 3),
+            ]
+        );
+
+        cleanup_db_files(&db_path);
+    }
+
+    #[test]
+    fn removing_a_backslash_folder_removes_its_tracks() {
+        let db_path = temp_db_path("folder_remove_backslash");
+        let db = Database::new(&db_path).expect("db init failed");
+
+        db.add_folder_with_tracks(
+            "books",
+            "D:\\Audio_Books",
+            "Audio_Books",
+            1_000,
+            &[
+                sample_track("book_1", "D:\\Audio_Books\\one.mp3"),
+                sample_track("book_2", "D:\\Audio_Books\\Series\\two.mp3"),
+                // `_` must match literally, not as a wildcard.
+                sample_track("lookalike", "D:\\AudioXBooks\\one.mp3"),
+                sample_track("sibling", "D:\\Audio_Books2\\one.mp3"),
+            ],
+        )
+        .expect("add folder failed");
+
+        db.remove_folder_with_tracks("books", "D:\\Audio_Books")
+            .expect("remove folder failed");
+
+        let mut remaining: Vec<String> = db.get_all_tracks().unwrap().into_iter().map(|t| t.id).collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["lookalike".to_string(), "sibling".to_string()]);
+
+        cleanup_db_files(&db_path);
+    }
+
     #[test]
     fn delete_playlist_removes_playlist_and_memberships() {
         let db_path = temp_db_path("playlist_delete_tx");
@@ -347,6 +484,38 @@ mod tests {
         cleanup_db_files(&db_path);
     }
 
+    #[test]
+    fn re_adding_a_path_updates_the_existing_row() {
+        let db_path = temp_db_path("upsert_by_path");
+        let db = Database::new(&db_path).expect("db init failed");
+
+        db.add_track(&sample_track("original_id", "C:/Music/Test/song.mp3"))
+            .expect("seed track failed");
+        db.set_track_rating("original_id", 5).expect("rating failed");
+        db.increment_play_count("original_id").expect("play count failed");
+        let playlist_id = db.create_playlist("Keep").expect("create playlist failed");
+        db.add_track_to_playlist(&playlist_id, "original_id", 0)
+            .expect("add to playlist failed");
+
+        let mut rescanned = sample_track("rescan_id", "C:/Music/Test/song.mp3");
+        rescanned.title = Some("Retagged".to_string());
+        let stored_ids = db.add_tracks_incremental_batch(&[(rescanned, 42)])
+            .expect("rescan upsert failed");
+        assert_eq!(stored_ids, ["original_id"], "the caller learns the id the row kept");
+
+        let all_tracks = db.get_all_tracks().expect("fetch tracks failed");
+        assert_eq!(all_tracks.len(), 1, "rescan must not duplicate the row");
+        let track = &all_tracks[0];
+        assert_eq!(track.id, "original_id");
+        assert_eq!(track.title.as_deref(), Some("Retagged"));
+        assert_eq!(track.rating, 5);
+        assert_eq!(track.play_count, 1);
+        assert_eq!(db.get_playlist_tracks(&playlist_id).expect("playlist fetch failed").len(), 1);
+
+        drop(db);
+        cleanup_db_files(&db_path);
+    }
+
     #[test]
     fn concurrent_increment_play_count_is_consistent() {
         let db_path = temp_db_path("concurrent_play_count");
@@ -386,4 +555,82 @@ mod tests {
         drop(db);
         cleanup_db_files(&db_path);
     }
+
+    #[test]
+    fn reads_do_not_wait_on_an_open_write() {
+        let db_path = temp_db_path("read_during_write");
+        let db = Arc::new(Database::new(&db_path).expect("db init failed"));
+        db.add_track(&sample_track("committed", "C:/Music/Test/committed.mp3")).unwrap();
+
+        // Hold the writer mid-transaction, as a scan batch does.
+        let writer = db.conn();
+        writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+        writer
+            .execute("UPDATE tracks SET title = 'uncommitted' WHERE id = 'committed'", [])
+            .unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let reader_db = Arc::clone(&db);
+        let reader = thread::spawn(move || {
+            tx.send(reader_db.get_all_tracks().expect("read failed")).unwrap();
+        });
+        let tracks = rx
+            .recv_timeout(std::time::Duration::from_secs(2))
+            .expect("read blocked behind the writer");
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].title.as_deref(), Some("Sample Title"), "readers see committed data");
+        reader.join().unwrap();
+
+        writer.execute_batch("COMMIT").unwrap();
+        drop(writer);
+        let track = db.get_track_by_id("committed").unwrap().unwrap();
+        assert_eq!(track.title.as_deref(), Some("uncommitted"));
+
+        drop(db);
+        cleanup_db_files(&db_path);
+    }
+
+    #[test]
+    fn concurrent_reads_and_writes_complete() {
+        let db_path = temp_db_path("concurrent_reads_writes");
+        let db = Arc::new(Database::new(&db_path).expect("db init failed"));
+
+        let writer_db = Arc::clone(&db);
+        let writer = thread::spawn(move || {
+            for batch in 0..20 {
+                let tracks: Vec<(Track, i64)> = (0..10)
+                    .map(|i| {
+                        let id = format!("track_{}_{}", batch, i);
+                        (sample_track(&id, &format!("C:/Music/Test/{}.mp3", id)), 0)
+                    })
+                    .collect();
+                writer_db.add_tracks_incremental_batch(&tracks).expect("batch write failed");
+            }
+        });
+
+        // More readers than pooled connections, so some wait for a free one.
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let reader_db = Arc::clone(&db);
+                thread::spawn(move || {
+                    let mut last_seen = 0;
+                    for _ in 0..50 {
+                        let seen = reader_db.get_all_tracks().expect("read failed").len();
+                        assert!(seen >= last_seen, "a reader never goes back in time");
+                        last_seen = seen;
+                        reader_db.get_tracks_page(TrackFilter::default(), 0, 25).expect("page read failed");
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().expect("writer thread panicked");
+        for reader in readers {
+            reader.join().expect("reader thread panicked");
+        }
+        assert_eq!(db.get_all_tracks().unwrap().len(), 200);
+
+        drop(db);
+        cleanup_db_files(&db_path);
+    }
 }
@@ -1,7 +1,17 @@
+use crate::art_cache_format::{encode_for_cache, sniff_mime, stored_settings};
+use crate::art_thumbnail::{make_thumbnail, THUMBNAIL_SIZE};
 use crate::database::Database;
-use rusqlite::{params, params_from_iter, Result};
+use log::warn;
+use rusqlite::{params, params_from_iter, OptionalExtension, Result};
 use std::collections::HashMap;
 
+/// Cached album art and its MIME type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedAlbumArt {
+    pub data: Vec<u8>,
+    pub mime: String,
+}
+
 impl Database {
     // Album art operations (stored in separate track_album_art table)
     pub fn get_album_art(&self, track_id: &str) -> Result<Option<Vec<u8>>> {
@@ -14,16 +24,69 @@ impl Database {
         result.or(Ok(None))
     }
 
+    /// Cached art for a track along with the format it is stored in.
+    pub fn get_album_art_with_mime(&self, track_id: &str) -> Result<Option<CachedAlbumArt>> {
+        let row: Option<(Vec<u8>, Option<String>)> = self
+            .conn()
+            .query_row(
+                "SELECT data, mime FROM track_album_art WHERE track_id = ?1",
+                params![track_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(row.map(|(data, mime)| {
+            let mime = mime.unwrap_or_else(|| sniff_mime(&data).to_string());
+            CachedAlbumArt { data, mime }
+        }))
+    }
+
+    /// Cache `art_data` for a track along with a thumbnail of it, in the
+    /// format chosen in the art cache settings (see `art_cache_format`). Art
+    /// that cannot be decoded is still cached; its thumbnail is left empty.
     pub fn set_album_art(&self, track_id: &str, art_data: &[u8]) -> Result<()> {
+        let thumbnail = thumbnail_or_warn(track_id, art_data);
+        let (data, mime) = encode_for_cache(art_data, &stored_settings(self));
         let conn = self.conn();
         conn.execute(
-            "INSERT INTO track_album_art (track_id, data) VALUES (?1, ?2)
-             ON CONFLICT(track_id) DO UPDATE SET data = excluded.data",
-            params![track_id, art_data],
+            "INSERT INTO track_album_art (track_id, data, thumbnail, mime) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(track_id) DO UPDATE SET
+                data = excluded.data, thumbnail = excluded.thumbnail, mime = excluded.mime",
+            params![track_id, data, thumbnail, mime],
         )?;
         Ok(())
     }
 
+    /// The cached thumbnail for a track, made (and stored) on first request
+    /// for art cached without one. Falls back to the full art if it cannot
+    /// be thumbnailed.
+    pub fn get_album_art_thumbnail(&self, track_id: &str) -> Result<Option<Vec<u8>>> {
+        let row: Option<(Vec<u8>, Option<Vec<u8>>)> = self
+            .conn()
+            .query_row(
+                "SELECT data, thumbnail FROM track_album_art WHERE track_id = ?1",
+                params![track_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((data, thumbnail)) = row else {
+            return Ok(None);
+        };
+        if let Some(thumbnail) = thumbnail {
+            return Ok(Some(thumbnail));
+        }
+        match thumbnail_or_warn(track_id, &data) {
+            Some(thumbnail) => {
+                self.conn().execute(
+                    "UPDATE track_album_art SET thumbnail = ?2 WHERE track_id = ?1",
+                    params![track_id, thumbnail],
+                )?;
+                Ok(Some(thumbnail))
+            }
+            None => Ok(Some(data)),
+        }
+    }
+
     pub fn has_album_art(&self, track_id: &str) -> bool {
         let conn = self.conn();
         let result: Result<i32> = conn.query_row(
@@ -34,12 +97,12 @@ impl Database {
         result.is_ok()
     }
 
-    /// Batch fetch album art blobs for a set of track IDs.
+    /// Batch fetch cached album art, with MIME types, for a set of track IDs.
     /// Returns entries in the same order as `track_ids`, with `None` for misses.
     pub fn get_album_art_batch(
         &self,
         track_ids: &[String],
-    ) -> Result<Vec<(String, Option<Vec<u8>>)>> {
+    ) -> Result<Vec<(String, Option<CachedAlbumArt>)>> {
         if track_ids.is_empty() {
             return Ok(Vec::new());
         }
@@ -48,7 +111,7 @@ impl Database {
             .collect::<Vec<_>>()
             .join(",");
         let sql = format!(
-            "SELECT track_id, data FROM track_album_art WHERE track_id IN ({})",
+            "SELECT track_id, data, mime FROM track_album_art WHERE track_id IN ({})",
             placeholders
         );
 
@@ -56,14 +119,30 @@ impl Database {
         let mut stmt = conn.prepare(&sql)?;
         let fetched = stmt
             .query_map(params_from_iter(track_ids.iter()), |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
             })?
             .collect::<Result<Vec<_>>>()?;
 
-        let by_id: HashMap<String, Vec<u8>> = fetched.into_iter().collect();
+        let by_id: HashMap<String, CachedAlbumArt> = fetched
+            .into_iter()
+            .map(|(id, data, mime)| {
+                let mime = mime.unwrap_or_else(|| sniff_mime(&data).to_string());
+                (id, CachedAlbumArt { data, mime })
+            })
+            .collect();
         Ok(track_ids
             .iter()
             .map(|id| (id.clone(), by_id.get(id).cloned()))
             .collect())
     }
 }
+
+fn thumbnail_or_warn(track_id: &str, art_data: &[u8]) -> Option<Vec<u8>> {
+    make_thumbnail(art_data, THUMBNAIL_SIZE)
+        .map_err(|e| warn!("No thumbnail for {}: {}", track_id, e))
+        .ok()
+}
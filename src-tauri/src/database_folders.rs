@@ -1,7 +1,11 @@
 use crate::database::Database;
+use crate::database_tracks::upsert_track;
 use crate::scanner::Track;
 use log::info;
-use rusqlite::{params, OptionalExtension, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// (id, path, name, date_added, track_count)
+pub type FolderWithCount = (String, String, String, i64, i64);
 
 impl Database {
     fn escape_like_pattern(value: &str) -> String {
@@ -25,8 +29,10 @@ impl Database {
             return (prefix.clone(), prefix);
         }
 
+        // `\\` is an escaped (literal) backslash; a single one would
+        // escape the `%` after it.
         (
-            format!("{}\\%", escaped_base),
+            format!("{}\\\\%", escaped_base),
             format!("{}/%", escaped_base),
         )
     }
@@ -45,6 +51,23 @@ impl Database {
         Ok(count)
     }
 
+    /// Register `folder_path`, or refresh `date_added` if it is already
+    /// registered; an existing row keeps its id.
+    fn upsert_folder(
+        conn: &Connection,
+        folder_id: &str,
+        folder_path: &str,
+        folder_name: &str,
+        date_added: i64,
+    ) -> Result<()> {
+        conn.execute(
+            "INSERT INTO folders (id, path, name, date_added) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(path) DO UPDATE SET date_added = excluded.date_added",
+            params![folder_id, folder_path, folder_name, date_added],
+        )?;
+        Ok(())
+    }
+
     pub fn add_folder(
         &self,
         folder_id: &str,
@@ -53,26 +76,7 @@ impl Database {
         date_added: i64,
     ) -> Result<()> {
         let conn = self.conn();
-
-        // Check if folder with same path already exists
-        let mut stmt = conn.prepare("SELECT id FROM folders WHERE path = ?1")?;
-        let existing_id: Option<String> =
-            stmt.query_row(params![folder_path], |row| row.get(0)).ok();
-
-        if let Some(existing_id) = existing_id {
-            // Update existing folder entry with new date_added
-            conn.execute(
-                "UPDATE folders SET date_added = ?1 WHERE id = ?2",
-                params![date_added, existing_id],
-            )?;
-        } else {
-            // Insert new folder
-            conn.execute(
-                "INSERT INTO folders (id, path, name, date_added) VALUES (?1, ?2, ?3, ?4)",
-                params![folder_id, folder_path, folder_name, date_added],
-            )?;
-        }
-
+        Self::upsert_folder(&conn, folder_id, folder_path, folder_name, date_added)?;
         Ok(())
     }
 
@@ -118,6 +122,39 @@ impl Database {
         Ok(folders)
     }
 
+    /// Folders with the number of tracks under each, most recently added
+    /// first. A track counts when its path continues the folder's path past
+    /// a `/` or `\` separator, so `/Music/A` doesn't claim `/Music/AB`.
+    pub fn get_folders_with_counts(&self) -> Result<Vec<FolderWithCount>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, path, name, date_added FROM folders
+             ORDER BY date_added DESC, path",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        // Counted with the same boundary patterns used to remove a folder's
+        // tracks, so the count matches what removing it would take away.
+        let mut count_stmt = conn.prepare(
+            "SELECT COUNT(*) FROM tracks
+             WHERE path LIKE ?1 ESCAPE '\\' OR path LIKE ?2 ESCAPE '\\'",
+        )?;
+        let folders = rows
+            .into_iter()
+            .map(|(id, path, name, date_added)| {
+                let (backslash_pattern, slash_pattern) = Self::folder_track_patterns(&path);
+                let count = count_stmt.query_row(params![backslash_pattern, slash_pattern], |row| row.get(0))?;
+                Ok((id, path, name, date_added, count))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(folders)
+    }
+
     pub fn remove_folder(&self, folder_id: &str) -> Result<()> {
         let conn = self.conn();
         conn.execute("DELETE FROM folders WHERE id = ?1", params![folder_id])?;
@@ -159,6 +196,8 @@ impl Database {
         Ok(())
     }
 
+    /// Store the folder and its tracks. Returns the stored id of each track,
+    /// in order; a path already in the library keeps its existing id.
     pub fn add_folder_with_tracks(
         &self,
         folder_id: &str,
@@ -166,57 +205,22 @@ impl Database {
         folder_name: &str,
         date_added: i64,
         tracks: &[Track],
-    ) -> Result<()> {
-        // 1. Insert/Update the folder in its own small transaction
+    ) -> Result<Vec<String>> {
+        // 1. Insert/Update the folder on its own
         {
-            let mut conn = self.conn();
-            let tx = conn.transaction()?;
-
-            let mut stmt = tx.prepare("SELECT id FROM folders WHERE path = ?1")?;
-            let existing_id: Option<String> =
-                stmt.query_row(params![folder_path], |row| row.get(0)).ok();
-            drop(stmt);
-
-            if let Some(existing_id) = existing_id {
-                tx.execute(
-                    "UPDATE folders SET date_added = ?1 WHERE id = ?2",
-                    params![date_added, existing_id],
-                )?;
-            } else {
-                tx.execute(
-                    "INSERT INTO folders (id, path, name, date_added) VALUES (?1, ?2, ?3, ?4)",
-                    params![folder_id, folder_path, folder_name, date_added],
-                )?;
-            }
-
-            tx.commit()?;
+            let conn = self.conn();
+            Self::upsert_folder(&conn, folder_id, folder_path, folder_name, date_added)?;
         }
 
         // 2. Insert tracks in chunks, releasing the database Mutex between chunks
         // This prevents massive library scans from freezing UI reads for several seconds.
+        let mut ids = Vec::with_capacity(tracks.len());
         for chunk in tracks.chunks(500) {
             let mut conn = self.conn();
             let tx = conn.transaction()?;
 
             for track in chunk {
-                tx.execute(
-                    "INSERT OR REPLACE INTO tracks (id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, play_count, last_played, rating)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE((SELECT play_count FROM tracks WHERE id = ?1), 0), COALESCE((SELECT last_played FROM tracks WHERE id = ?1), 0), COALESCE((SELECT rating FROM tracks WHERE id = ?1), 0))",
-                    params![
-                        track.id,
-                        track.path,
-                        track.name,
-                        track.title,
-                        track.artist,
-                        track.album,
-                        track.genre,
-                        track.year,
-                        track.track_number,
-                        track.disc_number,
-                        track.duration,
-                        track.date_added,
-                    ],
-                )?;
+                ids.push(upsert_track(&tx, track, None)?);
             }
 
             tx.commit()?;
@@ -224,7 +228,7 @@ impl Database {
             std::thread::yield_now();
         }
 
-        Ok(())
+        Ok(ids)
     }
 
     /// Return just the track IDs whose path starts with `folder_path`.
@@ -239,15 +243,31 @@ impl Database {
         Ok(ids)
     }
 
-    // Get tracks for a specific folder with their modification times
+    /// Id of the folder row registered for `folder_path`, if any.
+    pub fn get_folder_id_by_path(&self, folder_path: &str) -> Result<Option<String>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT id FROM folders WHERE path = ?1",
+            params![folder_path],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    // Get tracks for a specific folder with their modification times.
+    // Only tracks inside the folder match, not those of a sibling that
+    // merely shares its name as a prefix (`Music` vs `Music Videos`).
     pub fn get_folder_tracks(&self, folder_path: &str) -> Result<Vec<(String, String, i64)>> {
         info!("Getting tracks for folder: {}", folder_path);
+        let (backslash_pattern, slash_pattern) = Self::folder_track_patterns(folder_path);
         let conn = self.conn();
-        let mut stmt =
-            conn.prepare("SELECT id, path, file_modified FROM tracks WHERE path LIKE ?1")?;
+        let mut stmt = conn.prepare(
+            "SELECT id, path, file_modified FROM tracks
+             WHERE path LIKE ?1 ESCAPE '\\' OR path LIKE ?2 ESCAPE '\\'",
+        )?;
 
         let tracks = stmt
-            .query_map(params![format!("{}%", folder_path)], |row| {
+            .query_map(params![backslash_pattern, slash_pattern], |row| {
                 Ok((row.get(0)?, row.get(1)?, row.get(2).unwrap_or(0)))
             })?
             .collect::<Result<Vec<_>>>()?;
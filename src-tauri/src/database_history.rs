@@ -0,0 +1,110 @@
+use crate::database::Database;
+use crate::time_utils::now_millis;
+use rusqlite::{params, Result};
+use serde::Serialize;
+
+/// Aggregated listening statistics derived from the `play_history` table.
+///
+/// Day boundaries are evaluated in the machine's local time zone so that a
+/// late-night session counts towards the day the user perceives it in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningStats {
+    pub minutes_today: f64,
+    /// Minutes listened over the seven local calendar days ending today.
+    pub minutes_this_week: f64,
+    /// Consecutive local days with at least one listen, ending today (or
+    /// yesterday, if nothing has been played yet today).
+    pub current_streak_days: u32,
+    /// Number of listens per local hour of the day, index 0 = 00:00–00:59.
+    pub hourly_listens: Vec<u32>,
+}
+
+impl Database {
+    /// Record a single listen of `track_id` at `played_at` (UNIX millis).
+    pub fn add_play_history(&self, track_id: &str, played_at: i64, listened_secs: f64) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO play_history (track_id, played_at, listened_secs) VALUES (?1, ?2, ?3)",
+            params![track_id, played_at, listened_secs],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_listening_stats(&self) -> Result<ListeningStats> {
+        self.get_listening_stats_at(now_millis())
+    }
+
+    /// Compute listening stats as seen at `now` (UNIX millis).
+    pub fn get_listening_stats_at(&self, now: i64) -> Result<ListeningStats> {
+        let conn = self.conn();
+
+        // Local midnight of today / six days ago, converted back to UTC millis
+        // so the range queries can use the played_at index.
+        let (today_start, week_start, today_day): (i64, i64, i64) = conn.query_row(
+            "SELECT
+                CAST(strftime('%s', date(?1 / 1000, 'unixepoch', 'localtime'), 'utc') AS INTEGER) * 1000,
+                CAST(strftime('%s', date(?1 / 1000, 'unixepoch', 'localtime', '-6 days'), 'utc') AS INTEGER) * 1000,
+                CAST(julianday(date(?1 / 1000, 'unixepoch', 'localtime')) AS INTEGER)",
+            params![now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let seconds_between = |start: i64| -> Result<f64> {
+            conn.query_row(
+                "SELECT COALESCE(SUM(listened_secs), 0) FROM play_history
+                 WHERE played_at >= ?1 AND played_at <= ?2",
+                params![start, now],
+                |row| row.get(0),
+            )
+        };
+        let minutes_today = seconds_between(today_start)? / 60.0;
+        let minutes_this_week = seconds_between(week_start)? / 60.0;
+
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT CAST(julianday(date(played_at / 1000, 'unixepoch', 'localtime')) AS INTEGER) AS day
+             FROM play_history
+             WHERE played_at <= ?1
+             ORDER BY day DESC",
+        )?;
+        let days = stmt
+            .query_map(params![now], |row| row.get::<_, i64>(0))?
+            .collect::<Result<Vec<_>>>()?;
+
+        // A streak stays alive until a full local day passes without a listen.
+        let mut current_streak_days = 0u32;
+        let mut expected = match days.first() {
+            Some(&d) if d == today_day || d == today_day - 1 => d,
+            _ => i64::MIN,
+        };
+        for day in days {
+            if day != expected {
+                break;
+            }
+            current_streak_days += 1;
+            expected -= 1;
+        }
+
+        let mut hourly_listens = vec![0u32; 24];
+        let mut stmt = conn.prepare(
+            "SELECT CAST(strftime('%H', played_at / 1000, 'unixepoch', 'localtime') AS INTEGER) AS hour, COUNT(*)
+             FROM play_history
+             WHERE played_at <= ?1
+             GROUP BY hour",
+        )?;
+        let rows = stmt.query_map(params![now], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (hour, count) = row?;
+            if let Some(slot) = hourly_listens.get_mut(hour as usize) {
+                *slot = count as u32;
+            }
+        }
+
+        Ok(ListeningStats {
+            minutes_today,
+            minutes_this_week,
+            current_streak_days,
+            hourly_listens,
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use crate::database::Database;
+use crate::time_utils::now_millis;
+use rusqlite::{params, Result};
+
+impl Database {
+    // Files and folders excluded from scans. An entry covers everything
+    // below it, so a folder entry hides all of its contents.
+    pub fn add_ignored_path(&self, path: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO ignored_paths (path, added_at) VALUES (?1, ?2)",
+            params![path, now_millis()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns whether an entry was removed.
+    pub fn remove_ignored_path(&self, path: &str) -> Result<bool> {
+        let conn = self.conn();
+        let removed = conn.execute("DELETE FROM ignored_paths WHERE path = ?1", params![path])?;
+        Ok(removed > 0)
+    }
+
+    pub fn list_ignored_paths(&self) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT path FROM ignored_paths ORDER BY path")?;
+        let paths = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>>>()?;
+        Ok(paths)
+    }
+}
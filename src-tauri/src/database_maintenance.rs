@@ -0,0 +1,156 @@
+use crate::database::Database;
+use log::{info, warn};
+use rusqlite::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Settings key under which [`AutoVacuumSettings`] are persisted.
+pub const AUTO_VACUUM_KEY: &str = "auto_vacuum";
+
+/// Size above which a vacuum is worth suggesting, and the default
+/// auto-vacuum threshold.
+pub const VACUUM_RECOMMENDED_BYTES: u64 = 10_000_000;
+
+/// How often a deferred startup vacuum checks whether playback stopped.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Vacuum the database on startup once it has grown past `threshold_bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutoVacuumSettings {
+    pub enabled: bool,
+    pub threshold_bytes: u64,
+}
+
+impl Default for AutoVacuumSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_bytes: VACUUM_RECOMMENDED_BYTES,
+        }
+    }
+}
+
+impl AutoVacuumSettings {
+    /// The persisted settings, or the defaults when unset or unreadable.
+    pub fn load(db: &Database) -> Self {
+        match db.get_json_setting::<Self>(AUTO_VACUUM_KEY) {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to read auto-vacuum settings: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether a database of `size_bytes` should be vacuumed.
+    pub fn should_vacuum(&self, size_bytes: u64) -> bool {
+        self.enabled && size_bytes > self.threshold_bytes
+    }
+}
+
+/// What `cleanup_orphans` removed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanCleanup {
+    /// Playlist entries whose track or playlist no longer exists.
+    pub playlist_tracks: usize,
+    /// Cached album art rows whose track no longer exists.
+    pub album_art: usize,
+}
+
+impl Database {
+    /// Delete rows left behind by deletes that missed a cascade, in one
+    /// transaction. Album art is cached in `track_album_art`, so that is the
+    /// only art touched; files on disk are left alone.
+    pub fn cleanup_orphans(&self) -> Result<OrphanCleanup> {
+        let mut cleanup = OrphanCleanup::default();
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        cleanup.playlist_tracks = tx.execute(
+            "DELETE FROM playlist_tracks
+             WHERE track_id NOT IN (SELECT id FROM tracks)
+                OR playlist_id NOT IN (SELECT id FROM playlists)",
+            [],
+        )?;
+        cleanup.album_art = tx.execute(
+            "DELETE FROM track_album_art WHERE track_id NOT IN (SELECT id FROM tracks)",
+            [],
+        )?;
+        tx.commit()?;
+
+        info!(
+            "Removed {} orphaned playlist entries and {} album art rows",
+            cleanup.playlist_tracks, cleanup.album_art
+        );
+        Ok(cleanup)
+    }
+}
+
+impl Database {
+    /// Path of the main database file; `None` for an in-memory database.
+    pub fn file_path(&self) -> Result<Option<PathBuf>> {
+        let file: String = self.read_conn().query_row(
+            "SELECT file FROM pragma_database_list WHERE name = 'main'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok((!file.is_empty()).then(|| PathBuf::from(file)))
+    }
+
+    /// Size of the database file in bytes (pages in use plus free pages).
+    pub fn size_bytes(&self) -> Result<u64> {
+        let size: i64 = self.conn().query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(size.max(0) as u64)
+    }
+
+    /// Rebuild the database file to reclaim free pages. Returns the bytes
+    /// reclaimed. Holds the write connection for the whole run.
+    pub fn vacuum(&self) -> Result<u64> {
+        let before = self.size_bytes()?;
+        self.conn().execute("VACUUM", [])?;
+        let reclaimed = before.saturating_sub(self.size_bytes()?);
+        info!("Database vacuum reclaimed {} bytes ({} -> {})", reclaimed, before, before - reclaimed);
+        Ok(reclaimed)
+    }
+
+    /// Vacuum if `settings` call for it at `size_bytes`. Returns the bytes
+    /// reclaimed, or `None` when no vacuum was due.
+    pub fn auto_vacuum(&self, settings: &AutoVacuumSettings, size_bytes: u64) -> Result<Option<u64>> {
+        if !settings.should_vacuum(size_bytes) {
+            return Ok(None);
+        }
+        info!(
+            "Database is {} bytes, over the {} byte auto-vacuum threshold; vacuuming",
+            size_bytes, settings.threshold_bytes
+        );
+        self.vacuum().map(Some)
+    }
+}
+
+/// Startup auto-vacuum with the persisted settings and the current size.
+/// Meant to run on a background thread; failures are only logged. VACUUM
+/// holds the write connection throughout, so like the manual vacuum it only
+/// starts while nothing is playing, waiting for playback to stop if needed.
+pub fn auto_vacuum_on_startup(db: &Database, is_playing: impl Fn() -> bool) {
+    let settings = AutoVacuumSettings::load(db);
+    if !settings.enabled {
+        return;
+    }
+    if is_playing() {
+        info!("Deferring startup auto-vacuum until playback stops");
+        while is_playing() {
+            std::thread::sleep(IDLE_POLL_INTERVAL);
+        }
+    }
+    let result = db.size_bytes().and_then(|size| db.auto_vacuum(&settings, size));
+    if let Err(e) = result {
+        warn!("Startup auto-vacuum failed: {}", e);
+    }
+}
@@ -18,7 +18,7 @@ impl Database {
     }
 
     pub fn get_all_playlists(&self) -> Result<Vec<(String, String, i64)>> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt =
             conn.prepare("SELECT id, name, created_at FROM playlists ORDER BY created_at DESC")?;
 
@@ -149,7 +149,7 @@ impl Database {
         offset: Option<usize>,
         limit: Option<usize>,
     ) -> Result<Vec<Track>> {
-        let conn = self.conn();
+        let conn = self.read_conn();
 
         let (sql, params_vec): (String, Vec<Box<dyn rusqlite::types::ToSql>>) =
             match (offset, limit) {
@@ -188,8 +188,27 @@ impl Database {
         Ok(tracks)
     }
 
+    /// (track id, path) of the playlist's tracks whose file no longer
+    /// exists, in playlist order.
+    pub fn get_missing_playlist_files(&self, playlist_id: &str) -> Result<Vec<(String, String)>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.path FROM tracks t
+             INNER JOIN playlist_tracks pt ON t.id = pt.track_id
+             WHERE pt.playlist_id = ?1
+             ORDER BY pt.position ASC",
+        )?;
+        let entries = stmt
+            .query_map(params![playlist_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(String, String)>>>()?;
+        Ok(entries
+            .into_iter()
+            .filter(|(_, path)| !std::path::Path::new(path).exists())
+            .collect())
+    }
+
     pub fn get_playlist_track_count(&self, playlist_id: &str) -> Result<i32> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let count: i32 = conn.query_row(
             "SELECT COUNT(*) FROM playlist_tracks WHERE playlist_id = ?1",
             params![playlist_id],
@@ -0,0 +1,80 @@
+//! Read-only connections so library reads don't queue behind writes.
+//!
+//! In WAL mode a reader sees the last committed state while a writer holds
+//! the write lock, so a long scan no longer stalls the library view. Writes
+//! still go one at a time through `Database::conn()`; reads that only
+//! `SELECT` use `Database::read_conn()`, which hands out one of a few
+//! read-only connections and blocks only when all of them are busy.
+
+use log::warn;
+use rusqlite::{Connection, OpenFlags, Result};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Number of read-only connections kept open.
+pub const READ_POOL_SIZE: usize = 4;
+
+pub struct ReadPool {
+    idle: Mutex<Vec<Connection>>,
+    returned: Condvar,
+}
+
+impl ReadPool {
+    /// Open `size` read-only connections to the database at `db_path`,
+    /// which must already exist.
+    pub fn open(db_path: &Path, size: usize) -> Result<Self> {
+        let connections = (0..size.max(1))
+            .map(|_| {
+                let conn = Connection::open_with_flags(
+                    db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )?;
+                conn.execute_batch("PRAGMA busy_timeout=5000;")?;
+                Ok(conn)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { idle: Mutex::new(connections), returned: Condvar::new() })
+    }
+
+    /// Take an idle connection, waiting for one to be returned if none is.
+    pub fn get(&self) -> PooledConnection<'_> {
+        let mut idle = self.lock_idle();
+        loop {
+            if let Some(conn) = idle.pop() {
+                return PooledConnection { pool: self, conn: Some(conn) };
+            }
+            idle = self.returned.wait(idle).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    fn lock_idle(&self) -> MutexGuard<'_, Vec<Connection>> {
+        self.idle.lock().unwrap_or_else(|poisoned| {
+            warn!("Read pool mutex was poisoned — recovering");
+            poisoned.into_inner()
+        })
+    }
+}
+
+/// A read-only connection on loan from a `ReadPool`; returned on drop.
+pub struct PooledConnection<'a> {
+    pool: &'a ReadPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is present until drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.lock_idle().push(conn);
+            self.pool.returned.notify_one();
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use crate::database::Database;
+use crate::database_pool::{ReadPool, READ_POOL_SIZE};
 use log::{info, warn};
 use rusqlite::{params, Connection, Result};
 use std::path::Path;
 use std::sync::Mutex;
 
 /// Current database schema version. Increment when adding migrations.
-const SCHEMA_VERSION: i32 = 9;
+const SCHEMA_VERSION: i32 = 23;
 
 impl Database {
     pub fn new(db_path: &Path) -> Result<Self> {
@@ -33,14 +34,22 @@ impl Database {
                 play_count INTEGER DEFAULT 0,
                 last_played INTEGER DEFAULT 0,
                 rating INTEGER DEFAULT 0,
+                skip_count INTEGER DEFAULT 0,
                 file_modified INTEGER DEFAULT 0,
                 track_gain REAL,
                 track_peak REAL,
                 loudness REAL,
+                replaygain_target REAL,
+                fingerprint TEXT,
+                fingerprint_mtime INTEGER,
+                track_gain_override REAL,
+                last_position REAL,
                 genre TEXT,
                 year INTEGER,
                 track_number INTEGER,
-                disc_number INTEGER
+                disc_number INTEGER,
+                bpm REAL,
+                musical_key TEXT
             )",
             [],
         )?;
@@ -48,7 +57,9 @@ impl Database {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS track_album_art (
                 track_id TEXT PRIMARY KEY REFERENCES tracks(id) ON DELETE CASCADE,
-                data BLOB NOT NULL
+                data BLOB NOT NULL,
+                thumbnail BLOB,
+                mime TEXT
             )",
             [],
         )?;
@@ -62,6 +73,7 @@ impl Database {
                 loudness REAL NOT NULL,
                 track_count INTEGER NOT NULL DEFAULT 0,
                 updated_at INTEGER NOT NULL,
+                target_lufs REAL,
                 PRIMARY KEY (artist, album)
             )",
             [],
@@ -108,6 +120,35 @@ impl Database {
             [],
         )?;
 
+        // Per-listen history used for listening stats
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS play_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                track_id TEXT NOT NULL,
+                played_at INTEGER NOT NULL,
+                listened_secs REAL NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Key/value application settings
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Files and folders the user never wants scanned
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ignored_paths (
+                path TEXT PRIMARY KEY,
+                added_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         // Initialize smart playlists table
         crate::smart_playlists::create_smart_playlist_table(&conn)?;
 
@@ -117,12 +158,22 @@ impl Database {
         // Create indexes for common queries
         Self::create_indexes(&conn);
 
+        // Readers open after the schema exists and WAL is on.
+        let readers = ReadPool::open(db_path, READ_POOL_SIZE)?;
+
         info!("Database initialized successfully");
         Ok(Self {
             conn: Mutex::new(conn),
+            readers,
         })
     }
 
+    /// Schema version recorded in the database (0 for a legacy database).
+    pub fn schema_version(&self) -> Result<i32> {
+        self.read_conn()
+            .query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+    }
+
     /// Run versioned database migrations.
     /// Each migration is idempotent — ALTER TABLE ADD COLUMN is a no-op
     /// if the column already exists (fresh installs include all columns).
@@ -267,6 +318,152 @@ impl Database {
             }
         }
 
+        // Migration v10: Per-listen play history. Deliberately no foreign key
+        // to tracks — rescans replace track rows and must not erase history.
+        if current_version < 10 {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS play_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    track_id TEXT NOT NULL,
+                    played_at INTEGER NOT NULL,
+                    listened_secs REAL NOT NULL DEFAULT 0
+                )",
+                [],
+            )?;
+            info!("Migration v10 complete: play_history table created");
+        }
+
+        // Migration v11: Key/value settings store (shortcuts, playback prefs).
+        if current_version < 11 {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS settings (
+                    key TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                )",
+                [],
+            )?;
+            info!("Migration v11 complete: settings table created");
+        }
+
+        // Migration v12: Record the loudness target each ReplayGain value was
+        // computed against. NULL means the old fixed -18 LUFS target.
+        if current_version < 12 {
+            Self::migrate_add_column(conn, "tracks", "replaygain_target", "REAL", 12)?;
+            Self::migrate_add_column(conn, "album_replaygain", "target_lufs", "REAL", 12)?;
+            info!("Migration v12 complete: ReplayGain target columns");
+        }
+
+        // Migration v13: Cached acoustic fingerprint and the mtime it was taken at.
+        if current_version < 13 {
+            Self::migrate_add_column(conn, "tracks", "fingerprint", "TEXT", 13)?;
+            Self::migrate_add_column(conn, "tracks", "fingerprint_mtime", "INTEGER", 13)?;
+            info!("Migration v13 complete: fingerprint columns");
+        }
+
+        // Migration v14: Manual per-track gain in dB. NULL means no adjustment.
+        if current_version < 14 {
+            Self::migrate_add_column(conn, "tracks", "track_gain_override", "REAL", 14)?;
+            info!("Migration v14 complete: track_gain_override column");
+        }
+
+        // Migration v15: One row per path, so track upserts can match on path
+        // and keep the existing id. Duplicate rows are collapsed first, keeping
+        // the most played copy; playlist entries, history and art of the
+        // dropped copies move to the kept one.
+        if current_version < 15 {
+            conn.execute_batch(
+                "CREATE TEMP TABLE v15_duplicates AS
+                    SELECT old_id, kept_id FROM (
+                        SELECT id AS old_id, FIRST_VALUE(id) OVER (
+                            PARTITION BY path ORDER BY play_count DESC, rowid
+                        ) AS kept_id FROM tracks
+                    ) WHERE old_id != kept_id;
+                 UPDATE OR IGNORE playlist_tracks
+                    SET track_id = (SELECT kept_id FROM v15_duplicates WHERE old_id = track_id)
+                    WHERE track_id IN (SELECT old_id FROM v15_duplicates);
+                 UPDATE play_history
+                    SET track_id = (SELECT kept_id FROM v15_duplicates WHERE old_id = track_id)
+                    WHERE track_id IN (SELECT old_id FROM v15_duplicates);
+                 UPDATE OR IGNORE track_album_art
+                    SET track_id = (SELECT kept_id FROM v15_duplicates WHERE old_id = track_id)
+                    WHERE track_id IN (SELECT old_id FROM v15_duplicates);
+                 DELETE FROM playlist_tracks WHERE track_id IN (SELECT old_id FROM v15_duplicates);
+                 DELETE FROM track_album_art WHERE track_id IN (SELECT old_id FROM v15_duplicates);",
+            )?;
+            let removed = conn.execute(
+                "DELETE FROM tracks WHERE id IN (SELECT old_id FROM v15_duplicates)",
+                [],
+            )?;
+            conn.execute("DROP TABLE v15_duplicates", [])?;
+            conn.execute("DROP INDEX IF EXISTS idx_tracks_path", [])?;
+            conn.execute("CREATE UNIQUE INDEX idx_tracks_path ON tracks(path)", [])?;
+            info!("Migration v15 complete: unique track paths ({} duplicates removed)", removed);
+        }
+
+        // Migration v16: User-maintained list of files/folders excluded from scans.
+        if current_version < 16 {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS ignored_paths (
+                    path TEXT PRIMARY KEY,
+                    added_at INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            info!("Migration v16 complete: ignored_paths table created");
+        }
+
+        // Migration v17: Count of plays abandoned early, for "rarely finished" rules.
+        if current_version < 17 {
+            Self::migrate_add_column(conn, "tracks", "skip_count", "INTEGER DEFAULT 0", 17)?;
+            info!("Migration v17 complete: skip_count column");
+        }
+
+        // Migration v18: Per-track resume position in seconds. NULL = start over.
+        if current_version < 18 {
+            Self::migrate_add_column(conn, "tracks", "last_position", "REAL", 18)?;
+            info!("Migration v18 complete: last_position column");
+        }
+
+        if current_version < 19 {
+            // Thumbnails are filled in lazily for art cached before this version.
+            Self::migrate_add_column(conn, "track_album_art", "thumbnail", "BLOB", 19)?;
+            info!("Migration v19 complete: album art thumbnail column");
+        }
+
+        if current_version < 20 {
+            // NULL for art cached before this version; its type is sniffed on read.
+            Self::migrate_add_column(conn, "track_album_art", "mime", "TEXT", 20)?;
+            info!("Migration v20 complete: album art mime column");
+        }
+
+        if current_version < 21 {
+            Self::migrate_add_column(conn, "tracks", "bpm", "REAL", 21)?;
+            info!("Migration v21 complete: bpm column");
+        }
+
+        if current_version < 22 {
+            Self::migrate_add_column(conn, "tracks", "musical_key", "TEXT", 22)?;
+            info!("Migration v22 complete: musical_key column");
+        }
+
+        // Migration v23: One folder row per path, so rescans can't register a
+        // folder twice. Duplicates are collapsed first, keeping the oldest.
+        if current_version < 23 {
+            let removed = conn.execute(
+                "DELETE FROM folders WHERE rowid NOT IN (
+                    SELECT rowid FROM (
+                        SELECT rowid, ROW_NUMBER() OVER (
+                            PARTITION BY path ORDER BY date_added, rowid
+                        ) AS rn FROM folders
+                    ) WHERE rn = 1
+                )",
+                [],
+            )?;
+            conn.execute("DROP INDEX IF EXISTS idx_folders_path", [])?;
+            conn.execute("CREATE UNIQUE INDEX idx_folders_path ON folders(path)", [])?;
+            info!("Migration v23 complete: unique folder paths ({} duplicates removed)", removed);
+        }
+
         // Update stored schema version
         conn.execute("DELETE FROM schema_version", [])?;
         conn.execute(
@@ -312,7 +509,6 @@ impl Database {
             ("idx_tracks_genre", "tracks(genre)"),
             ("idx_tracks_artist", "tracks(artist)"),
             ("idx_tracks_album", "tracks(album)"),
-            ("idx_tracks_path", "tracks(path)"),
             ("idx_tracks_title_artist_album", "tracks(title, artist, album)"),
             ("idx_tracks_rating", "tracks(rating)"),
             ("idx_tracks_play_count", "tracks(play_count)"),
@@ -320,9 +516,9 @@ impl Database {
             ("idx_tracks_date_added", "tracks(date_added)"),
             ("idx_tracks_duration", "tracks(duration)"),
             ("idx_tracks_year", "tracks(year)"),
-            ("idx_folders_path", "folders(path)"),
             ("idx_playlist_tracks_playlist", "playlist_tracks(playlist_id)"),
             ("idx_playlist_tracks_track", "playlist_tracks(track_id)"),
+            ("idx_play_history_played_at", "play_history(played_at)"),
         ];
 
         for (name, definition) in &indexes {
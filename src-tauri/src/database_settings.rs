@@ -0,0 +1,56 @@
+use crate::database::Database;
+use log::warn;
+use rusqlite::{params, OptionalExtension, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+impl Database {
+    // Key/value application settings. Values are stored as text; structured
+    // settings go through the JSON helpers below.
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+        Ok(())
+    }
+
+    /// Read a JSON-encoded setting. A value that no longer deserializes (e.g.
+    /// after a format change) is logged and treated as unset.
+    pub fn get_json_setting<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let Some(raw) = self.get_setting(key)? else {
+            return Ok(None);
+        };
+        match serde_json::from_str(&raw) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => {
+                warn!("Ignoring unreadable setting '{}': {}", key, e);
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn set_json_setting<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let raw = serde_json::to_string(value)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.set_setting(key, &raw)
+    }
+}
@@ -1,16 +1,77 @@
-use crate::database::{Database, TrackFilter};
+use crate::database::{Database, TrackFilter, TrackMetadataPatch};
 use crate::query_builder::QueryBuilder;
 use crate::scanner::Track;
 use crate::time_utils::now_millis;
 use log::info;
-use rusqlite::{params, Result};
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Durations above this (in seconds) are treated as misread rather than real;
+/// generous enough for long audiobooks stored as a single file.
+pub const MAX_PLAUSIBLE_DURATION_SECS: f64 = 100.0 * 60.0 * 60.0;
+
+/// Facet bucket for tracks with no (or a blank) genre tag.
+pub const UNKNOWN_GENRE: &str = "Unknown";
+
+/// Insert `track`, or refresh the row that already holds its path. The
+/// existing row keeps its id, date added, rating and play history, so
+/// rescans update tracks in place instead of orphaning playlist entries.
+/// A row with the same id at another path is pointed at the new path, as
+/// `INSERT OR REPLACE` used to do. `file_modified` of None leaves a stored
+/// mtime unchanged. Returns the id the row is stored under, which differs
+/// from `track.id` when the path already belonged to another id.
+pub(crate) fn upsert_track(conn: &Connection, track: &Track, file_modified: Option<i64>) -> Result<String> {
+    conn.query_row(
+        "INSERT INTO tracks (id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, file_modified)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE(?13, 0))
+         ON CONFLICT(path) DO UPDATE SET
+            name = excluded.name,
+            title = excluded.title,
+            artist = excluded.artist,
+            album = excluded.album,
+            genre = excluded.genre,
+            year = excluded.year,
+            track_number = excluded.track_number,
+            disc_number = excluded.disc_number,
+            duration = excluded.duration,
+            file_modified = COALESCE(?13, file_modified)
+         ON CONFLICT(id) DO UPDATE SET
+            path = excluded.path,
+            name = excluded.name,
+            title = excluded.title,
+            artist = excluded.artist,
+            album = excluded.album,
+            genre = excluded.genre,
+            year = excluded.year,
+            track_number = excluded.track_number,
+            disc_number = excluded.disc_number,
+            duration = excluded.duration,
+            file_modified = COALESCE(?13, file_modified)
+         RETURNING id",
+        params![
+            track.id,
+            track.path,
+            track.name,
+            track.title,
+            track.artist,
+            track.album,
+            track.genre,
+            track.year,
+            track.track_number,
+            track.disc_number,
+            track.duration,
+            track.date_added,
+            file_modified,
+        ],
+        |row| row.get(0),
+    )
+}
 
 impl Database {
     pub fn get_tracks_page(&self, filter: TrackFilter, offset: usize, limit: usize) -> Result<(Vec<Track>, usize)> {
         let mut qb = QueryBuilder::new();
         qb.apply_track_filter(&filter);
 
-        let conn = self.conn();
+        let conn = self.read_conn();
 
         let count_sql = format!("SELECT COUNT(*) FROM tracks{}", qb.where_sql());
         let total: i64 = conn.query_row(
@@ -38,30 +99,13 @@ impl Database {
 
     pub fn add_track(&self, track: &Track) -> Result<()> {
         let conn = self.conn();
-        conn.execute(
-            "INSERT OR REPLACE INTO tracks (id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, play_count, last_played, rating)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE((SELECT play_count FROM tracks WHERE id = ?1), 0), COALESCE((SELECT last_played FROM tracks WHERE id = ?1), 0), COALESCE((SELECT rating FROM tracks WHERE id = ?1), 0))",
-            params![
-                track.id,
-                track.path,
-                track.name,
-                track.title,
-                track.artist,
-                track.album,
-                track.genre,
-                track.year,
-                track.track_number,
-                track.disc_number,
-                track.duration,
-                track.date_added,
-            ],
-        )?;
+        upsert_track(&conn, track, None)?;
         Ok(())
     }
 
     pub fn get_all_tracks(&self) -> Result<Vec<Track>> {
         info!("Fetching all tracks from database");
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(&format!(
             "SELECT {} FROM tracks",
             crate::scanner::TRACK_SELECT_COLUMNS
@@ -83,7 +127,7 @@ impl Database {
             qb.order_sql(),
         );
 
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(&sql)?;
 
         let tracks = stmt
@@ -105,6 +149,23 @@ impl Database {
             "UPDATE tracks SET play_count = play_count + 1, last_played = ?1 WHERE id = ?2",
             params![now, track_id],
         )?;
+        // Feed the listening-stats history; a completed play counts the full track length.
+        conn.execute(
+            "INSERT INTO play_history (track_id, played_at, listened_secs)
+             SELECT id, ?1, duration FROM tracks WHERE id = ?2",
+            params![now, track_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record that playback of `track_id` was abandoned early. Play count
+    /// and history are unaffected.
+    pub fn increment_skip_count(&self, track_id: &str) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET skip_count = skip_count + 1 WHERE id = ?1",
+            params![track_id],
+        )?;
         Ok(())
     }
 
@@ -118,7 +179,7 @@ impl Database {
     }
 
     pub fn get_recently_played(&self, limit: usize) -> Result<Vec<Track>> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(&format!(
             "SELECT {} FROM tracks WHERE last_played > 0 ORDER BY last_played DESC LIMIT ?1",
             crate::scanner::TRACK_SELECT_COLUMNS
@@ -132,7 +193,7 @@ impl Database {
     }
 
     pub fn get_most_played(&self, limit: usize) -> Result<Vec<Track>> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(&format!(
             "SELECT {} FROM tracks WHERE play_count > 0 ORDER BY play_count DESC LIMIT ?1",
             crate::scanner::TRACK_SELECT_COLUMNS
@@ -145,6 +206,83 @@ impl Database {
         Ok(tracks)
     }
 
+    /// Tracks that have never been played, oldest additions first.
+    pub fn get_unplayed_tracks(&self, limit: usize) -> Result<Vec<Track>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tracks WHERE play_count = 0 ORDER BY date_added ASC, id LIMIT ?1",
+            crate::scanner::TRACK_SELECT_COLUMNS
+        ))?;
+
+        let tracks = stmt
+            .query_map(params![limit], Track::from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(tracks)
+    }
+
+    /// Tracks whose stored duration is zero, negative or implausibly long,
+    /// usually from a truncated file or a failed duration read at scan time.
+    pub fn get_suspect_tracks(&self) -> Result<Vec<Track>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tracks WHERE duration <= 0 OR duration > ?1 ORDER BY path",
+            crate::scanner::TRACK_SELECT_COLUMNS
+        ))?;
+
+        let tracks = stmt
+            .query_map(params![MAX_PLAUSIBLE_DURATION_SECS], Track::from_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(tracks)
+    }
+
+    /// Distinct genres with their track counts, alphabetical (ignoring case),
+    /// with untagged tracks bucketed as [`UNKNOWN_GENRE`] at the end.
+    pub fn get_genres(&self) -> Result<Vec<(String, usize)>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(
+            "SELECT COALESCE(NULLIF(TRIM(genre), ''), ?1) AS g, COUNT(*) FROM tracks
+             GROUP BY g ORDER BY g = ?1, g COLLATE NOCASE, g",
+        )?;
+
+        let genres = stmt
+            .query_map(params![UNKNOWN_GENRE], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(genres)
+    }
+
+    /// Distinct years with their track counts, newest first, with untagged
+    /// tracks bucketed as year 0 at the end.
+    pub fn get_years(&self) -> Result<Vec<(i32, usize)>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(
+            "SELECT CASE WHEN year > 0 THEN year ELSE 0 END AS y, COUNT(*) FROM tracks
+             GROUP BY y ORDER BY y = 0, y DESC",
+        )?;
+
+        let years = stmt
+            .query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(years)
+    }
+
+    pub fn count_tracks(&self) -> Result<usize> {
+        let conn = self.read_conn();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM tracks", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn count_unplayed_tracks(&self) -> Result<usize> {
+        let conn = self.read_conn();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM tracks WHERE play_count = 0", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
     // Star rating for tracks
     pub fn set_track_rating(&self, track_id: &str, rating: i32) -> Result<()> {
         let conn = self.conn();
@@ -158,7 +296,7 @@ impl Database {
 
     // Get all track paths for validation
     pub fn get_all_track_paths(&self) -> Result<Vec<(String, String)>> {
-        let conn = self.conn();
+        let conn = self.read_conn();
         let mut stmt = conn.prepare("SELECT id, path FROM tracks")?;
 
         let paths = stmt
@@ -198,8 +336,125 @@ impl Database {
         Ok(())
     }
 
-    pub fn get_track_by_path(&self, path: &str) -> Result<Option<Track>> {
+    /// Overwrite only the metadata columns set in `patch`.
+    pub fn patch_track_metadata(&self, track_id: &str, patch: &TrackMetadataPatch) -> Result<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET
+                title = COALESCE(?1, title),
+                artist = COALESCE(?2, artist),
+                album = COALESCE(?3, album),
+                genre = COALESCE(?4, genre),
+                year = COALESCE(?5, year),
+                track_number = COALESCE(?6, track_number),
+                disc_number = COALESCE(?7, disc_number)
+             WHERE id = ?8",
+            params![
+                patch.title,
+                patch.artist,
+                patch.album,
+                patch.genre,
+                patch.year,
+                patch.track_number,
+                patch.disc_number,
+                track_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_track_by_id(&self, track_id: &str) -> Result<Option<Track>> {
+        let conn = self.read_conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM tracks WHERE id = ?1",
+            crate::scanner::TRACK_SELECT_COLUMNS
+        ))?;
+
+        let mut rows = stmt.query(params![track_id])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(Track::from_row(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Manual gain adjustment (dB) for the track at `path`, if one is set.
+    pub fn get_track_gain_override(&self, path: &str) -> Result<Option<f64>> {
         let conn = self.conn();
+        conn.query_row(
+            "SELECT track_gain_override FROM tracks WHERE path = ?1",
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()
+        .map(Option::flatten)
+    }
+
+    /// Set or clear (None) a track's manual gain adjustment. Returns the
+    /// number of rows updated, so 0 means the track does not exist.
+    pub fn set_track_gain_override(&self, track_id: &str, gain_db: Option<f64>) -> Result<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET track_gain_override = ?1 WHERE id = ?2",
+            params![gain_db, track_id],
+        )
+    }
+
+    /// Point a track at a moved/renamed file: updates both `path` and the
+    /// display `name` (file name). The id — and everything keyed by it, such
+    /// as album art and play history — is unchanged.
+    pub fn update_track_location(&self, track_id: &str, new_path: &str, new_name: &str) -> Result<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET path = ?1, name = ?2 WHERE id = ?3",
+            params![new_path, new_name, track_id],
+        )
+    }
+
+    /// Overwrite the tag-derived columns of `track.id` with `track`'s values.
+    /// Play history, rating and playlist membership are left alone.
+    pub fn refresh_track_tags(&self, track: &Track) -> Result<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET title = ?1, artist = ?2, album = ?3, genre = ?4, year = ?5,
+                track_number = ?6, disc_number = ?7, duration = ?8 WHERE id = ?9",
+            params![
+                track.title,
+                track.artist,
+                track.album,
+                track.genre,
+                track.year,
+                track.track_number,
+                track.disc_number,
+                track.duration,
+                track.id
+            ],
+        )
+    }
+
+    /// Remember where playback of `track_id` stopped; None forgets it.
+    pub fn set_track_position(&self, track_id: &str, secs: Option<f64>) -> Result<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE tracks SET last_position = ?1 WHERE id = ?2",
+            params![secs, track_id],
+        )
+    }
+
+    pub fn get_track_position(&self, track_id: &str) -> Result<Option<f64>> {
+        let conn = self.conn();
+        let position: Option<Option<f64>> = conn
+            .query_row(
+                "SELECT last_position FROM tracks WHERE id = ?1",
+                params![track_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(position.flatten())
+    }
+
+    pub fn get_track_by_path(&self, path: &str) -> Result<Option<Track>> {
+        let conn = self.read_conn();
         let mut stmt = conn.prepare(&format!(
             "SELECT {} FROM tracks WHERE path = ?1",
             crate::scanner::TRACK_SELECT_COLUMNS
@@ -216,7 +471,7 @@ impl Database {
     // Find duplicate tracks based on metadata similarity
     pub fn find_duplicates(&self) -> Result<Vec<Vec<Track>>> {
         info!("Searching for duplicate tracks");
-        let conn = self.conn();
+        let conn = self.read_conn();
 
         // Step 1: SQL finds (title, artist, album) combos that appear more than once.
         // This avoids loading the entire tracks table into memory.
@@ -322,28 +577,22 @@ impl Database {
         Ok(())
     }
 
+    /// Remove several tracks in one transaction; returns how many existed.
+    pub fn remove_tracks(&self, track_ids: &[String]) -> Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        for track_id in track_ids {
+            removed += tx.execute("DELETE FROM tracks WHERE id = ?1", params![track_id])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
     // Update track with file modification time
     pub fn add_track_with_mtime(&self, track: &Track, file_modified: i64) -> Result<()> {
         let conn = self.conn();
-        conn.execute(
-            "INSERT OR REPLACE INTO tracks (id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, play_count, last_played, rating, file_modified)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE((SELECT play_count FROM tracks WHERE id = ?1), 0), COALESCE((SELECT last_played FROM tracks WHERE id = ?1), 0), COALESCE((SELECT rating FROM tracks WHERE id = ?1), 0), ?13)",
-            params![
-                track.id,
-                track.path,
-                track.name,
-                track.title,
-                track.artist,
-                track.album,
-                track.genre,
-                track.year,
-                track.track_number,
-                track.disc_number,
-                track.duration,
-                track.date_added,
-                file_modified,
-            ],
-        )?;
+        upsert_track(&conn, track, Some(file_modified))?;
         Ok(())
     }
 
@@ -351,44 +600,22 @@ impl Database {
     ///
     /// Each element is `(track, file_modified)`. Wrapping all writes in one
     /// transaction is orders-of-magnitude faster than per-track implicit
-    /// transactions for large incremental scans.
-    pub fn add_tracks_incremental_batch(&self, tracks: &[(Track, i64)]) -> Result<usize> {
-        if tracks.is_empty() {
-            return Ok(0);
-        }
-        
-        let mut count = 0;
+    /// transactions for large incremental scans. Returns the stored id of
+    /// each track, in order (see [`upsert_track`]).
+    pub fn add_tracks_incremental_batch(&self, tracks: &[(Track, i64)]) -> Result<Vec<String>> {
+        let mut ids = Vec::with_capacity(tracks.len());
         
         for chunk in tracks.chunks(500) {
             let mut conn = self.conn();
             let tx = conn.transaction()?;
             
             for (track, file_modified) in chunk {
-                tx.execute(
-                    "INSERT OR REPLACE INTO tracks (id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, play_count, last_played, rating, file_modified)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, COALESCE((SELECT play_count FROM tracks WHERE id = ?1), 0), COALESCE((SELECT last_played FROM tracks WHERE id = ?1), 0), COALESCE((SELECT rating FROM tracks WHERE id = ?1), 0), ?13)",
-                    params![
-                        track.id,
-                        track.path,
-                        track.name,
-                        track.title,
-                        track.artist,
-                        track.album,
-                        track.genre,
-                        track.year,
-                        track.track_number,
-                        track.disc_number,
-                        track.duration,
-                        track.date_added,
-                        file_modified,
-                    ],
-                )?;
-                count += 1;
+                ids.push(upsert_track(&tx, track, Some(*file_modified))?);
             }
             tx.commit()?;
             // Explicitly yield to give the OS a chance to let the UI thread acquire the mutex
             std::thread::yield_now();
         }
-        Ok(count)
+        Ok(ids)
     }
 }
@@ -0,0 +1,84 @@
+//! Environment snapshot for support requests.
+//!
+//! Everything here comes from existing helpers; [`Diagnostics::collect`]
+//! only gathers it into one serializable struct. The player fills in the
+//! output device and format, which only it knows.
+
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+
+use crate::database::Database;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDiagnostics {
+    /// Name of the audio host (WASAPI, CoreAudio, ALSA, ...).
+    pub host: String,
+    pub devices: Vec<String>,
+    pub default_device: Option<String>,
+    /// Device the player has open, if any.
+    pub output_device: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+
+impl AudioDiagnostics {
+    /// Host and device list as the OS reports them; output fields empty.
+    pub fn from_host() -> Self {
+        let host = rodio::cpal::default_host();
+        let devices = host
+            .output_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default();
+        Self {
+            host: host.id().name().to_string(),
+            devices,
+            default_device: host.default_output_device().and_then(|d| d.name().ok()),
+            output_device: None,
+            sample_rate: None,
+            channels: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseDiagnostics {
+    pub path: Option<String>,
+    pub size_bytes: u64,
+    pub schema_version: i32,
+    pub track_count: usize,
+}
+
+impl DatabaseDiagnostics {
+    pub fn collect(db: &Database) -> rusqlite::Result<Self> {
+        Ok(Self {
+            path: db.file_path()?.map(|p| p.to_string_lossy().to_string()),
+            size_bytes: db.size_bytes()?,
+            schema_version: db.schema_version()?,
+            track_count: db.count_tracks()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub audio: AudioDiagnostics,
+    pub database: DatabaseDiagnostics,
+}
+
+impl Diagnostics {
+    pub fn collect(db: &Database, audio: AudioDiagnostics) -> rusqlite::Result<Self> {
+        Ok(Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            audio,
+            database: DatabaseDiagnostics::collect(db)?,
+        })
+    }
+}
@@ -7,11 +7,16 @@ const DSP_BLOCK_PROCESSING_ENV: &str = "VPLAYER_DSP_BLOCK_PROCESSING";
  * Audio DSP effects module
  * 
  * Provides high-quality real-time audio effects processing:
- * - 10-band Equalizer (Biquad IIR)
+ * - High-pass / low-pass / notch cleanup filters
+ * - Parametric graphic Equalizer (Biquad IIR, 10 bands by default)
  * - Tempo/speed control (applied at Sink level)
  * - Reverb (Freeverb-style Schroeder-Moorer)
  * - Bass boost (Low-shelf)
+ * - Bass/treble tone control (shelf pair, independent of the EQ)
+ * - Loudness compensation (volume-dependent equal-loudness shelves)
  * - Echo/delay (Feedback delay)
+ * - Crossfeed (headphone spatialization)
+ * - Lookahead peak limiter (optional, adds latency)
  * - Soft Clipper (Limiter)
  *
  * The processing order is configurable via `effect_order`.
@@ -46,16 +51,87 @@ pub struct EffectsConfig {
     pub echo_delay: f32,       // Echo delay in seconds
     pub echo_feedback: f32,    // Echo feedback (0.0 to 0.9)
     pub echo_mix: f32,         // Echo wet/dry mix (0.0 to 1.0)
-    pub eq_bands: [f32; 10],   // 10-band EQ gains in dB (-12.0 to +12.0)
+    /// EQ gains in dB (-12.0 to +12.0), one per entry of `eq_layout`. The
+    /// old fixed 10-element array deserializes into this unchanged.
+    pub eq_bands: Vec<f32>,
+    /// EQ band layout. Defaults to the classic 10 bands.
+    #[serde(default = "default_eq_layout")]
+    pub eq_layout: Vec<EqBand>,
     /// Processing chain order. Soft clipper always runs last.
     #[serde(default = "default_effect_order")]
     pub effect_order: Vec<EffectId>,
+    /// Headphone crossfeed (stereo only). Runs after the ordered chain.
+    #[serde(default)]
+    pub crossfeed_enabled: bool,
+    #[serde(default = "default_crossfeed_strength")]
+    pub crossfeed_strength: f32, // Crossfeed amount (0.0 to 1.0)
+    /// Global high-pass cutoff in Hz, e.g. to remove rumble (0.0 = off).
+    #[serde(default)]
+    pub highpass_freq: f32,
+    /// Global low-pass cutoff in Hz (0.0 = off).
+    #[serde(default)]
+    pub lowpass_freq: f32,
+    /// Optional notch, e.g. to kill 50/60 Hz mains hum.
+    #[serde(default)]
+    pub notch: Option<NotchConfig>,
+    /// Tone control shelf gains in dB (-12.0 to +12.0, 0.0 = bypass).
+    /// Independent of the graphic EQ; runs after the cleanup filters.
+    #[serde(default)]
+    pub bass_db: f32,
+    #[serde(default)]
+    pub treble_db: f32,
+    /// Lookahead peak limiter window in milliseconds (0.0 = off).
+    /// Adds exactly this much latency to the output.
+    #[serde(default)]
+    pub limiter_lookahead_ms: f32,
+}
+
+/// Centre frequency and Q of the notch filter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NotchConfig {
+    pub freq: f32,
+    pub q: f32,
+}
+
+/// One EQ band: centre frequency in Hz and Q. In layouts of two or more
+/// bands the first acts as a low shelf and the last as a high shelf.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub freq: f32,
+    pub q: f32,
+}
+
+/// Classic 10-band layout.
+pub const DEFAULT_EQ_FREQUENCIES: [f32; 10] =
+    [60.0, 170.0, 310.0, 600.0, 1000.0, 3000.0, 6000.0, 12000.0, 14000.0, 16000.0];
+
+impl EqBand {
+    /// Layout with the shelf Q on the outer bands and `q` on the peaks.
+    pub fn layout(frequencies: &[f32], q: f32) -> Vec<EqBand> {
+        let last = frequencies.len().saturating_sub(1);
+        frequencies
+            .iter()
+            .enumerate()
+            .map(|(i, &freq)| EqBand {
+                freq,
+                q: if last > 0 && (i == 0 || i == last) { 0.707 } else { q },
+            })
+            .collect()
+    }
+}
+
+fn default_eq_layout() -> Vec<EqBand> {
+    EqBand::layout(&DEFAULT_EQ_FREQUENCIES, 1.41)
 }
 
 fn default_effect_order() -> Vec<EffectId> {
     DEFAULT_EFFECT_ORDER.to_vec()
 }
 
+fn default_crossfeed_strength() -> f32 {
+    0.5
+}
+
 impl Default for EffectsConfig {
     fn default() -> Self {
         Self {
@@ -66,8 +142,17 @@ impl Default for EffectsConfig {
             echo_delay: 0.3,
             echo_feedback: 0.3,
             echo_mix: 0.0,
-            eq_bands: [0.0; 10],
+            eq_bands: vec![0.0; DEFAULT_EQ_FREQUENCIES.len()],
+            eq_layout: default_eq_layout(),
             effect_order: DEFAULT_EFFECT_ORDER.to_vec(),
+            crossfeed_enabled: false,
+            crossfeed_strength: default_crossfeed_strength(),
+            highpass_freq: 0.0,
+            lowpass_freq: 0.0,
+            notch: None,
+            bass_db: 0.0,
+            treble_db: 0.0,
+            limiter_lookahead_ms: 0.0,
         }
     }
 }
@@ -131,6 +216,63 @@ impl BiquadFilter {
         self.b2 = a2 / a0;
     }
 
+    pub fn set_highpass(&mut self, sample_rate: u32, freq: f32, q: f32) {
+        let w0 = 2.0 * PI * freq / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+
+        let b0 = (1.0 + cos_w0) / 2.0;
+        let b1 = -(1.0 + cos_w0);
+        let b2 = (1.0 + cos_w0) / 2.0;
+        let a0 = 1.0 + alpha;
+        let a1 = -2.0 * cos_w0;
+        let a2 = 1.0 - alpha;
+
+        self.a0 = b0 / a0;
+        self.a1 = b1 / a0;
+        self.a2 = b2 / a0;
+        self.b1 = a1 / a0;
+        self.b2 = a2 / a0;
+    }
+
+    pub fn set_lowpass(&mut self, sample_rate: u32, freq: f32, q: f32) {
+        let w0 = 2.0 * PI * freq / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+
+        let b0 = (1.0 - cos_w0) / 2.0;
+        let b1 = 1.0 - cos_w0;
+        let b2 = (1.0 - cos_w0) / 2.0;
+        let a0 = 1.0 + alpha;
+        let a1 = -2.0 * cos_w0;
+        let a2 = 1.0 - alpha;
+
+        self.a0 = b0 / a0;
+        self.a1 = b1 / a0;
+        self.a2 = b2 / a0;
+        self.b1 = a1 / a0;
+        self.b2 = a2 / a0;
+    }
+
+    pub fn set_notch(&mut self, sample_rate: u32, freq: f32, q: f32) {
+        let w0 = 2.0 * PI * freq / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+
+        let b0 = 1.0;
+        let b1 = -2.0 * cos_w0;
+        let b2 = 1.0;
+        let a0 = 1.0 + alpha;
+        let a1 = -2.0 * cos_w0;
+        let a2 = 1.0 - alpha;
+
+        self.a0 = b0 / a0;
+        self.a1 = b1 / a0;
+        self.a2 = b2 / a0;
+        self.b1 = a1 / a0;
+        self.b2 = a2 / a0;
+    }
+
     pub fn set_highshelf(&mut self, sample_rate: u32, freq: f32, q: f32, gain_db: f32) {
         let w0 = 2.0 * PI * freq / sample_rate as f32;
         let a = 10_f32.powf(gain_db / 40.0);
@@ -163,42 +305,46 @@ impl BiquadFilter {
     }
 }
 
-/// 10-band Equalizer
+/// Graphic equalizer over an arbitrary band layout
 pub struct Equalizer {
     filters: Vec<BiquadFilter>,
-    frequencies: [f32; 10],
+    bands: Vec<EqBand>,
     sample_rate: u32,
 }
 
 impl Equalizer {
-    pub fn new(sample_rate: u32) -> Self {
-        let frequencies = [60.0, 170.0, 310.0, 600.0, 1000.0, 3000.0, 6000.0, 12000.0, 14000.0, 16000.0];
-        let mut filters = Vec::with_capacity(10);
-        
-        for _ in 0..10 {
-            filters.push(BiquadFilter::new());
-        }
-
+    pub fn new(sample_rate: u32, bands: &[EqBand]) -> Self {
         let mut eq = Self {
-            filters,
-            frequencies,
+            filters: vec![BiquadFilter::new(); bands.len()],
+            bands: bands.to_vec(),
             sample_rate,
         };
-        
-        eq.update_gains(&[0.0; 10]);
+
+        eq.update_gains(&[]);
         eq
     }
 
-    pub fn update_gains(&mut self, gains: &[f32; 10]) {
-        for (i, &gain) in gains.iter().enumerate() {
-            let freq = self.frequencies[i];
-            
-            if i == 0 {
-                self.filters[i].set_lowshelf(self.sample_rate, freq, 0.707, gain);
-            } else if i == 9 {
-                self.filters[i].set_highshelf(self.sample_rate, freq, 0.707, gain);
+    pub fn bands(&self) -> &[EqBand] {
+        &self.bands
+    }
+
+    /// Recompute coefficients. Missing gains count as 0 dB, extra gains are
+    /// ignored, and bands above Nyquist are pulled down to keep the filters
+    /// stable at low sample rates.
+    pub fn update_gains(&mut self, gains: &[f32]) {
+        let last = self.bands.len().saturating_sub(1);
+        let max_freq = self.sample_rate as f32 * 0.45;
+        for (i, band) in self.bands.iter().enumerate() {
+            let gain = gains.get(i).copied().unwrap_or(0.0);
+            let freq = band.freq.clamp(1.0, max_freq);
+            let q = band.q.max(0.1);
+
+            if last > 0 && i == 0 {
+                self.filters[i].set_lowshelf(self.sample_rate, freq, q, gain);
+            } else if last > 0 && i == last {
+                self.filters[i].set_highshelf(self.sample_rate, freq, q, gain);
             } else {
-                self.filters[i].set_peaking(self.sample_rate, freq, 1.41, gain);
+                self.filters[i].set_peaking(self.sample_rate, freq, q, gain);
             }
         }
     }
@@ -479,6 +625,65 @@ impl BassBoost {
     }
 }
 
+/// Bass/treble tone control: a low-shelf and high-shelf pair, like the two
+/// knobs on a hi-fi amp. A shelf at 0 dB is skipped entirely.
+pub struct ToneControl {
+    bass: BiquadFilter,
+    treble: BiquadFilter,
+    bass_db: f32,
+    treble_db: f32,
+}
+
+impl ToneControl {
+    const BASS_FREQ: f32 = 120.0;
+    const TREBLE_FREQ: f32 = 8000.0;
+
+    pub fn new(sample_rate: u32, bass_db: f32, treble_db: f32) -> Self {
+        let mut tone = Self {
+            bass: BiquadFilter::new(),
+            treble: BiquadFilter::new(),
+            bass_db: 0.0,
+            treble_db: 0.0,
+        };
+        tone.set_gains(sample_rate, bass_db, treble_db);
+        tone
+    }
+
+    pub fn set_gains(&mut self, sample_rate: u32, bass_db: f32, treble_db: f32) {
+        self.bass_db = bass_db;
+        self.treble_db = treble_db;
+        let treble_freq = Self::TREBLE_FREQ.min(sample_rate as f32 * 0.45);
+        self.bass.set_lowshelf(sample_rate, Self::BASS_FREQ, 0.707, bass_db);
+        self.treble.set_highshelf(sample_rate, treble_freq, 0.707, treble_db);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.bass_db != 0.0 || self.treble_db != 0.0
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let mut output = input;
+        if self.bass_db != 0.0 {
+            output = self.bass.process(output);
+        }
+        if self.treble_db != 0.0 {
+            output = self.treble.process(output);
+        }
+        output
+    }
+}
+
+/// Equal-loudness ("loudness" button) shelf gains in dB for a user volume
+/// of 0.0–1.0. The ear loses bass, and to a lesser degree treble, as level
+/// drops, so the shelves grow with the attenuation: none at full volume,
+/// about +7 dB bass / +3.5 dB treble at 0.2.
+pub fn loudness_contour_db(volume: f32) -> (f32, f32) {
+    let attenuation_db = -20.0 * volume.clamp(0.001, 1.0).log10();
+    let bass_db = (attenuation_db * 0.5).min(12.0);
+    let treble_db = (attenuation_db * 0.25).min(6.0);
+    (bass_db, treble_db)
+}
+
 /// Soft Clipper / Limiter
 /// Prevents harsh digital clipping by rounding off peaks
 pub struct SoftClipper;
@@ -513,17 +718,268 @@ impl SoftClipper {
     }
 }
 
+/// Bauer-style headphone crossfeed.
+///
+/// Each channel receives a low-passed, slightly delayed copy of the opposite
+/// channel, approximating the path from a loudspeaker to the far ear. Only
+/// meaningful for interleaved stereo frames.
+pub struct Crossfeed {
+    lowpass_coeff: f32,
+    lowpass_state: [f32; 2],
+    delay_lines: [Vec<f32>; 2],
+    write_pos: usize,
+}
+
+impl Crossfeed {
+    const CUTOFF_HZ: f32 = 700.0;
+    const DELAY_SECONDS: f32 = 0.0003;
+
+    pub fn new(sample_rate: u32) -> Self {
+        let delay = ((sample_rate as f32 * Self::DELAY_SECONDS).round() as usize).max(1);
+        Self {
+            lowpass_coeff: 1.0 - (-2.0 * PI * Self::CUTOFF_HZ / sample_rate as f32).exp(),
+            lowpass_state: [0.0; 2],
+            delay_lines: [vec![0.0; delay], vec![0.0; delay]],
+            write_pos: 0,
+        }
+    }
+
+    /// Process one stereo frame. `strength` (0.0–1.0) controls how much of the
+    /// opposite channel bleeds across; the result is rescaled so the overall
+    /// level stays roughly constant.
+    pub fn process_frame(&mut self, left: f32, right: f32, strength: f32) -> (f32, f32) {
+        let feed = strength.clamp(0.0, 1.0) * 0.5;
+        let mut crossed = [0.0_f32; 2];
+        for (ch, input) in [left, right].into_iter().enumerate() {
+            let state = &mut self.lowpass_state[ch];
+            *state += self.lowpass_coeff * (input - *state);
+            if state.abs() < 1e-15 { *state = 0.0; }
+            crossed[ch] = self.delay_lines[ch][self.write_pos];
+            self.delay_lines[ch][self.write_pos] = *state;
+        }
+        self.write_pos = (self.write_pos + 1) % self.delay_lines[0].len();
+
+        let norm = 1.0 / (1.0 + feed);
+        ((left + crossed[1] * feed) * norm, (right + crossed[0] * feed) * norm)
+    }
+}
+
+/// Lookahead peak limiter.
+///
+/// Delays the signal by `lookahead` frames so the gain can be pulled down
+/// before a peak arrives instead of clipping it. Channels are linked (one
+/// gain for the whole frame) to keep the stereo image stable.
+pub struct LookaheadLimiter {
+    lookahead: usize,
+    channels: usize,
+    /// Ring buffer of `lookahead` interleaved frames.
+    delay: Vec<f32>,
+    /// Frame index of the oldest entry in `delay`.
+    pos: usize,
+    gain: f32,
+    release_coeff: f32,
+}
+
+impl LookaheadLimiter {
+    const THRESHOLD: f32 = 0.98;
+    const RELEASE_SECONDS: f32 = 0.05;
+
+    pub fn new(sample_rate: u32, channels: usize, lookahead_ms: f32) -> Self {
+        let lookahead = (sample_rate as f32 * lookahead_ms.max(0.0) / 1000.0).round() as usize;
+        let channels = channels.max(1);
+        Self {
+            lookahead,
+            channels,
+            delay: vec![0.0; lookahead * channels],
+            pos: 0,
+            gain: 1.0,
+            release_coeff: 1.0 - (-1.0 / (sample_rate as f32 * Self::RELEASE_SECONDS)).exp(),
+        }
+    }
+
+    /// Latency introduced by the limiter, in frames.
+    pub fn latency_frames(&self) -> usize {
+        self.lookahead
+    }
+
+    /// Limit an interleaved buffer in place. Trailing partial frames are left untouched.
+    pub fn process(&mut self, buffer: &mut [f32]) {
+        if self.lookahead == 0 {
+            return;
+        }
+        let ch = self.channels;
+        for frame in buffer.chunks_exact_mut(ch) {
+            let slot = self.pos * ch;
+
+            // Peak over every frame still waiting in the window, plus the new one.
+            let incoming_peak = frame.iter().fold(0.0_f32, |m, s| m.max(s.abs()));
+            let window_peak = self.delay.iter().fold(incoming_peak, |m, s| m.max(s.abs()));
+            let target = if window_peak > Self::THRESHOLD { Self::THRESHOLD / window_peak } else { 1.0 };
+
+            // Instant attack (the lookahead already gave us time), smooth release.
+            if target < self.gain {
+                self.gain = target;
+            } else {
+                self.gain += (target - self.gain) * self.release_coeff;
+            }
+
+            for (i, sample) in frame.iter_mut().enumerate() {
+                let delayed = std::mem::replace(&mut self.delay[slot + i], *sample);
+                *sample = delayed * self.gain;
+            }
+            self.pos = (self.pos + 1) % self.lookahead;
+        }
+    }
+}
+
+/// Filter state for a single output channel.
+///
+/// All channels share one `EffectsConfig`, but each needs its own filter
+/// history — running interleaved left/right samples through a single biquad
+/// or delay line smears the channels into each other.
+struct ChannelEffects {
+    highpass: BiquadFilter,
+    lowpass: BiquadFilter,
+    notch: BiquadFilter,
+    reverb: Reverb,
+    echo: Echo,
+    bass_boost: BassBoost,
+    tone: ToneControl,
+    loudness: ToneControl,
+    equalizer: Equalizer,
+}
+
+impl ChannelEffects {
+    fn new(sample_rate: u32, config: &EffectsConfig) -> Self {
+        let mut equalizer = Equalizer::new(sample_rate, &config.eq_layout);
+        equalizer.update_gains(&config.eq_bands);
+        let mut fx = Self {
+            highpass: BiquadFilter::new(),
+            lowpass: BiquadFilter::new(),
+            notch: BiquadFilter::new(),
+            reverb: Reverb::new(sample_rate, config.reverb_room_size),
+            echo: Echo::new(sample_rate, config.echo_delay, config.echo_feedback),
+            bass_boost: BassBoost::new(sample_rate, config.bass_boost),
+            tone: ToneControl::new(sample_rate, config.bass_db, config.treble_db),
+            loudness: ToneControl::new(sample_rate, 0.0, 0.0),
+            equalizer,
+        };
+        fx.configure_cleanup_filters(sample_rate, config);
+        fx
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: u32, config: &EffectsConfig) {
+        self.reverb.resize(sample_rate);
+        self.echo = Echo::new(sample_rate, config.echo_delay, config.echo_feedback);
+        self.bass_boost = BassBoost::new(sample_rate, config.bass_boost);
+        self.tone = ToneControl::new(sample_rate, config.bass_db, config.treble_db);
+        self.loudness = ToneControl::new(sample_rate, 0.0, 0.0);
+        self.equalizer = Equalizer::new(sample_rate, &config.eq_layout);
+        self.equalizer.update_gains(&config.eq_bands);
+        self.configure_cleanup_filters(sample_rate, config);
+    }
+
+    fn update_config(&mut self, sample_rate: u32, config: &EffectsConfig) {
+        self.reverb.set_room_size(config.reverb_room_size);
+        self.echo.set_delay(sample_rate, config.echo_delay);
+        self.echo.set_feedback(config.echo_feedback);
+        self.bass_boost.set_boost(sample_rate, config.bass_boost);
+        self.tone.set_gains(sample_rate, config.bass_db, config.treble_db);
+        if self.equalizer.bands() != config.eq_layout.as_slice() {
+            self.equalizer = Equalizer::new(sample_rate, &config.eq_layout);
+        }
+        self.equalizer.update_gains(&config.eq_bands);
+        self.configure_cleanup_filters(sample_rate, config);
+    }
+
+    /// Recompute the high-pass / low-pass / notch coefficients. Cutoffs are
+    /// kept below Nyquist so a 16 kHz low-pass can't go unstable at 22 kHz.
+    fn configure_cleanup_filters(&mut self, sample_rate: u32, config: &EffectsConfig) {
+        let max_freq = sample_rate as f32 * 0.45;
+        if config.highpass_freq > 0.0 {
+            self.highpass.set_highpass(sample_rate, config.highpass_freq.min(max_freq), 0.707);
+        }
+        if config.lowpass_freq > 0.0 {
+            self.lowpass.set_lowpass(sample_rate, config.lowpass_freq.min(max_freq), 0.707);
+        }
+        if let Some(notch) = config.notch {
+            self.notch.set_notch(sample_rate, notch.freq.clamp(1.0, max_freq), notch.q.max(0.1));
+        }
+    }
+
+    /// Front-of-chain cleanup filters; each is skipped when disabled.
+    fn process_cleanup(&mut self, config: &EffectsConfig, input: f32) -> f32 {
+        let mut output = input;
+        if config.highpass_freq > 0.0 {
+            output = self.highpass.process(output);
+        }
+        if config.lowpass_freq > 0.0 {
+            output = self.lowpass.process(output);
+        }
+        if config.notch.is_some() {
+            output = self.notch.process(output);
+        }
+        output
+    }
+}
+
+/// How often the chain's output exceeded full scale before the limiter and
+/// soft clipper caught it. A high count means gain is stacking up (e.g.
+/// ReplayGain boost plus EQ plus bass boost) and the preamp should come down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipStats {
+    /// Samples whose magnitude exceeded 1.0.
+    pub clipped_samples: u64,
+    /// Samples processed since the stats were last reset.
+    pub total_samples: u64,
+    /// Largest magnitude seen, in linear full-scale units.
+    pub peak: f32,
+}
+
+/// Interleaved samples per block reported by [`EffectsProcessor::measure_cost`].
+pub const COST_BLOCK_SAMPLES: usize = 1024;
+/// Blocks timed per measurement run; the fastest of a few runs is reported.
+const COST_BLOCKS_PER_RUN: usize = 64;
+const COST_RUNS: usize = 3;
+
+/// Estimated CPU cost of the current effects chain.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectsCost {
+    /// Processing time per 1024 interleaved samples, in microseconds.
+    pub micros_per_1024_samples: f64,
+    /// Playback time 1024 samples cover at the current rate and channel
+    /// count. Processing close to this will drop out.
+    pub budget_micros: f64,
+}
+
+/// What a benchmark chain copies from the live one: plain values, so the
+/// live chain's lock is held only while they are read.
+#[derive(Debug, Clone)]
+pub struct BenchmarkSettings {
+    config: EffectsConfig,
+    sample_rate: u32,
+    channels: u16,
+    block_processing_enabled: bool,
+    loudness_enabled: bool,
+    loudness_volume: f32,
+}
+
 /**
  * Audio effects processor chain
  */
 pub struct EffectsProcessor {
     config: EffectsConfig,
-    reverb: Reverb,
-    echo: Echo,
-    bass_boost: BassBoost,
-    equalizer: Equalizer,
+    channel_effects: Vec<ChannelEffects>,
+    crossfeed: Crossfeed,
+    limiter: LookaheadLimiter,
     sample_rate: u32,
     block_processing_enabled: bool,
+    loudness_enabled: bool,
+    /// User volume (0.0–1.0) the loudness contour is computed for.
+    loudness_volume: f32,
+    clip_stats: ClipStats,
 }
 
 fn env_flag_enabled(var_name: &str, default_value: bool) -> bool {
@@ -541,15 +997,22 @@ fn env_flag_enabled(var_name: &str, default_value: bool) -> bool {
 }
 
 impl EffectsProcessor {
+    /// Channel layout assumed until the source reports its own.
+    const DEFAULT_CHANNELS: usize = 2;
+
     pub fn new(sample_rate: u32, config: EffectsConfig) -> Self {
         Self {
-            reverb: Reverb::new(sample_rate, config.reverb_room_size),
-            echo: Echo::new(sample_rate, config.echo_delay, config.echo_feedback),
-            bass_boost: BassBoost::new(sample_rate, config.bass_boost),
-            equalizer: Equalizer::new(sample_rate),
+            channel_effects: (0..Self::DEFAULT_CHANNELS)
+                .map(|_| ChannelEffects::new(sample_rate, &config))
+                .collect(),
+            crossfeed: Crossfeed::new(sample_rate),
+            limiter: LookaheadLimiter::new(sample_rate, Self::DEFAULT_CHANNELS, config.limiter_lookahead_ms),
             config,
             sample_rate,
             block_processing_enabled: env_flag_enabled(DSP_BLOCK_PROCESSING_ENV, true),
+            loudness_enabled: false,
+            loudness_volume: 1.0,
+            clip_stats: ClipStats::default(),
         }
     }
 
@@ -566,51 +1029,221 @@ impl EffectsProcessor {
             self.sample_rate = new_sample_rate;
             
             // Reinitialize/Resize effects
-            self.reverb.resize(new_sample_rate);
-            self.echo = Echo::new(new_sample_rate, self.config.echo_delay, self.config.echo_feedback);
-            self.bass_boost = BassBoost::new(new_sample_rate, self.config.bass_boost);
-            self.equalizer = Equalizer::new(new_sample_rate);
-            self.equalizer.update_gains(&self.config.eq_bands);
+            for fx in &mut self.channel_effects {
+                fx.set_sample_rate(new_sample_rate, &self.config);
+            }
+            self.crossfeed = Crossfeed::new(new_sample_rate);
+            self.rebuild_limiter();
+            self.apply_loudness();
+        }
+    }
+
+    /// Set the interleaved channel count of the incoming buffers.
+    pub fn set_channels(&mut self, channels: u16) {
+        let channels = (channels as usize).max(1);
+        if channels != self.channel_effects.len() {
+            log::info!("Updating effects processor channels: {} -> {}", self.channel_effects.len(), channels);
+            self.rebuild_channels(channels);
+            self.rebuild_limiter();
+        }
+    }
+
+    fn rebuild_channels(&mut self, channels: usize) {
+        self.channel_effects = (0..channels)
+            .map(|_| ChannelEffects::new(self.sample_rate, &self.config))
+            .collect();
+        self.apply_loudness();
+    }
+
+    pub fn set_loudness_enabled(&mut self, enabled: bool) {
+        self.loudness_enabled = enabled;
+        self.apply_loudness();
+    }
+
+    pub fn is_loudness_enabled(&self) -> bool {
+        self.loudness_enabled
+    }
+
+    /// Recompute the loudness contour for a new user volume.
+    pub fn set_loudness_volume(&mut self, volume: f32) {
+        self.loudness_volume = volume.clamp(0.0, 1.0);
+        self.apply_loudness();
+    }
+
+    /// Shelf gains (bass, treble) currently applied by loudness compensation.
+    pub fn loudness_gains_db(&self) -> (f32, f32) {
+        if self.loudness_enabled {
+            loudness_contour_db(self.loudness_volume)
+        } else {
+            (0.0, 0.0)
+        }
+    }
+
+    fn apply_loudness(&mut self) {
+        let (bass_db, treble_db) = self.loudness_gains_db();
+        for fx in &mut self.channel_effects {
+            fx.loudness.set_gains(self.sample_rate, bass_db, treble_db);
         }
     }
+
+    fn rebuild_limiter(&mut self) {
+        self.limiter = LookaheadLimiter::new(
+            self.sample_rate,
+            self.channel_effects.len(),
+            self.config.limiter_lookahead_ms,
+        );
+    }
     
     pub fn update_config(&mut self, config: EffectsConfig) {
-        self.reverb.set_room_size(config.reverb_room_size);
-        self.echo.set_delay(self.sample_rate, config.echo_delay);
-        self.echo.set_feedback(config.echo_feedback);
-        self.bass_boost.set_boost(self.sample_rate, config.bass_boost);
-        self.equalizer.update_gains(&config.eq_bands);
+        for fx in &mut self.channel_effects {
+            fx.update_config(self.sample_rate, &config);
+        }
+        let lookahead_changed = config.limiter_lookahead_ms != self.config.limiter_lookahead_ms;
         self.config = config;
+        if lookahead_changed {
+            self.rebuild_limiter();
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Total latency the chain adds between input and output, in samples
+    /// per channel. IIR filters and wet-only delays don't count — only stages
+    /// that hold back the dry signal do.
+    pub fn latency_samples(&self) -> usize {
+        self.limiter.latency_frames()
     }
 
     pub fn get_config(&self) -> EffectsConfig {
         self.config.clone()
     }
-    
-    pub fn process(&mut self, input: f32) -> f32 {
-        let mut output = input;
+
+    pub fn clip_stats(&self) -> ClipStats {
+        self.clip_stats
+    }
+
+    /// Start counting afresh, e.g. for a new track.
+    pub fn reset_clip_stats(&mut self) {
+        self.clip_stats = ClipStats::default();
+    }
+
+    /// Clear every filter, delay, reverb and limiter buffer while keeping
+    /// the config, so nothing processed earlier rings into what comes next.
+    pub fn flush(&mut self) {
+        self.rebuild_channels(self.channel_effects.len());
+        self.crossfeed = Crossfeed::new(self.sample_rate);
+        self.rebuild_limiter();
+    }
+
+    /// Return to `EffectsConfig::default()` with all state flushed. Loudness
+    /// compensation isn't part of the config and is left as it is.
+    pub fn reset(&mut self) {
+        self.config = EffectsConfig::default();
+        self.flush();
+        self.reset_clip_stats();
+    }
+
+    /// This chain's config, rate, channels and loudness, to build a
+    /// [`benchmark_copy`](Self::benchmark_copy) from once the lock is released.
+    pub fn benchmark_settings(&self) -> BenchmarkSettings {
+        BenchmarkSettings {
+            config: self.config.clone(),
+            sample_rate: self.sample_rate,
+            channels: self.channel_effects.len() as u16,
+            block_processing_enabled: self.block_processing_enabled,
+            loudness_enabled: self.loudness_enabled,
+            loudness_volume: self.loudness_volume,
+        }
+    }
+
+    /// A fresh chain set up like the one `settings` came from, for
+    /// [`measure_cost`](Self::measure_cost) to run without holding up the live one.
+    pub fn benchmark_copy(settings: BenchmarkSettings) -> Self {
+        let mut bench = Self::new(settings.sample_rate, settings.config);
+        bench.block_processing_enabled = settings.block_processing_enabled;
+        bench.set_channels(settings.channels);
+        bench.loudness_enabled = settings.loudness_enabled;
+        bench.set_loudness_volume(settings.loudness_volume);
+        bench
+    }
+
+    /// Time this chain on a fixed test signal. Disturbs filter state, so run
+    /// it on a [`benchmark_copy`](Self::benchmark_copy).
+    pub fn measure_cost(&mut self) -> EffectsCost {
+        let channels = self.channel_effects.len();
+        let signal: Vec<f32> = (0..COST_BLOCK_SAMPLES)
+            .map(|i| {
+                let x = i as f32 * 0.017;
+                x.sin() * 0.6 + (x * 7.3).sin() * 0.2
+            })
+            .collect();
+        let mut block = signal.clone();
+        // Warm up so delay lines and caches are in steady state.
+        for _ in 0..4 {
+            block.copy_from_slice(&signal);
+            self.process_buffer(&mut block);
+        }
+
+        let mut best = std::time::Duration::MAX;
+        for _ in 0..COST_RUNS {
+            let start = std::time::Instant::now();
+            for _ in 0..COST_BLOCKS_PER_RUN {
+                block.copy_from_slice(&signal);
+                self.process_buffer(std::hint::black_box(&mut block));
+            }
+            best = best.min(start.elapsed());
+        }
+
+        let frames_per_block = COST_BLOCK_SAMPLES as f64 / channels.max(1) as f64;
+        EffectsCost {
+            micros_per_1024_samples: best.as_secs_f64() * 1e6 / COST_BLOCKS_PER_RUN as f64,
+            budget_micros: frames_per_block * 1e6 / self.sample_rate.max(1) as f64,
+        }
+    }
+
+    /// Count samples over full scale ahead of the limiter.
+    fn record_overflow(&mut self, buffer: &[f32]) {
+        let stats = &mut self.clip_stats;
+        stats.total_samples += buffer.len() as u64;
+        for sample in buffer {
+            let magnitude = sample.abs();
+            if magnitude > 1.0 {
+                stats.clipped_samples += 1;
+            }
+            stats.peak = stats.peak.max(magnitude);
+        }
+    }
+
+    /// Run one sample of `channel` through the user-ordered chain.
+    fn process_sample(&mut self, channel: usize, input: f32) -> f32 {
+        let fx = &mut self.channel_effects[channel];
+        let mut output = fx.process_cleanup(&self.config, input);
+        output = fx.tone.process(output);
+        output = fx.loudness.process(output);
 
         // Walk the user-defined effect order
         for effect in &self.config.effect_order {
             match effect {
                 EffectId::Equalizer => {
-                    output = self.equalizer.process(output);
+                    output = fx.equalizer.process(output);
                 }
                 EffectId::BassBoost => {
                     if self.config.bass_boost > 0.0 {
-                        output = self.bass_boost.process(output);
+                        output = fx.bass_boost.process(output);
                     }
                 }
                 EffectId::Echo => {
                     if self.config.echo_mix > 0.0 {
-                        let echo_wet = self.echo.process(output);
+                        let echo_wet = fx.echo.process(output);
                         output = output * (1.0 - self.config.echo_mix)
                             + echo_wet * self.config.echo_mix;
                     }
                 }
                 EffectId::Reverb => {
                     if self.config.reverb_mix > 0.0 {
-                        let reverb_wet = self.reverb.process(output);
+                        let reverb_wet = fx.reverb.process(output);
                         output = output * (1.0 - self.config.reverb_mix)
                             + reverb_wet * self.config.reverb_mix;
                     }
@@ -618,17 +1251,40 @@ impl EffectsProcessor {
             }
         }
 
-        // Soft Clipper always runs last (safety limiter)
-        SoftClipper::saturate(output)
+        output
+    }
+
+    /// Crossfeed runs after the ordered chain: it models the listening
+    /// environment rather than shaping the signal, so it is not reorderable.
+    fn apply_crossfeed(&mut self, buffer: &mut [f32]) {
+        if !self.config.crossfeed_enabled || self.channel_effects.len() != 2 {
+            return;
+        }
+        let strength = self.config.crossfeed_strength;
+        for frame in buffer.chunks_exact_mut(2) {
+            let (left, right) = self.crossfeed.process_frame(frame[0], frame[1], strength);
+            frame[0] = left;
+            frame[1] = right;
+        }
     }
 
     fn process_buffer_legacy(&mut self, buffer: &mut [f32]) {
-        for sample in buffer.iter_mut() {
+        let channels = self.channel_effects.len();
+        for (i, sample) in buffer.iter_mut().enumerate() {
             if sample.is_nan() {
                 *sample = 0.0;
                 continue;
             }
-            *sample = self.process(*sample);
+            *sample = self.process_sample(i % channels, *sample);
+        }
+
+        self.apply_crossfeed(buffer);
+        self.record_overflow(buffer);
+        self.limiter.process(buffer);
+
+        // Soft Clipper always runs last (safety limiter)
+        for sample in buffer.iter_mut() {
+            *sample = SoftClipper::saturate(*sample);
         }
     }
 
@@ -643,36 +1299,55 @@ impl EffectsProcessor {
         let bass_boost_db = self.config.bass_boost;
         let echo_mix = self.config.echo_mix;
         let reverb_mix = self.config.reverb_mix;
+        let channels = self.channel_effects.len();
+
+        if self.config.highpass_freq > 0.0 || self.config.lowpass_freq > 0.0 || self.config.notch.is_some() {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                *sample = self.channel_effects[i % channels].process_cleanup(&self.config, *sample);
+            }
+        }
+
+        if self.channel_effects.iter().any(|fx| fx.tone.is_active()) {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                *sample = self.channel_effects[i % channels].tone.process(*sample);
+            }
+        }
+
+        if self.loudness_enabled {
+            for (i, sample) in buffer.iter_mut().enumerate() {
+                *sample = self.channel_effects[i % channels].loudness.process(*sample);
+            }
+        }
 
         // Process by stage to reduce branch overhead in the hot path.
         for effect in effect_order {
             match effect {
                 EffectId::Equalizer => {
-                    for sample in buffer.iter_mut() {
-                        *sample = self.equalizer.process(*sample);
+                    for (i, sample) in buffer.iter_mut().enumerate() {
+                        *sample = self.channel_effects[i % channels].equalizer.process(*sample);
                     }
                 }
                 EffectId::BassBoost => {
                     if bass_boost_db > 0.0 {
-                        for sample in buffer.iter_mut() {
-                            *sample = self.bass_boost.process(*sample);
+                        for (i, sample) in buffer.iter_mut().enumerate() {
+                            *sample = self.channel_effects[i % channels].bass_boost.process(*sample);
                         }
                     }
                 }
                 EffectId::Echo => {
                     if echo_mix > 0.0 {
-                        for sample in buffer.iter_mut() {
+                        for (i, sample) in buffer.iter_mut().enumerate() {
                             let dry = *sample;
-                            let wet = self.echo.process(dry);
+                            let wet = self.channel_effects[i % channels].echo.process(dry);
                             *sample = dry * (1.0 - echo_mix) + wet * echo_mix;
                         }
                     }
                 }
                 EffectId::Reverb => {
                     if reverb_mix > 0.0 {
-                        for sample in buffer.iter_mut() {
+                        for (i, sample) in buffer.iter_mut().enumerate() {
                             let dry = *sample;
-                            let wet = self.reverb.process(dry);
+                            let wet = self.channel_effects[i % channels].reverb.process(dry);
                             *sample = dry * (1.0 - reverb_mix) + wet * reverb_mix;
                         }
                     }
@@ -680,11 +1355,17 @@ impl EffectsProcessor {
             }
         }
 
+        self.apply_crossfeed(buffer);
+        self.record_overflow(buffer);
+        self.limiter.process(buffer);
+
         for sample in buffer.iter_mut() {
             *sample = SoftClipper::saturate(*sample);
         }
     }
     
+    /// Process an interleaved buffer. The buffer must start on a frame
+    /// boundary for the configured channel count.
     pub fn process_buffer(&mut self, buffer: &mut [f32]) {
         if self.block_processing_enabled {
             self.process_buffer_staged(buffer);
@@ -699,12 +1380,103 @@ mod tests {
     use super::*;
     use std::time::Instant;
 
+    /// ISO 1/3-octave centre frequencies, 20 Hz – 20 kHz.
+    const ISO_31_BAND_FREQUENCIES: [f32; 31] = [
+        20.0, 25.0, 31.5, 40.0, 50.0, 63.0, 80.0, 100.0, 125.0, 160.0, 200.0, 250.0, 315.0, 400.0,
+        500.0, 630.0, 800.0, 1000.0, 1250.0, 1600.0, 2000.0, 2500.0, 3150.0, 4000.0, 5000.0, 6300.0,
+        8000.0, 10000.0, 12500.0, 16000.0, 20000.0,
+    ];
+
+    /// ISO 2/3-octave centre frequencies, 25 Hz – 16 kHz.
+    const ISO_15_BAND_FREQUENCIES: [f32; 15] = [
+        25.0, 40.0, 63.0, 100.0, 160.0, 250.0, 400.0, 630.0, 1000.0, 1600.0, 2500.0, 4000.0, 6300.0,
+        10000.0, 16000.0,
+    ];
+
     #[test]
     fn test_effects_config_default() {
         let config = EffectsConfig::default();
         assert_eq!(config.tempo, 1.0);
         assert_eq!(config.reverb_mix, 0.0);
-        assert_eq!(config.eq_bands, [0.0; 10]);
+        assert_eq!(config.eq_bands, vec![0.0; 10]);
+        assert_eq!(config.eq_layout.len(), 10);
+    }
+
+    #[test]
+    fn heavier_chain_measures_higher_cost() {
+        let bypass = EffectsConfig {
+            eq_bands: Vec::new(),
+            eq_layout: Vec::new(),
+            ..EffectsConfig::default()
+        };
+        let heavy = EffectsConfig {
+            eq_bands: vec![3.0; ISO_31_BAND_FREQUENCIES.len()],
+            eq_layout: EqBand::layout(&ISO_31_BAND_FREQUENCIES, 4.32),
+            reverb_mix: 0.4,
+            echo_mix: 0.3,
+            bass_boost: 6.0,
+            crossfeed_enabled: true,
+            limiter_lookahead_ms: 5.0,
+            ..EffectsConfig::default()
+        };
+
+        let bypass_cost = EffectsProcessor::new(44100, bypass).measure_cost();
+        let live = EffectsProcessor::new(44100, heavy);
+        let heavy_cost = EffectsProcessor::benchmark_copy(live.benchmark_settings()).measure_cost();
+        assert!(
+            heavy_cost.micros_per_1024_samples > bypass_cost.micros_per_1024_samples,
+            "heavy {:?} vs bypass {:?}",
+            heavy_cost,
+            bypass_cost
+        );
+        // 512 stereo frames at 44.1 kHz.
+        assert!((bypass_cost.budget_micros - 11_609.977).abs() < 0.01);
+    }
+
+    fn wet_config() -> EffectsConfig {
+        EffectsConfig {
+            reverb_mix: 0.8,
+            reverb_room_size: 0.9,
+            echo_mix: 0.6,
+            echo_delay: 0.05,
+            echo_feedback: 0.8,
+            bass_boost: 6.0,
+            limiter_lookahead_ms: 5.0,
+            ..EffectsConfig::default()
+        }
+    }
+
+    #[test]
+    fn reset_matches_a_fresh_processor() {
+        let mut processor = EffectsProcessor::new(44100, wet_config());
+        let mut noise: Vec<f32> = (0..8192).map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0).collect();
+        processor.process_buffer(&mut noise);
+
+        processor.reset();
+        assert_eq!(processor.get_config().reverb_mix, 0.0);
+        assert_eq!(processor.clip_stats(), ClipStats::default());
+
+        let signal: Vec<f32> = (0..4096).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let mut after_reset = signal.clone();
+        processor.process_buffer(&mut after_reset);
+        let mut fresh = signal;
+        EffectsProcessor::new(44100, EffectsConfig::default()).process_buffer(&mut fresh);
+        assert_eq!(after_reset, fresh);
+    }
+
+    #[test]
+    fn flush_leaves_no_reverb_or_echo_tail() {
+        let mut processor = EffectsProcessor::new(44100, wet_config());
+        let mut impulse = vec![0.0; 4096];
+        impulse[0] = 1.0;
+        impulse[1] = 1.0;
+        processor.process_buffer(&mut impulse);
+
+        let mut silence = vec![0.0; 44100];
+        processor.flush();
+        processor.process_buffer(&mut silence);
+        assert!(silence.iter().all(|s| *s == 0.0), "tail survived the flush");
+        assert_eq!(processor.get_config().reverb_mix, 0.8, "flush keeps the config");
     }
 
     #[test]
@@ -763,7 +1535,7 @@ mod tests {
         config.echo_delay = 0.1;
         config.reverb_mix = 0.3;
         config.reverb_room_size = 0.6;
-        config.eq_bands = [1.5, -0.8, 1.0, -1.2, 0.7, 0.2, -0.4, 0.8, -0.3, 0.6];
+        config.eq_bands = vec![1.5, -0.8, 1.0, -1.2, 0.7, 0.2, -0.4, 0.8, -0.3, 0.6];
 
         let mut legacy = EffectsProcessor::new_with_block_mode(44100, config.clone(), false);
         let mut staged = EffectsProcessor::new_with_block_mode(44100, config, true);
@@ -810,7 +1582,7 @@ mod tests {
         config.echo_delay = 0.1;
         config.reverb_mix = 0.3;
         config.reverb_room_size = 0.6;
-        config.eq_bands = [1.5, -0.8, 1.0, -1.2, 0.7, 0.2, -0.4, 0.8, -0.3, 0.6];
+        config.eq_bands = vec![1.5, -0.8, 1.0, -1.2, 0.7, 0.2, -0.4, 0.8, -0.3, 0.6];
 
         let mut processor = EffectsProcessor::new_with_block_mode(44100, config, block_mode);
         let seed: Vec<f32> = (0..buffer_len)
@@ -855,6 +1627,165 @@ mod tests {
         }
     }
 
+    /// Hard-left stereo input. Returns (left, right) after processing.
+    fn run_hard_left(crossfeed_enabled: bool) -> (Vec<f32>, Vec<f32>) {
+        let config = EffectsConfig {
+            crossfeed_enabled,
+            crossfeed_strength: 1.0,
+            ..EffectsConfig::default()
+        };
+        let mut processor = EffectsProcessor::new_with_block_mode(44100, config, true);
+        processor.set_channels(2);
+
+        let mut buffer = Vec::with_capacity(2048);
+        for i in 0..1024 {
+            let x = i as f32 * 2.0 * PI * 200.0 / 44100.0;
+            buffer.push(x.sin() * 0.5);
+            buffer.push(0.0);
+        }
+        processor.process_buffer(&mut buffer);
+
+        let left = buffer.iter().step_by(2).copied().collect();
+        let right = buffer.iter().skip(1).step_by(2).copied().collect();
+        (left, right)
+    }
+
+    #[test]
+    fn test_crossfeed_bleeds_delayed_lowpassed_signal() {
+        let delay = (44100.0 * Crossfeed::DELAY_SECONDS).round() as usize;
+        assert!(delay > 0);
+
+        let (left, right) = run_hard_left(true);
+
+        // Nothing reaches the right ear before the crossfeed delay elapses.
+        assert!(right[..delay].iter().all(|s| *s == 0.0));
+
+        let left_peak = left.iter().fold(0.0_f32, |a, s| a.max(s.abs()));
+        let right_peak = right.iter().fold(0.0_f32, |a, s| a.max(s.abs()));
+        assert!(right_peak > 0.01, "expected crossfed component, got {}", right_peak);
+        assert!(right_peak < left_peak * 0.5, "crossfeed too strong: {} vs {}", right_peak, left_peak);
+    }
+
+    #[test]
+    fn test_crossfeed_bypassed_when_disabled() {
+        let (_, right) = run_hard_left(false);
+        assert!(right.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_channels_keep_independent_filter_state() {
+        let config = EffectsConfig {
+            echo_mix: 0.5,
+            echo_delay: 0.01,
+            ..EffectsConfig::default()
+        };
+        let mut processor = EffectsProcessor::new_with_block_mode(44100, config, true);
+        processor.set_channels(2);
+
+        // Only the left channel carries signal; echo must not leak to the right.
+        let mut buffer: Vec<f32> = (0..4096)
+            .map(|i| if i % 2 == 0 { 0.5 } else { 0.0 })
+            .collect();
+        processor.process_buffer(&mut buffer);
+
+        assert!(buffer.iter().skip(1).step_by(2).all(|s| *s == 0.0));
+    }
+
+    /// Steady-state RMS of a mono tone after processing.
+    fn tone_rms(config: &EffectsConfig, freq: f32) -> f32 {
+        let mut processor = EffectsProcessor::new_with_block_mode(44100, config.clone(), true);
+        processor.set_channels(1);
+        let mut buffer: Vec<f32> = (0..8820)
+            .map(|i| (2.0 * PI * freq * i as f32 / 44100.0).sin() * 0.5)
+            .collect();
+        processor.process_buffer(&mut buffer);
+        let tail = &buffer[4410..];
+        (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_highpass_removes_rumble_and_passes_midrange() {
+        let flat = EffectsConfig::default();
+        let highpass = EffectsConfig {
+            highpass_freq: 200.0,
+            ..EffectsConfig::default()
+        };
+
+        let low_ratio = tone_rms(&highpass, 50.0) / tone_rms(&flat, 50.0);
+        let mid_ratio = tone_rms(&highpass, 1000.0) / tone_rms(&flat, 1000.0);
+
+        assert!(low_ratio < 0.1, "50 Hz should be strongly attenuated, ratio {}", low_ratio);
+        assert!(mid_ratio > 0.95, "1 kHz should pass, ratio {}", mid_ratio);
+    }
+
+    #[test]
+    fn test_notch_kills_hum_only() {
+        let flat = EffectsConfig::default();
+        let notch = EffectsConfig {
+            notch: Some(NotchConfig { freq: 60.0, q: 10.0 }),
+            ..EffectsConfig::default()
+        };
+
+        assert!(tone_rms(&notch, 60.0) / tone_rms(&flat, 60.0) < 0.1);
+        assert!(tone_rms(&notch, 1000.0) / tone_rms(&flat, 1000.0) > 0.95);
+    }
+
+    #[test]
+    fn test_limiter_lookahead_reports_latency() {
+        let mut processor = EffectsProcessor::new_with_block_mode(44100, EffectsConfig::default(), true);
+        assert_eq!(processor.latency_samples(), 0);
+
+        processor.update_config(EffectsConfig {
+            limiter_lookahead_ms: 5.0,
+            ..EffectsConfig::default()
+        });
+        assert_eq!(processor.latency_samples(), 221);
+
+        // The reported latency matches the actual delay of an impulse.
+        processor.set_channels(1);
+        let mut buffer = vec![0.0_f32; 512];
+        buffer[0] = 0.5;
+        processor.process_buffer(&mut buffer);
+        let arrival = buffer.iter().position(|s| s.abs() > 0.0);
+        assert_eq!(arrival, Some(processor.latency_samples()));
+
+        processor.update_config(EffectsConfig::default());
+        assert_eq!(processor.latency_samples(), 0);
+    }
+
+    #[test]
+    fn test_hot_signal_is_counted_before_the_limiter() {
+        for block_mode in [true, false] {
+            let mut processor = EffectsProcessor::new_with_block_mode(44100, EffectsConfig::default(), block_mode);
+            let mut quiet = vec![0.5_f32; 256];
+            processor.process_buffer(&mut quiet);
+            assert_eq!(processor.clip_stats().clipped_samples, 0);
+
+            // Every other sample is 1.5x full scale; the output is still tamed.
+            let mut hot: Vec<f32> = (0..256).map(|i| if i % 2 == 0 { 1.5 } else { -0.25 }).collect();
+            processor.process_buffer(&mut hot);
+            assert!(hot.iter().all(|s| s.abs() <= 1.0));
+
+            let stats = processor.clip_stats();
+            assert_eq!(stats.clipped_samples, 128);
+            assert_eq!(stats.total_samples, 512);
+            assert!((stats.peak - 1.5).abs() < 1e-3);
+
+            processor.reset_clip_stats();
+            assert_eq!(processor.clip_stats(), ClipStats::default());
+        }
+    }
+
+    #[test]
+    fn test_limiter_holds_peaks_below_threshold() {
+        let mut limiter = LookaheadLimiter::new(44100, 2, 2.0);
+        let mut buffer: Vec<f32> = (0..4096)
+            .map(|i| ((i / 2) as f32 * 0.05).sin() * 3.0)
+            .collect();
+        limiter.process(&mut buffer);
+        assert!(buffer.iter().all(|s| s.abs() <= LookaheadLimiter::THRESHOLD + 1e-6));
+    }
+
     // ── F-017a: BiquadFilter correctness & CombFilter index wrap ─────────────
 
     /// A ±12 dB low-shelf at 200 Hz should produce bounded, finite output for
@@ -943,4 +1874,143 @@ mod tests {
         assert!(c48 > c44, "48 kHz buffer must be larger than 44.1 kHz buffer");
         assert!(c96 > c48, "96 kHz buffer must be larger than 48 kHz buffer");
     }
+
+    #[test]
+    fn test_31_band_eq_processes_without_panic() {
+        let mut config = EffectsConfig::default();
+        config.eq_layout = EqBand::layout(&ISO_31_BAND_FREQUENCIES, 4.32);
+        config.eq_bands = (0..31).map(|i| if i % 2 == 0 { 6.0 } else { -6.0 }).collect();
+
+        // 32 kHz puts the top bands above Nyquist; they must be clamped, not blow up.
+        for sr in [32000, 44100, 96000] {
+            let mut processor = EffectsProcessor::new(sr, config.clone());
+            processor.set_channels(2);
+            let mut buffer: Vec<f32> = (0..8192)
+                .map(|i| ((i / 2) as f32 * 0.05).sin() * 0.5)
+                .collect();
+            processor.process_buffer(&mut buffer);
+            assert!(buffer.iter().all(|s| s.is_finite()), "non-finite output at sr={}", sr);
+        }
+    }
+
+    #[test]
+    fn test_eq_layout_change_rebuilds_filters() {
+        let mut processor = EffectsProcessor::new(44100, EffectsConfig::default());
+        let mut config = processor.get_config();
+        config.eq_layout = EqBand::layout(&ISO_15_BAND_FREQUENCIES, 2.15);
+        config.eq_bands = vec![3.0; 15];
+        processor.update_config(config);
+
+        let mut buffer = vec![0.25_f32; 1024];
+        processor.process_buffer(&mut buffer);
+        assert!(buffer.iter().all(|s| s.is_finite()));
+        assert_eq!(processor.channel_effects[0].equalizer.bands().len(), 15);
+    }
+
+    #[test]
+    fn test_legacy_ten_band_config_deserializes() {
+        let json = r#"{
+            "tempo": 1.0, "reverb_mix": 0.0, "reverb_room_size": 0.5, "bass_boost": 0.0,
+            "echo_delay": 0.3, "echo_feedback": 0.3, "echo_mix": 0.0,
+            "eq_bands": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        }"#;
+        let config: EffectsConfig = serde_json::from_str(json).expect("legacy config should parse");
+        assert_eq!(config.eq_bands.len(), 10);
+        assert_eq!(config.eq_bands[9], 10.0);
+        assert_eq!(config.eq_layout, default_eq_layout());
+    }
+
+    /// Magnitude of the FFT bin nearest `freq` over the steady-state tail of
+    /// a processed mono buffer.
+    fn fft_magnitude(buffer: &[f32], sample_rate: u32, freq: f32) -> f32 {
+        use rustfft::{num_complex::Complex, FftPlanner};
+
+        const FFT_SIZE: usize = 8192;
+        let tail = &buffer[buffer.len() - FFT_SIZE..];
+        let mut spectrum: Vec<Complex<f32>> = tail.iter().map(|&s| Complex::new(s, 0.0)).collect();
+        FftPlanner::new().plan_fft_forward(FFT_SIZE).process(&mut spectrum);
+
+        let bin = (freq * FFT_SIZE as f32 / sample_rate as f32).round() as usize;
+        spectrum[bin].norm()
+    }
+
+    #[test]
+    fn test_tone_bass_boost_lifts_lows_only() {
+        // Both tones sit exactly on FFT bins (8192-point at 44.1 kHz).
+        let low = 12.0 * 44100.0 / 8192.0; // ~64.6 Hz
+        let high = 1024.0 * 44100.0 / 8192.0; // ~5.5 kHz
+        let render = |config: EffectsConfig| {
+            let mut processor = EffectsProcessor::new_with_block_mode(44100, config, true);
+            processor.set_channels(1);
+            let mut buffer: Vec<f32> = (0..16384)
+                .map(|i| {
+                    let t = i as f32 / 44100.0;
+                    0.2 * (2.0 * PI * low * t).sin() + 0.2 * (2.0 * PI * high * t).sin()
+                })
+                .collect();
+            processor.process_buffer(&mut buffer);
+            (fft_magnitude(&buffer, 44100, low), fft_magnitude(&buffer, 44100, high))
+        };
+
+        let (flat_low, flat_high) = render(EffectsConfig::default());
+        let (boost_low, boost_high) = render(EffectsConfig { bass_db: 6.0, ..EffectsConfig::default() });
+
+        let low_gain_db = 20.0 * (boost_low / flat_low).log10();
+        let high_gain_db = 20.0 * (boost_high / flat_high).log10();
+        assert!(low_gain_db > 5.0, "bass should rise ~6 dB, got {:.2} dB", low_gain_db);
+        assert!(high_gain_db.abs() < 0.2, "highs should be unchanged, got {:.2} dB", high_gain_db);
+    }
+
+    #[test]
+    fn test_tone_control_works_alongside_eq() {
+        let config = EffectsConfig {
+            bass_db: 4.0,
+            treble_db: -4.0,
+            eq_bands: vec![3.0; 10],
+            ..EffectsConfig::default()
+        };
+        let tone_only = EffectsConfig { bass_db: 4.0, treble_db: -4.0, ..EffectsConfig::default() };
+
+        // The EQ adds its own +3 dB on top of the tone shelves.
+        assert!(tone_rms(&config, 1000.0) > tone_rms(&tone_only, 1000.0) * 1.3);
+        assert!(tone_rms(&tone_only, 60.0) > tone_rms(&EffectsConfig::default(), 60.0) * 1.4);
+        assert!(tone_rms(&tone_only, 15000.0) < tone_rms(&EffectsConfig::default(), 15000.0) * 0.7);
+    }
+
+    #[test]
+    fn test_loudness_contour_grows_as_volume_drops() {
+        let (quiet_bass, quiet_treble) = loudness_contour_db(0.2);
+        let (loud_bass, loud_treble) = loudness_contour_db(0.9);
+        assert!(quiet_bass > loud_bass && quiet_treble > loud_treble);
+        assert_eq!(loudness_contour_db(1.0), (0.0, 0.0));
+
+        let mut processor = EffectsProcessor::new(44100, EffectsConfig::default());
+        processor.set_loudness_volume(0.2);
+        assert_eq!(processor.loudness_gains_db(), (0.0, 0.0), "off until enabled");
+
+        processor.set_loudness_enabled(true);
+        let quiet = processor.loudness_gains_db();
+        processor.set_loudness_volume(0.9);
+        let loud = processor.loudness_gains_db();
+        assert!(quiet.0 > loud.0, "bass shelf {} at 0.2 vs {} at 0.9", quiet.0, loud.0);
+        assert!(quiet.1 > loud.1, "treble shelf {} at 0.2 vs {} at 0.9", quiet.1, loud.1);
+    }
+
+    #[test]
+    fn test_loudness_boosts_bass_at_low_volume() {
+        let render = |volume: f32| {
+            let mut processor = EffectsProcessor::new_with_block_mode(44100, EffectsConfig::default(), true);
+            processor.set_channels(1);
+            processor.set_loudness_enabled(true);
+            processor.set_loudness_volume(volume);
+            let mut buffer: Vec<f32> = (0..8820)
+                .map(|i| (2.0 * PI * 60.0 * i as f32 / 44100.0).sin() * 0.2)
+                .collect();
+            processor.process_buffer(&mut buffer);
+            let tail = &buffer[4410..];
+            (tail.iter().map(|s| s * s).sum::<f32>() / tail.len() as f32).sqrt()
+        };
+
+        assert!(render(0.2) > render(0.9) * 1.5);
+    }
 }
@@ -0,0 +1,89 @@
+//! Fade lengths for the sleep timer and for crossfades.
+//!
+//! The two fades are set independently: a long, gentle fade into sleep
+//! shouldn't stretch every track transition, and a short crossfade
+//! shouldn't make the sleep timer cut out abruptly. Both are persisted
+//! together and clamped to `0..=MAX_FADE_SECS` on their own.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+
+/// Settings key under which the fade lengths are persisted.
+pub const FADE_DURATIONS_KEY: &str = "fade_durations";
+pub const MAX_FADE_SECS: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FadeDurations {
+    /// How long the volume ramps down before the sleep timer pauses.
+    pub sleep_fade_secs: f32,
+    /// Overlap between the outgoing and incoming track.
+    pub crossfade_secs: f32,
+}
+
+impl Default for FadeDurations {
+    fn default() -> Self {
+        Self {
+            sleep_fade_secs: 10.0,
+            crossfade_secs: 3.0,
+        }
+    }
+}
+
+/// `secs` limited to `0..=MAX_FADE_SECS`. NaN and infinities are rejected
+/// rather than clamped, since they don't name any length.
+pub fn clamp_fade_secs(secs: f32) -> AppResult<f32> {
+    if !secs.is_finite() {
+        return Err(AppError::Validation(format!("Fade length must be a number of seconds, got {}", secs)));
+    }
+    Ok(secs.clamp(0.0, MAX_FADE_SECS))
+}
+
+impl FadeDurations {
+    /// The persisted lengths, or the defaults when unset or unreadable.
+    /// Stored values outside the range are clamped.
+    pub fn load(db: &Database) -> Self {
+        let stored = match db.get_json_setting::<Self>(FADE_DURATIONS_KEY) {
+            Ok(stored) => stored.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to read fade durations: {}", e);
+                Self::default()
+            }
+        };
+        let defaults = Self::default();
+        Self {
+            sleep_fade_secs: clamp_fade_secs(stored.sleep_fade_secs).unwrap_or(defaults.sleep_fade_secs),
+            crossfade_secs: clamp_fade_secs(stored.crossfade_secs).unwrap_or(defaults.crossfade_secs),
+        }
+    }
+
+    fn save(&self, db: &Database) -> AppResult<()> {
+        db.set_json_setting(FADE_DURATIONS_KEY, self)
+            .map_err(|e| AppError::Database(format!("Failed to save fade durations: {}", e)))
+    }
+
+    /// Persist a new sleep timer fade, leaving the crossfade length as is.
+    /// Returns the stored lengths.
+    pub fn set_sleep_fade_secs(db: &Database, secs: f32) -> AppResult<Self> {
+        let durations = Self {
+            sleep_fade_secs: clamp_fade_secs(secs)?,
+            ..Self::load(db)
+        };
+        durations.save(db)?;
+        Ok(durations)
+    }
+
+    /// Persist a new crossfade length, leaving the sleep timer fade as is.
+    /// Returns the stored lengths.
+    pub fn set_crossfade_secs(db: &Database, secs: f32) -> AppResult<Self> {
+        let durations = Self {
+            crossfade_secs: clamp_fade_secs(secs)?,
+            ..Self::load(db)
+        };
+        durations.save(db)?;
+        Ok(durations)
+    }
+}
@@ -0,0 +1,258 @@
+//! Acoustic fingerprinting for duplicate detection and metadata lookup.
+//!
+//! A simplified Haitsma–Kalker fingerprint: the first
+//! [`FINGERPRINT_SECONDS`] of audio are mixed to mono, split into
+//! overlapping frames, and each frame's energy is measured in
+//! [`FINGERPRINT_BANDS`] log-spaced bands between 300 Hz and 3 kHz. Every
+//! frame yields a 32-bit word whose bits record whether the energy
+//! difference between neighbouring bands rose or fell relative to the
+//! previous frame. The words are hex-encoded into the fingerprint string.
+//!
+//! Results are cached on the `tracks` row keyed by the file's mtime, so a
+//! file is only decoded again after it changes on disk.
+
+use crate::database::Database;
+use log::{info, warn};
+use rusqlite::{params, OptionalExtension};
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use symphonia::core::audio::{AudioBuffer, Signal};
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// How much audio from the start of the file is fingerprinted.
+pub const FINGERPRINT_SECONDS: f64 = 120.0;
+
+/// Number of frequency bands per frame (one more than the bits per word).
+pub const FINGERPRINT_BANDS: usize = 33;
+
+const MIN_FREQ_HZ: f64 = 300.0;
+const MAX_FREQ_HZ: f64 = 3000.0;
+
+/// Incremental fingerprint builder fed with mono samples.
+pub struct Fingerprinter {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    band_edges: Vec<usize>,
+    hop: usize,
+    max_samples: usize,
+    pending: Vec<f32>,
+    consumed: usize,
+    prev_energies: Option<Vec<f64>>,
+    words: Vec<u32>,
+}
+
+impl Fingerprinter {
+    pub fn new(sample_rate: u32) -> Self {
+        // ~93 ms frames at 44.1 kHz, scaled so band resolution is rate independent.
+        let frame_len = ((sample_rate as f64 * 0.093) as usize).next_power_of_two().max(256);
+        let hop = frame_len / 2;
+
+        let window = (0..frame_len)
+            .map(|i| {
+                let x = std::f32::consts::PI * 2.0 * i as f32 / (frame_len - 1) as f32;
+                0.5 - 0.5 * x.cos()
+            })
+            .collect();
+
+        let nyquist_bin = frame_len / 2;
+        let band_edges = (0..=FINGERPRINT_BANDS)
+            .map(|i| {
+                let ratio = i as f64 / FINGERPRINT_BANDS as f64;
+                let freq = MIN_FREQ_HZ * (MAX_FREQ_HZ / MIN_FREQ_HZ).powf(ratio);
+                ((freq * frame_len as f64 / sample_rate as f64).round() as usize).min(nyquist_bin)
+            })
+            .collect();
+
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(frame_len),
+            window,
+            band_edges,
+            hop,
+            max_samples: (FINGERPRINT_SECONDS * sample_rate as f64) as usize,
+            pending: Vec::with_capacity(frame_len * 2),
+            consumed: 0,
+            prev_energies: None,
+            words: Vec::new(),
+        }
+    }
+
+    /// True once enough audio has been seen; further samples are ignored.
+    pub fn is_full(&self) -> bool {
+        self.consumed >= self.max_samples
+    }
+
+    /// Feed mono samples. Anything past the fingerprint window is dropped.
+    pub fn push(&mut self, samples: &[f32]) {
+        let take = samples.len().min(self.max_samples.saturating_sub(self.consumed));
+        self.pending.extend_from_slice(&samples[..take]);
+        self.consumed += take;
+
+        let frame_len = self.window.len();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            let energies = self.band_energies(start);
+            if let Some(prev) = &self.prev_energies {
+                self.words.push(frame_word(prev, &energies));
+            }
+            self.prev_energies = Some(energies);
+            start += self.hop;
+        }
+        self.pending.drain(..start);
+    }
+
+    /// Hex-encode the collected words.
+    pub fn finish(self) -> Result<String, String> {
+        if self.words.is_empty() {
+            return Err("Audio is too short to fingerprint".to_string());
+        }
+        Ok(self.words.iter().map(|w| format!("{:08x}", w)).collect())
+    }
+
+    fn band_energies(&self, start: usize) -> Vec<f64> {
+        let mut buf: Vec<Complex<f32>> = self.pending[start..start + self.window.len()]
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buf);
+
+        self.band_edges
+            .windows(2)
+            .map(|edge| {
+                let (lo, hi) = (edge[0], edge[1].max(edge[0] + 1));
+                buf[lo..hi].iter().map(|c| c.norm_sqr() as f64).sum()
+            })
+            .collect()
+    }
+}
+
+/// Bit `m` is set when the energy step between bands `m` and `m + 1`
+/// grew compared to the previous frame.
+fn frame_word(prev: &[f64], cur: &[f64]) -> u32 {
+    (0..FINGERPRINT_BANDS - 1).fold(0u32, |word, m| {
+        let delta = (cur[m] - cur[m + 1]) - (prev[m] - prev[m + 1]);
+        if delta > 0.0 { word | (1 << m) } else { word }
+    })
+}
+
+/// Decode the start of `path` and compute its fingerprint.
+pub fn fingerprint_file(path: &str) -> Result<String, String> {
+    info!("Fingerprinting: {}", path);
+
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = Path::new(path).extension() {
+        hint.with_extension(&ext.to_string_lossy());
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to probe format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| "No sample rate info".to_string())?;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+    let mut fingerprinter = Fingerprinter::new(sample_rate);
+
+    while !fingerprinter.is_full() {
+        let Ok(packet) = format.next_packet() else { break };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut audio_buf = AudioBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                decoded.convert(&mut audio_buf);
+
+                let channels = spec.channels.count();
+                let frames = audio_buf.frames();
+                let mono: Vec<f32> = (0..frames)
+                    .map(|i| (0..channels).map(|ch| audio_buf.chan(ch)[i]).sum::<f32>() / channels as f32)
+                    .collect();
+                fingerprinter.push(&mono);
+            }
+            Err(e) => {
+                warn!("Decode error (continuing): {}", e);
+            }
+        }
+    }
+
+    fingerprinter.finish()
+}
+
+/// Modification time of `path` in seconds since the epoch.
+pub fn file_mtime(path: &str) -> Option<i64> {
+    std::fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
+/// Fingerprint a file, reusing the value cached on its `tracks` row when
+/// the file has not been modified since. Files outside the library are
+/// fingerprinted without caching.
+pub fn fingerprint_track(db: &Database, path: &str) -> Result<String, String> {
+    let mtime = file_mtime(path).ok_or_else(|| format!("File not found: {}", path))?;
+
+    let cached: Option<(Option<String>, Option<i64>)> = db
+        .conn()
+        .query_row(
+            "SELECT fingerprint, fingerprint_mtime FROM tracks WHERE path = ?1",
+            params![path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read fingerprint: {}", e))?;
+
+    if let Some((Some(fingerprint), Some(cached_mtime))) = &cached {
+        if *cached_mtime == mtime {
+            return Ok(fingerprint.clone());
+        }
+    }
+
+    let fingerprint = fingerprint_file(path)?;
+
+    if cached.is_some() {
+        store_fingerprint(db, path, &fingerprint, mtime)?;
+    }
+
+    Ok(fingerprint)
+}
+
+/// Cache `fingerprint` on the track at `path`, computed from the file as
+/// of `mtime` (seconds since the epoch).
+pub fn store_fingerprint(db: &Database, path: &str, fingerprint: &str, mtime: i64) -> Result<(), String> {
+    db.conn()
+        .execute(
+            "UPDATE tracks SET fingerprint = ?1, fingerprint_mtime = ?2 WHERE path = ?3",
+            params![fingerprint, mtime, path],
+        )
+        .map_err(|e| format!("Failed to store fingerprint: {}", e))?;
+    Ok(())
+}
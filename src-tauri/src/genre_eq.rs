@@ -0,0 +1,183 @@
+//! EQ presets that follow the genre of the loaded track.
+//!
+//! Settings map a genre to one of the built-in presets. When a track loads
+//! and its genre is mapped, [`GenreEq::bands_for_track`] returns the preset's
+//! gains; the user's own EQ is remembered and comes back on the next
+//! unmapped track. A manual EQ change while a preset is active wins for the
+//! rest of that track and becomes the EQ to come back to. Either switch is
+//! announced with [`EQ_BANDS_CHANGED_EVENT`] so the equalizer UI follows.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+
+/// Settings key under which the genre mapping is persisted.
+pub const GENRE_EQ_KEY: &str = "genre_eq";
+
+/// Event sent with [`EqBandsChanged`] when the backend changes the EQ bands
+/// on its own.
+pub const EQ_BANDS_CHANGED_EVENT: &str = "eq-bands-changed";
+
+/// Payload of [`EQ_BANDS_CHANGED_EVENT`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqBandsChanged {
+    /// The bands now in effect, gains in dB.
+    pub eq_bands: Vec<f32>,
+}
+
+/// Bands closer than this are the same setting: the UI's sliders move in
+/// 0.24 dB steps, so smaller differences are rounding on the way back.
+const BAND_TOLERANCE_DB: f32 = 0.15;
+
+/// Whether `a` and `b` are different EQ settings.
+pub fn bands_differ(a: &[f32], b: &[f32]) -> bool {
+    a.len() != b.len() || a.iter().zip(b).any(|(a, b)| (a - b).abs() > BAND_TOLERANCE_DB)
+}
+
+/// The presets, shared with the frontend (`EQ_PRESETS` in
+/// `utils/constants.ts` reads the same file).
+const EQ_PRESETS_JSON: &str = include_str!("../eq_presets.json");
+
+/// A built-in 10-band preset, gains in dB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqPreset {
+    /// Identifier, the key in `eq_presets.json`.
+    pub id: String,
+    pub name: String,
+    pub bands: [f32; 10],
+}
+
+#[derive(Deserialize)]
+struct PresetEntry {
+    name: String,
+    bands: [f32; 10],
+}
+
+/// Every built-in preset.
+pub fn eq_presets() -> &'static [EqPreset] {
+    static PRESETS: OnceLock<Vec<EqPreset>> = OnceLock::new();
+    PRESETS.get_or_init(|| {
+        let entries: BTreeMap<String, PresetEntry> =
+            serde_json::from_str(EQ_PRESETS_JSON).expect("eq_presets.json is a map of 10-band presets");
+        entries
+            .into_iter()
+            .map(|(id, entry)| EqPreset { id, name: entry.name, bands: entry.bands })
+            .collect()
+    })
+}
+
+/// The preset with id or display name `name`, ignoring case.
+pub fn find_preset(name: &str) -> Option<&'static EqPreset> {
+    let name = name.trim();
+    eq_presets()
+        .iter()
+        .find(|p| p.id.eq_ignore_ascii_case(name) || p.name.eq_ignore_ascii_case(name))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GenreEqSettings {
+    pub enabled: bool,
+    /// Genre → preset id. Genres match case-insensitively.
+    pub presets: BTreeMap<String, String>,
+}
+
+impl GenreEqSettings {
+    pub fn validate(&self) -> AppResult<()> {
+        for (genre, preset) in &self.presets {
+            if genre.trim().is_empty() {
+                return Err(AppError::Validation("Genre cannot be empty".to_string()));
+            }
+            if find_preset(preset).is_none() {
+                return Err(AppError::Validation(format!("Unknown EQ preset '{}' for genre '{}'", preset, genre)));
+            }
+        }
+        Ok(())
+    }
+
+    /// The persisted settings, or the default (off) when unset or unreadable.
+    pub fn load(db: &Database) -> Self {
+        match db.get_json_setting::<Self>(GENRE_EQ_KEY) {
+            Ok(settings) => settings.unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to read genre EQ settings: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// The preset mapped to `genre`, if any.
+    pub fn preset_for(&self, genre: &str) -> Option<&'static EqPreset> {
+        let genre = genre.trim();
+        self.presets
+            .iter()
+            .find(|(mapped, _)| mapped.trim().eq_ignore_ascii_case(genre))
+            .and_then(|(_, preset)| find_preset(preset))
+    }
+}
+
+/// Tracks the user's EQ while a genre preset stands in for it.
+#[derive(Default)]
+pub struct GenreEq {
+    /// The user's band gains, kept while a genre preset is applied.
+    user_bands: Mutex<Option<Vec<f32>>>,
+}
+
+impl GenreEq {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// EQ gains to apply for the track at `path`, given the gains currently
+    /// in effect; `None` leaves the EQ alone. A mapped genre gets its
+    /// preset, anything else gets the user's EQ back if a preset replaced it.
+    /// Presets only apply to the 10-band layout they are written for.
+    pub fn bands_for_track(&self, db: &Database, path: &str, current_bands: &[f32]) -> Option<Vec<f32>> {
+        let settings = GenreEqSettings::load(db);
+        let preset = settings
+            .enabled
+            .then(|| db.get_track_by_path(path).ok().flatten())
+            .flatten()
+            .and_then(|track| track.genre)
+            .and_then(|genre| settings.preset_for(&genre))
+            .filter(|preset| preset.bands.len() == current_bands.len());
+
+        let mut user_bands = self.user_bands.lock().unwrap_or_else(|p| p.into_inner());
+        match preset {
+            Some(preset) => {
+                info!("Applying {} EQ preset for {}", preset.name, path);
+                user_bands.get_or_insert_with(|| current_bands.to_vec());
+                Some(preset.bands.to_vec())
+            }
+            None => user_bands.take(),
+        }
+    }
+
+    /// The user set the EQ by hand: keep it, and return to it rather than
+    /// the EQ from before the preset.
+    pub fn manual_change(&self) {
+        self.user_bands.lock().unwrap_or_else(|p| p.into_inner()).take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_parse_and_are_found_by_id_or_name() {
+        let presets = eq_presets();
+        assert!(presets.iter().any(|p| p.id == "FLAT" && p.bands == [0.0; 10]));
+        assert!(presets.iter().all(|p| !p.name.is_empty()));
+
+        assert_eq!(find_preset("bass boost").map(|p| p.id.as_str()), Some("BASS_BOOST"));
+        assert_eq!(find_preset(" rock ").map(|p| p.name.as_str()), Some("Rock"));
+        assert!(find_preset("polka").is_none());
+    }
+}
@@ -0,0 +1,261 @@
+//! Musical key detection for harmonic mixing.
+//!
+//! The mono signal is split into ~370 ms frames whose spectrum between
+//! [`MIN_FREQ_HZ`] and [`MAX_FREQ_HZ`] is folded into a 12-bin chromagram
+//! (energy per pitch class). The summed chromagram is correlated against
+//! the Krumhansl–Kessler major and minor key profiles in all twelve
+//! transpositions, and the best-matching key wins.
+//!
+//! The key name (e.g. "A minor") is stored on the `tracks` row in
+//! `musical_key`; its Camelot wheel code is derived from it on demand.
+
+use crate::database::Database;
+use crate::pcm_decode;
+use log::info;
+use rusqlite::params;
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// How much audio from the start of the file is analysed.
+pub const KEY_ANALYSIS_SECONDS: f64 = 180.0;
+
+const MIN_FREQ_HZ: f64 = 65.0;
+const MAX_FREQ_HZ: f64 = 2100.0;
+
+const PITCH_NAMES: [&str; 12] = ["C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B"];
+
+/// Krumhansl–Kessler probe-tone ratings, tonic first.
+const MAJOR_PROFILE: [f64; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f64; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Major,
+    Minor,
+}
+
+/// A key as a tonic pitch class (0 = C) and mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub tonic: usize,
+    pub mode: Mode,
+}
+
+impl Key {
+    /// e.g. "C major", "F# minor".
+    pub fn name(&self) -> String {
+        let mode = match self.mode {
+            Mode::Major => "major",
+            Mode::Minor => "minor",
+        };
+        format!("{} {}", PITCH_NAMES[self.tonic % 12], mode)
+    }
+
+    /// Parse a stored key name such as "F# minor" or "Db major". Sharps and
+    /// flats are both accepted; anything else gives `None`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (pitch, mode) = name.trim().split_once(' ')?;
+        let mode = match mode.trim().to_ascii_lowercase().as_str() {
+            "major" => Mode::Major,
+            "minor" => Mode::Minor,
+            _ => return None,
+        };
+        let tonic = match pitch {
+            "Db" => 1,
+            "D#" => 3,
+            "Gb" => 6,
+            "G#" => 8,
+            "A#" => 10,
+            _ => PITCH_NAMES.iter().position(|p| *p == pitch)?,
+        };
+        Some(Self { tonic, mode })
+    }
+
+    /// Camelot wheel code: C major is 8B, A minor 8A, and each step round
+    /// the wheel is a fifth.
+    pub fn camelot(&self) -> String {
+        let (number, letter) = self.camelot_position();
+        format!("{}{}", number, letter)
+    }
+
+    fn camelot_position(&self) -> (usize, char) {
+        let (major_tonic, letter) = match self.mode {
+            Mode::Major => (self.tonic, 'B'),
+            Mode::Minor => ((self.tonic + 3) % 12, 'A'),
+        };
+        ((major_tonic * 7 % 12 + 7) % 12 + 1, letter)
+    }
+
+    /// Whether the two keys mix harmonically: the same key, a neighbour on
+    /// the Camelot wheel, or the relative major/minor.
+    pub fn is_compatible(&self, other: &Key) -> bool {
+        let (a, a_letter) = self.camelot_position();
+        let (b, b_letter) = other.camelot_position();
+        if a_letter == b_letter {
+            let steps = (a + 12 - b) % 12;
+            steps == 0 || steps == 1 || steps == 11
+        } else {
+            a == b
+        }
+    }
+}
+
+/// What `analyze_key` reports back.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyInfo {
+    pub key: String,
+    pub camelot: String,
+}
+
+impl From<Key> for KeyInfo {
+    fn from(key: Key) -> Self {
+        Self { key: key.name(), camelot: key.camelot() }
+    }
+}
+
+/// Incremental key detector fed with mono samples.
+pub struct KeyDetector {
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    /// Pitch class of each FFT bin in range, `None` outside it.
+    bin_pitch: Vec<Option<usize>>,
+    max_samples: usize,
+    pending: Vec<f32>,
+    consumed: usize,
+    chroma: [f64; 12],
+}
+
+impl KeyDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        // Long frames so neighbouring semitones separate down in the bass.
+        let frame_len = ((sample_rate as f64 * 0.37) as usize).next_power_of_two().max(1024);
+
+        let window = (0..frame_len)
+            .map(|i| {
+                let x = std::f32::consts::PI * 2.0 * i as f32 / (frame_len - 1) as f32;
+                0.5 - 0.5 * x.cos()
+            })
+            .collect();
+
+        let bin_pitch = (0..frame_len / 2)
+            .map(|bin| {
+                let freq = bin as f64 * sample_rate as f64 / frame_len as f64;
+                (MIN_FREQ_HZ..=MAX_FREQ_HZ).contains(&freq).then(|| {
+                    let midi = (69.0 + 12.0 * (freq / 440.0).log2()).round() as i64;
+                    midi.rem_euclid(12) as usize
+                })
+            })
+            .collect();
+
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(frame_len),
+            window,
+            bin_pitch,
+            max_samples: (KEY_ANALYSIS_SECONDS * sample_rate as f64) as usize,
+            pending: Vec::with_capacity(frame_len * 2),
+            consumed: 0,
+            chroma: [0.0; 12],
+        }
+    }
+
+    /// True once enough audio has been seen; further samples are ignored.
+    pub fn is_full(&self) -> bool {
+        self.consumed >= self.max_samples
+    }
+
+    /// Feed mono samples. Anything past the analysis window is dropped.
+    pub fn push(&mut self, samples: &[f32]) {
+        let take = samples.len().min(self.max_samples.saturating_sub(self.consumed));
+        self.pending.extend_from_slice(&samples[..take]);
+        self.consumed += take;
+
+        let frame_len = self.window.len();
+        let mut start = 0;
+        while self.pending.len() - start >= frame_len {
+            self.add_frame(start);
+            start += frame_len / 2;
+        }
+        self.pending.drain(..start);
+    }
+
+    /// The best-matching key, or an error if too little tonal audio was seen.
+    pub fn finish(self) -> Result<Key, String> {
+        let total: f64 = self.chroma.iter().sum();
+        if total <= f64::EPSILON {
+            return Err("No tonal content to detect a key from".to_string());
+        }
+
+        let mut best: Option<(Key, f64)> = None;
+        for (mode, profile) in [(Mode::Major, &MAJOR_PROFILE), (Mode::Minor, &MINOR_PROFILE)] {
+            for tonic in 0..12 {
+                let rotated: Vec<f64> = (0..12).map(|pc| profile[(pc + 12 - tonic) % 12]).collect();
+                let score = correlation(&self.chroma, &rotated);
+                if best.is_none_or(|(_, s)| score > s) {
+                    best = Some((Key { tonic, mode }, score));
+                }
+            }
+        }
+        best.map(|(key, _)| key).ok_or_else(|| "No key found".to_string())
+    }
+
+    fn add_frame(&mut self, start: usize) {
+        let mut buf: Vec<Complex<f32>> = self.pending[start..start + self.window.len()]
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| Complex::new(s * w, 0.0))
+            .collect();
+        self.fft.process(&mut buf);
+
+        for (bin, pitch) in self.bin_pitch.iter().enumerate() {
+            if let Some(pc) = pitch {
+                self.chroma[*pc] += buf[bin].norm() as f64;
+            }
+        }
+    }
+}
+
+/// Pearson correlation of two equal-length series.
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+/// Decode the start of `path` and detect its key.
+pub fn detect_file_key(path: &str) -> Result<Key, String> {
+    let mut detector: Option<KeyDetector> = None;
+    pcm_decode::decode_file(path, |block| {
+        let detector = detector.get_or_insert_with(|| KeyDetector::new(block.sample_rate));
+        detector.push(block.mono);
+        !detector.is_full()
+    })?;
+    detector.ok_or_else(|| "No audio decoded".to_string())?.finish()
+}
+
+/// Store `key` on the track at `path`.
+pub fn store_key(db: &Database, path: &str, key: Key) -> Result<(), String> {
+    db.conn()
+        .execute("UPDATE tracks SET musical_key = ?1 WHERE path = ?2", params![key.name(), path])
+        .map_err(|e| format!("Failed to store key: {}", e))?;
+    Ok(())
+}
+
+/// Detect the key of the track at `path` and store it.
+pub fn analyze_track_key(db: &Database, path: &str) -> Result<KeyInfo, String> {
+    info!("Detecting key: {}", path);
+    let key = detect_file_key(path)?;
+    store_key(db, path, key)?;
+    info!("Key of {}: {} ({})", path, key.name(), key.camelot());
+    Ok(key.into())
+}
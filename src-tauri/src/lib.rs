@@ -1,15 +1,49 @@
+pub mod analysis;
+pub mod art_cache_format;
+pub mod art_extraction;
+pub mod art_palette;
+pub mod art_thumbnail;
+pub mod bpm;
+pub mod chapters;
 pub mod context_log;
+pub mod crossfade;
 pub mod database;
 pub mod database_album_art;
 pub mod database_failed_tracks;
 pub mod database_folders;
+pub mod database_history;
+pub mod database_ignored_paths;
+pub mod database_maintenance;
 pub mod database_playlist;
+pub mod database_pool;
 pub mod database_schema;
+pub mod database_settings;
 pub mod database_tracks;
+pub mod diagnostics;
 pub mod error;
+pub mod fade_durations;
+pub mod fingerprint;
+pub mod genre_eq;
+pub mod key_detection;
+pub mod library_export;
+pub mod media_container;
+pub mod metadata_lookup;
+pub mod next_track;
+pub mod open_files;
+pub mod pcm_decode;
+pub mod playlist_io;
 pub mod query_builder;
+pub mod r128_gain;
 pub mod replaygain;
 pub mod replaygain_store;
+pub mod scan_events;
 pub mod scanner;
+pub mod similar_tracks;
 pub mod smart_playlists;
+pub mod smart_shuffle;
+pub mod startup;
+pub mod tag_service;
 pub mod time_utils;
+pub mod track_end;
+pub mod track_files;
+pub mod waveform;
@@ -0,0 +1,301 @@
+//! Whole-library export to CSV or JSON, and merging such a file back in.
+//!
+//! Both formats carry every `Track` field under its snake_case name, so an
+//! export from one machine can be imported on another (or edited in a
+//! spreadsheet first). Rows are matched to the library by path.
+
+use std::fs;
+use std::path::Path;
+
+use log::info;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+use crate::scanner::Track;
+use crate::time_utils::now_millis;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryImportSummary {
+    /// Paths that were not in the library yet.
+    pub added: usize,
+    /// Paths already in the library that were merged into.
+    pub updated: usize,
+}
+
+const CSV_COLUMNS: [&str; 15] = [
+    "id", "path", "name", "title", "artist", "album", "genre", "year", "track_number",
+    "disc_number", "duration", "date_added", "rating", "play_count", "last_played",
+];
+
+/// Write every library track to `path`. Returns the number of tracks written.
+pub fn export_library(db: &Database, path: &Path, format: ExportFormat) -> AppResult<usize> {
+    let tracks = db.get_all_tracks().map_err(|e| AppError::Database(e.to_string()))?;
+    let contents = match format {
+        ExportFormat::Csv => tracks_to_csv(&tracks),
+        ExportFormat::Json => serde_json::to_string_pretty(&tracks)
+            .map_err(|e| AppError::Validation(format!("Failed to serialize library: {}", e)))?,
+    };
+    fs::write(path, contents)?;
+    info!("Exported {} tracks to {}", tracks.len(), path.display());
+    Ok(tracks.len())
+}
+
+/// Merge the tracks in `path` into the library.
+///
+/// New paths are added with their ratings and play statistics. For paths
+/// already in the library, `overwrite` replaces metadata and statistics with
+/// the imported values; otherwise only empty fields are filled in, an
+/// unrated track takes the imported rating, and play count and last played
+/// keep whichever is higher.
+pub fn import_library(db: &Database, path: &Path, format: ExportFormat, overwrite: bool) -> AppResult<LibraryImportSummary> {
+    let contents = fs::read_to_string(path)?;
+    let tracks = match format {
+        ExportFormat::Csv => tracks_from_csv(&contents)?,
+        ExportFormat::Json => serde_json::from_str::<Vec<Track>>(&contents)
+            .map_err(|e| AppError::Validation(format!("Invalid library JSON: {}", e)))?,
+    };
+
+    let summary = merge_tracks(db, &tracks, overwrite).map_err(|e| AppError::Database(e.to_string()))?;
+    info!(
+        "Imported {} from {}: {} added, {} updated",
+        tracks.len(),
+        path.display(),
+        summary.added,
+        summary.updated
+    );
+    Ok(summary)
+}
+
+fn merge_tracks(db: &Database, tracks: &[Track], overwrite: bool) -> rusqlite::Result<LibraryImportSummary> {
+    let mut summary = LibraryImportSummary::default();
+    let mut conn = db.conn();
+    let tx = conn.transaction()?;
+
+    for track in tracks {
+        let existing: Option<String> = tx
+            .query_row("SELECT id FROM tracks WHERE path = ?1", params![track.path], |row| row.get(0))
+            .optional()?;
+
+        if let Some(id) = existing {
+            let sql = if overwrite {
+                "UPDATE tracks SET name = ?2, title = ?3, artist = ?4, album = ?5, genre = ?6, year = ?7,
+                    track_number = ?8, disc_number = ?9, duration = ?10, rating = ?11, play_count = ?12,
+                    last_played = ?13
+                 WHERE id = ?1"
+            } else {
+                "UPDATE tracks SET name = COALESCE(NULLIF(name, ''), ?2), title = COALESCE(title, ?3),
+                    artist = COALESCE(artist, ?4), album = COALESCE(album, ?5), genre = COALESCE(genre, ?6),
+                    year = COALESCE(year, ?7),
+                    track_number = COALESCE(track_number, ?8), disc_number = COALESCE(disc_number, ?9),
+                    duration = CASE WHEN duration > 0 THEN duration ELSE ?10 END,
+                    rating = CASE WHEN rating = 0 THEN ?11 ELSE rating END,
+                    play_count = MAX(play_count, ?12), last_played = MAX(last_played, ?13)
+                 WHERE id = ?1"
+            };
+            tx.execute(
+                sql,
+                params![
+                    id,
+                    track.name,
+                    track.title,
+                    track.artist,
+                    track.album,
+                    track.genre,
+                    track.year,
+                    track.track_number,
+                    track.disc_number,
+                    track.duration,
+                    track.rating.clamp(0, 5),
+                    track.play_count.max(0),
+                    track.last_played,
+                ],
+            )?;
+            summary.updated += 1;
+        } else {
+            // Keep the exported id unless another track already uses it.
+            let id_taken = tx
+                .query_row("SELECT 1 FROM tracks WHERE id = ?1", params![track.id], |_| Ok(()))
+                .optional()?
+                .is_some();
+            let id = if id_taken || track.id.is_empty() {
+                uuid::Uuid::new_v4().to_string()
+            } else {
+                track.id.clone()
+            };
+            let date_added = if track.date_added > 0 { track.date_added } else { now_millis() };
+            // file_modified stays 0 so the next incremental scan re-reads the file.
+            tx.execute(
+                "INSERT INTO tracks (id, path, name, title, artist, album, genre, year, track_number,
+                    disc_number, duration, date_added, rating, play_count, last_played, file_modified)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, 0)",
+                params![
+                    id,
+                    track.path,
+                    track.name,
+                    track.title,
+                    track.artist,
+                    track.album,
+                    track.genre,
+                    track.year,
+                    track.track_number,
+                    track.disc_number,
+                    track.duration,
+                    date_added,
+                    track.rating.clamp(0, 5),
+                    track.play_count.max(0),
+                    track.last_played,
+                ],
+            )?;
+            summary.added += 1;
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+// ── CSV ────────────────────────────────────────────────────────────
+
+/// Quote `field` if it contains a separator, quote or line break (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn tracks_to_csv(tracks: &[Track]) -> String {
+    let opt = |v: &Option<String>| v.clone().unwrap_or_default();
+    let num = |v: Option<i32>| v.map(|n| n.to_string()).unwrap_or_default();
+
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for t in tracks {
+        let row = [
+            t.id.clone(),
+            t.path.clone(),
+            t.name.clone(),
+            opt(&t.title),
+            opt(&t.artist),
+            opt(&t.album),
+            opt(&t.genre),
+            num(t.year),
+            num(t.track_number),
+            num(t.disc_number),
+            t.duration.to_string(),
+            t.date_added.to_string(),
+            t.rating.to_string(),
+            t.play_count.to_string(),
+            t.last_played.to_string(),
+        ];
+        out.push_str(&row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Split CSV text into records, honouring quoted fields that contain
+/// separators, doubled quotes and line breaks.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+fn tracks_from_csv(text: &str) -> AppResult<Vec<Track>> {
+    let mut records = parse_csv(text.trim_start_matches('\u{feff}')).into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| AppError::Validation("Library CSV is empty".to_string()))?;
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let path_col = column("path")
+        .ok_or_else(|| AppError::Validation("Library CSV has no \"path\" column".to_string()))?;
+    let columns: Vec<Option<usize>> = CSV_COLUMNS.iter().map(|c| column(c)).collect();
+
+    let mut tracks = Vec::new();
+    for (line, record) in records.enumerate() {
+        if record.iter().all(|f| f.is_empty()) {
+            continue;
+        }
+        let get = |i: usize| -> Option<&str> {
+            columns[i].and_then(|c| record.get(c)).map(|s| s.as_str()).filter(|s| !s.is_empty())
+        };
+        let parse = |i: usize| -> AppResult<Option<f64>> {
+            get(i)
+                .map(|v| {
+                    v.trim().parse::<f64>().map_err(|_| {
+                        AppError::Validation(format!("Row {}: invalid {} \"{}\"", line + 2, CSV_COLUMNS[i], v))
+                    })
+                })
+                .transpose()
+        };
+
+        let path = record.get(path_col).cloned().unwrap_or_default();
+        if path.is_empty() {
+            return Err(AppError::Validation(format!("Row {}: missing path", line + 2)));
+        }
+        let name = get(2).map(str::to_string).unwrap_or_else(|| {
+            Path::new(&path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        tracks.push(Track {
+            id: get(0).map(str::to_string).unwrap_or_default(),
+            path,
+            name,
+            title: get(3).map(str::to_string),
+            artist: get(4).map(str::to_string),
+            album: get(5).map(str::to_string),
+            genre: get(6).map(str::to_string),
+            year: parse(7)?.map(|v| v as i32),
+            track_number: parse(8)?.map(|v| v as i32),
+            disc_number: parse(9)?.map(|v| v as i32),
+            duration: parse(10)?.unwrap_or(0.0),
+            date_added: parse(11)?.unwrap_or(0.0) as i64,
+            rating: parse(12)?.unwrap_or(0.0) as i32,
+            play_count: parse(13)?.unwrap_or(0.0) as i32,
+            last_played: parse(14)?.unwrap_or(0.0) as i64,
+        });
+    }
+    Ok(tracks)
+}
@@ -1,7 +1,14 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use serde::{Serialize, Deserialize};
 
+/// Parsed files kept by `LyricsCache` before the least recently used is evicted.
+const LYRICS_CACHE_CAPACITY: usize = 32;
+
 /// Parsed LRC lyric line
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LyricLine {
@@ -16,6 +23,7 @@ pub struct LrcMetadata {
     pub artist: Option<String>,
     pub album: Option<String>,
     pub by: Option<String>,
+    /// `[offset:]` in milliseconds; positive values make lyrics appear sooner.
     pub offset: i32,
 }
 
@@ -50,6 +58,13 @@ impl Lrc {
             }
         }
 
+        // Apply the file's [offset] once every line is known, wherever the
+        // tag appears. Per the LRC format a positive offset shows lines sooner.
+        let shift = metadata.offset as f64 / 1000.0;
+        for line in &mut lines {
+            line.timestamp = (line.timestamp - shift).max(0.0);
+        }
+
         lines.sort_by(|a, b| {
             a.timestamp
                 .partial_cmp(&b.timestamp)
@@ -89,7 +104,7 @@ impl Lrc {
                                 
                                 if !text.is_empty() || lines.is_empty() {
                                     lines.push(LyricLine {
-                                        timestamp: timestamp + (metadata.offset as f64 / 1000.0),
+                                        timestamp,
                                         text,
                                     });
                                 }
@@ -116,18 +131,109 @@ impl Lrc {
         }
     }
 
-    /// Get lyric at specific time
+    /// Get lyric at specific time. For bilingual files this is the first
+    /// (original) line of the group; see `get_lyrics_at` for all of them.
     pub fn get_lyric_at(&self, time: f64) -> Option<&LyricLine> {
-        self.lines
-            .iter()
-            .rev()
-            .find(|line| line.timestamp <= time)
+        self.get_lyrics_at(time).into_iter().next()
+    }
+
+    /// All lines sharing the active timestamp at `time`, in file order —
+    /// e.g. an original line followed by its translation. Normal files
+    /// yield at most one line.
+    pub fn get_lyrics_at(&self, time: f64) -> Vec<&LyricLine> {
+        // Lines are sorted (stably) by timestamp, so the active group is the
+        // run of equal timestamps just before the first line past `time`.
+        let end = self.lines.partition_point(|line| line.timestamp <= time);
+        let Some(active) = end.checked_sub(1).map(|i| self.lines[i].timestamp) else {
+            return Vec::new();
+        };
+        let start = self.lines[..end].partition_point(|line| line.timestamp < active);
+        self.lines[start..end].iter().collect()
+    }
+
+    /// Like `get_lyrics_at`, with a user sync adjustment in seconds.
+    pub fn get_lyrics_at_offset(&self, time: f64, offset_secs: f64) -> Vec<&LyricLine> {
+        self.get_lyrics_at(time + offset_secs)
+    }
+
+    /// Like `get_lyric_at`, with a user sync adjustment in seconds on top of
+    /// the file's own offset. Positive values show lines sooner.
+    pub fn get_lyric_at_offset(&self, time: f64, offset_secs: f64) -> Option<&LyricLine> {
+        self.get_lyric_at(time + offset_secs)
+    }
+}
+
+/// Reads and parses an LRC file. Injectable so tests can count reads.
+pub type LrcLoader = fn(&Path) -> Result<Lrc, String>;
+
+struct CacheEntry {
+    mtime: Option<SystemTime>,
+    lrc: Arc<Lrc>,
+    last_used: u64,
+}
+
+/// LRU cache of parsed LRC files keyed by path and mtime. Live lyric
+/// lookups run several times a second, so a hit must not touch the file
+/// beyond a metadata call; an entry is reparsed once its mtime changes.
+pub struct LyricsCache {
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+    clock: AtomicU64,
+    load: LrcLoader,
+}
+
+impl LyricsCache {
+    pub fn new() -> Self {
+        Self::with_loader(Lrc::from_file)
+    }
+
+    fn with_loader(load: LrcLoader) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            clock: AtomicU64::new(0),
+            load,
+        }
+    }
+
+    pub fn get(&self, path: &Path) -> Result<Arc<Lrc>, String> {
+        let mtime = fs::metadata(path).ok().and_then(|m| m.modified().ok());
+        let now = self.clock.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+
+        if let Some(entry) = entries.get_mut(path) {
+            if entry.mtime == mtime {
+                entry.last_used = now;
+                return Ok(entry.lrc.clone());
+            }
+        }
+
+        let lrc = Arc::new((self.load)(path)?);
+        if !entries.contains_key(path) && entries.len() >= LYRICS_CACHE_CAPACITY {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(p, _)| p.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            path.to_path_buf(),
+            CacheEntry { mtime, lrc: lrc.clone(), last_used: now },
+        );
+        Ok(lrc)
+    }
+}
+
+impl Default for LyricsCache {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicUsize;
 
     #[test]
     fn test_parse_lrc() {
@@ -150,4 +256,115 @@ mod tests {
         assert_eq!(lrc.get_lyric_at(25.0).unwrap().text, "Line 2");
         assert!(lrc.get_lyric_at(5.0).is_none());
     }
+
+    #[test]
+    fn bilingual_lines_share_a_timestamp() {
+        let content = "[00:05.00]Intro\n[00:10.00]Bonjour\n[00:10.00]Hello\n[00:15.00]Fin\n[00:15.00]End";
+        let lrc = Lrc::from_str(content).unwrap();
+
+        let texts = |t: f64| -> Vec<String> {
+            lrc.get_lyrics_at(t).iter().map(|l| l.text.clone()).collect()
+        };
+        assert_eq!(texts(12.0), vec!["Bonjour", "Hello"]);
+        assert_eq!(texts(16.0), vec!["Fin", "End"]);
+        assert_eq!(texts(6.0), vec!["Intro"]);
+        assert!(texts(1.0).is_empty());
+
+        // The single-line lookup returns the original.
+        assert_eq!(lrc.get_lyric_at(12.0).unwrap().text, "Bonjour");
+    }
+
+    #[test]
+    fn file_offset_applies_to_every_line() {
+        // Positive offset shows lines sooner, even when the tag comes last.
+        let content = "[00:10.00]Line 1\n[00:20.00]Line 2\n[offset:500]";
+        let lrc = Lrc::from_str(content).unwrap();
+
+        assert_eq!(lrc.metadata.offset, 500);
+        assert_eq!(lrc.lines[0].timestamp, 9.5);
+        assert_eq!(lrc.lines[1].timestamp, 19.5);
+    }
+
+    #[test]
+    fn offset_sign_follows_the_lrc_spec() {
+        // The LRC format defines [offset:] in milliseconds: "+" shifts the
+        // lyrics up (shown sooner), "-" shifts them down (shown later).
+        let sooner = Lrc::from_str("[offset:+1000]\n[00:10.00]Line").unwrap();
+        assert_eq!(sooner.lines[0].timestamp, 9.0);
+        assert_eq!(sooner.get_lyric_at(9.5).unwrap().text, "Line");
+
+        let later = Lrc::from_str("[offset:-1000]\n[00:10.00]Line").unwrap();
+        assert_eq!(later.lines[0].timestamp, 11.0);
+        assert!(later.get_lyric_at(10.5).is_none());
+
+        // Shifting sooner never goes before the start of the track.
+        let clamped = Lrc::from_str("[offset:2000]\n[00:01.00]Line").unwrap();
+        assert_eq!(clamped.lines[0].timestamp, 0.0);
+    }
+
+    #[test]
+    fn user_offset_shifts_returned_line() {
+        let content = "[00:10.00]Line 1\n[00:20.00]Line 2";
+        let lrc = Lrc::from_str(content).unwrap();
+
+        assert_eq!(lrc.get_lyric_at_offset(19.6, 0.0).unwrap().text, "Line 1");
+        assert_eq!(lrc.get_lyric_at_offset(19.6, 0.5).unwrap().text, "Line 2");
+        assert_eq!(lrc.get_lyric_at_offset(20.2, -0.5).unwrap().text, "Line 1");
+    }
+
+    static READS: AtomicUsize = AtomicUsize::new(0);
+
+    fn counting_loader(path: &Path) -> Result<Lrc, String> {
+        READS.fetch_add(1, Ordering::SeqCst);
+        Lrc::from_file(path)
+    }
+
+    #[test]
+    fn cache_hits_skip_reads_until_the_file_changes() {
+        let dir = std::env::temp_dir().join(format!("vplayer_lyrics_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("song.lrc");
+        fs::write(&path, "[00:01.00]Old").unwrap();
+
+        let cache = LyricsCache::with_loader(counting_loader);
+        let first = cache.get(&path).unwrap();
+        let second = cache.get(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(READS.load(Ordering::SeqCst), 1);
+
+        fs::write(&path, "[00:01.00]New").unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert_eq!(cache.get(&path).unwrap().lines[0].text, "New");
+        assert_eq!(READS.load(Ordering::SeqCst), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let dir = std::env::temp_dir().join(format!("vplayer_lyrics_lru_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<PathBuf> = (0..=LYRICS_CACHE_CAPACITY)
+            .map(|i| {
+                let path = dir.join(format!("{}.lrc", i));
+                fs::write(&path, "[00:01.00]Line").unwrap();
+                path
+            })
+            .collect();
+
+        let cache = LyricsCache::new();
+        let kept = cache.get(&paths[0]).unwrap();
+        let evicted = cache.get(&paths[1]).unwrap();
+        for path in &paths[2..LYRICS_CACHE_CAPACITY] {
+            cache.get(path).unwrap();
+        }
+        cache.get(&paths[0]).unwrap(); // touch: paths[1] is now the oldest
+        cache.get(&paths[LYRICS_CACHE_CAPACITY]).unwrap();
+
+        assert!(Arc::ptr_eq(&kept, &cache.get(&paths[0]).unwrap()));
+        assert!(!Arc::ptr_eq(&evicted, &cache.get(&paths[1]).unwrap()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -4,43 +4,87 @@
 )]
 
 // Core modules
+mod art_cache_format;
+mod art_extraction;
+mod art_palette;
+mod art_thumbnail;
 mod audio;
 mod scanner;
+mod scan_events;
+mod chapters;
 mod context_log;
+mod crossfade;
 mod database;
 mod database_album_art;
 mod database_failed_tracks;
 mod database_folders;
+mod database_history;
+mod database_ignored_paths;
+mod database_maintenance;
 mod database_playlist;
+mod database_pool;
 mod database_schema;
+mod database_settings;
 mod database_tracks;
+mod diagnostics;
 mod error;
 mod watcher;
 mod playlist_io;
+mod next_track;
 mod query_builder;
 mod smart_playlists;
+mod similar_tracks;
+mod smart_shuffle;
 mod validation;
 mod lyrics;
+mod r128_gain;
 mod replaygain;
 mod replaygain_store;
 mod tag_service;
 mod effects;
+mod shortcuts;
+mod startup;
 mod visualizer;
 mod commands;
 mod time_utils;
+mod track_end;
+mod track_files;
+mod waveform;
+mod open_files;
+mod fade_durations;
+mod fingerprint;
+mod genre_eq;
+mod pcm_decode;
+mod analysis;
+mod bpm;
+mod key_detection;
+mod library_export;
+mod media_container;
+mod metadata_lookup;
 
-use audio::AudioPlayer;
+use audio::{AudioPlayer, NormalizeMode};
+use audio::device::{AudioConfig, AUDIO_CONFIG_KEY};
+use audio::normalize::NORMALIZE_MODE_KEY;
+use audio::resume::{POSITION_SAVE_INTERVAL, RESUME_PER_TRACK_KEY};
+use audio::silence::{SilenceTrimConfig, SILENCE_TRIM_KEY};
+use replaygain::{ReplayGainMode, REPLAYGAIN_MODE_KEY, REPLAYGAIN_PREAMP_KEY};
+use audio::progress::{ProgressTicker, DEFAULT_PROGRESS_INTERVAL_MS, PROGRESS_INTERVAL_KEY};
 use database::Database;
+use track_end::TrackEnd;
 use watcher::FolderWatcher;
 use visualizer::Visualizer;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{Manager, Emitter};
 use tauri::tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState};
 use tauri::menu::{Menu, MenuItem};
 use log::{info, warn};
 use serde::{Deserialize, Serialize};
 
+/// How often `playback-tick` goes out while playing.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Payload emitted every ~100 ms while a track is loaded.
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -52,41 +96,81 @@ struct PlaybackTick {
     is_paused: bool,
 }
 
+/// Payload of `playback-progress`, emitted at the configurable progress
+/// interval while playing.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PlaybackProgress {
+    position: f64,
+    duration: f64,
+    is_playing: bool,
+}
+
+/// Payload of `track-error`, emitted instead of `track-ended` when the
+/// track stopped because reading its file failed partway.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TrackError {
+    path: Option<String>,
+    position: f64,
+    message: String,
+}
+
+/// Payload of `track-unavailable`, emitted instead of `track-ended` when
+/// the current file can no longer be read.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TrackUnavailable {
+    path: Option<String>,
+    position: f64,
+    /// Whether the frontend should move on to the next queued track.
+    auto_skip: bool,
+}
+
 // Re-export commands for use in invoke_handler
 use commands::{
     // Audio commands
-    load_track, play_audio, pause_audio, stop_audio, set_volume, seek_to,
-    get_position, get_duration, is_playing, is_finished, recover_audio,
-    get_audio_devices, set_audio_device, preload_track, swap_to_preloaded,
+    load_track, play_audio, pause_audio, stop_audio, set_volume, toggle_mute, set_muted, is_muted, seek_to, seek_relative,
+    get_position, get_chapters, next_chapter, previous_chapter, get_duration, is_playing, is_finished, recover_audio, set_progress_interval, can_seek,
+    get_audio_devices, test_audio_device, set_audio_device, preload_track, swap_to_preloaded,
     clear_preload, has_preloaded, get_preloaded_path, set_balance, get_balance,
     get_audio_health,
     // Library commands
-    scan_folder, scan_folder_incremental, cancel_scan, get_track_ids_for_folder, get_all_tracks, get_filtered_tracks, get_tracks_page, get_all_folders,
+    scan_folder, scan_folder_incremental, rescan_folder, set_folder_scan_options, get_folder_scan_options, add_ignored_path, remove_ignored_path, list_ignored_paths, cancel_scan, set_scan_progress_throttle, get_scan_progress_throttle, get_track_ids_for_folder, get_all_tracks, get_filtered_tracks, get_tracks_page, get_all_folders, get_folders_with_counts,
     remove_folder, clear_failed_tracks, set_track_rating, check_missing_files,
-    update_track_path, find_duplicates, remove_track, remove_duplicate_folders, increment_play_count,
-    get_recently_played, get_most_played, get_album_art, get_album_art_batch, extract_and_cache_album_art,
-    update_track_tags, show_in_folder, reset_play_count, write_text_file,
+    update_track_path, move_track_file, refresh_track_metadata, update_tags_bulk, read_all_tags, write_tag, remove_tag, find_duplicates, fingerprint_track, analyze_bpm, analyze_key, analyze_all, cancel_analysis, lookup_track_metadata, apply_metadata_candidate, set_acoustid_api_key, remove_track, remove_duplicate_folders, increment_play_count, increment_skip_count,
+    get_recently_played, get_most_played, get_unplayed_tracks, get_unplayed_track_count, get_suspect_tracks, get_genres, get_years, build_smart_queue, get_similar_tracks, get_listening_stats, get_album_art, get_album_art_thumbnail, get_album_art_batch, get_album_art_cache_settings, set_album_art_cache_settings, extract_and_cache_album_art, extract_art_colors, prepare_next, set_album_art,
+    update_track_tags, show_in_folder, reset_play_count, write_text_file, export_library, import_library,
     // Playlist commands
     create_playlist, get_all_playlists, delete_playlist, rename_playlist,
     add_track_to_playlist, add_tracks_to_playlist, remove_track_from_playlist,
-    reorder_playlist_tracks, get_playlist_tracks, export_playlist, import_playlist,
+    reorder_playlist_tracks, get_playlist_tracks, validate_playlist, export_playlist, import_playlist, import_playlists_from_folder,
     // Smart playlist commands
     create_smart_playlist, get_all_smart_playlists, get_smart_playlist,
     update_smart_playlist, delete_smart_playlist, execute_smart_playlist,
     // Watcher commands
     start_folder_watch, stop_folder_watch, get_watched_folders,
     // Effects commands
-    set_audio_effects, get_audio_effects, set_effects_enabled, is_effects_enabled,
+    set_audio_effects, get_audio_effects, reset_effects, get_genre_eq_settings, set_genre_eq_settings, set_effects_enabled, is_effects_enabled,
+    set_loudness_enabled, is_loudness_enabled, get_clip_stats, measure_effects_cost,
     // Visualizer commands
-    get_visualizer_data, set_visualizer_mode, set_beat_sensitivity, get_track_waveform,
+    get_visualizer_data, set_visualizer_mode, set_beat_sensitivity, set_min_beat_interval, set_beat_band, set_visualizer_agc_decay, get_visualizer_bin_frequencies, set_pcm_tap_enabled, is_pcm_tap_enabled, get_pcm_tap, get_track_waveform,
     // Lyrics commands
-    load_lyrics, get_lyric_at_time,
+    load_lyrics, get_lyric_at_time, get_lyrics_at_time,
     // ReplayGain commands
-    analyze_replaygain, get_track_replaygain, get_album_replaygain, analyze_album_replaygain, set_replaygain, clear_replaygain,
+    analyze_replaygain, get_track_replaygain, write_replaygain_tags, get_album_replaygain, analyze_album_replaygain, set_replaygain, clear_replaygain,
+    set_track_volume_override, get_track_volume_override, set_normalize_mode, get_normalize_mode,
+    set_silence_trim, get_silence_trim, set_crossfade_policy, get_crossfade_policy, should_crossfade, get_fade_durations, set_sleep_fade_secs, set_crossfade_secs, set_audio_config, get_audio_config, get_output_format, set_resume_per_track, get_resume_per_track, set_skip_unavailable, get_skip_unavailable, set_track_position, get_track_position,
+    set_playback_speed, get_playback_speeds, get_queue_remaining_duration,
+    get_replaygain_target, set_replaygain_target, get_device_replaygain_targets, set_device_replaygain_target, set_replaygain_mode, get_replaygain_mode, set_replaygain_preamp, get_replaygain_preamp,
     // Cache/System commands
-    clear_album_art_cache, get_cache_size, get_database_size, get_performance_stats, get_runtime_diagnostics, vacuum_database, enforce_cache_limit,
+    clear_album_art_cache, get_cache_size, get_database_size, get_performance_stats, get_runtime_diagnostics, get_diagnostics, vacuum_database, get_auto_vacuum_settings, set_auto_vacuum_settings, cleanup_database, enforce_cache_limit,
     // Tray commands
-    set_tray_settings, get_tray_settings,
+    set_tray_settings, get_tray_settings, set_start_minimized, get_start_minimized,
+    // Shortcut commands
+    register_shortcut, unregister_shortcut, get_shortcuts,
+    // Open-with commands
+    take_opened_files,
 };
 
 /// Application state shared across all Tauri commands
@@ -97,8 +181,24 @@ pub struct AppState {
     pub visualizer: Arc<Mutex<Visualizer>>,
     pub tray_settings: Arc<Mutex<TraySettings>>,
     pub scan_cancel_flag: Arc<std::sync::atomic::AtomicBool>,
+    /// Set by `cancel_analysis` to stop a running `analyze_all`.
+    pub analysis_cancel_flag: Arc<std::sync::atomic::AtomicBool>,
     pub current_scan_id: Arc<Mutex<Option<String>>>,
     pub app_start_time: i64,
+    /// Action → accelerator for the currently bound global shortcuts.
+    pub shortcut_bindings: Arc<Mutex<HashMap<String, String>>>,
+    /// Gates `playback-progress` emission in the broadcast thread.
+    pub progress_ticker: Arc<ProgressTicker>,
+    /// Files opened via the OS, waiting for the frontend to collect them.
+    pub opened_files: Arc<Mutex<Option<open_files::OpenRequest>>>,
+    /// Parsed LRC files for live lyric lookups.
+    pub lyrics_cache: Arc<lyrics::LyricsCache>,
+    /// Coalesces concurrent album art extractions of the same file.
+    pub art_extractor: Arc<art_extraction::ArtExtractor>,
+    /// The user's EQ while a genre preset stands in for it.
+    pub genre_eq: Arc<genre_eq::GenreEq>,
+    /// Album art colour palettes by art hash.
+    pub palette_cache: Arc<art_palette::PaletteCache>,
 }
 
 /// Settings that control system-tray behaviour.
@@ -127,6 +227,19 @@ impl Default for TraySettings {
 
 fn main() {
     tauri::Builder::default()
+        // Must be registered first. A second launch ("Open with" while
+        // VPlayer is running) exits and forwards its arguments here.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+            let opened = open_files::audio_files_from_args(argv.iter().skip(1), std::path::Path::new(&cwd));
+            if let Some(request) = open_files::OpenRequest::from_paths(opened) {
+                commands::dispatch_open_request(app, request);
+            }
+        }))
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
@@ -138,6 +251,13 @@ fn main() {
             info!("Initializing VPlayer application");
             let player = Arc::new(AudioPlayer::new()
                 .map_err(|e| format!("Failed to initialize audio player: {}", e))?);
+
+            // Tell the UI whenever the output is reopened so it can resync
+            // its position and volume display.
+            let reinit_handle = app.handle().clone();
+            player.set_reinit_listener(Box::new(move |event| {
+                let _ = reinit_handle.emit("audio-reinitialized", event);
+            }));
             
             // Keep a clone for the position-broadcast thread
             let player_for_broadcast = player.clone();
@@ -159,28 +279,110 @@ fn main() {
             
             // Initialize visualizer
             let visualizer = Visualizer::new(44100, 64);
+
+            let shortcut_bindings = shortcuts::load_bindings(&db);
+
+            let progress_interval = db
+                .get_json_setting::<u64>(PROGRESS_INTERVAL_KEY)
+                .ok()
+                .flatten()
+                .unwrap_or(DEFAULT_PROGRESS_INTERVAL_MS);
+            let progress_ticker = Arc::new(ProgressTicker::new(progress_interval));
+
+            if let Ok(Some(true)) = db.get_json_setting::<bool>(commands::LOUDNESS_SETTING_KEY) {
+                player.set_loudness_enabled(true);
+            }
+            if let Ok(Some(mode)) = db.get_json_setting::<NormalizeMode>(NORMALIZE_MODE_KEY) {
+                player.set_normalize_mode(mode);
+            }
+            if let Ok(Some(mode)) = db.get_json_setting::<ReplayGainMode>(REPLAYGAIN_MODE_KEY) {
+                player.set_replaygain_mode(mode);
+            }
+            if let Ok(Some(preamp_db)) = db.get_json_setting::<f32>(REPLAYGAIN_PREAMP_KEY) {
+                player.set_replaygain_preamp(preamp_db);
+            }
+            if let Ok(Some(config)) = db.get_json_setting::<SilenceTrimConfig>(SILENCE_TRIM_KEY) {
+                player.set_silence_trim(config);
+            }
+            if let Ok(Some(true)) = db.get_json_setting::<bool>(RESUME_PER_TRACK_KEY) {
+                player.set_resume_per_track(true);
+            }
+            if let Ok(Some(config)) = db.get_json_setting::<AudioConfig>(AUDIO_CONFIG_KEY) {
+                if let Err(e) = player.set_audio_config(config) {
+                    warn!("Failed to apply saved audio config: {}", e);
+                }
+            }
+            let db = Arc::new(db);
+            let resume_db = db.clone();
+            player.set_resume_lookup(Box::new(move |path, duration| {
+                audio::resume::saved_position(&resume_db, path, duration)
+            }));
             
             app.manage(AppState {
                 player: player.clone(),
-                db: Arc::new(db),
+                db: db.clone(),
                 watcher: Arc::new(Mutex::new(watcher)),
                 visualizer: Arc::new(Mutex::new(visualizer)),
-                tray_settings: Arc::new(Mutex::new(TraySettings::default())),
+                tray_settings: Arc::new(Mutex::new(TraySettings {
+                    start_minimized: startup::start_minimized(&db),
+                    ..TraySettings::default()
+                })),
                 scan_cancel_flag: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                analysis_cancel_flag: Arc::new(std::sync::atomic::AtomicBool::new(false)),
                 current_scan_id: Arc::new(Mutex::new(None)),
                 app_start_time: crate::time_utils::now_millis(),
+                shortcut_bindings: Arc::new(Mutex::new(shortcut_bindings.clone())),
+                progress_ticker: progress_ticker.clone(),
+                opened_files: Arc::new(Mutex::new(None)),
+                lyrics_cache: Arc::new(lyrics::LyricsCache::new()),
+                art_extractor: Arc::new(art_extraction::ArtExtractor::new()),
+                genre_eq: Arc::new(genre_eq::GenreEq::new()),
+                palette_cache: Arc::new(art_palette::PaletteCache::new()),
             });
             
+            // Auto-vacuum can take seconds on a large library; keep it off
+            // the startup path.
+            let vacuum_db = db.clone();
+            let vacuum_player = player.clone();
+            std::thread::spawn(move || {
+                database_maintenance::auto_vacuum_on_startup(&vacuum_db, || vacuum_player.is_playing())
+            });
+
+            // Output device hotplug: poll the device list and emit
+            // `devices-changed` so the device picker updates live.
+            let devices_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                let mut watcher = audio::device::DeviceListWatcher::new();
+                loop {
+                    if let Ok(devices) = AudioPlayer::get_audio_devices() {
+                        if let Some(change) = watcher.observe(devices, Instant::now()) {
+                            info!(
+                                "Output devices changed (added: {:?}, removed: {:?})",
+                                change.added, change.removed
+                            );
+                            let _ = devices_handle.emit("devices-changed", change);
+                        }
+                    }
+                    std::thread::sleep(audio::device::DEVICE_POLL_INTERVAL);
+                }
+            });
+
             // ── Position-broadcast thread (#4) ──────────────────────────
-            // Emits `playback-tick` every ~100 ms while playing, and
-            // `track-ended` when the sink empties after playback.
+            // Emits `playback-tick` every ~100 ms while playing,
+            // `playback-progress` at the user-configurable progress interval
+            // (silent while paused/stopped), and `track-ended` when the sink
+            // empties after playback — or `track-unavailable` /
+            // `track-error` when it emptied because the file went away or
+            // failed to read partway through.
             //
             // Uses `broadcast_snapshot()` to capture is_playing, is_finished,
             // position, and duration under a single lock — preventing the race
             // where state changes between separate queries.
             //
-            // Adaptive sleep: 100ms while playing for smooth UI updates,
-            // 1000ms while idle to save CPU during long pauses/overnight.
+            // Adaptive sleep: while playing, until the next tick or progress
+            // event is due, whichever comes first (each keeps its own
+            // schedule); condvar wait while idle to save CPU during long
+            // pauses/overnight.
             //
             // Device-loss guard: when we detect a transition from playing to
             // finished, we check if the audio device is still available before
@@ -189,11 +391,15 @@ fn main() {
             // prompt rather than advancing to the next track.
             let broadcast_handle = app.handle().clone();
             let broadcast_wake = player_for_broadcast.broadcast_wake();
+            let broadcast_progress = progress_ticker.clone();
+            let broadcast_db = db.clone();
             std::thread::spawn(move || {
                 let mut was_playing = false;
+                let mut last_position_save = Instant::now();
+                let mut next_tick = Instant::now();
                 // ── Device-loss auto-recovery state ──────────────────
                 let mut device_lost = false;
-                let mut device_check_counter: u32 = 0;
+                let mut last_device_check = Instant::now();
 
                 loop {
                     // ── Device-lost recovery mode ────────────────────
@@ -222,12 +428,11 @@ fn main() {
                     let snap = player_for_broadcast.broadcast_snapshot();
 
                     // ── Proactive device-loss detection while playing ─
-                    // Every ~1 s (10 ticks × 100 ms) check whether the
-                    // audio device disappeared or changed underneath us.
+                    // Every ~1 s check whether the audio device
+                    // disappeared or changed underneath us.
                     if snap.is_playing {
-                        device_check_counter += 1;
-                        if device_check_counter >= 10 {
-                            device_check_counter = 0;
+                        if last_device_check.elapsed() >= Duration::from_secs(1) {
+                            last_device_check = Instant::now();
                             if !player_for_broadcast.is_device_available()
                                 || player_for_broadcast.has_device_changed()
                             {
@@ -244,31 +449,101 @@ fn main() {
                         }
 
                         // Emit tick
-                        let tick = PlaybackTick {
+                        let now = Instant::now();
+                        if now >= next_tick {
+                            next_tick = now + TICK_INTERVAL;
+                            let tick = PlaybackTick {
+                                position: snap.position,
+                                duration: snap.duration,
+                                is_playing: true,
+                                is_finished: false,
+                                is_paused: false,
+                            };
+                            let _ = broadcast_handle.emit("playback-tick", tick);
+                        }
+
+                        // Per-track resume: remember where the track is.
+                        if player_for_broadcast.resume_per_track()
+                            && last_position_save.elapsed() >= POSITION_SAVE_INTERVAL
+                        {
+                            last_position_save = Instant::now();
+                            if let Some(path) = player_for_broadcast.current_path() {
+                                audio::resume::save_position(&broadcast_db, &path, Some(snap.position));
+                            }
+                        }
+                    } else {
+                        last_device_check = Instant::now();
+                        // Started without an output device: open it as soon
+                        // as one shows up so the next play() is instant.
+                        if !player_for_broadcast.has_output()
+                            && player_for_broadcast.is_device_available()
+                            && player_for_broadcast.ensure_output().is_ok()
+                        {
+                            info!("Audio output device connected");
+                        }
+                    }
+
+                    if broadcast_progress.should_emit(snap.is_playing, Instant::now()) {
+                        let progress = PlaybackProgress {
                             position: snap.position,
                             duration: snap.duration,
                             is_playing: true,
-                            is_finished: false,
-                            is_paused: false,
                         };
-                        let _ = broadcast_handle.emit("playback-tick", tick);
-                    } else {
-                        device_check_counter = 0;
+                        let _ = broadcast_handle.emit("playback-progress", progress);
                     }
 
                     // Detect track-end transition: was playing → now finished
                     if was_playing && !snap.is_playing && snap.is_finished && !snap.is_paused {
-                        // Guard: if the device disappeared, the sink empties but
-                        // the track didn't truly finish — it was interrupted.
-                        if player_for_broadcast.is_device_available() {
-                            let _ = broadcast_handle.emit("track-ended", ());
-                        } else {
-                            info!("Device lost during playback — suppressing track-ended");
-                            let _ = broadcast_handle.emit("device-lost", ());
-                            device_lost = true;
-                            was_playing = false;
-                            std::thread::sleep(Duration::from_millis(1000));
-                            continue;
+                        let current_path = player_for_broadcast.current_path();
+                        let end = track_end::classify(
+                            current_path.as_deref().map(std::path::Path::new),
+                            snap.read_failed,
+                        );
+                        match end {
+                            // The file went away (network share, USB disk):
+                            // the sink emptied early, the track didn't finish.
+                            TrackEnd::Unavailable => {
+                                warn!("Track became unavailable at {:.2}s: {:?}", snap.position, current_path);
+                                let _ = broadcast_handle.emit("track-unavailable", TrackUnavailable {
+                                    path: current_path,
+                                    position: snap.position,
+                                    auto_skip: track_end::skip_unavailable(&broadcast_db),
+                                });
+                            }
+                            TrackEnd::ReadError => {
+                                warn!("Track stopped on a read error at {:.2}s", snap.position);
+                                let _ = broadcast_handle.emit("track-error", TrackError {
+                                    path: current_path,
+                                    position: snap.position,
+                                    message: "The file could not be read past this point".to_string(),
+                                });
+                            }
+                            // Guard: if the device disappeared, the sink empties but
+                            // the track didn't truly finish — it was interrupted.
+                            TrackEnd::Finished if player_for_broadcast.is_device_available() => {
+                                // A finished track starts over next time.
+                                if player_for_broadcast.resume_per_track() {
+                                    if let Some(path) = current_path {
+                                        audio::resume::save_position(&broadcast_db, &path, None);
+                                    }
+                                }
+                                let _ = broadcast_handle.emit("track-ended", ());
+                            }
+                            TrackEnd::Finished => {
+                                info!("Device lost during playback — suppressing track-ended");
+                                let _ = broadcast_handle.emit("device-lost", ());
+                                device_lost = true;
+                                was_playing = false;
+                                std::thread::sleep(Duration::from_millis(1000));
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Pausing saves the exact spot rather than the last periodic save.
+                    if was_playing && snap.is_paused && player_for_broadcast.resume_per_track() {
+                        if let Some(path) = player_for_broadcast.current_path() {
+                            audio::resume::save_position(&broadcast_db, &path, Some(snap.position));
                         }
                     }
 
@@ -276,7 +551,8 @@ fn main() {
 
                     // Adaptive sleep: fast ticks while playing, condvar-wait while idle
                     if snap.is_playing {
-                        std::thread::sleep(Duration::from_millis(100));
+                        let wake = broadcast_progress.next_due().map_or(next_tick, |due| due.min(next_tick));
+                        std::thread::sleep(wake.saturating_duration_since(Instant::now()));
                     } else {
                         // Block until play/load wakes us or a 30 s timeout fires
                         // (timeout is a safety net for edge cases like external
@@ -286,66 +562,8 @@ fn main() {
                 }
             });
             
-            // Register global shortcuts
-            use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
-            
-            let app_handle = app.handle().clone();
-            
-            // Play/Pause - Media Play/Pause key
-            if let Ok(shortcut) = "MediaPlayPause".parse::<Shortcut>() {
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    // Emit event to frontend
-                    let _ = app_handle.emit("global-shortcut", "play-pause");
-                });
-            }
-            
-            // Next Track - Media Next Track key
-            if let Ok(shortcut) = "MediaTrackNext".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "next-track");
-                });
-            }
-            
-            // Previous Track - Media Previous Track key
-            if let Ok(shortcut) = "MediaTrackPrevious".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "prev-track");
-                });
-            }
-            
-            // Stop - Media Stop key
-            if let Ok(shortcut) = "MediaStop".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "stop");
-                });
-            }
-            
-            // Volume Up - Volume Up key
-            if let Ok(shortcut) = "VolumeUp".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "volume-up");
-                });
-            }
-            
-            // Volume Down - Volume Down key
-            if let Ok(shortcut) = "VolumeDown".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "volume-down");
-                });
-            }
-            
-            // Mute - Volume Mute key
-            if let Ok(shortcut) = "VolumeMute".parse::<Shortcut>() {
-                let app_handle = app.handle().clone();
-                let _ = app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, _event| {
-                    let _ = app_handle.emit("global-shortcut", "mute");
-                });
-            }
+            // Register global shortcuts (media keys unless the user remapped them)
+            shortcuts::bind_all(app.handle(), &shortcut_bindings);
             
             // Setup system tray
             let app_handle = app.handle().clone();
@@ -394,6 +612,18 @@ fn main() {
                 })
                 .build(app)
                 .map_err(|e| format!("Failed to build tray icon: {}", e))?;
+
+            // The tray icon exists now, so the window can be hidden into it.
+            if let Some(window) = app.get_webview_window("main") {
+                startup::apply_start_minimized(&db, &window);
+            }
+
+            // Files passed on the command line ("Open with", double-click)
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let opened = open_files::audio_files_from_args(std::env::args().skip(1), &cwd);
+            if let Some(request) = open_files::OpenRequest::from_paths(opened) {
+                commands::dispatch_open_request(app.handle(), request);
+            }
             
             Ok(())
         })
@@ -403,7 +633,16 @@ fn main() {
             pause_audio,
             stop_audio,
             set_volume,
+            toggle_mute,
+            set_muted,
+            is_muted,
             seek_to,
+            seek_relative,
+            get_chapters,
+            next_chapter,
+            previous_chapter,
+            set_progress_interval,
+            can_seek,
             get_position,
             get_duration,
             is_playing,
@@ -411,15 +650,25 @@ fn main() {
             recover_audio,
             get_audio_health,
             get_audio_devices,
+            test_audio_device,
             set_audio_device,
             scan_folder,
             scan_folder_incremental,
+            rescan_folder,
+            set_folder_scan_options,
+            get_folder_scan_options,
+            add_ignored_path,
+            remove_ignored_path,
+            list_ignored_paths,
             cancel_scan,
+            set_scan_progress_throttle,
+            get_scan_progress_throttle,
             get_track_ids_for_folder,
             get_all_tracks,
             get_filtered_tracks,
             get_tracks_page,
             get_all_folders,
+            get_folders_with_counts,
             remove_folder,
             create_playlist,
             get_all_playlists,
@@ -431,8 +680,17 @@ fn main() {
             reorder_playlist_tracks,
             get_playlist_tracks,
             increment_play_count,
+            increment_skip_count,
             get_recently_played,
             get_most_played,
+            get_unplayed_tracks,
+            get_unplayed_track_count,
+            get_suspect_tracks,
+            get_genres,
+            get_years,
+            build_smart_queue,
+            get_similar_tracks,
+            get_listening_stats,
             start_folder_watch,
             stop_folder_watch,
             get_watched_folders,
@@ -440,16 +698,38 @@ fn main() {
             set_track_rating,
             check_missing_files,
             update_track_path,
+            move_track_file,
+            refresh_track_metadata,
+            update_tags_bulk,
+            read_all_tags,
+            write_tag,
+            remove_tag,
             find_duplicates,
+            fingerprint_track,
+            analyze_bpm,
+            analyze_key,
+            analyze_all,
+            cancel_analysis,
+            lookup_track_metadata,
+            apply_metadata_candidate,
+            set_acoustid_api_key,
             remove_track,
             remove_duplicate_folders,
             get_album_art,
+            get_album_art_thumbnail,
             get_album_art_batch,
+            get_album_art_cache_settings,
+            set_album_art_cache_settings,
             extract_and_cache_album_art,
+            extract_art_colors,
+            prepare_next,
+            set_album_art,
             update_track_tags,
             show_in_folder,
             reset_play_count,
             write_text_file,
+            export_library,
+            import_library,
             preload_track,
             swap_to_preloaded,
             clear_preload,
@@ -457,8 +737,10 @@ fn main() {
             get_preloaded_path,
             set_balance,
             get_balance,
+            validate_playlist,
             export_playlist,
             import_playlist,
+            import_playlists_from_folder,
             create_smart_playlist,
             get_all_smart_playlists,
             get_smart_playlist,
@@ -467,34 +749,101 @@ fn main() {
             execute_smart_playlist,
             get_performance_stats,
             get_runtime_diagnostics,
+            get_diagnostics,
             vacuum_database,
+            get_auto_vacuum_settings,
+            set_auto_vacuum_settings,
+            cleanup_database,
             load_lyrics,
-            // get_lyric_at_time is implemented but has no frontend caller yet;
-            // it is not registered to keep the IPC surface minimal.
+            get_lyric_at_time,
+            get_lyrics_at_time,
             analyze_replaygain,
             get_track_replaygain,
+            write_replaygain_tags,
             get_album_replaygain,
             analyze_album_replaygain,
+            get_replaygain_target,
+            set_replaygain_target,
+            get_device_replaygain_targets,
+            set_device_replaygain_target,
+            set_replaygain_mode,
+            get_replaygain_mode,
+            set_replaygain_preamp,
+            get_replaygain_preamp,
             set_replaygain,
             clear_replaygain,
+            set_track_volume_override,
+            get_track_volume_override,
+            set_normalize_mode,
+            get_normalize_mode,
+            set_silence_trim,
+            get_silence_trim,
+            set_crossfade_policy,
+            get_crossfade_policy,
+            should_crossfade,
+            get_fade_durations,
+            set_sleep_fade_secs,
+            set_crossfade_secs,
+            set_audio_config,
+            get_audio_config,
+            get_output_format,
+            set_resume_per_track,
+            get_resume_per_track,
+            set_skip_unavailable,
+            get_skip_unavailable,
+            set_track_position,
+            get_track_position,
+            set_playback_speed,
+            get_playback_speeds,
+            get_queue_remaining_duration,
+            register_shortcut,
+            unregister_shortcut,
+            get_shortcuts,
+            take_opened_files,
             set_audio_effects,
             get_audio_effects,
+            reset_effects,
+            get_genre_eq_settings,
+            set_genre_eq_settings,
             set_effects_enabled,
             is_effects_enabled,
+            set_loudness_enabled,
+            is_loudness_enabled,
+            get_clip_stats,
+            measure_effects_cost,
             get_visualizer_data,
             set_visualizer_mode,
             set_beat_sensitivity,
+            set_min_beat_interval,
+            set_beat_band,
+            set_visualizer_agc_decay,
+            get_visualizer_bin_frequencies,
+            set_pcm_tap_enabled,
+            is_pcm_tap_enabled,
+            get_pcm_tap,
             get_track_waveform,
             clear_album_art_cache,
             get_cache_size,
             get_database_size,
             set_tray_settings,
             get_tray_settings,
+            set_start_minimized,
+            get_start_minimized,
             enforce_cache_limit,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app_handle, event| {
+            // macOS delivers "Open with" files to the running app as URLs
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                let cwd = std::env::current_dir().unwrap_or_default();
+                let opened = open_files::audio_files_from_args(urls.iter().map(|u| u.as_str()), &cwd);
+                if let Some(request) = open_files::OpenRequest::from_paths(opened) {
+                    commands::dispatch_open_request(app_handle, request);
+                }
+            }
+
             if let tauri::RunEvent::WindowEvent { label: _, event, .. } = event {
                 if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                     // Check whether the user wants to hide to tray on close
@@ -0,0 +1,66 @@
+//! Audio from video container files (music videos in .mp4/.m4v/.mkv/.webm).
+//!
+//! Symphonia demuxes these containers and reports video tracks with a null
+//! codec, so the decoder, which takes the first track with a known codec,
+//! plays just the audio. Files with no audio track at all are caught here
+//! first so scanning and loading can say so instead of failing to decode.
+
+use std::fs::File;
+use std::path::Path;
+
+use symphonia::core::codecs::CODEC_TYPE_NULL;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Video container extensions whose audio track is playable.
+pub const VIDEO_CONTAINER_EXTENSIONS: [&str; 4] = ["mp4", "m4v", "mkv", "webm"];
+
+/// The audio track selected from a container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerAudioTrack {
+    pub track_id: u32,
+    pub sample_rate: Option<u32>,
+    /// Seconds, when the container records the track length.
+    pub duration: Option<f64>,
+}
+
+pub fn is_video_container(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| VIDEO_CONTAINER_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Find the audio track the decoder will play: the container's first track
+/// with a supported codec. Errors if the file has none.
+pub fn probe_audio_track(path: &Path) -> Result<ContainerAudioTrack, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension() {
+        hint.with_extension(&ext.to_string_lossy());
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unrecognised container {}: {}", path.display(), e))?;
+
+    let track = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| format!("{} has no playable audio track", path.display()))?;
+
+    let params = &track.codec_params;
+    let duration = params.time_base.zip(params.n_frames).map(|(base, frames)| {
+        let time = base.calc_time(frames);
+        time.seconds as f64 + time.frac
+    });
+    Ok(ContainerAudioTrack {
+        track_id: track.id,
+        sample_rate: params.sample_rate,
+        duration,
+    })
+}
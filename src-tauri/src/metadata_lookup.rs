@@ -0,0 +1,275 @@
+//! Online metadata lookup for tracks with missing or partial tags.
+//!
+//! Two sources are queried:
+//! - **AcoustID**, given a Chromaprint fingerprint and an API key. AcoustID
+//!   only understands Chromaprint, so fingerprints from [`crate::fingerprint`]
+//!   (a different algorithm, used for local duplicate detection) cannot be
+//!   sent there; callers must supply one from `fpcalc` or similar.
+//! - **MusicBrainz** recording search, built from whatever tags the track
+//!   has, falling back to its file name for the title.
+//!
+//! Lookups return candidates for the user to pick from; nothing is written
+//! until [`apply_candidate`]. Network and parse failures are logged and
+//! produce no candidates rather than an error. All HTTP goes through the
+//! [`HttpClient`] trait so tests can substitute canned responses.
+
+use crate::database::Database;
+use crate::scanner::Track;
+use crate::tag_service::{apply_tags_bulk, TagUpdateInput};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+
+/// Settings key under which the user's AcoustID API key is stored.
+pub const ACOUSTID_API_KEY_KEY: &str = "acoustid_api_key";
+
+const ACOUSTID_URL: &str = "https://api.acoustid.org/v2/lookup";
+const MUSICBRAINZ_URL: &str = "https://musicbrainz.org/ws/2/recording";
+/// MusicBrainz rejects requests without an identifying user agent.
+const USER_AGENT: &str = concat!("VPlayer/", env!("CARGO_PKG_VERSION"), " ( https://github.com/Veno89/Vplayer )");
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Most candidates returned per lookup.
+pub const MAX_CANDIDATES: usize = 5;
+
+/// Minimal blocking HTTP GET, so lookups can run against canned responses.
+pub trait HttpClient: Send + Sync {
+    /// GET `url` with `query` appended and return the response body.
+    fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<String, String>;
+}
+
+/// The real client, backed by `reqwest`.
+pub struct ReqwestClient {
+    client: reqwest::blocking::Client,
+}
+
+impl ReqwestClient {
+    pub fn new() -> Result<Self, String> {
+        // reqwest is built without a bundled TLS provider; use ring, which the
+        // updater already links. Fails harmlessly if one is installed.
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { client })
+    }
+}
+
+impl HttpClient for ReqwestClient {
+    fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<String, String> {
+        let url = reqwest::Url::parse_with_params(url, query)
+            .map_err(|e| format!("Invalid lookup URL {}: {}", url, e))?;
+        self.client
+            .get(url.clone())
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.text())
+            .map_err(|e| format!("Request to {} failed: {}", url, e))
+    }
+}
+
+/// One possible identification of a track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataCandidate {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<i32>,
+    /// Match confidence, 0–1.
+    pub score: f64,
+    /// MusicBrainz recording ID.
+    pub recording_id: Option<String>,
+    /// `"acoustid"` or `"musicbrainz"`.
+    pub source: String,
+}
+
+/// Leading four-digit year of a MusicBrainz date ("1997-05-21" → 1997).
+fn parse_year(date: &str) -> Option<i32> {
+    date.get(..4).and_then(|y| y.parse().ok())
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Parse a MusicBrainz `/ws/2/recording?fmt=json` search response.
+pub fn parse_musicbrainz_response(body: &str) -> Result<Vec<MetadataCandidate>, String> {
+    let json: Value = serde_json::from_str(body).map_err(|e| format!("Invalid MusicBrainz response: {}", e))?;
+    let recordings = json.get("recordings").and_then(Value::as_array).into_iter().flatten();
+
+    Ok(recordings
+        .take(MAX_CANDIDATES)
+        .map(|rec| {
+            let artist = rec.get("artist-credit").and_then(Value::as_array).map(|credits| {
+                credits
+                    .iter()
+                    .map(|c| {
+                        let name = c.get("name").and_then(Value::as_str).unwrap_or_default();
+                        let join = c.get("joinphrase").and_then(Value::as_str).unwrap_or_default();
+                        format!("{}{}", name, join)
+                    })
+                    .collect::<String>()
+            });
+            let release = rec.get("releases").and_then(Value::as_array).and_then(|r| r.first());
+            MetadataCandidate {
+                title: str_field(rec, "title"),
+                artist: artist.filter(|a| !a.is_empty()),
+                album: release.and_then(|r| str_field(r, "title")),
+                year: release
+                    .and_then(|r| r.get("date").and_then(Value::as_str))
+                    .or_else(|| rec.get("first-release-date").and_then(Value::as_str))
+                    .and_then(parse_year),
+                score: rec.get("score").and_then(Value::as_f64).unwrap_or(0.0) / 100.0,
+                recording_id: str_field(rec, "id"),
+                source: "musicbrainz".to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Parse an AcoustID `/v2/lookup` response requested with
+/// `meta=recordings releases`.
+pub fn parse_acoustid_response(body: &str) -> Result<Vec<MetadataCandidate>, String> {
+    let json: Value = serde_json::from_str(body).map_err(|e| format!("Invalid AcoustID response: {}", e))?;
+    if json.get("status").and_then(Value::as_str) != Some("ok") {
+        let message = json
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(format!("AcoustID lookup failed: {}", message));
+    }
+
+    let mut candidates = Vec::new();
+    for result in json.get("results").and_then(Value::as_array).into_iter().flatten() {
+        let score = result.get("score").and_then(Value::as_f64).unwrap_or(0.0);
+        for rec in result.get("recordings").and_then(Value::as_array).into_iter().flatten() {
+            let artist = rec.get("artists").and_then(Value::as_array).map(|artists| {
+                artists
+                    .iter()
+                    .filter_map(|a| a.get("name").and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+            let release = rec.get("releases").and_then(Value::as_array).and_then(|r| r.first());
+            candidates.push(MetadataCandidate {
+                title: str_field(rec, "title"),
+                artist: artist.filter(|a| !a.is_empty()),
+                album: release.and_then(|r| str_field(r, "title")),
+                year: release
+                    .and_then(|r| r.pointer("/date/year"))
+                    .and_then(Value::as_i64)
+                    .map(|y| y as i32),
+                score,
+                recording_id: str_field(rec, "id"),
+                source: "acoustid".to_string(),
+            });
+        }
+    }
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates.truncate(MAX_CANDIDATES);
+    Ok(candidates)
+}
+
+/// Look a Chromaprint fingerprint up on AcoustID.
+pub fn lookup_acoustid(
+    client: &dyn HttpClient,
+    api_key: &str,
+    fingerprint: &str,
+    duration_secs: f64,
+) -> Result<Vec<MetadataCandidate>, String> {
+    let duration = (duration_secs.round() as i64).to_string();
+    let body = client.get(
+        ACOUSTID_URL,
+        &[
+            ("client", api_key),
+            ("format", "json"),
+            ("meta", "recordings releases"),
+            ("duration", &duration),
+            ("fingerprint", fingerprint),
+        ],
+    )?;
+    parse_acoustid_response(&body)
+}
+
+/// Quote a value for a Lucene query, escaping embedded quotes/backslashes.
+fn lucene_phrase(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// MusicBrainz search query from the track's tags; the file name stands in
+/// for a missing title. None when there is nothing to search for.
+pub fn musicbrainz_query(track: &Track) -> Option<String> {
+    let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(str::to_string);
+    let title = non_empty(&track.title).or_else(|| {
+        Path::new(&track.path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().replace('_', " "))
+            .filter(|s| !s.trim().is_empty())
+    })?;
+
+    let mut parts = vec![format!("recording:{}", lucene_phrase(&title))];
+    if let Some(artist) = non_empty(&track.artist) {
+        parts.push(format!("artist:{}", lucene_phrase(&artist)));
+    }
+    if let Some(album) = non_empty(&track.album) {
+        parts.push(format!("release:{}", lucene_phrase(&album)));
+    }
+    Some(parts.join(" AND "))
+}
+
+/// Search MusicBrainz using the track's partial tags.
+pub fn lookup_musicbrainz(client: &dyn HttpClient, track: &Track) -> Result<Vec<MetadataCandidate>, String> {
+    let Some(query) = musicbrainz_query(track) else {
+        return Ok(Vec::new());
+    };
+    let limit = MAX_CANDIDATES.to_string();
+    let body = client.get(MUSICBRAINZ_URL, &[("query", &query), ("fmt", "json"), ("limit", &limit)])?;
+    parse_musicbrainz_response(&body)
+}
+
+/// Candidates for `track`: AcoustID first when a Chromaprint fingerprint and
+/// API key are available, otherwise (or if that finds nothing) MusicBrainz.
+/// Failures are logged and yield no candidates.
+pub fn lookup_candidates(
+    client: &dyn HttpClient,
+    track: &Track,
+    chromaprint: Option<&str>,
+    acoustid_key: Option<&str>,
+) -> Vec<MetadataCandidate> {
+    if let (Some(fingerprint), Some(key)) = (chromaprint, acoustid_key) {
+        match lookup_acoustid(client, key, fingerprint, track.duration) {
+            Ok(found) if !found.is_empty() => return found,
+            Ok(_) => info!("AcoustID had no match for {}", track.path),
+            Err(e) => warn!("AcoustID lookup failed for {}: {}", track.path, e),
+        }
+    }
+
+    lookup_musicbrainz(client, track).unwrap_or_else(|e| {
+        warn!("MusicBrainz lookup failed for {}: {}", track.path, e);
+        Vec::new()
+    })
+}
+
+/// Write the accepted candidate's fields to the track's file and library row.
+/// Fields the candidate lacks keep their current values.
+pub fn apply_candidate(db: &Database, track_id: &str, candidate: &MetadataCandidate) -> Result<(), String> {
+    let update = TagUpdateInput {
+        title: candidate.title.clone(),
+        artist: candidate.artist.clone(),
+        album: candidate.album.clone(),
+        year: candidate.year.map(|y| y.to_string()),
+        genre: None,
+        comment: None,
+        track_number: None,
+        disc_number: None,
+    };
+    let result = apply_tags_bulk(db, &[track_id.to_string()], &update);
+    match result.failed.into_iter().next() {
+        Some(failure) => Err(failure.error),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,55 @@
+//! Warming the caches for the track that plays next.
+//!
+//! Skipping to a track whose waveform and cover have to be produced on the
+//! spot makes the seekbar and art pop in late. The frontend names the
+//! upcoming track ahead of time and this fills the waveform cache and the
+//! album art and thumbnail cache for it, leaving playback alone. Anything
+//! already cached is reused, so preparing the same track twice is cheap.
+
+use log::{debug, warn};
+use serde::Serialize;
+
+use crate::art_extraction::ArtExtractor;
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+use crate::scanner::Scanner;
+use crate::waveform::{track_waveform, DEFAULT_WAVEFORM_BARS};
+
+/// Which caches hold an entry for the track after preparing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedNext {
+    pub waveform: bool,
+    /// Cover art and its thumbnail; false for files outside the library or
+    /// without embedded art.
+    pub art: bool,
+}
+
+/// Generate and cache the waveform and album art for the track at `path`.
+/// A waveform that can't be decoded is logged and reported as missing
+/// rather than failing the art step.
+pub fn prepare_next(db: &Database, extractor: &ArtExtractor, path: &str) -> AppResult<PreparedNext> {
+    let waveform = match track_waveform(path, DEFAULT_WAVEFORM_BARS) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Failed to prepare waveform for {}: {}", path, e);
+            false
+        }
+    };
+
+    let track = db.get_track_by_path(path).map_err(|e| AppError::Database(e.to_string()))?;
+    let art = match track {
+        Some(track) => {
+            let art = extractor.extract_and_cache(db, &track.id, path, Scanner::extract_album_art)?;
+            if art.is_some() {
+                db.get_album_art_thumbnail(&track.id)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            art.is_some()
+        }
+        None => false,
+    };
+
+    debug!("Prepared next track {} (waveform: {}, art: {})", path, waveform, art);
+    Ok(PreparedNext { waveform, art })
+}
@@ -0,0 +1,76 @@
+//! Audio files handed to VPlayer by the OS: command-line arguments from
+//! "Open with" / double-click on Windows and Linux, and `RunEvent::Opened`
+//! URLs on macOS.
+//!
+//! Parsing is kept free of app state so it can be tested; `main.rs` turns an
+//! `OpenRequest` into load + play of the first file and an `open-file` event.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::scanner::AUDIO_EXTENSIONS;
+
+/// What to do with a batch of opened files: play the first, queue the rest.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+    pub play: String,
+    pub enqueue: Vec<String>,
+}
+
+impl OpenRequest {
+    /// `None` when there is nothing playable in `paths`.
+    pub fn from_paths(paths: Vec<PathBuf>) -> Option<Self> {
+        let mut paths = paths.into_iter().map(|p| p.to_string_lossy().to_string());
+        let play = paths.next()?;
+        Some(Self {
+            play,
+            enqueue: paths.collect(),
+        })
+    }
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Resolve one argument to an existing audio file. Accepts plain paths
+/// (relative ones are taken from `cwd`) and `file://` URLs.
+fn resolve_arg(arg: &str, cwd: &Path) -> Option<PathBuf> {
+    let path = if arg.starts_with("file://") {
+        tauri::Url::parse(arg).ok()?.to_file_path().ok()?
+    } else {
+        let path = PathBuf::from(arg);
+        if path.is_absolute() {
+            path
+        } else {
+            cwd.join(path)
+        }
+    };
+    (path.is_file() && is_audio_file(&path)).then_some(path)
+}
+
+/// Audio files among process arguments (without argv[0]). Flags, missing
+/// files and non-audio files are skipped; duplicates are dropped, order kept.
+pub fn audio_files_from_args<I, S>(args: I, cwd: &Path) -> Vec<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut files: Vec<PathBuf> = Vec::new();
+    for arg in args {
+        let arg = arg.as_ref();
+        if arg.is_empty() || arg.starts_with('-') {
+            continue;
+        }
+        if let Some(path) = resolve_arg(arg, cwd) {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
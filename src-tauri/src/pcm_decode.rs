@@ -0,0 +1,96 @@
+//! Packet-by-packet decoding to f32 PCM for offline analysis.
+//!
+//! Analyzers that only need samples (tempo, key, …) take them from here
+//! rather than each carrying their own symphonia loop.
+
+use log::warn;
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::audio::{AudioBuffer, Signal};
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// One decoded packet.
+pub struct PcmBlock<'a> {
+    pub sample_rate: u32,
+    pub channels: usize,
+    /// Frame-interleaved samples, `channels` per frame.
+    pub interleaved: &'a [f32],
+    /// The channels averaged to mono, one sample per frame.
+    pub mono: &'a [f32],
+}
+
+/// Decode the audio track of `path`, handing each packet to `on_block`
+/// until the file ends or `on_block` returns false. Undecodable packets are
+/// logged and skipped.
+pub fn decode_file(path: &str, mut on_block: impl FnMut(&PcmBlock) -> bool) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = Path::new(path).extension() {
+        hint.with_extension(&ext.to_string_lossy());
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Failed to probe format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| "No sample rate info".to_string())?;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+    let mut interleaved = Vec::new();
+    let mut mono = Vec::new();
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                warn!("Decode error (continuing): {}", e);
+                continue;
+            }
+        };
+        let spec = *decoded.spec();
+        let mut audio_buf = AudioBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        decoded.convert(&mut audio_buf);
+
+        let channels = spec.channels.count().max(1);
+        let frames = audio_buf.frames();
+        interleaved.clear();
+        mono.clear();
+        for i in 0..frames {
+            let mut sum = 0.0;
+            for ch in 0..channels {
+                let sample = audio_buf.chan(ch)[i];
+                interleaved.push(sample);
+                sum += sample;
+            }
+            mono.push(sum / channels as f32);
+        }
+
+        let block = PcmBlock { sample_rate, channels, interleaved: &interleaved, mono: &mono };
+        if !on_block(&block) {
+            break;
+        }
+    }
+    Ok(())
+}
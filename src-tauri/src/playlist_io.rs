@@ -1,13 +1,36 @@
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
-use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context, Result};
 use log::{info, warn};
+use serde::Serialize;
+use crate::database::Database;
+use crate::scanner::Scanner;
+
+/// Playlist file extensions [`PlaylistIO::import_file`] understands.
+pub const PLAYLIST_EXTENSIONS: [&str; 4] = ["m3u", "m3u8", "pls", "xspf"];
+
+/// Byte order mark some editors put at the start of UTF-8 files.
+const UTF8_BOM: char = '\u{feff}';
+
+/// Outcome for one file of a folder import.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistImportResult {
+    pub file: String,
+    pub playlist_name: String,
+    /// `None` when the file couldn't be imported; see `error`.
+    pub playlist_id: Option<String>,
+    pub track_count: usize,
+    pub error: Option<String>,
+}
 
 pub struct PlaylistIO;
 
 impl PlaylistIO {
-    /// Export tracks to M3U playlist file
+    /// Export tracks to M3U playlist file, as UTF-8 without a BOM (valid
+    /// for both `.m3u` and `.m3u8`)
     pub fn export_m3u(tracks: &[(String, String)], output_path: &str) -> Result<()> {
         info!("Exporting playlist to: {}", output_path);
         
@@ -28,20 +51,46 @@ impl PlaylistIO {
         Ok(())
     }
     
+    /// Import tracks from an M3U, PLS or XSPF file, picked by extension.
+    /// Returns vec of (title, path) tuples
+    pub fn import_file(input_path: &str) -> Result<Vec<(String, String)>> {
+        let extension = Path::new(input_path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "m3u" | "m3u8" => Self::import_m3u(input_path),
+            "pls" => Self::import_pls(input_path),
+            "xspf" => Self::import_xspf(input_path),
+            _ => bail!("Unsupported playlist format: {}", input_path),
+        }
+    }
+
     /// Import tracks from M3U playlist file
     /// Returns vec of (title, path) tuples
+    ///
+    /// `.m3u8` files must be UTF-8. Plain `.m3u` has no defined encoding, so
+    /// bytes that aren't UTF-8 are replaced rather than failing the import;
+    /// entries they garble are skipped as missing files.
     pub fn import_m3u(input_path: &str) -> Result<Vec<(String, String)>> {
         info!("Importing playlist from: {}", input_path);
         
-        let file = File::open(input_path)
+        let bytes = std::fs::read(input_path)
             .context("Failed to open playlist file")?;
+        let is_m3u8 = Path::new(input_path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("m3u8"));
+        let content = if is_m3u8 {
+            String::from_utf8(bytes).context("M3U8 playlist is not valid UTF-8")?
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+        let base_dir = playlist_dir(input_path);
         
-        let reader = BufReader::new(file);
         let mut tracks = Vec::new();
         let mut current_title: Option<String> = None;
         
-        for line in reader.lines() {
-            let line = line?;
+        for line in strip_bom(&content).lines() {
             let line = line.trim();
             
             // Skip empty lines and comments (except #EXTINF)
@@ -57,29 +106,239 @@ impl PlaylistIO {
                 }
             } else {
                 // This is a file path
-                let path = line.to_string();
-                
-                // Check if file exists
-                if Path::new(&path).exists() {
-                    let title = current_title.take().unwrap_or_else(|| {
-                        // Extract filename as fallback title
-                        Path::new(&path)
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .unwrap_or("Unknown")
-                            .to_string()
-                    });
-                    
-                    tracks.push((title, path));
-                } else {
-                    warn!("Skipping non-existent file: {}", path);
+                if let Some(entry) = resolve_entry(line, &base_dir, current_title.take()) {
+                    tracks.push(entry);
                 }
-                
-                current_title = None;
             }
         }
         
         info!("Successfully imported {} tracks", tracks.len());
         Ok(tracks)
     }
+
+    /// Import tracks from a PLS playlist (`FileN=` / `TitleN=` entries).
+    pub fn import_pls(input_path: &str) -> Result<Vec<(String, String)>> {
+        info!("Importing PLS playlist from: {}", input_path);
+
+        let content = std::fs::read_to_string(input_path)
+            .context("Failed to read playlist file")?;
+        let content = strip_bom(&content);
+        let base_dir = playlist_dir(input_path);
+
+        // Entry number -> (file, title); entries may come in any order.
+        let mut entries: BTreeMap<u32, (Option<String>, Option<String>)> = BTreeMap::new();
+        for line in content.lines() {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let (slot, number) = if let Some(n) = key.strip_prefix("file") {
+                (0, n)
+            } else if let Some(n) = key.strip_prefix("title") {
+                (1, n)
+            } else {
+                continue;
+            };
+            let Ok(number) = number.parse::<u32>() else {
+                continue;
+            };
+            let entry = entries.entry(number).or_default();
+            let value = Some(value.trim().to_string());
+            if slot == 0 {
+                entry.0 = value;
+            } else {
+                entry.1 = value;
+            }
+        }
+
+        let tracks: Vec<(String, String)> = entries
+            .into_values()
+            .filter_map(|(file, title)| resolve_entry(&file?, &base_dir, title))
+            .collect();
+        info!("Successfully imported {} tracks", tracks.len());
+        Ok(tracks)
+    }
+
+    /// Import tracks from an XSPF playlist (`<location>` and `<title>` of
+    /// each `<track>`).
+    pub fn import_xspf(input_path: &str) -> Result<Vec<(String, String)>> {
+        info!("Importing XSPF playlist from: {}", input_path);
+
+        let content = std::fs::read_to_string(input_path)
+            .context("Failed to read playlist file")?;
+        let base_dir = playlist_dir(input_path);
+
+        let mut tracks = Vec::new();
+        let mut rest = content.as_str();
+        while let Some(start) = rest.find("<track>") {
+            let body = &rest[start + "<track>".len()..];
+            let end = body.find("</track>").unwrap_or(body.len());
+            let track = &body[..end];
+            rest = &body[end..];
+
+            let Some(location) = xml_element_text(track, "location") else {
+                continue;
+            };
+            if let Some(entry) = resolve_entry(&location, &base_dir, xml_element_text(track, "title")) {
+                tracks.push(entry);
+            }
+        }
+
+        info!("Successfully imported {} tracks", tracks.len());
+        Ok(tracks)
+    }
+}
+
+fn strip_bom(content: &str) -> &str {
+    content.strip_prefix(UTF8_BOM).unwrap_or(content)
+}
+
+fn playlist_dir(input_path: &str) -> PathBuf {
+    Path::new(input_path).parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// Resolve a playlist entry to an existing file as (title, path). Entries
+/// may be `file://` URLs, absolute paths, or paths relative to the
+/// playlist's folder; the title falls back to the file name.
+fn resolve_entry(entry: &str, base_dir: &Path, title: Option<String>) -> Option<(String, String)> {
+    let path = if entry.starts_with("file://") {
+        tauri::Url::parse(entry).ok()?.to_file_path().ok()?
+    } else {
+        let path = PathBuf::from(entry);
+        if path.is_absolute() {
+            path
+        } else {
+            base_dir.join(path)
+        }
+    };
+    if !path.exists() {
+        warn!("Skipping non-existent file: {}", entry);
+        return None;
+    }
+    let title = title.unwrap_or_else(|| {
+        // Extract filename as fallback title
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Unknown")
+            .to_string()
+    });
+    Some((title, path.to_string_lossy().to_string()))
+}
+
+/// Text of the first `<tag>` element in `xml`, with entities decoded.
+fn xml_element_text(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let text = xml[start..end]
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(text)
+}
+
+/// Create playlist `name` from `(title, path)` entries, adding files that
+/// aren't in the library yet. Returns the playlist id and the ids of the
+/// tracks added to it; entries that can't be read are skipped.
+pub fn import_as_playlist(db: &Database, name: &str, entries: &[(String, String)]) -> Result<(String, Vec<String>)> {
+    let playlist_id = db.create_playlist(name)
+        .context("Failed to create playlist")?;
+
+    let mut imported_track_ids = Vec::new();
+
+    // Add tracks to database and playlist
+    for (_title, path) in entries {
+        // Check if track exists in library
+        let track_id = match db.get_track_by_path(path) {
+            Ok(Some(track)) => track.id,
+            Ok(None) => {
+                // Track not in library, scan it
+                match Scanner::extract_track_info(Path::new(path)) {
+                    Ok(track) => {
+                        db.add_track(&track).context("Failed to add track")?;
+                        track.id
+                    },
+                    Err(e) => {
+                        warn!("Failed to scan {}: {}", path, e);
+                        continue;
+                    }
+                }
+            },
+            Err(e) => {
+                warn!("Database error for {}: {}", path, e);
+                continue;
+            }
+        };
+
+        imported_track_ids.push(track_id);
+    }
+
+    // Batch-insert all resolved tracks in a single transaction.
+    // This is atomic (all-or-nothing) and avoids N separate SQLite commits.
+    if !imported_track_ids.is_empty() {
+        db.add_tracks_to_playlist_batch(&playlist_id, &imported_track_ids, 0)
+            .context("Failed to add tracks to playlist")?;
+    }
+
+    Ok((playlist_id, imported_track_ids))
+}
+
+/// Import every playlist file directly inside `dir`, in name order, each as
+/// a playlist named after the file. Other files are skipped; a playlist
+/// that fails to import is reported and the rest are still imported.
+pub fn import_playlists_from_folder(db: &Database, dir: &Path) -> Result<Vec<PlaylistImportResult>> {
+    info!("Importing playlists from folder: {:?}", dir);
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .context("Failed to read playlist folder")?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .is_some_and(|ext| PLAYLIST_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .collect();
+    files.sort();
+
+    let results: Vec<PlaylistImportResult> = files
+        .iter()
+        .map(|path| {
+            let file = path.to_string_lossy().to_string();
+            let playlist_name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| file.clone());
+            let imported = PlaylistIO::import_file(&file)
+                .and_then(|entries| import_as_playlist(db, &playlist_name, &entries));
+            match imported {
+                Ok((playlist_id, track_ids)) => PlaylistImportResult {
+                    file,
+                    playlist_name,
+                    playlist_id: Some(playlist_id),
+                    track_count: track_ids.len(),
+                    error: None,
+                },
+                Err(e) => {
+                    warn!("Failed to import playlist {}: {:#}", file, e);
+                    PlaylistImportResult {
+                        file,
+                        playlist_name,
+                        playlist_id: None,
+                        track_count: 0,
+                        error: Some(format!("{:#}", e)),
+                    }
+                }
+            }
+        })
+        .collect();
+
+    info!("Imported {} playlist files from {:?}", results.len(), dir);
+    Ok(results)
 }
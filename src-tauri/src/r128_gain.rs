@@ -0,0 +1,94 @@
+//! Opus R128 gain tags.
+//!
+//! Opus files carry loudness as `R128_TRACK_GAIN` rather than
+//! `REPLAYGAIN_*`: a signed Q7.8 fixed-point gain in dB (value / 256)
+//! relative to -23 LUFS (RFC 7845 §5.2.1). Stored gains keep their own
+//! target, so an imported R128 gain is recorded against -23 LUFS and rebased
+//! at playback like any other.
+
+use crate::database::Database;
+use crate::replaygain::{rebase_gain, ReplayGainData, DEFAULT_TARGET_LUFS};
+use crate::replaygain_store::{get_replaygain, store_replaygain};
+use crate::scanner::Track;
+use crate::tag_service;
+use log::{info, warn};
+use std::path::Path;
+
+pub const R128_TRACK_GAIN_KEY: &str = "R128_TRACK_GAIN";
+/// Loudness R128 gains are computed against.
+pub const R128_REFERENCE_LUFS: f64 = -23.0;
+
+/// Encode a gain in dB as a Q7.8 integer, saturating at the format's range
+/// (about ±128 dB).
+pub fn db_to_q78(gain_db: f64) -> i16 {
+    (gain_db * 256.0).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+}
+
+pub fn q78_to_db(value: i16) -> f64 {
+    value as f64 / 256.0
+}
+
+pub fn is_opus(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("opus"))
+}
+
+/// ReplayGain data for an `R128_TRACK_GAIN` tag value. The file doesn't
+/// record its peak, so a peak of full scale is assumed, which keeps boosts
+/// from being applied unchecked.
+pub fn gain_from_tag_value(value: &str) -> Option<ReplayGainData> {
+    let gain_db = q78_to_db(value.trim().parse::<i16>().ok()?);
+    Some(ReplayGainData {
+        track_gain: gain_db,
+        track_peak: 1.0,
+        loudness: R128_REFERENCE_LUFS - gain_db,
+        target_lufs: R128_REFERENCE_LUFS,
+    })
+}
+
+/// The track gain tagged in the Opus file at `path`, if any.
+pub fn read_track_gain(path: &str) -> Option<ReplayGainData> {
+    let tags = tag_service::read_all_tags(path)
+        .map_err(|e| warn!("Failed to read R128 gain from {}: {}", path, e))
+        .ok()?;
+    let value = tags.get(R128_TRACK_GAIN_KEY)?.first()?;
+    let data = gain_from_tag_value(value);
+    if data.is_none() {
+        warn!("Ignoring malformed {} {:?} in {}", R128_TRACK_GAIN_KEY, value, path);
+    }
+    data
+}
+
+/// Store the tagged R128 gain of each scanned Opus track that has no
+/// ReplayGain data yet; analysed gains are left alone.
+pub fn import_scanned_gains(db: &Database, tracks: &[Track]) {
+    let mut imported = 0;
+    for track in tracks.iter().filter(|t| is_opus(Path::new(&t.path))) {
+        if matches!(get_replaygain(&db.conn, &track.path), Ok(Some(_))) {
+            continue;
+        }
+        let Some(data) = read_track_gain(&track.path) else {
+            continue;
+        };
+        match store_replaygain(&db.conn, &track.path, &data) {
+            Ok(()) => imported += 1,
+            Err(e) => warn!("Failed to store R128 gain for {}: {}", track.path, e),
+        }
+    }
+    if imported > 0 {
+        info!("Imported R128 track gain for {} Opus files", imported);
+    }
+}
+
+/// Write `data` into the file at `path`: `R128_TRACK_GAIN` for Opus,
+/// `REPLAYGAIN_TRACK_GAIN`/`_PEAK` (at the -18 LUFS ReplayGain reference)
+/// for everything else.
+pub fn write_track_gain(path: &str, data: &ReplayGainData) -> Result<(), String> {
+    if is_opus(Path::new(path)) {
+        let gain = rebase_gain(data.track_gain, data.target_lufs, R128_REFERENCE_LUFS);
+        tag_service::write_tag(path, R128_TRACK_GAIN_KEY, &db_to_q78(gain).to_string())
+    } else {
+        let gain = rebase_gain(data.track_gain, data.target_lufs, DEFAULT_TARGET_LUFS);
+        tag_service::write_tag(path, "REPLAYGAIN_TRACK_GAIN", &format!("{:.2} dB", gain))?;
+        tag_service::write_tag(path, "REPLAYGAIN_TRACK_PEAK", &format!("{:.6}", data.track_peak))
+    }
+}
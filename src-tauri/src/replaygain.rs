@@ -1,19 +1,102 @@
+use crate::pcm_decode;
 use ebur128::{EbuR128, Mode};
-use std::fs::File;
-use symphonia::core::audio::{AudioBuffer, Signal};
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
 use serde::{Serialize, Deserialize};
-use log::{info, warn};
+use log::info;
+
+/// Target used when none is configured, and assumed for gains stored
+/// before the target was recorded.
+pub const DEFAULT_TARGET_LUFS: f64 = -18.0;
+pub const MIN_TARGET_LUFS: f64 = -30.0;
+pub const MAX_TARGET_LUFS: f64 = -5.0;
+/// Settings key under which the user's target loudness is persisted.
+pub const REPLAYGAIN_TARGET_KEY: &str = "replaygain_target_lufs";
+/// Settings key under which device name → target loudness is persisted.
+pub const DEVICE_TARGETS_KEY: &str = "replaygain_device_targets";
+
+fn default_target_lufs() -> f64 {
+    DEFAULT_TARGET_LUFS
+}
+
+/// Check a target loudness is in the supported range
+/// (e.g. -14 for streaming, -18 default, -23 for broadcast).
+pub fn validate_target_lufs(target_lufs: f64) -> Result<f64, String> {
+    if target_lufs.is_finite() && (MIN_TARGET_LUFS..=MAX_TARGET_LUFS).contains(&target_lufs) {
+        Ok(target_lufs)
+    } else {
+        Err(format!(
+            "ReplayGain target must be between {} and {} LUFS",
+            MIN_TARGET_LUFS, MAX_TARGET_LUFS
+        ))
+    }
+}
+
+/// Move a gain computed against `from_target` so it reaches `to_target`.
+pub fn rebase_gain(gain_db: f64, from_target: f64, to_target: f64) -> f64 {
+    gain_db + (to_target - from_target)
+}
+
+/// Settings key under which the ReplayGain mode is persisted.
+pub const REPLAYGAIN_MODE_KEY: &str = "replaygain_mode";
+/// Settings key under which the ReplayGain preamp (dB) is persisted.
+pub const REPLAYGAIN_PREAMP_KEY: &str = "replaygain_preamp_db";
+
+/// Which stored gain is applied when a track is loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplayGainMode {
+    /// Loading a track clears any ReplayGain adjustment.
+    #[default]
+    Off,
+    Track,
+    Album,
+    /// Album gain, or the track's own gain when the album has none.
+    AlbumWithTrackFallback,
+}
+
+/// A track's stored ReplayGain data together with its album's.
+#[derive(Debug, Clone, Default)]
+pub struct StoredGains {
+    pub track: Option<ReplayGainData>,
+    pub album: Option<AlbumReplayGainData>,
+}
+
+/// The gain picked for playback, already rebased to the current target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectedGain {
+    pub gain_db: f64,
+    pub peak: f64,
+}
+
+impl ReplayGainMode {
+    /// Pick the gain this mode applies, rebased to `target_lufs`.
+    pub fn select(self, stored: &StoredGains, target_lufs: f64) -> Option<SelectedGain> {
+        let track = || {
+            stored.track.as_ref().map(|t| SelectedGain {
+                gain_db: rebase_gain(t.track_gain, t.target_lufs, target_lufs),
+                peak: t.track_peak,
+            })
+        };
+        let album = || {
+            stored.album.as_ref().map(|a| SelectedGain {
+                gain_db: rebase_gain(a.album_gain, a.target_lufs, target_lufs),
+                peak: a.album_peak,
+            })
+        };
+
+        match self {
+            ReplayGainMode::Off => None,
+            ReplayGainMode::Track => track(),
+            ReplayGainMode::Album => album(),
+            ReplayGainMode::AlbumWithTrackFallback => album().or_else(track),
+        }
+    }
+}
 
 /**
  * ReplayGain analyzer for track loudness normalization
  * 
  * Uses EBU R128 standard for consistent loudness measurement
- * Target loudness: -18 LUFS (streaming standard)
+ * Target loudness: configurable, -18 LUFS by default
  */
 /// ReplayGain data for a track
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +104,8 @@ pub struct ReplayGainData {
     pub track_gain: f64,  // dB adjustment needed
     pub track_peak: f64,  // Peak sample value (0.0-1.0)
     pub loudness: f64,    // LUFS measurement
+    #[serde(default = "default_target_lufs")]
+    pub target_lufs: f64, // Target the gain was computed against
 }
 
 /// Album-level ReplayGain data computed from tracks in the same album.
@@ -30,126 +115,74 @@ pub struct AlbumReplayGainData {
     pub album_peak: f64,
     pub loudness: f64,
     pub track_count: i64,
+    #[serde(default = "default_target_lufs")]
+    pub target_lufs: f64,
+}
+
+/// Incremental EBU R128 loudness and peak measurement fed with interleaved
+/// samples.
+pub struct GainAnalyzer {
+    ebur: EbuR128,
+    peak: f64,
+}
+
+impl GainAnalyzer {
+    pub fn new(channels: usize, sample_rate: u32) -> Result<Self, String> {
+        let ebur = EbuR128::new(channels as u32, sample_rate, Mode::I | Mode::TRUE_PEAK)
+            .map_err(|e| format!("Failed to create EBU R128 analyzer: {}", e))?;
+        Ok(Self { ebur, peak: 0.0 })
+    }
+
+    /// Feed frame-interleaved samples; the peak is tracked across all channels.
+    pub fn push(&mut self, interleaved: &[f32]) -> Result<(), String> {
+        self.ebur.add_frames_f32(interleaved)
+            .map_err(|e| format!("Failed to add frames: {}", e))?;
+        for &sample in interleaved {
+            self.peak = self.peak.max(sample.abs() as f64);
+        }
+        Ok(())
+    }
+
+    /// The gain that brings what was measured to `target_lufs`.
+    pub fn finish(self, target_lufs: f64) -> Result<ReplayGainData, String> {
+        let loudness = self.ebur.loudness_global()
+            .map_err(|e| format!("Failed to get loudness: {}", e))?;
+        let gain = target_lufs - loudness;
+
+        info!("ReplayGain analysis complete: loudness={:.2} LUFS, gain={:.2} dB (target {:.1}), peak={:.4}",
+              loudness, gain, target_lufs, self.peak);
+
+        Ok(ReplayGainData {
+            track_gain: gain,
+            track_peak: self.peak,
+            loudness,
+            target_lufs,
+        })
+    }
 }
 
 /**
- * Analyze audio file for ReplayGain data
+ * Analyze audio file for ReplayGain data against `target_lufs`
  */
-pub fn analyze_track(path: &str) -> Result<ReplayGainData, String> {
+pub fn analyze_track(path: &str, target_lufs: f64) -> Result<ReplayGainData, String> {
     info!("Analyzing ReplayGain for: {}", path);
-    
-    // Open audio file
-    let file = File::open(path)
-        .map_err(|e| format!("Failed to open file: {}", e))?;
-    
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-    
-    // Probe format
-    let mut hint = Hint::new();
-    if let Some(ext) = std::path::Path::new(path).extension() {
-        hint.with_extension(&ext.to_string_lossy());
-    }
-    
-    let probed = symphonia::default::get_probe()
-        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-        .map_err(|e| format!("Failed to probe format: {}", e))?;
-    
-    let mut format = probed.format;
-    
-    // Get default audio track
-    let track = format.tracks()
-        .iter()
-        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or_else(|| "No audio track found".to_string())?;
-    
-    let track_id = track.id;
-    let codec_params = &track.codec_params;
-    
-    // Create decoder
-    let mut decoder = symphonia::default::get_codecs()
-        .make(codec_params, &DecoderOptions::default())
-        .map_err(|e| format!("Failed to create decoder: {}", e))?;
-    
-    // Get audio parameters
-    let channels = codec_params.channels
-        .ok_or_else(|| "No channel info".to_string())?
-        .count();
-    
-    let sample_rate = codec_params.sample_rate
-        .ok_or_else(|| "No sample rate info".to_string())? as u32;
-    
-    // Initialize EBU R128 analyzer
-    let mut ebur = EbuR128::new(channels as u32, sample_rate, Mode::I | Mode::TRUE_PEAK)
-        .map_err(|e| format!("Failed to create EBU R128 analyzer: {}", e))?;
-    
-    let mut peak = 0.0_f64;
-    
-    // Decode and analyze all packets
-    while let Ok(packet) = format.next_packet() {
-        
-        // Skip packets from other tracks
-        if packet.track_id() != track_id {
-            continue;
-        }
-        
-        // Decode packet
-        match decoder.decode(&packet) {
-            Ok(decoded) => {
-                // Convert to f32 samples for analysis
-                let spec = *decoded.spec();
-                let duration = decoded.capacity() as u64;
-                let num_channels = spec.channels.count();
-                
-                // Create audio buffer
-                let mut audio_buf = AudioBuffer::<f32>::new(duration, spec);
-                decoded.convert(&mut audio_buf);
-                
-                // Interleave all channels for EBU R128 analysis
-                let num_frames = audio_buf.chan(0).len();
-                let mut interleaved = Vec::with_capacity(num_channels * num_frames);
-                for frame in 0..num_frames {
-                    for ch in 0..num_channels {
-                        interleaved.push(audio_buf.chan(ch)[frame]);
-                    }
-                }
-                
-                // Feed interleaved samples to EBU R128
-                ebur.add_frames_f32(&interleaved)
-                    .map_err(|e| format!("Failed to add frames: {}", e))?;
-                
-                // Track peak across ALL channels
-                for ch in 0..num_channels {
-                    for &sample in audio_buf.chan(ch) {
-                        let abs = sample.abs() as f64;
-                        if abs > peak {
-                            peak = abs;
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                warn!("Decode error (continuing): {}", e);
-                continue;
-            }
-        }
+
+    let mut analyzer: Option<GainAnalyzer> = None;
+    let mut error = None;
+    pcm_decode::decode_file(path, |block| {
+        let result = match &mut analyzer {
+            Some(analyzer) => analyzer.push(block.interleaved),
+            None => GainAnalyzer::new(block.channels, block.sample_rate)
+                .and_then(|a| analyzer.insert(a).push(block.interleaved)),
+        };
+        error = result.err();
+        error.is_none()
+    })?;
+    if let Some(e) = error {
+        return Err(e);
     }
-    
-    // Get loudness measurement
-    let loudness = ebur.loudness_global()
-        .map_err(|e| format!("Failed to get loudness: {}", e))?;
-    
-    // Calculate gain needed to reach target (-18 LUFS)
-    let target = -18.0;
-    let gain = target - loudness;
-    
-    info!("ReplayGain analysis complete: loudness={:.2} LUFS, gain={:.2} dB, peak={:.4}", 
-          loudness, gain, peak);
-    
-    Ok(ReplayGainData {
-        track_gain: gain,
-        track_peak: peak,
-        loudness,
-    })
+
+    analyzer.ok_or_else(|| "No audio decoded".to_string())?.finish(target_lufs)
 }
 
 // Storage functions have been moved to `replaygain_store.rs` to separate
@@ -159,9 +192,16 @@ pub fn analyze_track(path: &str) -> Result<ReplayGainData, String> {
 pub use crate::replaygain_store::{
     store_replaygain,
     get_replaygain,
+    get_stored_gains,
     store_album_replaygain,
     get_album_replaygain,
     analyze_album_replaygain,
+    get_target_lufs,
+    set_target_lufs,
+    get_device_targets,
+    set_device_target_lufs,
+    active_target_lufs,
+    playback_gain_db,
 };
 
 #[cfg(test)]
@@ -174,9 +214,58 @@ mod tests {
             track_gain: -5.0,
             track_peak: 0.8,
             loudness: -23.0,
+            target_lufs: DEFAULT_TARGET_LUFS,
         };
         assert_eq!(data.track_gain, -5.0);
         assert_eq!(data.track_peak, 0.8);
         assert_eq!(data.loudness, -23.0);
     }
+
+    #[test]
+    fn gain_is_rebased_to_other_targets() {
+        let data = ReplayGainData {
+            track_gain: 5.0,
+            track_peak: 0.5,
+            loudness: -23.0,
+            target_lufs: -18.0,
+        };
+        assert_eq!(rebase_gain(data.track_gain, data.target_lufs, -18.0), 5.0);
+        assert_eq!(rebase_gain(data.track_gain, data.target_lufs, -14.0), 9.0);
+        assert_eq!(rebase_gain(data.track_gain, data.target_lufs, -23.0), 0.0);
+    }
+
+    #[test]
+    fn legacy_json_assumes_default_target() {
+        let data: ReplayGainData =
+            serde_json::from_str(r#"{"track_gain":-2.0,"track_peak":0.9,"loudness":-16.0}"#).unwrap();
+        assert_eq!(data.target_lufs, DEFAULT_TARGET_LUFS);
+    }
+
+    #[test]
+    fn modes_pick_the_matching_gain() {
+        let stored = StoredGains {
+            track: Some(ReplayGainData { track_gain: -3.0, track_peak: 0.9, loudness: -15.0, target_lufs: -18.0 }),
+            album: Some(AlbumReplayGainData {
+                album_gain: -5.0, album_peak: 0.95, loudness: -13.0, track_count: 10, target_lufs: -18.0,
+            }),
+        };
+        let pick = |mode: ReplayGainMode| mode.select(&stored, -18.0).map(|g| g.gain_db);
+        assert_eq!(pick(ReplayGainMode::Off), None);
+        assert_eq!(pick(ReplayGainMode::Track), Some(-3.0));
+        assert_eq!(pick(ReplayGainMode::Album), Some(-5.0));
+        assert_eq!(ReplayGainMode::Track.select(&stored, -14.0).unwrap().gain_db, 1.0);
+
+        let track_only = StoredGains { album: None, ..stored };
+        assert_eq!(ReplayGainMode::Album.select(&track_only, -18.0), None);
+    }
+
+    #[test]
+    fn target_range_is_enforced() {
+        for ok in [-14.0, -18.0, -23.0] {
+            assert!(validate_target_lufs(ok).is_ok());
+        }
+        for bad in [0.0, -40.0, f64::NAN] {
+            assert!(validate_target_lufs(bad).is_err());
+        }
+    }
 }
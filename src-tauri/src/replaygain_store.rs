@@ -4,12 +4,79 @@
 //! This module owns all DB reads/writes for per-track and per-album
 //! ReplayGain data.
 
-use crate::replaygain::{AlbumReplayGainData, ReplayGainData};
+use crate::database::Database;
+use crate::replaygain::{
+    rebase_gain, validate_target_lufs, AlbumReplayGainData, ReplayGainData, StoredGains,
+    DEFAULT_TARGET_LUFS, DEVICE_TARGETS_KEY, REPLAYGAIN_TARGET_KEY,
+};
 use crate::time_utils::now_millis;
 use log::warn;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::sync::Mutex;
 
+/// The user's configured loudness target, or the default if unset or invalid.
+pub fn get_target_lufs(db: &Database) -> f64 {
+    db.get_json_setting::<f64>(REPLAYGAIN_TARGET_KEY)
+        .ok()
+        .flatten()
+        .and_then(|t| validate_target_lufs(t).ok())
+        .unwrap_or(DEFAULT_TARGET_LUFS)
+}
+
+/// Validate and persist the loudness target used for new analyses.
+pub fn set_target_lufs(db: &Database, target_lufs: f64) -> Result<(), String> {
+    let target_lufs = validate_target_lufs(target_lufs)?;
+    db.set_json_setting(REPLAYGAIN_TARGET_KEY, &target_lufs)
+        .map_err(|e| format!("Failed to save ReplayGain target: {}", e))
+}
+
+/// Per-device loudness targets; empty when nothing (or something
+/// unreadable) is stored.
+pub fn get_device_targets(db: &Database) -> HashMap<String, f64> {
+    db.get_json_setting::<HashMap<String, f64>>(DEVICE_TARGETS_KEY)
+        .unwrap_or_else(|e| {
+            warn!("Failed to read device ReplayGain targets: {}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+/// Validate and persist the target used while `device` is the output;
+/// `None` makes the device follow the global target again.
+pub fn set_device_target_lufs(db: &Database, device: &str, target_lufs: Option<f64>) -> Result<(), String> {
+    let mut targets = get_device_targets(db);
+    match target_lufs {
+        Some(target) => {
+            targets.insert(device.to_string(), validate_target_lufs(target)?);
+        }
+        None => {
+            targets.remove(device);
+        }
+    }
+    db.set_json_setting(DEVICE_TARGETS_KEY, &targets)
+        .map_err(|e| format!("Failed to save device ReplayGain target: {}", e))
+}
+
+/// The target playback normalizes to on `device`: its own target if one is
+/// stored, otherwise the global one.
+pub fn active_target_lufs(db: &Database, device: Option<&str>) -> f64 {
+    device
+        .and_then(|device| get_device_targets(db).get(device).copied())
+        .and_then(|t| validate_target_lufs(t).ok())
+        .unwrap_or_else(|| get_target_lufs(db))
+}
+
+/// A gain measured against `measured_target` (the default when unknown),
+/// rebased to the active target on `device`.
+pub fn playback_gain_db(db: &Database, device: Option<&str>, gain_db: f64, measured_target: Option<f64>) -> f64 {
+    rebase_gain(
+        gain_db,
+        measured_target.unwrap_or(DEFAULT_TARGET_LUFS),
+        active_target_lufs(db, device),
+    )
+}
+
 /// Store per-track ReplayGain data.
 pub fn store_replaygain(
     conn: &Mutex<Connection>,
@@ -22,8 +89,15 @@ pub fn store_replaygain(
     });
 
     conn.execute(
-        "UPDATE tracks SET track_gain = ?, track_peak = ?, loudness = ? WHERE path = ?",
-        rusqlite::params![data.track_gain, data.track_peak, data.loudness, track_path],
+        "UPDATE tracks SET track_gain = ?, track_peak = ?, loudness = ?, replaygain_target = ?
+         WHERE path = ?",
+        rusqlite::params![
+            data.track_gain,
+            data.track_peak,
+            data.loudness,
+            data.target_lufs,
+            track_path
+        ],
     )
     .map_err(|e| format!("Failed to store ReplayGain: {}", e))?;
 
@@ -41,19 +115,23 @@ pub fn get_replaygain(
     });
 
     let mut stmt = conn
-        .prepare("SELECT track_gain, track_peak, loudness FROM tracks WHERE path = ?")
+        .prepare(
+            "SELECT track_gain, track_peak, loudness, replaygain_target FROM tracks WHERE path = ?",
+        )
         .map_err(|e| format!("Failed to prepare statement: {}", e))?;
 
     let result = stmt.query_row(rusqlite::params![track_path], |row| {
         let gain: Option<f64> = row.get(0)?;
         let peak: Option<f64> = row.get(1)?;
         let loudness: Option<f64> = row.get(2)?;
+        let target: Option<f64> = row.get(3)?;
 
         if let (Some(g), Some(p), Some(l)) = (gain, peak, loudness) {
             Ok(Some(ReplayGainData {
                 track_gain: g,
                 track_peak: p,
                 loudness: l,
+                target_lufs: target.unwrap_or(DEFAULT_TARGET_LUFS),
             }))
         } else {
             Ok(None)
@@ -82,8 +160,8 @@ pub fn store_album_replaygain(
     let updated_at = now_millis();
     conn.execute(
         "INSERT OR REPLACE INTO album_replaygain
-            (artist, album, album_gain, album_peak, loudness, track_count, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (artist, album, album_gain, album_peak, loudness, track_count, updated_at, target_lufs)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         rusqlite::params![
             artist,
             album,
@@ -91,7 +169,8 @@ pub fn store_album_replaygain(
             data.album_peak,
             data.loudness,
             data.track_count,
-            updated_at
+            updated_at,
+            data.target_lufs
         ],
     )
     .map_err(|e| format!("Failed to store album ReplayGain: {}", e))?;
@@ -112,7 +191,7 @@ pub fn get_album_replaygain(
 
     let mut stmt = conn
         .prepare(
-            "SELECT album_gain, album_peak, loudness, track_count
+            "SELECT album_gain, album_peak, loudness, track_count, target_lufs
              FROM album_replaygain
              WHERE artist = ?1 AND album = ?2",
         )
@@ -124,6 +203,9 @@ pub fn get_album_replaygain(
             album_peak: row.get(1)?,
             loudness: row.get(2)?,
             track_count: row.get(3)?,
+            target_lufs: row
+                .get::<_, Option<f64>>(4)?
+                .unwrap_or(DEFAULT_TARGET_LUFS),
         })
     });
 
@@ -134,11 +216,43 @@ pub fn get_album_replaygain(
     }
 }
 
+/// Read a track's ReplayGain data and the cached data for its album.
+/// Tracks without artist and album tags have no album gain.
+pub fn get_stored_gains(conn: &Mutex<Connection>, track_path: &str) -> Result<StoredGains, String> {
+    let track = get_replaygain(conn, track_path)?;
+
+    let tags: Option<(Option<String>, Option<String>)> = {
+        let conn = conn.lock().unwrap_or_else(|poisoned| {
+            warn!("ReplayGain DB mutex was poisoned — recovering inner connection");
+            poisoned.into_inner()
+        });
+        conn.query_row(
+            "SELECT artist, album FROM tracks WHERE path = ?",
+            rusqlite::params![track_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| format!("Database error: {}", e))?
+    };
+
+    let album = match tags {
+        Some((Some(artist), Some(album))) if !artist.trim().is_empty() && !album.trim().is_empty() => {
+            get_album_replaygain(conn, artist.trim(), album.trim())?
+        }
+        _ => None,
+    };
+
+    Ok(StoredGains { track, album })
+}
+
 /// Compute album-level ReplayGain from existing per-track rows and cache it.
+/// Track gains measured against other targets are rebased to `target_lufs`
+/// before averaging.
 pub fn analyze_album_replaygain(
     conn: &Mutex<Connection>,
     artist: &str,
     album: &str,
+    target_lufs: f64,
 ) -> Result<Option<AlbumReplayGainData>, String> {
     let conn_guard = conn.lock().unwrap_or_else(|poisoned| {
         warn!("ReplayGain DB mutex was poisoned — recovering inner connection");
@@ -147,7 +261,7 @@ pub fn analyze_album_replaygain(
 
     let mut stmt = conn_guard
         .prepare(
-            "SELECT track_gain, track_peak, loudness, duration
+            "SELECT track_gain, track_peak, loudness, duration, replaygain_target
              FROM tracks
              WHERE artist = ?1
                AND album = ?2
@@ -163,6 +277,8 @@ pub fn analyze_album_replaygain(
             let peak: f64 = row.get(1)?;
             let loudness: f64 = row.get(2)?;
             let duration: f64 = row.get(3)?;
+            let target: Option<f64> = row.get(4)?;
+            let gain = rebase_gain(gain, target.unwrap_or(DEFAULT_TARGET_LUFS), target_lufs);
             Ok((gain, peak, loudness, duration.max(0.001)))
         })
         .map_err(|e| format!("Failed to execute album analysis query: {}", e))?
@@ -199,6 +315,7 @@ pub fn analyze_album_replaygain(
         album_peak: peak,
         loudness: weighted_loudness_sum / total_duration,
         track_count: rows.len() as i64,
+        target_lufs,
     };
 
     store_album_replaygain(conn, artist, album, &data)?;
@@ -0,0 +1,230 @@
+//! Payloads of the `scan-*` events emitted while scanning a folder.
+//!
+//! Every event carries a typed, camelCase payload so the frontend can tell
+//! which file a skip or error is about and why, instead of parsing messages.
+//! The scanner emits through [`ScanEventSink`], which the Tauri window
+//! implements and tests can replace with a collector.
+//!
+//! `scan-progress` is throttled by [`ProgressThrottle`] so scanning tens of
+//! thousands of files doesn't flood IPC with one event per file.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+
+/// Settings key under which the progress throttle is persisted.
+pub const SCAN_PROGRESS_THROTTLE_KEY: &str = "scan_progress_throttle";
+pub const MAX_PROGRESS_EVERY_MS: u64 = 10_000;
+
+/// Sent once before any file is processed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanTotal {
+    pub total: usize,
+}
+
+/// Which pass of the scan a progress event belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanPhase {
+    /// Reading tags; `total` counts the files found.
+    Reading,
+    /// Analyze-on-scan ReplayGain; `total` counts the tracks read.
+    Analyzing,
+}
+
+/// Sent as files are processed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    pub phase: ScanPhase,
+    pub current: usize,
+    pub total: usize,
+    pub current_file: String,
+    pub path: String,
+}
+
+/// Why a file was skipped or failed to scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanIssueKind {
+    /// Failed in an earlier scan and is on the failed-tracks list.
+    PreviouslyFailed,
+    /// Disappeared between listing the folder and reading it.
+    NotFound,
+    PermissionDenied,
+    /// Opened, but tags or audio properties could not be read.
+    Unreadable,
+}
+
+impl ScanIssueKind {
+    /// Classify a failed extraction of `path` by how opening it fails.
+    pub fn for_failed_file(path: &Path) -> Self {
+        match std::fs::File::open(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::NotFound,
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::Unreadable,
+        }
+    }
+}
+
+/// Payload of `scan-skip` and `scan-error`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanIssue {
+    pub path: String,
+    pub file_name: String,
+    pub kind: ScanIssueKind,
+    pub message: String,
+}
+
+impl ScanIssue {
+    pub fn new(path: &Path, kind: ScanIssueKind, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// Sent when the scan stops early because it was cancelled.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanCancelled {
+    /// Files handled before the cancellation was noticed.
+    pub processed: usize,
+}
+
+/// Sent when every file has been processed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanComplete {
+    /// Tracks read successfully.
+    pub track_count: usize,
+}
+
+/// One scan event; serializes as its payload alone.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ScanEvent {
+    Total(ScanTotal),
+    Progress(ScanProgress),
+    Skip(ScanIssue),
+    Error(ScanIssue),
+    Cancelled(ScanCancelled),
+    Complete(ScanComplete),
+}
+
+impl ScanEvent {
+    /// Tauri event name this event is emitted under.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Total(_) => "scan-total",
+            Self::Progress(_) => "scan-progress",
+            Self::Skip(_) => "scan-skip",
+            Self::Error(_) => "scan-error",
+            Self::Cancelled(_) => "scan-cancelled",
+            Self::Complete(_) => "scan-complete",
+        }
+    }
+}
+
+/// Receiver of scan events.
+pub trait ScanEventSink {
+    fn emit_scan_event(&self, event: ScanEvent);
+}
+
+impl<R: tauri::Runtime> ScanEventSink for tauri::Window<R> {
+    fn emit_scan_event(&self, event: ScanEvent) {
+        let _ = self.emit(event.name(), &event);
+    }
+}
+
+/// How often `scan-progress` may be emitted: once `every_files` files or
+/// `every_ms` milliseconds have passed since the last one, whichever comes
+/// first. The first and last file always report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanProgressThrottle {
+    pub every_files: usize,
+    pub every_ms: u64,
+}
+
+impl Default for ScanProgressThrottle {
+    fn default() -> Self {
+        Self {
+            every_files: 100,
+            every_ms: 100,
+        }
+    }
+}
+
+impl ScanProgressThrottle {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.every_files == 0 {
+            return Err(AppError::Validation("Progress file interval must be at least 1".to_string()));
+        }
+        if self.every_ms > MAX_PROGRESS_EVERY_MS {
+            return Err(AppError::Validation(format!(
+                "Progress interval must be at most {} ms",
+                MAX_PROGRESS_EVERY_MS
+            )));
+        }
+        Ok(())
+    }
+
+    /// The persisted throttle, or the default when unset or invalid.
+    pub fn load(db: &Database) -> Self {
+        match db.get_json_setting::<Self>(SCAN_PROGRESS_THROTTLE_KEY) {
+            Ok(Some(throttle)) if throttle.validate().is_ok() => throttle,
+            Ok(_) => Self::default(),
+            Err(e) => {
+                warn!("Failed to read scan progress throttle: {}", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Per-scan state deciding which files report progress.
+pub struct ProgressThrottle {
+    config: ScanProgressThrottle,
+    last_emit: Option<(usize, Instant)>,
+}
+
+impl ProgressThrottle {
+    pub fn new(config: ScanProgressThrottle) -> Self {
+        Self {
+            config,
+            last_emit: None,
+        }
+    }
+
+    /// Whether progress for file `current` of `total` is due at `now`.
+    pub fn should_emit(&mut self, current: usize, total: usize, now: Instant) -> bool {
+        let due = match self.last_emit {
+            None => true,
+            Some((last_file, at)) => {
+                current >= total
+                    || current.saturating_sub(last_file) >= self.config.every_files
+                    || now.saturating_duration_since(at) >= Duration::from_millis(self.config.every_ms)
+            }
+        };
+        if due {
+            self.last_emit = Some((current, now));
+        }
+        due
+    }
+}
@@ -1,17 +1,29 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use walkdir::WalkDir;
 use log::{info, warn, error};
 use lofty::TaggedFileExt;
-use tauri::{Window, Emitter};
+use tauri::Window;
 use crate::database::Database;
+use crate::fingerprint;
+use crate::media_container;
+use crate::r128_gain;
+use crate::replaygain::{self, ReplayGainData};
+use crate::scan_events::{
+    ProgressThrottle, ScanCancelled, ScanComplete, ScanEvent, ScanEventSink, ScanIssue, ScanIssueKind,
+    ScanPhase, ScanProgress, ScanProgressThrottle, ScanTotal,
+};
 use crate::time_utils::now_millis;
 
 /// Standard SELECT column list for Track::from_row.
 /// Every query that uses Track::from_row MUST select exactly these columns in this order.
 pub const TRACK_SELECT_COLUMNS: &str = "id, path, name, title, artist, album, genre, year, track_number, disc_number, duration, date_added, rating, play_count, last_played";
+/// Number of columns in TRACK_SELECT_COLUMNS; extra columns selected after
+/// them start at this index.
+pub const TRACK_SELECT_COLUMN_COUNT: usize = 15;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Track {
@@ -62,30 +74,224 @@ impl Track {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct ScanProgress {
-    pub current: usize,
-    pub total: usize,
-    pub current_file: String,
+/// Track ids changed by a single-folder rescan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderRescanDiff {
+    /// Files not in the library before.
+    pub added: Vec<String>,
+    /// Files modified since they were last scanned and re-read.
+    pub updated: Vec<String>,
+    /// Tracks whose file no longer exists.
+    pub removed: Vec<String>,
+    /// `removed` was only proposed, not applied: part of the folder couldn't
+    /// be read, or the folder is missing or empty, which is more likely an
+    /// unmounted or unreachable folder than a real deletion.
+    pub removal_withheld: bool,
+}
+
+/// Audio files found under a folder, and what couldn't be read on the way.
+struct CollectedFiles {
+    files: Vec<std::path::PathBuf>,
+    /// Directories and entries the walk failed to read, skipped.
+    walk_errors: Vec<String>,
 }
 
 pub struct Scanner;
 
-/// Supported audio file extensions, shared with watcher module.
-pub const AUDIO_EXTENSIONS: [&str; 7] = ["mp3", "m4a", "flac", "wav", "ogg", "opus", "aac"];
+/// Upper bound on files decoded at once by analyze-on-scan; decoding is
+/// also disk-bound, so more threads stop helping quickly.
+const MAX_ANALYSIS_WORKERS: usize = 4;
+
+/// Supported audio file extensions, shared with watcher module. Includes
+/// video containers, which are played for their audio track.
+pub const AUDIO_EXTENSIONS: [&str; 11] = [
+    "mp3", "m4a", "flac", "wav", "ogg", "opus", "aac", "mp4", "m4v", "mkv", "webm",
+];
+
+/// Per-folder scan profile. The default reproduces the classic behaviour:
+/// all supported extensions, symlinks followed, unlimited depth, nothing ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanOptions {
+    /// Extensions to pick up, case-insensitive, without the leading dot.
+    pub extensions: Vec<String>,
+    pub follow_links: bool,
+    /// Maximum directory depth below the root (files directly in the root
+    /// are depth 1). `None` = unlimited.
+    pub max_depth: Option<usize>,
+    /// Glob patterns to skip. A pattern without `/` matches any file or
+    /// folder name (e.g. `Podcasts`, `*.tmp.mp3`); one with `/` matches the
+    /// path relative to the scan root (e.g. `Live/**`). Ignored folders are
+    /// not descended into.
+    pub ignore_globs: Vec<String>,
+    /// Measure ReplayGain for every scanned file once the scan is stored, so
+    /// a freshly scanned library is normalized without a separate step.
+    pub analyze_replaygain: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            extensions: AUDIO_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            follow_links: true,
+            max_depth: None,
+            ignore_globs: Vec::new(),
+            analyze_replaygain: false,
+        }
+    }
+}
+
+impl ScanOptions {
+    fn settings_key(folder_path: &str) -> String {
+        format!("scan_options:{}", folder_path)
+    }
+
+    /// Saved profile for `folder_path`, or the default one.
+    pub fn load_for_folder(db: &Database, folder_path: &str) -> Self {
+        db.get_json_setting(&Self::settings_key(folder_path))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    pub fn save_for_folder(&self, db: &Database, folder_path: &str) -> Result<(), String> {
+        db.set_json_setting(&Self::settings_key(folder_path), self)
+            .map_err(|e| format!("Failed to save scan options: {}", e))
+    }
+
+    /// Compile `ignore_globs`, rejecting malformed patterns up front.
+    pub fn compile_ignore_globs(&self) -> Result<Vec<glob::Pattern>, String> {
+        self.ignore_globs
+            .iter()
+            .map(|g| glob::Pattern::new(g).map_err(|e| format!("Invalid ignore pattern '{}': {}", g, e)))
+            .collect()
+    }
+
+    fn matches_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Whether `path` (below `root`) is excluded by one of `patterns`.
+fn is_ignored(patterns: &[glob::Pattern], root: &Path, path: &Path) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative_str = relative.to_string_lossy().replace('\\', "/");
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    patterns.iter().any(|p| {
+        if p.as_str().contains('/') {
+            p.matches(&relative_str)
+        } else {
+            p.matches(&name)
+        }
+    })
+}
+
+/// `path` resolved the way the scanner compares walked entries, for storing
+/// in the ignore list. A path that can't be resolved (e.g. on a drive that
+/// isn't connected) is kept as given.
+pub fn canonical_ignored_path(path: &str) -> String {
+    match std::path::Path::new(path).canonicalize() {
+        Ok(canonical) => canonical.to_string_lossy().into_owned(),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// The user's ignore list as canonical paths; empty without a database.
+/// Entries stored before they were canonicalized are resolved here.
+fn ignored_roots(db: Option<&Database>) -> Vec<std::path::PathBuf> {
+    let Some(db) = db else {
+        return Vec::new();
+    };
+    match db.list_ignored_paths() {
+        Ok(paths) => paths
+            .iter()
+            .map(|p| std::path::PathBuf::from(canonical_ignored_path(p)))
+            .collect(),
+        Err(e) => {
+            warn!("Scanner: failed to load ignored paths: {}", e);
+            Vec::new()
+        }
+    }
+}
 
 impl Scanner {
-    /// Collect all audio file paths from a directory tree.
-    fn collect_audio_files(path: &str) -> Vec<std::path::PathBuf> {
+    /// Collect all audio file paths from a directory tree, skipping anything
+    /// at or below an entry of `ignored` (canonical paths). Entries that can't be read are
+    /// skipped with a warning and reported in `walk_errors`, so a caller that
+    /// deletes what it didn't find can tell a partial walk from a full one.
+    fn collect_audio_files(
+        path: &str,
+        options: &ScanOptions,
+        ignored: &[std::path::PathBuf],
+    ) -> Result<CollectedFiles, String> {
         let root_path = std::path::Path::new(path);
         // Canonicalize once so symlink resolution comparisons are consistent.
         // Falls back to the original path on error (e.g. unusual Windows paths).
         let canonical_root = root_path.canonicalize().unwrap_or_else(|_| root_path.to_path_buf());
+        let ignore = options.compile_ignore_globs()?;
+
+        let mut walker = WalkDir::new(path).follow_links(options.follow_links);
+        if let Some(depth) = options.max_depth {
+            walker = walker.max_depth(depth);
+        }
+
+        // Real directories already walked. With symlinks followed, a link to an
+        // ancestor (or two links to one folder) would otherwise revisit the
+        // same tree — forever, in the ancestor case.
+        let mut visited_dirs: HashSet<std::path::PathBuf> = HashSet::new();
 
-        WalkDir::new(path)
-            .follow_links(true)
+        let mut walk_errors: Vec<String> = Vec::new();
+
+        let files: Vec<std::path::PathBuf> = walker
             .into_iter()
-            .filter_map(|e| e.ok())
+            // Skip ignored entries before descending so whole subtrees drop out.
+            .filter_entry(|e| {
+                // Where the entry sits under the canonical root, so a root
+                // spelled differently from the stored entries still matches.
+                let walk_path = canonical_root.join(e.path().strip_prefix(root_path).unwrap_or(e.path()));
+                if ignored.iter().any(|p| walk_path.starts_with(p)) {
+                    return false;
+                }
+                if e.depth() > 0 && is_ignored(&ignore, root_path, e.path()) {
+                    return false;
+                }
+                if options.follow_links && e.file_type().is_dir() {
+                    let real = e.path().canonicalize().unwrap_or_else(|_| e.path().to_path_buf());
+                    if ignored.iter().any(|p| real.starts_with(p)) {
+                        return false;
+                    }
+                    if !visited_dirs.insert(real.clone()) {
+                        warn!("Scanner: skipping already-scanned directory {:?} (resolves to {:?})", e.path(), real);
+                        return false;
+                    }
+                }
+                true
+            })
+            .filter_map(|e| match e {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    if let Some(ancestor) = err.loop_ancestor() {
+                        warn!("Scanner: symlink loop at {:?} back to {:?}", err.path(), ancestor);
+                    } else if err.path().is_some_and(|p| p.is_symlink()) {
+                        warn!("Scanner: skipping unreadable symlink {:?}: {}", err.path(), err);
+                    } else {
+                        warn!("Scanner: skipping unreadable entry {:?}: {}", err.path().unwrap_or(root_path), err);
+                        walk_errors.push(format!("Failed to read {:?}: {}", err.path().unwrap_or(root_path), err));
+                    }
+                    None
+                }
+            })
             // Symlink boundary guard: if an entry is itself a symlink, verify that
             // it resolves inside the scan root. This prevents a symlink like
             // `Music/link -> C:\Windows\System32` from walking outside the intended
@@ -112,39 +318,42 @@ impl Scanner {
                 }
             })
             .filter(|e| e.path().is_file())
-            .filter(|e| {
-                e.path().extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-                    .unwrap_or(false)
-            })
+            .filter(|e| options.matches_extension(e.path()))
             .map(|e| e.path().to_path_buf())
-            .collect()
+            .collect();
+        Ok(CollectedFiles { files, walk_errors })
     }
 
     /// Shared processing loop for scanning audio files.
     /// Handles cancellation, progress events, failed-track skipping, and extraction.
-    fn process_files(
+    /// Progress is throttled by the persisted [`ScanProgressThrottle`] (the
+    /// default without a database). With `analysis_follows`, `scan-complete`
+    /// is left to [`persist_and_analyze`](Self::persist_and_analyze).
+    pub fn scan_files(
         files: &[std::path::PathBuf],
-        window: Option<&Window>,
+        events: Option<&dyn ScanEventSink>,
         cancel_flag: &Option<Arc<AtomicBool>>,
         db: Option<&Database>,
+        analysis_follows: bool,
     ) -> Result<Vec<Track>, String> {
+        let mut throttle = ProgressThrottle::new(db.map(ScanProgressThrottle::load).unwrap_or_default());
+        let emit = |event: ScanEvent| {
+            if let Some(sink) = events {
+                sink.emit_scan_event(event);
+            }
+        };
+
         let mut tracks = Vec::new();
         let total = files.len();
 
-        if let Some(win) = window {
-            let _ = win.emit("scan-total", total);
-        }
+        emit(ScanEvent::Total(ScanTotal { total }));
 
         for (i, path_buf) in files.iter().enumerate() {
             // Check for cancellation
             if let Some(flag) = cancel_flag {
                 if flag.load(Ordering::Relaxed) {
                     warn!("Scan cancelled after {} files", i);
-                    if let Some(win) = window {
-                        let _ = win.emit("scan-cancelled", i);
-                    }
+                    emit(ScanEvent::Cancelled(ScanCancelled { processed: i }));
                     return Ok(tracks);
                 }
             }
@@ -155,24 +364,27 @@ impl Scanner {
             // Skip if this path previously failed
             if let Some(database) = db {
                 if database.is_failed_track(&path_str) {
-                    if let Some(win) = window {
-                        let _ = win.emit("scan-skip", format!("Skipping previously failed: {:?}", path_buf.file_name()));
-                    }
+                    emit(ScanEvent::Skip(ScanIssue::new(
+                        path_buf,
+                        ScanIssueKind::PreviouslyFailed,
+                        "Skipping previously failed file",
+                    )));
                     continue;
                 }
             }
 
             // Emit progress update
-            if let Some(win) = window {
-                let progress = ScanProgress {
+            if events.is_some() && throttle.should_emit(processed, total, std::time::Instant::now()) {
+                emit(ScanEvent::Progress(ScanProgress {
+                    phase: ScanPhase::Reading,
                     current: processed,
                     total,
                     current_file: path_buf.file_name()
                         .and_then(|n| n.to_str())
                         .unwrap_or("Unknown")
                         .to_string(),
-                };
-                let _ = win.emit("scan-progress", &progress);
+                    path: path_str.clone(),
+                }));
             }
 
             match Self::extract_track_info(path_buf) {
@@ -182,30 +394,154 @@ impl Scanner {
                     if let Some(database) = db {
                         let _ = database.add_failed_track(&path_str, &e);
                     }
-                    if let Some(win) = window {
-                        let _ = win.emit("scan-error", format!("Failed to read: {:?}", path_buf.file_name()));
-                    }
+                    let kind = ScanIssueKind::for_failed_file(path_buf);
+                    emit(ScanEvent::Error(ScanIssue::new(path_buf, kind, e)));
                 }
             }
         }
 
         info!("Scan completed: {} tracks successfully extracted", tracks.len());
-        if let Some(win) = window {
-            let _ = win.emit("scan-complete", tracks.len());
+        if !analysis_follows {
+            emit(ScanEvent::Complete(ScanComplete { track_count: tracks.len() }));
         }
 
         Ok(tracks)
     }
 
+    /// Store freshly read `tracks` with `persist`, import their embedded R128
+    /// gains, then analyze them when `analyze` is set. `persist` returns the
+    /// id each track is stored under, which replaces the scanned id when the
+    /// path was already in the library under another one. Reading held
+    /// `scan-complete` back for the analysis, so when persisting fails it is
+    /// sent here instead and the scan still ends.
+    pub fn persist_and_analyze<E>(
+        db: &Database,
+        tracks: &mut [Track],
+        events: Option<&dyn ScanEventSink>,
+        cancel_flag: &Option<Arc<AtomicBool>>,
+        analyze: bool,
+        persist: impl FnOnce(&[Track]) -> Result<Vec<String>, E>,
+    ) -> Result<(), E> {
+        match persist(tracks) {
+            Ok(ids) => {
+                for (track, id) in tracks.iter_mut().zip(ids) {
+                    track.id = id;
+                }
+            }
+            Err(e) => {
+                if let (true, Some(sink)) = (analyze, events) {
+                    sink.emit_scan_event(ScanEvent::Complete(ScanComplete { track_count: tracks.len() }));
+                }
+                return Err(e);
+            }
+        }
+        r128_gain::import_scanned_gains(db, tracks);
+        if analyze {
+            Self::analyze_gains(db, tracks, events, cancel_flag);
+        }
+        Ok(())
+    }
+
+    /// Measure and store ReplayGain for stored `tracks` at the configured
+    /// target, decoding several files at once. Emits `scan-progress` in the
+    /// analyzing phase, then `scan-complete` (or `scan-cancelled`). Returns
+    /// how many tracks got a gain; files that fail to decode are skipped.
+    fn analyze_gains(
+        db: &Database,
+        tracks: &[Track],
+        events: Option<&dyn ScanEventSink>,
+        cancel_flag: &Option<Arc<AtomicBool>>,
+    ) -> usize {
+        let emit = |event: ScanEvent| {
+            if let Some(sink) = events {
+                sink.emit_scan_event(event);
+            }
+        };
+        let is_cancelled = || cancel_flag.as_ref().is_some_and(|f| f.load(Ordering::Relaxed));
+        // A scan cancelled while reading has already said so.
+        if is_cancelled() {
+            return 0;
+        }
+
+        let target_lufs = replaygain::get_target_lufs(db);
+        let total = tracks.len();
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .clamp(1, MAX_ANALYSIS_WORKERS)
+            .min(total.max(1));
+        info!("Analyzing ReplayGain for {} scanned tracks on {} workers", total, workers);
+
+        let mut throttle = ProgressThrottle::new(ScanProgressThrottle::load(db));
+        let next = AtomicUsize::new(0);
+        let mut analyzed = 0;
+        let mut processed = 0;
+        std::thread::scope(|scope| {
+            let (tx, rx) = std::sync::mpsc::channel::<(&Track, Result<ReplayGainData, String>)>();
+            for _ in 0..workers {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    if is_cancelled() {
+                        break;
+                    }
+                    let Some(track) = tracks.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    if tx.send((track, replaygain::analyze_track(&track.path, target_lufs))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(tx);
+
+            // Results are stored and reported from this thread only.
+            for (track, result) in rx {
+                processed += 1;
+                match result {
+                    Ok(data) => match replaygain::store_replaygain(&db.conn, &track.path, &data) {
+                        Ok(()) => analyzed += 1,
+                        Err(e) => warn!("Failed to store ReplayGain for {}: {}", track.path, e),
+                    },
+                    Err(e) => warn!("ReplayGain analysis failed for {}: {}", track.path, e),
+                }
+                if events.is_some() && throttle.should_emit(processed, total, std::time::Instant::now()) {
+                    emit(ScanEvent::Progress(ScanProgress {
+                        phase: ScanPhase::Analyzing,
+                        current: processed,
+                        total,
+                        current_file: track.name.clone(),
+                        path: track.path.clone(),
+                    }));
+                }
+            }
+        });
+
+        if is_cancelled() {
+            warn!("ReplayGain analysis cancelled after {} of {} tracks", processed, total);
+            emit(ScanEvent::Cancelled(ScanCancelled { processed }));
+        } else {
+            info!("ReplayGain analysed for {} of {} scanned tracks", analyzed, total);
+            emit(ScanEvent::Complete(ScanComplete { track_count: total }));
+        }
+        analyzed
+    }
+
     /// Perform incremental scan: only process new or modified files
-    pub fn scan_directory_incremental(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: &Database) -> Result<Vec<Track>, String> {
+    pub fn scan_directory_incremental(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: &Database, options: &ScanOptions) -> Result<Vec<Track>, String> {
+        Self::scan_incremental(path, window, cancel_flag, db, options).map(|(tracks, _)| tracks)
+    }
+
+    /// [`scan_directory_incremental`](Self::scan_directory_incremental), also
+    /// returning the walk errors.
+    fn scan_incremental(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: &Database, options: &ScanOptions) -> Result<(Vec<Track>, Vec<String>), String> {
         info!("Starting incremental directory scan: {}", path);
 
         // Check for cancellation before starting
         if let Some(flag) = &cancel_flag {
             if flag.load(Ordering::Relaxed) {
                 warn!("Incremental scan cancelled before starting");
-                return Ok(Vec::new());
+                return Ok((Vec::new(), Vec::new()));
             }
         }
 
@@ -219,8 +555,9 @@ impl Scanner {
             .collect();
 
         // Collect all audio files and filter to only new/modified
-        let all_files = Self::collect_audio_files(path);
-        let files_to_scan: Vec<std::path::PathBuf> = all_files
+        let collected = Self::collect_audio_files(path, options, &ignored_roots(Some(db)))?;
+        let files_to_scan: Vec<std::path::PathBuf> = collected
+            .files
             .into_iter()
             .filter(|path_buf| {
                 let path_str = path_buf.to_string_lossy().to_string();
@@ -243,10 +580,77 @@ impl Scanner {
 
         info!("Incremental scan: {} files need processing (new or modified)", files_to_scan.len());
 
-        Self::process_files(&files_to_scan, window, &cancel_flag, Some(db))
+        let tracks = Self::scan_files(
+            &files_to_scan,
+            window.map(|w| w as &dyn ScanEventSink),
+            &cancel_flag,
+            Some(db),
+            options.analyze_replaygain,
+        )?;
+        Ok((tracks, collected.walk_errors))
     }
 
-    pub fn scan_directory(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: Option<&Database>) -> Result<Vec<Track>, String> {
+    /// Bring the tracks under `path` up to date with the disk: scan new and
+    /// modified files, persist them, and drop tracks whose file is gone.
+    /// Only tracks are touched; the caller owns the folder row. Removals are
+    /// only proposed, not applied, when part of the folder couldn't be read
+    /// or the folder is missing or empty.
+    pub fn rescan_folder(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: &Database) -> Result<FolderRescanDiff, String> {
+        let known = db.get_folder_tracks(path)
+            .map_err(|e| format!("Failed to get existing tracks: {}", e))?;
+        let known_paths: HashSet<&str> = known.iter().map(|(_, path, _)| path.as_str()).collect();
+
+        let options = ScanOptions::load_for_folder(db, path);
+        let (mut tracks, walk_errors) = Self::scan_incremental(path, window, cancel_flag.clone(), db, &options)?;
+        Self::persist_and_analyze(
+            db,
+            &mut tracks,
+            window.map(|w| w as &dyn ScanEventSink),
+            &cancel_flag,
+            options.analyze_replaygain,
+            |tracks| {
+                let batch: Vec<(Track, i64)> = tracks
+                    .iter()
+                    .map(|track| (track.clone(), fingerprint::file_mtime(&track.path).unwrap_or(0)))
+                    .collect();
+                db.add_tracks_incremental_batch(&batch)
+                    .map_err(|e| format!("Failed to persist rescanned tracks: {}", e))
+            },
+        )?;
+
+        let mut diff = FolderRescanDiff::default();
+        for track in &tracks {
+            if known_paths.contains(track.path.as_str()) {
+                diff.updated.push(track.id.clone());
+            } else {
+                diff.added.push(track.id.clone());
+            }
+        }
+        diff.removed = known
+            .iter()
+            .filter(|(_, path, _)| !Path::new(path).exists())
+            .map(|(id, _, _)| id.clone())
+            .collect();
+        let root_empty = std::fs::read_dir(path).map_or(true, |mut entries| entries.next().is_none());
+        if !diff.removed.is_empty() && (root_empty || !walk_errors.is_empty()) {
+            warn!(
+                "Rescan of {} could not read the whole folder ({} errors, empty: {}); not removing {} tracks",
+                path, walk_errors.len(), root_empty, diff.removed.len()
+            );
+            diff.removal_withheld = true;
+        } else {
+            db.remove_tracks(&diff.removed)
+                .map_err(|e| format!("Failed to remove deleted tracks: {}", e))?;
+        }
+
+        info!(
+            "Rescanned {}: {} added, {} updated, {} removed",
+            path, diff.added.len(), diff.updated.len(), diff.removed.len()
+        );
+        Ok(diff)
+    }
+
+    pub fn scan_directory(path: &str, window: Option<&Window>, cancel_flag: Option<Arc<AtomicBool>>, db: Option<&Database>, options: &ScanOptions) -> Result<Vec<Track>, String> {
         info!("Starting directory scan: {}", path);
 
         // Check for cancellation before starting
@@ -257,22 +661,39 @@ impl Scanner {
             }
         }
 
-        let files = Self::collect_audio_files(path);
+        let files = Self::collect_audio_files(path, options, &ignored_roots(db))?.files;
         info!("Found {} audio files to scan", files.len());
 
-        Self::process_files(&files, window, &cancel_flag, db)
+        Self::scan_files(
+            &files,
+            window.map(|w| w as &dyn ScanEventSink),
+            &cancel_flag,
+            db,
+            options.analyze_replaygain,
+        )
     }
     
     pub fn extract_track_info(path: &Path) -> Result<Track, String> {
         use lofty::{Probe, Accessor, AudioFile};
+
+        // Video containers must carry an audio track; lofty can't read tags
+        // from every one of them (e.g. Matroska), so they may have none.
+        let container_audio = if media_container::is_video_container(path) {
+            Some(media_container::probe_audio_track(path)?)
+        } else {
+            None
+        };
+
+        let tagged_file = match Probe::open(path).and_then(|probe| probe.read()) {
+            Ok(file) => Some(file),
+            Err(e) if container_audio.is_some() => {
+                info!("No tags readable from {:?}: {}", path, e);
+                None
+            }
+            Err(e) => return Err(e.to_string()),
+        };
         
-        let tagged_file = Probe::open(path)
-            .map_err(|e| e.to_string())?
-            .read()
-            .map_err(|e| e.to_string())?;
-        
-        let tags = tagged_file.primary_tag()
-            .or_else(|| tagged_file.first_tag());
+        let tags = tagged_file.as_ref().and_then(|f| f.primary_tag().or_else(|| f.first_tag()));
         
         let title = tags.and_then(|t| t.title().map(|s| s.to_string()));
         let artist = tags.and_then(|t| t.artist().map(|s| s.to_string()));
@@ -282,7 +703,12 @@ impl Scanner {
         let track_number = tags.and_then(|t| t.track()).map(|n| n as i32);
         let disc_number = tags.and_then(|t| t.disk()).map(|n| n as i32);
         
-        let duration = tagged_file.properties().duration().as_secs_f64();
+        let duration = tagged_file
+            .as_ref()
+            .map(|f| f.properties().duration().as_secs_f64())
+            .filter(|d| *d > 0.0)
+            .or_else(|| container_audio.and_then(|a| a.duration))
+            .unwrap_or(0.0);
         
         let file_name = path.file_name()
             .and_then(|n| n.to_str())
@@ -349,6 +775,43 @@ mod tests {
         ))
     }
 
+    #[test]
+    fn from_row_maps_every_selected_column() {
+        assert_eq!(TRACK_SELECT_COLUMNS.split(',').count(), TRACK_SELECT_COLUMN_COUNT);
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        let track = conn
+            .query_row(
+                "SELECT 'id1', 'C:/a.mp3', 'a.mp3', 'Title', 'Artist', 'Album', 'Rock', 2001, 3, 2, 181.5, 1000, 4, 7, 99",
+                [],
+                Track::from_row,
+            )
+            .unwrap();
+        assert_eq!((track.id.as_str(), track.path.as_str(), track.name.as_str()), ("id1", "C:/a.mp3", "a.mp3"));
+        assert_eq!(track.title.as_deref(), Some("Title"));
+        assert_eq!(track.artist.as_deref(), Some("Artist"));
+        assert_eq!(track.album.as_deref(), Some("Album"));
+        assert_eq!(track.genre.as_deref(), Some("Rock"));
+        assert_eq!((track.year, track.track_number, track.disc_number), (Some(2001), Some(3), Some(2)));
+        assert_eq!((track.duration, track.date_added), (181.5, 1000));
+        assert_eq!((track.rating, track.play_count, track.last_played), (4, 7, 99));
+    }
+
+    #[test]
+    fn from_row_tolerates_nulls() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let track = conn
+            .query_row(
+                "SELECT 'id1', 'C:/a.mp3', 'a.mp3', NULL, NULL, NULL, NULL, NULL, NULL, NULL, 0.0, 0, NULL, NULL, NULL",
+                [],
+                Track::from_row,
+            )
+            .unwrap();
+        assert!(track.title.is_none() && track.artist.is_none() && track.album.is_none() && track.genre.is_none());
+        assert!(track.year.is_none() && track.track_number.is_none() && track.disc_number.is_none());
+        assert_eq!((track.rating, track.play_count, track.last_played), (0, 0, 0));
+    }
+
     #[test]
     fn collect_audio_files_filters_by_supported_extensions_case_insensitive() {
         let dir = temp_scan_dir("extensions");
@@ -364,7 +827,9 @@ mod tests {
         fs::write(&txt, b"dummy").expect("write txt placeholder failed");
         fs::write(&no_ext, b"dummy").expect("write no-ext placeholder failed");
 
-        let mut files = Scanner::collect_audio_files(&dir.to_string_lossy());
+        let mut files = Scanner::collect_audio_files(&dir.to_string_lossy(), &ScanOptions::default(), &[])
+            .expect("collect should succeed")
+            .files;
         files.sort();
 
         assert_eq!(files.len(), 2);
@@ -391,6 +856,7 @@ mod tests {
             None,
             Some(cancel_flag),
             None,
+            &ScanOptions::default(),
         )
         .expect("scan_directory should return Ok when pre-cancelled");
 
@@ -399,4 +865,101 @@ mod tests {
         let _ = fs::remove_file(candidate);
         let _ = fs::remove_dir_all(dir);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn max_depth_limits_traversal() {
+        let dir = temp_scan_dir("max_depth");
+        let deep = dir.join("artist").join("album");
+        fs::create_dir_all(&deep).expect("create nested dirs failed");
+        fs::write(dir.join("root.mp3"), b"dummy").expect("write failed");
+        fs::write(dir.join("artist").join("single.mp3"), b"dummy").expect("write failed");
+        fs::write(deep.join("track.mp3"), b"dummy").expect("write failed");
+
+        let count = |max_depth| {
+            let options = ScanOptions { max_depth, ..ScanOptions::default() };
+            Scanner::collect_audio_files(&dir.to_string_lossy(), &options, &[])
+                .expect("collect should succeed")
+                .files
+                .len()
+        };
+
+        assert_eq!(count(Some(1)), 1);
+        assert_eq!(count(Some(2)), 2);
+        assert_eq!(count(None), 3);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn ignore_globs_exclude_subfolder_and_files() {
+        let dir = temp_scan_dir("ignore");
+        let podcasts = dir.join("Podcasts").join("Show");
+        let live = dir.join("Live");
+        fs::create_dir_all(&podcasts).expect("create dirs failed");
+        fs::create_dir_all(&live).expect("create dirs failed");
+        fs::write(podcasts.join("episode.mp3"), b"dummy").expect("write failed");
+        fs::write(live.join("set.flac"), b"dummy").expect("write failed");
+        fs::write(live.join("set.partial.flac"), b"dummy").expect("write failed");
+        fs::write(dir.join("keep.ogg"), b"dummy").expect("write failed");
+
+        // Name patterns: a whole subfolder and a file pattern.
+        let options = ScanOptions {
+            ignore_globs: vec!["Podcasts".into(), "*.partial.*".into()],
+            ..ScanOptions::default()
+        };
+        let mut files = Scanner::collect_audio_files(&dir.to_string_lossy(), &options, &[])
+            .expect("collect should succeed")
+            .files;
+        files.sort();
+        let mut expected = vec![live.join("set.flac"), dir.join("keep.ogg")];
+        expected.sort();
+        assert_eq!(files, expected);
+
+        // Root-relative pattern, combined with a custom extension list.
+        let options = ScanOptions {
+            ignore_globs: vec!["Live/**".into()],
+            extensions: vec![".OGG".into(), "mp3".into()],
+            ..ScanOptions::default()
+        };
+        let mut files = Scanner::collect_audio_files(&dir.to_string_lossy(), &options, &[])
+            .expect("collect should succeed")
+            .files;
+        files.sort();
+        let mut expected = vec![podcasts.join("episode.mp3"), dir.join("keep.ogg")];
+        expected.sort();
+        assert_eq!(files, expected);
+
+        let bad = ScanOptions { ignore_globs: vec!["[".into()], ..ScanOptions::default() };
+        assert!(Scanner::collect_audio_files(&dir.to_string_lossy(), &bad, &[]).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn self_referential_symlink_does_not_hang_scan() {
+        let dir = temp_scan_dir("symlink_cycle");
+        let album = dir.join("album");
+        fs::create_dir_all(&album).expect("create dirs failed");
+        fs::write(album.join("track.mp3"), b"dummy").expect("write failed");
+        // album/loop -> .. (the scan root) and a second link to the same album.
+        std::os::unix::fs::symlink(&dir, album.join("loop")).expect("symlink failed");
+        std::os::unix::fs::symlink(&album, dir.join("album-again")).expect("symlink failed");
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let root = dir.to_string_lossy().to_string();
+        std::thread::spawn(move || {
+            let files = Scanner::collect_audio_files(&root, &ScanOptions::default(), &[]);
+            let _ = tx.send(files);
+        });
+        let files = rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("scan should terminate despite the symlink cycle")
+            .expect("collect should succeed")
+            .files;
+
+        assert_eq!(files.len(), 1, "each real file is collected once: {:?}", files);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,160 @@
+//! Global shortcut bindings.
+//!
+//! Each binding maps a player action (the payload of the `global-shortcut`
+//! event) to an accelerator string such as `Ctrl+Alt+Right`. Defaults are the
+//! media keys; once the user customizes anything, the full mapping is saved
+//! in the settings table and replaces the defaults on the next start.
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+use log::{info, warn};
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+const SETTINGS_KEY: &str = "global_shortcuts";
+
+/// Actions that can be bound, with their default accelerators.
+pub const DEFAULT_SHORTCUTS: [(&str, &str); 7] = [
+    ("play-pause", "MediaPlayPause"),
+    ("next-track", "MediaTrackNext"),
+    ("prev-track", "MediaTrackPrevious"),
+    ("stop", "MediaStop"),
+    ("volume-up", "VolumeUp"),
+    ("volume-down", "VolumeDown"),
+    ("mute", "VolumeMute"),
+];
+
+const MODIFIERS: &[&str] = &[
+    "ctrl", "control", "alt", "option", "shift", "super", "meta", "cmd", "command",
+    "cmdorctrl", "cmdorcontrol", "commandorctrl", "commandorcontrol",
+];
+
+pub fn validate_action(action: &str) -> Result<(), String> {
+    if DEFAULT_SHORTCUTS.iter().any(|(a, _)| *a == action) {
+        Ok(())
+    } else {
+        Err(format!("Unknown shortcut action: {}", action))
+    }
+}
+
+/// Structural check of an accelerator: zero or more distinct modifiers
+/// followed by exactly one non-modifier key, joined with `+`. Returns the
+/// trimmed accelerator. Whether the key name itself exists is left to the
+/// plugin's parser.
+pub fn validate_accelerator(accelerator: &str) -> Result<String, String> {
+    let parts: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(format!("Invalid accelerator '{}': empty key segment", accelerator));
+    }
+
+    let (key, modifiers) = parts.split_last().ok_or_else(|| "Accelerator cannot be empty".to_string())?;
+    if MODIFIERS.contains(&key.to_ascii_lowercase().as_str()) {
+        return Err(format!("Invalid accelerator '{}': missing a non-modifier key", accelerator));
+    }
+
+    let mut seen = Vec::with_capacity(modifiers.len());
+    for modifier in modifiers {
+        let lower = modifier.to_ascii_lowercase();
+        if !MODIFIERS.contains(&lower.as_str()) {
+            return Err(format!("Invalid accelerator '{}': '{}' is not a modifier", accelerator, modifier));
+        }
+        if seen.contains(&lower) {
+            return Err(format!("Invalid accelerator '{}': duplicate modifier '{}'", accelerator, modifier));
+        }
+        seen.push(lower);
+    }
+
+    Ok(parts.join("+"))
+}
+
+/// Validate and parse an accelerator into a plugin `Shortcut`.
+pub fn parse_accelerator(accelerator: &str) -> AppResult<Shortcut> {
+    let normalized = validate_accelerator(accelerator).map_err(AppError::Validation)?;
+    normalized
+        .parse::<Shortcut>()
+        .map_err(|e| AppError::Validation(format!("Invalid accelerator '{}': {}", accelerator, e)))
+}
+
+/// Saved bindings, or the media-key defaults if the user never customized them.
+pub fn load_bindings(db: &Database) -> HashMap<String, String> {
+    match db.get_json_setting::<HashMap<String, String>>(SETTINGS_KEY) {
+        Ok(Some(saved)) => saved
+            .into_iter()
+            .filter(|(action, _)| validate_action(action).is_ok())
+            .collect(),
+        Ok(None) => default_bindings(),
+        Err(e) => {
+            warn!("Failed to load shortcut bindings, using defaults: {}", e);
+            default_bindings()
+        }
+    }
+}
+
+pub fn save_bindings(db: &Database, bindings: &HashMap<String, String>) -> AppResult<()> {
+    db.set_json_setting(SETTINGS_KEY, bindings)
+        .map_err(|e| AppError::Database(format!("Failed to save shortcuts: {}", e)))
+}
+
+fn default_bindings() -> HashMap<String, String> {
+    DEFAULT_SHORTCUTS
+        .iter()
+        .map(|(action, accel)| (action.to_string(), accel.to_string()))
+        .collect()
+}
+
+/// Register `shortcut` so that pressing it emits `action` on `global-shortcut`.
+pub fn bind(app: &AppHandle, action: &str, shortcut: Shortcut) -> AppResult<()> {
+    let app_handle = app.clone();
+    let payload = action.to_string();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, _event| {
+            let _ = app_handle.emit("global-shortcut", payload.as_str());
+        })
+        .map_err(|e| AppError::InvalidState(format!("Failed to register shortcut for {}: {}", action, e)))
+}
+
+pub fn unbind(app: &AppHandle, accelerator: &str) -> AppResult<()> {
+    let shortcut = parse_accelerator(accelerator)?;
+    app.global_shortcut()
+        .unregister(shortcut)
+        .map_err(|e| AppError::InvalidState(format!("Failed to unregister shortcut {}: {}", accelerator, e)))
+}
+
+/// Bind every entry of `bindings` at startup. Failures are logged, not fatal.
+pub fn bind_all(app: &AppHandle, bindings: &HashMap<String, String>) {
+    for (action, accelerator) in bindings {
+        match parse_accelerator(accelerator).and_then(|shortcut| bind(app, action, shortcut)) {
+            Ok(()) => info!("Global shortcut {} -> {}", accelerator, action),
+            Err(e) => warn!("Skipping global shortcut {} for {}: {}", accelerator, action, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_modifier_combinations_and_bare_keys() {
+        assert_eq!(validate_accelerator("Ctrl+Alt+Right").unwrap(), "Ctrl+Alt+Right");
+        assert_eq!(validate_accelerator(" CmdOrCtrl + Shift + P ").unwrap(), "CmdOrCtrl+Shift+P");
+        assert_eq!(validate_accelerator("MediaPlayPause").unwrap(), "MediaPlayPause");
+    }
+
+    #[test]
+    fn rejects_malformed_accelerators() {
+        assert!(validate_accelerator("").is_err());
+        assert!(validate_accelerator("Ctrl+").is_err());
+        assert!(validate_accelerator("Ctrl+Alt").is_err(), "modifier-only");
+        assert!(validate_accelerator("Ctrl+Ctrl+K").is_err(), "duplicate modifier");
+        assert!(validate_accelerator("Banana+K").is_err(), "unknown modifier");
+        assert!(validate_accelerator("Ctrl+A+B").is_err(), "two keys");
+    }
+
+    #[test]
+    fn only_known_actions_are_bindable() {
+        assert!(validate_action("next-track").is_ok());
+        assert!(validate_action("launch-missiles").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! "More like this": tracks similar to a seed track.
+//!
+//! Every other track is scored on what it shares with the seed: the same
+//! artist, the same genre, a nearby tempo and a harmonically compatible key.
+//! Tempo and key only count when both tracks have been analysed, so an
+//! unanalysed library still gets artist and genre matches.
+
+use rusqlite::{params, OptionalExtension, Result};
+
+use crate::database::Database;
+use crate::key_detection::Key;
+use crate::scanner::{Track, TRACK_SELECT_COLUMNS, TRACK_SELECT_COLUMN_COUNT};
+
+const ARTIST_WEIGHT: f64 = 3.0;
+const GENRE_WEIGHT: f64 = 2.0;
+const BPM_WEIGHT: f64 = 1.5;
+const KEY_WEIGHT: f64 = 1.0;
+
+/// Tempo difference (BPM) at which the tempo score drops to zero.
+const BPM_WINDOW: f64 = 20.0;
+
+/// What a track is compared on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimilarityFeatures {
+    pub artist: Option<String>,
+    pub genre: Option<String>,
+    pub bpm: Option<f64>,
+    pub key: Option<Key>,
+}
+
+impl SimilarityFeatures {
+    fn from_row(track: &Track, bpm: Option<f64>, key: Option<String>) -> Self {
+        Self {
+            artist: track.artist.clone(),
+            genre: track.genre.clone(),
+            bpm: bpm.filter(|b| *b > 0.0),
+            key: key.as_deref().and_then(Key::from_name),
+        }
+    }
+}
+
+fn same_text(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a.as_deref().map(str::trim), b.as_deref().map(str::trim)) {
+        (Some(a), Some(b)) => !a.is_empty() && a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+/// How similar `other` is to `seed`; 0 when they share nothing.
+pub fn similarity_score(seed: &SimilarityFeatures, other: &SimilarityFeatures) -> f64 {
+    let mut score = 0.0;
+    if same_text(&seed.artist, &other.artist) {
+        score += ARTIST_WEIGHT;
+    }
+    if same_text(&seed.genre, &other.genre) {
+        score += GENRE_WEIGHT;
+    }
+    if let (Some(a), Some(b)) = (seed.bpm, other.bpm) {
+        score += BPM_WEIGHT * (1.0 - (a - b).abs() / BPM_WINDOW).max(0.0);
+    }
+    if let (Some(a), Some(b)) = (seed.key, other.key) {
+        if a.is_compatible(&b) {
+            score += KEY_WEIGHT;
+        }
+    }
+    score
+}
+
+/// Up to `limit` tracks most similar to `track_id`, best first, never
+/// including the seed or tracks sharing nothing with it. `None` when the
+/// seed track doesn't exist.
+pub fn get_similar_tracks(db: &Database, track_id: &str, limit: usize) -> Result<Option<Vec<Track>>> {
+    let conn = db.read_conn();
+    let select = format!("SELECT {}, bpm, musical_key FROM tracks", TRACK_SELECT_COLUMNS);
+    let read = |row: &rusqlite::Row| -> Result<(Track, SimilarityFeatures)> {
+        let track = Track::from_row(row)?;
+        let features = SimilarityFeatures::from_row(
+            &track,
+            row.get(TRACK_SELECT_COLUMN_COUNT)?,
+            row.get(TRACK_SELECT_COLUMN_COUNT + 1)?,
+        );
+        Ok((track, features))
+    };
+
+    let seed = conn
+        .query_row(&format!("{} WHERE id = ?1", select), params![track_id], read)
+        .optional()?;
+    let Some((_, seed)) = seed else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(&format!("{} WHERE id != ?1", select))?;
+    let mut scored = stmt
+        .query_map(params![track_id], read)?
+        .map(|row| row.map(|(track, features)| (similarity_score(&seed, &features), track)))
+        .collect::<Result<Vec<_>>>()?;
+
+    scored.retain(|(score, _)| *score > 0.0);
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+    Ok(Some(scored.into_iter().take(limit).map(|(_, track)| track).collect()))
+}
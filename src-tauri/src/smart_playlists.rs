@@ -6,14 +6,15 @@ use serde::{Deserialize, Serialize};
 /// This whitelist prevents SQL injection through the `field` parameter.
 const ALLOWED_FIELDS: &[&str] = &[
     "title", "artist", "album", "genre", "year", "track_number", "disc_number",
-    "duration", "rating", "play_count", "last_played", "date_added", "name", "path",
-    "track_gain", "track_peak", "loudness", "file_modified",
+    "duration", "rating", "play_count", "skip_count", "last_played", "date_added", "name", "path",
+    "track_gain", "track_peak", "loudness", "file_modified", "bpm", "musical_key",
 ];
 
 /// Allowed column names for ORDER BY clauses.
 const ALLOWED_SORT_FIELDS: &[&str] = &[
     "title", "artist", "album", "genre", "year", "track_number", "disc_number",
-    "duration", "rating", "play_count", "last_played", "date_added", "name", "path",
+    "duration", "rating", "play_count", "skip_count", "last_played", "date_added", "name", "path",
+    "bpm", "musical_key",
 ];
 
 /// Validate that a field name is an allowed column. Returns an error if not.
@@ -60,10 +61,32 @@ pub struct SmartPlaylist {
     pub created_at: i64,
 }
 
+/// Operators that test playlist membership instead of a track column. They
+/// ignore `field`; `value` is a playlist id, or empty for "any playlist".
+const MEMBERSHIP_OPERATORS: &[&str] = &["in_playlist", "not_in_playlist"];
+
+/// Build an EXISTS / NOT EXISTS condition over `playlist_tracks` for a
+/// membership rule, pushing the playlist id as a parameter.
+fn membership_condition(rule: &Rule, sql_params: &mut Vec<Value>) -> Result<String> {
+    let negate = match rule.operator.as_str() {
+        "in_playlist" => "",
+        "not_in_playlist" => "NOT ",
+        _ => return Err(rusqlite::Error::InvalidQuery),
+    };
+    let playlist_id = rule.value.trim();
+    let subquery = if playlist_id.is_empty() {
+        "SELECT 1 FROM playlist_tracks pt WHERE pt.track_id = tracks.id".to_string()
+    } else {
+        sql_params.push(Value::Text(playlist_id.to_string()));
+        "SELECT 1 FROM playlist_tracks pt WHERE pt.track_id = tracks.id AND pt.playlist_id = ?".to_string()
+    };
+    Ok(format!("{}EXISTS ({})", negate, subquery))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
     pub field: String,      // "artist", "album", "genre", "rating", "play_count", "duration", etc.
-    pub operator: String,   // "equals", "contains", "greater_than", "less_than", "between", "in_last", etc.
+    pub operator: String,   // "equals", "contains", "greater_than", "less_than", "between", "in_last", "in_playlist", etc.
     pub value: String,      // The comparison value(s)
 }
 
@@ -75,6 +98,10 @@ impl SmartPlaylist {
         let mut sql_params: Vec<Value> = Vec::new();
         
         for rule in &self.rules {
+            if MEMBERSHIP_OPERATORS.contains(&rule.operator.as_str()) {
+                conditions.push(membership_condition(rule, &mut sql_params)?);
+                continue;
+            }
             validate_field(&rule.field)?;
             
             let condition = match rule.operator.as_str() {
@@ -0,0 +1,97 @@
+//! "Smart shuffle": a random queue biased toward favourites.
+//!
+//! Each track gets a score from its rating, play count and how long ago it
+//! was last played; tracks are then drawn without replacement with
+//! probability proportional to their score. Every track keeps a small base
+//! score so unrated, never-played tracks still turn up.
+
+use serde::{Deserialize, Serialize};
+
+use crate::scanner::Track;
+
+/// Score every track has before its rating, plays and recency are added.
+const BASE_SCORE: f64 = 0.1;
+
+/// Days after which a played track counts as "not recently played".
+const RECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// How much each factor contributes to a track's score. Zero turns a factor
+/// off; all zero gives a plain shuffle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShuffleWeights {
+    pub rating: f64,
+    pub play_count: f64,
+    pub recency: f64,
+}
+
+impl Default for ShuffleWeights {
+    fn default() -> Self {
+        Self { rating: 1.0, play_count: 0.5, recency: 1.0 }
+    }
+}
+
+/// Score of `track` at `now` (UNIX millis). `max_play_count` is the highest
+/// play count in the pool, so play counts are judged relative to the library.
+pub fn track_score(track: &Track, weights: &ShuffleWeights, max_play_count: i32, now: i64) -> f64 {
+    let rating = track.rating.clamp(0, 5) as f64 / 5.0;
+
+    let plays = if max_play_count > 0 {
+        (1.0 + track.play_count.max(0) as f64).ln() / (1.0 + max_play_count as f64).ln()
+    } else {
+        0.0
+    };
+
+    // 0 right after a play, approaching 1 as the track goes unplayed.
+    let staleness = if track.last_played > 0 {
+        let days = (now - track.last_played).max(0) as f64 / MILLIS_PER_DAY;
+        1.0 - 0.5_f64.powf(days / RECENCY_HALF_LIFE_DAYS)
+    } else {
+        1.0
+    };
+
+    BASE_SCORE
+        + weights.rating.max(0.0) * rating
+        + weights.play_count.max(0.0) * plays
+        + weights.recency.max(0.0) * staleness
+}
+
+/// Up to `size` tracks from `tracks` in weighted random order. The same
+/// `seed` always yields the same queue.
+pub fn build_smart_queue(tracks: &[Track], size: usize, weights: &ShuffleWeights, now: i64, seed: u64) -> Vec<Track> {
+    let max_play_count = tracks.iter().map(|t| t.play_count).max().unwrap_or(0);
+    let mut rng = SplitMix64(seed);
+
+    // Weighted sampling without replacement (Efraimidis–Spirakis): order by
+    // u^(1/score) for uniform u, highest first.
+    let mut keyed: Vec<(f64, &Track)> = tracks
+        .iter()
+        .map(|track| {
+            let score = track_score(track, weights, max_play_count, now);
+            (rng.next_f64().powf(1.0 / score), track)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.into_iter().take(size).map(|(_, track)| track.clone()).collect()
+}
+
+/// Small seedable generator; enough for shuffling and keeps the queue
+/// reproducible without an extra dependency.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in (0, 1].
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,52 @@
+//! Startup window visibility ("start minimized to tray").
+//!
+//! The switch is persisted in the settings table and read once in `setup()`.
+//! Hiding goes through [`StartupWindow`] so the decision can be tested
+//! without a real webview.
+
+use crate::database::Database;
+use log::{info, warn};
+
+/// Settings key under which the "start minimized to tray" switch is persisted.
+pub const START_MINIMIZED_KEY: &str = "start_minimized";
+
+/// The part of the main window that startup needs.
+pub trait StartupWindow {
+    fn hide(&self) -> Result<(), String>;
+}
+
+impl<R: tauri::Runtime> StartupWindow for tauri::WebviewWindow<R> {
+    fn hide(&self) -> Result<(), String> {
+        tauri::WebviewWindow::hide(self).map_err(|e| e.to_string())
+    }
+}
+
+/// Whether the app should start hidden in the tray. Off when unset or when
+/// the setting can't be read.
+pub fn start_minimized(db: &Database) -> bool {
+    match db.get_json_setting::<bool>(START_MINIMIZED_KEY) {
+        Ok(value) => value.unwrap_or(false),
+        Err(e) => {
+            warn!("Failed to read start minimized setting: {}", e);
+            false
+        }
+    }
+}
+
+/// Hide `window` when the app is set to start minimized. Returns whether the
+/// window was hidden.
+pub fn apply_start_minimized(db: &Database, window: &impl StartupWindow) -> bool {
+    if !start_minimized(db) {
+        return false;
+    }
+    match window.hide() {
+        Ok(()) => {
+            info!("Starting minimized to tray");
+            true
+        }
+        Err(e) => {
+            warn!("Failed to hide main window on startup: {}", e);
+            false
+        }
+    }
+}
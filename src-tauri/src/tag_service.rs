@@ -1,4 +1,11 @@
-use lofty::{Accessor, ItemKey, Probe, TagExt, TaggedFileExt};
+use crate::database::{Database, TrackMetadataPatch};
+use lofty::{
+    Accessor, ItemKey, ItemValue, MimeType, Picture, PictureType, Probe, Tag, TagExt, TagItem,
+    TagType, TaggedFile, TaggedFileExt,
+};
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs::OpenOptions;
 
 #[derive(Debug, Clone)]
@@ -13,18 +20,132 @@ pub struct TagUpdateInput {
     pub disc_number: Option<String>,
 }
 
-/// Read, mutate, and persist audio file tags using lofty.
-pub fn apply_tags_to_file(track_path: &str, update: &TagUpdateInput) -> Result<(), String> {
-    let tagged_file = Probe::open(track_path)
+impl TagUpdateInput {
+    /// The library columns this update touches; unparsable numbers are skipped.
+    pub fn to_metadata_patch(&self) -> TrackMetadataPatch {
+        TrackMetadataPatch {
+            title: self.title.clone(),
+            artist: self.artist.clone(),
+            album: self.album.clone(),
+            genre: self.genre.clone(),
+            year: self.year.as_ref().and_then(|y| y.parse().ok()),
+            track_number: self.track_number.as_ref().and_then(|t| t.parse().ok()),
+            disc_number: self.disc_number.as_ref().and_then(|d| d.parse().ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTagFailure {
+    /// File path, or the track id when the track isn't in the library.
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkTagResult {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BulkTagFailure>,
+}
+
+/// Apply the set fields of `update` to every track's file and library row.
+/// A failure on one track is recorded and the rest still get updated.
+pub fn apply_tags_bulk(db: &Database, track_ids: &[String], update: &TagUpdateInput) -> BulkTagResult {
+    let patch = update.to_metadata_patch();
+    let mut result = BulkTagResult::default();
+
+    for track_id in track_ids {
+        let path = match db.get_track_by_id(track_id) {
+            Ok(Some(track)) => track.path,
+            Ok(None) => {
+                result.failed.push(BulkTagFailure {
+                    path: track_id.clone(),
+                    error: "Track not found in library".to_string(),
+                });
+                continue;
+            }
+            Err(e) => {
+                result.failed.push(BulkTagFailure { path: track_id.clone(), error: e.to_string() });
+                continue;
+            }
+        };
+
+        let outcome = apply_tags_to_file(&path, update).and_then(|_| {
+            db.patch_track_metadata(track_id, &patch)
+                .map_err(|e| format!("Tags written but database update failed: {}", e))
+        });
+        match outcome {
+            Ok(()) => result.succeeded.push(path),
+            Err(error) => {
+                warn!("Bulk tag update failed for {}: {}", path, error);
+                result.failed.push(BulkTagFailure { path, error });
+            }
+        }
+    }
+
+    info!(
+        "Bulk tag update: {} succeeded, {} failed",
+        result.succeeded.len(),
+        result.failed.len()
+    );
+    result
+}
+
+/// Copy of `source` converted to `tag_type`: every item the target format can
+/// represent, plus all pictures.
+fn seed_tag(tag_type: TagType, source: Option<&Tag>) -> Tag {
+    let mut tag = Tag::new(tag_type);
+    if let Some(source) = source {
+        for item in source.items() {
+            tag.push(item.clone());
+        }
+        for picture in source.pictures() {
+            tag.push_picture(picture.clone());
+        }
+    }
+    tag
+}
+
+/// Read `track_path`, making sure it has a primary tag to edit.
+fn read_with_primary_tag(track_path: &str) -> Result<TaggedFile, String> {
+    let mut tagged_file = Probe::open(track_path)
         .map_err(|e| format!("Failed to open file: {}", e))?
         .read()
         .map_err(|e| format!("Failed to read file: {}", e))?;
 
-    let mut tag = tagged_file
-        .primary_tag()
-        .or_else(|| tagged_file.first_tag())
-        .ok_or_else(|| "No tag found in file".to_string())?
-        .to_owned();
+    if tagged_file.primary_tag().is_none() {
+        let seeded = seed_tag(tagged_file.primary_tag_type(), tagged_file.first_tag());
+        tagged_file.insert_tag(seeded);
+    }
+    Ok(tagged_file)
+}
+
+/// Write only `tag` back to the file, leaving any other tag blocks alone.
+fn save_tag(track_path: &str, tag: &Tag) -> Result<(), String> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(track_path)
+        .map_err(|e| format!("Failed to open file for writing: {}", e))?;
+
+    tag.save_to(&mut file)
+        .map_err(|e| format!("Failed to save tags: {}", e))
+}
+
+/// Read, mutate, and persist audio file tags using lofty.
+///
+/// Edits are merged into the file's primary tag in place: fields not in
+/// `update` and embedded pictures are left as they are. A file without a
+/// primary tag gets one, seeded from whatever other tag block it has so
+/// nothing is lost. Only the primary tag is rewritten; other tag blocks
+/// (e.g. ID3v1 or RIFF INFO) stay untouched on disk.
+pub fn apply_tags_to_file(track_path: &str, update: &TagUpdateInput) -> Result<(), String> {
+    let mut tagged_file = read_with_primary_tag(track_path)?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| "File format does not support tags".to_string())?;
 
     if let Some(ref title) = update.title {
         tag.set_title(title.clone());
@@ -55,14 +176,155 @@ pub fn apply_tags_to_file(track_path: &str, update: &TagUpdateInput) -> Result<(
         }
     }
 
-    let mut file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .open(track_path)
-        .map_err(|e| format!("Failed to open file for writing: {}", e))?;
+    save_tag(track_path, tag)
+}
 
-    tag.save_to(&mut file)
-        .map_err(|e| format!("Failed to save tags: {}", e))?;
+/// Check that `data` is an image we can embed and that it matches the
+/// declared `mime`. Only formats every common tag type accepts are allowed.
+pub fn validate_cover_image(data: &[u8], mime: &str) -> Result<MimeType, String> {
+    let declared = match mime.trim().to_ascii_lowercase().as_str() {
+        "image/jpg" => MimeType::Jpeg,
+        other => MimeType::from_str(other),
+    };
+    let sniffed = if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        MimeType::Png
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        MimeType::Jpeg
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        MimeType::Gif
+    } else if data.starts_with(b"BM") {
+        MimeType::Bmp
+    } else {
+        return Err("Unsupported image data: expected PNG, JPEG, GIF or BMP".to_string());
+    };
+
+    if declared != sniffed {
+        return Err(format!(
+            "Image data is {} but was declared as {}",
+            sniffed.as_str(),
+            mime
+        ));
+    }
+    Ok(sniffed)
+}
+
+/// Embed `data` as the front cover of `track_path`, replacing any existing
+/// front cover. The cover is stored first so readers that take the first
+/// picture pick it up; other pictures and all text fields are kept.
+pub fn write_album_art(track_path: &str, data: &[u8], mime: &str) -> Result<(), String> {
+    let mime_type = validate_cover_image(data, mime)?;
+
+    let mut tagged_file = read_with_primary_tag(track_path)?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| "File format does not support tags".to_string())?;
+
+    tag.remove_picture_type(PictureType::CoverFront);
+    let others: Vec<Picture> = (0..tag.pictures().len())
+        .map(|_| tag.remove_picture(0))
+        .collect();
+    tag.push_picture(Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(mime_type),
+        None,
+        data.to_vec(),
+    ));
+    for picture in others {
+        tag.push_picture(picture);
+    }
+
+    save_tag(track_path, tag)
+}
 
+/// Longest tag key accepted by `write_tag` / `remove_tag`.
+pub const MAX_TAG_KEY_LEN: usize = 64;
+
+/// Check a tag key is safe to write to any format: printable ASCII without
+/// `=` (the Vorbis comment separator), at most [`MAX_TAG_KEY_LEN`] long.
+pub fn validate_tag_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > MAX_TAG_KEY_LEN {
+        return Err(format!("Tag key must be 1-{} characters", MAX_TAG_KEY_LEN));
+    }
+    if !key.bytes().all(|b| (0x20..=0x7E).contains(&b) && b != b'=') {
+        return Err(format!("Tag key contains invalid characters: {:?}", key));
+    }
     Ok(())
 }
+
+/// Every text and URL item in the file's primary tag (or its only tag),
+/// keyed by the format's own key names: ID3v2 frame IDs or TXXX
+/// descriptions, Vorbis field names, and so on. Keys with several values
+/// list them all. Binary items and pictures are not included.
+pub fn read_all_tags(track_path: &str) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let tagged_file = Probe::open(track_path)
+        .map_err(|e| format!("Failed to open file: {}", e))?
+        .read()
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut tags: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) else {
+        return Ok(tags);
+    };
+
+    for item in tag.items() {
+        let Some(key) = item.key().map_key(tag.tag_type(), true) else {
+            continue;
+        };
+        let value = match item.value() {
+            ItemValue::Text(text) | ItemValue::Locator(text) => text.clone(),
+            ItemValue::Binary(_) => continue,
+        };
+        tags.entry(key.to_string()).or_default().push(value);
+    }
+    Ok(tags)
+}
+
+/// Set `key` to `value` in the primary tag, replacing any existing values.
+/// Keys the format does not know are written as custom fields where the
+/// format has them (ID3v2 TXXX, Vorbis comments, APE).
+pub fn write_tag(track_path: &str, key: &str, value: &str) -> Result<(), String> {
+    validate_tag_key(key)?;
+
+    let mut tagged_file = read_with_primary_tag(track_path)?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| "File format does not support tags".to_string())?;
+    let tag_type = tag.tag_type();
+
+    let item_key = ItemKey::from_key(tag_type, key);
+    if !tag.insert_text(item_key.clone(), value.to_string()) {
+        if !matches!(item_key, ItemKey::Unknown(_)) || !supports_custom_keys(tag_type, key) {
+            return Err(format!("Tag key {:?} is not supported by {:?} tags", key, tag_type));
+        }
+        tag.remove_key(&item_key);
+        tag.insert_unchecked(TagItem::new(item_key, ItemValue::Text(value.to_string())));
+    }
+
+    save_tag(track_path, tag)
+}
+
+/// Remove every value stored under `key` from the primary tag.
+pub fn remove_tag(track_path: &str, key: &str) -> Result<(), String> {
+    validate_tag_key(key)?;
+
+    let mut tagged_file = read_with_primary_tag(track_path)?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| "File format does not support tags".to_string())?;
+
+    tag.remove_key(&ItemKey::from_key(tag.tag_type(), key));
+    save_tag(track_path, tag)
+}
+
+/// Whether `key`, unknown to lofty, can still be stored in `tag_type`.
+fn supports_custom_keys(tag_type: TagType, key: &str) -> bool {
+    match tag_type {
+        // Four-character keys are taken as frame IDs, which must be A-Z/0-9;
+        // anything else becomes a TXXX frame described by the key.
+        TagType::Id3v2 => {
+            key.len() != 4 || key.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        }
+        TagType::VorbisComments | TagType::Ape => true,
+        _ => false,
+    }
+}
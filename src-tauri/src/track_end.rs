@@ -0,0 +1,60 @@
+//! How a track that stopped playing came to an end.
+//!
+//! When the sink empties, the broadcast thread has to tell a track that
+//! played to the end apart from one whose file went away (a network share
+//! dropped, a USB disk pulled) or failed to read partway. Only the first
+//! should advance the queue as if nothing happened; the others get their
+//! own events so the frontend can say what went wrong.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::database::Database;
+use log::warn;
+
+/// Settings key under which the "skip unavailable tracks" switch is persisted.
+pub const SKIP_UNAVAILABLE_KEY: &str = "skip_unavailable_tracks";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackEnd {
+    /// Played to the end.
+    Finished,
+    /// The file is still there but reading it failed partway.
+    ReadError,
+    /// The file can no longer be read.
+    Unavailable,
+}
+
+/// Whether `path` can still be opened and read from.
+pub fn is_readable(path: &Path) -> bool {
+    File::open(path)
+        .and_then(|mut file| file.read(&mut [0u8; 1]))
+        .is_ok()
+}
+
+/// Classify the end of the track at `path`. Only a read failure touches the
+/// disk: it is then told apart as a file that went away, since losing the
+/// file is what makes reading fail, or one that is there but unreadable.
+/// A clean end is never held up on a slow or stalled drive.
+pub fn classify(path: Option<&Path>, read_failed: bool) -> TrackEnd {
+    if !read_failed {
+        return TrackEnd::Finished;
+    }
+    match path {
+        Some(path) if !is_readable(path) => TrackEnd::Unavailable,
+        _ => TrackEnd::ReadError,
+    }
+}
+
+/// Whether playback should move on to the next track when the current one
+/// becomes unavailable. On when unset or when the setting can't be read.
+pub fn skip_unavailable(db: &Database) -> bool {
+    match db.get_json_setting::<bool>(SKIP_UNAVAILABLE_KEY) {
+        Ok(value) => value.unwrap_or(true),
+        Err(e) => {
+            warn!("Failed to read skip unavailable setting: {}", e);
+            true
+        }
+    }
+}
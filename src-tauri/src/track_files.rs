@@ -0,0 +1,141 @@
+//! Filesystem operations on library tracks that must stay in sync with the
+//! database.
+
+use crate::database::Database;
+use crate::error::{AppError, AppResult};
+use crate::scanner::{Scanner, Track};
+use log::{info, warn};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+fn io_error(action: &str, path: &Path, err: io::Error) -> AppError {
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => {
+            AppError::PermissionDenied(format!("Cannot {} {}: {}", action, path.display(), err))
+        }
+        io::ErrorKind::NotFound => AppError::NotFound(format!("Cannot {} {}: {}", action, path.display(), err)),
+        _ => AppError::Io(io::Error::new(err.kind(), format!("Cannot {} {}: {}", action, path.display(), err))),
+    }
+}
+
+fn destination_exists(to: &Path) -> AppError {
+    AppError::Validation(format!("Destination already exists: {}", to.display()))
+}
+
+/// Move `from` to `to` without ever overwriting an existing file. The new
+/// name is claimed atomically: a hard link where the filesystem supports
+/// one, otherwise a copy into a freshly created file (e.g. across volumes).
+pub fn move_file(from: &Path, to: &Path) -> AppResult<()> {
+    if let Some(parent) = to.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| io_error("create", parent, e))?;
+    }
+
+    match fs::hard_link(from, to) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(destination_exists(to)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(io_error("move", from, e)),
+        Err(e) => {
+            info!("Linking {:?} -> {:?} failed ({}), falling back to copy", from, to, e);
+            copy_new(from, to)?;
+        }
+    }
+
+    if let Err(e) = fs::remove_file(from) {
+        // Leave the library pointing at the original rather than a duplicate.
+        let _ = fs::remove_file(to);
+        return Err(io_error("remove", from, e));
+    }
+    Ok(())
+}
+
+/// Copy `from` into `to`, which must not exist yet.
+fn copy_new(from: &Path, to: &Path) -> AppResult<()> {
+    let mut source = fs::File::open(from).map_err(|e| io_error("copy", from, e))?;
+    let mut dest = fs::OpenOptions::new().write(true).create_new(true).open(to).map_err(|e| {
+        if e.kind() == io::ErrorKind::AlreadyExists {
+            destination_exists(to)
+        } else {
+            io_error("create", to, e)
+        }
+    })?;
+
+    let copied = io::copy(&mut source, &mut dest).and_then(|_| {
+        let permissions = source.metadata()?.permissions();
+        dest.set_permissions(permissions)?;
+        dest.sync_all()
+    });
+    if let Err(e) = copied {
+        drop(dest);
+        let _ = fs::remove_file(to);
+        return Err(io_error("copy", from, e));
+    }
+    Ok(())
+}
+
+/// Move a track's file on disk and update its row. If the database update
+/// fails the file is moved back, so disk and library never disagree.
+pub fn move_track_file(db: &Database, track_id: &str, new_path: &str) -> AppResult<Track> {
+    let track = db
+        .get_track_by_id(track_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))?;
+
+    let from = Path::new(&track.path);
+    let to = Path::new(new_path);
+    if from == to {
+        return Ok(track);
+    }
+    let new_name = to
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| AppError::Validation(format!("Invalid destination file name: {}", new_path)))?
+        .to_string();
+
+    move_file(from, to)?;
+
+    if let Err(e) = db.update_track_location(track_id, new_path, &new_name) {
+        warn!("Moved {:?} but the database update failed, moving back: {}", from, e);
+        if let Err(restore) = move_file(to, from) {
+            warn!("Failed to restore {:?} after aborted move: {}", from, restore);
+        }
+        return Err(e.into());
+    }
+
+    info!("Moved track {} from {:?} to {:?}", track_id, from, to);
+    Ok(Track {
+        path: new_path.to_string(),
+        name: new_name,
+        ..track
+    })
+}
+
+/// Re-read a track's tags from disk and update its row in place. Only the
+/// tag-derived fields change; rating, play history and playlist membership
+/// are kept.
+pub fn refresh_track_metadata(db: &Database, track_id: &str) -> AppResult<Track> {
+    let track = db
+        .get_track_by_id(track_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Track not found: {}", track_id)))?;
+
+    let path = Path::new(&track.path);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("File not found: {}", track.path)));
+    }
+    let scanned = Scanner::extract_track_info(path).map_err(AppError::Scanner)?;
+
+    let refreshed = Track {
+        title: scanned.title,
+        artist: scanned.artist,
+        album: scanned.album,
+        genre: scanned.genre,
+        year: scanned.year,
+        track_number: scanned.track_number,
+        disc_number: scanned.disc_number,
+        duration: scanned.duration,
+        ..track
+    };
+    db.refresh_track_tags(&refreshed)?;
+
+    info!("Refreshed metadata for track {} from {}", track_id, refreshed.path);
+    Ok(refreshed)
+}
@@ -16,8 +16,45 @@ pub enum VisualizerMode {
     Waveform,       // Time-domain waveform
     CircularSpectrum, // Radial frequency display
     Spectrogram,    // Frequency over time (waterfall)
+    StereoSpectrum, // Separate left/right frequency bars
 }
 
+const MIN_DISPLAY_FREQ: f32 = 20.0;
+const MAX_DISPLAY_FREQ: f32 = 20000.0;
+
+/// Lower edge in Hz of bar `i` out of `num_bins` on the log display scale.
+fn band_edge(i: usize, num_bins: usize) -> f32 {
+    MIN_DISPLAY_FREQ * (MAX_DISPLAY_FREQ / MIN_DISPLAY_FREQ).powf(i as f32 / num_bins as f32)
+}
+
+/// Center frequency in Hz of every bar, using the same log mapping as the
+/// spectrum binning (geometric mean of each bar's edges).
+pub fn bin_frequencies(num_bins: usize) -> Vec<f32> {
+    (0..num_bins)
+        .map(|i| (band_edge(i, num_bins) * band_edge(i + 1, num_bins)).sqrt())
+        .collect()
+}
+
+/// Most samples analysed for one frame. The player's buffer holds 4096, so
+/// this only trims a malformed block.
+pub const MAX_FRAME_SAMPLES: usize = 16_384;
+
+/// Make a block of player samples safe to analyse: keep the newest
+/// [`MAX_FRAME_SAMPLES`] and turn NaN or infinite values, which would poison
+/// the FFT and the AGC level, into silence. One bad sample from the DSP
+/// chain then costs a frame's accuracy instead of failing every poll.
+pub fn sanitize_samples(samples: &mut Vec<f32>) {
+    if samples.len() > MAX_FRAME_SAMPLES {
+        samples.drain(..samples.len() - MAX_FRAME_SAMPLES);
+    }
+    for sample in samples.iter_mut().filter(|s| !s.is_finite()) {
+        *sample = 0.0;
+    }
+}
+
+/// Default per-frame decay of the AGC reference level (~2 s to fall 20 dB at 30 fps).
+pub const DEFAULT_AGC_DECAY: f32 = 0.963;
+
 /// FFT analyzer for frequency spectrum
 pub struct FftAnalyzer {
     buffer: VecDeque<f32>,
@@ -25,6 +62,10 @@ pub struct FftAnalyzer {
     fft_size: usize,
     sample_rate: u32,
     planner: FftPlanner<f32>,
+    /// Slowly-decaying running maximum the spectrum is normalized against.
+    agc_level: f32,
+    /// Multiplier applied to `agc_level` each frame (closer to 1 = slower).
+    agc_decay: f32,
 }
 
 impl FftAnalyzer {
@@ -43,8 +84,20 @@ impl FftAnalyzer {
             fft_size,
             sample_rate,
             planner: FftPlanner::new(),
+            agc_level: 0.0,
+            agc_decay: DEFAULT_AGC_DECAY,
         }
     }
+
+    /// Set how quickly the display gain recovers after loud passages.
+    /// `decay` is the per-frame multiplier in (0, 1]; 1 never recovers.
+    pub fn set_agc_decay(&mut self, decay: f32) -> Result<(), String> {
+        if !(decay > 0.0 && decay <= 1.0) {
+            return Err(format!("AGC decay must be in (0, 1], got {}", decay));
+        }
+        self.agc_decay = decay;
+        Ok(())
+    }
     
     /// Add audio samples to the buffer
     pub fn add_samples(&mut self, samples: &[f32]) {
@@ -87,14 +140,14 @@ impl FftAnalyzer {
     }
     
     /// Group frequency bins logarithmically for better visualization
-    fn bin_spectrum(&self, magnitudes: &[f32], num_bins: usize) -> Vec<f32> {
+    fn bin_spectrum(&mut self, magnitudes: &[f32], num_bins: usize) -> Vec<f32> {
         let mut bins = vec![0.0; num_bins];
         let half_size = self.fft_size / 2;
         
         for (i, bin) in bins.iter_mut().enumerate() {
             // Logarithmic mapping
-            let freq_start = 20.0 * (20000.0_f32 / 20.0).powf(i as f32 / num_bins as f32);
-            let freq_end = 20.0 * (20000.0_f32 / 20.0).powf((i + 1) as f32 / num_bins as f32);
+            let freq_start = band_edge(i, num_bins);
+            let freq_end = band_edge(i + 1, num_bins);
             
             let bin_start = (freq_start * half_size as f32 / (self.sample_rate as f32 / 2.0)) as usize;
             let bin_end = (freq_end * half_size as f32 / (self.sample_rate as f32 / 2.0)) as usize;
@@ -106,11 +159,14 @@ impl FftAnalyzer {
             }
         }
         
-        // Normalize
+        // Automatic gain control: normalize against a running maximum that
+        // jumps up with loud frames and decays slowly, so quiet passages stay
+        // quiet instead of being stretched to full scale every frame.
         let max = bins.iter().fold(0.0f32, |a, &b| a.max(b));
-        if max > 0.0 {
+        self.agc_level = max.max(self.agc_level * self.agc_decay);
+        if self.agc_level > 0.0 {
             for bin in bins.iter_mut() {
-                *bin /= max;
+                *bin = (*bin / self.agc_level).min(1.0);
             }
         }
         
@@ -141,6 +197,8 @@ pub struct BeatDetector {
     threshold_multiplier: f32,
     last_beat_time: f32,
     min_beat_interval: f32,
+    /// Spectrum bins `[low, high)` whose energy counts as the beat band.
+    band: (usize, usize),
 }
 
 impl BeatDetector {
@@ -151,13 +209,20 @@ impl BeatDetector {
             threshold_multiplier: 1.5,
             last_beat_time: 0.0,
             min_beat_interval: 0.3, // Minimum 300ms between beats
+            band: (0, 8),           // Lowest bins: bass/kick
         }
     }
     
     /// Detect if current frame contains a beat
     pub fn detect_beat(&mut self, spectrum: &[f32], current_time: f32) -> bool {
-        // Calculate energy of low-mid frequencies (bass/kick)
-        let bass_energy: f32 = spectrum.iter().take(8).map(|x| x * x).sum();
+        // Calculate energy of the beat band (bass/kick by default)
+        let (low, high) = self.band;
+        let bass_energy: f32 = spectrum
+            .iter()
+            .take(high)
+            .skip(low)
+            .map(|x| x * x)
+            .sum();
         
         self.energy_history.push_back(bass_energy);
         if self.energy_history.len() > self.history_size {
@@ -187,6 +252,30 @@ impl BeatDetector {
         // sensitivity 0.0-1.0, lower = more sensitive
         self.threshold_multiplier = 1.2 + (1.0 - sensitivity) * 0.8;
     }
+
+    /// Shortest gap between two reported beats, in seconds. Fast genres
+    /// need less than the 0.3 s default.
+    pub fn set_min_beat_interval(&mut self, seconds: f32) -> Result<(), String> {
+        if !seconds.is_finite() || seconds < 0.0 {
+            return Err(format!("Invalid beat interval: {}", seconds));
+        }
+        self.min_beat_interval = seconds;
+        Ok(())
+    }
+
+    /// Choose which spectrum bins `[low_bin, high_bin)` are summed for beat
+    /// energy, e.g. move up from the lowest bins for bass-light material.
+    pub fn set_beat_band(&mut self, low_bin: usize, high_bin: usize, spectrum_len: usize) -> Result<(), String> {
+        if low_bin >= high_bin || high_bin > spectrum_len {
+            return Err(format!(
+                "Invalid beat band {}..{} for a {}-bin spectrum",
+                low_bin, high_bin, spectrum_len
+            ));
+        }
+        self.band = (low_bin, high_bin);
+        self.energy_history.clear();
+        Ok(())
+    }
 }
 
 /// Visualizer data for frontend
@@ -197,11 +286,18 @@ pub struct VisualizerData {
     pub beat_detected: bool,
     pub peak_frequency: f32,
     pub rms_level: f32,
+    /// Per-channel spectra, only filled in `StereoSpectrum` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_spectrum: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right_spectrum: Option<Vec<f32>>,
 }
 
 /// Main visualizer processor
 pub struct Visualizer {
     fft_analyzer: FftAnalyzer,
+    left_analyzer: FftAnalyzer,
+    right_analyzer: FftAnalyzer,
     beat_detector: BeatDetector,
     mode: VisualizerMode,
     num_bars: usize,
@@ -212,6 +308,8 @@ impl Visualizer {
     pub fn new(sample_rate: u32, num_bars: usize) -> Self {
         Self {
             fft_analyzer: FftAnalyzer::new(2048, sample_rate),
+            left_analyzer: FftAnalyzer::new(2048, sample_rate),
+            right_analyzer: FftAnalyzer::new(2048, sample_rate),
             beat_detector: BeatDetector::new(sample_rate),
             mode: VisualizerMode::Spectrum,
             num_bars,
@@ -222,10 +320,35 @@ impl Visualizer {
     pub fn set_mode(&mut self, mode: VisualizerMode) {
         self.mode = mode;
     }
+
+    pub fn mode(&self) -> VisualizerMode {
+        self.mode
+    }
     
     pub fn set_beat_sensitivity(&mut self, sensitivity: f32) {
         self.beat_detector.set_sensitivity(sensitivity);
     }
+
+    pub fn set_min_beat_interval(&mut self, seconds: f32) -> Result<(), String> {
+        self.beat_detector.set_min_beat_interval(seconds)
+    }
+
+    /// Center frequency in Hz of each spectrum bar, for labeling an axis.
+    pub fn bin_frequencies(&self) -> Vec<f32> {
+        bin_frequencies(self.num_bars)
+    }
+
+    /// Set the spectrum AGC decay for every analyzer.
+    pub fn set_agc_decay(&mut self, decay: f32) -> Result<(), String> {
+        self.fft_analyzer.set_agc_decay(decay)?;
+        self.left_analyzer.set_agc_decay(decay)?;
+        self.right_analyzer.set_agc_decay(decay)
+    }
+
+    /// Set the beat band, validated against the number of spectrum bars.
+    pub fn set_beat_band(&mut self, low_bin: usize, high_bin: usize) -> Result<(), String> {
+        self.beat_detector.set_beat_band(low_bin, high_bin, self.num_bars)
+    }
     
     /// Process audio samples and generate visualization data
     pub fn process(&mut self, samples: &[f32], delta_time: f32) -> VisualizerData {
@@ -251,7 +374,7 @@ impl Visualizer {
             .map(|(i, _)| i)
             .unwrap_or(0);
         
-        let peak_frequency = 20.0 * (20000.0_f32 / 20.0).powf(peak_idx as f32 / self.num_bars as f32);
+        let peak_frequency = band_edge(peak_idx, self.num_bars);
         
         // Calculate RMS level
         let rms_level = (samples.iter().map(|x| x * x).sum::<f32>() / samples.len() as f32).sqrt();
@@ -262,14 +385,53 @@ impl Visualizer {
             beat_detected,
             peak_frequency,
             rms_level,
+            left_spectrum: None,
+            right_spectrum: None,
         }
     }
+
+    /// Like `process`, adding left/right spectra in `StereoSpectrum` mode.
+    /// In other modes the channel samples are ignored.
+    pub fn process_stereo(&mut self, samples: &[f32], left: &[f32], right: &[f32], delta_time: f32) -> VisualizerData {
+        let mut data = self.process(samples, delta_time);
+        if self.mode == VisualizerMode::StereoSpectrum {
+            self.left_analyzer.add_samples(left);
+            self.right_analyzer.add_samples(right);
+            data.left_spectrum = Some(self.left_analyzer.get_spectrum(self.num_bars));
+            data.right_spectrum = Some(self.right_analyzer.get_spectrum(self.num_bars));
+        }
+        data
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sanitize_samples_silences_bad_input() {
+        let mut samples = vec![0.0, 0.5, -1.0];
+        sanitize_samples(&mut samples);
+        assert_eq!(samples, vec![0.0, 0.5, -1.0]);
+
+        let mut samples = vec![0.2, f32::NAN, 0.3, f32::INFINITY, f32::NEG_INFINITY];
+        sanitize_samples(&mut samples);
+        assert_eq!(samples, vec![0.2, 0.0, 0.3, 0.0, 0.0]);
+
+        let mut samples = vec![0.1; MAX_FRAME_SAMPLES];
+        samples.push(0.9);
+        sanitize_samples(&mut samples);
+        assert_eq!(samples.len(), MAX_FRAME_SAMPLES);
+        assert_eq!(samples.last(), Some(&0.9), "the newest samples are kept");
+
+        // A frame with a NaN still produces a usable spectrum.
+        let mut samples = vec![0.0; 2048];
+        samples[100] = f32::NAN;
+        sanitize_samples(&mut samples);
+        let mut vis = Visualizer::new(44100, 64);
+        assert!(vis.process(&samples, 0.033).spectrum.iter().all(|v| v.is_finite()));
+    }
+
     #[test]
     fn test_fft_analyzer_creation() {
         let analyzer = FftAnalyzer::new(2048, 44100);
@@ -294,6 +456,51 @@ mod tests {
         }
     }
 
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn quiet_frame_after_loud_ones_is_not_boosted() {
+        let mut analyzer = FftAnalyzer::new(2048, 44100);
+        for _ in 0..5 {
+            analyzer.add_samples(&sine(1.0, 4096));
+            let loud = analyzer.get_spectrum(32);
+            assert!((loud.iter().cloned().fold(0.0, f32::max) - 1.0).abs() < 1e-6);
+        }
+
+        analyzer.add_samples(&sine(0.05, 4096));
+        let quiet = analyzer.get_spectrum(32);
+        let peak = quiet.iter().cloned().fold(0.0, f32::max);
+        assert!(peak < 0.1, "quiet frame jumped to {}", peak);
+    }
+
+    #[test]
+    fn agc_recovers_at_the_configured_rate() {
+        let peak_after_quiet_frames = |decay: f32| {
+            let mut analyzer = FftAnalyzer::new(2048, 44100);
+            analyzer.set_agc_decay(decay).unwrap();
+            analyzer.add_samples(&sine(1.0, 4096));
+            analyzer.get_spectrum(32);
+            analyzer.add_samples(&sine(0.1, 4096));
+            (0..20).map(|_| analyzer.get_spectrum(32)).last().unwrap()
+                .into_iter().fold(0.0, f32::max)
+        };
+        assert!(peak_after_quiet_frames(0.7) > peak_after_quiet_frames(0.99));
+        assert!(FftAnalyzer::new(2048, 44100).set_agc_decay(0.0).is_err());
+    }
+
+    #[test]
+    fn bin_frequencies_increase_across_the_audible_range() {
+        let freqs = Visualizer::new(44100, 64).bin_frequencies();
+        assert_eq!(freqs.len(), 64);
+        assert!(freqs.windows(2).all(|w| w[1] > w[0]));
+        assert!(freqs[0] > 20.0 && freqs[0] < 25.0, "first bar at {}", freqs[0]);
+        assert!(freqs[63] < 20000.0 && freqs[63] > 18000.0, "last bar at {}", freqs[63]);
+    }
+
     #[test]
     fn test_waveform() {
         let mut analyzer = FftAnalyzer::new(2048, 44100);
@@ -314,6 +521,68 @@ mod tests {
         assert!(!beat);
     }
 
+    /// Beats at 0.1 s spacing after a second of quiet; returns how many register.
+    fn count_fast_beats(detector: &mut BeatDetector) -> usize {
+        let quiet = vec![0.1; 32];
+        let loud = vec![1.0; 32];
+        let mut time = 0.0;
+        for _ in 0..43 {
+            detector.detect_beat(&quiet, time);
+            time += 0.05;
+        }
+        (0..20)
+            .filter(|i| {
+                time += 0.05;
+                let frame = if i % 2 == 0 { &loud } else { &quiet };
+                detector.detect_beat(frame, time)
+            })
+            .count()
+    }
+
+    #[test]
+    fn shorter_beat_interval_allows_closer_beats() {
+        let default_count = count_fast_beats(&mut BeatDetector::new(44100));
+
+        let mut fast = BeatDetector::new(44100);
+        fast.set_min_beat_interval(0.08).unwrap();
+        let fast_count = count_fast_beats(&mut fast);
+
+        assert_eq!(fast_count, 10);
+        assert!(default_count < fast_count, "default {} vs fast {}", default_count, fast_count);
+    }
+
+    #[test]
+    fn beat_band_is_validated() {
+        let mut vis = Visualizer::new(44100, 32);
+        assert!(vis.set_beat_band(4, 12).is_ok());
+        assert!(vis.set_beat_band(12, 4).is_err());
+        assert!(vis.set_beat_band(0, 33).is_err());
+        assert!(vis.set_min_beat_interval(-1.0).is_err());
+    }
+
+    #[test]
+    fn hard_panned_signal_only_shows_in_one_channel() {
+        let mut vis = Visualizer::new(44100, 32);
+        vis.set_mode(VisualizerMode::StereoSpectrum);
+
+        let left: Vec<f32> = (0..2048)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin())
+            .collect();
+        let right = vec![0.0; 2048];
+        let mixed: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+
+        let data = vis.process_stereo(&mixed, &left, &right, 0.01);
+        let left_energy: f32 = data.left_spectrum.unwrap().iter().sum();
+        let right_energy: f32 = data.right_spectrum.unwrap().iter().sum();
+        assert!(left_energy > 0.0);
+        assert_eq!(right_energy, 0.0);
+
+        // Other modes keep the mono-only payload.
+        vis.set_mode(VisualizerMode::Spectrum);
+        let data = vis.process_stereo(&mixed, &left, &right, 0.01);
+        assert!(data.left_spectrum.is_none() && data.right_spectrum.is_none());
+    }
+
     #[test]
     fn test_visualizer() {
         let mut vis = Visualizer::new(44100, 32);
@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use vplayer::database::Database;
-use vplayer::replaygain::{analyze_album_replaygain, get_album_replaygain};
+use vplayer::replaygain::{analyze_album_replaygain, get_album_replaygain, DEFAULT_TARGET_LUFS};
 use vplayer::scanner::Track;
 use vplayer::time_utils::now_millis;
 
@@ -69,7 +69,7 @@ fn analyze_album_replaygain_derives_and_caches_album_data() {
         .expect("update rg track2 should succeed");
     }
 
-    let derived = analyze_album_replaygain(&db.conn, "Band A", "Album A")
+    let derived = analyze_album_replaygain(&db.conn, "Band A", "Album A", DEFAULT_TARGET_LUFS)
         .expect("album analysis should succeed")
         .expect("album data should be present");
 
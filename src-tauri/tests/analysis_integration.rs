@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+use vplayer::analysis::{analyze_track_all, analyze_tracks, AnalyzerSelection};
+use vplayer::database::Database;
+use vplayer::replaygain::get_replaygain;
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+const SAMPLE_RATE: u32 = 22050;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn midi_freq(note: u8) -> f32 {
+    440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// 12 s of I–IV–V–I in C major, a chord per second, with a noise-burst
+/// click every half second (120 BPM).
+fn beat_and_chords() -> Vec<f32> {
+    let chords: [&[u8]; 4] = [&[48, 64, 67, 72], &[53, 65, 69, 72], &[55, 62, 67, 71], &[48, 64, 67, 72]];
+    let total = SAMPLE_RATE as usize * 12;
+    let beat = SAMPLE_RATE as usize / 2;
+    let click_len = SAMPLE_RATE as usize / 100;
+    (0..total)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            let chord = chords[(n / SAMPLE_RATE as usize) % chords.len()];
+            let tone: f32 = chord
+                .iter()
+                .map(|&note| (2.0 * std::f32::consts::PI * midi_freq(note) * t).sin())
+                .sum();
+            let in_click = n % beat;
+            let click = if in_click < click_len {
+                let noise = (n as u32).wrapping_mul(2_654_435_761) as f32 / u32::MAX as f32 - 0.5;
+                noise * (1.0 - in_click as f32 / click_len as f32)
+            } else {
+                0.0
+            };
+            tone * 0.1 + click
+        })
+        .collect()
+}
+
+fn write_wav(path: &Path, samples: &[f32]) {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &v in samples {
+        bytes.extend_from_slice(&((v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+fn add_track(db: &Database, id: &str, path: &str) {
+    db.add_track(&Track {
+        id: id.to_string(),
+        path: path.to_string(),
+        name: format!("{}.wav", id),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 12.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+}
+
+type StoredAnalysis = (Option<f64>, Option<String>, Option<String>);
+
+fn stored(db: &Database, id: &str) -> StoredAnalysis {
+    db.conn
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT bpm, musical_key, fingerprint FROM tracks WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap()
+}
+
+#[test]
+fn one_pass_populates_every_selected_result() {
+    let dir = temp_dir("analysis_all");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("mix.wav");
+    write_wav(&path, &beat_and_chords());
+    let path_str = path.to_str().unwrap();
+    add_track(&db, "t1", path_str);
+
+    let result = analyze_track_all(&db, path_str, AnalyzerSelection::all(), -18.0, &AtomicBool::new(false)).unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+    let bpm = result.bpm.unwrap();
+    assert!((bpm - 120.0).abs() < 1.0, "estimated {}", bpm);
+    assert_eq!(result.key.as_ref().unwrap().key, "C major");
+    let gain = result.replaygain.as_ref().unwrap();
+    assert!(gain.loudness.is_finite() && gain.track_peak > 0.0);
+
+    let (stored_bpm, stored_key, stored_fp) = stored(&db, "t1");
+    assert_eq!(stored_bpm, Some(bpm));
+    assert_eq!(stored_key.as_deref(), Some("C major"));
+    assert_eq!(stored_fp, result.fingerprint);
+    assert!(stored_fp.is_some());
+    let stored_gain = get_replaygain(&db.conn, path_str).unwrap().unwrap();
+    assert_eq!(stored_gain.loudness, gain.loudness);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn unselected_analyzers_are_skipped() {
+    let dir = temp_dir("analysis_selection");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("mix.wav");
+    write_wav(&path, &beat_and_chords());
+    let path_str = path.to_str().unwrap();
+    add_track(&db, "t1", path_str);
+
+    let selection = AnalyzerSelection { key: true, ..Default::default() };
+    let result = analyze_track_all(&db, path_str, selection, -18.0, &AtomicBool::new(false)).unwrap();
+
+    assert!(result.key.is_some());
+    assert!(result.bpm.is_none() && result.replaygain.is_none() && result.fingerprint.is_none());
+    assert_eq!(stored(&db, "t1"), (None, Some("C major".to_string()), None));
+    assert!(get_replaygain(&db.conn, path_str).unwrap().is_none());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn batch_reports_progress_and_keeps_going_past_bad_files() {
+    let dir = temp_dir("analysis_batch");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let good = dir.join("good.wav");
+    write_wav(&good, &beat_and_chords());
+    let bad = dir.join("bad.wav");
+    fs::write(&bad, b"not audio").unwrap();
+    let paths = vec![bad.to_str().unwrap().to_string(), good.to_str().unwrap().to_string()];
+
+    let selection = AnalyzerSelection { bpm: true, ..Default::default() };
+    let mut progress = Vec::new();
+    let results = analyze_tracks(&db, &paths, selection, -18.0, &AtomicBool::new(false), |p| {
+        progress.push((p.current, p.total, p.current_file.clone()));
+    });
+
+    assert_eq!(progress, vec![(1, 2, paths[0].clone()), (2, 2, paths[1].clone())]);
+    assert_eq!(results.len(), 2);
+    assert!(!results[0].errors.is_empty() && results[0].bpm.is_none());
+    assert!(results[1].errors.is_empty() && results[1].bpm.is_some());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cancelled_batch_stops_without_storing() {
+    let dir = temp_dir("analysis_cancel");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("mix.wav");
+    write_wav(&path, &beat_and_chords());
+    let path_str = path.to_str().unwrap();
+    add_track(&db, "t1", path_str);
+
+    let cancel = AtomicBool::new(true);
+    assert!(analyze_track_all(&db, path_str, AnalyzerSelection::all(), -18.0, &cancel).is_err());
+    let results = analyze_tracks(&db, &[path_str.to_string()], AnalyzerSelection::all(), -18.0, &cancel, |_| {
+        panic!("no file should start after cancellation")
+    });
+    assert!(results.is_empty());
+    assert_eq!(stored(&db, "t1"), (None, None, None));
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::{ImageFormat, RgbImage};
+use vplayer::art_cache_format::{ArtCacheFormat, ArtCacheSettings, ART_CACHE_SETTINGS_KEY};
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn track(id: &str) -> Track {
+    Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.mp3", id),
+        name: format!("{}.mp3", id),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+/// A photo-like PNG: smooth gradients with some fine detail.
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x / 2) as u8, (y / 2) as u8, ((x * y) % 251) as u8])
+    });
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn png_art_is_cached_as_smaller_webp() {
+    let dir = temp_dir("art_cache_webp");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    db.add_track(&track("t1")).unwrap();
+    db.add_track(&track("t2")).unwrap();
+
+    let original = png(512, 512);
+    db.set_album_art("t1", &original).unwrap();
+    let cached = db.get_album_art_with_mime("t1").unwrap().unwrap();
+    assert_eq!(cached.mime, "image/png", "art is cached as is by default");
+    assert_eq!(cached.data, original);
+
+    db.set_json_setting(ART_CACHE_SETTINGS_KEY, &ArtCacheSettings { format: ArtCacheFormat::Webp, quality: 75.0 })
+        .unwrap();
+    db.set_album_art("t2", &original).unwrap();
+    let cached = db.get_album_art_with_mime("t2").unwrap().unwrap();
+    assert_eq!(cached.mime, "image/webp");
+    assert!(cached.data.len() < original.len(), "{} >= {} bytes", cached.data.len(), original.len());
+
+    let decoded = image::load_from_memory_with_format(&cached.data, ImageFormat::WebP).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (512, 512));
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn art_that_cannot_be_converted_is_cached_unchanged() {
+    let dir = temp_dir("art_cache_fallback");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    db.add_track(&track("t1")).unwrap();
+    db.set_json_setting(ART_CACHE_SETTINGS_KEY, &ArtCacheSettings { format: ArtCacheFormat::Webp, quality: 75.0 })
+        .unwrap();
+
+    db.set_album_art("t1", b"not an image").unwrap();
+    let cached = db.get_album_art_with_mime("t1").unwrap().unwrap();
+    assert_eq!(cached.data, b"not an image");
+    assert_eq!(cached.mime, "image/jpeg");
+
+    drop(db);
+    cleanup(&dir);
+}
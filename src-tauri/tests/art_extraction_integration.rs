@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use vplayer::art_extraction::ArtExtractor;
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn track(id: &str, path: &str) -> Track {
+    Track {
+        id: id.to_string(),
+        path: path.to_string(),
+        name: "song.mp3".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+#[test]
+fn concurrent_requests_for_the_same_track_extract_once() {
+    let dir = temp_dir("art_extraction");
+    let db = Arc::new(Database::new(&dir.join("test.db")).unwrap());
+    db.add_track(&track("t1", "C:/Music/song.mp3")).unwrap();
+    let extractor = Arc::new(ArtExtractor::new());
+    let extractions = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(8));
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let (db, extractor, extractions, barrier) =
+                (db.clone(), extractor.clone(), extractions.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                extractor
+                    .extract_and_cache(&db, "t1", "C:/Music/song.mp3", |_| {
+                        extractions.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(100));
+                        Ok(Some(vec![0xFF, 0xD8, 0xFF, 0xE0]))
+                    })
+                    .unwrap()
+            })
+        })
+        .collect();
+    let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(extractions.load(Ordering::SeqCst), 1);
+    assert!(results.iter().all(|art| {
+        let art = art.as_ref().unwrap();
+        art.data == [0xFF, 0xD8, 0xFF, 0xE0] && art.mime == "image/jpeg"
+    }));
+    assert_eq!(db.get_album_art("t1").unwrap(), Some(vec![0xFF, 0xD8, 0xFF, 0xE0]));
+
+    // Cached art is returned, with its MIME type, without extracting again.
+    let cached = extractor
+        .extract_and_cache(&db, "t1", "C:/Music/song.mp3", |_| panic!("should use the cache"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(cached.mime, "image/jpeg");
+
+    drop(db);
+    cleanup(&dir);
+}
@@ -0,0 +1,52 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgb, RgbImage};
+use vplayer::art_palette::{extract_palette, PaletteCache};
+
+fn png(width: u32, height: u32, color: impl Fn(u32, u32) -> [u8; 3]) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| Rgb(color(x, y)));
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn solid_red_art_has_a_red_dominant_colour() {
+    let palette = extract_palette(&png(300, 300, |_, _| [255, 0, 0])).unwrap();
+    assert_eq!(palette.dominant, "#ff0000");
+    // Nothing contrasts with a single colour.
+    assert_eq!(palette.accent, "#ff0000");
+    assert_eq!(palette.colors, vec!["#ff0000".to_string()]);
+}
+
+#[test]
+fn accent_is_the_contrasting_minority_colour() {
+    // Three quarters blue, one quarter yellow.
+    let art = png(64, 64, |x, _| if x < 16 { [255, 255, 0] } else { [0, 0, 255] });
+    let palette = extract_palette(&art).unwrap();
+    assert_eq!(palette.dominant, "#0000ff");
+    assert_eq!(palette.accent, "#ffff00");
+}
+
+#[test]
+fn undecodable_art_is_an_error() {
+    assert!(extract_palette(b"not an image").is_err());
+}
+
+#[test]
+fn cache_is_keyed_by_art_bytes() {
+    let cache = PaletteCache::new();
+    let red = png(32, 32, |_, _| [255, 0, 0]);
+    let green = png(32, 32, |_, _| [0, 255, 0]);
+
+    assert_eq!(cache.get_or_extract(&red).unwrap().dominant, "#ff0000");
+    assert_eq!(cache.get_or_extract(&red).unwrap().dominant, "#ff0000");
+    assert_eq!(cache.len(), 1);
+
+    assert_eq!(cache.get_or_extract(&green).unwrap().dominant, "#00ff00");
+    assert_eq!(cache.len(), 2);
+
+    // Failures are not cached.
+    assert!(cache.get_or_extract(b"garbage").is_err());
+    assert_eq!(cache.len(), 2);
+}
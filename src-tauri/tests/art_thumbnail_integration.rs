@@ -0,0 +1,79 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use image::{ImageFormat, RgbImage};
+use vplayer::art_thumbnail::{make_thumbnail, THUMBNAIL_SIZE};
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = RgbImage::from_fn(width, height, |x, y| image::Rgb([(x % 256) as u8, (y % 256) as u8, 128]));
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Png).unwrap();
+    out.into_inner()
+}
+
+fn dimensions(bytes: &[u8]) -> (u32, u32) {
+    let image = image::load_from_memory(bytes).unwrap();
+    (image.width(), image.height())
+}
+
+#[test]
+fn thumbnails_fit_the_bound_and_keep_aspect_ratio() {
+    assert_eq!(dimensions(&make_thumbnail(&png(1200, 600), THUMBNAIL_SIZE).unwrap()), (256, 128));
+    assert_eq!(dimensions(&make_thumbnail(&png(300, 900), THUMBNAIL_SIZE).unwrap()), (85, 256));
+    // Small art is not enlarged.
+    assert_eq!(dimensions(&make_thumbnail(&png(100, 80), THUMBNAIL_SIZE).unwrap()), (100, 80));
+    assert!(make_thumbnail(b"not an image", THUMBNAIL_SIZE).is_err());
+}
+
+#[test]
+fn cached_art_has_a_thumbnail_alongside_the_original() {
+    let dir = temp_dir("art_thumbnail");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    db.add_track(&Track {
+        id: "t1".to_string(),
+        path: "C:/Music/song.mp3".to_string(),
+        name: "song.mp3".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+
+    let original = png(1000, 1000);
+    db.set_album_art("t1", &original).unwrap();
+    assert_eq!(db.get_album_art("t1").unwrap(), Some(original));
+
+    let thumbnail = db.get_album_art_thumbnail("t1").unwrap().unwrap();
+    let (width, height) = dimensions(&thumbnail);
+    assert!(width <= THUMBNAIL_SIZE && height <= THUMBNAIL_SIZE, "{}x{}", width, height);
+    assert_eq!(db.get_album_art_thumbnail("missing").unwrap(), None);
+
+    drop(db);
+    cleanup(&dir);
+}
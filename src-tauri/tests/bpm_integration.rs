@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::bpm::{analyze_track_bpm, BpmDetector};
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+const SAMPLE_RATE: u32 = 44100;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// `seconds` of 10 ms decaying 1 kHz clicks at `bpm` over a quiet noise floor.
+fn click_track(bpm: f64, seconds: f64) -> Vec<f32> {
+    let total = (seconds * SAMPLE_RATE as f64) as usize;
+    let interval = 60.0 / bpm * SAMPLE_RATE as f64;
+    let click_len = SAMPLE_RATE as usize / 100;
+    let mut samples: Vec<f32> = (0..total)
+        .map(|n| ((n as u32).wrapping_mul(2_654_435_761) as f32 / u32::MAX as f32 - 0.5) * 0.002)
+        .collect();
+    let mut beat = 0.0;
+    while (beat as usize) < total {
+        let start = beat as usize;
+        for i in 0..click_len.min(total - start) {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let envelope = 1.0 - i as f32 / click_len as f32;
+            samples[start + i] += (2.0 * std::f32::consts::PI * 1000.0 * t).sin() * 0.8 * envelope;
+        }
+        beat += interval;
+    }
+    samples
+}
+
+fn estimate(samples: &[f32]) -> f64 {
+    let mut detector = BpmDetector::new(SAMPLE_RATE);
+    // Feed in packet-sized pieces like the decoder does.
+    for chunk in samples.chunks(1152) {
+        detector.push(chunk);
+    }
+    detector.finish().unwrap()
+}
+
+fn write_wav(path: &Path, samples: &[f32]) {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &v in samples {
+        bytes.extend_from_slice(&((v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn click_tracks_are_estimated_within_tolerance() {
+    for bpm in [90.0, 120.0, 128.0, 174.0] {
+        let estimated = estimate(&click_track(bpm, 20.0));
+        assert!((estimated - bpm).abs() < 1.0, "expected {} BPM, estimated {}", bpm, estimated);
+    }
+}
+
+#[test]
+fn silence_and_short_audio_have_no_tempo() {
+    let mut detector = BpmDetector::new(SAMPLE_RATE);
+    detector.push(&vec![0.0; SAMPLE_RATE as usize * 20]);
+    assert!(detector.finish().is_err());
+
+    let mut detector = BpmDetector::new(SAMPLE_RATE);
+    detector.push(&click_track(120.0, 1.0));
+    assert!(detector.finish().is_err());
+}
+
+#[test]
+fn analyzed_bpm_is_stored_on_the_track() {
+    let dir = temp_dir("bpm");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("beat.wav");
+    write_wav(&path, &click_track(100.0, 15.0));
+    let path_str = path.to_str().unwrap();
+
+    db.add_track(&Track {
+        id: "t1".to_string(),
+        path: path_str.to_string(),
+        name: "beat.wav".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 15.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+
+    let bpm = analyze_track_bpm(&db, path_str).unwrap();
+    assert!((bpm - 100.0).abs() < 1.0, "estimated {}", bpm);
+    let stored: f64 = db
+        .conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT bpm FROM tracks WHERE id = 't1'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(stored, bpm);
+
+    let _ = fs::remove_dir_all(&dir);
+}
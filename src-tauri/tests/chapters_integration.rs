@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lofty::{ItemKey, ItemValue, Tag, TagExt, TagItem, TagType};
+use vplayer::chapters::{next_chapter, previous_chapter, read_chapters, Chapter};
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn chapter(start: f64, title: &str) -> Chapter {
+    Chapter { start, title: title.to_string() }
+}
+
+fn synchsafe(n: usize) -> [u8; 4] {
+    [(n >> 21) as u8 & 0x7f, (n >> 14) as u8 & 0x7f, (n >> 7) as u8 & 0x7f, n as u8 & 0x7f]
+}
+
+fn id3v24_frame(id: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut frame = id.to_vec();
+    frame.extend_from_slice(&synchsafe(body.len()));
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(body);
+    frame
+}
+
+fn chap_frame(element_id: &str, start_ms: u32, end_ms: u32, title: Option<&str>) -> Vec<u8> {
+    let mut body = element_id.as_bytes().to_vec();
+    body.push(0);
+    body.extend_from_slice(&start_ms.to_be_bytes());
+    body.extend_from_slice(&end_ms.to_be_bytes());
+    body.extend_from_slice(&[0xff; 8]); // byte offsets unused
+    if let Some(title) = title {
+        let mut text = vec![3u8]; // UTF-8
+        text.extend_from_slice(title.as_bytes());
+        body.extend(id3v24_frame(b"TIT2", &text));
+    }
+    id3v24_frame(b"CHAP", &body)
+}
+
+/// An MP3-style file: an ID3v2.4 tag holding `frames`, then some audio bytes.
+fn write_id3_file(path: &Path, frames: &[Vec<u8>]) {
+    let body: Vec<u8> = frames.concat();
+    let mut bytes = b"ID3\x04\x00\x00".to_vec();
+    bytes.extend_from_slice(&synchsafe(body.len() + 16));
+    bytes.extend(body);
+    bytes.extend_from_slice(&[0; 16]); // padding
+    bytes.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+    fs::write(path, bytes).expect("write mp3 failed");
+}
+
+fn mp4_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut b = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    b.extend_from_slice(name);
+    b.extend_from_slice(payload);
+    b
+}
+
+#[test]
+fn reads_id3v2_chap_frames() {
+    let dir = temp_dir("chapters_id3");
+    let path = dir.join("episode.mp3");
+    write_id3_file(
+        &path,
+        &[
+            id3v24_frame(b"TIT2", b"\x03Episode 12"),
+            // Deliberately out of order; chapters come back sorted.
+            chap_frame("ch1", 754_500, 1_800_000, Some("Interview")),
+            chap_frame("ch0", 0, 754_500, Some("Intro")),
+            chap_frame("ch2", 1_800_000, 2_400_000, None),
+        ],
+    );
+
+    let chapters = read_chapters(&path).expect("chapters should parse");
+    assert_eq!(
+        chapters,
+        vec![chapter(0.0, "Intro"), chapter(754.5, "Interview"), chapter(1800.0, "ch2")]
+    );
+
+    cleanup(&dir);
+}
+
+#[test]
+fn reads_mp4_nero_chapters() {
+    let dir = temp_dir("chapters_mp4");
+    let path = dir.join("book.m4a");
+
+    let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+    for (start, title) in [(0u64, "Chapter One"), (6_000_000_000, "Chapter Two")] {
+        chpl.extend_from_slice(&start.to_be_bytes());
+        chpl.push(title.len() as u8);
+        chpl.extend_from_slice(title.as_bytes());
+    }
+    let mut bytes = mp4_box(b"ftyp", b"M4B \0\0\0\0");
+    bytes.extend(mp4_box(b"moov", &[mp4_box(b"mvhd", &[0; 20]), mp4_box(b"udta", &mp4_box(b"chpl", &chpl))].concat()));
+    bytes.extend(mp4_box(b"mdat", &[0; 32]));
+    fs::write(&path, bytes).unwrap();
+
+    let chapters = read_chapters(&path).expect("chapters should parse");
+    assert_eq!(chapters, vec![chapter(0.0, "Chapter One"), chapter(600.0, "Chapter Two")]);
+
+    cleanup(&dir);
+}
+
+#[test]
+fn reads_vorbis_comment_chapters() {
+    let dir = temp_dir("chapters_flac");
+    let path = dir.join("book.flac");
+
+    // Minimal FLAC: the marker, STREAMINFO for 1s of 44.1kHz mono, then
+    // PADDING (lofty's writer expects one to be present).
+    let mut bytes = b"fLaC".to_vec();
+    bytes.extend_from_slice(&[0x00, 0, 0, 34]);
+    bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+    bytes.extend_from_slice(&[0x0a, 0xc4, 0x40, 0xf0, 0x00, 0x00, 0xac, 0x44]);
+    bytes.extend_from_slice(&[0; 16]);
+    bytes.extend_from_slice(&[0x81, 0, 0, 64]);
+    bytes.extend_from_slice(&[0; 64]);
+    fs::write(&path, bytes).unwrap();
+
+    let mut tag = Tag::new(TagType::VorbisComments);
+    for (key, value) in [
+        ("CHAPTER001", "00:00:00.000"),
+        ("CHAPTER001NAME", "Opening"),
+        ("CHAPTER002", "01:02:03.500"),
+        ("CHAPTER002NAME", "Finale"),
+    ] {
+        tag.insert_unchecked(TagItem::new(ItemKey::Unknown(key.to_string()), ItemValue::Text(value.to_string())));
+    }
+    tag.save_to_path(&path).expect("tag write failed");
+
+    let chapters = read_chapters(&path).expect("chapters should parse");
+    assert_eq!(chapters, vec![chapter(0.0, "Opening"), chapter(3723.5, "Finale")]);
+
+    cleanup(&dir);
+}
+
+#[test]
+fn files_without_chapters_return_an_empty_list() {
+    let dir = temp_dir("chapters_none");
+
+    let mp3 = dir.join("song.mp3");
+    write_id3_file(&mp3, &[id3v24_frame(b"TIT2", b"\x03Just a song")]);
+    assert_eq!(read_chapters(&mp3).unwrap(), Vec::new());
+
+    let m4a = dir.join("song.m4a");
+    let mut bytes = mp4_box(b"ftyp", b"M4A \0\0\0\0");
+    bytes.extend(mp4_box(b"moov", &mp4_box(b"mvhd", &[0; 20])));
+    fs::write(&m4a, bytes).unwrap();
+    assert_eq!(read_chapters(&m4a).unwrap(), Vec::new());
+
+    assert!(read_chapters(&dir.join("missing.mp3")).is_err());
+
+    cleanup(&dir);
+}
+
+#[test]
+fn corrupt_sizes_do_not_overflow_or_over_allocate() {
+    let dir = temp_dir("chapters_corrupt");
+
+    // A 64-bit box size that would wrap past the end of the file.
+    let m4a = dir.join("broken.m4a");
+    let mut bytes = mp4_box(b"ftyp", b"M4A \0\0\0\0");
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes.extend_from_slice(b"free");
+    bytes.extend_from_slice(&u64::MAX.to_be_bytes());
+    bytes.extend(mp4_box(b"moov", &mp4_box(b"mvhd", &[0; 20])));
+    fs::write(&m4a, bytes).unwrap();
+    assert_eq!(read_chapters(&m4a).unwrap(), Vec::new());
+
+    // An ID3 header claiming a ~256 MB tag in a file of a few bytes.
+    let mp3 = dir.join("broken.mp3");
+    let mut bytes = b"ID3\x04\x00\x00\x7f\x7f\x7f\x7f".to_vec();
+    bytes.extend(id3v24_frame(b"TIT2", b"\x03Tiny"));
+    fs::write(&mp3, bytes).unwrap();
+    assert_eq!(read_chapters(&mp3).unwrap(), Vec::new());
+
+    cleanup(&dir);
+}
+
+#[test]
+fn chapter_navigation_from_a_position() {
+    let chapters = vec![chapter(0.0, "A"), chapter(60.0, "B"), chapter(120.0, "C")];
+
+    assert_eq!(next_chapter(&chapters, 10.0), Some(&chapters[1]));
+    assert_eq!(next_chapter(&chapters, 60.0), Some(&chapters[2]), "sitting on a marker moves on");
+    assert_eq!(next_chapter(&chapters, 130.0), None);
+
+    assert_eq!(previous_chapter(&chapters, 90.0), Some(&chapters[1]), "restarts the current chapter");
+    assert_eq!(previous_chapter(&chapters, 61.0), Some(&chapters[0]), "near the start goes back one");
+    assert_eq!(previous_chapter(&chapters, 1.0), Some(&chapters[0]));
+    assert_eq!(previous_chapter(&[], 10.0), None);
+}
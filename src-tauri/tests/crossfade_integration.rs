@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::crossfade::{should_crossfade_between, CrossfadePolicy, CROSSFADE_POLICY_KEY};
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+fn add(db: &Database, id: &str, album: Option<&str>) {
+    db.add_track(&Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.flac", id),
+        name: format!("{}.flac", id),
+        title: None,
+        artist: None,
+        album: album.map(str::to_string),
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 300.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .expect("seed track insert should succeed");
+}
+
+#[test]
+fn only_between_albums_skips_the_fade_within_an_album() {
+    let db_path = temp_db_path("crossfade_policy");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    add(&db, "live_1", Some("Live at the Hall"));
+    add(&db, "live_2", Some("live at the hall "));
+    add(&db, "studio", Some("Studio Album"));
+    add(&db, "untagged", None);
+    let path = |id: &str| format!("C:/Music/{}.flac", id);
+    let fades = |from: &str, to: &str| should_crossfade_between(&db, &path(from), &path(to)).unwrap();
+
+    assert_eq!(CrossfadePolicy::load(&db), CrossfadePolicy::Always);
+    assert!(fades("live_1", "live_2"), "the default crossfades every transition");
+
+    db.set_json_setting(CROSSFADE_POLICY_KEY, &CrossfadePolicy::OnlyBetweenAlbums).unwrap();
+    assert!(!fades("live_1", "live_2"), "same album is a gapless swap");
+    assert!(fades("live_2", "studio"), "album change crossfades");
+    assert!(fades("untagged", "live_1"));
+    assert!(fades("live_1", "not_in_library"));
+
+    db.set_json_setting(CROSSFADE_POLICY_KEY, &CrossfadePolicy::Never).unwrap();
+    assert!(!fades("live_2", "studio"));
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn untagged_tracks_are_never_the_same_album() {
+    let policy = CrossfadePolicy::OnlyBetweenAlbums;
+    assert!(!policy.should_crossfade(Some("A"), Some("a")));
+    assert!(policy.should_crossfade(Some("A"), Some("B")));
+    assert!(policy.should_crossfade(None, None));
+    assert!(policy.should_crossfade(Some(""), Some("")));
+}
@@ -83,3 +83,109 @@ fn playlist_crud_and_membership_roundtrip() {
     drop(db);
     cleanup_db_files(&db_path);
 }
+
+#[test]
+fn cleanup_orphans_removes_entries_for_deleted_tracks() {
+    let path = temp_db_path("cleanup_orphans");
+    let db = Database::new(&path).expect("db init should succeed");
+
+    db.add_track(&sample_track("kept", "C:/Music/kept.mp3")).unwrap();
+    let playlist = db.create_playlist("Mix").unwrap();
+    db.add_track_to_playlist(&playlist, "kept", 0).unwrap();
+    db.set_album_art("kept", &[1, 2, 3]).unwrap();
+
+    // Simulate a delete path that skipped the cascade: a second connection
+    // without foreign key enforcement leaves rows pointing at a missing track.
+    {
+        let raw = rusqlite::Connection::open(&path).unwrap();
+        raw.execute_batch("PRAGMA foreign_keys=OFF;").unwrap();
+        raw.execute(
+            "INSERT INTO playlist_tracks (playlist_id, track_id, position) VALUES (?1, 'gone', 1)",
+            [&playlist],
+        )
+        .unwrap();
+        raw.execute("INSERT INTO track_album_art (track_id, data) VALUES ('gone', x'00')", []).unwrap();
+    }
+    let removed = db.cleanup_orphans().expect("cleanup should succeed");
+    assert_eq!(removed.playlist_tracks, 1);
+    assert_eq!(removed.album_art, 1);
+
+    let remaining = db.get_playlist_tracks(&playlist).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, "kept");
+    assert_eq!(db.get_album_art("kept").unwrap(), Some(vec![1, 2, 3]));
+
+    // Nothing left to remove on a second pass.
+    assert_eq!(db.cleanup_orphans().unwrap().playlist_tracks, 0);
+
+    drop(db);
+    cleanup_db_files(&path);
+}
+
+#[test]
+fn removing_a_track_cascades_to_playlists() {
+    let path = temp_db_path("fk_cascade");
+    let db = Database::new(&path).expect("db init should succeed");
+
+    {
+        let conn = db.conn.lock().unwrap();
+        let journal_mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(journal_mode, "wal");
+        let foreign_keys: i64 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert_eq!(foreign_keys, 1, "cascades need foreign keys on");
+    }
+
+    db.add_track(&sample_track("doomed", "C:/Music/doomed.mp3")).unwrap();
+    db.add_track(&sample_track("kept", "C:/Music/kept.mp3")).unwrap();
+    let playlist = db.create_playlist("Mix").unwrap();
+    db.add_track_to_playlist(&playlist, "doomed", 0).unwrap();
+    db.add_track_to_playlist(&playlist, "kept", 1).unwrap();
+
+    db.remove_track("doomed").unwrap();
+
+    let rows: i64 = db
+        .conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM playlist_tracks WHERE track_id = 'doomed'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(rows, 0, "the foreign key cascade removes the membership");
+    let remaining = db.get_playlist_tracks(&playlist).unwrap();
+    assert_eq!(remaining.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), ["kept"]);
+
+    drop(db);
+    cleanup_db_files(&path);
+}
+
+#[test]
+fn missing_playlist_files_only_lists_that_playlists_missing_tracks() {
+    let db_path = temp_db_path("playlist_missing_files");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    let dir = std::env::temp_dir().join(format!("vplayer_integration_playlist_files_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let present = dir.join("present.mp3");
+    std::fs::write(&present, b"audio").unwrap();
+    let missing = dir.join("missing.mp3");
+
+    let present_track = sample_track("present", present.to_str().unwrap());
+    let missing_track = sample_track("missing", missing.to_str().unwrap());
+    let elsewhere_track = sample_track("elsewhere", dir.join("also-missing.mp3").to_str().unwrap());
+    for track in [&present_track, &missing_track, &elsewhere_track] {
+        db.add_track(track).unwrap();
+    }
+    let playlist_id = db.create_playlist("Checked").unwrap();
+    db.add_track_to_playlist(&playlist_id, "present", 0).unwrap();
+    db.add_track_to_playlist(&playlist_id, "missing", 1).unwrap();
+    let other_id = db.create_playlist("Other").unwrap();
+    db.add_track_to_playlist(&other_id, "elsewhere", 0).unwrap();
+
+    assert_eq!(
+        db.get_missing_playlist_files(&playlist_id).unwrap(),
+        vec![("missing".to_string(), missing_track.path.clone())]
+    );
+    assert!(db.get_missing_playlist_files("no_such_playlist").unwrap().is_empty());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+    let _ = std::fs::remove_dir_all(&dir);
+}
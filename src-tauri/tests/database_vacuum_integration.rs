@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::database_maintenance::{AutoVacuumSettings, AUTO_VACUUM_KEY};
+use vplayer::scanner::Track;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+#[test]
+fn auto_vacuum_runs_only_when_enabled_and_over_threshold() {
+    let db_path = temp_db_path("auto_vacuum");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    assert_eq!(AutoVacuumSettings::load(&db), AutoVacuumSettings::default());
+    assert!(!AutoVacuumSettings::default().enabled, "auto-vacuum is opt-in");
+
+    let settings = AutoVacuumSettings { enabled: true, threshold_bytes: 1_000_000 };
+    db.set_json_setting(AUTO_VACUUM_KEY, &settings).unwrap();
+    assert_eq!(AutoVacuumSettings::load(&db), settings);
+
+    // Stubbed sizes around the threshold.
+    assert!(!settings.should_vacuum(999_999));
+    assert!(!settings.should_vacuum(1_000_000));
+    assert!(settings.should_vacuum(1_000_001));
+    assert!(!AutoVacuumSettings { enabled: false, ..settings }.should_vacuum(u64::MAX));
+
+    assert_eq!(db.auto_vacuum(&settings, 500_000).unwrap(), None);
+
+    // Grow the file, free the rows, then vacuum with a size over the threshold.
+    for i in 0..500 {
+        db.add_track(&Track {
+            id: format!("t{}", i),
+            path: format!("C:/Music/{}-{}.mp3", i, "x".repeat(200)),
+            name: format!("{}.mp3", i),
+            title: Some("y".repeat(200)),
+            artist: None,
+            album: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            duration: 200.0,
+            date_added: 0,
+            rating: 0,
+            play_count: 0,
+            last_played: 0,
+        })
+        .expect("seed track insert should succeed");
+    }
+    db.conn.lock().unwrap().execute("DELETE FROM tracks", []).unwrap();
+    let before = db.size_bytes().unwrap();
+
+    let reclaimed = db
+        .auto_vacuum(&settings, 2_000_000)
+        .expect("vacuum should succeed")
+        .expect("vacuum should run over the threshold");
+    assert!(reclaimed > 0, "freed pages should be reclaimed");
+    assert_eq!(db.size_bytes().unwrap(), before - reclaimed);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
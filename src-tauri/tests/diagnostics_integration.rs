@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::diagnostics::{AudioDiagnostics, Diagnostics};
+use vplayer::scanner::Track;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+fn add(db: &Database, id: &str) {
+    db.add_track(&Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.flac", id),
+        name: format!("{}.flac", id),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 300.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .expect("add track should succeed");
+}
+
+#[test]
+fn diagnostics_cover_app_os_and_database() {
+    let db_path = temp_db_path("diagnostics");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    add(&db, "one");
+    add(&db, "two");
+
+    // The host may have no devices in CI; only the host name is guaranteed.
+    let audio = AudioDiagnostics::from_host();
+    assert!(!audio.host.is_empty());
+
+    let diagnostics = Diagnostics::collect(&db, audio).expect("collect should succeed");
+    assert_eq!(diagnostics.app_version, env!("CARGO_PKG_VERSION"));
+    assert!(!diagnostics.os.is_empty());
+    assert!(!diagnostics.arch.is_empty());
+
+    let database = &diagnostics.database;
+    let path = PathBuf::from(database.path.as_deref().expect("file-backed database has a path"));
+    assert_eq!(path.file_name(), db_path.file_name());
+    assert!(database.size_bytes > 0);
+    assert!(database.schema_version > 0);
+    assert_eq!(database.track_count, 2);
+
+    let json = serde_json::to_value(&diagnostics).unwrap();
+    assert_eq!(json["database"]["trackCount"], 2);
+    assert!(json["audio"]["outputDevice"].is_null());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::fade_durations::{FadeDurations, FADE_DURATIONS_KEY, MAX_FADE_SECS};
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+#[test]
+fn each_fade_is_set_and_clamped_independently() {
+    let db_path = temp_db_path("fade_durations");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    let defaults = FadeDurations::default();
+    assert_eq!(FadeDurations::load(&db), defaults);
+
+    let after_sleep = FadeDurations::set_sleep_fade_secs(&db, 45.0).unwrap();
+    assert_eq!(after_sleep.sleep_fade_secs, MAX_FADE_SECS);
+    assert_eq!(after_sleep.crossfade_secs, defaults.crossfade_secs);
+
+    let after_crossfade = FadeDurations::set_crossfade_secs(&db, -2.0).unwrap();
+    assert_eq!(after_crossfade.crossfade_secs, 0.0);
+    assert_eq!(after_crossfade.sleep_fade_secs, MAX_FADE_SECS, "sleep fade is untouched");
+
+    let after_sleep = FadeDurations::set_sleep_fade_secs(&db, 12.5).unwrap();
+    assert_eq!(after_sleep.sleep_fade_secs, 12.5);
+    assert_eq!(after_sleep.crossfade_secs, 0.0, "crossfade is untouched");
+
+    // Non-numbers are rejected and change nothing.
+    assert!(FadeDurations::set_crossfade_secs(&db, f32::NAN).is_err());
+    assert!(FadeDurations::set_sleep_fade_secs(&db, f32::INFINITY).is_err());
+    assert_eq!(FadeDurations::load(&db), after_sleep);
+
+    // Persisted across reopen.
+    drop(db);
+    let db = Database::new(&db_path).expect("db reopen should succeed");
+    assert_eq!(FadeDurations::load(&db), after_sleep);
+
+    // Out-of-range stored values are clamped on load.
+    db.set_json_setting(FADE_DURATIONS_KEY, &serde_json::json!({ "sleepFadeSecs": 99.0, "crossfadeSecs": 4.0 }))
+        .unwrap();
+    let loaded = FadeDurations::load(&db);
+    assert_eq!(loaded.sleep_fade_secs, MAX_FADE_SECS);
+    assert_eq!(loaded.crossfade_secs, 4.0);
+    db.set_json_setting(FADE_DURATIONS_KEY, &serde_json::json!({ "crossfadeSecs": 45.0 })).unwrap();
+    assert_eq!(FadeDurations::load(&db).crossfade_secs, MAX_FADE_SECS);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
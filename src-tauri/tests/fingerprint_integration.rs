@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::fingerprint::{fingerprint_file, fingerprint_track};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Write a 16-bit mono WAV playing each frequency in `notes` for a quarter second.
+fn write_melody_wav(path: &Path, notes: &[f32]) {
+    let sample_rate = 22050u32;
+    let per_note = sample_rate / 4;
+    let samples = per_note * notes.len() as u32;
+    let data_len = samples * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for n in 0..samples {
+        let freq = notes[(n / per_note) as usize];
+        let t = n as f32 / sample_rate as f32;
+        let v = (2.0 * std::f32::consts::PI * freq * t).sin() * 0.5;
+        bytes.extend_from_slice(&((v * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+const TUNE_A: [f32; 8] = [440.0, 494.0, 523.0, 587.0, 659.0, 698.0, 784.0, 880.0];
+const TUNE_B: [f32; 8] = [1568.0, 392.0, 1175.0, 330.0, 2093.0, 523.0, 988.0, 1319.0];
+
+#[test]
+fn copies_match_and_different_audio_differs() {
+    let dir = temp_dir("fingerprint");
+    let original = dir.join("original.wav");
+    let copy = dir.join("copy.wav");
+    let other = dir.join("other.wav");
+    write_melody_wav(&original, &TUNE_A);
+    fs::copy(&original, &copy).unwrap();
+    write_melody_wav(&other, &TUNE_B);
+
+    let a = fingerprint_file(original.to_str().unwrap()).unwrap();
+    let b = fingerprint_file(copy.to_str().unwrap()).unwrap();
+    let c = fingerprint_file(other.to_str().unwrap()).unwrap();
+
+    assert!(!a.is_empty());
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn fingerprint_is_cached_until_the_file_changes() {
+    let dir = temp_dir("fingerprint_cache");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("song.wav");
+    write_melody_wav(&path, &TUNE_A);
+    let path_str = path.to_str().unwrap();
+
+    db.add_track(&Track {
+        id: "t1".to_string(),
+        path: path_str.to_string(),
+        name: "song.wav".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 2.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+
+    let first = fingerprint_track(&db, path_str).unwrap();
+    let (stored, mtime): (String, i64) = db
+        .conn
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT fingerprint, fingerprint_mtime FROM tracks WHERE id = 't1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(stored, first);
+
+    // A cache hit returns the stored value without decoding.
+    db.conn
+        .lock()
+        .unwrap()
+        .execute("UPDATE tracks SET fingerprint = 'cafe' WHERE id = 't1'", [])
+        .unwrap();
+    assert_eq!(fingerprint_track(&db, path_str).unwrap(), "cafe");
+
+    // A different mtime invalidates it.
+    db.conn
+        .lock()
+        .unwrap()
+        .execute(
+            "UPDATE tracks SET fingerprint_mtime = ?1 WHERE id = 't1'",
+            [mtime - 1],
+        )
+        .unwrap();
+    assert_eq!(fingerprint_track(&db, path_str).unwrap(), first);
+
+    let _ = fs::remove_dir_all(&dir);
+}
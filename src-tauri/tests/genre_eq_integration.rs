@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::genre_eq::{find_preset, GenreEq, GenreEqSettings, GENRE_EQ_KEY};
+use vplayer::scanner::Track;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+fn add(db: &Database, id: &str, genre: Option<&str>) -> String {
+    let path = format!("C:/Music/{}.flac", id);
+    db.add_track(&Track {
+        id: id.to_string(),
+        path: path.clone(),
+        name: format!("{}.flac", id),
+        title: None,
+        artist: None,
+        album: None,
+        genre: genre.map(str::to_string),
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 300.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .expect("add track should succeed");
+    path
+}
+
+fn settings(enabled: bool) -> GenreEqSettings {
+    GenreEqSettings {
+        enabled,
+        presets: BTreeMap::from([("Jazz".to_string(), "JAZZ".to_string()), ("rock".to_string(), "Rock".to_string())]),
+    }
+}
+
+#[test]
+fn loading_a_jazz_track_applies_the_jazz_preset() {
+    let db_path = temp_db_path("genre_eq");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    let jazz = add(&db, "jazz", Some("jazz"));
+    let rock = add(&db, "rock", Some("Rock"));
+    let untagged = add(&db, "untagged", None);
+    db.set_json_setting(GENRE_EQ_KEY, &settings(true)).unwrap();
+
+    let jazz_bands = find_preset("JAZZ").unwrap().bands.to_vec();
+    let rock_bands = find_preset("ROCK").unwrap().bands.to_vec();
+    let user_bands = vec![1.0; 10];
+    let genre_eq = GenreEq::new();
+
+    // Genres match case-insensitively; the user's EQ is set aside.
+    assert_eq!(genre_eq.bands_for_track(&db, &jazz, &user_bands), Some(jazz_bands.clone()));
+    assert_eq!(genre_eq.bands_for_track(&db, &rock, &jazz_bands), Some(rock_bands.clone()));
+    // An unmapped track gets the user's EQ back, and then nothing changes.
+    assert_eq!(genre_eq.bands_for_track(&db, &untagged, &rock_bands), Some(user_bands.clone()));
+    assert_eq!(genre_eq.bands_for_track(&db, &untagged, &user_bands), None);
+
+    // A manual change during a preset track sticks past the track.
+    assert_eq!(genre_eq.bands_for_track(&db, &jazz, &user_bands), Some(jazz_bands.clone()));
+    genre_eq.manual_change();
+    let manual_bands = vec![-2.0; 10];
+    assert_eq!(genre_eq.bands_for_track(&db, &untagged, &manual_bands), None);
+    // ...but the mapping applies again from the next mapped track.
+    assert_eq!(genre_eq.bands_for_track(&db, &jazz, &manual_bands), Some(jazz_bands));
+    assert_eq!(genre_eq.bands_for_track(&db, &untagged, &[0.0; 10]), Some(manual_bands));
+
+    // Presets don't fit other band layouts.
+    let thirty_one = vec![0.0; 31];
+    assert_eq!(genre_eq.bands_for_track(&db, &jazz, &thirty_one), None);
+
+    // Disabled: nothing is applied.
+    db.set_json_setting(GENRE_EQ_KEY, &settings(false)).unwrap();
+    assert_eq!(GenreEq::new().bands_for_track(&db, &jazz, &user_bands), None);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn unknown_presets_are_rejected() {
+    assert!(settings(true).validate().is_ok());
+    let mut bad = settings(true);
+    bad.presets.insert("Metal".to_string(), "THRASH".to_string());
+    assert!(bad.validate().is_err());
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::key_detection::{analyze_track_key, Key, KeyDetector, Mode};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+const SAMPLE_RATE: u32 = 22050;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn midi_freq(note: u8) -> f32 {
+    440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// Each chord (as MIDI notes) held for one second, with a little second
+/// harmonic on every note.
+fn chord_sequence(chords: &[&[u8]]) -> Vec<f32> {
+    let per_chord = SAMPLE_RATE as usize;
+    let mut samples = Vec::with_capacity(per_chord * chords.len());
+    for chord in chords {
+        for n in 0..per_chord {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            let v: f32 = chord
+                .iter()
+                .map(|&note| {
+                    let phase = 2.0 * std::f32::consts::PI * midi_freq(note) * t;
+                    phase.sin() + 0.3 * (2.0 * phase).sin()
+                })
+                .sum();
+            samples.push(v * 0.2);
+        }
+    }
+    samples
+}
+
+/// I–IV–V–I in C major, twice.
+fn c_major_cadence() -> Vec<f32> {
+    let (c, f, g): (&[u8], &[u8], &[u8]) = (&[48, 64, 67, 72], &[53, 65, 69, 72], &[55, 62, 67, 71]);
+    chord_sequence(&[c, f, g, c, c, f, g, c])
+}
+
+fn detect(samples: &[f32]) -> Key {
+    let mut detector = KeyDetector::new(SAMPLE_RATE);
+    for chunk in samples.chunks(1152) {
+        detector.push(chunk);
+    }
+    detector.finish().unwrap()
+}
+
+fn write_wav(path: &Path, samples: &[f32]) {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &v in samples {
+        bytes.extend_from_slice(&((v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn c_major_chords_are_detected_as_c_major() {
+    let key = detect(&c_major_cadence());
+    assert_eq!(key, Key { tonic: 0, mode: Mode::Major }, "detected {}", key.name());
+    assert_eq!(key.name(), "C major");
+    assert_eq!(key.camelot(), "8B");
+}
+
+#[test]
+fn minor_progression_is_detected_as_minor() {
+    // i–iv–V–i in A minor.
+    let (am, dm, e): (&[u8], &[u8], &[u8]) = (&[45, 60, 64, 69], &[50, 62, 65, 69], &[52, 59, 64, 68]);
+    let key = detect(&chord_sequence(&[am, dm, e, am, am, dm, e, am]));
+    assert_eq!(key.name(), "A minor");
+    assert_eq!(key.camelot(), "8A");
+}
+
+#[test]
+fn camelot_codes_follow_the_circle_of_fifths() {
+    let cases = [
+        (7, Mode::Major, "G major", "9B"),
+        (11, Mode::Major, "B major", "1B"),
+        (5, Mode::Major, "F major", "7B"),
+        (4, Mode::Minor, "E minor", "9A"),
+        (8, Mode::Minor, "Ab minor", "1A"),
+        (2, Mode::Minor, "D minor", "7A"),
+    ];
+    for (tonic, mode, name, code) in cases {
+        let key = Key { tonic, mode };
+        assert_eq!(key.name(), name);
+        assert_eq!(key.camelot(), code, "{}", name);
+    }
+}
+
+#[test]
+fn silence_has_no_key() {
+    let mut detector = KeyDetector::new(SAMPLE_RATE);
+    detector.push(&vec![0.0; SAMPLE_RATE as usize * 5]);
+    assert!(detector.finish().is_err());
+}
+
+#[test]
+fn analyzed_key_is_stored_on_the_track() {
+    let dir = temp_dir("key_detection");
+    let db = Database::new(&dir.join("test.db")).unwrap();
+    let path = dir.join("cadence.wav");
+    write_wav(&path, &c_major_cadence());
+    let path_str = path.to_str().unwrap();
+
+    db.add_track(&Track {
+        id: "t1".to_string(),
+        path: path_str.to_string(),
+        name: "cadence.wav".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 8.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+
+    let info = analyze_track_key(&db, path_str).unwrap();
+    assert_eq!(info.key, "C major");
+    assert_eq!(info.camelot, "8B");
+    let stored: String = db
+        .conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT musical_key FROM tracks WHERE id = 't1'", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(stored, "C major");
+
+    let _ = fs::remove_dir_all(&dir);
+}
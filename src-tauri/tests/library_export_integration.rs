@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::library_export::{export_library, import_library, ExportFormat, LibraryImportSummary};
+use vplayer::scanner::Track;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn track(id: &str, path: &str, title: Option<&str>) -> Track {
+    Track {
+        id: id.to_string(),
+        path: path.to_string(),
+        name: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+        title: title.map(str::to_string),
+        artist: Some("Artist".to_string()),
+        album: Some("Album".to_string()),
+        genre: None,
+        year: Some(1999),
+        track_number: Some(3),
+        disc_number: None,
+        duration: 245.5,
+        date_added: 1_700_000_000_000,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+/// Three tracks whose titles exercise CSV quoting, with ratings and plays.
+fn seed_library(db: &Database) {
+    db.add_track(&track("t1", "C:/Music/plain.mp3", Some("Plain"))).unwrap();
+    db.add_track(&track("t2", "C:/Music/Comma, Inc/quoted.mp3", Some("Say \"Hi\", World"))).unwrap();
+    db.add_track(&track("t3", "C:/Music/multi.mp3", Some("Line one\nLine two"))).unwrap();
+    db.set_track_rating("t1", 5).unwrap();
+    db.set_track_rating("t2", 3).unwrap();
+    db.increment_play_count("t2").unwrap();
+    db.increment_play_count("t2").unwrap();
+}
+
+fn by_path(db: &Database) -> Vec<Track> {
+    let mut tracks = db.get_all_tracks().unwrap();
+    tracks.sort_by(|a, b| a.path.cmp(&b.path));
+    tracks
+}
+
+fn assert_same_tracks(a: &[Track], b: &[Track]) {
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(b) {
+        assert_eq!(
+            (&x.id, &x.path, &x.name, &x.title, &x.artist, &x.album, &x.genre),
+            (&y.id, &y.path, &y.name, &y.title, &y.artist, &y.album, &y.genre)
+        );
+        assert_eq!((x.year, x.track_number, x.disc_number), (y.year, y.track_number, y.disc_number));
+        assert_eq!((x.duration, x.date_added), (y.duration, y.date_added));
+        assert_eq!((x.rating, x.play_count, x.last_played), (y.rating, y.play_count, y.last_played));
+    }
+}
+
+#[test]
+fn library_round_trips_through_csv_and_json() {
+    let dir = temp_dir("library_export");
+    let source = Database::new(&dir.join("source.db")).unwrap();
+    seed_library(&source);
+    let original = by_path(&source);
+
+    for (format, file) in [(ExportFormat::Csv, "library.csv"), (ExportFormat::Json, "library.json")] {
+        let export_path = dir.join(file);
+        assert_eq!(export_library(&source, &export_path, format).unwrap(), 3);
+
+        let target = Database::new(&dir.join(format!("{}.db", file))).unwrap();
+        let summary = import_library(&target, &export_path, format, false).unwrap();
+        assert_eq!(summary, LibraryImportSummary { added: 3, updated: 0 });
+        assert_same_tracks(&by_path(&target), &original);
+
+        // Importing the same file again changes nothing.
+        let again = import_library(&target, &export_path, format, false).unwrap();
+        assert_eq!(again, LibraryImportSummary { added: 0, updated: 3 });
+        assert_same_tracks(&by_path(&target), &original);
+    }
+
+    drop(source);
+    cleanup(&dir);
+}
+
+#[test]
+fn import_keeps_existing_ratings_unless_overwriting() {
+    let dir = temp_dir("library_import_merge");
+    let source = Database::new(&dir.join("source.db")).unwrap();
+    seed_library(&source);
+    let export_path = dir.join("library.csv");
+    export_library(&source, &export_path, ExportFormat::Csv).unwrap();
+
+    let target = Database::new(&dir.join("target.db")).unwrap();
+    let mut local = track("local1", "C:/Music/plain.mp3", None);
+    local.name = "My Plain Song".to_string();
+    local.genre = Some("Rock".to_string());
+    target.add_track(&local).unwrap();
+    target.set_track_rating("local1", 2).unwrap();
+
+    import_library(&target, &export_path, ExportFormat::Csv, false).unwrap();
+    let merged = target.get_track_by_path("C:/Music/plain.mp3").unwrap().unwrap();
+    assert_eq!(merged.id, "local1", "rows are matched by path, not id");
+    assert_eq!(merged.rating, 2, "existing rating is kept");
+    assert_eq!(merged.name, "My Plain Song", "existing name is kept");
+    assert_eq!(merged.title.as_deref(), Some("Plain"), "missing title is filled in");
+    assert_eq!(merged.genre.as_deref(), Some("Rock"));
+
+    import_library(&target, &export_path, ExportFormat::Csv, true).unwrap();
+    let overwritten = target.get_track_by_path("C:/Music/plain.mp3").unwrap().unwrap();
+    assert_eq!(overwritten.rating, 5);
+    assert_eq!(overwritten.genre, None);
+
+    drop(source);
+    drop(target);
+    cleanup(&dir);
+}
@@ -108,3 +108,61 @@ fn get_filtered_tracks_respects_artist_search_and_rating() {
     drop(db);
     cleanup_db_files(&db_path);
 }
+
+#[test]
+fn suspect_tracks_have_zero_or_implausible_durations() {
+    let db_path = temp_db_path("suspect_tracks");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    for (id, duration) in [("normal", 180.0), ("zero", 0.0), ("audiobook", 30.0 * 3600.0), ("absurd", 1.0e9)] {
+        let mut track = sample_track(id, &format!("C:/Music/{}.mp3", id), id, "Artist", 0);
+        track.duration = duration;
+        db.add_track(&track).expect("seed track failed");
+    }
+
+    let suspect: Vec<String> = db.get_suspect_tracks().unwrap().into_iter().map(|t| t.id).collect();
+    assert_eq!(suspect, vec!["absurd", "zero"]);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn genre_and_year_facets_count_tracks_and_bucket_untagged() {
+    let db_path = temp_db_path("facets");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    let seeds = [
+        ("a", Some("Rock"), Some(1999)),
+        ("b", Some("rock"), Some(2024)),
+        ("c", Some("Rock"), Some(2024)),
+        ("d", Some("Jazz"), None),
+        ("e", Some("  "), Some(1999)),
+        ("f", None, Some(2010)),
+        ("g", Some("ambient"), Some(0)),
+    ];
+    for (id, genre, year) in seeds {
+        let mut track = sample_track(id, &format!("C:/Music/{}.mp3", id), id, "Artist", 0);
+        track.genre = genre.map(str::to_string);
+        track.year = year;
+        db.add_track(&track).expect("seed track failed");
+    }
+
+    let genres = db.get_genres().expect("genres query should succeed");
+    assert_eq!(
+        genres,
+        vec![
+            ("ambient".to_string(), 1),
+            ("Jazz".to_string(), 1),
+            ("Rock".to_string(), 2),
+            ("rock".to_string(), 1),
+            ("Unknown".to_string(), 2),
+        ]
+    );
+
+    let years = db.get_years().expect("years query should succeed");
+    assert_eq!(years, vec![(2024, 2), (2010, 1), (1999, 2), (0, 2)]);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
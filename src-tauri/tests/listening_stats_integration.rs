@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+const HOUR_MS: i64 = 60 * 60 * 1000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+/// Local noon today, in UNIX millis. Anchoring seeds at noon keeps whole-day
+/// offsets on the intended local date regardless of the host time zone.
+fn local_noon_today() -> i64 {
+    let conn = Connection::open_in_memory().expect("in-memory db open failed");
+    conn.query_row(
+        "SELECT CAST(strftime('%s', date('now', 'localtime'), '+12 hours', 'utc') AS INTEGER) * 1000",
+        [],
+        |row| row.get(0),
+    )
+    .expect("local noon query failed")
+}
+
+fn sample_track(id: &str, duration: f64) -> Track {
+    Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.mp3", id),
+        name: format!("{}.mp3", id),
+        title: Some(id.to_string()),
+        artist: Some("Stats Artist".to_string()),
+        album: Some("Stats Album".to_string()),
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+#[test]
+fn listening_stats_over_seeded_history() {
+    let db_path = temp_db_path("listening_stats");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    let noon = local_noon_today();
+
+    // Today: two listens at noon and one at 14:00 → 3 + 3 + 4 = 10 minutes.
+    db.add_play_history("a", noon, 180.0).expect("seed");
+    db.add_play_history("b", noon + 60_000, 180.0).expect("seed");
+    db.add_play_history("c", noon + 2 * HOUR_MS, 240.0).expect("seed");
+    // Yesterday and the day before keep the streak going.
+    db.add_play_history("a", noon - DAY_MS, 120.0).expect("seed");
+    db.add_play_history("a", noon - 2 * DAY_MS, 120.0).expect("seed");
+    // Gap on day -3, then an older listen that is still within the week.
+    db.add_play_history("b", noon - 4 * DAY_MS, 60.0).expect("seed");
+    // Outside the seven-day window.
+    db.add_play_history("c", noon - 10 * DAY_MS, 600.0).expect("seed");
+
+    let stats = db
+        .get_listening_stats_at(noon + 3 * HOUR_MS)
+        .expect("stats should succeed");
+
+    assert!((stats.minutes_today - 10.0).abs() < 1e-9, "today: {}", stats.minutes_today);
+    assert!((stats.minutes_this_week - 15.0).abs() < 1e-9, "week: {}", stats.minutes_this_week);
+    assert_eq!(stats.current_streak_days, 3);
+    assert_eq!(stats.hourly_listens.len(), 24);
+    assert_eq!(stats.hourly_listens[12], 6);
+    assert_eq!(stats.hourly_listens[14], 1);
+    assert_eq!(stats.hourly_listens.iter().sum::<u32>(), 7);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn streak_survives_until_today_has_no_listens_yet() {
+    let db_path = temp_db_path("listening_streak");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    let noon = local_noon_today();
+
+    db.add_play_history("a", noon - DAY_MS, 60.0).expect("seed");
+    db.add_play_history("a", noon - 2 * DAY_MS, 60.0).expect("seed");
+
+    let stats = db.get_listening_stats_at(noon).expect("stats should succeed");
+    assert_eq!(stats.minutes_today, 0.0);
+    assert_eq!(stats.current_streak_days, 2);
+
+    // Two days later with nothing played in between, the streak is broken.
+    let stats = db
+        .get_listening_stats_at(noon + 2 * DAY_MS)
+        .expect("stats should succeed");
+    assert_eq!(stats.current_streak_days, 0);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn increment_play_count_records_history() {
+    let db_path = temp_db_path("listening_increment");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    db.add_track(&sample_track("stats_track", 150.0)).expect("track insert should succeed");
+    db.increment_play_count("stats_track").expect("increment should succeed");
+
+    let stats = db.get_listening_stats().expect("stats should succeed");
+    assert!((stats.minutes_today - 2.5).abs() < 1e-9);
+    assert_eq!(stats.current_streak_days, 1);
+    assert_eq!(stats.hourly_listens.iter().sum::<u32>(), 1);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn unplayed_tracks_exclude_anything_played() {
+    let db_path = temp_db_path("unplayed");
+    let db = Database::new(&db_path).expect("db init failed");
+
+    for (i, id) in ["old_unplayed", "played", "new_unplayed", "played_twice"].iter().enumerate() {
+        let mut track = sample_track(id, 180.0);
+        track.date_added = 1_000 + i as i64;
+        db.add_track(&track).expect("seed track failed");
+    }
+    db.increment_play_count("played").unwrap();
+    db.increment_play_count("played_twice").unwrap();
+    db.increment_play_count("played_twice").unwrap();
+
+    let unplayed: Vec<String> = db
+        .get_unplayed_tracks(10)
+        .expect("unplayed query failed")
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+    assert_eq!(unplayed, vec!["old_unplayed", "new_unplayed"]);
+    assert_eq!(db.count_unplayed_tracks().unwrap(), 2);
+    assert_eq!(db.get_unplayed_tracks(1).unwrap().len(), 1);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
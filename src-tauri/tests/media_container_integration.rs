@@ -0,0 +1,193 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use rodio::Source;
+use vplayer::media_container::{is_video_container, probe_audio_track};
+use vplayer::scanner::Scanner;
+
+/// One silent AAC-LC frame for a mono channel (1024 samples).
+const SILENT_AAC_FRAME: [u8; 4] = [0x01, 0x40, 0x20, 0x07];
+const FRAMES: u32 = 43;
+const SAMPLE_RATE: u32 = 44_100;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn mp4_box(name: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut b = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+    b.extend_from_slice(name);
+    b.extend_from_slice(payload);
+    b
+}
+
+fn full_box(name: &[u8; 4], flags: u32, payload: &[u8]) -> Vec<u8> {
+    let mut body = flags.to_be_bytes().to_vec();
+    body.extend_from_slice(payload);
+    mp4_box(name, &body)
+}
+
+fn u32s(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_be_bytes()).collect()
+}
+
+const MATRIX: [u32; 9] = [0x0001_0000, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000];
+
+fn tkhd(track_id: u32, duration: u32, volume: u16) -> Vec<u8> {
+    let mut p = u32s(&[0, 0, track_id, 0, duration, 0, 0]);
+    p.extend_from_slice(&[0, 0, 0, 0]); // layer, alternate group
+    p.extend_from_slice(&volume.to_be_bytes());
+    p.extend_from_slice(&[0, 0]);
+    p.extend(u32s(&MATRIX));
+    p.extend(u32s(&[0, 0])); // width, height
+    full_box(b"tkhd", 3, &p)
+}
+
+fn mdia(handler: &[u8; 4], timescale: u32, duration: u32, stbl: Vec<u8>) -> Vec<u8> {
+    let mut mdhd = u32s(&[0, 0, timescale, duration]);
+    mdhd.extend_from_slice(&[0x55, 0xc4, 0, 0]); // language "und", quality
+    let mut hdlr = u32s(&[0]);
+    hdlr.extend_from_slice(handler);
+    hdlr.extend(u32s(&[0, 0, 0]));
+    hdlr.push(0);
+
+    let mut body = full_box(b"mdhd", 0, &mdhd);
+    body.extend(full_box(b"hdlr", 0, &hdlr));
+    body.extend(mp4_box(b"minf", &mp4_box(b"stbl", &stbl)));
+    mp4_box(b"mdia", &body)
+}
+
+fn sample_tables(entry: Vec<u8>, stts: &[u32], stsc: &[u32], stsz: &[u32], stco: &[u32]) -> Vec<u8> {
+    let mut stsd = u32s(&[1]);
+    stsd.extend(entry);
+    let mut stbl = full_box(b"stsd", 0, &stsd);
+    stbl.extend(full_box(b"stts", 0, &u32s(stts)));
+    stbl.extend(full_box(b"stsc", 0, &u32s(stsc)));
+    stbl.extend(full_box(b"stsz", 0, &u32s(stsz)));
+    stbl.extend(full_box(b"stco", 0, &u32s(stco)));
+    stbl
+}
+
+fn descriptor(tag: u8, payload: &[u8]) -> Vec<u8> {
+    let mut d = vec![tag, payload.len() as u8];
+    d.extend_from_slice(payload);
+    d
+}
+
+/// mp4a sample entry for mono AAC-LC at 44.1 kHz.
+fn mp4a_entry() -> Vec<u8> {
+    let mut dec_config = vec![0x40, 0x15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    dec_config.extend(descriptor(0x05, &[0x12, 0x08]));
+    let mut es = vec![0, 1, 0];
+    es.extend(descriptor(0x04, &dec_config));
+    es.extend(descriptor(0x06, &[0x02]));
+
+    let mut p = vec![0; 6];
+    p.extend_from_slice(&1u16.to_be_bytes()); // data reference index
+    p.extend_from_slice(&[0; 8]); // version, revision, vendor
+    p.extend_from_slice(&1u16.to_be_bytes()); // channels
+    p.extend_from_slice(&16u16.to_be_bytes()); // sample size
+    p.extend_from_slice(&[0; 4]);
+    p.extend(u32s(&[SAMPLE_RATE << 16]));
+    p.extend(full_box(b"esds", 0, &descriptor(0x03, &es)));
+    mp4_box(b"mp4a", &p)
+}
+
+/// An mp4 with a sample-less video track followed, optionally, by an AAC
+/// audio track of `FRAMES` silent frames.
+fn write_music_video(path: &Path, with_audio: bool) {
+    let audio_duration = FRAMES * 1024;
+    let ms = audio_duration * 1000 / SAMPLE_RATE;
+
+    let video = {
+        let avc1 = mp4_box(b"avc1", &[0; 78]);
+        let stbl = sample_tables(avc1, &[0], &[0], &[0, 0], &[0]);
+        let mut trak = tkhd(1, ms, 0);
+        trak.extend(mdia(b"vide", 1000, ms, stbl));
+        mp4_box(b"trak", &trak)
+    };
+
+    let build = |chunk_offset: u32| {
+        let mut mvhd = u32s(&[0, 0, 1000, ms, 0x0001_0000]);
+        mvhd.extend_from_slice(&[0x01, 0x00]);
+        mvhd.extend_from_slice(&[0; 10]);
+        mvhd.extend(u32s(&MATRIX));
+        mvhd.extend_from_slice(&[0; 24]);
+        mvhd.extend(u32s(&[3]));
+        let mut moov = full_box(b"mvhd", 0, &mvhd);
+        moov.extend(video.clone());
+        if with_audio {
+            let stbl = sample_tables(
+                mp4a_entry(),
+                &[1, FRAMES, 1024],
+                &[1, 1, FRAMES, 1],
+                &[SILENT_AAC_FRAME.len() as u32, FRAMES],
+                &[1, chunk_offset],
+            );
+            let mut trak = tkhd(2, ms, 0x0100);
+            trak.extend(mdia(b"soun", SAMPLE_RATE, audio_duration, stbl));
+            moov.extend(mp4_box(b"trak", &trak));
+        }
+
+        let mut bytes = mp4_box(b"ftyp", b"isom\0\0\x02\0isomiso2mp41");
+        bytes.extend(mp4_box(b"moov", &moov));
+        bytes
+    };
+
+    // The chunk offset points just past the mdat header, after the moov.
+    let header_len = build(0).len() as u32;
+    let mut bytes = build(header_len + 8);
+    let mdat: Vec<u8> = (0..FRAMES).flat_map(|_| SILENT_AAC_FRAME).collect();
+    bytes.extend(mp4_box(b"mdat", &mdat));
+    fs::write(path, bytes).expect("write mp4 failed");
+}
+
+#[test]
+fn plays_the_aac_track_of_a_music_video() {
+    let dir = temp_dir("music_video");
+    let path = dir.join("video.mp4");
+    write_music_video(&path, true);
+    assert!(is_video_container(&path));
+
+    let audio = probe_audio_track(&path).unwrap();
+    assert_eq!(audio.track_id, 1, "the video track (index 0) is skipped");
+    assert_eq!(audio.sample_rate, Some(SAMPLE_RATE));
+    let expected_secs = (FRAMES * 1024) as f64 / SAMPLE_RATE as f64;
+    assert!((audio.duration.unwrap() - expected_secs).abs() < 0.01);
+
+    let decoder = rodio::Decoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+    assert_eq!(decoder.sample_rate(), SAMPLE_RATE);
+    let samples = decoder.take(SAMPLE_RATE as usize / 2).collect::<Vec<f32>>();
+    assert_eq!(samples.len(), SAMPLE_RATE as usize / 2, "decodes frames from the audio track");
+    assert!(samples.iter().all(|s| s.abs() < 1e-3), "the frames are silent");
+
+    let track = Scanner::extract_track_info(&path).unwrap();
+    assert!((track.duration - expected_secs).abs() < 0.05, "duration was {}", track.duration);
+
+    cleanup(&dir);
+}
+
+#[test]
+fn video_without_audio_is_rejected_clearly() {
+    let dir = temp_dir("silent_video");
+    let path = dir.join("silent.m4v");
+    write_music_video(&path, false);
+
+    let err = probe_audio_track(&path).unwrap_err();
+    assert!(err.contains("no playable audio track"), "unexpected error: {}", err);
+    let err = Scanner::extract_track_info(&path).unwrap_err();
+    assert!(err.contains("no playable audio track"), "unexpected error: {}", err);
+
+    cleanup(&dir);
+}
@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use vplayer::database::Database;
+use vplayer::metadata_lookup::{
+    apply_candidate, lookup_candidates, musicbrainz_query, HttpClient, MetadataCandidate,
+};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+const MUSICBRAINZ_RESPONSE: &str = r#"{
+  "created": "2024-01-01T00:00:00.000Z",
+  "count": 2,
+  "offset": 0,
+  "recordings": [
+    {
+      "id": "b1a9c0e9-d987-4042-ae91-78d6a3267d69",
+      "score": 100,
+      "title": "Paranoid Android",
+      "length": 387000,
+      "artist-credit": [{ "name": "Radiohead", "joinphrase": "" }],
+      "first-release-date": "1997-05-21",
+      "releases": [{ "id": "r1", "title": "OK Computer", "date": "1997-05-21" }]
+    },
+    {
+      "id": "c2",
+      "score": 62,
+      "title": "Paranoid Android (live)",
+      "artist-credit": [
+        { "name": "Radiohead", "joinphrase": " feat. " },
+        { "name": "Guest", "joinphrase": "" }
+      ]
+    }
+  ]
+}"#;
+
+const ACOUSTID_RESPONSE: &str = r#"{
+  "status": "ok",
+  "results": [
+    {
+      "id": "acoustid-1",
+      "score": 0.94,
+      "recordings": [
+        {
+          "id": "rec-1",
+          "title": "Teardrop",
+          "artists": [{ "id": "a1", "name": "Massive Attack" }],
+          "releases": [{ "id": "rel-1", "title": "Mezzanine", "date": { "year": 1998, "month": 4 } }]
+        }
+      ]
+    }
+  ]
+}"#;
+
+/// URL prefix and the body (or error) served for it.
+type Canned = (&'static str, Result<&'static str, &'static str>);
+/// URL and query pairs of one request.
+type Request = (String, Vec<(String, String)>);
+
+/// Serves canned bodies by URL prefix and records every request.
+struct CannedClient {
+    responses: Vec<Canned>,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl CannedClient {
+    fn new(responses: Vec<Canned>) -> Self {
+        Self { responses, requests: Mutex::new(Vec::new()) }
+    }
+}
+
+impl HttpClient for CannedClient {
+    fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<String, String> {
+        self.requests.lock().unwrap().push((
+            url.to_string(),
+            query.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        ));
+        self.responses
+            .iter()
+            .find(|(prefix, _)| url.starts_with(prefix))
+            .map(|(_, body)| body.map(str::to_string).map_err(str::to_string))
+            .unwrap_or_else(|| Err(format!("no canned response for {}", url)))
+    }
+}
+
+fn untagged_track(path: &str) -> Track {
+    Track {
+        id: "lookup_track".to_string(),
+        path: path.to_string(),
+        name: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 387.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+#[test]
+fn musicbrainz_search_uses_file_name_and_parses_candidates() {
+    let client = CannedClient::new(vec![("https://musicbrainz.org/", Ok(MUSICBRAINZ_RESPONSE))]);
+    let track = untagged_track("C:/Music/Paranoid_Android.mp3");
+
+    let candidates = lookup_candidates(&client, &track, None, None);
+
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(
+        candidates[0],
+        MetadataCandidate {
+            title: Some("Paranoid Android".to_string()),
+            artist: Some("Radiohead".to_string()),
+            album: Some("OK Computer".to_string()),
+            year: Some(1997),
+            score: 1.0,
+            recording_id: Some("b1a9c0e9-d987-4042-ae91-78d6a3267d69".to_string()),
+            source: "musicbrainz".to_string(),
+        }
+    );
+    assert_eq!(candidates[1].artist.as_deref(), Some("Radiohead feat. Guest"));
+    assert_eq!(candidates[1].album, None);
+
+    let requests = client.requests.lock().unwrap();
+    let query = &requests[0].1;
+    assert!(query.contains(&("query".to_string(), "recording:\"Paranoid Android\"".to_string())));
+}
+
+#[test]
+fn acoustid_is_preferred_when_a_fingerprint_and_key_are_given() {
+    let client = CannedClient::new(vec![
+        ("https://api.acoustid.org/", Ok(ACOUSTID_RESPONSE)),
+        ("https://musicbrainz.org/", Ok(MUSICBRAINZ_RESPONSE)),
+    ]);
+    let track = untagged_track("C:/Music/track01.flac");
+
+    let candidates = lookup_candidates(&client, &track, Some("AQADtE..."), Some("test-key"));
+
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].title.as_deref(), Some("Teardrop"));
+    assert_eq!(candidates[0].artist.as_deref(), Some("Massive Attack"));
+    assert_eq!(candidates[0].year, Some(1998));
+    assert!((candidates[0].score - 0.94).abs() < 1e-9);
+    assert_eq!(candidates[0].source, "acoustid");
+
+    let requests = client.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].1.contains(&("duration".to_string(), "387".to_string())));
+}
+
+#[test]
+fn network_failures_yield_no_candidates() {
+    let client = CannedClient::new(vec![
+        ("https://api.acoustid.org/", Err("connection refused")),
+        ("https://musicbrainz.org/", Ok("<html>503 Service Unavailable</html>")),
+    ]);
+    let track = untagged_track("C:/Music/anything.mp3");
+
+    assert!(lookup_candidates(&client, &track, Some("AQAD"), Some("key")).is_empty());
+    // Both sources were tried before giving up.
+    assert_eq!(client.requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn query_combines_partial_tags_and_escapes_quotes() {
+    let mut track = untagged_track("C:/Music/x.mp3");
+    track.title = Some("Say \"Hello\"".to_string());
+    track.artist = Some("Band".to_string());
+    assert_eq!(
+        musicbrainz_query(&track).as_deref(),
+        Some(r#"recording:"Say \"Hello\"" AND artist:"Band""#)
+    );
+}
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    std::fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Write a short silent 16-bit mono WAV without any tags.
+fn write_wav(path: &Path) {
+    let data_len = 4410u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&44100u32.to_le_bytes());
+    bytes.extend_from_slice(&(44100u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    std::fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn applying_a_candidate_updates_file_and_library_row() {
+    let dir = temp_dir("metadata_apply");
+    let wav = dir.join("untitled.wav");
+    write_wav(&wav);
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+
+    let mut track = untagged_track(&wav.to_string_lossy());
+    track.genre = Some("Trip Hop".to_string());
+    db.add_track(&track).expect("track insert should succeed");
+
+    let client = CannedClient::new(vec![("https://api.acoustid.org/", Ok(ACOUSTID_RESPONSE))]);
+    let candidate = lookup_candidates(&client, &track, Some("AQAD"), Some("key")).remove(0);
+    apply_candidate(&db, &track.id, &candidate).expect("apply should succeed");
+
+    let row = db.get_track_by_id(&track.id).unwrap().unwrap();
+    assert_eq!(row.title.as_deref(), Some("Teardrop"));
+    assert_eq!(row.artist.as_deref(), Some("Massive Attack"));
+    assert_eq!(row.album.as_deref(), Some("Mezzanine"));
+    assert_eq!(row.year, Some(1998));
+    assert_eq!(row.genre.as_deref(), Some("Trip Hop"));
+
+    let tags = vplayer::tag_service::read_all_tags(&wav.to_string_lossy()).unwrap();
+    assert_eq!(tags.get("TIT2"), Some(&vec!["Teardrop".to_string()]));
+
+    drop(db);
+    let _ = std::fs::remove_dir_all(&dir);
+}
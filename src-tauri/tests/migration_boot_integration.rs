@@ -107,10 +107,19 @@ fn legacy_database_boot_runs_migrations() {
         .expect("sqlite_master query should succeed");
     assert_eq!(album_art_table_exists, 1);
 
+    let play_history_table_exists: i32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'play_history'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("sqlite_master query should succeed");
+    assert_eq!(play_history_table_exists, 1);
+
     let schema_version: i32 = conn
         .query_row("SELECT version FROM schema_version LIMIT 1", [], |row| row.get(0))
         .expect("schema_version query should succeed");
-    assert_eq!(schema_version, 9);
+    assert_eq!(schema_version, 23);
 
     drop(stmt);
     drop(conn);
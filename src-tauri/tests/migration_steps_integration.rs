@@ -171,7 +171,7 @@ fn migration_v0_to_v8_adds_all_columns() {
     }
     assert!(table_exists(&path, "track_album_art"));
     assert!(table_exists(&path, "album_replaygain"));
-    assert_eq!(get_schema_version(&path), 8);
+    assert_eq!(get_schema_version(&path), 23);
     cleanup_db_files(&path);
 }
 
@@ -190,7 +190,7 @@ fn migration_v3_to_v8_adds_remaining_columns() {
     }
     assert!(table_exists(&path, "track_album_art"));
     assert!(table_exists(&path, "album_replaygain"));
-    assert_eq!(get_schema_version(&path), 8);
+    assert_eq!(get_schema_version(&path), 23);
     cleanup_db_files(&path);
 }
 
@@ -204,7 +204,7 @@ fn migration_v6_to_v8_creates_new_tables() {
 
     assert!(table_exists(&path, "track_album_art"), "v7 table should be created");
     assert!(table_exists(&path, "album_replaygain"), "v8 table should be created");
-    assert_eq!(get_schema_version(&path), 8);
+    assert_eq!(get_schema_version(&path), 23);
     cleanup_db_files(&path);
 }
 
@@ -244,6 +244,102 @@ fn migration_v7_album_art_data_is_moved() {
     cleanup_db_files(&path);
 }
 
+#[test]
+fn migration_v15_collapses_duplicate_paths() {
+    let path = temp_db_path("v15_dedupe");
+    create_db_at_version(&path, 6);
+
+    {
+        let conn = Connection::open(&path).expect("open");
+        conn.execute_batch(
+            "INSERT INTO tracks (id, path, name, duration, date_added, play_count)
+             VALUES ('a', '/music/song.mp3', 'song.mp3', 180.0, 1000, 1);
+             INSERT INTO tracks (id, path, name, duration, date_added, play_count)
+             VALUES ('b', '/music/song.mp3', 'song.mp3', 180.0, 2000, 7);
+             INSERT INTO tracks (id, path, name, duration, date_added, play_count)
+             VALUES ('c', '/music/other.mp3', 'other.mp3', 90.0, 3000, 0);
+             INSERT INTO playlists (id, name, created_at) VALUES ('p', 'Mix', 1000);
+             INSERT INTO playlist_tracks (playlist_id, track_id, position) VALUES ('p', 'a', 0);
+             CREATE TABLE play_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                track_id TEXT NOT NULL,
+                played_at INTEGER NOT NULL,
+                listened_secs REAL NOT NULL DEFAULT 0
+             );
+             INSERT INTO play_history (track_id, played_at) VALUES ('a', 1500);",
+        )
+        .expect("insert tracks");
+    }
+
+    let db = Database::new(&path).expect("boot with duplicate paths");
+    drop(db);
+
+    let conn = Connection::open(&path).expect("reopen");
+    let ids: Vec<String> = conn
+        .prepare("SELECT id FROM tracks ORDER BY id")
+        .expect("prepare")
+        .query_map([], |row| row.get(0))
+        .expect("query")
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .expect("collect");
+    assert_eq!(ids, vec!["b", "c"], "the most played duplicate is kept");
+    let playlist_track: String = conn
+        .query_row("SELECT track_id FROM playlist_tracks WHERE playlist_id = 'p'", [], |row| row.get(0))
+        .expect("playlist entry survives");
+    assert_eq!(playlist_track, "b", "playlist entries move to the kept row");
+    let history_track: String = conn
+        .query_row("SELECT track_id FROM play_history", [], |row| row.get(0))
+        .expect("history survives");
+    assert_eq!(history_track, "b", "history moves to the kept row");
+
+    let duplicate = conn.execute(
+        "INSERT INTO tracks (id, path, name, duration, date_added) VALUES ('d', '/music/other.mp3', 'x', 1.0, 1)",
+        [],
+    );
+    assert!(duplicate.is_err(), "path must be unique after v15");
+
+    drop(conn);
+    cleanup_db_files(&path);
+}
+
+#[test]
+fn migration_v23_collapses_duplicate_folders() {
+    let path = temp_db_path("v23_folders");
+    create_db_at_version(&path, 6);
+
+    {
+        let conn = Connection::open(&path).expect("open");
+        conn.execute_batch(
+            "INSERT INTO folders (id, path, name, date_added) VALUES ('f_new', '/music', 'music', 2000);
+             INSERT INTO folders (id, path, name, date_added) VALUES ('f_old', '/music', 'music', 1000);
+             INSERT INTO folders (id, path, name, date_added) VALUES ('f_other', '/podcasts', 'podcasts', 3000);",
+        )
+        .expect("insert folders");
+    }
+
+    let db = Database::new(&path).expect("boot with duplicate folders");
+    drop(db);
+
+    let conn = Connection::open(&path).expect("reopen");
+    let ids: Vec<String> = conn
+        .prepare("SELECT id FROM folders ORDER BY id")
+        .expect("prepare")
+        .query_map([], |row| row.get(0))
+        .expect("query")
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .expect("collect");
+    assert_eq!(ids, vec!["f_old", "f_other"], "the oldest duplicate is kept");
+
+    let duplicate = conn.execute(
+        "INSERT INTO folders (id, path, name, date_added) VALUES ('f_dup', '/podcasts', 'x', 1)",
+        [],
+    );
+    assert!(duplicate.is_err(), "path must be unique after v23");
+
+    drop(conn);
+    cleanup_db_files(&path);
+}
+
 #[test]
 fn migration_is_idempotent() {
     let path = temp_db_path("idempotent");
@@ -255,7 +351,7 @@ fn migration_is_idempotent() {
     let db = Database::new(&path).expect("second boot");
     drop(db);
 
-    assert_eq!(get_schema_version(&path), 8);
+    assert_eq!(get_schema_version(&path), 23);
 
     // Verify all tables are intact
     assert!(table_exists(&path, "tracks"));
@@ -273,12 +369,14 @@ fn fresh_database_starts_at_latest_version() {
     let db = Database::new(&path).expect("fresh db");
     drop(db);
 
-    assert_eq!(get_schema_version(&path), 8);
+    assert_eq!(get_schema_version(&path), 23);
 
     let cols = get_track_columns(&path);
     assert!(cols.iter().any(|c| c == "disc_number"), "fresh DB should have all columns");
     assert!(table_exists(&path, "track_album_art"));
     assert!(table_exists(&path, "album_replaygain"));
+    assert!(table_exists(&path, "play_history"));
+    assert!(table_exists(&path, "settings"));
 
     cleanup_db_files(&path);
 }
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::art_extraction::ArtExtractor;
+use vplayer::database::Database;
+use vplayer::next_track::{prepare_next, PreparedNext};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+use vplayer::waveform::{read_cached_waveform, DEFAULT_WAVEFORM_BARS};
+
+const SAMPLE_RATE: u32 = 22050;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Two seconds of a 440 Hz tone that fades in, so the peaks aren't flat.
+fn write_wav(path: &Path) {
+    let samples: Vec<f32> = (0..SAMPLE_RATE * 2)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            (t / 2.0) * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+        })
+        .collect();
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for &v in &samples {
+        bytes.extend_from_slice(&((v.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn prepare_next_populates_the_waveform_cache() {
+    let dir = temp_dir("prepare_next");
+    let wav = dir.join("next.wav");
+    write_wav(&wav);
+    let path = wav.to_string_lossy().to_string();
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    db.add_track(&Track {
+        id: "next".to_string(),
+        path: path.clone(),
+        name: "next.wav".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 2.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .expect("seed track insert should succeed");
+    let extractor = ArtExtractor::new();
+
+    assert!(read_cached_waveform(&path, DEFAULT_WAVEFORM_BARS).is_none());
+
+    let prepared = prepare_next(&db, &extractor, &path).expect("prepare_next should succeed");
+    assert_eq!(prepared, PreparedNext { waveform: true, art: false });
+
+    let cached = read_cached_waveform(&path, DEFAULT_WAVEFORM_BARS).expect("waveform should be cached");
+    assert_eq!(cached.len(), DEFAULT_WAVEFORM_BARS);
+    assert!(cached.iter().all(|p| (0.0..=1.0).contains(p)));
+    assert!(cached.iter().any(|p| *p > 0.9), "peaks should be normalised");
+
+    // Preparing again reuses the cache.
+    let again = prepare_next(&db, &extractor, &path).expect("second prepare_next should succeed");
+    assert_eq!(again, prepared);
+    assert_eq!(read_cached_waveform(&path, DEFAULT_WAVEFORM_BARS), Some(cached));
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::PathBuf;
+
+use vplayer::open_files::{audio_files_from_args, OpenRequest};
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+#[test]
+fn picks_existing_audio_files_and_skips_everything_else() {
+    let dir = temp_dir("open_args");
+    for name in ["a.mp3", "b.FLAC", "notes.txt"] {
+        fs::write(dir.join(name), b"x").unwrap();
+    }
+    let abs_a = dir.join("a.mp3");
+
+    let args = vec![
+        "--minimized".to_string(),
+        abs_a.to_string_lossy().to_string(),
+        "b.FLAC".to_string(),                // relative to cwd
+        "notes.txt".to_string(),             // not audio
+        "missing.mp3".to_string(),           // does not exist
+        abs_a.to_string_lossy().to_string(), // duplicate
+        String::new(),
+    ];
+    let files = audio_files_from_args(&args, &dir);
+    assert_eq!(files, vec![abs_a.clone(), dir.join("b.FLAC")]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn accepts_file_urls() {
+    let dir = temp_dir("open_urls");
+    let path = dir.join("with space.ogg");
+    fs::write(&path, b"x").unwrap();
+
+    let url = tauri::Url::from_file_path(&path).unwrap().to_string();
+    assert!(url.contains("%20"));
+    assert_eq!(audio_files_from_args([url.as_str()], &dir), vec![path]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn first_file_plays_and_the_rest_are_queued() {
+    assert_eq!(OpenRequest::from_paths(Vec::new()), None);
+
+    let request = OpenRequest::from_paths(vec![
+        PathBuf::from("/m/one.mp3"),
+        PathBuf::from("/m/two.mp3"),
+        PathBuf::from("/m/three.mp3"),
+    ])
+    .unwrap();
+    assert_eq!(request.play, PathBuf::from("/m/one.mp3").to_string_lossy());
+    assert_eq!(request.enqueue.len(), 2);
+    assert_eq!(request.enqueue[1], PathBuf::from("/m/three.mp3").to_string_lossy());
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::playlist_io::{import_playlists_from_folder, PlaylistIO};
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn write_wav(path: &Path) {
+    let sample_rate = 8000u32;
+    let data_len = sample_rate * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn imports_every_playlist_file_in_a_folder() {
+    let dir = temp_dir("playlist_folder");
+    let music = dir.join("music");
+    fs::create_dir_all(&music).unwrap();
+    for name in ["one.wav", "two.wav", "three.wav"] {
+        write_wav(&music.join(name));
+    }
+
+    let lists = dir.join("lists");
+    fs::create_dir_all(&lists).unwrap();
+    // M3U with one relative and one absolute entry, plus a missing file.
+    fs::write(
+        lists.join("Road Trip.m3u"),
+        format!(
+            "#EXTM3U\n#EXTINF:1,First\n../music/one.wav\n{}\nmissing.wav\n",
+            music.join("two.wav").display()
+        ),
+    )
+    .unwrap();
+    fs::write(
+        lists.join("Evening.pls"),
+        "[playlist]\nFile1=../music/three.wav\nTitle1=Third\nFile2=../music/one.wav\nNumberOfEntries=2\n",
+    )
+    .unwrap();
+    fs::write(lists.join("notes.txt"), "not a playlist").unwrap();
+
+    let db_path = dir.join("library.db");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    let results = import_playlists_from_folder(&db, &lists).expect("folder import should succeed");
+    assert_eq!(results.len(), 2, "non-playlist files are skipped");
+
+    let evening = &results[0];
+    assert_eq!(evening.playlist_name, "Evening");
+    assert_eq!(evening.track_count, 2);
+    assert!(evening.error.is_none());
+
+    let road_trip = &results[1];
+    assert_eq!(road_trip.playlist_name, "Road Trip");
+    assert_eq!(road_trip.track_count, 2);
+    assert!(road_trip.error.is_none());
+
+    let playlist_id = road_trip.playlist_id.as_deref().expect("playlist should be created");
+    let tracks = db.get_playlist_tracks(playlist_id).unwrap();
+    let paths: Vec<PathBuf> = tracks.iter().map(|t| PathBuf::from(&t.path)).collect();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("one.wav"));
+    assert_eq!(paths[1], music.join("two.wav"));
+
+    let playlists = db.get_all_playlists().unwrap();
+    assert_eq!(playlists.len(), 2);
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn imports_utf8_m3u8_with_bom_and_non_ascii_paths() {
+    let dir = temp_dir("playlist_m3u8");
+    let music = dir.join("Música");
+    fs::create_dir_all(&music).unwrap();
+    for name in ["Café del Mar.wav", "東京.wav"] {
+        write_wav(&music.join(name));
+    }
+
+    let playlist = dir.join("Favoritos.m3u8");
+    let mut bytes = "\u{feff}".as_bytes().to_vec();
+    bytes.extend_from_slice(
+        format!(
+            "#EXTM3U\n#EXTINF:1,Café del Mar – Ñandú\nMúsica/Café del Mar.wav\n{}\n",
+            music.join("東京.wav").display()
+        )
+        .as_bytes(),
+    );
+    fs::write(&playlist, bytes).unwrap();
+
+    let entries = PlaylistIO::import_file(&playlist.to_string_lossy()).expect("m3u8 import should succeed");
+    assert_eq!(entries.len(), 2, "the BOM must not hide or break any line");
+    assert_eq!(entries[0].0, "Café del Mar – Ñandú");
+    assert_eq!(PathBuf::from(&entries[0].1), music.join("Café del Mar.wav"));
+    assert_eq!(entries[1].0, "東京");
+    assert_eq!(PathBuf::from(&entries[1].1), music.join("東京.wav"));
+
+    // .m3u8 is strictly UTF-8.
+    let latin1 = dir.join("latin1.m3u8");
+    fs::write(&latin1, b"#EXTM3U\nCaf\xe9.wav\n").unwrap();
+    assert!(PlaylistIO::import_file(&latin1.to_string_lossy()).is_err());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn exported_m3u8_is_utf8_without_bom() {
+    let dir = temp_dir("playlist_m3u8_export");
+    let track = dir.join("Ærø – Ünïcödé.wav");
+    write_wav(&track);
+
+    let output = dir.join("export.m3u8");
+    let entries = vec![("Ærø – Ünïcödé".to_string(), track.to_string_lossy().to_string())];
+    PlaylistIO::export_m3u(&entries, &output.to_string_lossy()).expect("export should succeed");
+
+    let bytes = fs::read(&output).unwrap();
+    assert!(bytes.starts_with(b"#EXTM3U"), "no BOM before the header");
+    assert!(String::from_utf8(bytes).is_ok());
+
+    let reimported = PlaylistIO::import_file(&output.to_string_lossy()).unwrap();
+    assert_eq!(reimported, entries);
+
+    let _ = fs::remove_dir_all(&dir);
+}
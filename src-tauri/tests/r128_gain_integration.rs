@@ -0,0 +1,39 @@
+use vplayer::r128_gain::{db_to_q78, gain_from_tag_value, is_opus, q78_to_db, R128_REFERENCE_LUFS};
+use vplayer::replaygain::rebase_gain;
+
+#[test]
+fn db_round_trips_through_q78() {
+    for gain_db in [0.0, -6.5, 3.25, -12.34, 0.004, 40.0] {
+        let decoded = q78_to_db(db_to_q78(gain_db));
+        assert!((decoded - gain_db).abs() <= 0.5 / 256.0, "{} came back as {}", gain_db, decoded);
+    }
+    assert_eq!(db_to_q78(-6.5), -1664);
+    assert_eq!(q78_to_db(-1664), -6.5);
+}
+
+#[test]
+fn q78_saturates_at_its_range() {
+    assert_eq!(db_to_q78(500.0), i16::MAX);
+    assert_eq!(db_to_q78(-500.0), i16::MIN);
+}
+
+#[test]
+fn tag_value_becomes_gain_against_the_r128_reference() {
+    let data = gain_from_tag_value(" -1664 ").unwrap();
+    assert_eq!(data.track_gain, -6.5);
+    assert_eq!(data.target_lufs, R128_REFERENCE_LUFS);
+    assert_eq!(data.loudness, -16.5);
+    assert_eq!(data.track_peak, 1.0, "unknown peak is assumed full scale");
+
+    // At the default -18 LUFS target the same track needs 5 dB more gain.
+    assert_eq!(rebase_gain(data.track_gain, data.target_lufs, -18.0), -1.5);
+
+    assert!(gain_from_tag_value("-6.5 dB").is_none());
+    assert!(gain_from_tag_value("99999").is_none());
+}
+
+#[test]
+fn only_opus_files_use_r128() {
+    assert!(is_opus(std::path::Path::new("C:/Music/track.OPUS")));
+    assert!(!is_opus(std::path::Path::new("C:/Music/track.ogg")));
+}
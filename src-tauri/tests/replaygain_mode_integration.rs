@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::replaygain::{
+    analyze_album_replaygain, get_stored_gains, ReplayGainMode, DEFAULT_TARGET_LUFS,
+};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+fn sample_track(id: &str, artist: &str, album: &str) -> Track {
+    Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.mp3", id),
+        name: format!("{}.mp3", id),
+        title: Some(id.to_string()),
+        artist: Some(artist.to_string()),
+        album: Some(album.to_string()),
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 100.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+fn set_track_gain(db: &Database, id: &str, gain: f64) {
+    let conn = db.conn.lock().unwrap_or_else(|p| p.into_inner());
+    conn.execute(
+        "UPDATE tracks SET track_gain = ?1, track_peak = 0.5, loudness = -15.0 WHERE id = ?2",
+        rusqlite::params![gain, id],
+    )
+    .expect("update rg should succeed");
+}
+
+#[test]
+fn album_with_track_fallback_prefers_album_gain() {
+    let db_path = temp_db_path("replaygain_mode");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    let on_album = sample_track("rgm_album", "Band", "Analyzed");
+    let loose = sample_track("rgm_loose", "Band", "Not Analyzed");
+    db.add_track(&on_album).expect("insert should succeed");
+    db.add_track(&loose).expect("insert should succeed");
+    set_track_gain(&db, "rgm_album", -4.0);
+    set_track_gain(&db, "rgm_loose", -2.0);
+
+    // Only the first album has a cached album gain; replace it with a value
+    // distinct from the track gain so the pick is observable.
+    analyze_album_replaygain(&db.conn, "Band", "Analyzed", DEFAULT_TARGET_LUFS)
+        .expect("album analysis should succeed");
+    {
+        let conn = db.conn.lock().unwrap_or_else(|p| p.into_inner());
+        conn.execute("UPDATE album_replaygain SET album_gain = -7.0", [])
+            .expect("update album gain should succeed");
+    }
+
+    let mode = ReplayGainMode::AlbumWithTrackFallback;
+
+    let stored = get_stored_gains(&db.conn, &on_album.path).expect("lookup should succeed");
+    let picked = mode.select(&stored, DEFAULT_TARGET_LUFS).expect("album gain should be picked");
+    assert!((picked.gain_db + 7.0).abs() < 1e-9);
+
+    let stored = get_stored_gains(&db.conn, &loose.path).expect("lookup should succeed");
+    assert!(stored.album.is_none());
+    let picked = mode.select(&stored, DEFAULT_TARGET_LUFS).expect("track gain should be picked");
+    assert!((picked.gain_db + 2.0).abs() < 1e-9);
+
+    let stored = get_stored_gains(&db.conn, "C:/Music/unknown.mp3").expect("lookup should succeed");
+    assert!(mode.select(&stored, DEFAULT_TARGET_LUFS).is_none());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
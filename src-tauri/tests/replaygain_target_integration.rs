@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::replaygain::{
+    active_target_lufs, analyze_album_replaygain, analyze_track, get_device_targets, get_replaygain,
+    get_target_lufs, playback_gain_db, rebase_gain, set_device_target_lufs, set_target_lufs,
+    store_replaygain, DEFAULT_TARGET_LUFS,
+};
+use vplayer::scanner::Track;
+use vplayer::time_utils::now_millis;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Write two seconds of a 1 kHz 16-bit mono sine at `amplitude`.
+fn write_sine_wav(path: &Path, amplitude: f32) {
+    let sample_rate = 44100u32;
+    let samples = sample_rate * 2;
+    let data_len = samples * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for n in 0..samples {
+        let t = n as f32 / sample_rate as f32;
+        let v = (2.0 * std::f32::consts::PI * 1000.0 * t).sin() * amplitude;
+        bytes.extend_from_slice(&((v * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+fn track_at(id: &str, path: &str, duration: f64) -> Track {
+    Track {
+        id: id.to_string(),
+        path: path.to_string(),
+        name: format!("{}.wav", id),
+        title: Some(id.to_string()),
+        artist: Some("Band".to_string()),
+        album: Some("Record".to_string()),
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+#[test]
+fn same_file_gets_different_gain_per_target() {
+    let dir = temp_dir("rg_target");
+    let path = dir.join("sine.wav");
+    write_sine_wav(&path, 0.25);
+    let path = path.to_str().unwrap();
+
+    let streaming = analyze_track(path, -14.0).expect("analysis at -14 failed");
+    let broadcast = analyze_track(path, -23.0).expect("analysis at -23 failed");
+
+    assert!((streaming.loudness - broadcast.loudness).abs() < 1e-9);
+    assert_eq!(streaming.target_lufs, -14.0);
+    assert_eq!(broadcast.target_lufs, -23.0);
+    assert!((streaming.track_gain - broadcast.track_gain - 9.0).abs() < 1e-9);
+    assert!((streaming.track_gain - (-14.0 - streaming.loudness)).abs() < 1e-9);
+    assert!((rebase_gain(broadcast.track_gain, broadcast.target_lufs, -14.0) - streaming.track_gain).abs() < 1e-9);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn target_setting_and_stored_target_round_trip() {
+    let dir = temp_dir("rg_target_store");
+    let db = Database::new(&dir.join("lib.db")).expect("db init should succeed");
+
+    assert_eq!(get_target_lufs(&db), DEFAULT_TARGET_LUFS);
+    set_target_lufs(&db, -14.0).expect("valid target should save");
+    assert_eq!(get_target_lufs(&db), -14.0);
+    assert!(set_target_lufs(&db, 3.0).is_err());
+    assert_eq!(get_target_lufs(&db), -14.0);
+
+    let path = dir.join("sine.wav");
+    write_sine_wav(&path, 0.25);
+    let path = path.to_str().unwrap();
+    db.add_track(&track_at("t1", path, 2.0)).unwrap();
+
+    let data = analyze_track(path, get_target_lufs(&db)).unwrap();
+    store_replaygain(&db.conn, path, &data).unwrap();
+    let stored = get_replaygain(&db.conn, path).unwrap().expect("stored data");
+    assert_eq!(stored.target_lufs, -14.0);
+    assert!((stored.track_gain - data.track_gain).abs() < 1e-9);
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn album_gain_rebases_tracks_measured_at_other_targets() {
+    let dir = temp_dir("rg_target_album");
+    let db = Database::new(&dir.join("lib.db")).expect("db init should succeed");
+    db.add_track(&track_at("a", "/m/a.wav", 100.0)).unwrap();
+    db.add_track(&track_at("b", "/m/b.wav", 100.0)).unwrap();
+
+    {
+        let conn = db.conn.lock().unwrap_or_else(|p| p.into_inner());
+        // Both tracks measure -20 LUFS; "a" predates stored targets (-18),
+        // "b" was analyzed against -14.
+        conn.execute(
+            "UPDATE tracks SET track_gain = 2.0, track_peak = 0.5, loudness = -20.0 WHERE id = 'a'",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE tracks SET track_gain = 6.0, track_peak = 0.5, loudness = -20.0,
+                    replaygain_target = -14.0 WHERE id = 'b'",
+            [],
+        )
+        .unwrap();
+    }
+
+    let album = analyze_album_replaygain(&db.conn, "Band", "Record", -23.0)
+        .unwrap()
+        .expect("album data");
+    assert!((album.album_gain - -3.0).abs() < 1e-9);
+    assert_eq!(album.target_lufs, -23.0);
+    assert!((rebase_gain(album.album_gain, album.target_lufs, -18.0) - 2.0).abs() < 1e-9);
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn switching_devices_changes_the_active_target() {
+    let dir = temp_dir("rg_device_target");
+    let db = Database::new(&dir.join("lib.db")).expect("db init should succeed");
+
+    set_target_lufs(&db, -16.0).unwrap();
+    set_device_target_lufs(&db, "Headphones", Some(-23.0)).unwrap();
+    assert!(set_device_target_lufs(&db, "Speakers", Some(0.0)).is_err());
+    assert_eq!(get_device_targets(&db).len(), 1);
+
+    assert_eq!(active_target_lufs(&db, None), -16.0);
+    assert_eq!(active_target_lufs(&db, Some("Speakers")), -16.0);
+    assert_eq!(active_target_lufs(&db, Some("Headphones")), -23.0);
+
+    // The same set_replaygain call lands on each device's own target.
+    let on_speakers = playback_gain_db(&db, Some("Speakers"), -4.0, Some(-18.0));
+    let on_headphones = playback_gain_db(&db, Some("Headphones"), -4.0, Some(-18.0));
+    assert_eq!(on_speakers, -2.0);
+    assert_eq!(on_headphones, -9.0);
+
+    // Clearing the device target falls back to the global one.
+    set_device_target_lufs(&db, "Headphones", None).unwrap();
+    assert_eq!(playback_gain_db(&db, Some("Headphones"), -4.0, None), -2.0);
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -1,9 +1,15 @@
+use std::cell::RefCell;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use vplayer::scanner::Scanner;
+use vplayer::database::Database;
+use vplayer::replaygain::get_replaygain;
+use vplayer::scan_events::{
+    ScanEvent, ScanEventSink, ScanIssue, ScanIssueKind, ScanProgressThrottle, SCAN_PROGRESS_THROTTLE_KEY,
+};
+use vplayer::scanner::{canonical_ignored_path, ScanOptions, Scanner};
 
 fn temp_dir(test_name: &str) -> PathBuf {
     let dir = std::env::temp_dir().join(format!(
@@ -20,7 +26,7 @@ fn cleanup(dir: &PathBuf) {
 #[test]
 fn scan_empty_directory_returns_no_tracks() {
     let dir = temp_dir("empty");
-    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None)
+    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None, &ScanOptions::default())
         .expect("scan should succeed");
     assert!(tracks.is_empty(), "empty dir should yield no tracks");
     cleanup(&dir);
@@ -33,7 +39,7 @@ fn scan_directory_with_no_audio_files_returns_empty() {
     fs::write(dir.join("image.png"), &[0x89, 0x50, 0x4E, 0x47]).unwrap();
     fs::write(dir.join("data.json"), r#"{"key":"value"}"#).unwrap();
 
-    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None)
+    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None, &ScanOptions::default())
         .expect("scan should succeed");
     assert!(tracks.is_empty(), "non-audio files should not be picked up");
     cleanup(&dir);
@@ -45,7 +51,7 @@ fn scan_nonexistent_directory_returns_empty() {
     // Ensure it really doesn't exist
     let _ = fs::remove_dir_all(&dir);
 
-    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None)
+    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None, &ScanOptions::default())
         .expect("scan of non-existent dir should not panic");
     assert!(tracks.is_empty());
 }
@@ -58,7 +64,7 @@ fn scan_corrupt_mp3_records_failure_but_does_not_crash() {
     // Write another corrupt file with .flac extension
     fs::write(dir.join("bad.flac"), &[0x00; 64]).unwrap();
 
-    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None)
+    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None, &ScanOptions::default())
         .expect("scan with corrupt files should not crash");
     // Corrupt files should be skipped, not cause a panic
     assert!(tracks.is_empty(), "corrupt files should not produce tracks");
@@ -80,6 +86,7 @@ fn scan_with_cancel_flag_stops_early() {
         None,
         Some(cancel),
         None,
+        &ScanOptions::default(),
     ).expect("cancelled scan should succeed");
 
     assert!(tracks.is_empty(), "immediately cancelled scan should return no tracks");
@@ -97,10 +104,370 @@ fn scan_subdirectories_are_traversed() {
     // Put a non-audio file at root
     fs::write(dir.join("notes.txt"), "text").unwrap();
 
-    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None)
+    let tracks = Scanner::scan_directory(dir.to_str().unwrap(), None, None, None, &ScanOptions::default())
         .expect("nested scan should not crash");
     // The mp3 is corrupt so no tracks produced, but it shouldn't crash
     // The key test is that the scanner didn't panic traversing subdirs
     assert!(tracks.is_empty());
     cleanup(&dir);
 }
+
+#[test]
+fn files_under_an_ignored_folder_are_never_scanned() {
+    let dir = temp_dir("ignored_paths");
+    let audiobooks = dir.join("Audiobooks");
+    fs::create_dir_all(&audiobooks).unwrap();
+    // Placeholder bytes: every file that is scanned fails extraction and is
+    // recorded in failed_tracks, which shows exactly what was visited.
+    let ignored = audiobooks.join("chapter01.mp3");
+    let scanned = dir.join("song.mp3");
+    fs::write(&ignored, b"not real audio").unwrap();
+    fs::write(&scanned, b"not real audio").unwrap();
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    db.add_ignored_path(&audiobooks.to_string_lossy()).unwrap();
+    assert_eq!(db.list_ignored_paths().unwrap(), vec![audiobooks.to_string_lossy().to_string()]);
+
+    Scanner::scan_directory(dir.to_str().unwrap(), None, None, Some(&db), &ScanOptions::default())
+        .expect("scan should succeed");
+    assert!(db.is_failed_track(&scanned.to_string_lossy()), "regular file should be scanned");
+    assert!(!db.is_failed_track(&ignored.to_string_lossy()), "ignored folder must be skipped");
+
+    assert!(db.remove_ignored_path(&audiobooks.to_string_lossy()).unwrap());
+    assert!(!db.remove_ignored_path(&audiobooks.to_string_lossy()).unwrap());
+    Scanner::scan_directory(dir.to_str().unwrap(), None, None, Some(&db), &ScanOptions::default())
+        .expect("scan should succeed");
+    assert!(db.is_failed_track(&ignored.to_string_lossy()), "un-ignored folder is scanned again");
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn ignored_folders_match_through_a_differently_spelled_root() {
+    let dir = temp_dir("ignored_paths_canonical");
+    let library = dir.join("library");
+    let audiobooks = library.join("Audiobooks");
+    fs::create_dir_all(&audiobooks).unwrap();
+    fs::write(audiobooks.join("chapter01.mp3"), b"not real audio").unwrap();
+    fs::write(library.join("song.mp3"), b"not real audio").unwrap();
+    let link = dir.join("music");
+    std::os::unix::fs::symlink(&library, &link).unwrap();
+
+    // Stored resolved, however the folder was named when it was added.
+    let stored = canonical_ignored_path(&link.join("Audiobooks").join("..").join("Audiobooks").to_string_lossy());
+    assert_eq!(PathBuf::from(&stored), audiobooks.canonicalize().unwrap());
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    db.add_ignored_path(&stored).unwrap();
+
+    // Scanning through the link walks paths that don't start with the entry.
+    Scanner::scan_directory(link.to_str().unwrap(), None, None, Some(&db), &ScanOptions::default())
+        .expect("scan should succeed");
+    assert!(db.is_failed_track(&link.join("song.mp3").to_string_lossy()), "regular file should be scanned");
+    assert!(
+        !db.is_failed_track(&link.join("Audiobooks").join("chapter01.mp3").to_string_lossy()),
+        "ignored folder must be skipped"
+    );
+
+    drop(db);
+    cleanup(&dir);
+}
+
+/// One second of 16-bit mono silence.
+fn write_wav(path: &std::path::Path) {
+    let sample_rate = 8000u32;
+    let data_len = sample_rate * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+fn track_id(db: &Database, path: &std::path::Path) -> String {
+    db.get_track_by_path(&path.to_string_lossy()).unwrap().expect("track should be stored").id
+}
+
+#[test]
+fn folder_rescan_reports_added_updated_and_removed_tracks() {
+    let dir = temp_dir("rescan");
+    let music = dir.join("Music");
+    // Shares the `Music` prefix but is a different folder.
+    let sibling = dir.join("Music 2");
+    fs::create_dir_all(&music).unwrap();
+    fs::create_dir_all(&sibling).unwrap();
+    let (kept, deleted, new) = (music.join("kept.wav"), music.join("deleted.wav"), music.join("new.wav"));
+    write_wav(&kept);
+    write_wav(&deleted);
+    write_wav(&sibling.join("other.wav"));
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    let music_path = music.to_str().unwrap();
+    db.add_folder("folder_music", music_path, "Music", 0).unwrap();
+
+    let first = Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+    let mut added = first.added.clone();
+    added.sort();
+    let mut expected = vec![track_id(&db, &deleted), track_id(&db, &kept)];
+    expected.sort();
+    assert_eq!(added, expected);
+    assert!(first.updated.is_empty() && first.removed.is_empty());
+    Scanner::rescan_folder(sibling.to_str().unwrap(), None, None, &db).unwrap();
+
+    let deleted_id = track_id(&db, &deleted);
+    fs::remove_file(&deleted).unwrap();
+    write_wav(&new);
+    // Pretend `kept` changed on disk since it was scanned.
+    db.conn
+        .lock()
+        .unwrap()
+        .execute("UPDATE tracks SET file_modified = 0 WHERE path = ?1", [kept.to_string_lossy()])
+        .unwrap();
+
+    let diff = Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+    assert_eq!(diff.added, vec![track_id(&db, &new)]);
+    assert_eq!(diff.updated, vec![track_id(&db, &kept)]);
+    assert_eq!(diff.removed, vec![deleted_id]);
+    assert!(db.get_track_by_path(&deleted.to_string_lossy()).unwrap().is_none());
+    assert!(
+        db.get_track_by_path(&sibling.join("other.wav").to_string_lossy()).unwrap().is_some(),
+        "tracks of a sibling folder are left alone"
+    );
+
+    // Nothing changed since: an empty diff.
+    assert_eq!(Scanner::rescan_folder(music_path, None, None, &db).unwrap(), Default::default());
+
+    let folders = db.get_all_folders().unwrap();
+    assert_eq!(folders.len(), 1);
+    assert_eq!(folders[0].0, "folder_music");
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn rescanning_an_emptied_folder_only_proposes_removals() {
+    // What an unmounted drive looks like: the mount point is still there,
+    // just empty.
+    let dir = temp_dir("rescan_empty");
+    let music = dir.join("Music");
+    fs::create_dir_all(&music).unwrap();
+    let (a, b) = (music.join("a.wav"), music.join("b.wav"));
+    write_wav(&a);
+    write_wav(&b);
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    let music_path = music.to_str().unwrap();
+    db.add_folder("folder_music", music_path, "Music", 0).unwrap();
+    Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+    let mut ids = vec![track_id(&db, &a), track_id(&db, &b)];
+    ids.sort();
+
+    fs::remove_file(&a).unwrap();
+    fs::remove_file(&b).unwrap();
+    let diff = Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+
+    let mut removed = diff.removed.clone();
+    removed.sort();
+    assert_eq!(removed, ids);
+    assert!(diff.removal_withheld);
+    assert!(db.get_track_by_path(&a.to_string_lossy()).unwrap().is_some(), "nothing is deleted");
+    assert!(db.get_track_by_path(&b.to_string_lossy()).unwrap().is_some(), "nothing is deleted");
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn rescanning_removes_a_folders_only_track() {
+    let dir = temp_dir("rescan_only_track");
+    let music = dir.join("Music");
+    fs::create_dir_all(&music).unwrap();
+    let only = music.join("only.wav");
+    write_wav(&only);
+    fs::write(music.join("cover.jpg"), b"art").unwrap();
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    let music_path = music.to_str().unwrap();
+    db.add_folder("folder_music", music_path, "Music", 0).unwrap();
+    Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+    let id = track_id(&db, &only);
+
+    fs::remove_file(&only).unwrap();
+    let diff = Scanner::rescan_folder(music_path, None, None, &db).unwrap();
+
+    assert_eq!(diff.removed, vec![id]);
+    assert!(!diff.removal_withheld, "the folder was read in full");
+    assert!(db.get_track_by_path(&only.to_string_lossy()).unwrap().is_none());
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn scan_error_event_carries_path_and_kind() {
+    let dir = temp_dir("scan_error_event");
+    let path = dir.join("broken.mp3");
+    fs::write(&path, b"not audio").unwrap();
+
+    let event = ScanEvent::Error(ScanIssue::new(
+        &path,
+        ScanIssueKind::for_failed_file(&path),
+        "No format could be determined",
+    ));
+    assert_eq!(event.name(), "scan-error");
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "path": path.to_string_lossy(),
+            "fileName": "broken.mp3",
+            "kind": "unreadable",
+            "message": "No format could be determined",
+        })
+    );
+
+    let missing = dir.join("gone.flac");
+    assert_eq!(ScanIssueKind::for_failed_file(&missing), ScanIssueKind::NotFound);
+    cleanup(&dir);
+}
+
+#[derive(Default)]
+struct CollectingSink {
+    events: RefCell<Vec<ScanEvent>>,
+}
+
+impl ScanEventSink for CollectingSink {
+    fn emit_scan_event(&self, event: ScanEvent) {
+        self.events.borrow_mut().push(event);
+    }
+}
+
+impl CollectingSink {
+    fn progress_currents(&self) -> Vec<usize> {
+        self.events
+            .borrow()
+            .iter()
+            .filter_map(|e| match e {
+                ScanEvent::Progress(p) => Some(p.current),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn scan_completes_when_persisting_fails_before_analysis() {
+    let dir = temp_dir("persist_fails");
+    let db = Database::new(&dir.join("library.db")).expect("db init");
+    let sink = CollectingSink::default();
+
+    let result = Scanner::persist_and_analyze(&db, &mut [], Some(&sink), &None, true, |_| Err("disk full"));
+
+    assert_eq!(result, Err("disk full"));
+    let events = sink.events.borrow();
+    assert!(
+        matches!(events.as_slice(), [ScanEvent::Complete(c)] if c.track_count == 0),
+        "got {:?}",
+        events
+    );
+    drop(events);
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn scan_progress_is_throttled() {
+    let dir = temp_dir("progress_throttle");
+    // Nonexistent files: each one fails fast but still reports progress.
+    let files: Vec<PathBuf> = (0..10_000).map(|i| dir.join(format!("track_{i}.mp3"))).collect();
+
+    let sink = CollectingSink::default();
+    Scanner::scan_files(&files, Some(&sink), &None, None, false).unwrap();
+    let currents = sink.progress_currents();
+    assert!(currents.len() < 1_000, "emitted {} progress events", currents.len());
+    assert_eq!(currents.first(), Some(&1));
+    assert_eq!(currents.last(), Some(&10_000));
+
+    // A tighter persisted throttle is picked up from the database.
+    let db = Database::new(&dir.join("library.db")).expect("db init");
+    let throttle = ScanProgressThrottle { every_files: 10, every_ms: 10_000 };
+    db.set_json_setting(SCAN_PROGRESS_THROTTLE_KEY, &throttle).unwrap();
+    let sink = CollectingSink::default();
+    Scanner::scan_files(&files[..1_000], Some(&sink), &None, Some(&db), false).unwrap();
+    let currents = sink.progress_currents();
+    assert_eq!(currents.len(), 101);
+    assert_eq!(currents[1], 11);
+
+    drop(db);
+    cleanup(&dir);
+}
+
+/// One second of a 440 Hz 16-bit mono sine, loud enough to measure.
+fn write_sine_wav(path: &std::path::Path) {
+    let sample_rate = 8000u32;
+    let data_len = sample_rate * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for n in 0..sample_rate {
+        let t = n as f32 / sample_rate as f32;
+        let v = (2.0 * std::f32::consts::PI * 440.0 * t).sin() * 0.3;
+        bytes.extend_from_slice(&((v * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+#[test]
+fn analyze_on_scan_stores_replaygain_for_scanned_files() {
+    let dir = temp_dir("analyze_on_scan");
+    let (plain, analyzed) = (dir.join("plain"), dir.join("analyzed"));
+    fs::create_dir_all(&plain).unwrap();
+    fs::create_dir_all(&analyzed).unwrap();
+    let plain_file = plain.join("a.wav");
+    write_sine_wav(&plain_file);
+    let analyzed_files: Vec<PathBuf> = (0..5).map(|i| analyzed.join(format!("{i}.wav"))).collect();
+    for path in &analyzed_files {
+        write_sine_wav(path);
+    }
+
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+    let options = ScanOptions { analyze_replaygain: true, ..ScanOptions::default() };
+    options.save_for_folder(&db, analyzed.to_str().unwrap()).unwrap();
+
+    Scanner::rescan_folder(plain.to_str().unwrap(), None, None, &db).unwrap();
+    Scanner::rescan_folder(analyzed.to_str().unwrap(), None, None, &db).unwrap();
+
+    assert!(get_replaygain(&db.conn, &plain_file.to_string_lossy()).unwrap().is_none());
+    for path in &analyzed_files {
+        let data = get_replaygain(&db.conn, &path.to_string_lossy())
+            .unwrap()
+            .expect("scanned file should have ReplayGain");
+        assert!(data.track_gain.is_finite());
+        assert!(data.track_peak > 0.2 && data.track_peak <= 1.0);
+    }
+
+    drop(db);
+    cleanup(&dir);
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use std::cell::Cell;
+
+use vplayer::database::Database;
+use vplayer::startup::{apply_start_minimized, StartupWindow, START_MINIMIZED_KEY};
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+#[test]
+fn settings_roundtrip_and_survive_reopen() {
+    let db_path = temp_db_path("settings_roundtrip");
+
+    {
+        let db = Database::new(&db_path).expect("db init should succeed");
+        assert_eq!(db.get_setting("missing").expect("read"), None);
+
+        db.set_setting("theme", "dark").expect("write");
+        db.set_setting("theme", "light").expect("overwrite");
+
+        let mut map = HashMap::new();
+        map.insert("next-track".to_string(), "Ctrl+Alt+Right".to_string());
+        db.set_json_setting("shortcuts", &map).expect("json write");
+    }
+
+    let db = Database::new(&db_path).expect("db reopen should succeed");
+    assert_eq!(db.get_setting("theme").expect("read").as_deref(), Some("light"));
+
+    let map: HashMap<String, String> = db
+        .get_json_setting("shortcuts")
+        .expect("json read")
+        .expect("value present");
+    assert_eq!(map.get("next-track").map(String::as_str), Some("Ctrl+Alt+Right"));
+
+    db.delete_setting("theme").expect("delete");
+    assert_eq!(db.get_setting("theme").expect("read"), None);
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn unreadable_json_setting_is_treated_as_unset() {
+    let db_path = temp_db_path("settings_corrupt");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    db.set_setting("shortcuts", "{not json").expect("write");
+    let value: Option<HashMap<String, String>> = db.get_json_setting("shortcuts").expect("read");
+    assert!(value.is_none());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[derive(Default)]
+struct FakeWindow {
+    hidden: Cell<bool>,
+}
+
+impl StartupWindow for FakeWindow {
+    fn hide(&self) -> Result<(), String> {
+        self.hidden.set(true);
+        Ok(())
+    }
+}
+
+#[test]
+fn start_minimized_setting_decides_startup_visibility() {
+    let db_path = temp_db_path("start_minimized");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    let window = FakeWindow::default();
+    assert!(!apply_start_minimized(&db, &window), "unset means start visible");
+    assert!(!window.hidden.get());
+
+    db.set_json_setting(START_MINIMIZED_KEY, &false).expect("write");
+    assert!(!apply_start_minimized(&db, &window));
+    assert!(!window.hidden.get());
+
+    db.set_json_setting(START_MINIMIZED_KEY, &true).expect("write");
+    assert!(apply_start_minimized(&db, &window));
+    assert!(window.hidden.get());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
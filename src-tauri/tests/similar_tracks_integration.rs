@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::bpm::store_bpm;
+use vplayer::database::Database;
+use vplayer::key_detection::{store_key, Key};
+use vplayer::scanner::Track;
+use vplayer::similar_tracks::get_similar_tracks;
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+fn seed(db: &Database, id: &str, artist: &str, genre: &str, bpm: f64, key: &str) {
+    let path = format!("C:/Music/{}.mp3", id);
+    db.add_track(&Track {
+        id: id.to_string(),
+        path: path.clone(),
+        name: format!("{}.mp3", id),
+        title: Some(id.to_string()),
+        artist: Some(artist.to_string()),
+        album: None,
+        genre: Some(genre.to_string()),
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .expect("seed track insert should succeed");
+    store_bpm(db, &path, bpm).expect("storing BPM should succeed");
+    store_key(db, &path, Key::from_name(key).expect("valid key name")).expect("storing key should succeed");
+}
+
+#[test]
+fn same_artist_and_genre_rank_above_unrelated_tracks() {
+    let db_path = temp_db_path("similar_tracks");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    seed(&db, "seed", "Band A", "House", 124.0, "A minor");
+    seed(&db, "sibling", "Band A", "House", 126.0, "C major");
+    seed(&db, "same_artist", "band a", "Ambient", 70.0, "F# major");
+    seed(&db, "same_genre", "Band B", "House", 124.0, "E minor");
+    seed(&db, "unrelated", "Band C", "Metal", 180.0, "F# major");
+    seed(&db, "unrelated_2", "Band D", "Jazz", 90.0, "Eb major");
+
+    let similar: Vec<String> = get_similar_tracks(&db, "seed", 10)
+        .expect("query should succeed")
+        .expect("seed track should exist")
+        .into_iter()
+        .map(|t| t.id)
+        .collect();
+
+    assert!(!similar.contains(&"seed".to_string()), "the seed is never its own match");
+    assert_eq!(similar[0], "sibling");
+    let mut related = similar.clone();
+    related.sort();
+    assert_eq!(related, ["same_artist", "same_genre", "sibling"], "tracks sharing nothing are left out");
+
+    let top_two = get_similar_tracks(&db, "seed", 2).unwrap().unwrap();
+    assert_eq!(top_two.len(), 2);
+    assert!(get_similar_tracks(&db, "missing", 5).unwrap().is_none());
+
+    // An untagged seed has nothing to match on.
+    db.add_track(&Track {
+        id: "untagged".to_string(),
+        path: "C:/Music/untagged.mp3".to_string(),
+        name: "untagged.mp3".to_string(),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    })
+    .unwrap();
+    assert!(get_similar_tracks(&db, "untagged", 5).unwrap().unwrap().is_empty());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn relative_and_neighbouring_keys_are_compatible() {
+    let a_minor = Key::from_name("A minor").unwrap();
+    assert!(a_minor.is_compatible(&Key::from_name("C major").unwrap()));
+    assert!(a_minor.is_compatible(&Key::from_name("E minor").unwrap()));
+    assert!(a_minor.is_compatible(&Key::from_name("D minor").unwrap()));
+    assert!(!a_minor.is_compatible(&Key::from_name("F# major").unwrap()));
+    assert_eq!(Key::from_name("Db major"), Key::from_name("C# major"));
+    assert!(Key::from_name("H minor").is_none());
+}
@@ -141,3 +141,165 @@ fn smart_playlist_executes_against_initialized_database() {
     drop(db);
     cleanup_db_files(&db_path);
 }
+
+#[test]
+fn skips_are_counted_separately_and_usable_in_rules() {
+    let db_path = temp_db_path("smart_playlist_skips");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    for id in ["skipped", "finished"] {
+        db.add_track(&Track {
+            id: id.to_string(),
+            path: format!("C:/Music/{}.mp3", id),
+            name: format!("{}.mp3", id),
+            title: Some(id.to_string()),
+            artist: None,
+            album: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            duration: 200.0,
+            date_added: now_millis(),
+            rating: 0,
+            play_count: 0,
+            last_played: 0,
+        })
+        .expect("seed track insert should succeed");
+    }
+    db.increment_play_count("skipped").expect("play count should succeed");
+    db.increment_skip_count("skipped").expect("skip count should succeed");
+    db.increment_skip_count("skipped").expect("skip count should succeed");
+    db.increment_play_count("finished").expect("play count should succeed");
+
+    let skipped = db.get_track_by_id("skipped").unwrap().expect("track should exist");
+    assert_eq!(skipped.play_count, 1, "skips must not touch play_count");
+
+    let conn = Connection::open(&db_path).expect("connection open should succeed");
+    let skip_count: i64 = conn
+        .query_row("SELECT skip_count FROM tracks WHERE id = 'skipped'", [], |row| row.get(0))
+        .expect("skip count query should succeed");
+    assert_eq!(skip_count, 2);
+
+    let playlist = SmartPlaylist {
+        id: "sp_skips".to_string(),
+        name: "Rarely Finished".to_string(),
+        description: String::new(),
+        rules: vec![Rule {
+            field: "skip_count".to_string(),
+            operator: "greater_equal".to_string(),
+            value: "2".to_string(),
+        }],
+        match_all: true,
+        limit: None,
+        sort_by: Some("skip_count".to_string()),
+        sort_desc: true,
+        live_update: true,
+        created_at: now_millis(),
+    };
+    let (query, query_params) = playlist.to_sql().expect("skip_count should be an allowed field");
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = query_params
+        .iter()
+        .map(|p| p as &dyn rusqlite::types::ToSql)
+        .collect();
+    let mut stmt = conn.prepare(&query).expect("query prepare should succeed");
+    let ids: Vec<String> = stmt
+        .query_map(param_refs.as_slice(), Track::from_row)
+        .expect("query execution should succeed")
+        .map(|t| t.expect("row should decode").id)
+        .collect();
+    assert_eq!(ids, vec!["skipped"]);
+
+    drop(stmt);
+    drop(conn);
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn playlist_membership_rules_use_parameterized_subqueries() {
+    let db_path = temp_db_path("smart_playlist_membership");
+    let db = Database::new(&db_path).expect("db init should succeed");
+
+    for id in ["listed", "elsewhere", "loose"] {
+        db.add_track(&Track {
+            id: id.to_string(),
+            path: format!("C:/Music/{}.mp3", id),
+            name: format!("{}.mp3", id),
+            title: Some(id.to_string()),
+            artist: None,
+            album: None,
+            genre: None,
+            year: None,
+            track_number: None,
+            disc_number: None,
+            duration: 200.0,
+            date_added: now_millis(),
+            rating: 0,
+            play_count: 0,
+            last_played: 0,
+        })
+        .expect("seed track insert should succeed");
+    }
+    let favourites = db.create_playlist("Favourites").expect("playlist should be created");
+    let other = db.create_playlist("Other").expect("playlist should be created");
+    db.add_track_to_playlist(&favourites, "listed", 0).expect("add should succeed");
+    db.add_track_to_playlist(&other, "elsewhere", 0).expect("add should succeed");
+
+    let conn = Connection::open(&db_path).expect("connection open should succeed");
+    let run = |operator: &str, value: &str| -> Vec<String> {
+        let playlist = SmartPlaylist {
+            id: "sp_membership".to_string(),
+            name: "Membership".to_string(),
+            description: String::new(),
+            rules: vec![Rule {
+                field: "playlist".to_string(),
+                operator: operator.to_string(),
+                value: value.to_string(),
+            }],
+            match_all: true,
+            limit: None,
+            sort_by: Some("path".to_string()),
+            sort_desc: false,
+            live_update: true,
+            created_at: now_millis(),
+        };
+        let (query, query_params) = playlist.to_sql().expect("membership rule should build");
+        assert!(!query.contains(value) || value.is_empty(), "playlist id must be a parameter");
+        let mut stmt = conn.prepare(&query).expect("query prepare should succeed");
+        let ids = stmt
+            .query_map(rusqlite::params_from_iter(query_params.iter()), Track::from_row)
+            .expect("query execution should succeed")
+            .map(|t| t.expect("row should decode").id)
+            .collect();
+        ids
+    };
+
+    assert_eq!(run("in_playlist", &favourites), vec!["listed"]);
+    assert_eq!(run("not_in_playlist", &favourites), vec!["elsewhere", "loose"]);
+    assert_eq!(run("not_in_playlist", ""), vec!["loose"]);
+    assert_eq!(run("in_playlist", ""), vec!["elsewhere", "listed"]);
+
+    // Normal rules still go through the field whitelist.
+    let bad = SmartPlaylist {
+        id: "sp_bad".to_string(),
+        name: "Bad".to_string(),
+        description: String::new(),
+        rules: vec![Rule {
+            field: "playlist".to_string(),
+            operator: "equals".to_string(),
+            value: favourites.clone(),
+        }],
+        match_all: true,
+        limit: None,
+        sort_by: None,
+        sort_desc: false,
+        live_update: true,
+        created_at: now_millis(),
+    };
+    assert!(bad.to_sql().is_err());
+
+    drop(conn);
+    drop(db);
+    cleanup_db_files(&db_path);
+}
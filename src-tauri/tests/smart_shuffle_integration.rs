@@ -0,0 +1,77 @@
+use vplayer::scanner::Track;
+use vplayer::smart_shuffle::{build_smart_queue, track_score, ShuffleWeights};
+
+const NOW: i64 = 1_700_000_000_000;
+const DAY: i64 = 86_400_000;
+
+fn track(id: &str, rating: i32, play_count: i32, last_played: i64) -> Track {
+    Track {
+        id: id.to_string(),
+        path: format!("C:/Music/{}.mp3", id),
+        name: format!("{}.mp3", id),
+        title: None,
+        artist: None,
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 200.0,
+        date_added: 0,
+        rating,
+        play_count,
+        last_played,
+    }
+}
+
+/// A pool of filler tracks plus one 5-star and one 1-star track that are
+/// otherwise identical.
+fn library() -> Vec<Track> {
+    let mut tracks: Vec<Track> = (0..20).map(|i| track(&format!("filler{}", i), 3, 2, NOW - 30 * DAY)).collect();
+    tracks.push(track("five_star", 5, 2, NOW - 30 * DAY));
+    tracks.push(track("one_star", 1, 2, NOW - 30 * DAY));
+    tracks
+}
+
+#[test]
+fn five_star_track_is_queued_more_often_than_one_star() {
+    let tracks = library();
+    let weights = ShuffleWeights::default();
+
+    let (mut five, mut one) = (0, 0);
+    for seed in 0..2000 {
+        let queue = build_smart_queue(&tracks, 5, &weights, NOW, seed);
+        five += queue.iter().filter(|t| t.id == "five_star").count();
+        one += queue.iter().filter(|t| t.id == "one_star").count();
+    }
+    assert!(five > one + one / 5, "5-star queued {} times, 1-star {} times", five, one);
+}
+
+#[test]
+fn same_seed_gives_the_same_queue() {
+    let tracks = library();
+    let weights = ShuffleWeights::default();
+
+    let ids = |seed| -> Vec<String> {
+        build_smart_queue(&tracks, 10, &weights, NOW, seed).into_iter().map(|t| t.id).collect()
+    };
+    assert_eq!(ids(42), ids(42));
+    assert_ne!(ids(42), ids(43));
+
+    let everything = build_smart_queue(&tracks, 100, &weights, NOW, 7);
+    assert_eq!(everything.len(), tracks.len(), "size is capped at the library and nothing repeats");
+}
+
+#[test]
+fn recently_played_and_unplayed_tracks_score_as_expected() {
+    let weights = ShuffleWeights { rating: 0.0, play_count: 0.0, recency: 1.0 };
+
+    let just_played = track_score(&track("a", 3, 1, NOW - 60_000), &weights, 1, NOW);
+    let month_ago = track_score(&track("b", 3, 1, NOW - 30 * DAY), &weights, 1, NOW);
+    let never = track_score(&track("c", 3, 0, 0), &weights, 1, NOW);
+    assert!(just_played < month_ago && month_ago < never);
+
+    // All weights off still leaves every track a chance.
+    let off = ShuffleWeights { rating: 0.0, play_count: 0.0, recency: 0.0 };
+    assert!(track_score(&track("d", 0, 0, NOW), &off, 0, NOW) > 0.0);
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lofty::{Accessor, Tag, TagExt, TagType, TaggedFileExt};
+use vplayer::database::Database;
+use vplayer::scanner::Track;
+use vplayer::tag_service::{apply_tags_bulk, TagUpdateInput};
+use vplayer::time_utils::now_millis;
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Write a short silent 16-bit mono WAV with an ID3v2 title/artist tag.
+fn write_tagged_wav(path: &Path, title: &str) {
+    let samples = 4410u32;
+    let data_len = samples * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&44100u32.to_le_bytes());
+    bytes.extend_from_slice(&(44100u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    fs::write(path, bytes).expect("write wav failed");
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.set_title(title.to_string());
+    tag.set_artist("Original Artist".to_string());
+    tag.save_to_path(path).expect("initial tag write failed");
+}
+
+fn track_at(id: &str, path: &Path) -> Track {
+    Track {
+        id: id.to_string(),
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        title: Some(id.to_string()),
+        artist: Some("Original Artist".to_string()),
+        album: None,
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 0.1,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+#[test]
+fn bulk_genre_update_touches_every_file_and_row() {
+    let dir = temp_dir("tag_bulk");
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let id = format!("bulk_{}", i);
+        let path = dir.join(format!("{}.wav", id));
+        write_tagged_wav(&path, &id);
+        db.add_track(&track_at(&id, &path)).expect("track insert should succeed");
+        ids.push(id);
+    }
+    // A missing file and an unknown id must not stop the batch.
+    let gone = dir.join("gone.wav");
+    db.add_track(&track_at("gone", &gone)).expect("track insert should succeed");
+    ids.insert(1, "gone".to_string());
+    ids.push("not_in_library".to_string());
+
+    let update = TagUpdateInput {
+        title: None,
+        artist: None,
+        album: None,
+        year: None,
+        genre: Some("Shoegaze".to_string()),
+        comment: None,
+        track_number: None,
+        disc_number: None,
+    };
+    let result = apply_tags_bulk(&db, &ids, &update);
+
+    assert_eq!(result.succeeded.len(), 3, "failures: {:?}", result.failed);
+    assert_eq!(result.failed.len(), 2);
+    assert!(result.failed.iter().any(|f| f.path == gone.to_string_lossy()));
+    assert!(result.failed.iter().any(|f| f.path == "not_in_library"));
+
+    for id in ["bulk_0", "bulk_1", "bulk_2"] {
+        let track = db.get_track_by_id(id).unwrap().expect("track exists");
+        assert_eq!(track.genre.as_deref(), Some("Shoegaze"));
+        assert_eq!(track.title.as_deref(), Some(id), "untouched columns stay");
+        assert_eq!(track.artist.as_deref(), Some("Original Artist"));
+
+        let tagged = lofty::read_from_path(&track.path).expect("re-read failed");
+        let tag = tagged.primary_tag().expect("tag present");
+        assert_eq!(tag.genre().as_deref(), Some("Shoegaze"));
+        assert_eq!(tag.title().as_deref(), Some(id));
+    }
+
+    drop(db);
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lofty::{
+    Accessor, AudioFile, MimeType, Picture, PictureType, Probe, Tag, TagExt, TagType, TaggedFileExt,
+};
+use vplayer::scanner::Scanner;
+use vplayer::tag_service::{
+    apply_tags_to_file, read_all_tags, remove_tag, validate_cover_image, validate_tag_key,
+    write_album_art, write_tag, TagUpdateInput,
+};
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+/// Write a short silent 16-bit mono WAV without any tags.
+fn write_wav(path: &Path) {
+    let samples = 4410u32;
+    let data_len = samples * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&44100u32.to_le_bytes());
+    bytes.extend_from_slice(&(44100u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    fs::write(path, bytes).expect("write wav failed");
+}
+
+fn cover_bytes() -> Vec<u8> {
+    // PNG signature followed by filler; lofty stores picture data verbatim.
+    let mut data = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    data.extend((0..256u32).map(|i| (i % 251) as u8));
+    data
+}
+
+fn title_only(title: &str) -> TagUpdateInput {
+    TagUpdateInput {
+        title: Some(title.to_string()),
+        artist: None,
+        album: None,
+        year: None,
+        genre: None,
+        comment: None,
+        track_number: None,
+        disc_number: None,
+    }
+}
+
+#[test]
+fn title_edit_keeps_artist_album_and_cover_art() {
+    let dir = temp_dir("tag_write_preserve");
+    let path = dir.join("song.wav");
+    write_wav(&path);
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.set_title("Old Title".to_string());
+    tag.set_artist("Kept Artist".to_string());
+    tag.set_album("Kept Album".to_string());
+    tag.push_picture(Picture::new_unchecked(
+        PictureType::CoverFront,
+        Some(MimeType::Png),
+        None,
+        cover_bytes(),
+    ));
+    tag.save_to_path(&path).expect("initial tag write failed");
+
+    apply_tags_to_file(path.to_str().unwrap(), &title_only("New Title"))
+        .expect("tag write failed");
+
+    let tagged = Probe::open(&path).unwrap().read().unwrap();
+    let tag = tagged.primary_tag().expect("primary tag missing after write");
+    assert_eq!(tag.title().as_deref(), Some("New Title"));
+    assert_eq!(tag.artist().as_deref(), Some("Kept Artist"));
+    assert_eq!(tag.album().as_deref(), Some("Kept Album"));
+    assert_eq!(tag.pictures().len(), 1);
+    assert_eq!(tag.pictures()[0].pic_type(), PictureType::CoverFront);
+    assert_eq!(tag.pictures()[0].data(), cover_bytes().as_slice());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn missing_primary_tag_is_seeded_from_existing_tag() {
+    let dir = temp_dir("tag_write_seed");
+    let path = dir.join("song.wav");
+    write_wav(&path);
+
+    let mut info = Tag::new(TagType::RiffInfo);
+    info.set_title("Old Title".to_string());
+    info.set_artist("Info Artist".to_string());
+    info.save_to_path(&path).expect("initial tag write failed");
+
+    apply_tags_to_file(path.to_str().unwrap(), &title_only("New Title"))
+        .expect("tag write failed");
+
+    let tagged = Probe::open(&path).unwrap().read().unwrap();
+    let primary = tagged.primary_tag().expect("primary tag should be created");
+    assert_eq!(primary.title().as_deref(), Some("New Title"));
+    assert_eq!(primary.artist().as_deref(), Some("Info Artist"));
+
+    let info = tagged.tag(TagType::RiffInfo).expect("RIFF INFO tag should be left alone");
+    assert_eq!(info.title().as_deref(), Some("Old Title"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn set_png_cover_and_read_it_back() {
+    let dir = temp_dir("tag_write_cover");
+    let path = dir.join("song.wav");
+    write_wav(&path);
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.set_title("Covered".to_string());
+    tag.push_picture(Picture::new_unchecked(
+        PictureType::Artist,
+        Some(MimeType::Jpeg),
+        None,
+        vec![0xFF, 0xD8, 0xFF, 0xE0, 0, 0],
+    ));
+    tag.save_to_path(&path).expect("initial tag write failed");
+
+    let png = cover_bytes();
+    write_album_art(path.to_str().unwrap(), &png, "image/png").expect("cover write failed");
+
+    let extracted = Scanner::extract_album_art(path.to_str().unwrap())
+        .expect("extract failed")
+        .expect("cover missing after write");
+    assert_eq!(extracted, png);
+
+    let tagged = Probe::open(&path).unwrap().read().unwrap();
+    let tag = tagged.primary_tag().unwrap();
+    assert_eq!(tag.title().as_deref(), Some("Covered"));
+    let cover = tag.get_picture_type(PictureType::CoverFront).unwrap();
+    assert_eq!(cover.mime_type(), Some(&MimeType::Png));
+    assert!(
+        tag.get_picture_type(PictureType::Artist).is_some(),
+        "other pictures should be kept"
+    );
+
+    // Replacing the cover leaves exactly one front cover.
+    let mut second = cover_bytes();
+    second.push(0xAA);
+    write_album_art(path.to_str().unwrap(), &second, "image/png").expect("second write failed");
+    let tagged = Probe::open(&path).unwrap().read().unwrap();
+    let tag = tagged.primary_tag().unwrap();
+    let covers = tag
+        .pictures()
+        .iter()
+        .filter(|p| p.pic_type() == PictureType::CoverFront)
+        .count();
+    assert_eq!(covers, 1);
+    assert_eq!(tag.pictures()[0].data(), second.as_slice());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cover_validation_rejects_unsupported_or_mislabelled_images() {
+    assert!(validate_cover_image(&cover_bytes(), "image/png").is_ok());
+    assert!(validate_cover_image(&[0xFF, 0xD8, 0xFF, 0xE0], "image/jpg").is_ok());
+    assert!(validate_cover_image(&cover_bytes(), "image/jpeg").is_err());
+    assert!(validate_cover_image(b"RIFF....WEBPVP8 ", "image/webp").is_err());
+    assert!(validate_cover_image(&[], "image/png").is_err());
+}
+
+#[test]
+fn custom_key_is_written_as_txxx_and_read_back() {
+    let dir = temp_dir("tag_custom_key");
+    let path = dir.join("custom.wav");
+    write_wav(&path);
+    let path_str = path.to_string_lossy().to_string();
+
+    write_tag(&path_str, "TIT2", "Known Frame").expect("write title failed");
+    write_tag(&path_str, "MOOD_SOURCE", "hand-picked").expect("write custom failed");
+
+    // The custom key lands in a TXXX frame described by the key.
+    let mut file = fs::File::open(&path).expect("open failed");
+    let wav = lofty::iff::wav::WavFile::read_from(&mut file, lofty::ParseOptions::new())
+        .expect("wav read failed");
+    let id3v2 = wav.id3v2().expect("id3v2 tag missing");
+    assert_eq!(id3v2.get_user_text("MOOD_SOURCE"), Some("hand-picked"));
+
+    let tags = read_all_tags(&path_str).expect("read_all_tags failed");
+    assert_eq!(tags.get("MOOD_SOURCE"), Some(&vec!["hand-picked".to_string()]));
+    assert_eq!(tags.get("TIT2"), Some(&vec!["Known Frame".to_string()]));
+
+    write_tag(&path_str, "MOOD_SOURCE", "replaced").expect("overwrite failed");
+    let tags = read_all_tags(&path_str).expect("read_all_tags failed");
+    assert_eq!(tags.get("MOOD_SOURCE"), Some(&vec!["replaced".to_string()]));
+
+    remove_tag(&path_str, "MOOD_SOURCE").expect("remove failed");
+    let tags = read_all_tags(&path_str).expect("read_all_tags failed");
+    assert!(!tags.contains_key("MOOD_SOURCE"));
+    assert!(tags.contains_key("TIT2"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn tag_keys_are_validated() {
+    assert!(validate_tag_key("REPLAYGAIN_TRACK_GAIN").is_ok());
+    assert!(validate_tag_key("").is_err());
+    assert!(validate_tag_key("A=B").is_err());
+    assert!(validate_tag_key("line\nbreak").is_err());
+    assert!(validate_tag_key(&"K".repeat(65)).is_err());
+}
@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use vplayer::database::Database;
+use vplayer::track_end::{classify, skip_unavailable, TrackEnd, SKIP_UNAVAILABLE_KEY};
+
+fn temp_db_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}.db",
+        test_name,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+fn cleanup_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    let wal = PathBuf::from(format!("{}-wal", path.to_string_lossy()));
+    let shm = PathBuf::from(format!("{}-shm", path.to_string_lossy()));
+    let _ = std::fs::remove_file(wal);
+    let _ = std::fs::remove_file(shm);
+}
+
+#[test]
+fn vanished_file_is_unavailable_rather_than_finished() {
+    let dir = std::env::temp_dir().join(format!("vplayer_track_end_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let track = dir.join("song.mp3");
+    std::fs::write(&track, b"ID3 audio").unwrap();
+
+    assert_eq!(classify(Some(&track), false), TrackEnd::Finished);
+    assert_eq!(classify(Some(&track), true), TrackEnd::ReadError);
+    assert_eq!(classify(None, false), TrackEnd::Finished, "nothing loaded means nothing went away");
+
+    // The drive holding the file goes away mid-play.
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        classify(Some(&track), true),
+        TrackEnd::Unavailable,
+        "the missing file is why reading failed"
+    );
+    assert_eq!(classify(Some(&track), false), TrackEnd::Finished, "a clean end isn't checked on disk");
+}
+
+#[test]
+fn a_directory_in_place_of_the_file_is_unavailable() {
+    let dir = std::env::temp_dir().join(format!("vplayer_track_end_dir_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    assert_eq!(classify(Some(&dir), true), TrackEnd::Unavailable);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skipping_unavailable_tracks_defaults_on() {
+    let db_path = temp_db_path("skip_unavailable");
+    let db = Database::new(&db_path).expect("db init should succeed");
+    assert!(skip_unavailable(&db), "unset means skip");
+
+    db.set_json_setting(SKIP_UNAVAILABLE_KEY, &false).expect("write");
+    assert!(!skip_unavailable(&db));
+
+    db.set_json_setting(SKIP_UNAVAILABLE_KEY, &true).expect("write");
+    assert!(skip_unavailable(&db));
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
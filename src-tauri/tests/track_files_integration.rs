@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lofty::{Accessor, Tag, TagExt, TagType};
+use vplayer::database::Database;
+use vplayer::error::AppError;
+use vplayer::scanner::{Scanner, Track};
+use vplayer::time_utils::now_millis;
+use vplayer::track_files::{move_track_file, refresh_track_metadata};
+
+fn temp_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "vplayer_integration_{}_{}",
+        test_name,
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir failed");
+    dir
+}
+
+fn cleanup(dir: &Path) {
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn track_at(id: &str, path: &Path) -> Track {
+    Track {
+        id: id.to_string(),
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().unwrap().to_string_lossy().to_string(),
+        title: Some("Song".to_string()),
+        artist: Some("Artist".to_string()),
+        album: Some("Album".to_string()),
+        genre: None,
+        year: None,
+        track_number: None,
+        disc_number: None,
+        duration: 120.0,
+        date_added: now_millis(),
+        rating: 0,
+        play_count: 0,
+        last_played: 0,
+    }
+}
+
+/// Write a short silent 16-bit mono WAV tagged with `title` and `artist`.
+fn write_tagged_wav(path: &Path, title: &str, artist: &str) {
+    let data_len = 4410u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&44100u32.to_le_bytes());
+    bytes.extend_from_slice(&(44100u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.resize(44 + data_len as usize, 0);
+    fs::write(path, bytes).expect("write wav failed");
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.set_title(title.to_string());
+    tag.set_artist(artist.to_string());
+    tag.save_to_path(path).expect("tag write failed");
+}
+
+#[test]
+fn move_track_file_renames_on_disk_and_updates_db() {
+    let dir = temp_dir("move_track");
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+
+    let original = dir.join("incoming").join("01 song.mp3");
+    fs::create_dir_all(original.parent().unwrap()).unwrap();
+    fs::write(&original, b"audio bytes").unwrap();
+    db.add_track(&track_at("t1", &original)).expect("track insert should succeed");
+    db.set_album_art("t1", &[1, 2, 3]).expect("art insert should succeed");
+
+    let destination = dir.join("Artist").join("Album").join("01 - Song.mp3");
+    let moved = move_track_file(&db, "t1", &destination.to_string_lossy())
+        .expect("move should succeed");
+
+    assert!(!original.exists(), "source should be gone");
+    assert_eq!(fs::read(&destination).unwrap(), b"audio bytes");
+    assert_eq!(moved.name, "01 - Song.mp3");
+
+    let stored = db.get_track_by_id("t1").unwrap().expect("track should still exist");
+    assert_eq!(stored.path, destination.to_string_lossy());
+    assert_eq!(stored.name, "01 - Song.mp3");
+    assert_eq!(db.get_album_art("t1").unwrap(), Some(vec![1, 2, 3]), "art follows the track id");
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn move_track_file_refuses_to_overwrite() {
+    let dir = temp_dir("move_track_exists");
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+
+    let original = dir.join("a.mp3");
+    let occupied = dir.join("b.mp3");
+    fs::write(&original, b"a").unwrap();
+    fs::write(&occupied, b"b").unwrap();
+    db.add_track(&track_at("t1", &original)).expect("track insert should succeed");
+
+    let err = move_track_file(&db, "t1", &occupied.to_string_lossy())
+        .expect_err("occupied destination must be rejected");
+    assert!(matches!(err, AppError::Validation(_)), "unexpected error: {}", err);
+
+    assert_eq!(fs::read(&original).unwrap(), b"a");
+    assert_eq!(fs::read(&occupied).unwrap(), b"b");
+    assert_eq!(db.get_track_by_id("t1").unwrap().unwrap().path, original.to_string_lossy());
+
+    assert!(matches!(
+        move_track_file(&db, "missing", &dir.join("c.mp3").to_string_lossy()),
+        Err(AppError::NotFound(_))
+    ));
+
+    drop(db);
+    cleanup(&dir);
+}
+
+#[test]
+fn refresh_picks_up_external_tag_edits_and_keeps_rating() {
+    let dir = temp_dir("refresh_metadata");
+    let db = Database::new(&dir.join("library.db")).expect("db init should succeed");
+
+    let path = dir.join("song.wav");
+    write_tagged_wav(&path, "Old Title", "Artist");
+    let track = Scanner::extract_track_info(&path).expect("scan should succeed");
+    let id = track.id.clone();
+    db.add_track(&track).expect("track insert should succeed");
+    db.set_track_rating(&id, 4).unwrap();
+    db.increment_play_count(&id).unwrap();
+    let playlist = db.create_playlist("Favourites").unwrap();
+    db.add_track_to_playlist(&playlist, &id, 0).unwrap();
+
+    // Another program retags the file.
+    write_tagged_wav(&path, "New Title", "Other Artist");
+
+    let refreshed = refresh_track_metadata(&db, &id).expect("refresh should succeed");
+    assert_eq!(refreshed.title.as_deref(), Some("New Title"));
+
+    let stored = db.get_track_by_id(&id).unwrap().expect("track should still exist");
+    assert_eq!(stored.title.as_deref(), Some("New Title"));
+    assert_eq!(stored.artist.as_deref(), Some("Other Artist"));
+    assert_eq!(stored.rating, 4);
+    assert_eq!(stored.play_count, 1);
+    assert_eq!(db.get_playlist_tracks(&playlist).unwrap().len(), 1);
+
+    assert!(matches!(refresh_track_metadata(&db, "missing"), Err(AppError::NotFound(_))));
+
+    drop(db);
+    cleanup(&dir);
+}
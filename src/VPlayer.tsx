@@ -5,6 +5,7 @@ import { useAutoResize } from './hooks/useAutoResize';
 import { useShortcuts } from './hooks/useShortcuts';
 import { useUpdater } from './hooks/useUpdater';
 import { useTrayBehavior } from './hooks/useTrayBehavior';
+import { useOpenedFiles } from './hooks/useOpenedFiles';
 import { useSleepTimer } from './hooks/useSleepTimer';
 import { useTitleBar } from './hooks/useTitleBar';
 import { useCurrentColors } from './hooks/useStoreHooks';
@@ -42,6 +43,9 @@ const VPlayerInner = () => {
   // ── System tray behaviour (close-to-tray, minimize-to-tray, etc.) ─
   useTrayBehavior();
 
+  // ── Files opened via command line / "Open With" ───────────────────
+  useOpenedFiles();
+
   // ── Sleep timer (countdown → pause playback) ──────────────────────
   useSleepTimer();
 
@@ -64,11 +64,11 @@ export function AlbumArt({
         setLoading(true);
         setError(false);
         
-        const base64Data = await AlbumArtLoader.loadArt(trackId, trackPath, abortController.signal);
+        const art = await AlbumArtLoader.loadArt(trackId, trackPath, abortController.signal);
         
         if (mounted) {
-          if (base64Data) {
-            setArtData(`data:image/jpeg;base64,${base64Data}`);
+          if (art) {
+            setArtData(`data:${art.mime};base64,${art.data}`);
           } else {
             setError(true);
           }
@@ -1,6 +1,10 @@
 import { createContext, useContext, useEffect, useMemo, type ReactNode, type MutableRefObject } from 'react';
 import { useCrossfade } from '../hooks/useCrossfade';
 import type { CrossfadeAPI } from '../hooks/useCrossfade';
+import { bandsFromDb } from '../hooks/useEqualizer';
+import { TauriAPI } from '../services/TauriAPI';
+import { useStore } from '../store/useStore';
+import { EVENTS } from '../utils/constants';
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Context type
@@ -30,6 +34,25 @@ export function EffectsProvider({ children, crossfadeRef }: EffectsProviderProps
   // Keep the ref in sync for the AudioEngine's onEnded/onDeviceLost callbacks
   useEffect(() => { crossfadeRef.current = crossfade; });
 
+  // ── Genre EQ ──────────────────────────────────────────────────────
+  // The backend swaps EQ presets as tracks load; show its bands without
+  // sending them back.
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    TauriAPI.onEvent<{ eqBands: number[] }>(EVENTS.EQ_BANDS_CHANGED, (event) => {
+      const { eqBands, setEqBands } = useStore.getState();
+      setEqBands(bandsFromDb(eqBands, event.payload.eqBands));
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
   const value = useMemo<EffectsContextValue>(() => ({ crossfade }), [crossfade]);
 
   return (
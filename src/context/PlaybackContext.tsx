@@ -96,6 +96,8 @@ export function PlaybackProvider({ children, playerHookRef, tracksRef }: Playbac
   const setPlaying = useStore(s => s.setPlaying);
   const volume = useStore(s => s.volume);
   const setVolume = useStore(s => s.setVolume);
+  const muted = useStore(s => s.muted);
+  const setMuted = useStore(s => s.setMuted);
   const shuffle = useStore(s => s.shuffle);
   const repeatMode = useStore(s => s.repeatMode);
   const setLoadingTrackIndex = useStore(s => s.setLoadingTrackIndex);
@@ -119,7 +121,7 @@ export function PlaybackProvider({ children, playerHookRef, tracksRef }: Playbac
 
   const playerHook = usePlayerHook({
     audio,
-    player: { currentTrack, setCurrentTrack, shuffle, repeatMode, progress, duration, volume, setVolume },
+    player: { currentTrack, setCurrentTrack, shuffle, repeatMode, progress, duration, volume, setVolume, muted, setMuted },
     tracks: playbackTracks,
     toast,
     crossfade,
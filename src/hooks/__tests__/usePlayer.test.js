@@ -1,6 +1,17 @@
 import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { renderHook, act } from '@testing-library/react';
 import { usePlayer } from '../usePlayer';
+import { TauriAPI } from '../../services/TauriAPI';
+
+vi.mock('../../services/TauriAPI', () => ({
+  TauriAPI: {
+    preloadTrack: vi.fn().mockResolvedValue(undefined),
+    incrementSkipCount: vi.fn().mockResolvedValue(undefined),
+    shouldCrossfade: vi.fn().mockResolvedValue(true),
+    toggleMute: vi.fn().mockResolvedValue(true),
+    setMuted: vi.fn().mockResolvedValue(undefined),
+  },
+}));
 
 describe('usePlayer', () => {
   let mockAudio;
@@ -25,6 +36,8 @@ describe('usePlayer', () => {
       duration: 180,
       volume: 0.7,
       setVolume: vi.fn(),
+      muted: false,
+      setMuted: vi.fn(),
     };
 
     // Mock toast service
@@ -225,10 +238,32 @@ describe('usePlayer', () => {
       expect(mockAudio.changeVolume).toHaveBeenCalledWith(0.5);
     });
 
-    it('should toggle mute', () => {
+    it('should toggle mute through the backend', async () => {
       const tracks = [{ id: '1', title: 'Track 1' }];
 
-      mockPlayer.volume = 0.7;
+      const { result } = renderHook(() =>
+        usePlayer({
+          audio: mockAudio,
+          player: mockPlayer,
+          tracks,
+          toast: mockToast,
+          crossfade: mockCrossfade,
+        })
+      );
+
+      await act(async () => {
+        result.current.handleToggleMute();
+      });
+
+      expect(TauriAPI.toggleMute).toHaveBeenCalled();
+      expect(mockPlayer.setMuted).toHaveBeenCalledWith(true);
+      // The volume level is kept for unmuting
+      expect(mockPlayer.setVolume).not.toHaveBeenCalled();
+    });
+
+    it('should unmute when the volume is raised while muted', () => {
+      const tracks = [{ id: '1', title: 'Track 1' }];
+      mockPlayer.muted = true;
 
       const { result } = renderHook(() =>
         usePlayer({
@@ -241,11 +276,54 @@ describe('usePlayer', () => {
       );
 
       act(() => {
-        result.current.handleToggleMute();
+        result.current.handleVolumeChange(0.4);
       });
 
-      // Should mute (set to 0)
-      expect(mockPlayer.setVolume).toHaveBeenCalledWith(0);
+      expect(mockPlayer.setMuted).toHaveBeenCalledWith(false);
+      expect(TauriAPI.setMuted).toHaveBeenCalledWith(false);
+    });
+  });
+
+  describe('gapless preload', () => {
+    it('should preload the new upcoming track when play next changes it', () => {
+      TauriAPI.preloadTrack.mockClear();
+      const tracks = [
+        { id: '1', title: 'Track 1', path: '/music/1.mp3' },
+        { id: '2', title: 'Track 2', path: '/music/2.mp3' },
+        { id: '3', title: 'Track 3', path: '/music/3.mp3' },
+      ];
+      let queue = [];
+      const store = {
+        gaplessPlayback: true,
+        activePlaybackTracks: tracks,
+        get queue() { return queue; },
+        peekNextInQueue: () => queue[0] ?? null,
+        clearShuffleState: vi.fn(),
+      };
+      mockPlayer.progress = 178;
+
+      const { rerender } = renderHook(
+        ({ player }) =>
+          usePlayer({
+            audio: mockAudio,
+            player,
+            tracks,
+            toast: mockToast,
+            crossfade: mockCrossfade,
+            storeGetter: () => store,
+          }),
+        { initialProps: { player: mockPlayer } }
+      );
+      expect(TauriAPI.preloadTrack).toHaveBeenCalledWith('/music/2.mp3');
+
+      // "Play next" puts track 3 ahead of track 2.
+      queue = [tracks[2]];
+      rerender({ player: { ...mockPlayer, progress: 178.5 } });
+      expect(TauriAPI.preloadTrack).toHaveBeenLastCalledWith('/music/3.mp3');
+
+      // Later progress ticks don't preload it again.
+      rerender({ player: { ...mockPlayer, progress: 179 } });
+      expect(TauriAPI.preloadTrack).toHaveBeenCalledTimes(2);
     });
   });
 });
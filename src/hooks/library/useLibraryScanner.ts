@@ -104,8 +104,8 @@ export function useLibraryScanner({ libraryFolders, loadAllTracks, loadAllFolder
 
         // Listen for total files count
         unlistenPromises.push(
-            TauriAPI.onEvent<number>(EVENTS.SCAN_TOTAL, (event) => {
-                setScanTotal(event.payload);
+            TauriAPI.onEvent<{ total: number }>(EVENTS.SCAN_TOTAL, (event) => {
+                setScanTotal(event.payload.total);
                 setScanCurrent(0);
                 setScanProgress(0);
             })
@@ -113,11 +113,11 @@ export function useLibraryScanner({ libraryFolders, loadAllTracks, loadAllFolder
 
         // Listen for progress updates
         unlistenPromises.push(
-            TauriAPI.onEvent<{ current: number; total: number; current_file: string }>(EVENTS.SCAN_PROGRESS, (event) => {
-                const { current, total, current_file } = event.payload;
+            TauriAPI.onEvent<{ phase: 'reading' | 'analyzing'; current: number; total: number; currentFile: string; path: string }>(EVENTS.SCAN_PROGRESS, (event) => {
+                const { current, total, currentFile } = event.payload;
                 setScanCurrent(current);
                 setScanTotal(total);
-                setScanCurrentFile(current_file);
+                setScanCurrentFile(currentFile);
 
                 // Calculate percentage
                 const percent = total > 0 ? Math.round((current / total) * 100) : 0;
@@ -127,8 +127,8 @@ export function useLibraryScanner({ libraryFolders, loadAllTracks, loadAllFolder
 
         // Listen for scan completion
         unlistenPromises.push(
-            TauriAPI.onEvent<number>(EVENTS.SCAN_COMPLETE, (event) => {
-                log.info(`Scan complete: ${event.payload} tracks found`);
+            TauriAPI.onEvent<{ trackCount: number }>(EVENTS.SCAN_COMPLETE, (event) => {
+                log.info(`Scan complete: ${event.payload.trackCount} tracks found`);
                 setScanProgress(100);
                 setScanCurrentFile('');
 
@@ -144,8 +144,8 @@ export function useLibraryScanner({ libraryFolders, loadAllTracks, loadAllFolder
 
         // Listen for scan cancellation
         unlistenPromises.push(
-            TauriAPI.onEvent<number>(EVENTS.SCAN_CANCELLED, (event) => {
-                log.info(`Scan cancelled: ${event.payload} tracks processed`);
+            TauriAPI.onEvent<{ processed: number }>(EVENTS.SCAN_CANCELLED, (event) => {
+                log.info(`Scan cancelled: ${event.payload.processed} tracks processed`);
                 setScanCurrentFile('Cancelled');
 
                 // Reset scanning state
@@ -161,8 +161,9 @@ export function useLibraryScanner({ libraryFolders, loadAllTracks, loadAllFolder
 
         // Listen for scan errors
         unlistenPromises.push(
-            TauriAPI.onEvent<string>(EVENTS.SCAN_ERROR, (event) => {
-                console.warn('Scan error:', event.payload);
+            TauriAPI.onEvent<{ path: string; fileName: string; kind: string; message: string }>(EVENTS.SCAN_ERROR, (event) => {
+                const { path, kind, message } = event.payload;
+                console.warn(`Scan error (${kind}) for ${path}:`, message);
             })
         );
 
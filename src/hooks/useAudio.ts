@@ -35,6 +35,26 @@ interface PlaybackTickPayload {
   isFinished: boolean;
 }
 
+/** Emitted instead of `track-ended` when reading a track failed partway. */
+interface TrackErrorPayload {
+  path: string | null;
+  position: number;
+  message: string;
+}
+
+/** Emitted instead of `track-ended` when the current file can no longer be read. */
+interface TrackUnavailablePayload {
+  path: string | null;
+  position: number;
+  autoSkip: boolean;
+}
+
+/** Emitted after the Rust side reopened the output stream. */
+interface AudioReinitializedPayload {
+  deviceName: string | null;
+  position: number;
+}
+
 /**
  * Audio playback hook – event-driven.
  *
@@ -97,8 +117,11 @@ export function useAudio({ onEnded, onDeviceLost, onTimeUpdate, initialVolume =
   useEffect(() => {
     let unlistenTick: UnlistenFn | undefined;
     let unlistenEnded: UnlistenFn | undefined;
+    let unlistenTrackError: UnlistenFn | undefined;
+    let unlistenUnavailable: UnlistenFn | undefined;
     let unlistenDeviceLost: UnlistenFn | undefined;
     let unlistenDeviceRecovered: UnlistenFn | undefined;
+    let unlistenReinitialized: UnlistenFn | undefined;
 
     const setup = async () => {
       unlistenTick = await TauriAPI.onEvent<PlaybackTickPayload>('playback-tick', (event) => {
@@ -148,6 +171,33 @@ export function useAudio({ onEnded, onDeviceLost, onTimeUpdate, initialVolume =
         if (onEndedRef.current) onEndedRef.current();
       });
 
+      // Track-error: reading the file failed partway through. Say so
+      // instead of passing it off as a normal end, then move on.
+      unlistenTrackError = await TauriAPI.onEvent<TrackErrorPayload>('track-error', (event) => {
+        const { path, position, message } = event.payload;
+        log.warn(`[Audio] Read error in ${path ?? 'current track'} at ${position.toFixed(1)}s: ${message}`);
+        const name = path?.split(/[\\/]/).pop() ?? 'track';
+        toast.showError(`Playback stopped early: ${name} could not be read`);
+        useStore.getState().setProgress(0);
+        if (onEndedRef.current) onEndedRef.current();
+      });
+
+      // Track-unavailable: the file went away mid-play (network share,
+      // USB disk). Skip to the next track or stop, as configured.
+      unlistenUnavailable = await TauriAPI.onEvent<TrackUnavailablePayload>('track-unavailable', (event) => {
+        const { path, position, autoSkip } = event.payload;
+        log.warn(`[Audio] ${path ?? 'Current track'} became unavailable at ${position.toFixed(1)}s`);
+        const name = path?.split(/[\\/]/).pop() ?? 'Track';
+        if (autoSkip) {
+          toast.showWarning(`${name} is no longer available — skipping`);
+          useStore.getState().setProgress(0);
+          if (onEndedRef.current) onEndedRef.current();
+        } else {
+          toast.showWarning(`${name} is no longer available`);
+          useStore.getState().setPlaying(false);
+        }
+      });
+
       // Device-lost: the Rust broadcast thread detected the audio device
       // disappeared while playing. Pause the UI and show a recoverable
       // error instead of advancing to the next track (which would also fail).
@@ -167,6 +217,18 @@ export function useAudio({ onEnded, onDeviceLost, onTimeUpdate, initialVolume =
         useStore.getState().setPlaying(true);
         toast.showSuccess('Audio device reconnected');
       });
+
+      // Audio-reinitialized: the backend reopened the output (device switch,
+      // long pause, recovery) and reloaded the track. Resync position and
+      // push the UI volume to the new sink.
+      unlistenReinitialized = await TauriAPI.onEvent<AudioReinitializedPayload>('audio-reinitialized', (event) => {
+        const { deviceName, position } = event.payload;
+        log.info(`[Audio] Output reinitialized on ${deviceName ?? 'unknown device'} at ${position.toFixed(1)}s`);
+        useStore.getState().setProgress(position);
+        TauriAPI.setVolume(useStore.getState().volume).catch((err) => {
+          log.warn('[Audio] Failed to restore volume after reinit', err);
+        });
+      });
     };
 
     setup();
@@ -174,8 +236,11 @@ export function useAudio({ onEnded, onDeviceLost, onTimeUpdate, initialVolume =
     return () => {
       unlistenTick?.();
       unlistenEnded?.();
+      unlistenTrackError?.();
+      unlistenUnavailable?.();
       unlistenDeviceLost?.();
       unlistenDeviceRecovered?.();
+      unlistenReinitialized?.();
     };
   }, []);
 
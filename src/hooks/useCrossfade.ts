@@ -1,7 +1,8 @@
-import { useEffect, useCallback, useRef } from 'react';
+import { useEffect, useCallback, useRef, useState } from 'react';
 import { CROSSFADE_CONFIG } from '../utils/constants';
 import { log } from '../utils/logger';
-import { useStore } from '../store/useStore';
+import { TauriAPI } from '../services/TauriAPI';
+import { useStore, takeLegacyCrossfadeDuration } from '../store/useStore';
 
 export interface CrossfadeParams {
   setVolume: (vol: number) => void;
@@ -32,11 +33,11 @@ export interface CrossfadeAPI {
  * @returns {Object} Crossfade control interface
  */
 export function useCrossfade(): CrossfadeAPI {
-  // Read/write directly from Zustand store (persisted) — no local useState copy
+  // The on/off switch lives in the Zustand store (persisted); the length is
+  // owned by the backend, which keeps it apart from the sleep timer fade.
   const enabled = useStore(state => state.crossfadeEnabled);
-  const duration = useStore(state => state.crossfadeDuration);
   const setStoredEnabled = useStore(state => state.setCrossfadeEnabled);
-  const setStoredDuration = useStore(state => state.setCrossfadeDuration);
+  const [duration, setDuration] = useState<number>(CROSSFADE_CONFIG.DEFAULT_DURATION_MS);
 
   const fadeIntervalRef = useRef<number | null>(null);
   const fadeTimeoutRef = useRef<number | null>(null);
@@ -49,13 +50,31 @@ export function useCrossfade(): CrossfadeAPI {
     setStoredEnabled(!enabled);
   }, [enabled, setStoredEnabled]);
 
+  useEffect(() => {
+    // Carry over a length persisted by older versions, then rewrite the
+    // store so the old key is dropped.
+    const legacy = takeLegacyCrossfadeDuration();
+    const load = legacy !== null
+      ? TauriAPI.setCrossfadeSecs(legacy / 1000).then(durations => {
+          useStore.setState({});
+          return durations;
+        })
+      : TauriAPI.getFadeDurations();
+    load
+      .then(durations => setDuration(durations.crossfadeSecs * 1000))
+      .catch(err => log.warn('[Crossfade] Failed to load crossfade length:', err));
+  }, []);
+
   const setDurationMs = useCallback((ms: number) => {
     const clamped = Math.max(
       CROSSFADE_CONFIG.MIN_DURATION_MS,
       Math.min(CROSSFADE_CONFIG.MAX_DURATION_MS, ms)
     );
-    setStoredDuration(clamped);
-  }, [setStoredDuration]);
+    setDuration(clamped);
+    TauriAPI.setCrossfadeSecs(clamped / 1000)
+      .then(durations => setDuration(durations.crossfadeSecs * 1000))
+      .catch(err => log.warn('[Crossfade] Failed to save crossfade length:', err));
+  }, []);
 
   /**
    * Check if crossfade should start based on current progress
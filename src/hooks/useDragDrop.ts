@@ -27,7 +27,7 @@ export interface DragDropAPI {
  * Handles both internal track dragging and external file drops
  * 
  * Supported external drops:
- * - Audio files (.mp3, .flac, .wav, .ogg, .m4a, .aac, .wma)
+ * - Audio files (.mp3, .flac, .wav, .ogg, .m4a, .aac, .wma), and video files played for their audio (.mp4, .m4v, .mkv, .webm)
  * - Folders containing audio files
  * 
  * @param {Object} params
@@ -81,7 +81,7 @@ export function useDragDrop({ addFolder, refreshTracks, toast }: DragDropParams)
 
               for (const path of uniquePaths) {
                 // Check if path is a directory by seeing if it doesn't have a common audio extension
-                const isFolder = !path.match(/\.(mp3|flac|wav|ogg|m4a|aac|wma|opus)$/i);
+                const isFolder = !path.match(/\.(mp3|flac|wav|ogg|m4a|aac|wma|opus|mp4|m4v|mkv|webm)$/i);
 
                 if (isFolder) {
                   // 1. Scan for new/modified files so the library is up to date.
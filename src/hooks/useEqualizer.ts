@@ -4,6 +4,14 @@ import { TauriAPI } from '../services/TauriAPI';
 import { useStore } from '../store/useStore';
 import type { EqBand } from '../store/types';
 
+/** UI bands (0-100, 50 = 0 dB) holding the given gains in dB (-12..+12) */
+export function bandsFromDb(bands: EqBand[], gains: number[]): EqBand[] {
+  return bands.map((band, index) => ({
+    ...band,
+    value: Math.round(50 + (gains[index] ?? 0) * (50 / 12)),
+  }));
+}
+
 export interface EqualizerAPI {
   eqBands: EqBand[];
   setEqBands: (bands: EqBand[]) => void;
@@ -66,14 +74,7 @@ export function useEqualizer(): EqualizerAPI {
     const preset = (EQ_PRESETS as Record<string, { name: string; bands: number[] }>)[presetName];
     if (!preset) return;
 
-    const newBands = eqBands.map((band, index) => ({
-      ...band,
-      // Presets are in dB (-12..+12); UI slider is 0-100 (50 = 0 dB)
-      // Convert: ui = 50 + dB * (50/12)
-      value: Math.round(50 + preset.bands[index] * (50 / 12))
-    }));
-
-    setEqBands(newBands);
+    setEqBands(bandsFromDb(eqBands, preset.bands));
     currentPresetRef.current = presetName;
   }, [eqBands, setEqBands]);
 
@@ -0,0 +1,51 @@
+/**
+ * useOpenedFiles – picks up audio files handed to VPlayer on the command line
+ * or through the OS "Open With" menu, including files opened while VPlayer is
+ * already running. The backend has already started playing the first file;
+ * this hook mirrors that into the queue.
+ *
+ * Mount this once in VPlayer (or App).
+ */
+import { useEffect } from 'react';
+import { useStore } from '../store/useStore';
+import { TauriAPI, type OpenRequest } from '../services/TauriAPI';
+import type { Track } from '../types';
+
+function trackFromPath(path: string): Track {
+  const name = path.split(/[\\/]/).pop() || path;
+  return { id: path, path, name, duration: 0 };
+}
+
+function applyOpenRequest(request: OpenRequest | null) {
+  if (!request) return;
+  const tracks = [request.play, ...request.enqueue].map(trackFromPath);
+  const { replaceQueue, setPlaying } = useStore.getState();
+  replaceQueue(tracks, 0);
+  setPlaying(true);
+}
+
+export function useOpenedFiles() {
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+
+    // Every request goes through takeOpenedFiles so the backend copy is
+    // cleared once delivered and a request is never applied twice.
+    const takePending = () => TauriAPI.takeOpenedFiles().then(applyOpenRequest).catch(() => {});
+
+    // Files passed at launch may have been dispatched before we mounted.
+    takePending();
+
+    TauriAPI.onEvent<OpenRequest>('open-file', () => {
+      takePending();
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    }).catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+}
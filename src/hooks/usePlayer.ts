@@ -1,5 +1,5 @@
 import { useCallback, useRef, useEffect } from 'react';
-import { SEEK_THRESHOLD_SECONDS } from '../utils/constants';
+import { SEEK_THRESHOLD_SECONDS, SKIP_COUNT_THRESHOLD } from '../utils/constants';
 import { TauriAPI } from '../services/TauriAPI';
 import { log } from '../utils/logger';
 import type {
@@ -47,14 +47,19 @@ export function usePlayer({
         progress,
         duration,
         volume,
-        setVolume
+        setVolume,
+        muted,
+        setMuted
     } = player;
 
-    const nextTrackPreloadedRef = useRef<boolean>(false);
+    // Path of the upcoming track handed to the backend preload. When the
+    // upcoming track changes (e.g. "play next" inserts before it), the new
+    // one is preloaded in its place.
+    const preloadedPathRef = useRef<string | null>(null);
     const crossfadeStartedRef = useRef<boolean>(false);
     const crossfadeInProgressRef = useRef<boolean>(false);
-    const previousVolumeRef = useRef<number>(0.7);
     const userVolumeRef = useRef<number>(volume);
+    const currentTrackRef = useRef<number | null>(currentTrack);
     const seekTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
     const lastSeekTimeRef = useRef<number>(0);
 
@@ -65,6 +70,10 @@ export function usePlayer({
         }
     }, [volume]);
 
+    useEffect(() => {
+        currentTrackRef.current = currentTrack;
+    }, [currentTrack]);
+
     // Cleanup timeout on unmount
     useEffect(() => {
         return () => {
@@ -209,25 +218,44 @@ export function usePlayer({
 
             if (nextIdx !== null && nextIdx !== currentTrack) {
                 crossfadeStartedRef.current = true;
-                crossfadeInProgressRef.current = true;
-                log.info('[Crossfade] Initiating crossfade to track:', nextIdx);
-
-                crossfade.startCrossfade({
-                    setVolume: (vol: number) => {
-                        audio.changeVolume(vol).catch(err => console.error('Volume change failed:', err));
-                    },
-                    currentVolume: userVolumeRef.current,
-                    onMidpoint: () => {
-                        setCurrentTrack(nextIdx);
-                    },
-                    onComplete: () => {
-                        crossfadeStartedRef.current = false;
-                        crossfadeInProgressRef.current = false;
-                        nextTrackPreloadedRef.current = false;
-                        audio.changeVolume(userVolumeRef.current).catch(err =>
-                            console.error('Failed to restore volume:', err)
-                        );
+                const fromTrack = currentTrack;
+                const fromPath = tracks[currentTrack]?.path;
+                const toPath = tracks[nextIdx]?.path;
+                const allowed = fromPath && toPath
+                    ? TauriAPI.shouldCrossfade(fromPath, toPath).catch(() => true)
+                    : Promise.resolve(true);
+
+                allowed.then((fade) => {
+                    // The answer is stale if the track changed or the
+                    // crossfade was reset while the backend was asked.
+                    if (currentTrackRef.current !== fromTrack || !crossfadeStartedRef.current) {
+                        return;
+                    }
+                    if (!fade) {
+                        // Same album under "only between albums": let the gapless swap handle it.
+                        log.info('[Crossfade] Skipping crossfade to track:', nextIdx);
+                        return;
                     }
+                    crossfadeInProgressRef.current = true;
+                    log.info('[Crossfade] Initiating crossfade to track:', nextIdx);
+
+                    crossfade.startCrossfade({
+                        setVolume: (vol: number) => {
+                            audio.changeVolume(vol).catch(err => console.error('Volume change failed:', err));
+                        },
+                        currentVolume: userVolumeRef.current,
+                        onMidpoint: () => {
+                            setCurrentTrack(nextIdx);
+                        },
+                        onComplete: () => {
+                            crossfadeStartedRef.current = false;
+                            crossfadeInProgressRef.current = false;
+                            preloadedPathRef.current = null;
+                            audio.changeVolume(userVolumeRef.current).catch(err =>
+                                console.error('Failed to restore volume:', err)
+                            );
+                        }
+                    });
                 });
             }
         }
@@ -248,24 +276,24 @@ export function usePlayer({
         const preloadLeadTime = Math.max(5, crossfadeSecs + 2);
         const timeRemaining = duration - progress;
 
-        if (timeRemaining <= preloadLeadTime && timeRemaining > 0 && !nextTrackPreloadedRef.current) {
+        if (timeRemaining <= preloadLeadTime && timeRemaining > 0) {
             const nextIdx = peekNextTrackIndex(currentTrack, tracks.length, shuffle, repeatMode);
 
             if (nextIdx !== null && nextIdx !== currentTrack) {
                 const nextTrack = tracks[nextIdx];
-                if (nextTrack?.path) {
+                if (nextTrack?.path && nextTrack.path !== preloadedPathRef.current) {
                     log.info(`[Gapless] Preloading next track: ${nextTrack.title || nextTrack.name}`);
-                    nextTrackPreloadedRef.current = true;
+                    preloadedPathRef.current = nextTrack.path;
                     TauriAPI.preloadTrack(nextTrack.path).catch(err => {
                         console.warn('[Gapless] Preload failed:', err);
-                        nextTrackPreloadedRef.current = false;
+                        preloadedPathRef.current = null;
                     });
                 }
             }
         }
 
         if (progress < 1) {
-            nextTrackPreloadedRef.current = false;
+            preloadedPathRef.current = null;
         }
     }, [progress, duration, currentTrack, tracks, shuffle, repeatMode, crossfade, peekNextTrackIndex, storeGetter]);
 
@@ -292,12 +320,21 @@ export function usePlayer({
 
         const nextIdx = consumeNextTrackIndex(currentTrackIdx, currentTracks.length, currentShuffle, currentRepeatMode);
         if (nextIdx !== null) {
+            // Leaving before the halfway point counts as a skip; natural track
+            // ends arrive here with progress at the end and are not counted.
+            const skippedId = currentTracks[currentTrackIdx]?.id;
+            const currentProgress = store?.progress ?? progress;
+            const currentDuration = store?.duration ?? duration;
+            if (skippedId && currentDuration > 0 && currentProgress < currentDuration * SKIP_COUNT_THRESHOLD) {
+                TauriAPI.incrementSkipCount(skippedId)
+                    .catch(err => console.warn('Failed to record skip:', err));
+            }
             // Record current track in shuffle history before moving forward
             if (currentShuffle && store) {
                 store.pushShuffleHistory(currentTrackIdx);
             }
             setCurrentTrack(nextIdx);
-            nextTrackPreloadedRef.current = false;
+            preloadedPathRef.current = null;
             crossfadeStartedRef.current = false;
         }
     }, [setCurrentTrack, crossfade, audio, consumeNextTrackIndex]);
@@ -364,7 +401,7 @@ export function usePlayer({
                 setCurrentTrack(currentTracks.length - 1);
             }
         }
-        nextTrackPreloadedRef.current = false;
+        preloadedPathRef.current = null;
         crossfadeStartedRef.current = false;
     }, [audio, setCurrentTrack, toast, crossfade]);
 
@@ -407,7 +444,12 @@ export function usePlayer({
             console.error('Failed to change volume:', err);
             toast.showError('Failed to change volume');
         });
-    }, [audio, setVolume, toast]);
+        // Raising the volume while muted is a request to hear it
+        if (muted && newVolume > 0) {
+            setMuted(false);
+            TauriAPI.setMuted(false).catch(err => console.error('Failed to unmute:', err));
+        }
+    }, [audio, setVolume, muted, setMuted, toast]);
 
     /**
      * Increase volume by step
@@ -429,16 +471,16 @@ export function usePlayer({
 
     /**
      * Toggle mute state
-     * Mutes to 0, unmutes to previous volume
+     * The backend silences output and keeps the volume level for unmuting
      */
     const handleToggleMute = useCallback(() => {
-        if (volume > 0) {
-            previousVolumeRef.current = userVolumeRef.current;
-            handleVolumeChange(0);
-        } else {
-            handleVolumeChange(previousVolumeRef.current || 0.7);
-        }
-    }, [volume, handleVolumeChange]);
+        TauriAPI.toggleMute()
+            .then(setMuted)
+            .catch(err => {
+                console.error('Failed to toggle mute:', err);
+                toast.showError('Failed to toggle mute');
+            });
+    }, [setMuted, toast]);
 
     return {
         handleNextTrack,
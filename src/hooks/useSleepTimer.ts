@@ -1,14 +1,24 @@
 import { useEffect, useRef } from 'react';
 import { useStore } from '../store/useStore';
+import { TauriAPI } from '../services/TauriAPI';
+import { SLEEP_FADE_CONFIG } from '../utils/constants';
+import { log } from '../utils/logger';
+
+/** Countdown tick; short enough for the fade-out to sound smooth. */
+const TICK_MS = 250;
+/** Delay before restoring the volume, so the pause lands first. */
+const RESTORE_VOLUME_DELAY_MS = 500;
 
 /**
  * Sleep timer hook.
  *
- * When `sleepTimerMinutes` > 0, starts a countdown. When it hits zero,
- * pauses playback and resets the timer setting to 0.
+ * When `sleepTimerMinutes` > 0, starts a countdown. For the last
+ * `sleepFadeSecs` (a backend setting, separate from the crossfade length)
+ * the output volume ramps down; when the countdown hits zero, pauses
+ * playback, restores the volume and resets the timer setting to 0.
  *
- * The countdown is driven by a 1-second interval. The remaining time
- * is tracked via a ref so the interval doesn't re-create on every tick.
+ * The countdown runs against a deadline tracked via refs, so the interval
+ * doesn't re-create on every tick.
  *
  * Mount this once in VPlayer.tsx.
  */
@@ -17,7 +27,9 @@ export function useSleepTimer(): void {
   const setSleepTimerMinutes = useStore(s => s.setSleepTimerMinutes);
   const setPlaying = useStore(s => s.setPlaying);
 
-  const remainingRef = useRef<number>(0);
+  const deadlineRef = useRef<number>(0);
+  const fadeSecsRef = useRef<number>(SLEEP_FADE_CONFIG.DEFAULT_SECS);
+  const fadingRef = useRef<boolean>(false);
   const intervalRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
   // When the user sets a new timer value, (re)start the countdown
@@ -28,31 +40,63 @@ export function useSleepTimer(): void {
       intervalRef.current = null;
     }
 
+    const restoreVolume = () => {
+      TauriAPI.setVolume(useStore.getState().volume).catch(err => {
+        log.warn('[SleepTimer] Failed to restore volume:', err);
+      });
+    };
+
+    // A timer cancelled or changed mid-fade gets its volume back
+    if (fadingRef.current) {
+      fadingRef.current = false;
+      restoreVolume();
+    }
+
     if (sleepTimerMinutes <= 0) {
-      remainingRef.current = 0;
+      deadlineRef.current = 0;
       return;
     }
 
-    remainingRef.current = sleepTimerMinutes * 60; // convert to seconds
+    deadlineRef.current = Date.now() + sleepTimerMinutes * 60 * 1000;
+    TauriAPI.getFadeDurations()
+      .then(durations => { fadeSecsRef.current = durations.sleepFadeSecs; })
+      .catch(err => log.warn('[SleepTimer] Failed to load fade length:', err));
 
     intervalRef.current = setInterval(() => {
-      remainingRef.current -= 1;
+      const remainingSecs = (deadlineRef.current - Date.now()) / 1000;
+      // Read fresh state to check if actually playing
+      const state = useStore.getState();
 
-      if (remainingRef.current <= 0) {
+      if (remainingSecs <= 0) {
         // Time's up — pause playback and reset timer
         if (intervalRef.current) {
           clearInterval(intervalRef.current);
           intervalRef.current = null;
         }
 
-        // Read fresh state to check if actually playing
-        const state = useStore.getState();
         if (state.playing) {
           state.setPlaying(false);
         }
+        if (fadingRef.current) {
+          fadingRef.current = false;
+          setTimeout(restoreVolume, RESTORE_VOLUME_DELAY_MS);
+        }
         state.setSleepTimerMinutes(0);
+        return;
+      }
+
+      const fadeSecs = fadeSecsRef.current;
+      if (state.playing && fadeSecs > 0 && remainingSecs <= fadeSecs) {
+        fadingRef.current = true;
+        TauriAPI.setVolume(state.volume * (remainingSecs / fadeSecs)).catch(err => {
+          log.warn('[SleepTimer] Failed to fade volume:', err);
+        });
+      } else if (fadingRef.current && !state.playing) {
+        // Paused by hand mid-fade; resuming shouldn't stay quiet
+        fadingRef.current = false;
+        restoreVolume();
       }
-    }, 1000);
+    }, TICK_MS);
 
     return () => {
       if (intervalRef.current) {
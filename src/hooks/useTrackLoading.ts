@@ -1,8 +1,6 @@
 import { useState, useEffect, useRef } from 'react';
 import { ERROR_MESSAGES, DEFAULT_PREFERENCES } from '../utils/constants';
 import { TauriAPI } from '../services/TauriAPI';
-import { useReplayGain } from './useReplayGain';
-import { useReplayGain } from './useReplayGain';
 import { useStore } from '../store/useStore';
 import { devCounters } from '../utils/devCounters';
 import { confirm as nativeConfirm } from '@tauri-apps/plugin-dialog';
@@ -44,9 +42,6 @@ export function useTrackLoading({
   const lastToastTrackId = useRef<string | null>(null);
   const shouldRestorePosition = useRef(true);
 
-  // ReplayGain hook for volume normalization
-  const replayGain = useReplayGain();
-
   // Note: Startup restore logic is handled by useStartupRestore hook.
   // hasRestoredTrack / setHasRestoredTrack are still exposed here so that
   // the restore hook can coordinate with track loading state.
@@ -144,9 +139,6 @@ export function useTrackLoading({
         setLoadedTrackId(track.id);
         setLoadingTrackIndex(null);
 
-        // Apply ReplayGain if enabled
-        await replayGain.applyReplayGain(track);
-
         // Restore last position if we should
         const shouldRestore = shouldRestorePosition.current && track.id === freshState.lastTrackId;
         if (shouldRestore) {
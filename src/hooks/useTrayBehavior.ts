@@ -1,6 +1,7 @@
 /**
  * useTrayBehavior – syncs tray-related settings to the Rust backend and
- * handles `minimizeToTray` / `startMinimized` from the JS side.
+ * handles `minimizeToTray` from the JS side. Starting minimized is handled
+ * by the backend at startup.
  *
  * Mount this once in VPlayer (or App).
  */
@@ -8,36 +9,17 @@ import { useEffect, useRef } from 'react';
 import { useStore } from '../store/useStore';
 import { TauriAPI } from '../services/TauriAPI';
 
-let startMinimizedHandled = false;
-
 export function useTrayBehavior() {
   const closeToTray = useStore(s => s.closeToTray);
   const minimizeToTray = useStore(s => s.minimizeToTray);
-  const startMinimized = useStore(s => s.startMinimized);
 
   const minimizeToTrayRef = useRef(minimizeToTray);
   minimizeToTrayRef.current = minimizeToTray;
 
   // ── Sync settings to Rust whenever they change ─────────────────────
   useEffect(() => {
-    TauriAPI.setTraySettings(closeToTray, minimizeToTray, startMinimized).catch(() => {});
-  }, [closeToTray, minimizeToTray, startMinimized]);
-
-  // ── startMinimized: hide the window once on first mount ────────────
-  useEffect(() => {
-    if (startMinimized && !startMinimizedHandled) {
-      startMinimizedHandled = true;
-      (async () => {
-        try {
-          const { getCurrentWindow } = await import('@tauri-apps/api/window');
-          await getCurrentWindow().hide();
-        } catch { /* not in Tauri context */ }
-      })();
-    } else {
-      startMinimizedHandled = true;
-    }
-    // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, []); // run once
+    TauriAPI.setTraySettings(closeToTray, minimizeToTray).catch(() => {});
+  }, [closeToTray, minimizeToTray]);
 
   // ── minimizeToTray: listen for window state changes and hide when minimized ─
   useEffect(() => {
@@ -1,10 +1,10 @@
-import { TauriAPI } from './TauriAPI';
+import { TauriAPI, type AlbumArtData } from './TauriAPI';
 import { devCounters } from '../utils/devCounters';
 
 interface QueueItem {
   trackId: string;
   trackPath: string;
-  resolve: (data: string | null) => void;
+  resolve: (data: AlbumArtData | null) => void;
   reject: (err: Error) => void;
   signal?: AbortSignal;
 }
@@ -12,7 +12,7 @@ interface QueueItem {
 const MAX_FAILED_CACHE_SIZE = 1000;
 
 class AlbumArtLoaderService {
-  private inFlight = new Map<string, Promise<string | null>>();
+  private inFlight = new Map<string, Promise<AlbumArtData | null>>();
   private queue: QueueItem[] = [];
   private activeCount = 0;
   private maxConcurrent = 4;
@@ -33,7 +33,7 @@ class AlbumArtLoaderService {
     // Let's assume we just want to track max queue depth.
   }
 
-  async loadArt(trackId: string, trackPath: string, signal?: AbortSignal): Promise<string | null> {
+  async loadArt(trackId: string, trackPath: string, signal?: AbortSignal): Promise<AlbumArtData | null> {
     if (signal?.aborted) {
       devCounters.incAlbumArt('requestsCancelledWhileQueued');
       throw new Error('Aborted');
@@ -51,7 +51,7 @@ class AlbumArtLoaderService {
       return this.inFlight.get(trackId)!;
     }
 
-    const promise = new Promise<string | null>((resolve, reject) => {
+    const promise = new Promise<AlbumArtData | null>((resolve, reject) => {
       this.queue.push({ trackId, trackPath, resolve, reject, signal });
       devCounters.incAlbumArt('requestsQueued');
       devCounters.setAlbumArtValue('currentQueueDepth', this.queue.length);
@@ -120,6 +120,18 @@ class AlbumArtLoaderService {
   clearFailedCache() {
     this.failedSet.clear();
   }
+
+  // Forget what we know about one track, e.g. after its art was replaced
+  invalidate(trackId: string) {
+    this.failedSet.delete(trackId);
+    this.inFlight.delete(trackId);
+  }
+
+  async setArt(trackId: string, trackPath: string, imageBytes: Uint8Array, mime: string): Promise<AlbumArtData> {
+    const data = await TauriAPI.setAlbumArt(trackId, trackPath, imageBytes, mime);
+    this.invalidate(trackId);
+    return data;
+  }
 }
 
 export const AlbumArtLoader = new AlbumArtLoaderService();
@@ -18,9 +18,53 @@ export interface AudioEffectsConfig {
     echo_delay: number;
     echo_feedback: number;
     echo_mix: number;
+    /** EQ gains in dB, one per entry of `eq_layout`. */
     eq_bands: number[];
+    /** EQ band centre frequencies (Hz) and Q; defaults to the classic 10 bands. */
+    eq_layout?: { freq: number; q: number }[];
     /** Processing chain order. Soft clipper always runs last. */
     effect_order?: EffectId[];
+    /** Headphone crossfeed; stereo only, runs after the ordered chain. */
+    crossfeed_enabled?: boolean;
+    crossfeed_strength?: number;
+    /** Global high-/low-pass cutoffs in Hz (0 = off). */
+    highpass_freq?: number;
+    lowpass_freq?: number;
+    /** Optional notch filter, e.g. for 50/60 Hz hum. */
+    notch?: { freq: number; q: number } | null;
+    /** Bass/treble tone shelves in dB (0 = bypass), independent of the EQ. */
+    bass_db?: number;
+    treble_db?: number;
+    /** Lookahead limiter window in ms (0 = off); adds that much latency. */
+    limiter_lookahead_ms?: number;
+}
+
+/** Matches Rust BulkTagResult */
+export interface BulkTagResult {
+    succeeded: string[];
+    failed: { path: string; error: string }[];
+}
+
+/** Per-folder scan profile; matches Rust ScanOptions */
+export interface ScanOptions {
+    /** Extensions without the leading dot, case-insensitive. */
+    extensions: string[];
+    followLinks: boolean;
+    /** Max depth below the folder (1 = only its own files); null = unlimited. */
+    maxDepth: number | null;
+    /** Name patterns (no `/`) or folder-relative path patterns to skip. */
+    ignoreGlobs: string[];
+    /** Measure ReplayGain for scanned files as part of the scan. */
+    analyzeReplaygain?: boolean;
+}
+
+/** Track ids changed by a single-folder rescan; matches Rust FolderRescanDiff */
+export interface FolderRescanDiff {
+    added: string[];
+    updated: string[];
+    removed: string[];
+    /** True when `removed` was only proposed: part of the folder couldn't be read, or it is missing or empty, so nothing was deleted */
+    removalWithheld: boolean;
 }
 
 /** Matches Rust TagUpdate struct */
@@ -52,9 +96,34 @@ export interface PerformanceStats {
     };
 }
 
+/** Returned by get_listening_stats */
+export interface ListeningStats {
+    minutesToday: number;
+    minutesThisWeek: number;
+    currentStreakDays: number;
+    /** 24 entries, one per local hour */
+    hourlyListens: number[];
+}
+
 /** Returned by check_missing_files — (trackId, path) tuples */
 export type MissingFile = [string, string];
 
+/** Outcome for one file of import_playlists_from_folder */
+export interface PlaylistImportResult {
+    file: string;
+    playlistName: string;
+    /** null when the file couldn't be imported; see `error` */
+    playlistId: string | null;
+    trackCount: number;
+    error: string | null;
+}
+
+/** Emit scan-progress once this many files or milliseconds have passed */
+export interface ScanProgressThrottle {
+    everyFiles: number;
+    everyMs: number;
+}
+
 /** Options accepted by the file-open dialog */
 export interface SelectFolderOptions {
     title?: string;
@@ -70,6 +139,209 @@ export interface TracksPageResponse {
     hasMore: boolean;
 }
 
+/** Volume normalization: none, real-time peak, or pre-analysed ReplayGain */
+export type NormalizeMode = 'off' | 'peak' | 'replayGain';
+
+export type ReplayGainMode = 'off' | 'track' | 'album' | 'albumWithTrackFallback';
+
+/** When to crossfade: every transition, none, or only when the album changes */
+export type CrossfadePolicy = 'always' | 'never' | 'onlyBetweenAlbums';
+
+/** Environment snapshot for support requests */
+export interface Diagnostics {
+    appVersion: string;
+    os: string;
+    arch: string;
+    audio: {
+        host: string;
+        devices: string[];
+        defaultDevice: string | null;
+        outputDevice: string | null;
+        sampleRate: number | null;
+        channels: number | null;
+    };
+    database: {
+        path: string | null;
+        sizeBytes: number;
+        schemaVersion: number;
+        trackCount: number;
+    };
+}
+
+/** EQ presets that follow the loaded track's genre */
+export interface GenreEqSettings {
+    enabled: boolean;
+    /** Genre (matched case-insensitively) → preset id from EQ_PRESETS, e.g. "JAZZ" */
+    presets: Record<string, string>;
+}
+
+/** Result of probing an output device before switching to it */
+export interface DeviceTestResult {
+    deviceName: string;
+    ok: boolean;
+    format: OutputFormat | null;
+    error: string | null;
+}
+
+/** Independently set fade lengths, each 0–30 seconds */
+export interface FadeDurations {
+    sleepFadeSecs: number;
+    crossfadeSecs: number;
+}
+
+export interface SilenceTrimConfig {
+    enabled: boolean;
+    thresholdDb: number;
+    minDurationSecs: number;
+}
+
+/** Output stream rate: the device default, the loaded track's rate, or a fixed rate in Hz */
+export type OutputSampleRate = 'deviceDefault' | 'matchSource' | { fixed: number };
+
+/** Output stream options; 'stereo' opens 2 channels even on multichannel devices */
+export interface AudioConfig {
+    outputChannels: 'deviceNative' | 'stereo';
+    sampleRate: OutputSampleRate;
+}
+
+/** Format the output stream actually runs at */
+export interface OutputFormat {
+    sampleRate: number;
+    channels: number;
+}
+
+export interface MetadataCandidate {
+    title: string | null;
+    artist: string | null;
+    album: string | null;
+    year: number | null;
+    score: number;
+    recordingId: string | null;
+    source: 'acoustid' | 'musicbrainz';
+}
+
+/** Files handed to the app via the command line or the OS "Open With" menu */
+export interface OpenRequest {
+    play: string;
+    enqueue: string[];
+}
+
+/** Colours from a track's album art, as #rrggbb */
+export interface ArtPalette {
+    dominant: string;
+    /** A vivid contrasting colour; equals dominant when nothing stands out */
+    accent: string;
+    /** All palette colours, most common first */
+    colors: string[];
+}
+
+/** Post-effects PCM from the tap */
+export interface PcmChunk {
+    samples: number[];
+    channels: number;
+    sampleRate: number;
+    /** Frames lost because reads fell too far behind */
+    droppedFrames: number;
+}
+
+/** Startup auto-vacuum: runs when the database is larger than thresholdBytes */
+export interface AutoVacuumSettings {
+    enabled: boolean;
+    thresholdBytes: number;
+}
+
+/** Counts removed by cleanupDatabase */
+export interface OrphanCleanup {
+    playlistTracks: number;
+    albumArt: number;
+}
+
+export type LibraryExportFormat = 'csv' | 'json';
+
+export interface LibraryImportSummary {
+    added: number;
+    updated: number;
+}
+
+/** How much each factor biases smart shuffle; 0 turns a factor off */
+export interface ShuffleWeights {
+    rating: number;
+    playCount: number;
+    recency: number;
+}
+
+/** Playback speed is remembered separately for music and spoken word */
+export type ContentType = 'music' | 'spoken';
+
+export interface PlaybackSpeeds {
+    music: number;
+    spoken: number;
+}
+
+/** Chapter marker embedded in a podcast or audiobook file */
+export interface Chapter {
+    /** Seconds from the start of the file */
+    start: number;
+    title: string;
+}
+
+export interface AlbumArtData {
+    /** Base64 image bytes */
+    data: string;
+    mime: string;
+}
+
+/** Format album art is cached in; 'webp' re-encodes at `quality` (0-100) */
+export interface ArtCacheSettings {
+    format: 'original' | 'webp';
+    quality: number;
+}
+
+/** Full-scale overflows ahead of the limiter; many suggest lowering the preamp */
+export interface ClipStats {
+    clippedSamples: number;
+    totalSamples: number;
+    /** Largest magnitude seen (1.0 = full scale) */
+    peak: number;
+}
+
+/** Returned by measure_effects_cost; costs near the budget will drop out */
+export interface EffectsCost {
+    microsPer1024Samples: number;
+    /** Playback time 1024 samples cover at the current rate */
+    budgetMicros: number;
+}
+
+/** Detected musical key, e.g. `{ key: "A minor", camelot: "8A" }` */
+export interface KeyInfo {
+    key: string;
+    camelot: string;
+}
+
+/** Analyses for analyzeAll to run; omitted fields are off */
+export interface AnalyzerSelection {
+    replaygain?: boolean;
+    bpm?: boolean;
+    key?: boolean;
+    fingerprint?: boolean;
+}
+
+/** One file's analyzeAll results; failed or unselected analyses are null */
+export interface TrackAnalysis {
+    path: string;
+    replaygain: { track_gain: number, track_peak: number, loudness: number, target_lufs: number } | null;
+    bpm: number | null;
+    key: KeyInfo | null;
+    fingerprint: string | null;
+    errors: string[];
+}
+
+export interface AnalysisProgress {
+    current: number;
+    total: number;
+    currentFile: string;
+}
+
 /**
  * Centralized Tauri API service with error handling and logging
  */
@@ -192,10 +464,48 @@ class TauriAPIService {
         return this._invoke('set_volume', { volume });
     }
 
+    /** Mute keeps the volume level for unmuting; returns whether output is now muted. */
+    async toggleMute(): Promise<boolean> {
+        return this._invoke('toggle_mute');
+    }
+
+    async setMuted(muted: boolean): Promise<void> {
+        return this._invoke('set_muted', { muted });
+    }
+
+    async isMuted(): Promise<boolean> {
+        return this._invoke('is_muted');
+    }
+
     async seekTo(position: number): Promise<void> {
         return this._invoke('seek_to', { position });
     }
 
+    /** Skip forward (or back, when negative); resolves to the new position. */
+    async seekRelative(deltaSecs: number): Promise<number> {
+        return this._invoke('seek_relative', { deltaSecs });
+    }
+
+    /** Whether the loaded track supports direct seeking (otherwise seeks reload the file). */
+    async canSeek(): Promise<boolean> {
+        return this._invoke('can_seek');
+    }
+
+    /** Embedded chapter markers of a file; empty when it has none. */
+    async getChapters(path: string): Promise<Chapter[]> {
+        return this._invoke('get_chapters', { path });
+    }
+
+    /** Seek to the next chapter of the loaded track; null in the last chapter. */
+    async nextChapter(): Promise<Chapter | null> {
+        return this._invoke('next_chapter');
+    }
+
+    /** Restart the current chapter, or go to the previous one near its start. */
+    async previousChapter(): Promise<Chapter | null> {
+        return this._invoke('previous_chapter');
+    }
+
     // Backward-compatible alias used by tests/legacy code.
     async getPosition(): Promise<number> {
         return this._invoke('get_position');
@@ -214,6 +524,11 @@ class TauriAPIService {
         return this._invoke('get_duration');
     }
 
+    /** Set how often (ms) the backend emits `playback-progress` while playing. */
+    async setProgressInterval(intervalMs: number): Promise<void> {
+        return this._invoke('set_progress_interval', { intervalMs });
+    }
+
     // ========== Balance/Pan Commands ==========
 
     /**
@@ -238,7 +553,7 @@ class TauriAPIService {
      * Analyze a track for ReplayGain data (LUFS loudness measurement)
      * @param {string} trackPath - Path to the audio file
      */
-    async analyzeReplayGain(trackPath: string): Promise<{ track_gain: number, track_peak: number, loudness: number }> {
+    async analyzeReplayGain(trackPath: string): Promise<{ track_gain: number, track_peak: number, loudness: number, target_lufs: number }> {
         return this._invoke('analyze_replaygain', { trackPath });
     }
 
@@ -246,15 +561,21 @@ class TauriAPIService {
      * Get stored ReplayGain data for a track
      * @param {string} trackPath - Path to the audio file
      */
-    async getTrackReplayGain(trackPath: string): Promise<{ track_gain: number, track_peak: number, loudness: number } | null> {
+    async getTrackReplayGain(trackPath: string): Promise<{ track_gain: number, track_peak: number, loudness: number, target_lufs: number } | null> {
         return this._invoke('get_track_replaygain', { trackPath });
     }
 
+    /** Write stored ReplayGain into the file (R128_TRACK_GAIN for Opus). */
+    async writeReplayGainTags(trackPath: string): Promise<void> {
+        return this._invoke('write_replaygain_tags', { trackPath });
+    }
+
     async getAlbumReplayGain(artist: string, album: string): Promise<{
         album_gain: number;
         album_peak: number;
         loudness: number;
         track_count: number;
+        target_lufs: number;
     } | null> {
         return this._invoke('get_album_replaygain', { artist, album });
     }
@@ -264,17 +585,58 @@ class TauriAPIService {
         album_peak: number;
         loudness: number;
         track_count: number;
+        target_lufs: number;
     } | null> {
         return this._invoke('analyze_album_replaygain', { artist, album });
     }
 
+    /** Loudness target in LUFS used for analysis and playback (default -18). */
+    async getReplayGainTarget(): Promise<number> {
+        return this._invoke('get_replaygain_target');
+    }
+
+    /** Set the loudness target, e.g. -14 (streaming), -18 or -23 (broadcast). */
+    async setReplayGainTarget(targetLufs: number): Promise<void> {
+        return this._invoke('set_replaygain_target', { targetLufs });
+    }
+
+    /** Loudness targets set for specific output devices, by device name. */
+    async getDeviceReplayGainTargets(): Promise<Record<string, number>> {
+        return this._invoke('get_device_replaygain_targets');
+    }
+
+    /** Target used while `deviceName` is the output; null follows the global target. */
+    async setDeviceReplayGainTarget(deviceName: string, targetLufs: number | null): Promise<void> {
+        return this._invoke('set_device_replaygain_target', { deviceName, targetLufs });
+    }
+
+    /** Which stored gain the backend applies when a track loads. */
+    async setReplayGainMode(mode: ReplayGainMode): Promise<void> {
+        return this._invoke('set_replaygain_mode', { mode });
+    }
+
+    async getReplayGainMode(): Promise<ReplayGainMode> {
+        return this._invoke('get_replaygain_mode');
+    }
+
+    /** Preamp in dB added to every track's ReplayGain; resolves to the clamped value. */
+    async setReplayGainPreamp(preampDb: number): Promise<number> {
+        return this._invoke('set_replaygain_preamp', { preampDb });
+    }
+
+    async getReplayGainPreamp(): Promise<number> {
+        return this._invoke('get_replaygain_preamp');
+    }
+
     /**
      * Set ReplayGain adjustment for current playback
      * @param {number} gainDb - ReplayGain value in dB
      * @param {number} preampDb - Additional preamp adjustment in dB
+     * @param {number} [targetLufs] - Target the gain was computed against; rebased to the current target
+     * @param {number} [peak] - Linear peak of the track/album; limits boosts so they do not clip
      */
-    async setReplayGain(gainDb: number, preampDb: number): Promise<void> {
-        return this._invoke('set_replaygain', { gainDb, preampDb });
+    async setReplayGain(gainDb: number, preampDb: number, targetLufs?: number, peak?: number): Promise<void> {
+        return this._invoke('set_replaygain', { gainDb, preampDb, targetLufs, peak });
     }
 
     /**
@@ -284,6 +646,117 @@ class TauriAPIService {
         return this._invoke('clear_replaygain');
     }
 
+    async setNormalizeMode(mode: NormalizeMode): Promise<void> {
+        return this._invoke('set_normalize_mode', { mode });
+    }
+
+    async getNormalizeMode(): Promise<NormalizeMode> {
+        return this._invoke('get_normalize_mode');
+    }
+
+    /** Skip leading/trailing silence longer than `minDurationSecs` below `thresholdDb`. */
+    async setSilenceTrim(config: SilenceTrimConfig): Promise<void> {
+        return this._invoke('set_silence_trim', { config });
+    }
+
+    async getSilenceTrim(): Promise<SilenceTrimConfig> {
+        return this._invoke('get_silence_trim');
+    }
+
+    async setCrossfadePolicy(policy: CrossfadePolicy): Promise<void> {
+        return this._invoke('set_crossfade_policy', { policy });
+    }
+
+    async getCrossfadePolicy(): Promise<CrossfadePolicy> {
+        return this._invoke('get_crossfade_policy');
+    }
+
+    async getFadeDurations(): Promise<FadeDurations> {
+        return this._invoke('get_fade_durations');
+    }
+
+    /** Set the sleep timer fade-out, clamped to 0–30 s; returns the stored lengths. */
+    async setSleepFadeSecs(secs: number): Promise<FadeDurations> {
+        return this._invoke('set_sleep_fade_secs', { secs });
+    }
+
+    /** Set the crossfade overlap, clamped to 0–30 s; returns the stored lengths. */
+    async setCrossfadeSecs(secs: number): Promise<FadeDurations> {
+        return this._invoke('set_crossfade_secs', { secs });
+    }
+
+    /** Whether the policy allows crossfading between these two tracks; false means a gapless swap. */
+    async shouldCrossfade(fromPath: string, toPath: string): Promise<boolean> {
+        return this._invoke('should_crossfade', { fromPath, toPath });
+    }
+
+    /** Reopens the output stream with the new options right away. */
+    async setAudioConfig(config: AudioConfig): Promise<void> {
+        return this._invoke('set_audio_config', { config });
+    }
+
+    async getAudioConfig(): Promise<AudioConfig> {
+        return this._invoke('get_audio_config');
+    }
+
+    /** Negotiated output format; null while no output device is open. */
+    async getOutputFormat(): Promise<OutputFormat | null> {
+        return this._invoke('get_output_format');
+    }
+
+    /** When on, tracks continue from where they were last left. */
+    async setResumePerTrack(enabled: boolean): Promise<void> {
+        return this._invoke('set_resume_per_track', { enabled });
+    }
+
+    async getResumePerTrack(): Promise<boolean> {
+        return this._invoke('get_resume_per_track');
+    }
+
+    /** When on, playback moves to the next track if the current file becomes unreadable. */
+    async setSkipUnavailable(enabled: boolean): Promise<void> {
+        return this._invoke('set_skip_unavailable', { enabled });
+    }
+
+    async getSkipUnavailable(): Promise<boolean> {
+        return this._invoke('get_skip_unavailable');
+    }
+
+    /**
+     * Set and remember the playback speed (0.5x-2.0x) for a content type,
+     * by default that of the loaded track.
+     */
+    async setPlaybackSpeed(multiplier: number, contentType?: ContentType): Promise<void> {
+        return this._invoke('set_playback_speed', { multiplier, contentType: contentType ?? null });
+    }
+
+    async getPlaybackSpeeds(): Promise<PlaybackSpeeds> {
+        return this._invoke('get_playback_speeds');
+    }
+
+    /** Seconds of playback left in the queue from the current track on. */
+    async getQueueRemainingDuration(trackIds: string[], currentIndex: number): Promise<number> {
+        return this._invoke('get_queue_remaining_duration', { trackIds, currentIndex });
+    }
+
+    /** Save a track's resume position in seconds; null clears it. */
+    async setTrackPosition(trackId: string, secs: number | null): Promise<void> {
+        return this._invoke('set_track_position', { trackId, secs });
+    }
+
+    async getTrackPosition(trackId: string): Promise<number | null> {
+        return this._invoke('get_track_position', { trackId });
+    }
+
+    /** Permanently adjust a track's volume in dB on top of ReplayGain; null removes it. */
+    async setTrackVolumeOverride(trackId: string, db: number | null): Promise<void> {
+        return this._invoke('set_track_volume_override', { trackId, db });
+    }
+
+    async getTrackVolumeOverride(trackId: string): Promise<number | null> {
+        return this._invoke('get_track_volume_override', { trackId });
+    }
+
     // ========== Library Commands ==========
 
     async scanFolder(folderPath: string, scanId?: string): Promise<Track[]> {
@@ -294,6 +767,39 @@ class TauriAPIService {
         return this._invoke('scan_folder_incremental', { folderPath, scanId: scanId || Date.now().toString() });
     }
 
+    async rescanFolder(folderPath: string): Promise<FolderRescanDiff> {
+        return this._invoke('rescan_folder', { folderPath });
+    }
+
+    async setFolderScanOptions(folderPath: string, options: ScanOptions): Promise<void> {
+        return this._invoke('set_folder_scan_options', { folderPath, options });
+    }
+
+    async getFolderScanOptions(folderPath: string): Promise<ScanOptions> {
+        return this._invoke('get_folder_scan_options', { folderPath });
+    }
+
+    async setScanProgressThrottle(throttle: ScanProgressThrottle): Promise<void> {
+        return this._invoke('set_scan_progress_throttle', { throttle });
+    }
+
+    async getScanProgressThrottle(): Promise<ScanProgressThrottle> {
+        return this._invoke('get_scan_progress_throttle');
+    }
+
+    /** Never scan this file or folder (or anything below it). */
+    async addIgnoredPath(path: string): Promise<void> {
+        return this._invoke('add_ignored_path', { path });
+    }
+
+    async removeIgnoredPath(path: string): Promise<void> {
+        return this._invoke('remove_ignored_path', { path });
+    }
+
+    async listIgnoredPaths(): Promise<string[]> {
+        return this._invoke('list_ignored_paths');
+    }
+
     async cancelScan(scanId: string): Promise<void> {
         return this._invoke('cancel_scan', { scanId });
     }
@@ -323,10 +829,25 @@ class TauriAPIService {
         return this._invoke('get_all_folders');
     }
 
+    /** [id, path, name, dateAdded, trackCount] per folder, most recently added first. */
+    async getFoldersWithCounts(): Promise<[string, string, string, number, number][]> {
+        return this._invoke('get_folders_with_counts');
+    }
+
     async removeTrack(trackId: string): Promise<void> {
         return this._invoke('remove_track', { trackId });
     }
 
+    /** Move/rename the track's file on disk and update the library entry. */
+    async moveTrackFile(trackId: string, newPath: string): Promise<Track> {
+        return this._invoke('move_track_file', { trackId, newPath });
+    }
+
+    /** Re-read the track's tags from disk, keeping its rating and play history. */
+    async refreshTrackMetadata(trackId: string): Promise<Track> {
+        return this._invoke('refresh_track_metadata', { trackId });
+    }
+
     async removeFolder(folderId: string, folderPath: string): Promise<void> {
         return this._invoke('remove_folder', { folderId, folderPath });
     }
@@ -335,10 +856,56 @@ class TauriAPIService {
         return this._invoke('increment_play_count', { trackId });
     }
 
+    /** Record that the track was skipped before reaching the halfway point. */
+    async incrementSkipCount(trackId: string): Promise<void> {
+        return this._invoke('increment_skip_count', { trackId });
+    }
+
     async findDuplicates(sensitivity?: string): Promise<Track[][]> {
         return this._invoke('find_duplicates', { sensitivity: sensitivity ?? null });
     }
 
+    async fingerprintTrack(path: string): Promise<string> {
+        return this._invoke('fingerprint_track', { path });
+    }
+
+    /** Estimate and store the track's tempo in BPM. */
+    async analyzeBpm(path: string): Promise<number> {
+        return this._invoke('analyze_bpm', { path });
+    }
+
+    /** Detect and store the track's musical key. */
+    async analyzeKey(path: string): Promise<KeyInfo> {
+        return this._invoke('analyze_key', { path });
+    }
+
+    /**
+     * Decode each file once and run the chosen analyzers (all when omitted),
+     * storing the results. Progress arrives as `analysis-progress` events.
+     */
+    async analyzeAll(paths: string[], analyzers?: AnalyzerSelection): Promise<TrackAnalysis[]> {
+        return this._invoke('analyze_all', { paths, analyzers: analyzers ?? null });
+    }
+
+    /** Stop a running analyzeAll batch; results stored so far are kept. */
+    async cancelAnalysis(): Promise<void> {
+        return this._invoke('cancel_analysis');
+    }
+
+    /** Candidate metadata from AcoustID (with a Chromaprint fingerprint) or MusicBrainz. */
+    async lookupTrackMetadata(trackId: string, chromaprint?: string): Promise<MetadataCandidate[]> {
+        return this._invoke('lookup_track_metadata', { trackId, chromaprint: chromaprint ?? null });
+    }
+
+    /** Write an accepted candidate to the file's tags and the library. */
+    async applyMetadataCandidate(trackId: string, candidate: MetadataCandidate): Promise<void> {
+        return this._invoke('apply_metadata_candidate', { trackId, candidate });
+    }
+
+    async setAcoustidApiKey(apiKey: string | null): Promise<void> {
+        return this._invoke('set_acoustid_api_key', { apiKey });
+    }
+
     async removeDuplicateFolders(): Promise<void> {
         return this._invoke('remove_duplicate_folders');
     }
@@ -361,23 +928,43 @@ class TauriAPIService {
 
     // ========== Album Art Commands ==========
 
-    async getAlbumArt(trackId: string): Promise<string | null> {
+    /** Cached art as base64 with the MIME type it is stored in. */
+    async getAlbumArt(trackId: string): Promise<AlbumArtData | null> {
         return this._invoke('get_album_art', { trackId });
     }
 
-    async getAlbumArtBatch(trackIds: string[]): Promise<Record<string, string | null>> {
-        const items = await this._invoke<Array<[string, string | null]>>('get_album_art_batch', { trackIds });
-        const byId: Record<string, string | null> = {};
+    /** Cached art scaled to fit 256×256 (JPEG, or PNG for transparent art). */
+    async getAlbumArtThumbnail(trackId: string): Promise<AlbumArtData | null> {
+        return this._invoke('get_album_art_thumbnail', { trackId });
+    }
+
+    async getAlbumArtBatch(trackIds: string[]): Promise<Record<string, AlbumArtData | null>> {
+        const items = await this._invoke<Array<[string, AlbumArtData | null]>>('get_album_art_batch', { trackIds });
+        const byId: Record<string, AlbumArtData | null> = {};
         for (const [trackId, art] of items) {
             byId[trackId] = art;
         }
         return byId;
     }
 
-    async extractAndCacheAlbumArt(trackId: string, trackPath: string): Promise<string | null> {
+    async getAlbumArtCacheSettings(): Promise<ArtCacheSettings> {
+        return this._invoke('get_album_art_cache_settings');
+    }
+
+    /** Applies to art cached from now on; already cached art keeps its format. */
+    async setAlbumArtCacheSettings(settings: ArtCacheSettings): Promise<void> {
+        return this._invoke('set_album_art_cache_settings', { settings });
+    }
+
+    async extractAndCacheAlbumArt(trackId: string, trackPath: string): Promise<AlbumArtData | null> {
         return this._invoke('extract_and_cache_album_art', { trackId, trackPath });
     }
 
+    /** Embed a PNG/JPEG/GIF/BMP cover in the file and refresh the cached art; returns the new art. */
+    async setAlbumArt(trackId: string, trackPath: string, imageBytes: Uint8Array, mime: string): Promise<AlbumArtData> {
+        return this._invoke('set_album_art', { trackId, trackPath, imageBytes: Array.from(imageBytes), mime });
+    }
+
     // ========== Gapless Playback Commands ==========
 
     async preloadTrack(path: string): Promise<void> {
@@ -410,6 +997,20 @@ class TauriAPIService {
         return this._invoke('get_audio_effects');
     }
 
+    /** Reset effects to flat defaults and clear reverb/echo tails; returns the new config. */
+    async resetEffects(): Promise<AudioEffectsConfig> {
+        return this._invoke('reset_effects');
+    }
+
+    /** Genre → EQ preset mapping applied as tracks load */
+    async getGenreEqSettings(): Promise<GenreEqSettings> {
+        return this._invoke('get_genre_eq_settings');
+    }
+
+    async setGenreEqSettings(settings: GenreEqSettings): Promise<void> {
+        return this._invoke('set_genre_eq_settings', { settings });
+    }
+
     async setEffectsEnabled(enabled: boolean): Promise<void> {
         return this._invoke('set_effects_enabled', { enabled });
     }
@@ -418,6 +1019,25 @@ class TauriAPIService {
         return this._invoke('is_effects_enabled');
     }
 
+    /** Toggle equal-loudness compensation (persisted; follows the volume). */
+    async setLoudnessEnabled(enabled: boolean): Promise<void> {
+        return this._invoke('set_loudness_enabled', { enabled });
+    }
+
+    async isLoudnessEnabled(): Promise<boolean> {
+        return this._invoke('is_loudness_enabled');
+    }
+
+    /** Samples over full scale ahead of the limiter for the current track. */
+    async getClipStats(): Promise<ClipStats> {
+        return this._invoke('get_clip_stats');
+    }
+
+    /** Benchmark the current effects chain, even while effects are off. */
+    async measureEffectsCost(): Promise<EffectsCost> {
+        return this._invoke('measure_effects_cost');
+    }
+
     // ========== Visualizer Commands ==========
 
     async getVisualizerData(): Promise<number[]> {
@@ -432,18 +1052,84 @@ class TauriAPIService {
         return this._invoke('set_beat_sensitivity', { sensitivity });
     }
 
+    async setMinBeatInterval(seconds: number): Promise<void> {
+        return this._invoke('set_min_beat_interval', { seconds });
+    }
+
+    async setBeatBand(lowBin: number, highBin: number): Promise<void> {
+        return this._invoke('set_beat_band', { lowBin, highBin });
+    }
+
+    /** Per-frame decay (0–1] of the spectrum's automatic gain reference. */
+    async setVisualizerAgcDecay(decay: number): Promise<void> {
+        return this._invoke('set_visualizer_agc_decay', { decay });
+    }
+
+    /** Center frequency in Hz of each spectrum bar, for a labeled axis. */
+    async getVisualizerBinFrequencies(): Promise<number[]> {
+        return this._invoke('get_visualizer_bin_frequencies');
+    }
+
+    /** Start or stop copying the post-effects output for getPcmTap (off by default). */
+    async setPcmTapEnabled(enabled: boolean): Promise<void> {
+        return this._invoke('set_pcm_tap_enabled', { enabled });
+    }
+
+    async isPcmTapEnabled(): Promise<boolean> {
+        return this._invoke('is_pcm_tap_enabled');
+    }
+
+    /** Interleaved post-effects samples written since the last call, up to maxFrames frames. */
+    async getPcmTap(maxFrames: number): Promise<PcmChunk> {
+        return this._invoke('get_pcm_tap', { maxFrames });
+    }
+
     async getTrackWaveform(path: string, numBars?: number): Promise<number[]> {
         return this._invoke('get_track_waveform', { path, numBars: numBars ?? 200 });
     }
 
+    /** Dominant and accent colours of the track's cached album art; null when it has none. */
+    async extractArtColors(trackId: string): Promise<ArtPalette | null> {
+        return this._invoke('extract_art_colors', { trackId });
+    }
+
+    /** Warm the waveform and album art caches for the upcoming track; returns before the work is done. */
+    async prepareNext(path: string): Promise<void> {
+        return this._invoke('prepare_next', { path });
+    }
+
     // ========== Tag Editor Commands ==========
 
     async updateTrackTags(trackId: string, trackPath: string, tags: TagUpdate): Promise<void> {
         return this._invoke('update_track_tags', { trackId, trackPath, tags });
     }
 
+    /** Apply the set fields of `tags` to every track; per-file failures are reported, not thrown. */
+    async updateTagsBulk(trackIds: string[], tags: TagUpdate): Promise<BulkTagResult> {
+        return this._invoke('update_tags_bulk', { trackIds, tags });
+    }
+
+    /** Every text tag in the file by its native key (frame ID, TXXX description, Vorbis field…). */
+    async readAllTags(path: string): Promise<Record<string, string[]>> {
+        return this._invoke('read_all_tags', { path });
+    }
+
+    /** Set an arbitrary tag key, replacing its existing values. */
+    async writeTag(path: string, key: string, value: string): Promise<void> {
+        return this._invoke('write_tag', { path, key, value });
+    }
+
+    async removeTag(path: string, key: string): Promise<void> {
+        return this._invoke('remove_tag', { path, key });
+    }
+
     // ========== Playlist Import/Export Commands ==========
 
+    /** The playlist's tracks whose file no longer exists. */
+    async validatePlaylist(playlistId: string): Promise<MissingFile[]> {
+        return this._invoke('validate_playlist', { playlistId });
+    }
+
     async exportPlaylist(playlistId: string, outputPath: string): Promise<void> {
         return this._invoke('export_playlist', { playlistId, outputPath });
     }
@@ -452,6 +1138,11 @@ class TauriAPIService {
         return this._invoke('import_playlist', { playlistName, inputPath });
     }
 
+    /** Import every .m3u/.m3u8/.pls/.xspf file in a folder, one playlist per file. */
+    async importPlaylistsFromFolder(dir: string): Promise<PlaylistImportResult[]> {
+        return this._invoke('import_playlists_from_folder', { dir });
+    }
+
     // ========== Playlist Commands ==========
 
     async getAllPlaylists(): Promise<Playlist[]> {
@@ -558,7 +1249,13 @@ class TauriAPIService {
         return this._invoke('get_audio_devices');
     }
 
-    async setAudioDevice(deviceName: string): Promise<void> {
+    /** Play a short quiet tone on a device through its own stream; current playback is unaffected. The device in use passes without a tone. */
+    async testAudioDevice(deviceName: string): Promise<DeviceTestResult> {
+        return this._invoke('test_audio_device', { deviceName });
+    }
+
+    /** Switch output device; resolves to the volume restored for it, if one was remembered. */
+    async setAudioDevice(deviceName: string): Promise<number | null> {
         return this._invoke('set_audio_device', { deviceName });
     }
 
@@ -593,18 +1290,83 @@ class TauriAPIService {
         return this._invoke('get_most_played', { limit });
     }
 
+    /** Tracks never played yet, oldest additions first. */
+    async getUnplayedTracks(limit: number = 50): Promise<Track[]> {
+        return this._invoke('get_unplayed_tracks', { limit });
+    }
+
+    async getUnplayedTrackCount(): Promise<number> {
+        return this._invoke('get_unplayed_track_count');
+    }
+
+    /** Tracks with a zero or implausibly long duration, worth a rescan */
+    async getSuspectTracks(): Promise<Track[]> {
+        return this._invoke('get_suspect_tracks');
+    }
+
+    /** Genre facet as [genre, trackCount] pairs; untagged tracks are "Unknown", listed last. */
+    async getGenres(): Promise<[string, number][]> {
+        return this._invoke('get_genres');
+    }
+
+    /** Year facet as [year, trackCount] pairs, newest first; untagged tracks are year 0, listed last. */
+    async getYears(): Promise<[number, number][]> {
+        return this._invoke('get_years');
+    }
+
+    /**
+     * Shuffle favourites in more often: a queue of up to `size` tracks biased
+     * toward high ratings and play counts and away from recent plays. The
+     * same seed reproduces the same queue.
+     */
+    async buildSmartQueue(size: number, weights?: Partial<ShuffleWeights>, seed?: number): Promise<Track[]> {
+        return this._invoke('build_smart_queue', { size, weights: weights ?? null, seed: seed ?? null });
+    }
+
+    /** Tracks most like the given one (shared artist/genre, close BPM, compatible key), best first. */
+    async getSimilarTracks(trackId: string, limit: number = 20): Promise<Track[]> {
+        return this._invoke('get_similar_tracks', { trackId, limit });
+    }
+
+    async getListeningStats(): Promise<ListeningStats> {
+        return this._invoke('get_listening_stats');
+    }
+
     // ========== Lyrics Commands ==========
 
     async loadLyrics(trackPath: string): Promise<string | null> {
         return this._invoke('load_lyrics', { trackPath });
     }
 
+    /** Current line at `time`; `lyricOffset` (seconds, positive = sooner) nudges sync. */
+    async getLyricAtTime(trackPath: string, time: number, lyricOffset?: number): Promise<[number, string] | null> {
+        return this._invoke('get_lyric_at_time', { trackPath, time, lyricOffset: lyricOffset ?? null });
+    }
+
+    /** All lines at the active timestamp (e.g. original + translation). */
+    async getLyricsAtTime(trackPath: string, time: number, lyricOffset?: number): Promise<[number, string][]> {
+        return this._invoke('get_lyrics_at_time', { trackPath, time, lyricOffset: lyricOffset ?? null });
+    }
+
     // ========== File System Commands ==========
 
     async writeTextFile(filePath: string, content: string): Promise<void> {
         return this._invoke('write_text_file', { filePath, content });
     }
 
+    /** Write every library track (metadata, ratings, play counts) to a file; returns the count. */
+    async exportLibrary(path: string, format: LibraryExportFormat): Promise<number> {
+        return this._invoke('export_library', { path, format });
+    }
+
+    /**
+     * Merge a library export back in, matching tracks by path. Existing
+     * ratings and play counts are kept unless `overwrite` is set.
+     */
+    async importLibrary(path: string, format: LibraryExportFormat, overwrite = false): Promise<LibraryImportSummary> {
+        return this._invoke('import_library', { path, format, overwrite });
+    }
+
     async checkMissingFiles(): Promise<MissingFile[]> {
         return this._invoke('check_missing_files');
     }
@@ -619,6 +1381,11 @@ class TauriAPIService {
         return this._invoke('get_runtime_diagnostics');
     }
 
+    /** App, OS, audio and database details for support requests. */
+    async getDiagnostics(): Promise<Diagnostics> {
+        return this._invoke('get_diagnostics');
+    }
+
     async getCacheSize(): Promise<number> {
         return this._invoke('get_cache_size');
     }
@@ -631,20 +1398,60 @@ class TauriAPIService {
         return this._invoke('vacuum_database');
     }
 
+    async getAutoVacuumSettings(): Promise<AutoVacuumSettings> {
+        return this._invoke('get_auto_vacuum_settings');
+    }
+
+    async setAutoVacuumSettings(settings: AutoVacuumSettings): Promise<void> {
+        return this._invoke('set_auto_vacuum_settings', { settings });
+    }
+
+    /** Remove playlist entries and cached art left behind by deleted tracks. */
+    async cleanupDatabase(): Promise<OrphanCleanup> {
+        return this._invoke('cleanup_database');
+    }
+
     async clearAlbumArtCache(): Promise<void> {
         return this._invoke('clear_album_art_cache');
     }
 
     // ========== Tray Settings Commands ==========
 
-    async setTraySettings(closeToTray: boolean, minimizeToTray: boolean, startMinimized: boolean): Promise<void> {
-        return this._invoke('set_tray_settings', { closeToTray, minimizeToTray, startMinimized });
+    async setTraySettings(closeToTray: boolean, minimizeToTray: boolean): Promise<void> {
+        return this._invoke('set_tray_settings', { closeToTray, minimizeToTray });
     }
 
     async getTraySettings(): Promise<{ closeToTray: boolean; minimizeToTray: boolean; startMinimized: boolean }> {
         return this._invoke('get_tray_settings');
     }
 
+    /** Start hidden in the tray on the next launch. */
+    async setStartMinimized(enabled: boolean): Promise<void> {
+        return this._invoke('set_start_minimized', { enabled });
+    }
+
+    async getStartMinimized(): Promise<boolean> {
+        return this._invoke('get_start_minimized');
+    }
+
+    // ========== Global Shortcut Commands ==========
+
+    async registerShortcut(action: string, accelerator: string): Promise<void> {
+        return this._invoke('register_shortcut', { action, accelerator });
+    }
+
+    async unregisterShortcut(action: string): Promise<void> {
+        return this._invoke('unregister_shortcut', { action });
+    }
+
+    async takeOpenedFiles(): Promise<OpenRequest | null> {
+        return this._invoke('take_opened_files');
+    }
+
+    async getShortcuts(): Promise<Record<string, string>> {
+        return this._invoke('get_shortcuts');
+    }
+
     // ========== Cache Limit Commands ==========
 
     async enforceCacheLimit(limitMb: number): Promise<number> {
@@ -21,7 +21,7 @@ describe('AlbumArtLoader', () => {
   it('limits concurrency to maxConcurrentRequests (4)', async () => {
     vi.mocked(TauriAPI.extractAndCacheAlbumArt).mockImplementation(async () => {
       await sleep(50);
-      return 'base64data';
+      return { data: 'base64data', mime: 'image/png' };
     });
 
     const initialCompleted = devCounters.counters.albumArt.requestsCompleted;
@@ -44,7 +44,7 @@ describe('AlbumArtLoader', () => {
   it('deduplicates concurrent requests for the same track', async () => {
     vi.mocked(TauriAPI.extractAndCacheAlbumArt).mockImplementation(async () => {
       await sleep(10);
-      return 'data';
+      return { data: 'data', mime: 'image/jpeg' };
     });
 
     const initialDedupe = devCounters.counters.albumArt.requestsDeduplicated;
@@ -63,7 +63,7 @@ describe('AlbumArtLoader', () => {
     
     vi.mocked(TauriAPI.extractAndCacheAlbumArt).mockImplementation(async () => {
       await sleep(50);
-      return 'data';
+      return { data: 'data', mime: 'image/jpeg' };
     });
     
     AlbumArtLoader.loadArt('b1', '/b1');
@@ -287,6 +287,11 @@ describe('TauriAPI', () => {
       expect(invoke).toHaveBeenCalledWith('set_replaygain', { gainDb: -3.5, preampDb: 2.0 });
     });
 
+    it('setReplayGain should pass the analysis target and peak through', async () => {
+      await TauriAPI.setReplayGain(4.0, 0, -14, 0.5);
+      expect(invoke).toHaveBeenCalledWith('set_replaygain', { gainDb: 4.0, preampDb: 0, targetLufs: -14, peak: 0.5 });
+    });
+
     it('clearReplayGain should invoke clear_replaygain', async () => {
       await TauriAPI.clearReplayGain();
       expect(invoke).toHaveBeenCalledWith('clear_replaygain', {});
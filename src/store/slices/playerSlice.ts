@@ -13,6 +13,7 @@ export const createPlayerSlice = (set: SetFn, get: GetFn): PlayerSlice => ({
     duration: 0,
     loadingTrackIndex: null,
     volume: 0.7,
+    muted: false,
     shuffle: false,
     repeatMode: 'off',
 
@@ -67,6 +68,7 @@ export const createPlayerSlice = (set: SetFn, get: GetFn): PlayerSlice => ({
     setDuration: (duration) => set({ duration }),
     setLoadingTrackIndex: (index) => set({ loadingTrackIndex: index }),
     setVolume: (volume) => set({ volume }),
+    setMuted: (muted) => set({ muted }),
 
     setActivePlaybackTracks: (tracks) =>
         set((state) => {
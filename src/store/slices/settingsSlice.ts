@@ -17,8 +17,6 @@ export const SETTINGS_DEFAULTS: SettingsSliceState = {
   gaplessPlayback: true,
   autoPlayOnStartup: false,
   resumeLastTrack: true,
-  replayGainMode: 'off',
-  replayGainPreamp: 0,
   playbackSpeed: 1.0,
   fadeOnPause: true,
   fadeDuration: 200,
@@ -34,7 +32,6 @@ export const SETTINGS_DEFAULTS: SettingsSliceState = {
   // Behavior Settings
   minimizeToTray: true,
   closeToTray: false,
-  startMinimized: false,
   rememberWindowPositions: true,
   playlistAutoScroll: true,
   autoResizeWindow: true,
@@ -75,7 +72,6 @@ export const SETTINGS_DEFAULTS: SettingsSliceState = {
 
   // Crossfade Settings
   crossfadeEnabled: false,
-  crossfadeDuration: 3000,
 
   // Keyboard Shortcuts
   keyboardShortcuts: null,
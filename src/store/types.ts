@@ -178,6 +178,8 @@ export interface PlayerSliceState {
     duration: number;
     loadingTrackIndex: number | null;
     volume: number;
+    /** Mirrors the backend mute, which keeps `volume` for unmuting. Not persisted. */
+    muted: boolean;
     shuffle: boolean;
     repeatMode: RepeatMode;
 
@@ -211,6 +213,7 @@ export interface PlayerSliceActions {
     setDuration: (duration: number) => void;
     setLoadingTrackIndex: (index: number | null) => void;
     setVolume: (volume: number) => void;
+    setMuted: (muted: boolean) => void;
     setShuffle: (shuffle: boolean | ((prev: boolean) => boolean)) => void;
     setRepeatMode: (mode: RepeatMode) => void;
 
@@ -330,8 +333,6 @@ export interface SettingsSliceState {
     gaplessPlayback: boolean;
     autoPlayOnStartup: boolean;
     resumeLastTrack: boolean;
-    replayGainMode: 'off' | 'track' | 'album';
-    replayGainPreamp: number;
     playbackSpeed: number;
     fadeOnPause: boolean;
     fadeDuration: number;
@@ -347,7 +348,6 @@ export interface SettingsSliceState {
     // Behavior Settings
     minimizeToTray: boolean;
     closeToTray: boolean;
-    startMinimized: boolean;
     rememberWindowPositions: boolean;
     playlistAutoScroll: boolean;
     autoResizeWindow: boolean;
@@ -377,7 +377,6 @@ export interface SettingsSliceState {
 
     // Crossfade Settings
     crossfadeEnabled: boolean;
-    crossfadeDuration: number;
 
     // Keyboard Shortcuts
     keyboardShortcuts: KeyboardShortcut[] | null;
@@ -398,8 +397,6 @@ export interface SettingsSliceActions {
     setGaplessPlayback: (enabled: boolean) => void;
     setAutoPlayOnStartup: (enabled: boolean) => void;
     setResumeLastTrack: (enabled: boolean) => void;
-    setReplayGainMode: (mode: 'off' | 'track' | 'album') => void;
-    setReplayGainPreamp: (preamp: number) => void;
     setPlaybackSpeed: (speed: number) => void;
     setFadeOnPause: (enabled: boolean) => void;
     setFadeDuration: (duration: number) => void;
@@ -411,7 +408,6 @@ export interface SettingsSliceActions {
     setAutoFetchAlbumArt: (enabled: boolean) => void;
     setMinimizeToTray: (enabled: boolean) => void;
     setCloseToTray: (enabled: boolean) => void;
-    setStartMinimized: (enabled: boolean) => void;
     setRememberWindowPositions: (enabled: boolean) => void;
     setPlaylistAutoScroll: (enabled: boolean) => void;
     setAutoResizeWindow: (enabled: boolean) => void;
@@ -431,7 +427,6 @@ export interface SettingsSliceActions {
     setEqBands: (bands: EqBand[]) => void;
     setEffectOrder: (order: EffectId[]) => void;
     setCrossfadeEnabled: (enabled: boolean) => void;
-    setCrossfadeDuration: (duration: number) => void;
     setKeyboardShortcuts: (shortcuts: KeyboardShortcut[] | null) => void;
     setOnboardingComplete: (complete: boolean) => void;
 }
@@ -23,6 +23,18 @@ import {
 } from './slices';
 import { pruneExpiredDiscographyData } from './slices/musicBrainzSlice';
 
+// The crossfade length used to be persisted here as `crossfadeDuration` (ms).
+// It now lives in the backend; hydration holds the old value until
+// useCrossfade hands it over, and partialize no longer writes it back.
+let legacyCrossfadeDuration: number | null = null;
+
+/** The crossfade length from old persisted state, returned at most once. */
+export function takeLegacyCrossfadeDuration(): number | null {
+  const duration = legacyCrossfadeDuration;
+  legacyCrossfadeDuration = null;
+  return duration;
+}
+
 export const useStore = create<AppStore>()(
   persist(
     (set, get) => ({
@@ -52,9 +64,13 @@ export const useStore = create<AppStore>()(
       },
       // Merge persisted state with fresh defaults to add new windows
       merge: (persistedState, currentState) => {
-        const persisted = persistedState as Partial<AppStore>;
+        const { crossfadeDuration, ...persisted } = (persistedState ?? {}) as Partial<AppStore> & { crossfadeDuration?: unknown };
         const merged = { ...currentState, ...persisted };
 
+        if (typeof crossfadeDuration === 'number') {
+          legacyCrossfadeDuration = crossfadeDuration;
+        }
+
         // Shuffle order/history are session-only and should never survive restarts.
         merged.shuffleOrder = [];
         merged.shuffleSignature = '';
@@ -57,6 +57,8 @@ export interface PlayerState {
     duration: number;
     volume: number;
     setVolume: (volume: number) => void;
+    muted: boolean;
+    setMuted: (muted: boolean) => void;
 }
 
 export interface PlayerHookParams {
@@ -1,6 +1,10 @@
+import eqPresets from '../../src-tauri/eq_presets.json';
+
 // Audio Constants
 export const VOLUME_STEP = 0.1;
 export const SEEK_THRESHOLD_SECONDS = 3;
+/** Moving on before this fraction of a track has played counts as a skip. */
+export const SKIP_COUNT_THRESHOLD = 0.5;
 
 // Per-window minimum sizes for proper content display
 export const WINDOW_MIN_SIZES = {
@@ -22,45 +26,9 @@ export const WINDOW_MIN_SIZES = {
 // Search & Filter
 export const SEARCH_DEBOUNCE_MS = 300;
 
-// EQ Presets
-export const EQ_PRESETS = {
-  FLAT: {
-    name: 'Flat',
-    bands: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
-  },
-  ROCK: {
-    name: 'Rock',
-    bands: [5, 3, -2, -3, -1, 2, 4, 5, 5, 5],
-  },
-  JAZZ: {
-    name: 'Jazz',
-    bands: [4, 3, 1, 2, -1, -1, 0, 2, 3, 4],
-  },
-  CLASSICAL: {
-    name: 'Classical',
-    bands: [5, 4, 3, 2, -1, -1, 0, 2, 3, 4],
-  },
-  POP: {
-    name: 'Pop',
-    bands: [-1, -1, 0, 2, 4, 4, 2, 0, -1, -1],
-  },
-  ELECTRONIC: {
-    name: 'Electronic',
-    bands: [5, 4, 2, 0, -2, 2, 1, 2, 4, 5],
-  },
-  BASS_BOOST: {
-    name: 'Bass Boost',
-    bands: [8, 6, 4, 2, 0, 0, 0, 0, 0, 0],
-  },
-  TREBLE_BOOST: {
-    name: 'Treble Boost',
-    bands: [0, 0, 0, 0, 0, 2, 4, 6, 8, 10],
-  },
-  VOCAL: {
-    name: 'Vocal',
-    bands: [-2, -1, -1, 1, 3, 3, 2, 1, 0, -1],
-  },
-};
+// EQ Presets, in dB per band, from src-tauri/eq_presets.json. The backend
+// reads the same file for genre presets (genre_eq.rs).
+export const EQ_PRESETS = eqPresets;
 
 // Audio Retry
 export const AUDIO_RETRY_CONFIG = {
@@ -74,10 +42,16 @@ export const AUDIO_RETRY_CONFIG = {
 export const CROSSFADE_CONFIG = {
   DEFAULT_DURATION_MS: 3000,
   MIN_DURATION_MS: 1000,
-  MAX_DURATION_MS: 10000,
+  MAX_DURATION_MS: 30000,
   DEFAULT_ENABLED: false,
 };
 
+// Sleep timer
+export const SLEEP_FADE_CONFIG = {
+  DEFAULT_SECS: 10,
+  MAX_SECS: 30,
+};
+
 // Event Names
 export const EVENTS = {
   SCAN_TOTAL: 'scan-total',
@@ -86,6 +60,7 @@ export const EVENTS = {
   SCAN_CANCELLED: 'scan-cancelled',
   SCAN_ERROR: 'scan-error',
   GLOBAL_SHORTCUT: 'global-shortcut',
+  EQ_BANDS_CHANGED: 'eq-bands-changed',
 };
 
 // Global Shortcut Actions
@@ -114,9 +114,9 @@ export function AlbumViewWindow() {
         }
 
         const mapped: Record<string, string> = {};
-        Object.entries(batch).forEach(([trackId, base64]) => {
-          if (base64) {
-            mapped[trackId] = `data:image/jpeg;base64,${base64}`;
+        Object.entries(batch).forEach(([trackId, art]) => {
+          if (art) {
+            mapped[trackId] = `data:${art.mime};base64,${art.data}`;
           }
         });
 
@@ -21,7 +21,7 @@ export function MiniPlayerWindow({ onMaximize, onClose }: MiniPlayerWindowProps)
   const currentColors = useCurrentColors();
   const { playbackTracks, handleNextTrack, handlePrevTrack, handleToggleMute, audio } = usePlayerContext();
   const tracks = playbackTracks;
-  const isMuted = volume === 0; // TODO: wire up when mute state is in store
+  const isMuted = useStore(s => s.muted);
   const togglePlay = useCallback(() => setPlaying(p => !p), [setPlaying]);
   const currentTrackData = useStore(s => s.getCurrentTrackData)();
   const progressPercent = duration > 0 ? (progress / duration) * 100 : 0;
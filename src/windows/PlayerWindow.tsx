@@ -31,7 +31,7 @@ export function PlayerWindow() {
 
   // ── Derived ───────────────────────────────────────────────────────
   const currentColors = useCurrentColors();
-  const isMuted = false; // TODO: wire up properly when mute state is in store
+  const isMuted = useStore(s => s.muted);
   const togglePlay = useCallback(() => setPlaying(p => !p), [setPlaying]);
   const currentTrackData = useStore(s => s.getCurrentTrackData)();
   const progressPercent = duration > 0 ? (progress / duration) * 100 : 0;
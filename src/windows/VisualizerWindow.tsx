@@ -11,6 +11,8 @@ interface VisualizerData {
   spectrum?: number[];
   waveform?: number[];
   beat_detected?: boolean;
+  left_spectrum?: number[];
+  right_spectrum?: number[];
 }
 
 /**
@@ -68,8 +68,6 @@ export function AdvancedTab({ debugVisible, setDebugVisible }: AdvancedTabProps)
           gaplessPlayback: state.gaplessPlayback,
           autoPlayOnStartup: state.autoPlayOnStartup,
           resumeLastTrack: state.resumeLastTrack,
-          replayGainMode: state.replayGainMode,
-          replayGainPreamp: state.replayGainPreamp,
           playbackSpeed: state.playbackSpeed,
           fadeOnPause: state.fadeOnPause,
           fadeDuration: state.fadeDuration,
@@ -80,7 +78,6 @@ export function AdvancedTab({ debugVisible, setDebugVisible }: AdvancedTabProps)
           autoFetchAlbumArt: state.autoFetchAlbumArt,
           minimizeToTray: state.minimizeToTray,
           closeToTray: state.closeToTray,
-          startMinimized: state.startMinimized,
           rememberWindowPositions: state.rememberWindowPositions,
           rememberTrackPosition: state.rememberTrackPosition,
           confirmBeforeDelete: state.confirmBeforeDelete,
@@ -92,7 +89,6 @@ export function AdvancedTab({ debugVisible, setDebugVisible }: AdvancedTabProps)
           cacheSizeLimit: state.cacheSizeLimit,
           eqBands: state.eqBands,
           crossfadeEnabled: state.crossfadeEnabled,
-          crossfadeDuration: state.crossfadeDuration,
           backgroundImage: state.backgroundImage,
           stopAfterCurrent: state.stopAfterCurrent,
           sleepTimerMinutes: state.sleepTimerMinutes,
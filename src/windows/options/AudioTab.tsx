@@ -1,8 +1,10 @@
 import React, { useState, useEffect } from 'react';
-import { Volume2, Speaker, RefreshCw, Loader, Check, Gauge } from 'lucide-react';
-import { SettingCard, SettingSlider, SettingBadge } from './SettingsComponents';
+import { Volume2, Speaker, RefreshCw, Loader, Check, Gauge, SlidersHorizontal, Trash2, Plus } from 'lucide-react';
+import { SettingCard, SettingSlider, SettingBadge, SettingToggle } from './SettingsComponents';
 import { TauriAPI } from '../../services/TauriAPI';
+import type { GenreEqSettings } from '../../services/TauriAPI';
 import { useStore } from '../../store/useStore';
+import { EQ_PRESETS } from '../../utils/constants';
 import { nativeError } from '../../utils/nativeDialog';
 
 interface AudioDevice {
@@ -26,6 +28,22 @@ export function AudioTab() {
     syncPlaybackSpeed();
   }, []);
 
+  // Keep the device list current when outputs are plugged in or removed
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    let cancelled = false;
+    TauriAPI.onEvent<{ devices: AudioDevice[] }>('devices-changed', (event) => {
+      setAudioDevices(event.payload.devices || []);
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    }).catch((err) => console.error('Failed to listen for device changes:', err));
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
   const loadAudioDevices = async () => {
     try {
       setLoadingDevices(true);
@@ -43,12 +61,13 @@ export function AudioTab() {
     }
   };
 
-  // On mount, sync store speed → Rust backend
+  // On mount, show the speed the backend is playing at. It keeps one speed
+  // for music and one for spoken word and switches between them on load.
   const syncPlaybackSpeed = async () => {
     try {
       const effects = await TauriAPI.getAudioEffects();
       if (effects && effects.tempo !== playbackSpeed) {
-        await TauriAPI.setAudioEffects({ ...effects, tempo: playbackSpeed });
+        setPlaybackSpeed(effects.tempo);
       }
     } catch (err) {
       console.error('Failed to sync playback speed:', err);
@@ -58,11 +77,7 @@ export function AudioTab() {
   const handleSpeedChange = async (speed: number) => {
     setPlaybackSpeed(speed);
     try {
-      const effects = await TauriAPI.getAudioEffects();
-      await TauriAPI.setAudioEffects({
-        ...effects,
-        tempo: speed,
-      });
+      await TauriAPI.setPlaybackSpeed(speed);
     } catch (err) {
       console.error('Failed to set playback speed:', err);
     }
@@ -71,8 +86,19 @@ export function AudioTab() {
   const handleDeviceChange = async (deviceName: string) => {
     try {
       setSwitchingDevice(true);
-      await TauriAPI.setAudioDevice(deviceName);
+      // Probe first so a device that can't play leaves the current output alone
+      const probe = await TauriAPI.testAudioDevice(deviceName);
+      if (!probe.ok) {
+        await nativeError(`Cannot switch to ${deviceName}: ${probe.error ?? 'device test failed'}`);
+        return;
+      }
+      const restoredVolume = await TauriAPI.setAudioDevice(deviceName);
       setSelectedDevice(deviceName);
+      if (restoredVolume !== null) {
+        // Keep the UI (and the reinit handler, which pushes it) on the device's level
+        useStore.getState().setVolume(restoredVolume);
+        await TauriAPI.setVolume(restoredVolume);
+      }
     } catch (err) {
       console.error('Failed to set audio device:', err);
       await nativeError(`Failed to set audio device: ${err}`);
@@ -128,7 +154,8 @@ export function AudioTab() {
       {/* Playback Speed */}
       <SettingCard title="Playback Speed" icon={Gauge} accent="orange">
         <p className="text-xs text-slate-500 mb-4">
-          Adjust the speed of audio playback (affects tempo, not pitch)
+          Adjust the speed of audio playback. Pitch rises and falls with the
+          speed, like a tape played faster or slower. Music and spoken word (podcasts, audiobooks) each remember their own speed.
         </p>
         
         <SettingSlider
@@ -147,7 +174,7 @@ export function AudioTab() {
         
         {/* Speed presets */}
         <div className="flex gap-2 mt-3">
-          {[0.5, 0.75, 1.0, 1.25, 1.5, 2.0].map(speed => (
+          {[0.75, 1.0, 1.25, 1.5, 2.0].map(speed => (
             <button
               key={speed}
               onClick={() => handleSpeedChange(speed)}
@@ -170,6 +197,9 @@ export function AudioTab() {
         )}
       </SettingCard>
 
+      {/* Genre EQ */}
+      <GenreEqCard />
+
       {/* Audio Engine Info */}
       <SettingCard title="Audio Engine" icon={Volume2} accent="emerald">
         <div className="grid grid-cols-2 gap-3">
@@ -198,6 +228,119 @@ export function AudioTab() {
   );
 }
 
+const presetOptions = Object.entries(EQ_PRESETS).map(([id, preset]) => ({ value: id, label: preset.name }));
+
+const selectClass = 'bg-slate-800/80 border border-slate-600 rounded-lg px-2 py-1.5 text-white text-sm cursor-pointer hover:border-slate-500 focus:border-cyan-500 focus:outline-none transition-colors';
+
+// Genre → EQ preset mapping, applied by the backend as tracks load
+function GenreEqCard() {
+  const [settings, setSettings] = useState<GenreEqSettings>({ enabled: false, presets: {} });
+  const [newGenre, setNewGenre] = useState('');
+  const [newPreset, setNewPreset] = useState(presetOptions[0]?.value ?? 'FLAT');
+
+  useEffect(() => {
+    TauriAPI.getGenreEqSettings()
+      .then(setSettings)
+      .catch(err => console.error('Failed to load genre EQ settings:', err));
+  }, []);
+
+  const save = (next: GenreEqSettings) => {
+    setSettings(next);
+    TauriAPI.setGenreEqSettings(next)
+      .catch(err => nativeError(`Failed to save genre EQ settings: ${err}`));
+  };
+
+  const setPreset = (genre: string, preset: string) => {
+    save({ ...settings, presets: { ...settings.presets, [genre]: preset } });
+  };
+
+  const removeGenre = (genre: string) => {
+    const presets = { ...settings.presets };
+    delete presets[genre];
+    save({ ...settings, presets });
+  };
+
+  const addGenre = () => {
+    const genre = newGenre.trim();
+    if (!genre) return;
+    setPreset(genre, newPreset);
+    setNewGenre('');
+  };
+
+  return (
+    <SettingCard title="Genre EQ" icon={SlidersHorizontal} accent="cyan">
+      <p className="text-xs text-slate-500 mb-4">
+        Switch the equalizer to a preset when a track of a mapped genre loads.
+        Other tracks get your own EQ back.
+      </p>
+
+      <SettingToggle
+        label="Apply EQ presets by genre"
+        description="Genres match regardless of case"
+        checked={settings.enabled}
+        onChange={enabled => save({ ...settings, enabled })}
+      />
+
+      <div className="space-y-2 mt-3">
+        {Object.entries(settings.presets).map(([genre, preset]) => (
+          <div key={genre} className="flex items-center gap-2 p-2 rounded-lg bg-slate-800/30 border border-slate-700/50">
+            <span className="flex-1 text-white text-sm truncate">{genre}</span>
+            <select
+              value={preset}
+              onChange={e => setPreset(genre, e.target.value)}
+              onMouseDown={e => e.stopPropagation()}
+              className={selectClass}
+            >
+              {presetOptions.map(opt => (
+                <option key={opt.value} value={opt.value}>{opt.label}</option>
+              ))}
+            </select>
+            <button
+              onClick={() => removeGenre(genre)}
+              onMouseDown={e => e.stopPropagation()}
+              title={`Remove ${genre}`}
+              className="p-1.5 rounded-lg text-slate-400 hover:text-red-400 hover:bg-slate-700 transition-colors"
+            >
+              <Trash2 className="w-4 h-4" />
+            </button>
+          </div>
+        ))}
+
+        <div className="flex items-center gap-2">
+          <input
+            type="text"
+            value={newGenre}
+            onChange={e => setNewGenre(e.target.value)}
+            onKeyDown={e => { if (e.key === 'Enter') addGenre(); }}
+            onMouseDown={e => e.stopPropagation()}
+            placeholder="Genre, e.g. Jazz"
+            className="flex-1 bg-slate-800/80 border border-slate-600 rounded-lg px-3 py-1.5 text-white text-sm placeholder-slate-500 focus:border-cyan-500 focus:outline-none"
+          />
+          <select
+            value={newPreset}
+            onChange={e => setNewPreset(e.target.value)}
+            onMouseDown={e => e.stopPropagation()}
+            className={selectClass}
+          >
+            {presetOptions.map(opt => (
+              <option key={opt.value} value={opt.value}>{opt.label}</option>
+            ))}
+          </select>
+          <button
+            onClick={addGenre}
+            onMouseDown={e => e.stopPropagation()}
+            disabled={!newGenre.trim()}
+            className="px-3 py-1.5 bg-cyan-600 hover:bg-cyan-500 text-white text-sm rounded-lg transition-colors flex items-center gap-1 disabled:opacity-50"
+          >
+            <Plus className="w-4 h-4" />
+            Add
+          </button>
+        </div>
+      </div>
+    </SettingCard>
+  );
+}
+
 // Device option component
 interface DeviceOptionProps {
   device: AudioDevice;
@@ -1,6 +1,8 @@
-import React from 'react';
+import React, { useEffect, useState } from 'react';
 import { Sliders, Monitor, Bell, Grid3X3, Layout, MousePointer, Keyboard, MousePointerClick, Type } from 'lucide-react';
 import { useStore } from '../../store/useStore';
+import { TauriAPI } from '../../services/TauriAPI';
+import { nativeError } from '../../utils/nativeDialog';
 import { SettingToggle, SettingSlider, SettingSelect, SettingCard, SettingDivider, SettingButton } from './SettingsComponents';
 import type { LayoutTemplate } from '../../store/types';
 
@@ -17,8 +19,6 @@ export function BehaviorTab({ layouts, currentLayout, applyLayout, toggleWindow
   const setMinimizeToTray = useStore(state => state.setMinimizeToTray);
   const closeToTray = useStore(state => state.closeToTray);
   const setCloseToTray = useStore(state => state.setCloseToTray);
-  const startMinimized = useStore(state => state.startMinimized);
-  const setStartMinimized = useStore(state => state.setStartMinimized);
   const rememberWindowPositions = useStore(state => state.rememberWindowPositions);
   const setRememberWindowPositions = useStore(state => state.setRememberWindowPositions);
   const autoResizeWindow = useStore(state => state.autoResizeWindow);
@@ -39,6 +39,19 @@ export function BehaviorTab({ layouts, currentLayout, applyLayout, toggleWindow
   const setTrackChangeNotification = useStore(state => state.setTrackChangeNotification);
   const titleBarFormat = useStore(state => state.titleBarFormat);
   const setTitleBarFormat = useStore(state => state.setTitleBarFormat);
+  const [startMinimized, setStartMinimized] = useState(false);
+
+  useEffect(() => {
+    TauriAPI.getStartMinimized()
+      .then(setStartMinimized)
+      .catch(() => {});
+  }, []);
+
+  const handleStartMinimizedChange = (enabled: boolean) => {
+    setStartMinimized(enabled);
+    TauriAPI.setStartMinimized(enabled)
+      .catch(err => nativeError(`Failed to save start minimized setting: ${err}`));
+  };
 
   // Layout preview colors
   const windowColors: Record<string, { bg: string; label: string }> = {
@@ -72,7 +85,7 @@ export function BehaviorTab({ layouts, currentLayout, applyLayout, toggleWindow
           label="Start Minimized"
           description="Launch the app minimized to system tray"
           checked={startMinimized}
-          onChange={setStartMinimized}
+          onChange={handleStartMinimizedChange}
         />
       </SettingCard>
 
@@ -1,10 +1,11 @@
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import { Play, SkipForward, Volume2, Clock, Mic2, Activity, Loader, StopCircle, Timer, Gauge } from 'lucide-react';
-import { TauriAPI } from '../../services/TauriAPI';
+import { TauriAPI, type ReplayGainMode } from '../../services/TauriAPI';
 import { useStore } from '../../store/useStore';
 import { nativeAlert, nativeError } from '../../utils/nativeDialog';
 import { SettingToggle, SettingSlider, SettingSelect, SettingCard, SettingDivider, SettingButton } from './SettingsComponents';
 import type { CrossfadeAPI } from '../../hooks/useCrossfade';
+import { CROSSFADE_CONFIG, SLEEP_FADE_CONFIG } from '../../utils/constants';
 
 interface PlaybackTabProps {
   crossfade?: CrossfadeAPI;
@@ -21,10 +22,6 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
   const setAutoPlayOnStartup = useStore(state => state.setAutoPlayOnStartup);
   const resumeLastTrack = useStore(state => state.resumeLastTrack);
   const setResumeLastTrack = useStore(state => state.setResumeLastTrack);
-  const replayGainMode = useStore(state => state.replayGainMode);
-  const setReplayGainMode = useStore(state => state.setReplayGainMode);
-  const replayGainPreamp = useStore(state => state.replayGainPreamp);
-  const setReplayGainPreamp = useStore(state => state.setReplayGainPreamp);
   const fadeOnPause = useStore(state => state.fadeOnPause);
   const setFadeOnPause = useStore(state => state.setFadeOnPause);
   const fadeDuration = useStore(state => state.fadeDuration);
@@ -43,6 +40,51 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
   const setVolumeStep = useStore(state => state.setVolumeStep);
   const rememberQueue = useStore(state => state.rememberQueue);
   const setRememberQueue = useStore(state => state.setRememberQueue);
+  const [sleepFadeSecs, setSleepFadeSecs] = useState<number>(SLEEP_FADE_CONFIG.DEFAULT_SECS);
+  const [skipUnavailable, setSkipUnavailable] = useState(true);
+  const [replayGainMode, setReplayGainMode] = useState<ReplayGainMode>('off');
+  const [replayGainPreamp, setReplayGainPreamp] = useState(0);
+
+  useEffect(() => {
+    TauriAPI.getFadeDurations()
+      .then(durations => setSleepFadeSecs(durations.sleepFadeSecs))
+      .catch(() => {});
+    TauriAPI.getSkipUnavailable()
+      .then(setSkipUnavailable)
+      .catch(() => {});
+    TauriAPI.getReplayGainMode()
+      .then(setReplayGainMode)
+      .catch(() => {});
+    TauriAPI.getReplayGainPreamp()
+      .then(setReplayGainPreamp)
+      .catch(() => {});
+  }, []);
+
+  const handleSkipUnavailableChange = (enabled: boolean) => {
+    setSkipUnavailable(enabled);
+    TauriAPI.setSkipUnavailable(enabled)
+      .catch(err => nativeError(`Failed to save skip setting: ${err}`));
+  };
+
+  const handleReplayGainModeChange = (mode: ReplayGainMode) => {
+    setReplayGainMode(mode);
+    TauriAPI.setReplayGainMode(mode)
+      .catch(err => nativeError(`Failed to save ReplayGain mode: ${err}`));
+  };
+
+  const handleReplayGainPreampChange = (preampDb: number) => {
+    setReplayGainPreamp(preampDb);
+    TauriAPI.setReplayGainPreamp(preampDb)
+      .then(setReplayGainPreamp)
+      .catch(err => nativeError(`Failed to save ReplayGain pre-amp: ${err}`));
+  };
+
+  const handleSleepFadeChange = (secs: number) => {
+    setSleepFadeSecs(secs);
+    TauriAPI.setSleepFadeSecs(secs)
+      .then(durations => setSleepFadeSecs(durations.sleepFadeSecs))
+      .catch(err => nativeError(`Failed to save sleep fade: ${err}`));
+  };
 
   return (
     <div className="space-y-6">
@@ -88,6 +130,14 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
           icon={StopCircle}
         />
 
+        <SettingToggle
+          label="Skip Unavailable Tracks"
+          description="Move to the next track when a file on a network or removable drive disappears mid-play"
+          checked={skipUnavailable}
+          onChange={handleSkipUnavailableChange}
+          icon={SkipForward}
+        />
+
         <SettingToggle
           label="Remember Play Queue"
           description="Persist the play queue across app restarts"
@@ -179,6 +229,20 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
             </button>
           ))}
         </div>
+
+        <SettingSlider
+          label="Fade Out"
+          description="Volume ramps down over the last seconds before playback stops"
+          value={sleepFadeSecs}
+          onChange={handleSleepFadeChange}
+          min={0}
+          max={SLEEP_FADE_CONFIG.MAX_SECS}
+          step={1}
+          formatValue={v => v === 0 ? 'Off' : `${v}s`}
+          minLabel="Off"
+          maxLabel="30s"
+          accentColor="indigo"
+        />
       </SettingCard>
 
       {/* Fading & Transitions */}
@@ -223,12 +287,12 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
                 description="Length of the crossfade overlap between tracks"
                 value={crossfade.duration}
                 onChange={crossfade.setDuration}
-                min={1000}
-                max={10000}
+                min={CROSSFADE_CONFIG.MIN_DURATION_MS}
+                max={CROSSFADE_CONFIG.MAX_DURATION_MS}
                 step={500}
                 formatValue={v => `${(v / 1000).toFixed(1)}s`}
                 minLabel="1s"
-                maxLabel="10s"
+                maxLabel="30s"
                 accentColor="purple"
               />
             )}
@@ -242,11 +306,12 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
           label="ReplayGain Mode"
           description="Automatically adjust volume levels so all tracks play at similar loudness"
           value={replayGainMode}
-          onChange={v => setReplayGainMode(v as 'off' | 'track' | 'album')}
+          onChange={v => handleReplayGainModeChange(v as ReplayGainMode)}
           options={[
             { value: 'off', label: 'Off - No normalization' },
             { value: 'track', label: 'Track Gain - Normalize each track individually' },
-            { value: 'album', label: 'Album Gain - Preserve album dynamics (coming soon)' },
+            { value: 'album', label: 'Album Gain - Preserve album dynamics' },
+            { value: 'albumWithTrackFallback', label: 'Album Gain, falling back to Track Gain' },
           ]}
         />
 
@@ -256,7 +321,7 @@ export function PlaybackTab({ crossfade }: PlaybackTabProps) {
               label="Pre-amp Adjustment"
               description="Additional gain applied after normalization"
               value={replayGainPreamp}
-              onChange={setReplayGainPreamp}
+              onChange={handleReplayGainPreampChange}
               min={-15}
               max={15}
               step={0.5}